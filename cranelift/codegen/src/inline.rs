@@ -0,0 +1,602 @@
+//! Function inlining.
+//!
+//! This module splices the body of a callee function into a caller at a
+//! `call` site. It doesn't make any decisions about what to inline, that's up
+//! to the embedder which knows how to get at the callee's IR and which calls
+//! are worth inlining. This is expected to run before the optimization passes
+//! of `Context::optimize` so the inlined body gets optimized along with the
+//! rest of the caller.
+//!
+//! Inlining a call works as follows:
+//!
+//! * The block containing the call is split after the call, and the call's
+//!   results become the parameters of the new block.
+//!
+//! * Every block of the callee gets copied into the caller between those two
+//!   blocks, and every entity the callee refers to (stack slots, global values,
+//!   signatures, external functions, constants, ...) is copied as well.
+//!
+//! * The call is replaced with a jump to the copy of the callee's entry block
+//!   and each `return` in the callee becomes a jump to the new block.
+//!
+//! Source locations of the callee's instructions are preserved so traps and
+//! address maps keep pointing at the callee's code, unless the embedder asks
+//! for them to be rewritten with `inline_call_with_srclocs`, and safepoints in the
+//! callee get the stack map entries of the call site so that values live
+//! across the call are still found by the embedder's GC.
+
+use crate::entity::SecondaryMap;
+use crate::ir::{
+    self, ArgumentPurpose, Block, BlockCall, ExternalName, Function, GlobalValueData, Inst,
+    InstructionData, JumpTableData, Opcode, SourceLoc, UserStackMapEntry, Value, ValueList,
+};
+use crate::packed_option::ReservedValue;
+use crate::result::{CodegenError, CodegenResult};
+use alloc::vec::Vec;
+
+/// Inlines `callee` into `func` at `inst`, a `call` instruction calling a
+/// function with `callee`'s signature.
+///
+/// Returns an `Unsupported` error, leaving `func` unmodified, if the call or
+/// the callee can't be inlined. This happens for callees using dynamic
+/// vector types, memory types or tail calls, and for callees which use their
+/// `vmctx` parameter as a global value when the call doesn't pass the caller's
+/// own `vmctx`.
+pub fn inline_call(func: &mut Function, inst: Inst, callee: &Function) -> CodegenResult<()> {
+    inline_call_with_srclocs(func, inst, callee, |srcloc| srcloc)
+}
+
+/// Like [`inline_call`], but the source location of each of the callee's
+/// instructions is passed through `map_srcloc` before it's set on the copy of
+/// the instruction in `func`.
+///
+/// This allows embedders to tell the copies of a callee which was inlined at
+/// several call sites apart, for example to report which call some inlined
+/// code belongs to. Instructions without a source location get the location
+/// of the call instead, which isn't passed through `map_srcloc`.
+pub fn inline_call_with_srclocs(
+    func: &mut Function,
+    inst: Inst,
+    callee: &Function,
+    mut map_srcloc: impl FnMut(SourceLoc) -> SourceLoc,
+) -> CodegenResult<()> {
+    check_inlinable(func, inst, callee)?;
+
+    let entry = callee
+        .layout
+        .entry_block()
+        .ok_or_else(|| unsupported("inlining a function without a body"))?;
+    let call_block = func.layout.inst_block(inst).unwrap();
+    let call_args = func.dfg.inst_args(inst).to_vec();
+    let call_srcloc = func.srcloc(inst);
+    let call_stack_map = func
+        .dfg
+        .user_stack_map_entries(inst)
+        .map(|entries| entries.to_vec())
+        .unwrap_or_default();
+
+    // Split the caller's block after the call, the instructions following the
+    // call now live in `ret_block` which the callee "returns" to.
+    let ret_block = func.dfg.make_block();
+    let next = func
+        .layout
+        .next_inst(inst)
+        .expect("calls are not terminators");
+    func.layout.split_block(ret_block, next);
+
+    let mut inliner = Inliner::new(func, callee);
+    for block in callee.layout.blocks() {
+        let new_block = inliner.blocks[block];
+        inliner.func.layout.insert_block(new_block, ret_block);
+        if callee.layout.is_cold(block) {
+            inliner.func.layout.set_cold(new_block);
+        }
+        for callee_inst in callee.layout.block_insts(block) {
+            let new_inst = inliner.copy_inst(callee_inst, ret_block);
+            inliner.func.layout.append_inst(new_inst, new_block);
+
+            let srcloc = callee.srcloc(callee_inst);
+            let srcloc = if srcloc.is_default() {
+                call_srcloc
+            } else {
+                map_srcloc(srcloc)
+            };
+            if !srcloc.is_default() {
+                inliner.func.set_srcloc(new_inst, srcloc);
+            }
+
+            if inliner.func.dfg.insts[new_inst].opcode().is_safepoint() {
+                if let Some(entries) = callee.dfg.user_stack_map_entries(callee_inst) {
+                    for entry in entries {
+                        let entry = UserStackMapEntry {
+                            slot: inliner.stack_slots[entry.slot],
+                            ..*entry
+                        };
+                        inliner
+                            .func
+                            .dfg
+                            .append_user_stack_map_entry(new_inst, entry);
+                    }
+                }
+                for entry in call_stack_map.iter() {
+                    inliner
+                        .func
+                        .dfg
+                        .append_user_stack_map_entry(new_inst, entry.clone());
+                }
+            }
+        }
+    }
+    inliner.map_values();
+    let entry = inliner.blocks[entry];
+
+    // Finally replace the call with a jump to the callee's entry block, and
+    // turn the call's results into the parameters of `ret_block`.
+    let results = func.dfg.inst_results(inst).to_vec();
+    func.dfg.clear_results(inst);
+    for result in results {
+        func.dfg.attach_block_param(ret_block, result);
+    }
+    let destination = BlockCall::new(entry, &call_args, &mut func.dfg.value_lists);
+    let jump = func.dfg.make_inst(InstructionData::Jump {
+        opcode: Opcode::Jump,
+        destination,
+    });
+    func.layout.remove_inst(inst);
+    func.layout.append_inst(jump, call_block);
+    if !call_srcloc.is_default() {
+        func.set_srcloc(jump, call_srcloc);
+    }
+    Ok(())
+}
+
+fn unsupported(what: &str) -> CodegenError {
+    CodegenError::Unsupported(what.into())
+}
+
+fn check_inlinable(func: &Function, inst: Inst, callee: &Function) -> CodegenResult<()> {
+    if func.dfg.insts[inst].opcode() != Opcode::Call {
+        return Err(unsupported("inlining anything but a `call` instruction"));
+    }
+    let arg_tys = func
+        .dfg
+        .inst_args(inst)
+        .iter()
+        .map(|v| func.dfg.value_type(*v));
+    let result_tys = func
+        .dfg
+        .inst_results(inst)
+        .iter()
+        .map(|v| func.dfg.value_type(*v));
+    if !arg_tys.eq(callee.signature.params.iter().map(|p| p.value_type))
+        || !result_tys.eq(callee.signature.returns.iter().map(|p| p.value_type))
+    {
+        return Err(unsupported("inlining a call with a mismatched signature"));
+    }
+
+    if !callee.dynamic_stack_slots.is_empty() || !callee.dfg.dynamic_types.is_empty() {
+        return Err(unsupported("inlining functions with dynamic types"));
+    }
+    if !callee.memory_types.is_empty() {
+        return Err(unsupported("inlining functions with memory types"));
+    }
+    let tail_calls = callee.layout.blocks().any(|block| {
+        callee.layout.block_insts(block).any(|inst| {
+            matches!(
+                callee.dfg.insts[inst].opcode(),
+                Opcode::ReturnCall | Opcode::ReturnCallIndirect
+            )
+        })
+    });
+    if tail_calls {
+        return Err(unsupported("inlining functions with tail calls"));
+    }
+
+    // `GlobalValueData::VMContext` refers to the function's own `vmctx`
+    // parameter, so it's only the same value after inlining when the callee is
+    // passed the caller's `vmctx`.
+    let uses_vmctx = callee
+        .global_values
+        .values()
+        .any(|gv| matches!(gv, GlobalValueData::VMContext));
+    if uses_vmctx {
+        let callee_vmctx = callee
+            .signature
+            .special_param_index(ArgumentPurpose::VMContext);
+        let caller_vmctx = func.special_param(ArgumentPurpose::VMContext);
+        let passed = callee_vmctx.map(|i| func.dfg.inst_args(inst)[i]);
+        if caller_vmctx.is_none() || passed != caller_vmctx {
+            return Err(unsupported(
+                "inlining a call which isn't passed the caller's vmctx",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Copies entities from a callee into the function it's being inlined into.
+struct Inliner<'a> {
+    func: &'a mut Function,
+    callee: &'a Function,
+    blocks: SecondaryMap<Block, Block>,
+    values: SecondaryMap<Value, Value>,
+    stack_slots: SecondaryMap<ir::StackSlot, ir::StackSlot>,
+    global_values: SecondaryMap<ir::GlobalValue, ir::GlobalValue>,
+    sig_refs: SecondaryMap<ir::SigRef, ir::SigRef>,
+    func_refs: SecondaryMap<ir::FuncRef, ir::FuncRef>,
+    insts: Vec<Inst>,
+}
+
+impl<'a> Inliner<'a> {
+    fn new(func: &'a mut Function, callee: &'a Function) -> Self {
+        let mut inliner = Inliner {
+            func,
+            callee,
+            blocks: SecondaryMap::with_default(Block::reserved_value()),
+            values: SecondaryMap::with_default(Value::reserved_value()),
+            stack_slots: SecondaryMap::with_default(ir::StackSlot::reserved_value()),
+            global_values: SecondaryMap::with_default(ir::GlobalValue::reserved_value()),
+            sig_refs: SecondaryMap::with_default(ir::SigRef::reserved_value()),
+            func_refs: SecondaryMap::with_default(ir::FuncRef::reserved_value()),
+            insts: Vec::new(),
+        };
+        inliner.copy_entities();
+        inliner
+    }
+
+    fn copy_entities(&mut self) {
+        let callee = self.callee;
+        let func = &mut *self.func;
+
+        for (slot, data) in callee.sized_stack_slots.iter() {
+            self.stack_slots[slot] = func.create_sized_stack_slot(data.clone());
+        }
+
+        // Global values may refer to each other, so first allocate all of them
+        // and then fix up their bases.
+        let gv_base = func.global_values.len();
+        for (gv, data) in callee.global_values.iter() {
+            let data = match data {
+                GlobalValueData::Symbol {
+                    name,
+                    offset,
+                    colocated,
+                    tls,
+                } => GlobalValueData::Symbol {
+                    name: copy_name(func, callee, name),
+                    offset: *offset,
+                    colocated: *colocated,
+                    tls: *tls,
+                },
+                other => other.clone(),
+            };
+            self.global_values[gv] = func.create_global_value(data);
+        }
+        for gv in func.global_values.keys().skip(gv_base) {
+            if let GlobalValueData::Load { base, .. } | GlobalValueData::IAddImm { base, .. } =
+                &mut func.global_values[gv]
+            {
+                *base = self.global_values[*base];
+            }
+        }
+
+        for (sig_ref, sig) in callee.dfg.signatures.iter() {
+            self.sig_refs[sig_ref] = func.import_signature(sig.clone());
+        }
+        for (func_ref, data) in callee.dfg.ext_funcs.iter() {
+            let data = ir::ExtFuncData {
+                name: copy_name(func, callee, &data.name),
+                signature: self.sig_refs[data.signature],
+                colocated: data.colocated,
+            };
+            self.func_refs[func_ref] = func.import_function(data);
+        }
+
+        // Blocks and their parameters are created up front as instructions
+        // may refer to blocks, and values defined in blocks, which come later
+        // in the layout.
+        for block in callee.layout.blocks() {
+            let new_block = func.dfg.make_block();
+            for param in callee.dfg.block_params(block) {
+                let ty = callee.dfg.value_type(*param);
+                self.values[*param] = func.dfg.append_block_param(new_block, ty);
+            }
+            self.blocks[block] = new_block;
+        }
+    }
+
+    /// Copies `inst` into the caller, with `return`s replaced with jumps to
+    /// `ret_block`.
+    ///
+    /// The copy still refers to the callee's values which are mapped later in
+    /// `map_values`, once all values have been copied.
+    fn copy_inst(&mut self, inst: Inst, ret_block: Block) -> Inst {
+        let callee = self.callee;
+        let callee_pool = &callee.dfg.value_lists;
+        let mut data = callee.dfg.insts[inst];
+        match &mut data {
+            InstructionData::MultiAry {
+                opcode: Opcode::Return,
+                args,
+            } => {
+                let destination = BlockCall::new(
+                    ret_block,
+                    args.as_slice(callee_pool),
+                    &mut self.func.dfg.value_lists,
+                );
+                data = InstructionData::Jump {
+                    opcode: Opcode::Jump,
+                    destination,
+                };
+            }
+            InstructionData::MultiAry { args, .. } => {
+                *args = self.copy_list(*args);
+            }
+            InstructionData::Call { args, func_ref, .. } => {
+                *args = self.copy_list(*args);
+                *func_ref = self.func_refs[*func_ref];
+            }
+            InstructionData::CallIndirect { args, sig_ref, .. } => {
+                *args = self.copy_list(*args);
+                *sig_ref = self.sig_refs[*sig_ref];
+            }
+            InstructionData::FuncAddr { func_ref, .. } => {
+                *func_ref = self.func_refs[*func_ref];
+            }
+            InstructionData::Jump { destination, .. } => {
+                *destination = self.copy_block_call(*destination);
+            }
+            InstructionData::Brif { blocks, .. } => {
+                for block in blocks {
+                    *block = self.copy_block_call(*block);
+                }
+            }
+            InstructionData::BranchTable { table, .. } => {
+                let data = &callee.dfg.jump_tables[*table];
+                let default = self.copy_block_call(data.default_block());
+                let branches = data
+                    .as_slice()
+                    .iter()
+                    .map(|b| self.copy_block_call(*b))
+                    .collect::<Vec<_>>();
+                *table = self
+                    .func
+                    .create_jump_table(JumpTableData::new(default, &branches));
+            }
+            InstructionData::StackLoad { stack_slot, .. }
+            | InstructionData::StackStore { stack_slot, .. } => {
+                *stack_slot = self.stack_slots[*stack_slot];
+            }
+            InstructionData::UnaryGlobalValue { global_value, .. } => {
+                *global_value = self.global_values[*global_value];
+            }
+            InstructionData::UnaryConst {
+                constant_handle, ..
+            } => {
+                let constant = callee.dfg.constants.get(*constant_handle).clone();
+                *constant_handle = self.func.dfg.constants.insert(constant);
+            }
+            InstructionData::Shuffle { imm, .. } => {
+                let mask = callee.dfg.immediates[*imm].clone();
+                *imm = self.func.dfg.immediates.push(mask);
+            }
+            // Dynamic stack slots are rejected by `check_inlinable` and all
+            // other formats only have values and immediates.
+            _ => {}
+        }
+
+        let new_inst = self.func.dfg.make_inst(data);
+        self.func
+            .dfg
+            .make_inst_results(new_inst, callee.dfg.ctrl_typevar(inst));
+        for (old, new) in callee
+            .dfg
+            .inst_results(inst)
+            .iter()
+            .zip(self.func.dfg.inst_results(new_inst))
+        {
+            self.values[*old] = *new;
+        }
+        self.insts.push(new_inst);
+        new_inst
+    }
+
+    fn copy_list(&mut self, list: ValueList) -> ValueList {
+        ValueList::from_slice(
+            list.as_slice(&self.callee.dfg.value_lists),
+            &mut self.func.dfg.value_lists,
+        )
+    }
+
+    fn copy_block_call(&mut self, call: BlockCall) -> BlockCall {
+        let pool = &self.callee.dfg.value_lists;
+        BlockCall::new(
+            self.blocks[call.block(pool)],
+            call.args_slice(pool),
+            &mut self.func.dfg.value_lists,
+        )
+    }
+
+    /// Rewrites all copied instructions to use the caller's values.
+    fn map_values(&mut self) {
+        let callee = self.callee;
+        let values = &self.values;
+        for inst in self.insts.iter() {
+            self.func.dfg.map_inst_values(*inst, |v| {
+                let v = values[callee.dfg.resolve_aliases(v)];
+                debug_assert!(v != Value::reserved_value());
+                v
+            });
+        }
+    }
+}
+
+fn copy_name(func: &mut Function, callee: &Function, name: &ExternalName) -> ExternalName {
+    match name {
+        ExternalName::User(name) => {
+            let name = callee.params.user_named_funcs()[*name].clone();
+            ExternalName::User(func.declare_imported_user_function(name))
+        }
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cursor::{Cursor, FuncCursor};
+    use crate::ir::types::*;
+    use crate::ir::{AbiParam, InstBuilder, Signature, StackSlotData, StackSlotKind, TrapCode};
+    use crate::isa::CallConv;
+    use crate::settings;
+    use crate::verifier::verify_function;
+    use alloc::string::ToString;
+
+    fn signature() -> Signature {
+        let mut sig = Signature::new(CallConv::SystemV);
+        sig.params.push(AbiParam::new(I32));
+        sig.returns.push(AbiParam::new(I32));
+        sig
+    }
+
+    /// `fn(x) = if x { x + 1 } else { stack[0] = x; stack[0] }`
+    fn callee() -> Function {
+        let mut func = Function::with_name_signature(Default::default(), signature());
+        let slot =
+            func.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 4, 2));
+        let block0 = func.dfg.make_block();
+        let x = func.dfg.append_block_param(block0, I32);
+        let block1 = func.dfg.make_block();
+        let block2 = func.dfg.make_block();
+
+        let mut cur = FuncCursor::new(&mut func);
+        cur.insert_block(block0);
+        cur.ins().brif(x, block1, &[], block2, &[]);
+        cur.insert_block(block1);
+        let y = cur.ins().iadd_imm(x, 1);
+        cur.ins().return_(&[y]);
+        cur.insert_block(block2);
+        cur.ins().stack_store(x, slot, 0);
+        let y = cur.ins().stack_load(I32, slot, 0);
+        cur.ins().return_(&[y]);
+        func
+    }
+
+    #[test]
+    fn inline_branchy_callee() {
+        let callee = callee();
+        let mut func = Function::with_name_signature(Default::default(), signature());
+        let sig = func.import_signature(signature());
+        let func_ref = func.import_function(ir::ExtFuncData {
+            name: ExternalName::testcase("callee"),
+            signature: sig,
+            colocated: true,
+        });
+        let block0 = func.dfg.make_block();
+        let x = func.dfg.append_block_param(block0, I32);
+
+        let mut cur = FuncCursor::new(&mut func);
+        cur.insert_block(block0);
+        let call = cur.ins().call(func_ref, &[x]);
+        let y = cur.func.dfg.first_result(call);
+        cur.set_srcloc(SourceLoc::new(10));
+        let z = cur.ins().imul(y, y);
+        let trap = cur.ins().trapz(z, TrapCode::INTEGER_DIVISION_BY_ZERO);
+        cur.ins().return_(&[z]);
+
+        inline_call(&mut func, call, &callee).unwrap();
+        let flags = settings::Flags::new(settings::builder());
+        verify_function(&func, &flags).unwrap();
+
+        // The call is gone, its result is now a block parameter, and the
+        // callee's blocks and stack slot were copied over.
+        let calls = func
+            .layout
+            .blocks()
+            .flat_map(|b| func.layout.block_insts(b))
+            .filter(|i| func.dfg.insts[*i].opcode().is_call());
+        assert_eq!(calls.count(), 0, "{}", func.display());
+        assert_eq!(func.layout.blocks().count(), 5);
+        assert_eq!(func.sized_stack_slots.len(), 1);
+        let ret_block = func.layout.inst_block(trap).unwrap();
+        assert_eq!(func.dfg.block_params(ret_block), &[y]);
+        assert_eq!(func.srcloc(trap), SourceLoc::new(10));
+    }
+
+    #[test]
+    fn reject_tail_calls() {
+        let mut callee = callee();
+        let sig = callee.import_signature(signature());
+        let func_ref = callee.import_function(ir::ExtFuncData {
+            name: ExternalName::testcase("other"),
+            signature: sig,
+            colocated: true,
+        });
+        let block = callee.layout.last_block().unwrap();
+        let ret = callee.layout.last_inst(block).unwrap();
+        let arg = callee.dfg.inst_args(ret)[0];
+        callee.layout.remove_inst(ret);
+        let mut cur = FuncCursor::new(&mut callee).at_bottom(block);
+        cur.ins().return_call(func_ref, &[arg]);
+
+        let mut func = Function::with_name_signature(Default::default(), signature());
+        let sig = func.import_signature(signature());
+        let func_ref = func.import_function(ir::ExtFuncData {
+            name: ExternalName::testcase("callee"),
+            signature: sig,
+            colocated: true,
+        });
+        let block0 = func.dfg.make_block();
+        let x = func.dfg.append_block_param(block0, I32);
+        let mut cur = FuncCursor::new(&mut func);
+        cur.insert_block(block0);
+        let call = cur.ins().call(func_ref, &[x]);
+        let y = cur.func.dfg.first_result(call);
+        cur.ins().return_(&[y]);
+
+        let before = func.display().to_string();
+        assert!(inline_call(&mut func, call, &callee).is_err());
+        assert_eq!(func.display().to_string(), before);
+    }
+
+    #[test]
+    fn map_callee_srclocs() {
+        let mut callee = callee();
+        let block = callee.layout.entry_block().unwrap();
+        let brif = callee.layout.first_inst(block).unwrap();
+        callee.set_srcloc(brif, SourceLoc::new(20));
+
+        let mut func = Function::with_name_signature(Default::default(), signature());
+        let sig = func.import_signature(signature());
+        let func_ref = func.import_function(ir::ExtFuncData {
+            name: ExternalName::testcase("callee"),
+            signature: sig,
+            colocated: true,
+        });
+        let block0 = func.dfg.make_block();
+        let x = func.dfg.append_block_param(block0, I32);
+        let mut cur = FuncCursor::new(&mut func);
+        cur.insert_block(block0);
+        cur.set_srcloc(SourceLoc::new(10));
+        let call = cur.ins().call(func_ref, &[x]);
+        let y = cur.func.dfg.first_result(call);
+        cur.ins().return_(&[y]);
+
+        inline_call_with_srclocs(&mut func, call, &callee, |srcloc| {
+            SourceLoc::new(srcloc.bits() + 100)
+        })
+        .unwrap();
+
+        // The callee's own locations are mapped while instructions without
+        // one get the call's location as-is.
+        let mut srclocs = func
+            .layout
+            .blocks()
+            .flat_map(|b| func.layout.block_insts(b))
+            .map(|i| func.srcloc(i).bits())
+            .collect::<Vec<_>>();
+        srclocs.sort();
+        srclocs.dedup();
+        assert_eq!(srclocs, [10, 120]);
+    }
+}
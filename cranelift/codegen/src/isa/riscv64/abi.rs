@@ -6,6 +6,7 @@ use crate::ir::types::*;
 use crate::isa;
 
 use crate::isa::riscv64::{inst::*, Riscv64Backend};
+use crate::isa::winch;
 use crate::isa::CallConv;
 use crate::machinst::*;
 
@@ -95,11 +96,7 @@ impl ABIMachineSpec for Riscv64MachineDeps {
         add_ret_area_ptr: bool,
         mut args: ArgsAccumulator,
     ) -> CodegenResult<(u32, Option<usize>)> {
-        assert_ne!(
-            call_conv,
-            isa::CallConv::Winch,
-            "riscv64 does not support the 'winch' calling convention yet"
-        );
+        let is_winch_return = call_conv == isa::CallConv::Winch && args_or_rets == ArgsOrRets::Rets;
 
         // All registers that can be used as parameters or rets.
         // both start and end are included.
@@ -125,7 +122,7 @@ impl ABIMachineSpec for Riscv64MachineDeps {
             None
         };
 
-        for param in params {
+        for (i, param) in params.iter().enumerate() {
             if let ir::ArgumentPurpose::StructArgument(_) = param.purpose {
                 panic!(
                     "StructArgument parameters are not supported on riscv64. \
@@ -137,7 +134,12 @@ impl ABIMachineSpec for Riscv64MachineDeps {
             let (rcs, reg_tys) = Inst::rc_for_type(param.value_type)?;
             let mut slots = ABIArgSlotVec::new();
             for (rc, reg_ty) in rcs.iter().zip(reg_tys.iter()) {
-                let next_reg = if (next_x_reg <= x_end) && *rc == RegClass::Int {
+                // Winch uses the first register to return the last result,
+                // all other results are returned on the stack.
+                let reg_available = !is_winch_return || i == params.len() - 1;
+                let next_reg = if !reg_available {
+                    None
+                } else if (next_x_reg <= x_end) && *rc == RegClass::Int {
                     let x = Some(x_reg(next_x_reg));
                     next_x_reg += 1;
                     x
@@ -166,10 +168,17 @@ impl ABIMachineSpec for Riscv64MachineDeps {
                     // Compute size and 16-byte stack alignment happens
                     // separately after all args.
                     let size = reg_ty.bits() / 8;
-                    let size = std::cmp::max(size, 8);
-                    // Align.
-                    debug_assert!(size.is_power_of_two());
-                    next_stack = align_to(next_stack, size);
+                    let size = if is_winch_return {
+                        // Winch packs its stack results using their natural
+                        // size, without any padding in between.
+                        size
+                    } else {
+                        let size = std::cmp::max(size, 8);
+                        // Align.
+                        debug_assert!(size.is_power_of_two());
+                        next_stack = align_to(next_stack, size);
+                        size
+                    };
                     slots.push(ABIArgSlot::Stack {
                         offset: next_stack as i64,
                         ty: *reg_ty,
@@ -190,6 +199,10 @@ impl ABIMachineSpec for Riscv64MachineDeps {
             None
         };
 
+        if is_winch_return {
+            winch::reverse_stack(args, next_stack, false);
+        }
+
         next_stack = align_to(next_stack, Self::stack_align(call_conv));
 
         Ok((next_stack, pos))
@@ -637,8 +650,13 @@ impl ABIMachineSpec for Riscv64MachineDeps {
         MACHINE_ENV.get_or_init(create_reg_environment)
     }
 
-    fn get_regs_clobbered_by_call(_call_conv_of_callee: isa::CallConv) -> PRegSet {
-        DEFAULT_CLOBBERS
+    fn get_regs_clobbered_by_call(call_conv_of_callee: isa::CallConv) -> PRegSet {
+        match call_conv_of_callee {
+            // The `winch` calling convention doesn't have any callee-save
+            // registers.
+            CallConv::Winch => ALL_CLOBBERS,
+            _ => DEFAULT_CLOBBERS,
+        }
     }
 
     fn compute_frame_layout(
@@ -894,6 +912,33 @@ const DEFAULT_CLOBBERS: PRegSet = PRegSet::empty()
     .with(pv_reg(30))
     .with(pv_reg(31));
 
+/// All integer and float registers other than the stack and frame pointers,
+/// which are clobbered by calls to functions using the `winch` calling
+/// convention. Vector registers are already covered by `DEFAULT_CLOBBERS`.
+const ALL_CLOBBERS: PRegSet = DEFAULT_CLOBBERS
+    .with(px_reg(9))
+    .with(px_reg(18))
+    .with(px_reg(19))
+    .with(px_reg(20))
+    .with(px_reg(21))
+    .with(px_reg(22))
+    .with(px_reg(23))
+    .with(px_reg(24))
+    .with(px_reg(25))
+    .with(px_reg(26))
+    .with(px_reg(27))
+    .with(pf_reg(8))
+    .with(pf_reg(18))
+    .with(pf_reg(19))
+    .with(pf_reg(20))
+    .with(pf_reg(21))
+    .with(pf_reg(22))
+    .with(pf_reg(23))
+    .with(pf_reg(24))
+    .with(pf_reg(25))
+    .with(pf_reg(26))
+    .with(pf_reg(27));
+
 fn create_reg_environment() -> MachineEnv {
     // Some C Extension instructions can only use a subset of the registers.
     // x8 - x15, f8 - f15, v8 - v15 so we should prefer to use those since
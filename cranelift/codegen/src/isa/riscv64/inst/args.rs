@@ -217,9 +217,12 @@ impl Into<AMode> for StackAMode {
 /// risc-v always take two register to compare
 #[derive(Clone, Copy, Debug)]
 pub struct IntegerCompare {
-    pub(crate) kind: IntCC,
-    pub(crate) rs1: Reg,
-    pub(crate) rs2: Reg,
+    /// The condition to evaluate.
+    pub kind: IntCC,
+    /// The left-hand side operand.
+    pub rs1: Reg,
+    /// The right-hand side operand.
+    pub rs2: Reg,
 }
 
 pub(crate) enum BranchFunct3 {
@@ -714,6 +717,7 @@ impl AluOPRRR {
         }
     }
 
+    /// The `funct3` field of the encoding.
     pub fn funct3(self) -> u32 {
         match self {
             AluOPRRR::Add => 0b000,
@@ -788,6 +792,7 @@ impl AluOPRRR {
         }
     }
 
+    /// The major opcode of the encoding.
     pub fn op_code(self) -> u32 {
         match self {
             AluOPRRR::Add
@@ -858,6 +863,7 @@ impl AluOPRRR {
         }
     }
 
+    /// The `funct7` field of the encoding.
     pub const fn funct7(self) -> u32 {
         match self {
             AluOPRRR::Add => 0b0000000,
@@ -1035,6 +1041,7 @@ impl AluOPRRI {
         }
     }
 
+    /// The `funct3` field of the encoding.
     pub fn funct3(self) -> u32 {
         match self {
             AluOPRRI::Addi => 0b000,
@@ -1072,6 +1079,7 @@ impl AluOPRRI {
         }
     }
 
+    /// The major opcode of the encoding.
     pub fn op_code(self) -> u32 {
         match self {
             AluOPRRI::Addi
@@ -1588,7 +1596,9 @@ impl Inst {
     }
 }
 
-pub(crate) fn f32_cvt_to_int_bounds(signed: bool, out_bits: u32) -> (f32, f32) {
+/// The exclusive bounds of the `f32` values that can be converted to an
+/// integer of `out_bits` bits without overflowing.
+pub fn f32_cvt_to_int_bounds(signed: bool, out_bits: u32) -> (f32, f32) {
     match (signed, out_bits) {
         (true, 8) => (i8::min_value() as f32 - 1., i8::max_value() as f32 + 1.),
         (true, 16) => (i16::min_value() as f32 - 1., i16::max_value() as f32 + 1.),
@@ -1602,7 +1612,9 @@ pub(crate) fn f32_cvt_to_int_bounds(signed: bool, out_bits: u32) -> (f32, f32) {
     }
 }
 
-pub(crate) fn f64_cvt_to_int_bounds(signed: bool, out_bits: u32) -> (f64, f64) {
+/// The exclusive bounds of the `f64` values that can be converted to an
+/// integer of `out_bits` bits without overflowing.
+pub fn f64_cvt_to_int_bounds(signed: bool, out_bits: u32) -> (f64, f64) {
     match (signed, out_bits) {
         (true, 8) => (i8::min_value() as f64 - 1., i8::max_value() as f64 + 1.),
         (true, 16) => (i16::min_value() as f64 - 1., i16::max_value() as f64 + 1.),
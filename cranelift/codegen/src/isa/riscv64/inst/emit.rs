@@ -7,13 +7,15 @@ use crate::isa::riscv64::lower::isle::generated_code::{
 };
 use cranelift_control::ControlPlane;
 
+/// Constant state used during emission of a sequence of instructions.
 pub struct EmitInfo {
     shared_flag: settings::Flags,
     isa_flags: super::super::riscv_settings::Flags,
 }
 
 impl EmitInfo {
-    pub(crate) fn new(
+    /// Create a constant state for emission of instructions.
+    pub fn new(
         shared_flag: settings::Flags,
         isa_flags: super::super::riscv_settings::Flags,
     ) -> Self {
@@ -4,6 +4,7 @@
 use super::Inst;
 use std::fmt::{Debug, Display, Formatter, Result};
 
+/// A signed 12-bit immediate.
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Imm12 {
     /// 16-bit container where the low 12 bits are the data payload.
@@ -16,10 +17,12 @@ impl Imm12 {
     pub(crate) const ZERO: Self = Self { bits: 0 };
     pub(crate) const ONE: Self = Self { bits: 1 };
 
+    /// Create a signed 12-bit immediate from a u64, if it fits.
     pub fn maybe_from_u64(val: u64) -> Option<Imm12> {
         Self::maybe_from_i64(val as i64)
     }
 
+    /// Create a signed 12-bit immediate from an i64, if it fits.
     pub fn maybe_from_i64(val: i64) -> Option<Imm12> {
         if val >= -2048 && val <= 2047 {
             Some(Imm12 {
@@ -30,6 +33,7 @@ impl Imm12 {
         }
     }
 
+    /// Create a signed 12-bit immediate from an i16, which must fit.
     #[inline]
     pub fn from_i16(bits: i16) -> Self {
         assert!(bits >= -2048 && bits <= 2047);
@@ -38,11 +42,13 @@ impl Imm12 {
         }
     }
 
+    /// The sign-extended value of the immediate.
     #[inline]
     pub fn as_i16(self) -> i16 {
         (self.bits << 4) as i16 >> 4
     }
 
+    /// Bits for encoding.
     #[inline]
     pub fn bits(&self) -> u32 {
         self.bits.into()
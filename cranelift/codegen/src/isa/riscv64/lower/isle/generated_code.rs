@@ -2,7 +2,7 @@
 // the generated ISLE source below because we include!() it. We must include!() it because its path
 // depends on an environment variable; and also because of this, we can't do the `#[path = "..."]
 // mod generated_code;` trick either.
-#![allow(dead_code, unreachable_code, unreachable_patterns)]
+#![allow(missing_docs, dead_code, unreachable_code, unreachable_patterns)]
 #![allow(unused_imports, unused_variables, non_snake_case, unused_mut)]
 #![allow(irrefutable_let_patterns, clippy::clone_on_copy)]
 
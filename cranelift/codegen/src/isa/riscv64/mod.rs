@@ -1,5 +1,11 @@
 //! risc-v 64-bit Instruction Set Architecture.
 
+pub use self::inst::{
+    f32_cvt_to_int_bounds, f64_cvt_to_int_bounds, AMode, AluOPRRI, AluOPRRR, CondBrTarget,
+    EmitInfo, EmitState, FpuOPRR, FpuOPRRR, FpuOPWidth, Imm12, Inst, IntegerCompare, LoadOP,
+    StoreOP, FRM,
+};
+
 use crate::dominator_tree::DominatorTree;
 use crate::ir::{Function, Type};
 use crate::isa::riscv64::settings as riscv_settings;
@@ -19,11 +25,14 @@ use target_lexicon::{Architecture, Triple};
 mod abi;
 pub(crate) mod inst;
 mod lower;
-mod settings;
+pub mod settings;
 #[cfg(feature = "unwind")]
 use crate::isa::unwind::systemv;
+#[cfg(feature = "unwind")]
+use crate::{Final, MachBufferFinalized};
 
-use self::inst::EmitInfo;
+#[cfg(feature = "unwind")]
+pub use inst::unwind::systemv::create_cie;
 
 /// An riscv64 backend.
 pub struct Riscv64Backend {
@@ -120,22 +129,7 @@ impl TargetIsa for Riscv64Backend {
         result: &CompiledCode,
         kind: crate::isa::unwind::UnwindInfoKind,
     ) -> CodegenResult<Option<crate::isa::unwind::UnwindInfo>> {
-        use crate::isa::unwind::UnwindInfo;
-        use crate::isa::unwind::UnwindInfoKind;
-        Ok(match kind {
-            UnwindInfoKind::SystemV => {
-                let mapper = self::inst::unwind::systemv::RegisterMapper;
-                Some(UnwindInfo::SystemV(
-                    crate::isa::unwind::systemv::create_unwind_info_from_insts(
-                        &result.buffer.unwind_info[..],
-                        result.buffer.data().len(),
-                        &mapper,
-                    )?,
-                ))
-            }
-            UnwindInfoKind::Windows => None,
-            _ => None,
-        })
+        emit_unwind_info(&result.buffer, kind)
     }
 
     #[cfg(feature = "unwind")]
@@ -231,6 +225,29 @@ impl TargetIsa for Riscv64Backend {
     }
 }
 
+/// Emit unwind info for a riscv64 target.
+#[cfg(feature = "unwind")]
+pub fn emit_unwind_info(
+    buffer: &MachBufferFinalized<Final>,
+    kind: crate::isa::unwind::UnwindInfoKind,
+) -> CodegenResult<Option<crate::isa::unwind::UnwindInfo>> {
+    use crate::isa::unwind::{UnwindInfo, UnwindInfoKind};
+    Ok(match kind {
+        UnwindInfoKind::SystemV => {
+            let mapper = self::inst::unwind::systemv::RegisterMapper;
+            Some(UnwindInfo::SystemV(
+                crate::isa::unwind::systemv::create_unwind_info_from_insts(
+                    &buffer.unwind_info[..],
+                    buffer.data().len(),
+                    &mapper,
+                )?,
+            ))
+        }
+        UnwindInfoKind::Windows => None,
+        _ => None,
+    })
+}
+
 impl fmt::Display for Riscv64Backend {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("MachBackend")
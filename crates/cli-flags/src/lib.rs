@@ -187,11 +187,17 @@ wasmtime_option_group! {
 wasmtime_option_group! {
     #[derive(PartialEq, Clone)]
     pub struct CodegenOptions {
-        /// Either `cranelift` or `winch`.
+        /// Either `cranelift`, `winch` or `tiered`.
         ///
         /// Currently only `cranelift` and `winch` are supported, but not all
-        /// builds of Wasmtime have both built in.
+        /// builds of Wasmtime have both built in. `tiered` compiles with
+        /// `winch` first and recompiles hot functions with `cranelift`,
+        /// requires both, and requires `-W epoch-interruption`.
         pub compiler: Option<wasmtime::Strategy>,
+        /// Number of times a function must be found running when the epoch
+        /// deadline is reached before it's recompiled with Cranelift when
+        /// using `-C compiler=tiered`.
+        pub tier_up_samples: Option<u32>,
         /// Which garbage collector to use: `drc` or `null`.
         ///
         /// `drc` is the deferred reference-counting collector.
@@ -212,6 +218,13 @@ wasmtime_option_group! {
         pub parallel_compilation: Option<bool>,
         /// Whether to enable proof-carrying code (PCC)-based validation.
         pub pcc: Option<bool>,
+        /// Whether to inline small functions into their callers.
+        pub inlining: Option<bool>,
+        /// Size, in bytes of wasm, under which functions are always inlined.
+        pub inlining_small_callee_size: Option<u32>,
+        /// Size, in bytes of wasm, which a function may grow to by inlining
+        /// larger functions into it.
+        pub inlining_sum_size_threshold: Option<u32>,
         /// Controls whether native unwind information is present in compiled
         /// object files.
         pub native_unwind_info: Option<bool>,
@@ -330,6 +343,15 @@ wasmtime_option_group! {
         /// the specification. Note that enabling this option may come at a
         /// performance cost.
         pub relaxed_simd_deterministic: Option<bool>,
+        /// Require bit-for-bit deterministic execution of WebAssembly.
+        ///
+        /// This canonicalizes NaNs, forces deterministic relaxed-simd
+        /// behavior, and rejects the threads proposal. With WASI this also
+        /// uses virtual clocks and a fixed random seed, disables sockets,
+        /// closes stdin, and rejects options which could leak host state into
+        /// the guest, such as inheriting the environment or preopening
+        /// directories.
+        pub deterministic: Option<bool>,
         /// Configure support for the tail-call proposal.
         pub tail_call: Option<bool>,
         /// Configure support for the threads proposal.
@@ -346,6 +368,11 @@ wasmtime_option_group! {
         pub function_references: Option<bool>,
         /// Configure support for the GC proposal.
         pub gc: Option<bool>,
+        /// Configure support for the exception-handling proposal.
+        pub exceptions: Option<bool>,
+        /// Configure support for the stack-switching proposal, which isn't
+        /// enabled by `all-proposals`.
+        pub stack_switching: Option<bool>,
         /// Configure support for the custom-page-sizes proposal.
         pub custom_page_sizes: Option<bool>,
         /// Configure support for the wide-arithmetic proposal.
@@ -381,6 +408,22 @@ wasmtime_option_group! {
         /// Maximum size allowed in a write call to the outgoing body's output-stream.
         /// Default: 1024 * 1024.
         pub http_outgoing_body_chunk_size: Option<usize>,
+        /// Reuse outgoing HTTP connections across the requests handled by
+        /// `wasmtime serve` (true, default) or open a new connection for each
+        /// outgoing request (false).
+        pub http_connection_pool: Option<bool>,
+        /// How long a pooled outgoing HTTP connection may stay idle before
+        /// it's closed (`wasmtime serve` only).
+        /// Default: 90s.
+        pub http_connection_pool_idle_timeout: Option<Duration>,
+        /// Maximum number of pooled outgoing HTTP connections open to a single
+        /// host at once (`wasmtime serve` only).
+        /// Default: unlimited.
+        pub http_connection_pool_max_per_host: Option<usize>,
+        /// Path to a PEM file of root certificates used to verify `https`
+        /// servers for pooled outgoing connections, instead of the Mozilla
+        /// root certificates (`wasmtime serve` only).
+        pub http_connection_pool_root_certificates: Option<String>,
         /// Enable support for WASI config imports (experimental)
         pub config: Option<bool>,
         /// Enable support for WASI key-value imports (experimental)
@@ -423,6 +466,9 @@ wasmtime_option_group! {
         pub config_var: Vec<KeyValuePair>,
         /// Preset data for the In-Memory provider of WASI key-value API.
         pub keyvalue_in_memory_data: Vec<KeyValuePair>,
+        /// Persist WASI key-value buckets in the given host directory instead
+        /// of in memory, with one subdirectory per bucket.
+        pub keyvalue_dir: Option<String>,
     }
 
     enum Wasi {
@@ -570,6 +616,11 @@ impl CommonOptions {
             strategy => config.strategy(strategy),
             _ => err,
         }
+        match_feature! {
+            ["cranelift" : self.codegen.tier_up_samples]
+            samples => config.tier_up_samples(samples),
+            _ => err,
+        }
         match_feature! {
             ["gc" : self.codegen.collector]
             collector => config.collector(collector),
@@ -612,6 +663,21 @@ impl CommonOptions {
             enable => config.cranelift_pcc(enable),
             true => err,
         }
+        match_feature! {
+            ["cranelift" : self.codegen.inlining]
+            enable => config.cranelift_inlining(enable),
+            true => err,
+        }
+        match_feature! {
+            ["cranelift" : self.codegen.inlining_small_callee_size]
+            size => config.cranelift_inlining_small_callee_size(size),
+            _ => err,
+        }
+        match_feature! {
+            ["cranelift" : self.codegen.inlining_sum_size_threshold]
+            size => config.cranelift_inlining_sum_size_threshold(size),
+            _ => err,
+        }
 
         self.enable_wasm_features(&mut config)?;
 
@@ -847,6 +913,9 @@ impl CommonOptions {
         if let Some(enable) = self.wasm.relaxed_simd_deterministic {
             config.relaxed_simd_deterministic(enable);
         }
+        if let Some(enable) = self.wasm.deterministic {
+            config.deterministic(enable);
+        }
         match_feature! {
             ["cranelift" : self.wasm.wmemcheck]
             enable => config.wmemcheck(enable),
@@ -911,6 +980,18 @@ impl CommonOptions {
             ("gc", gc, wasm_gc)
             ("gc", reference_types, wasm_reference_types)
             ("gc", function_references, wasm_function_references)
+            ("gc", exceptions, wasm_exceptions)
+        }
+
+        // Stack switching isn't compatible with async support, so unlike
+        // other proposals it's only enabled when explicitly requested.
+        if let Some(enable) = self.wasm.stack_switching {
+            #[cfg(feature = "stack-switching")]
+            config.wasm_stack_switching(enable);
+            #[cfg(not(feature = "stack-switching"))]
+            if enable {
+                anyhow::bail!("support for stack-switching was disabled at compile-time");
+            }
         }
         Ok(())
     }
@@ -389,12 +389,15 @@ impl WasmtimeOptionValue for wasmtime::RegallocAlgorithm {
 }
 
 impl WasmtimeOptionValue for wasmtime::Strategy {
-    const VAL_HELP: &'static str = "=winch|cranelift";
+    const VAL_HELP: &'static str = "=winch|cranelift|tiered";
     fn parse(val: Option<&str>) -> Result<Self> {
         match String::parse(val)?.as_str() {
             "cranelift" => Ok(wasmtime::Strategy::Cranelift),
             "winch" => Ok(wasmtime::Strategy::Winch),
-            other => bail!("unknown compiler `{other}` only `cranelift` and `winch` accepted",),
+            "tiered" => Ok(wasmtime::Strategy::Tiered),
+            other => {
+                bail!("unknown compiler `{other}` only `cranelift`, `winch` and `tiered` accepted",)
+            }
         }
     }
 }
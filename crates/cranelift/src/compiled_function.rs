@@ -17,6 +17,12 @@ pub struct FunctionAddressMap {
     /// gap between it and the next item in the array.
     pub instructions: Box<[InstructionAddressMap]>,
 
+    /// The calls which code in this function was inlined at, in the same
+    /// format as `instructions`. Code which wasn't inlined from another
+    /// function maps to the default position, and this array is empty if no
+    /// calls were inlined.
+    pub inlined_calls: Box<[InstructionAddressMap]>,
+
     /// Function's initial offset in the source file, specified in bytes from
     /// the front of the file.
     pub start_srcloc: FilePos,
@@ -100,6 +106,11 @@ impl CompiledFunction {
         &self.metadata.address_map
     }
 
+    /// Get a mutable reference to the function's address map.
+    pub fn address_map_mut(&mut self) -> &mut FunctionAddressMap {
+        &mut self.metadata.address_map
+    }
+
     /// Create and return the compiled function address map from the original source offset
     /// and length.
     pub fn set_address_map(&mut self, offset: u32, length: u32, with_instruction_addresses: bool) {
@@ -120,6 +131,7 @@ impl CompiledFunction {
 
         let address_map = FunctionAddressMap {
             instructions: instructions.into(),
+            inlined_calls: Default::default(),
             start_srcloc,
             end_srcloc,
             body_offset: 0,
@@ -25,16 +25,18 @@ use std::collections::HashMap;
 use std::mem;
 use std::path;
 use std::sync::{Arc, Mutex};
-use wasmparser::{FuncValidatorAllocations, FunctionBody};
+use wasmparser::{FuncValidator, FuncValidatorAllocations, FunctionBody, WasmModuleResources};
 use wasmtime_environ::{
     AddressMapSection, BuiltinFunctionIndex, CacheStore, CompileError, DefinedFuncIndex, FlagValue,
-    FunctionBodyData, FunctionLoc, HostCall, ModuleTranslation, ModuleTypesBuilder, PtrSize,
-    RelocationTarget, StackMapInformation, StaticModuleIndex, TrapEncodingBuilder, TrapSentinel,
-    TripleExt, Tunables, VMOffsets, WasmFuncType, WasmFunctionInfo, WasmValType,
+    FuncIndex, FunctionBodyData, FunctionLoc, HostCall, ModuleTranslation, ModuleTypesBuilder,
+    PtrSize, RelocationTarget, StackMapInformation, StaticModuleIndex, TrapEncodingBuilder,
+    TrapSentinel, TripleExt, Tunables, VMOffsets, WasmFuncType, WasmFunctionInfo, WasmValType,
 };
 
 #[cfg(feature = "component-model")]
 mod component;
+mod inlining;
+mod profile;
 
 struct IncrementalCacheContext {
     #[cfg(feature = "incremental-cache")]
@@ -178,33 +180,30 @@ impl Compiler {
 
         builder.ins().call_indirect(sig, addr, args)
     }
-}
 
-impl wasmtime_environ::Compiler for Compiler {
-    fn compile_function(
+    /// Translates the wasm function `func_index` to CLIF in `func`.
+    fn translate_function(
         &self,
+        func_translator: &mut FuncTranslator,
         translation: &ModuleTranslation<'_>,
-        func_index: DefinedFuncIndex,
-        input: FunctionBodyData<'_>,
         types: &ModuleTypesBuilder,
-    ) -> Result<(WasmFunctionInfo, Box<dyn Any + Send>), CompileError> {
+        func_index: FuncIndex,
+        validator: &mut FuncValidator<impl WasmModuleResources>,
+        body: FunctionBody<'_>,
+        func: &mut ir::Function,
+    ) -> Result<(), CompileError> {
         let isa = &*self.isa;
-        let module = &translation.module;
-        let func_index = module.func_index(func_index);
         let sig = translation.module.functions[func_index].signature;
         let wasm_func_ty = types[sig].unwrap_func();
 
-        let mut compiler = self.function_compiler();
-
-        let context = &mut compiler.cx.codegen_context;
-        context.func.signature = wasm_call_signature(isa, wasm_func_ty, &self.tunables);
-        context.func.name = UserFuncName::User(UserExternalName {
+        func.signature = wasm_call_signature(isa, wasm_func_ty, &self.tunables);
+        func.name = UserFuncName::User(UserExternalName {
             namespace: crate::NS_WASM_FUNC,
             index: func_index.as_u32(),
         });
 
         if self.tunables.generate_native_debuginfo {
-            context.func.collect_debug_info();
+            func.collect_debug_info();
         }
 
         let mut func_env = FuncEnvironment::new(self, translation, types, wasm_func_ty);
@@ -242,41 +241,81 @@ impl wasmtime_environ::Compiler for Compiler {
         // the embedder should cause wasm to trap before it reaches that
         // (ensuring the host has enough space as well for its functionality).
         if !isa.triple().is_pulley() {
-            let vmctx = context
-                .func
-                .create_global_value(ir::GlobalValueData::VMContext);
-            let interrupts_ptr = context.func.create_global_value(ir::GlobalValueData::Load {
+            let vmctx = func.create_global_value(ir::GlobalValueData::VMContext);
+            let interrupts_ptr = func.create_global_value(ir::GlobalValueData::Load {
                 base: vmctx,
                 offset: i32::from(func_env.offsets.ptr.vmctx_runtime_limits()).into(),
                 global_type: isa.pointer_type(),
                 flags: MemFlags::trusted().with_readonly(),
             });
-            let stack_limit = context.func.create_global_value(ir::GlobalValueData::Load {
+            let stack_limit = func.create_global_value(ir::GlobalValueData::Load {
                 base: interrupts_ptr,
                 offset: i32::from(func_env.offsets.ptr.vmruntime_limits_stack_limit()).into(),
                 global_type: isa.pointer_type(),
                 flags: MemFlags::trusted(),
             });
             if self.tunables.signals_based_traps {
-                context.func.stack_limit = Some(stack_limit);
+                func.stack_limit = Some(stack_limit);
             } else {
                 func_env.stack_limit_at_function_entry = Some(stack_limit);
             }
         }
+
+        func_translator.translate_body(validator, body, func, &mut func_env)?;
+        Ok(())
+    }
+}
+
+impl wasmtime_environ::Compiler for Compiler {
+    fn compile_function(
+        &self,
+        translation: &ModuleTranslation<'_>,
+        def_func_index: DefinedFuncIndex,
+        input: FunctionBodyData<'_>,
+        types: &ModuleTypesBuilder,
+    ) -> Result<(WasmFunctionInfo, Box<dyn Any + Send>), CompileError> {
+        let func_index = translation.module.func_index(def_func_index);
+        let mut compiler = self.function_compiler();
+        let context = &mut compiler.cx.codegen_context;
+
         let FunctionBodyData { validator, body } = input;
         let mut validator =
             validator.into_validator(mem::take(&mut compiler.cx.validator_allocations));
-        compiler.cx.func_translator.translate_body(
+        self.translate_function(
+            &mut compiler.cx.func_translator,
+            translation,
+            types,
+            func_index,
             &mut validator,
             body.clone(),
             &mut context.func,
-            &mut func_env,
         )?;
 
-        let (info, func) = compiler.finish_with_info(
+        let mut inlined = inlining::InlinedSrcLocs::default();
+        if self.tunables.inlining
+            && !self.tunables.generate_native_debuginfo
+            && !self.isa.flags().enable_pcc()
+        {
+            inlined = self.inline_calls(
+                &mut compiler.cx.func_translator,
+                translation,
+                types,
+                def_func_index,
+                validator.resources(),
+                *validator.features(),
+                &mut context.func,
+            );
+        }
+
+        if let Some(profile) = translation.profile_data {
+            profile::mark_cold_loops(profile, body.range(), &inlined, &mut context.func);
+        }
+
+        let (info, mut func) = compiler.finish_with_info(
             Some((&body, &self.tunables)),
             &format!("wasm_func_{}", func_index.as_u32()),
         )?;
+        inlined.resolve_address_map(func.address_map_mut());
 
         let timing = cranelift_codegen::timing::take_current();
         log::debug!("{:?} translated in {:?}", func_index, timing.total());
@@ -454,6 +493,9 @@ impl wasmtime_environ::Compiler for Compiler {
             if self.tunables.generate_address_map {
                 let addr = func.address_map();
                 addrs.push(range.clone(), &addr.instructions);
+                if !addr.inlined_calls.is_empty() {
+                    addrs.push_inlined_calls(range.clone(), &addr.inlined_calls);
+                }
             }
             traps.push(range.clone(), &func.traps().collect::<Vec<_>>());
             builder.append_padding(self.linkopts.padding_between_functions);
@@ -0,0 +1,215 @@
+//! Inlining of small wasm functions into their callers.
+//!
+//! Each function is still compiled on its own, so when inlining is enabled
+//! the bodies of the direct callees of a function are translated to CLIF
+//! again while compiling it, and then spliced into the caller with
+//! `cranelift_codegen::inline` before the caller is optimized. Only direct
+//! calls to functions defined in the same module are considered, and callees
+//! are inlined without inlining their own callees in turn.
+//!
+//! When the module has profile data, calls which were sampled are inlined
+//! first, and regardless of the caller's size, while calls which weren't are
+//! only inlined when the callee is small.
+//!
+//! Inlined code keeps the source locations of the callee, so traps within it
+//! are reported at the right instruction, but backtraces also need to know
+//! which call the code was inlined at. Cranelift only tracks a single source
+//! location per instruction, so inlined instructions get placeholder
+//! locations standing for a pair of locations instead, see `InlinedSrcLocs`,
+//! which are resolved once the caller has been compiled.
+
+use crate::compiled_function::FunctionAddressMap;
+use crate::compiler::Compiler;
+use crate::translate::FuncTranslator;
+use cranelift_codegen::ir::{self, ExternalName, InstructionData, Opcode, SourceLoc};
+use std::collections::HashMap;
+use wasmparser::{FuncToValidate, WasmFeatures, WasmModuleResources};
+use wasmtime_environ::{
+    DefinedFuncIndex, FilePos, FuncIndex, InstructionAddressMap, ModuleTranslation,
+    ModuleTypesBuilder,
+};
+
+/// Source locations of the code inlined into a function.
+///
+/// Each inlined instruction gets a placeholder source location, counting down
+/// from `u32::MAX - 1`, which stands for the instruction's own location within
+/// the callee and the location of the call it was inlined at. Placeholders are
+/// far past the end of any wasm binary that can be compiled, so they can't be
+/// confused with the caller's own locations.
+#[derive(Default)]
+pub(super) struct InlinedSrcLocs {
+    locs: Vec<(SourceLoc, SourceLoc)>,
+    placeholders: HashMap<(SourceLoc, SourceLoc), SourceLoc>,
+}
+
+impl InlinedSrcLocs {
+    fn placeholder(&mut self, srcloc: SourceLoc, call: SourceLoc) -> SourceLoc {
+        *self.placeholders.entry((srcloc, call)).or_insert_with(|| {
+            let index = u32::try_from(self.locs.len()).unwrap();
+            self.locs.push((srcloc, call));
+            SourceLoc::new(u32::MAX - 1 - index)
+        })
+    }
+
+    /// Returns the location within the callee and the location of the call
+    /// which `placeholder` stands for, or `None` if it's not a placeholder.
+    fn resolve(&self, placeholder: u32) -> Option<(SourceLoc, SourceLoc)> {
+        let index = (u32::MAX - 1).checked_sub(placeholder)?;
+        self.locs.get(usize::try_from(index).ok()?).copied()
+    }
+
+    /// Returns the location in the wasm binary that `srcloc` stands for.
+    pub(super) fn original(&self, srcloc: SourceLoc) -> SourceLoc {
+        match self.resolve(srcloc.bits()) {
+            Some((srcloc, _call)) => srcloc,
+            None => srcloc,
+        }
+    }
+
+    /// Replaces the placeholders in `address_map` with the locations they
+    /// stand for, and records which calls the inlined code belongs to in its
+    /// `inlined_calls`.
+    pub(super) fn resolve_address_map(&self, address_map: &mut FunctionAddressMap) {
+        if self.locs.is_empty() {
+            return;
+        }
+        let mut calls = Vec::<InstructionAddressMap>::new();
+        for instr in address_map.instructions.iter_mut() {
+            let call = match instr.srcloc.file_offset().and_then(|p| self.resolve(p)) {
+                Some((srcloc, call)) => {
+                    instr.srcloc = FilePos::new(srcloc.bits());
+                    FilePos::new(call.bits())
+                }
+                None => FilePos::default(),
+            };
+            if calls.last().map(|c| c.srcloc) != Some(call) {
+                calls.push(InstructionAddressMap {
+                    srcloc: call,
+                    code_offset: instr.code_offset,
+                });
+            }
+        }
+        address_map.inlined_calls = calls.into();
+    }
+}
+
+impl Compiler {
+    /// Inlines direct calls in `func`, the translated body of `caller`, to
+    /// functions which are small enough according to `Tunables`, returning
+    /// the source locations of the inlined code.
+    ///
+    /// Calls which can't be inlined are left as-is, and so are calls to
+    /// functions which fail to translate; those errors are reported when the
+    /// callee itself is compiled.
+    pub(super) fn inline_calls<R: WasmModuleResources>(
+        &self,
+        func_translator: &mut FuncTranslator,
+        translation: &ModuleTranslation<'_>,
+        types: &ModuleTypesBuilder,
+        caller: DefinedFuncIndex,
+        resources: &R,
+        features: WasmFeatures,
+        func: &mut ir::Function,
+    ) -> InlinedSrcLocs {
+        let mut srclocs = InlinedSrcLocs::default();
+        let module = &translation.module;
+        let bodies = &translation.function_bodies;
+        let body_size = |index: DefinedFuncIndex| bodies[index].range().len();
+        let Some(mut size) = bodies.get(caller).map(|_| body_size(caller)) else {
+            return srclocs;
+        };
+
+        let mut calls = Vec::new();
+        for block in func.layout.blocks() {
+            for inst in func.layout.block_insts(block) {
+                let InstructionData::Call {
+                    opcode: Opcode::Call,
+                    func_ref,
+                    ..
+                } = func.dfg.insts[inst]
+                else {
+                    continue;
+                };
+                let ExternalName::User(name) = func.dfg.ext_funcs[func_ref].name else {
+                    continue;
+                };
+                let name = &func.params.user_named_funcs()[name];
+                if name.namespace != crate::NS_WASM_FUNC {
+                    continue;
+                }
+                let callee = FuncIndex::from_u32(name.index);
+                match module.defined_func_index(callee) {
+                    Some(callee) if callee != caller => calls.push((inst, callee)),
+                    _ => {}
+                }
+            }
+        }
+
+        let profile = translation.profile_data;
+        let body_start =
+            |index: DefinedFuncIndex| u32::try_from(bodies[index].range().start).unwrap();
+        let call_samples = |callee: DefinedFuncIndex| {
+            profile.map_or(0, |p| {
+                p.call_samples(body_start(caller), body_start(callee))
+            })
+        };
+        if profile.is_some() {
+            calls.sort_by_key(|(_, callee)| core::cmp::Reverse(call_samples(*callee)));
+        }
+
+        let small = self.tunables.inlining_small_callee_size as usize;
+        let sum = self.tunables.inlining_sum_size_threshold as usize;
+        let mut callees = HashMap::new();
+        for (inst, callee) in calls {
+            let callee_size = body_size(callee);
+            let inline = match profile {
+                Some(_) if call_samples(callee) > 0 => callee_size <= sum,
+                Some(_) => callee_size <= small,
+                None => callee_size <= small || size + callee_size <= sum,
+            };
+            if !inline {
+                continue;
+            }
+            let callee_func = callees.entry(callee).or_insert_with(|| {
+                let func_index = module.func_index(callee);
+                let index = func_index.as_u32();
+                let mut validator = FuncToValidate {
+                    resources,
+                    index,
+                    ty: resources.type_index_of_function(index)?,
+                    features,
+                }
+                .into_validator(Default::default());
+                let mut callee_func = ir::Function::new();
+                self.translate_function(
+                    func_translator,
+                    translation,
+                    types,
+                    func_index,
+                    &mut validator,
+                    bodies[callee].clone(),
+                    &mut callee_func,
+                )
+                .ok()?;
+                Some(callee_func)
+            });
+            let Some(callee_func) = callee_func else {
+                continue;
+            };
+            let call = func.srcloc(inst);
+            let result =
+                cranelift_codegen::inline::inline_call_with_srclocs(func, inst, callee_func, |s| {
+                    if call.is_default() {
+                        s
+                    } else {
+                        srclocs.placeholder(s, call)
+                    }
+                });
+            match result {
+                Ok(()) => size += callee_size,
+                Err(e) => log::trace!("not inlining {callee:?} into {caller:?}: {e}"),
+            }
+        }
+        srclocs
+    }
+}
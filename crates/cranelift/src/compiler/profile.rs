@@ -0,0 +1,123 @@
+//! Using a module's `ProfileData` to guide the compilation of its functions.
+//!
+//! Samples are generally only taken at function entries and loop headers, as
+//! that's where epoch interruption checks are, so an individual block not
+//! having any samples doesn't mean it's never executed. A loop none of whose
+//! blocks were sampled while the function containing it was is a good sign
+//! that the loop is rarely executed though, so such loops are marked cold.
+//!
+//! Cranelift's block layout sinks cold blocks to the end of the function.
+//! Every block which can only be reached through a cold loop, such as the code
+//! following it, is marked cold as well so the whole region is moved out of
+//! line. A loop is therefore only marked cold if every sampled block of the
+//! function can still be reached without going through it.
+//!
+//! Register allocation isn't informed by the profile beyond this layout:
+//! regalloc2 derives spill weights from its own loop depth estimate and has no
+//! way to be told about block frequencies.
+
+use super::inlining::InlinedSrcLocs;
+use cranelift_codegen::dominator_tree::DominatorTree;
+use cranelift_codegen::flowgraph::ControlFlowGraph;
+use cranelift_codegen::ir;
+use cranelift_codegen::loop_analysis::LoopAnalysis;
+use std::collections::HashSet;
+use std::ops::Range;
+use wasmtime_environ::ProfileData;
+
+/// Marks the blocks of loops in `func` which were never sampled, and the
+/// blocks only reachable through them, as cold if the function itself, whose
+/// body spans `range` in the wasm binary, was sampled.
+///
+/// The source locations of code inlined into `func` are resolved through
+/// `inlined`.
+pub(super) fn mark_cold_loops(
+    profile: &ProfileData,
+    range: Range<usize>,
+    inlined: &InlinedSrcLocs,
+    func: &mut ir::Function,
+) {
+    let (Ok(start), Ok(end)) = (u32::try_from(range.start), u32::try_from(range.end)) else {
+        return;
+    };
+    if profile.range_samples(start, end) == 0 {
+        return;
+    }
+
+    let cfg = ControlFlowGraph::with_function(func);
+    let domtree = DominatorTree::with_function(func, &cfg);
+    let mut loops = LoopAnalysis::new();
+    loops.compute(func, &cfg, &domtree);
+
+    let sampled = func
+        .layout
+        .blocks()
+        .filter(|block| {
+            func.layout.block_insts(*block).any(|inst| {
+                let srcloc = inlined.original(func.srcloc(inst));
+                !srcloc.is_default() && profile.offset_samples(srcloc.bits()) > 0
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // A loop is hot if any block within it, including within nested loops,
+    // was sampled.
+    let mut hot = HashSet::new();
+    for block in sampled.iter() {
+        let mut lp = loops.innermost_loop(*block);
+        while let Some(l) = lp {
+            if !hot.insert(l) {
+                break;
+            }
+            lp = loops.loop_parent(l);
+        }
+    }
+
+    let mut cold = HashSet::new();
+    for lp in loops.loops() {
+        if hot.contains(&lp) || cold.contains(&loops.loop_header(lp)) {
+            continue;
+        }
+        let blocks = func
+            .layout
+            .blocks()
+            .filter(|block| loops.is_in_loop(*block, lp) && !cold.contains(block))
+            .collect::<Vec<_>>();
+        cold.extend(blocks.iter().copied());
+        let warm = warm_blocks(func, &cfg, &cold);
+        if !sampled.iter().all(|block| warm.contains(block)) {
+            for block in blocks {
+                cold.remove(&block);
+            }
+        }
+    }
+
+    if cold.is_empty() {
+        return;
+    }
+    let warm = warm_blocks(func, &cfg, &cold);
+    let blocks = func.layout.blocks().collect::<Vec<_>>();
+    for block in blocks {
+        if !warm.contains(&block) {
+            func.layout.set_cold(block);
+        }
+    }
+}
+
+/// Returns the blocks which can be reached from the entry of `func` without
+/// going through any of the `cold` blocks.
+fn warm_blocks(
+    func: &ir::Function,
+    cfg: &ControlFlowGraph,
+    cold: &HashSet<ir::Block>,
+) -> HashSet<ir::Block> {
+    let mut warm = HashSet::new();
+    let mut worklist = func.layout.entry_block().into_iter().collect::<Vec<_>>();
+    while let Some(block) = worklist.pop() {
+        if cold.contains(&block) || !warm.insert(block) {
+            continue;
+        }
+        worklist.extend(cfg.succ_iter(block));
+    }
+    warm
+}
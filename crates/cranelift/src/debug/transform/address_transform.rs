@@ -699,6 +699,7 @@ mod tests {
                 },
             ]
             .into(),
+            inlined_calls: Default::default(),
             start_srcloc: FilePos::new(wasm_offset),
             end_srcloc: FilePos::new(wasm_offset + 10),
             body_offset: 0,
@@ -1221,6 +1221,7 @@ mod tests {
                     },
                 ]
                 .into(),
+                inlined_calls: Default::default(),
                 start_srcloc: FilePos::new(code_section_offset + 10),
                 end_srcloc: FilePos::new(code_section_offset + 20),
                 body_offset: 0,
@@ -3,7 +3,7 @@ use crate::translate::{
     FuncTranslationState, GlobalVariable, Heap, HeapData, StructFieldsVec, TableData, TableSize,
     TargetEnvironment,
 };
-use crate::{gc, BuiltinFunctionSignatures, TRAP_INTERNAL_ASSERT};
+use crate::{gc, stack_switching, BuiltinFunctionSignatures, TRAP_INTERNAL_ASSERT};
 use cranelift_codegen::cursor::FuncCursor;
 use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
 use cranelift_codegen::ir::immediates::{Imm64, Offset32};
@@ -22,9 +22,9 @@ use wasmparser::{Operator, WasmFeatures};
 use wasmtime_environ::{
     BuiltinFunctionIndex, DataIndex, ElemIndex, EngineOrModuleTypeIndex, FuncIndex, GlobalIndex,
     IndexType, Memory, MemoryIndex, Module, ModuleInternedTypeIndex, ModuleTranslation,
-    ModuleTypesBuilder, PtrSize, Table, TableIndex, TripleExt, Tunables, TypeConvert, TypeIndex,
-    VMOffsets, WasmCompositeInnerType, WasmFuncType, WasmHeapTopType, WasmHeapType, WasmRefType,
-    WasmResult, WasmValType,
+    ModuleTypesBuilder, PtrSize, Table, TableIndex, TagIndex, TripleExt, Tunables, TypeConvert,
+    TypeIndex, VMOffsets, WasmCompositeInnerType, WasmFuncType, WasmHeapTopType, WasmHeapType,
+    WasmRefType, WasmResult, WasmValType, RESUME_SWITCH_HANDLER_BIT,
 };
 use wasmtime_environ::{FUNCREF_INIT_BIT, FUNCREF_MASK};
 
@@ -210,6 +210,56 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
         self.isa.pointer_type()
     }
 
+    /// Returns the payload types of the given tag.
+    pub fn tag_params(&self, tag_index: TagIndex) -> &'module_environment [WasmValType] {
+        let types = self.types;
+        let sig = self.module.tags[tag_index].signature;
+        types[sig.unwrap_module_type_index()].unwrap_func().params()
+    }
+
+    /// Returns the result types of the given tag, which are the types of the
+    /// values that a continuation suspended with it is resumed with.
+    pub fn tag_results(&self, tag_index: TagIndex) -> &'module_environment [WasmValType] {
+        let types = self.types;
+        let sig = self.module.tags[tag_index].signature;
+        types[sig.unwrap_module_type_index()]
+            .unwrap_func()
+            .returns()
+    }
+
+    /// Returns the parameter types of the given continuation type.
+    pub fn cont_type_params(&self, type_index: TypeIndex) -> &'module_environment [WasmValType] {
+        self.cont_func_type(self.module.types[type_index]).params()
+    }
+
+    /// Returns the result types of the given continuation type.
+    pub fn cont_type_results(&self, type_index: TypeIndex) -> &'module_environment [WasmValType] {
+        self.cont_func_type(self.module.types[type_index]).returns()
+    }
+
+    /// Returns the types of the values that a continuation is resumed with
+    /// after switching to a continuation of the given type: the parameters of
+    /// the continuation type of the latter's last parameter.
+    fn switch_results(&self, type_index: TypeIndex) -> &'module_environment [WasmValType] {
+        match self.cont_type_params(type_index).last() {
+            Some(WasmValType::Ref(WasmRefType {
+                heap_type: WasmHeapType::ConcreteCont(index),
+                ..
+            })) => self
+                .cont_func_type(index.unwrap_module_type_index())
+                .params(),
+            _ => unreachable!(
+                "validated `switch` to a continuation type without a continuation parameter"
+            ),
+        }
+    }
+
+    fn cont_func_type(&self, ty: ModuleInternedTypeIndex) -> &'module_environment WasmFuncType {
+        let types = self.types;
+        let func_ty = types[ty].unwrap_cont().0.unwrap_module_type_index();
+        types[func_ty].unwrap_func()
+    }
+
     pub(crate) fn vmctx(&mut self, func: &mut Function) -> ir::GlobalValue {
         self.vmctx.unwrap_or_else(|| {
             let vmctx = func.create_global_value(ir::GlobalValueData::VMContext);
@@ -354,6 +404,7 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
             // cost is incurred with the conditional check.
             Operator::Block { .. }
             | Operator::Loop { .. }
+            | Operator::TryTable { .. }
             | Operator::Unreachable
             | Operator::Return
             | Operator::Else
@@ -377,6 +428,13 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
             | Operator::Return
             | Operator::CallIndirect { .. }
             | Operator::Call { .. }
+            | Operator::CallRef { .. }
+            | Operator::Throw { .. }
+            | Operator::ThrowRef
+            | Operator::Resume { .. }
+            | Operator::ResumeThrow { .. }
+            | Operator::Suspend { .. }
+            | Operator::Switch { .. }
             | Operator::ReturnCall { .. }
             | Operator::ReturnCallRef { .. }
             | Operator::ReturnCallIndirect { .. } => {
@@ -437,7 +495,7 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
         // After a function call we need to reload our fuel value since the
         // function may have changed it.
         match op {
-            Operator::Call { .. } | Operator::CallIndirect { .. } => {
+            Operator::Call { .. } | Operator::CallIndirect { .. } | Operator::CallRef { .. } => {
                 self.fuel_load_into_var(builder);
             }
             _ => {}
@@ -1459,6 +1517,11 @@ impl<'a, 'func, 'module_env> Call<'a, 'func, 'module_env> {
             | WasmHeapType::ConcreteFunc(EngineOrModuleTypeIndex::RecGroup(_))
             | WasmHeapType::Extern
             | WasmHeapType::NoExtern
+            | WasmHeapType::Exn
+            | WasmHeapType::NoExn
+            | WasmHeapType::Cont
+            | WasmHeapType::ConcreteCont(_)
+            | WasmHeapType::NoCont
             | WasmHeapType::Any
             | WasmHeapType::Eq
             | WasmHeapType::I31
@@ -1689,7 +1752,10 @@ impl<'module_environment> TargetEnvironment for FuncEnvironment<'module_environm
     fn reference_type(&self, wasm_ty: WasmHeapType) -> (ir::Type, bool) {
         let ty = crate::reference_type(wasm_ty, self.pointer_type());
         let needs_stack_map = match wasm_ty.top() {
-            WasmHeapTopType::Extern | WasmHeapTopType::Any => true,
+            WasmHeapTopType::Extern
+            | WasmHeapTopType::Any
+            | WasmHeapTopType::Exn
+            | WasmHeapTopType::Cont => true,
             WasmHeapTopType::Func => false,
         };
         (ty, needs_stack_map)
@@ -1791,7 +1857,10 @@ impl FuncEnvironment<'_> {
         let heap_ty = table.ref_type.heap_type;
         match heap_ty.top() {
             // GC-managed types.
-            WasmHeapTopType::Any | WasmHeapTopType::Extern => {
+            WasmHeapTopType::Any
+            | WasmHeapTopType::Extern
+            | WasmHeapTopType::Exn
+            | WasmHeapTopType::Cont => {
                 let (src, flags) = table_data.prepare_table_addr(self, builder, index);
                 gc::gc_compiler(self)?.translate_read_gc_reference(
                     self,
@@ -1822,7 +1891,10 @@ impl FuncEnvironment<'_> {
         let heap_ty = table.ref_type.heap_type;
         match heap_ty.top() {
             // GC-managed types.
-            WasmHeapTopType::Any | WasmHeapTopType::Extern => {
+            WasmHeapTopType::Any
+            | WasmHeapTopType::Extern
+            | WasmHeapTopType::Exn
+            | WasmHeapTopType::Cont => {
                 let (dst, flags) = table_data.prepare_table_addr(self, builder, index);
                 gc::gc_compiler(self)?.translate_write_gc_reference(
                     self,
@@ -2225,6 +2297,383 @@ impl FuncEnvironment<'_> {
         gc::translate_ref_test(self, builder, ref_ty, gc_ref)
     }
 
+    /// Returns whether the store currently has a pending exception, that is
+    /// whether the last call threw an exception that has not been caught yet.
+    pub fn translate_exception_pending(&mut self, builder: &mut FunctionBuilder) -> ir::Value {
+        let pointer_type = self.pointer_type();
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let limits = builder.ins().load(
+            pointer_type,
+            MemFlags::trusted().with_readonly(),
+            vmctx,
+            i32::from(self.offsets.ptr.vmctx_runtime_limits()),
+        );
+        builder.ins().load(
+            pointer_type,
+            MemFlags::trusted(),
+            limits,
+            i32::from(self.offsets.ptr.vmruntime_limits_exception_pending()),
+        )
+    }
+
+    /// Hook invoked at the start of the block that dispatches a pending
+    /// exception to a `try_table`'s catch clauses.
+    pub fn before_exception_dispatch(&mut self, builder: &mut FunctionBuilder) {
+        // The call that threw may have consumed fuel, and control doesn't
+        // reach the reload after the call when it throws.
+        if self.tunables.consume_fuel {
+            self.fuel_load_into_var(builder);
+        }
+    }
+
+    /// Throws a new exception with the given tag and payload, leaving it
+    /// pending in the store.
+    pub fn translate_throw(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        tag_index: TagIndex,
+        args: &[ir::Value],
+    ) -> WasmResult<()> {
+        let libcall = gc::builtins::throw(self, builder.func)?;
+        let params = self.tag_params(tag_index);
+        let payload = self.store_values_to_stack_slot(builder, params, args, args.len());
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let tag = builder.ins().iconst(I32, i64::from(tag_index.as_u32()));
+        builder.ins().call(libcall, &[vmctx, tag, payload]);
+        Ok(())
+    }
+
+    /// Rethrows the given exception reference, leaving it pending in the
+    /// store.
+    pub fn translate_throw_ref(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        exnref: ir::Value,
+    ) -> WasmResult<()> {
+        let libcall = gc::builtins::throw_ref(self, builder.func)?;
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        builder.ins().call(libcall, &[vmctx, exnref]);
+        Ok(())
+    }
+
+    /// Attempts to catch the pending exception with the given tag, or any
+    /// exception when `tag_index` is `None`.
+    ///
+    /// Returns a reference to the caught exception, as an `i64`, or zero if it
+    /// does not match, along with the address of the caught exception's
+    /// payload.
+    pub fn translate_catch(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        tag_index: Option<TagIndex>,
+    ) -> WasmResult<(ir::Value, ir::Value)> {
+        let libcall = gc::builtins::catch_exception(self, builder.func)?;
+        let pointer_type = self.pointer_type();
+        let (tag, payload) = match tag_index {
+            Some(tag_index) => {
+                let params = self.tag_params(tag_index);
+                let slot = self.payload_stack_slot(builder, params.len());
+                (
+                    i64::from(tag_index.as_u32()),
+                    builder.ins().stack_addr(pointer_type, slot, 0),
+                )
+            }
+            None => (i64::from(u32::MAX), builder.ins().iconst(pointer_type, 0)),
+        };
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let tag = builder.ins().iconst(I32, tag);
+        let call = builder.ins().call(libcall, &[vmctx, tag, payload]);
+        Ok((builder.func.dfg.first_result(call), payload))
+    }
+
+    /// Loads the payload of a caught exception with the given tag from the
+    /// address returned by `translate_catch`.
+    pub fn translate_catch_payload(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        tag_index: TagIndex,
+        payload: ir::Value,
+    ) -> SmallVec<[ir::Value; 4]> {
+        let params = self.tag_params(tag_index);
+        self.load_values_from_stack_slot(builder, params, payload)
+    }
+
+    /// Creates a new continuation from the given function reference.
+    pub fn translate_cont_new(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        func_ref: ir::Value,
+    ) -> WasmResult<ir::Value> {
+        let libcall = stack_switching::builtins::cont_new(self, builder.func)?;
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let call = builder.ins().call(libcall, &[vmctx, func_ref]);
+        Ok(self.contref_result(builder, call))
+    }
+
+    /// Binds `args` to the first parameters of the given continuation, whose
+    /// type is `type_index`, returning the new continuation.
+    pub fn translate_cont_bind(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        type_index: TypeIndex,
+        contref: ir::Value,
+        args: &[ir::Value],
+    ) -> WasmResult<ir::Value> {
+        let libcall = stack_switching::builtins::cont_bind(self, builder.func)?;
+        let params = &self.cont_type_params(type_index)[..args.len()];
+        let values = self.store_values_to_stack_slot(builder, params, args, args.len());
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let num_args = builder
+            .ins()
+            .iconst(I32, i64::try_from(args.len()).unwrap());
+        let call = builder
+            .ins()
+            .call(libcall, &[vmctx, contref, num_args, values]);
+        Ok(self.contref_result(builder, call))
+    }
+
+    fn contref_result(&mut self, builder: &mut FunctionBuilder, call: ir::Inst) -> ir::Value {
+        let contref = builder.func.dfg.first_result(call);
+        let contref = builder.ins().ireduce(I32, contref);
+        builder.declare_value_needs_stack_map(contref);
+        contref
+    }
+
+    /// Resumes the given continuation, whose type is `type_index`, with `args`,
+    /// or by throwing an exception with `throw_tag` and payload `args`.
+    /// `handlers` are the handled tags, and whether each is a `switch`
+    /// handler.
+    ///
+    /// Returns the index of the handler that the continuation suspended to,
+    /// or the number of handlers if it returned, along with the address of
+    /// the values passed to the handler or returned.
+    pub fn translate_resume(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        type_index: TypeIndex,
+        contref: ir::Value,
+        throw_tag: Option<TagIndex>,
+        args: &[ir::Value],
+        handlers: &[(TagIndex, bool)],
+    ) -> WasmResult<(ir::Value, ir::Value)> {
+        let libcall = stack_switching::builtins::resume(self, builder.func)?;
+        let params = match throw_tag {
+            Some(tag_index) => self.tag_params(tag_index),
+            None => self.cont_type_params(type_index),
+        };
+        let len = handlers
+            .iter()
+            .filter(|(_, switch)| !switch)
+            .map(|&(tag_index, _)| self.tag_params(tag_index).len() + 1)
+            .chain([params.len(), self.cont_type_results(type_index).len()])
+            .max()
+            .unwrap();
+        let values = self.store_values_to_stack_slot(builder, params, args, len);
+
+        let entry_size = mem::size_of::<u32>();
+        let slot = builder.func.create_sized_stack_slot(ir::StackSlotData::new(
+            ir::StackSlotKind::ExplicitSlot,
+            u32::try_from(handlers.len().max(1) * entry_size).unwrap(),
+            2,
+        ));
+        let table = builder.ins().stack_addr(self.pointer_type(), slot, 0);
+        for (i, &(tag_index, switch)) in handlers.iter().enumerate() {
+            let mut entry = tag_index.as_u32();
+            if switch {
+                entry |= RESUME_SWITCH_HANDLER_BIT;
+            }
+            let entry = builder.ins().iconst(I32, i64::from(entry));
+            let offset = i32::try_from(i * entry_size).unwrap();
+            builder
+                .ins()
+                .store(MemFlags::trusted(), entry, table, offset);
+        }
+
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let throw_tag = throw_tag.map_or(u32::MAX, |t| t.as_u32());
+        let throw_tag = builder.ins().iconst(I32, i64::from(throw_tag));
+        let num_handlers = builder
+            .ins()
+            .iconst(I32, i64::try_from(handlers.len()).unwrap());
+        let call = builder.ins().call(
+            libcall,
+            &[vmctx, contref, throw_tag, table, num_handlers, values],
+        );
+        let handler = builder.func.dfg.first_result(call);
+        let handler = builder.ins().ireduce(I32, handler);
+        self.after_stack_switch(builder);
+        Ok((handler, values))
+    }
+
+    /// Loads the results of a continuation of the given type that returned
+    /// from the address returned by `translate_resume`.
+    pub fn translate_resume_results(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        type_index: TypeIndex,
+        values: ir::Value,
+    ) -> SmallVec<[ir::Value; 4]> {
+        let results = self.cont_type_results(type_index);
+        self.load_values_from_stack_slot(builder, results, values)
+    }
+
+    /// Loads the arguments of the handler for the given tag, that is the tag's
+    /// payload followed by the suspended continuation, from the address
+    /// returned by `translate_resume`.
+    pub fn translate_resume_handler_args(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        tag_index: TagIndex,
+        values: ir::Value,
+    ) -> SmallVec<[ir::Value; 4]> {
+        let mut types = self.tag_params(tag_index).to_vec();
+        types.push(WasmValType::Ref(WasmRefType {
+            nullable: false,
+            heap_type: WasmHeapType::Cont,
+        }));
+        self.load_values_from_stack_slot(builder, &types, values)
+    }
+
+    /// Suspends the current continuation with the given tag and payload.
+    ///
+    /// Returns the address of the values that the continuation is resumed
+    /// with, to be loaded with `translate_suspend_results`.
+    pub fn translate_suspend(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        tag_index: TagIndex,
+        args: &[ir::Value],
+    ) -> WasmResult<ir::Value> {
+        let libcall = stack_switching::builtins::suspend(self, builder.func)?;
+        let params = self.tag_params(tag_index);
+        let len = params.len().max(self.tag_results(tag_index).len());
+        let values = self.store_values_to_stack_slot(builder, params, args, len);
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let tag = builder.ins().iconst(I32, i64::from(tag_index.as_u32()));
+        builder.ins().call(libcall, &[vmctx, tag, values]);
+        self.after_stack_switch(builder);
+        Ok(values)
+    }
+
+    /// Loads the values that a continuation that suspended with the given tag
+    /// was resumed with.
+    pub fn translate_suspend_results(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        tag_index: TagIndex,
+        values: ir::Value,
+    ) -> SmallVec<[ir::Value; 4]> {
+        let results = self.tag_results(tag_index);
+        self.load_values_from_stack_slot(builder, results, values)
+    }
+
+    /// Switches from the current continuation to the given one, whose type is
+    /// `type_index`, passing it `args`.
+    ///
+    /// Returns the address of the values that the current continuation is
+    /// resumed with, to be loaded with `translate_switch_results`.
+    pub fn translate_switch(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        type_index: TypeIndex,
+        tag_index: TagIndex,
+        contref: ir::Value,
+        args: &[ir::Value],
+    ) -> WasmResult<ir::Value> {
+        let libcall = stack_switching::builtins::switch(self, builder.func)?;
+        let params = &self.cont_type_params(type_index)[..args.len()];
+        let len = args.len().max(self.switch_results(type_index).len());
+        let values = self.store_values_to_stack_slot(builder, params, args, len);
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let tag = builder.ins().iconst(I32, i64::from(tag_index.as_u32()));
+        builder.ins().call(libcall, &[vmctx, contref, tag, values]);
+        self.after_stack_switch(builder);
+        Ok(values)
+    }
+
+    /// Loads the values that a continuation that switched to a continuation
+    /// of the given type was resumed with.
+    pub fn translate_switch_results(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        type_index: TypeIndex,
+        values: ir::Value,
+    ) -> SmallVec<[ir::Value; 4]> {
+        let results = self.switch_results(type_index);
+        self.load_values_from_stack_slot(builder, results, values)
+    }
+
+    /// Hook invoked after any instruction that switches stacks, once the
+    /// current continuation is executing again.
+    fn after_stack_switch(&mut self, builder: &mut FunctionBuilder) {
+        // Other continuations may have consumed fuel in the meantime.
+        if self.tunables.consume_fuel {
+            self.fuel_load_into_var(builder);
+        }
+    }
+
+    fn payload_stack_slot(&mut self, builder: &mut FunctionBuilder, len: usize) -> ir::StackSlot {
+        let size = u32::try_from(len.max(1) * mem::size_of::<u128>()).unwrap();
+        builder.func.create_sized_stack_slot(ir::StackSlotData::new(
+            ir::StackSlotKind::ExplicitSlot,
+            size,
+            4,
+        ))
+    }
+
+    /// Stores `values` to a new stack slot laid out as an array of `len`
+    /// `ValRaw`s, with room for at least as many values, and returns its
+    /// address.
+    fn store_values_to_stack_slot(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        types: &[WasmValType],
+        values: &[ir::Value],
+        len: usize,
+    ) -> ir::Value {
+        debug_assert_eq!(types.len(), values.len());
+        debug_assert!(values.len() <= len);
+        let slot = self.payload_stack_slot(builder, len);
+        let addr = builder.ins().stack_addr(self.pointer_type(), slot, 0);
+        let flags = MemFlags::trusted().with_endianness(ir::Endianness::Little);
+        for (i, val) in values.iter().enumerate() {
+            let offset = i32::try_from(i * mem::size_of::<u128>()).unwrap();
+            builder.ins().store(flags, *val, addr, offset);
+        }
+        addr
+    }
+
+    /// Loads values of the given types from an array of `ValRaw`s at `addr`.
+    fn load_values_from_stack_slot(
+        &mut self,
+        builder: &mut FunctionBuilder,
+        types: &[WasmValType],
+        addr: ir::Value,
+    ) -> SmallVec<[ir::Value; 4]> {
+        let flags = MemFlags::trusted().with_endianness(ir::Endianness::Little);
+        let mut values = SmallVec::new();
+        for (i, ty) in types.iter().enumerate() {
+            let offset = i32::try_from(i * mem::size_of::<u128>()).unwrap();
+            let (ir_ty, needs_stack_map) = match ty {
+                WasmValType::I32 => (I32, false),
+                WasmValType::I64 => (I64, false),
+                WasmValType::F32 => (F32, false),
+                WasmValType::F64 => (F64, false),
+                WasmValType::V128 => (I8X16, false),
+                WasmValType::Ref(r) => (
+                    self.reference_type(r.heap_type).0,
+                    ty.is_vmgcref_type_and_not_i31(),
+                ),
+            };
+            let val = builder.ins().load(ir_ty, flags, addr, offset);
+            if needs_stack_map {
+                builder.declare_value_needs_stack_map(val);
+            }
+            values.push(val);
+        }
+        values
+    }
+
     pub fn translate_ref_null(
         &mut self,
         mut pos: cranelift_codegen::cursor::FuncCursor,
@@ -2233,7 +2682,10 @@ impl FuncEnvironment<'_> {
         Ok(match ht.top() {
             WasmHeapTopType::Func => pos.ins().iconst(self.pointer_type(), 0),
             // NB: null GC references don't need to be in stack maps.
-            WasmHeapTopType::Any | WasmHeapTopType::Extern => pos.ins().iconst(types::I32, 0),
+            WasmHeapTopType::Any
+            | WasmHeapTopType::Extern
+            | WasmHeapTopType::Exn
+            | WasmHeapTopType::Cont => pos.ins().iconst(types::I32, 0),
         })
     }
 
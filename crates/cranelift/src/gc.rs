@@ -185,5 +185,8 @@ pub mod builtins {
         array_copy,
         array_init_data,
         array_init_elem,
+        throw,
+        throw_ref,
+        catch_exception,
     }
 }
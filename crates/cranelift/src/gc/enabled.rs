@@ -117,7 +117,10 @@ fn read_field_at_addr(
             WasmValType::F64 => builder.ins().load(ir::types::F64, flags, addr, 0),
             WasmValType::V128 => builder.ins().load(ir::types::I8X16, flags, addr, 0),
             WasmValType::Ref(r) => match r.heap_type.top() {
-                WasmHeapTopType::Any | WasmHeapTopType::Extern => gc_compiler(func_env)?
+                WasmHeapTopType::Any
+                | WasmHeapTopType::Extern
+                | WasmHeapTopType::Exn
+                | WasmHeapTopType::Cont => gc_compiler(func_env)?
                     .translate_read_gc_reference(func_env, builder, r, addr, flags)?,
                 WasmHeapTopType::Func => {
                     let expected_ty = match r.heap_type {
@@ -1053,8 +1056,19 @@ pub fn translate_ref_test(
         | WasmHeapType::NoExtern
         | WasmHeapType::Func
         | WasmHeapType::NoFunc
+        | WasmHeapType::Exn
+        | WasmHeapType::NoExn
+        | WasmHeapType::Cont
+        | WasmHeapType::NoCont
         | WasmHeapType::I31 => unreachable!("handled top, bottom, and i31 types above"),
 
+        // Continuations don't record their type at runtime.
+        WasmHeapType::ConcreteCont(_) => {
+            return Err(wasm_unsupported!(
+                "testing or casting to a concrete continuation type"
+            ))
+        }
+
         // For these abstract but non-top and non-bottom types, we check the
         // `VMGcKind` that is in the object's header.
         WasmHeapType::Eq => check_header_kind(func_env, builder, val, VMGcKind::EqRef),
@@ -1293,9 +1307,12 @@ impl FuncEnvironment<'_> {
     }
 
     /// Get the GC heap's bound.
+    ///
+    /// Note that this is not a read-only load: the GC heap may grow, updating
+    /// its bound, during any call out to the runtime.
     fn get_gc_heap_bound(&mut self, builder: &mut FunctionBuilder) -> ir::Value {
         let ptr_ty = self.pointer_type();
-        let flags = ir::MemFlags::trusted().with_readonly();
+        let flags = ir::MemFlags::trusted();
 
         let vmctx = self.vmctx(builder.func);
         let vmctx = builder.ins().global_value(ptr_ty, vmctx);
@@ -1422,7 +1439,13 @@ impl FuncEnvironment<'_> {
             | WasmHeapType::None => false,
 
             // Wrong type hierarchy: cannot be an i31.
-            WasmHeapType::Extern | WasmHeapType::NoExtern => false,
+            WasmHeapType::Extern
+            | WasmHeapType::NoExtern
+            | WasmHeapType::Exn
+            | WasmHeapType::NoExn
+            | WasmHeapType::Cont
+            | WasmHeapType::ConcreteCont(_)
+            | WasmHeapType::NoCont => false,
 
             // Wrong type hierarchy, and also funcrefs are not GC-managed
             // types. Should have been caught by the assertion at the start of
@@ -90,7 +90,9 @@ impl NullCompiler {
             .ins()
             .band(next_plus_align_minus_one, not_align_minus_one);
 
-        // Check whether the allocation fits in the heap space we have left.
+        // Check whether the allocation fits in the heap space we have left,
+        // and if not, call out to the runtime to grow the GC heap. The GC heap
+        // is never moved when it grows, so `base` remains valid afterwards.
         let end_of_object =
             func_env.uadd_overflow_trap(builder, aligned, size, crate::TRAP_ALLOCATION_TOO_LARGE);
         let uext_end_of_object = uextend_i32_to_pointer_type(builder, pointer_type, end_of_object);
@@ -100,7 +102,32 @@ impl NullCompiler {
             uext_end_of_object,
             bound,
         );
-        func_env.trapz(builder, is_in_bounds, crate::TRAP_ALLOCATION_TOO_LARGE);
+
+        let current_block = builder.current_block().unwrap();
+        let grow_block = builder.create_block();
+        let continue_block = builder.create_block();
+        builder.set_cold_block(grow_block);
+        builder.insert_block_after(grow_block, current_block);
+        builder.insert_block_after(continue_block, grow_block);
+        builder
+            .ins()
+            .brif(is_in_bounds, continue_block, &[], grow_block, &[]);
+
+        builder.switch_to_block(grow_block);
+        builder.seal_block(grow_block);
+        let bytes_needed = builder.ins().isub(uext_end_of_object, bound);
+        let bytes_needed = match pointer_type {
+            ir::types::I32 => builder.ins().uextend(ir::types::I64, bytes_needed),
+            ir::types::I64 => bytes_needed,
+            _ => unreachable!(),
+        };
+        let grow_gc_heap = func_env.builtin_functions.grow_gc_heap(builder.func);
+        let vmctx = func_env.vmctx_val(&mut builder.cursor());
+        builder.ins().call(grow_gc_heap, &[vmctx, bytes_needed]);
+        builder.ins().jump(continue_block, &[]);
+
+        builder.switch_to_block(continue_block);
+        builder.seal_block(continue_block);
 
         // Write the header, update the bump "pointer", and return the newly
         // allocated object.
@@ -35,6 +35,7 @@ mod compiler;
 mod debug;
 mod func_environ;
 mod gc;
+mod stack_switching;
 mod translate;
 
 use self::compiler::Compiler;
@@ -167,15 +168,15 @@ fn wasm_call_conv(isa: &dyn TargetIsa, tunables: &Tunables) -> CallConv {
     //
     // The Winch calling convention is used instead when generating trampolines
     // which call Winch-generated functions. The winch calling convention is
-    // only implemented for x64 and aarch64, so assert that here and panic on
-    // other architectures.
+    // only implemented for x64, aarch64 and riscv64, so assert that here and
+    // panic on other architectures.
     if tunables.winch_callable {
         assert!(
             matches!(
                 isa.triple().architecture,
-                Architecture::X86_64 | Architecture::Aarch64(_)
+                Architecture::X86_64 | Architecture::Aarch64(_) | Architecture::Riscv64(_)
             ),
-            "The Winch calling convention is only implemented for x86_64 and aarch64"
+            "The Winch calling convention is only implemented for x86_64, aarch64 and riscv64"
         );
         CallConv::Winch
     } else {
@@ -201,7 +202,10 @@ fn wasm_call_signature(
 fn reference_type(wasm_ht: WasmHeapType, pointer_type: ir::Type) -> ir::Type {
     match wasm_ht.top() {
         WasmHeapTopType::Func => pointer_type,
-        WasmHeapTopType::Any | WasmHeapTopType::Extern => ir::types::I32,
+        WasmHeapTopType::Any
+        | WasmHeapTopType::Extern
+        | WasmHeapTopType::Exn
+        | WasmHeapTopType::Cont => ir::types::I32,
     }
 }
 
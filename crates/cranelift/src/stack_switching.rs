@@ -0,0 +1,42 @@
+//! Interface to compiling stack-switching-related things.
+//!
+//! The instructions of the stack-switching proposal are implemented with
+//! libcalls into the runtime, which are only available when the
+//! `stack-switching` cargo feature is enabled.
+
+pub mod builtins {
+    use crate::func_environ::FuncEnvironment;
+    use cranelift_codegen::ir;
+    use wasmtime_environ::WasmResult;
+
+    macro_rules! define_builtin_accessors {
+        ( $( $name:ident , )* ) => {
+            $(
+                #[inline]
+                pub fn $name(
+                    func_env: &mut FuncEnvironment<'_>,
+                    func: &mut ir::Function,
+                ) -> WasmResult<ir::FuncRef> {
+                    #[cfg(feature = "stack-switching")]
+                    return Ok(func_env.builtin_functions.$name(func));
+
+                    #[cfg(not(feature = "stack-switching"))]
+                    let _ = (func, func_env);
+                    #[cfg(not(feature = "stack-switching"))]
+                    return Err(wasmtime_environ::wasm_unsupported!(
+                        "support for stack switching disabled at compile time because the \
+                         `stack-switching` cargo feature was not enabled"
+                    ));
+                }
+            )*
+        };
+    }
+
+    define_builtin_accessors! {
+        cont_new,
+        cont_bind,
+        resume,
+        suspend,
+        switch,
+    }
+}
@@ -91,10 +91,12 @@ use itertools::Itertools;
 use smallvec::SmallVec;
 use std::collections::{hash_map, HashMap};
 use std::vec::Vec;
-use wasmparser::{FuncValidator, MemArg, Operator, WasmModuleResources};
+use wasmparser::{
+    Catch, FuncValidator, Handle, MemArg, Operator, ResumeTable, WasmModuleResources,
+};
 use wasmtime_environ::{
     wasm_unsupported, DataIndex, ElemIndex, FuncIndex, GlobalIndex, MemoryIndex, Signed,
-    TableIndex, TypeConvert, TypeIndex, Unsigned, WasmRefType, WasmResult,
+    TableIndex, TagIndex, TypeConvert, TypeIndex, Unsigned, WasmRefType, WasmResult,
 };
 
 /// Given a `Reachability<T>`, unwrap the inner `T` or, when unreachable, set
@@ -453,6 +455,12 @@ pub fn translate_operator(
                 builder.seal_block(header)
             }
 
+            // If it is a `try_table` then no more calls can unwind into its
+            // handlers, so they can be sealed as well.
+            if let Some(handler) = state.pop_handler() {
+                builder.seal_block(handler);
+            }
+
             frame.truncate_value_stack_to_original_size(&mut state.stack);
             state
                 .stack
@@ -601,7 +609,6 @@ pub fn translate_operator(
         /********************************** Exception handing **********************************/
         Operator::Try { .. }
         | Operator::Catch { .. }
-        | Operator::Throw { .. }
         | Operator::Rethrow { .. }
         | Operator::Delegate { .. }
         | Operator::CatchAll => {
@@ -643,6 +650,9 @@ pub fn translate_operator(
             );
             state.popn(num_args);
             state.pushn(inst_results);
+            if validator.features().exceptions() {
+                translate_exception_check(builder, state, environ);
+            }
         }
         Operator::CallIndirect {
             type_index,
@@ -682,6 +692,9 @@ pub fn translate_operator(
             );
             state.popn(num_args);
             state.pushn(inst_results);
+            if validator.features().exceptions() {
+                translate_exception_check(builder, state, environ);
+            }
         }
         /******************************* Tail Calls ******************************************
          * The tail call instructions pop their arguments from the stack and
@@ -2477,6 +2490,9 @@ pub fn translate_operator(
             );
             state.popn(num_args);
             state.pushn(inst_results);
+            if validator.features().exceptions() {
+                translate_exception_check(builder, state, environ);
+            }
         }
         Operator::RefAsNonNull => {
             let r = state.pop1();
@@ -2577,10 +2593,43 @@ pub fn translate_operator(
             state.push1(val);
         }
 
-        Operator::TryTable { .. } | Operator::ThrowRef => {
-            return Err(wasm_unsupported!(
-                "exception operators are not yet implemented"
-            ));
+        Operator::TryTable { try_table } => {
+            let (params, results) = blocktype_params_results(validator, try_table.ty)?;
+            let next = block_with_params(builder, results.clone(), environ)?;
+
+            // The body of the `try_table` is a new block so that the code
+            // dispatching exceptions to its catch clauses can be emitted now,
+            // while the labels the clauses refer to are known.
+            let body = builder.create_block();
+            builder.ins().jump(body, &[]);
+            builder.seal_block(body);
+
+            let dispatch = builder.create_block();
+            builder.set_cold_block(dispatch);
+            builder.switch_to_block(dispatch);
+            environ.before_exception_dispatch(builder);
+            translate_catch_clauses(&try_table.catches, builder, state, environ)?;
+
+            builder.switch_to_block(body);
+            let depth = state.control_stack.len();
+            state.push_block(next, params.len(), results.len());
+            state.handlers.push((dispatch, depth));
+        }
+        Operator::Throw { tag_index } => {
+            let tag_index = TagIndex::from_u32(*tag_index);
+            let arity = environ.tag_params(tag_index).len();
+            environ.translate_throw(builder, tag_index, state.peekn(arity))?;
+            state.popn(arity);
+            let handler = exception_handler(builder, state);
+            builder.ins().jump(handler, &[]);
+            state.reachable = false;
+        }
+        Operator::ThrowRef => {
+            let exnref = state.pop1();
+            environ.translate_throw_ref(builder, exnref)?;
+            let handler = exception_handler(builder, state);
+            builder.ins().jump(handler, &[]);
+            state.reachable = false;
         }
 
         Operator::ArrayNew { array_type_index } => {
@@ -2900,15 +2949,90 @@ pub fn translate_operator(
             ));
         }
 
-        Operator::ContNew { .. }
-        | Operator::ContBind { .. }
-        | Operator::Suspend { .. }
-        | Operator::Resume { .. }
-        | Operator::ResumeThrow { .. }
-        | Operator::Switch { .. } => {
-            return Err(wasm_unsupported!(
-                "stack-switching operators are not yet implemented"
-            ));
+        Operator::ContNew { cont_type_index: _ } => {
+            let func_ref = state.pop1();
+            let contref = environ.translate_cont_new(builder, func_ref)?;
+            state.push1(contref);
+        }
+        Operator::ContBind {
+            argument_index,
+            result_index,
+        } => {
+            let argument_index = TypeIndex::from_u32(*argument_index);
+            let result_index = TypeIndex::from_u32(*result_index);
+            let arity = environ.cont_type_params(argument_index).len()
+                - environ.cont_type_params(result_index).len();
+            let contref = state.pop1();
+            let contref = environ.translate_cont_bind(
+                builder,
+                argument_index,
+                contref,
+                state.peekn(arity),
+            )?;
+            state.popn(arity);
+            state.push1(contref);
+        }
+        Operator::Resume {
+            cont_type_index,
+            resume_table,
+        } => {
+            translate_resume(
+                validator,
+                TypeIndex::from_u32(*cont_type_index),
+                None,
+                resume_table,
+                builder,
+                state,
+                environ,
+            )?;
+        }
+        Operator::ResumeThrow {
+            cont_type_index,
+            tag_index,
+            resume_table,
+        } => {
+            translate_resume(
+                validator,
+                TypeIndex::from_u32(*cont_type_index),
+                Some(TagIndex::from_u32(*tag_index)),
+                resume_table,
+                builder,
+                state,
+                environ,
+            )?;
+        }
+        Operator::Suspend { tag_index } => {
+            let tag_index = TagIndex::from_u32(*tag_index);
+            let arity = environ.tag_params(tag_index).len();
+            let values = environ.translate_suspend(builder, tag_index, state.peekn(arity))?;
+            state.popn(arity);
+            if validator.features().exceptions() {
+                translate_exception_check(builder, state, environ);
+            }
+            let results = environ.translate_suspend_results(builder, tag_index, values);
+            state.pushn(&results);
+        }
+        Operator::Switch {
+            cont_type_index,
+            tag_index,
+        } => {
+            let type_index = TypeIndex::from_u32(*cont_type_index);
+            let tag_index = TagIndex::from_u32(*tag_index);
+            let arity = environ.cont_type_params(type_index).len() - 1;
+            let contref = state.pop1();
+            let values = environ.translate_switch(
+                builder,
+                type_index,
+                tag_index,
+                contref,
+                state.peekn(arity),
+            )?;
+            state.popn(arity);
+            if validator.features().exceptions() {
+                translate_exception_check(builder, state, environ);
+            }
+            let results = environ.translate_switch_results(builder, type_index, values);
+            state.pushn(&results);
         }
 
         Operator::I64MulWideS => {
@@ -2976,7 +3100,9 @@ fn translate_unreachable_operator(
                 blockty,
             );
         }
-        Operator::Loop { blockty: _ } | Operator::Block { blockty: _ } => {
+        Operator::Loop { blockty: _ }
+        | Operator::Block { blockty: _ }
+        | Operator::TryTable { try_table: _ } => {
             state.push_block(ir::Block::reserved_value(), 0, 0);
         }
         Operator::Else => {
@@ -3080,6 +3206,10 @@ fn translate_unreachable_operator(
                 stack.extend_from_slice(builder.block_params(frame.following_code()));
                 state.reachable = true;
             }
+
+            if let Some(handler) = state.pop_handler() {
+                builder.seal_block(handler);
+            }
         }
         _ => {
             // We don't translate because this is unreachable code
@@ -3660,6 +3790,214 @@ fn translate_br_if_args(
     (br_destination, inputs)
 }
 
+/// Returns the block that a pending exception is dispatched to: the handlers of
+/// the innermost enclosing `try_table`, or a block propagating the exception
+/// to the caller if there is none.
+fn exception_handler(builder: &mut FunctionBuilder, state: &mut FuncTranslationState) -> ir::Block {
+    if let Some((handler, _)) = state.handlers.last() {
+        return *handler;
+    }
+    *state.propagate_exception_block.get_or_insert_with(|| {
+        let block = builder.create_block();
+        builder.set_cold_block(block);
+        block
+    })
+}
+
+/// Checks whether the call that was just translated left an exception pending
+/// and, if so, transfers control to the exception's handler.
+fn translate_exception_check(
+    builder: &mut FunctionBuilder,
+    state: &mut FuncTranslationState,
+    environ: &mut FuncEnvironment<'_>,
+) {
+    let pending = environ.translate_exception_pending(builder);
+    let handler = exception_handler(builder, state);
+    let continuation = builder.create_block();
+    builder.ins().brif(pending, handler, &[], continuation, &[]);
+    builder.seal_block(continuation); // The only predecessor is the current block.
+    builder.switch_to_block(continuation);
+}
+
+/// Dispatches a pending exception to the first matching catch clause of a
+/// `try_table`, or to the enclosing handler if none of them match.
+///
+/// This must be called before the `try_table`'s own frame is pushed onto the
+/// control stack since the clauses' labels are relative to its enclosing
+/// frames.
+fn translate_catch_clauses(
+    catches: &[Catch],
+    builder: &mut FunctionBuilder,
+    state: &mut FuncTranslationState,
+    environ: &mut FuncEnvironment<'_>,
+) -> WasmResult<()> {
+    for catch in catches {
+        let (tag_index, label, is_ref) = match *catch {
+            Catch::One { tag, label } => (Some(TagIndex::from_u32(tag)), label, false),
+            Catch::OneRef { tag, label } => (Some(TagIndex::from_u32(tag)), label, true),
+            Catch::All { label } => (None, label, false),
+            Catch::AllRef { label } => (None, label, true),
+        };
+        let (caught, payload) = environ.translate_catch(builder, tag_index)?;
+
+        let i = state.control_stack.len() - 1 - (label as usize);
+        let frame = &mut state.control_stack[i];
+        frame.set_branched_to_exit();
+        let destination = frame.br_destination();
+
+        // A `catch_all` always matches, but a tagged clause falls through to
+        // the next one when the exception's tag is different.
+        let mut args = SmallVec::<[Value; 4]>::new();
+        let no_match = match tag_index {
+            Some(tag_index) => {
+                let matched = builder.create_block();
+                let no_match = builder.create_block();
+                builder.ins().brif(caught, matched, &[], no_match, &[]);
+                builder.seal_block(matched);
+                builder.seal_block(no_match);
+                builder.switch_to_block(matched);
+                args = environ.translate_catch_payload(builder, tag_index, payload);
+                Some(no_match)
+            }
+            None => None,
+        };
+        if is_ref {
+            let exnref = builder.ins().ireduce(I32, caught);
+            builder.declare_value_needs_stack_map(exnref);
+            args.push(exnref);
+        }
+        canonicalise_then_jump(builder, destination, &args);
+
+        match no_match {
+            Some(block) => builder.switch_to_block(block),
+            None => return Ok(()),
+        }
+    }
+
+    let handler = exception_handler(builder, state);
+    builder.ins().jump(handler, &[]);
+    Ok(())
+}
+
+/// Translates `resume` and `resume_throw`, which continue with the
+/// continuation's results when it returns, and otherwise branch to the label
+/// of the handler for the tag it suspended with.
+fn translate_resume(
+    validator: &FuncValidator<impl WasmModuleResources>,
+    type_index: TypeIndex,
+    throw_tag: Option<TagIndex>,
+    resume_table: &ResumeTable,
+    builder: &mut FunctionBuilder,
+    state: &mut FuncTranslationState,
+    environ: &mut FuncEnvironment<'_>,
+) -> WasmResult<()> {
+    let handlers = resume_table
+        .handlers
+        .iter()
+        .map(|handle| match *handle {
+            Handle::OnLabel { tag, .. } => (TagIndex::from_u32(tag), false),
+            Handle::OnSwitch { tag } => (TagIndex::from_u32(tag), true),
+        })
+        .collect::<SmallVec<[_; 4]>>();
+    let arity = match throw_tag {
+        Some(tag_index) => environ.tag_params(tag_index).len(),
+        None => environ.cont_type_params(type_index).len(),
+    };
+    let contref = state.pop1();
+    let (handler, values) = environ.translate_resume(
+        builder,
+        type_index,
+        contref,
+        throw_tag,
+        state.peekn(arity),
+        &handlers,
+    )?;
+    state.popn(arity);
+    if validator.features().exceptions() {
+        translate_exception_check(builder, state, environ);
+    }
+
+    let finished = builder.create_block();
+    let mut targets = Vec::with_capacity(handlers.len());
+    let mut labels = Vec::new();
+    for handle in &resume_table.handlers {
+        let block = match *handle {
+            Handle::OnLabel { tag, label } => {
+                let block = builder.create_block();
+                labels.push((block, TagIndex::from_u32(tag), label));
+                block
+            }
+            // Switching to another continuation is handled by the runtime,
+            // which never returns the index of a `switch` handler.
+            Handle::OnSwitch { .. } => finished,
+        };
+        targets.push(builder.func.dfg.block_call(block, &[]));
+    }
+    if !labels.is_empty() {
+        let default = builder.func.dfg.block_call(finished, &[]);
+        let jt = builder.create_jump_table(JumpTableData::new(default, &targets));
+        builder.ins().br_table(handler, jt);
+        for (block, tag_index, label) in labels {
+            builder.seal_block(block); // The only predecessor is the `br_table`.
+            builder.switch_to_block(block);
+            let args = environ.translate_resume_handler_args(builder, tag_index, values);
+            let i = state.control_stack.len() - 1 - (label as usize);
+            let frame = &mut state.control_stack[i];
+            frame.set_branched_to_exit();
+            canonicalise_then_jump(builder, frame.br_destination(), &args);
+        }
+    } else {
+        builder.ins().jump(finished, &[]);
+    }
+    builder.seal_block(finished);
+    builder.switch_to_block(finished);
+
+    let results = environ.translate_resume_results(builder, type_index, values);
+    state.pushn(&results);
+    Ok(())
+}
+
+/// Fills in the block propagating pending exceptions to the caller, if any
+/// code in the function needed it.
+///
+/// The exception stays pending in the store, so this just returns from the
+/// function with dummy results which the caller will never observe.
+pub fn translate_propagate_exception_block(
+    builder: &mut FunctionBuilder,
+    state: &mut FuncTranslationState,
+) {
+    let Some(block) = state.propagate_exception_block.take() else {
+        return;
+    };
+    builder.switch_to_block(block);
+    builder.seal_block(block);
+    let results = builder
+        .func
+        .signature
+        .returns
+        .iter()
+        .map(|ret| ret.value_type)
+        .collect::<SmallVec<[Type; 4]>>();
+    let results = results
+        .into_iter()
+        .map(|ty| match ty {
+            F32 => builder.ins().f32const(0.0),
+            F64 => builder.ins().f64const(0.0),
+            ty if ty.is_vector() => {
+                let zero =
+                    builder
+                        .func
+                        .dfg
+                        .constants
+                        .insert(ConstantData::from(vec![0; ty.bytes() as usize]));
+                builder.ins().vconst(ty, zero)
+            }
+            ty => builder.ins().iconst(ty, 0),
+        })
+        .collect::<SmallVec<[Value; 4]>>();
+    builder.ins().return_(&results);
+}
+
 /// Determine the returned value type of a WebAssembly operator
 fn type_of(operator: &Operator) -> Type {
     match operator {
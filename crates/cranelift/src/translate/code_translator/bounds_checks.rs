@@ -61,12 +61,21 @@ pub fn bounds_check_and_compute_addr(
         .memory
         .can_use_virtual_memory(env.tunables(), host_page_size_log2)
         && clif_memory_traps_enabled;
-    let can_elide_bounds_check = heap
+    let memory_guard_size = env.tunables().memory_guard_size;
+    let memory_reservation = env.tunables().memory_reservation;
+    let can_elide_bounds_check = (heap
         .memory
         .can_elide_bounds_check(env.tunables(), host_page_size_log2)
+        // A 64-bit index that is zero-extended from a 32-bit value, such as
+        // one produced by `i64.extend_i32_u`, is bounded just like the index
+        // of a 32-bit memory, so the same elision applies to it. This is
+        // common in code that was ported from 32-bit memories, and isn't
+        // supported with proof-carrying code at this time.
+        || (can_use_virtual_memory
+            && !pcc
+            && memory_reservation >= (1 << 32)
+            && index_fits_in_u32(&builder.func, index)))
         && clif_memory_traps_enabled;
-    let memory_guard_size = env.tunables().memory_guard_size;
-    let memory_reservation = env.tunables().memory_reservation;
 
     let statically_in_bounds = statically_in_bounds(&builder.func, heap, index, offset_and_size);
 
@@ -202,14 +211,16 @@ pub fn bounds_check_and_compute_addr(
     //     index <= u32::MAX <= bound + guard_size - (offset + access_size)
     //
     // This expression is always true when the heap is indexed with
-    // 32-bit integers because `index` cannot be larger than
-    // `u32::MAX`. This means that `index` is always either in bounds or
-    // within the guard page region, neither of which require emitting an
-    // explicit bounds check.
+    // 32-bit integers, or with 64-bit integers that were zero-extended from
+    // 32-bit ones, because `index` cannot be larger than `u32::MAX`. This
+    // means that `index` is always either in bounds or within the guard page
+    // region, neither of which require emitting an explicit bounds check.
     if can_elide_bounds_check
         && u64::from(u32::MAX) <= memory_reservation + memory_guard_size - offset_and_size
     {
-        assert!(heap.index_type() == ir::types::I32);
+        assert!(
+            heap.index_type() == ir::types::I32 || index_fits_in_u32(&builder.func, orig_index)
+        );
         assert!(
             can_use_virtual_memory,
             "static memories require the ability to use virtual memory"
@@ -764,6 +775,24 @@ fn offset_plus_size(offset: u32, size: u8) -> u64 {
     offset as u64 + size as u64
 }
 
+/// Returns whether `index` is statically known to be no larger than
+/// `u32::MAX`, because it is a 32-bit value or is zero-extended from one.
+fn index_fits_in_u32(func: &ir::Function, index: ir::Value) -> bool {
+    if func.dfg.value_type(index).bits() <= 32 {
+        return true;
+    }
+    match func.dfg.value_def(index).inst() {
+        Some(inst) => match func.dfg.insts[inst] {
+            ir::InstructionData::Unary {
+                opcode: ir::Opcode::Uextend,
+                arg,
+            } => func.dfg.value_type(arg).bits() <= 32,
+            _ => false,
+        },
+        None => false,
+    }
+}
+
 /// Returns whether `index` is statically in-bounds with respect to this
 /// `heap`'s configuration.
 ///
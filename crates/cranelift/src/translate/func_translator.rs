@@ -5,7 +5,9 @@
 //! WebAssembly module and the runtime environment.
 
 use crate::func_environ::FuncEnvironment;
-use crate::translate::code_translator::{bitcast_wasm_returns, translate_operator};
+use crate::translate::code_translator::{
+    bitcast_wasm_returns, translate_operator, translate_propagate_exception_block,
+};
 use crate::translate::state::FuncTranslationState;
 use crate::translate::translation_utils::get_vmctx_value_label;
 use crate::translate::TargetEnvironment;
@@ -273,6 +275,8 @@ fn parse_function_body(
     // or the end of the function is unreachable.
     state.stack.clear();
 
+    translate_propagate_exception_block(builder, state);
+
     Ok(())
 }
 
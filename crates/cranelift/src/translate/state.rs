@@ -225,6 +225,17 @@ pub struct FuncTranslationState {
     /// like End, Return, or Unreachable.
     pub(crate) reachable: bool,
 
+    /// A stack of the exception handlers of the active `try_table`s.
+    ///
+    /// Each entry is the block that dispatches a pending exception to the
+    /// `try_table`'s catch clauses, along with the size of the control stack
+    /// just before the `try_table`'s frame was pushed.
+    pub(crate) handlers: Vec<(Block, usize)>,
+
+    /// The block that propagates a pending exception to this function's
+    /// caller, if any code has needed it yet.
+    pub(crate) propagate_exception_block: Option<Block>,
+
     // Map of global variables that have already been created by `FuncEnvironment::make_global`.
     globals: HashMap<GlobalIndex, GlobalVariable>,
 
@@ -258,6 +269,8 @@ impl FuncTranslationState {
             stack: Vec::new(),
             control_stack: Vec::new(),
             reachable: true,
+            handlers: Vec::new(),
+            propagate_exception_block: None,
             globals: HashMap::new(),
             memory_to_heap: HashMap::new(),
             signatures: HashMap::new(),
@@ -268,7 +281,9 @@ impl FuncTranslationState {
     fn clear(&mut self) {
         debug_assert!(self.stack.is_empty());
         debug_assert!(self.control_stack.is_empty());
+        debug_assert!(self.handlers.is_empty());
         self.reachable = true;
+        self.propagate_exception_block = None;
         self.globals.clear();
         self.memory_to_heap.clear();
         self.signatures.clear();
@@ -407,6 +422,18 @@ impl FuncTranslationState {
         });
     }
 
+    /// Pop the exception handler of the `try_table` whose frame was just
+    /// popped off of the control stack, if that frame was for a reachable
+    /// `try_table`.
+    pub(crate) fn pop_handler(&mut self) -> Option<Block> {
+        match self.handlers.last() {
+            Some((_, depth)) if *depth == self.control_stack.len() => {
+                self.handlers.pop().map(|(block, _)| block)
+            }
+            _ => None,
+        }
+    }
+
     /// Push a loop on the control stack.
     pub(crate) fn push_loop(
         &mut self,
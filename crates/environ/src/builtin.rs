@@ -90,6 +90,12 @@ macro_rules! foreach_builtin_function {
                 align: i32
             ) -> i64;
 
+            // Grow the GC heap so that at least `bytes_needed` more bytes are
+            // available past its current bound. Used by the null collector's
+            // inline bump allocation once it reaches the end of the GC heap.
+            #[cfg(feature = "gc-null")]
+            grow_gc_heap(vmctx: vmctx, bytes_needed: i64) -> bool;
+
             // Intern a `funcref` into the GC heap, returning its
             // `FuncRefTableId`.
             //
@@ -196,6 +202,69 @@ macro_rules! foreach_builtin_function {
             #[cfg(feature = "gc")]
             table_fill_gc_ref(vmctx: vmctx, table: i32, dst: i64, val: i32, len: i64) -> bool;
 
+            // Begins throwing a new exception with the given tag, whose
+            // payload is read from the `ValRaw` array at `payload`.
+            #[cfg(feature = "gc")]
+            throw(vmctx: vmctx, tag: i32, payload: pointer) -> bool;
+
+            // Begins rethrowing the exception referenced by `exnref`.
+            #[cfg(feature = "gc")]
+            throw_ref(vmctx: vmctx, exnref: i32) -> bool;
+
+            // Attempts to catch the pending exception with the given tag, or
+            // any exception if `tag` is `u32::MAX`. On a match the payload is
+            // written to the `ValRaw` array at `payload`, the exception stops
+            // being pending, and a reference to it is returned. Otherwise
+            // returns zero.
+            #[cfg(feature = "gc")]
+            catch_exception(vmctx: vmctx, tag: i32, payload: pointer) -> i64;
+
+            // Creates a new continuation from the given function reference and
+            // returns a reference to it.
+            #[cfg(feature = "stack-switching")]
+            cont_new(vmctx: vmctx, func_ref: pointer) -> i64;
+
+            // Binds the first `num_args` arguments of the given continuation,
+            // read from the `ValRaw` array at `args`, consuming it and
+            // returning a reference to a new continuation.
+            #[cfg(feature = "stack-switching")]
+            cont_bind(vmctx: vmctx, contref: i32, num_args: i32, args: pointer) -> i64;
+
+            // Resumes the given continuation with the arguments in the `ValRaw`
+            // array at `values`, or by throwing an exception with `throw_tag`
+            // and that payload if the tag is not `u32::MAX`. `handlers` is an
+            // array of `num_handlers` tag indices, with the high bit set for
+            // `switch` handlers.
+            //
+            // Returns `num_handlers` if the continuation returned, in which
+            // case its results are in `values`. Otherwise returns the index of
+            // the handler for the tag it suspended with, and `values` holds the
+            // tag's payload followed by a reference to the new continuation.
+            #[cfg(feature = "stack-switching")]
+            resume(
+                vmctx: vmctx,
+                contref: i32,
+                throw_tag: i32,
+                handlers: pointer,
+                num_handlers: i32,
+                values: pointer
+            ) -> i64;
+
+            // Suspends the current continuation to the handler for the given
+            // tag. The tag's payload is read from the `ValRaw` array at
+            // `values`, and the values it is resumed with are written back to
+            // it.
+            #[cfg(feature = "stack-switching")]
+            suspend(vmctx: vmctx, tag: i32, values: pointer) -> bool;
+
+            // Switches from the current continuation to the given one, passing
+            // it the values in the `ValRaw` array at `values` followed by a
+            // reference to the suspended current continuation. The values the
+            // current continuation is resumed with are written back to
+            // `values`.
+            #[cfg(feature = "stack-switching")]
+            switch(vmctx: vmctx, contref: i32, tag: i32, values: pointer) -> bool;
+
             // Raises an unconditional trap with the specified code.
             //
             // This is used when signals-based-traps are disabled for backends
@@ -209,6 +278,11 @@ macro_rules! foreach_builtin_function {
     };
 }
 
+/// The bit that is set in the entries of the handler table passed to the
+/// `resume` builtin for `(on $tag switch)` handlers, as opposed to
+/// `(on $tag $label)` handlers.
+pub const RESUME_SWITCH_HANDLER_BIT: u32 = 1 << 31;
+
 /// Helper macro to define a builtin type such as `BuiltinFunctionIndex` and
 /// `ComponentBuiltinFunctionIndex` using the iterator macro, e.g.
 /// `foreach_builtin_function`, as the way to generate accessor methods.
@@ -361,6 +435,10 @@ impl BuiltinFunctionIndex {
             (@get gc_alloc_raw i64) => (TrapSentinel::Negative);
             (@get array_new_data i64) => (TrapSentinel::Negative);
             (@get array_new_elem i64) => (TrapSentinel::Negative);
+            (@get catch_exception i64) => (TrapSentinel::Negative);
+            (@get cont_new i64) => (TrapSentinel::Negative);
+            (@get cont_bind i64) => (TrapSentinel::Negative);
+            (@get resume i64) => (TrapSentinel::Negative);
 
             // The final epoch represents a trap
             (@get new_epoch i64) => (TrapSentinel::NegativeOne);
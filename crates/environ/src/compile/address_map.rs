@@ -1,6 +1,6 @@
 //! Data structures to provide transformation of the source
 
-use crate::obj::ELF_WASMTIME_ADDRMAP;
+use crate::obj::{ELF_WASMTIME_ADDRMAP, ELF_WASMTIME_INLINED_CALLS};
 use crate::prelude::*;
 use crate::InstructionAddressMap;
 use object::write::{Object, StandardSegment};
@@ -11,9 +11,16 @@ use std::ops::Range;
 ///
 /// This builder is used to conveniently built the `ELF_WASMTIME_ADDRMAP`
 /// section by compilers, and provides utilities to directly insert the results
-/// into an `Object`.
+/// into an `Object`. The `ELF_WASMTIME_INLINED_CALLS` section, which shares
+/// its encoding, is built alongside it.
 #[derive(Default)]
 pub struct AddressMapSection {
+    instrs: Entries,
+    inlined_calls: Entries,
+}
+
+#[derive(Default)]
+struct Entries {
     offsets: Vec<U32Bytes<LittleEndian>>,
     positions: Vec<U32Bytes<LittleEndian>>,
     last_offset: u32,
@@ -33,6 +40,31 @@ impl AddressMapSection {
     /// `instrs` map must be sorted based on code offset in the native text
     /// section.
     pub fn push(&mut self, func: Range<u64>, instrs: &[InstructionAddressMap]) {
+        self.instrs.push(func, instrs);
+    }
+
+    /// Pushes the call sites of code inlined into a function added in the
+    /// executable.
+    ///
+    /// This is like `push`, except that the `srcloc` of each of the `calls` is
+    /// the position of the call that the code starting at `code_offset` was
+    /// inlined at, or the default position for code which wasn't inlined.
+    pub fn push_inlined_calls(&mut self, func: Range<u64>, calls: &[InstructionAddressMap]) {
+        self.inlined_calls.push(func, calls);
+    }
+
+    /// Finishes encoding this section into the `Object` provided.
+    pub fn append_to(self, obj: &mut Object) {
+        self.instrs.append_to(obj, ELF_WASMTIME_ADDRMAP);
+        if !self.inlined_calls.offsets.is_empty() {
+            self.inlined_calls
+                .append_to(obj, ELF_WASMTIME_INLINED_CALLS);
+        }
+    }
+}
+
+impl Entries {
+    fn push(&mut self, func: Range<u64>, instrs: &[InstructionAddressMap]) {
         // NB: for now this only supports <=4GB text sections in object files.
         // Alternative schemes will need to be created for >32-bit offsets to
         // avoid making this section overly large.
@@ -56,11 +88,10 @@ impl AddressMapSection {
         self.last_offset = func_end;
     }
 
-    /// Finishes encoding this section into the `Object` provided.
-    pub fn append_to(self, obj: &mut Object) {
+    fn append_to(self, obj: &mut Object, name: &str) {
         let section = obj.add_section(
             obj.segment_name(StandardSegment::Data).to_vec(),
-            ELF_WASMTIME_ADDRMAP.as_bytes().to_vec(),
+            name.as_bytes().to_vec(),
             SectionKind::ReadOnlyData,
         );
 
@@ -5,7 +5,7 @@ use crate::prelude::*;
 use crate::{
     obj, CompiledFunctionInfo, CompiledModuleInfo, DebugInfoData, DefinedFuncIndex, FunctionLoc,
     FunctionName, MemoryInitialization, Metadata, ModuleInternedTypeIndex, ModuleTranslation,
-    PrimaryMap, Tunables,
+    PrimaryMap, ProfileData, Tunables,
 };
 use anyhow::{bail, Result};
 use object::write::{Object, SectionId, StandardSegment, WritableBuffer};
@@ -126,6 +126,7 @@ impl<'a> ObjectBuilder<'a> {
             data,
             data_align,
             passive_data,
+            wasm,
             ..
         } = translation;
 
@@ -227,6 +228,7 @@ impl<'a> ObjectBuilder<'a> {
                 code_section_offset: debuginfo.wasm_file.code_section_offset,
                 has_wasm_debuginfo: self.tunables.parse_wasm_debuginfo,
                 dwarf,
+                wasm_hash: ProfileData::hash_wasm(wasm),
             },
         })
     }
@@ -6,9 +6,10 @@ use crate::prelude::*;
 use crate::{
     ConstExpr, ConstOp, DataIndex, DefinedFuncIndex, ElemIndex, EngineOrModuleTypeIndex,
     EntityIndex, EntityType, FuncIndex, GlobalIndex, IndexType, InitMemory, MemoryIndex,
-    ModuleInternedTypeIndex, ModuleTypesBuilder, PrimaryMap, SizeOverflow, StaticMemoryInitializer,
-    TableIndex, TableInitialValue, Tunables, TypeConvert, TypeIndex, Unsigned, WasmError,
-    WasmHeapTopType, WasmHeapType, WasmResult, WasmValType, WasmparserTypeConverter,
+    ModuleInternedTypeIndex, ModuleTypesBuilder, PrimaryMap, ProfileData, SizeOverflow,
+    StaticMemoryInitializer, TableIndex, TableInitialValue, Tag, TagIndex, Tunables, TypeConvert,
+    TypeIndex, Unsigned, WasmError, WasmHeapTopType, WasmHeapType, WasmResult, WasmValType,
+    WasmparserTypeConverter,
 };
 use anyhow::{bail, Result};
 use cranelift_entity::packed_option::ReservedValue;
@@ -54,6 +55,17 @@ pub struct ModuleTranslation<'data> {
     /// References to the function bodies.
     pub function_body_inputs: PrimaryMap<DefinedFuncIndex, FunctionBodyData<'data>>,
 
+    /// The bodies of all defined functions, which unlike
+    /// `function_body_inputs` are left in place while compiling.
+    ///
+    /// This is only populated when `Tunables::inlining` is enabled so that
+    /// compilers can translate callees while compiling their callers.
+    pub function_bodies: PrimaryMap<DefinedFuncIndex, FunctionBody<'data>>,
+
+    /// A profile of the module's execution to guide its compilation, if one
+    /// was provided.
+    pub profile_data: Option<&'data ProfileData>,
+
     /// A list of type signatures which are considered exported from this
     /// module, or those that can possibly be called. This list is sorted, and
     /// trampolines for each of these signatures are required.
@@ -318,9 +330,10 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                             self.result.module.num_imported_tables += 1;
                             EntityType::Table(self.convert_table_type(&ty)?)
                         }
-
-                        // doesn't get past validation
-                        TypeRef::Tag(_) => unreachable!(),
+                        TypeRef::Tag(ty) => {
+                            self.result.module.num_imported_tags += 1;
+                            EntityType::Tag(self.convert_tag_type(ty))
+                        }
                     };
                     self.declare_import(import.module, import.name, ty);
                 }
@@ -384,9 +397,13 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
             Payload::TagSection(tags) => {
                 self.validator.tag_section(&tags)?;
 
-                // This feature isn't enabled at this time, so we should
-                // never get here.
-                unreachable!();
+                let cnt = usize::try_from(tags.count()).unwrap();
+                self.result.module.tags.reserve_exact(cnt);
+
+                for entry in tags {
+                    let tag = self.convert_tag_type(entry?);
+                    self.result.module.tags.push(tag);
+                }
             }
 
             Payload::GlobalSection(globals) => {
@@ -424,9 +441,7 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                         ExternalKind::Table => EntityIndex::Table(TableIndex::from_u32(index)),
                         ExternalKind::Memory => EntityIndex::Memory(MemoryIndex::from_u32(index)),
                         ExternalKind::Global => EntityIndex::Global(GlobalIndex::from_u32(index)),
-
-                        // this never gets past validation
-                        ExternalKind::Tag => unreachable!(),
+                        ExternalKind::Tag => EntityIndex::Tag(TagIndex::from_u32(index)),
                     };
                     self.result
                         .module
@@ -550,6 +565,9 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                             params: sig.params().into(),
                         });
                 }
+                if self.tunables.inlining {
+                    self.result.function_bodies.push(body.clone());
+                }
                 self.result
                     .function_body_inputs
                     .push(FunctionBodyData { validator, body });
@@ -768,7 +786,18 @@ and for re-adding support for interface types you can see this issue:
             EntityType::Table(ty) => EntityIndex::Table(self.result.module.tables.push(ty)),
             EntityType::Memory(ty) => EntityIndex::Memory(self.result.module.memories.push(ty)),
             EntityType::Global(ty) => EntityIndex::Global(self.result.module.globals.push(ty)),
-            EntityType::Tag(_) => unimplemented!(),
+            EntityType::Tag(ty) => EntityIndex::Tag(self.result.module.tags.push(ty)),
+        }
+    }
+
+    fn convert_tag_type(&self, ty: wasmparser::TagType) -> Tag {
+        match ty.kind {
+            wasmparser::TagKind::Exception => {
+                let index = TypeIndex::from_u32(ty.func_type_idx);
+                Tag {
+                    signature: EngineOrModuleTypeIndex::Module(self.result.module.types[index]),
+                }
+            }
         }
     }
 
@@ -1209,7 +1238,10 @@ impl ModuleTranslation<'_> {
                 // initializer won't trap so we could continue processing
                 // segments, but that's left as a future optimization if
                 // necessary.
-                WasmHeapTopType::Any | WasmHeapTopType::Extern => break,
+                WasmHeapTopType::Any
+                | WasmHeapTopType::Extern
+                | WasmHeapTopType::Exn
+                | WasmHeapTopType::Cont => break,
             }
 
             // Function indices can be optimized here, but fully general
@@ -41,6 +41,10 @@ pub struct CompiledComponentInfo {
     /// The location of the wasm-to-array trampoline for the `resource.drop`
     /// intrinsic.
     pub resource_drop_wasm_to_array_trampoline: Option<FunctionLoc>,
+
+    /// Hash of the original component binary, as computed by
+    /// [`ProfileData::hash_wasm`](crate::ProfileData::hash_wasm).
+    pub wasm_hash: u64,
 }
 
 /// A triple of related functions/trampolines variants with differing calling
@@ -2,9 +2,9 @@ use crate::component::*;
 use crate::prelude::*;
 use crate::ScopeVec;
 use crate::{
-    EngineOrModuleTypeIndex, EntityIndex, ModuleEnvironment, ModuleInternedTypeIndex,
-    ModuleTranslation, ModuleTypesBuilder, PrimaryMap, Tunables, TypeConvert, WasmHeapType,
-    WasmResult, WasmValType,
+    wasm_unsupported, EngineOrModuleTypeIndex, EntityIndex, ModuleEnvironment,
+    ModuleInternedTypeIndex, ModuleTranslation, ModuleTypesBuilder, PrimaryMap, Tunables,
+    TypeConvert, WasmHeapType, WasmResult, WasmValType,
 };
 use anyhow::anyhow;
 use anyhow::{bail, Result};
@@ -850,7 +850,7 @@ impl<'a, 'data> Translator<'a, 'data> {
                             self.instantiate_module(index, &args)
                         }
                         wasmparser::Instance::FromExports(exports) => {
-                            self.instantiate_module_from_exports(&exports)
+                            self.instantiate_module_from_exports(&exports)?
                         }
                     };
                     self.result.initializers.push(init);
@@ -927,7 +927,7 @@ impl<'a, 'data> Translator<'a, 'data> {
                             name,
                         } => {
                             let instance = ModuleInstanceIndex::from_u32(instance_index);
-                            self.alias_module_instance_export(kind, instance, name)
+                            self.alias_module_instance_export(kind, instance, name)?
                         }
                     };
                     self.result.initializers.push(init);
@@ -976,7 +976,7 @@ impl<'a, 'data> Translator<'a, 'data> {
     fn instantiate_module_from_exports(
         &mut self,
         exports: &[wasmparser::Export<'data>],
-    ) -> LocalInitializer<'data> {
+    ) -> Result<LocalInitializer<'data>> {
         let mut map = HashMap::with_capacity(exports.len());
         for export in exports {
             let idx = match export.kind {
@@ -997,12 +997,16 @@ impl<'a, 'data> Translator<'a, 'data> {
                     EntityIndex::Global(index)
                 }
 
-                // doesn't get past validation
-                wasmparser::ExternalKind::Tag => unimplemented!("wasm exceptions"),
+                wasmparser::ExternalKind::Tag => {
+                    return Err(wasm_unsupported!(
+                        "exporting a tag from a synthetic core instance in a component"
+                    )
+                    .into());
+                }
             };
             map.insert(export.name, idx);
         }
-        LocalInitializer::ModuleSynthetic(map)
+        Ok(LocalInitializer::ModuleSynthetic(map))
     }
 
     fn instantiate_component(
@@ -1074,16 +1078,19 @@ impl<'a, 'data> Translator<'a, 'data> {
         kind: wasmparser::ExternalKind,
         instance: ModuleInstanceIndex,
         name: &'data str,
-    ) -> LocalInitializer<'data> {
-        match kind {
+    ) -> Result<LocalInitializer<'data>> {
+        Ok(match kind {
             wasmparser::ExternalKind::Func => LocalInitializer::AliasExportFunc(instance, name),
             wasmparser::ExternalKind::Memory => LocalInitializer::AliasExportMemory(instance, name),
             wasmparser::ExternalKind::Table => LocalInitializer::AliasExportTable(instance, name),
             wasmparser::ExternalKind::Global => LocalInitializer::AliasExportGlobal(instance, name),
             wasmparser::ExternalKind::Tag => {
-                unimplemented!("wasm exceptions");
+                return Err(wasm_unsupported!(
+                    "aliasing a tag exported from a core instance in a component"
+                )
+                .into());
             }
-        }
+        })
     }
 
     fn alias_component_outer(
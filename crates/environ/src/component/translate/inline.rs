@@ -1229,6 +1229,9 @@ impl<'a> Inliner<'a> {
                 EntityIndex::Table(i) => frame.tables[i].clone().into(),
                 EntityIndex::Global(i) => frame.globals[i].clone().into(),
                 EntityIndex::Memory(i) => frame.memories[i].clone().into(),
+                // Synthetic instances with tags are rejected during
+                // translation.
+                EntityIndex::Tag(_) => unreachable!(),
             },
         }
     }
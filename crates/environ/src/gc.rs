@@ -158,14 +158,14 @@ pub trait GcTypeLayouts {
 
     /// Get this collector's layout for the given composite type.
     ///
-    /// Returns `None` if the type is a function type, as functions are not
-    /// managed by the GC.
+    /// Returns `None` if the type is a function or continuation type, as
+    /// functions and continuations are not managed by the GC.
     fn gc_layout(&self, ty: &WasmCompositeType) -> Option<GcLayout> {
         assert!(!ty.shared);
         match &ty.inner {
             WasmCompositeInnerType::Array(ty) => Some(self.array_layout(ty).into()),
             WasmCompositeInnerType::Struct(ty) => Some(self.struct_layout(ty).into()),
-            WasmCompositeInnerType::Func(_) => None,
+            WasmCompositeInnerType::Func(_) | WasmCompositeInnerType::Cont(_) => None,
         }
     }
 
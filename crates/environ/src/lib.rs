@@ -26,6 +26,7 @@ mod module;
 mod module_artifacts;
 mod module_types;
 pub mod obj;
+mod profile_data;
 mod ref_bits;
 mod scopevec;
 mod stack_map;
@@ -44,6 +45,7 @@ pub use crate::hostcall::*;
 pub use crate::module::*;
 pub use crate::module_artifacts::*;
 pub use crate::module_types::*;
+pub use crate::profile_data::ProfileData;
 pub use crate::ref_bits::*;
 pub use crate::scopevec::ScopeVec;
 pub use crate::stack_map::StackMap;
@@ -110,6 +110,13 @@ pub struct Metadata {
     /// Dwarf sections and the offsets at which they're stored in the
     /// ELF_WASMTIME_DWARF
     pub dwarf: Vec<(u8, Range<u64>)>,
+
+    /// Hash of the original wasm binary, as computed by
+    /// [`ProfileData::hash_wasm`](crate::ProfileData::hash_wasm).
+    ///
+    /// For core modules within a component this is the hash of the whole
+    /// component, as that's what their offsets are relative to.
+    pub wasm_hash: u64,
 }
 
 /// Value of a configured setting for a [`Compiler`](crate::Compiler)
@@ -52,6 +52,17 @@ pub const SH_WASMTIME_NOT_EXECUTED: u64 = 1 << 0;
 /// mean that >=4gb text sections are not supported.
 pub const ELF_WASMTIME_ADDRMAP: &str = ".wasmtime.addrmap";
 
+/// A custom Wasmtime-specific section of our compilation image which stores
+/// the call sites of code inlined into other functions.
+///
+/// This section has the same encoding as `ELF_WASMTIME_ADDRMAP`, but maps
+/// ranges of the text section containing inlined code to the offset of the
+/// call which was inlined in the original wasm binary. Ranges of code which
+/// wasn't inlined map to no offset. It's read with `lookup_file_pos` as well.
+///
+/// This section is only present if at least one call was inlined.
+pub const ELF_WASMTIME_INLINED_CALLS: &str = ".wasmtime.inlined_calls";
+
 /// A custom binary-encoded section of wasmtime compilation artifacts which
 /// encodes the ability to map an offset in the text section to the trap code
 /// that it corresponds to.
@@ -137,6 +148,16 @@ pub const ELF_NAME_DATA: &'static str = ".name.wasm";
 /// metadata.
 pub const ELF_WASMTIME_DWARF: &str = ".wasmtime.dwarf";
 
+/// This is the name of the section in the final ELF image which contains the
+/// original wasm binary, either a core module or a component, that the image
+/// was compiled from.
+///
+/// This section is only present in images compiled for `Strategy::Tiered`,
+/// where it's used to recompile hot functions with Cranelift at runtime. This
+/// means that images loaded from disk can be tiered up just like those
+/// compiled in-process.
+pub const ELF_WASMTIME_TIER_UP: &str = ".wasmtime.tier_up";
+
 macro_rules! libcalls {
     ($($rust:ident = $sym:tt)*) => (
         #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -0,0 +1,149 @@
+use alloc::collections::BTreeMap;
+use serde_derive::{Deserialize, Serialize};
+
+/// Execution profile of a WebAssembly module which can be used to guide its
+/// compilation.
+///
+/// A profile is a collection of samples, each of which is the stack of wasm
+/// frames executing at some point in time. Each frame is recorded as the
+/// offset of the instruction it's executing within the original wasm binary,
+/// so a profile is only meaningful for the exact binary it was collected from.
+/// Functions are identified by the offset of their body as well, which keeps
+/// them apart across the core modules of a component.
+///
+/// A hash of the binary is recorded along with the samples, see
+/// [`ProfileData::matches`], to catch profiles being used with other binaries.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProfileData {
+    /// The hash of the wasm binary the profile was collected from, if known,
+    /// as computed by `ProfileData::hash_wasm`.
+    #[serde(default)]
+    wasm_hash: Option<u64>,
+    /// Number of samples with a frame executing the instruction at each
+    /// offset within the wasm binary.
+    offsets: BTreeMap<u32, u64>,
+    /// Number of samples with a frame in a function called from a frame in
+    /// another, keyed by the offsets of the caller's and then the callee's
+    /// function bodies.
+    calls: BTreeMap<u32, BTreeMap<u32, u64>>,
+}
+
+impl ProfileData {
+    /// Creates an empty profile for the wasm binary, either a core module or
+    /// a component, whose hash is `wasm_hash`.
+    pub fn new(wasm_hash: u64) -> ProfileData {
+        ProfileData {
+            wasm_hash: Some(wasm_hash),
+            ..ProfileData::default()
+        }
+    }
+
+    /// Hashes the `wasm` binary for [`ProfileData::new`].
+    ///
+    /// This is a 64-bit FNV-1a hash, which is stable across versions and
+    /// platforms but isn't meant to resist deliberate collisions.
+    pub fn hash_wasm(wasm: &[u8]) -> u64 {
+        wasm.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    /// Returns the hash of the wasm binary this profile was collected from,
+    /// if it was recorded.
+    pub fn wasm_hash(&self) -> Option<u64> {
+        self.wasm_hash
+    }
+
+    /// Returns whether this profile may have been collected from `wasm`,
+    /// which is the case unless it has a hash of a different binary.
+    pub fn matches(&self, wasm: &[u8]) -> bool {
+        self.wasm_hash
+            .map_or(true, |hash| hash == ProfileData::hash_wasm(wasm))
+    }
+
+    /// Records a sample with the given stack of `(function, offset)` frames,
+    /// which are listed innermost first. Each function is identified by the
+    /// offset of its body within the wasm binary.
+    ///
+    /// The offset a frame is executing may be unknown, in which case the
+    /// frame only contributes to the calls it was making.
+    pub fn add_sample(&mut self, frames: &[(u32, Option<u32>)]) {
+        for offset in frames.iter().filter_map(|(_, offset)| *offset) {
+            *self.offsets.entry(offset).or_default() += 1;
+        }
+        for pair in frames.windows(2) {
+            let (callee, caller) = (pair[0].0, pair[1].0);
+            *self
+                .calls
+                .entry(caller)
+                .or_default()
+                .entry(callee)
+                .or_default() += 1;
+        }
+    }
+
+    /// Returns whether no samples have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the number of samples executing the instruction at `offset`.
+    pub fn offset_samples(&self, offset: u32) -> u64 {
+        self.offsets.get(&offset).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of samples executing any instruction within
+    /// `start..end`, such as the body of a function.
+    pub fn range_samples(&self, start: u32, end: u32) -> u64 {
+        if start >= end {
+            return 0;
+        }
+        self.offsets.range(start..end).map(|(_, n)| n).sum()
+    }
+
+    /// Returns the number of samples where `caller` was executing a call to
+    /// `callee`, both of which are the offsets of function bodies.
+    pub fn call_samples(&self, caller: u32, callee: u32) -> u64 {
+        self.calls
+            .get(&caller)
+            .and_then(|calls| calls.get(&callee))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples() {
+        let mut data = ProfileData::default();
+        assert!(data.is_empty());
+        data.add_sample(&[(28, Some(30)), (10, Some(12))]);
+        data.add_sample(&[(28, Some(31)), (10, Some(12))]);
+        data.add_sample(&[(10, Some(15))]);
+        data.add_sample(&[(28, Some(32)), (10, None)]);
+        assert!(!data.is_empty());
+
+        assert_eq!(data.offset_samples(12), 2);
+        assert_eq!(data.offset_samples(13), 0);
+        assert_eq!(data.range_samples(10, 20), 3);
+        assert_eq!(data.range_samples(30, 31), 1);
+        assert_eq!(data.range_samples(30, 40), 3);
+        assert_eq!(data.range_samples(31, 30), 0);
+        assert_eq!(data.call_samples(10, 28), 3);
+        assert_eq!(data.call_samples(28, 10), 0);
+    }
+
+    #[test]
+    fn wasm_hash() {
+        let wasm = b"\0asm\x01\0\0\0";
+        assert!(ProfileData::default().matches(wasm));
+        let data = ProfileData::new(ProfileData::hash_wasm(wasm));
+        assert_eq!(data.wasm_hash(), Some(ProfileData::hash_wasm(wasm)));
+        assert!(data.matches(wasm));
+        assert!(!data.matches(b"\0asm\x0d\0\x01\0"));
+        assert_ne!(ProfileData::hash_wasm(b"a"), ProfileData::hash_wasm(b"b"));
+    }
+}
@@ -92,6 +92,14 @@ pub enum Trap {
     /// Async-lifted export failed to produce a result by calling `task.return`
     /// before returning `STATUS_DONE` and/or after all host tasks completed.
     NoAsyncResult,
+
+    /// A continuation suspended with a tag for which no enclosing `resume`
+    /// installed a handler.
+    UnhandledTag,
+
+    /// A continuation that had already been resumed, or otherwise consumed,
+    /// was used again.
+    ContinuationAlreadyConsumed,
     // if adding a variant here be sure to update the `check!` macro below
 }
 
@@ -129,6 +137,8 @@ impl Trap {
             CastFailure
             CannotEnterComponent
             NoAsyncResult
+            UnhandledTag
+            ContinuationAlreadyConsumed
         }
 
         None
@@ -160,6 +170,8 @@ impl fmt::Display for Trap {
             CastFailure => "cast failure",
             CannotEnterComponent => "cannot enter component instance",
             NoAsyncResult => "async-lifted export failed to produce a result",
+            UnhandledTag => "unhandled tag",
+            ContinuationAlreadyConsumed => "continuation already consumed",
         };
         write!(f, "wasm trap: {desc}")
     }
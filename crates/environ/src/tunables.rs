@@ -120,6 +120,18 @@ define_tunables! {
 
         /// Whether CoW images might be used to initialize linear memories.
         pub memory_init_cow: bool,
+
+        /// Whether or not small functions are inlined into their callers
+        /// within a module.
+        pub inlining: bool,
+
+        /// Functions whose body is at most this many bytes of wasm are always
+        /// inlined into their callers when `inlining` is enabled.
+        pub inlining_small_callee_size: u32,
+
+        /// Larger functions are only inlined while the caller plus everything
+        /// inlined into it so far stays within this many bytes of wasm.
+        pub inlining_sum_size_threshold: u32,
     }
 
     pub struct ConfigTunables {
@@ -182,6 +194,9 @@ impl Tunables {
             winch_callable: false,
             signals_based_traps: true,
             memory_init_cow: true,
+            inlining: false,
+            inlining_small_callee_size: 50,
+            inlining_sum_size_threshold: 2000,
         }
     }
 
@@ -436,6 +436,15 @@ pub enum WasmHeapType {
     Struct,
     ConcreteStruct(EngineOrModuleTypeIndex),
     None,
+
+    // Exception types.
+    Exn,
+    NoExn,
+
+    // Continuation types.
+    Cont,
+    ConcreteCont(EngineOrModuleTypeIndex),
+    NoCont,
 }
 
 impl From<WasmHeapTopType> for WasmHeapType {
@@ -445,6 +454,8 @@ impl From<WasmHeapTopType> for WasmHeapType {
             WasmHeapTopType::Extern => Self::Extern,
             WasmHeapTopType::Any => Self::Any,
             WasmHeapTopType::Func => Self::Func,
+            WasmHeapTopType::Exn => Self::Exn,
+            WasmHeapTopType::Cont => Self::Cont,
         }
     }
 }
@@ -456,6 +467,8 @@ impl From<WasmHeapBottomType> for WasmHeapType {
             WasmHeapBottomType::NoExtern => Self::NoExtern,
             WasmHeapBottomType::None => Self::None,
             WasmHeapBottomType::NoFunc => Self::NoFunc,
+            WasmHeapBottomType::NoExn => Self::NoExn,
+            WasmHeapBottomType::NoCont => Self::NoCont,
         }
     }
 }
@@ -476,6 +489,11 @@ impl fmt::Display for WasmHeapType {
             Self::Struct => write!(f, "struct"),
             Self::ConcreteStruct(i) => write!(f, "struct {i}"),
             Self::None => write!(f, "none"),
+            Self::Exn => write!(f, "exn"),
+            Self::NoExn => write!(f, "noexn"),
+            Self::Cont => write!(f, "cont"),
+            Self::ConcreteCont(i) => write!(f, "cont {i}"),
+            Self::NoCont => write!(f, "nocont"),
         }
     }
 }
@@ -489,6 +507,7 @@ impl TypeTrace for WasmHeapType {
             Self::ConcreteArray(i) => func(i),
             Self::ConcreteFunc(i) => func(i),
             Self::ConcreteStruct(i) => func(i),
+            Self::ConcreteCont(i) => func(i),
             _ => Ok(()),
         }
     }
@@ -501,6 +520,7 @@ impl TypeTrace for WasmHeapType {
             Self::ConcreteArray(i) => func(i),
             Self::ConcreteFunc(i) => func(i),
             Self::ConcreteStruct(i) => func(i),
+            Self::ConcreteCont(i) => func(i),
             _ => Ok(()),
         }
     }
@@ -511,9 +531,13 @@ impl WasmHeapType {
     #[inline]
     pub fn is_vmgcref_type(&self) -> bool {
         match self.top() {
-            // All `t <: (ref null any)` and `t <: (ref null extern)` are
-            // represented as `VMGcRef`s.
-            WasmHeapTopType::Any | WasmHeapTopType::Extern => true,
+            // All `t <: (ref null any)`, `t <: (ref null extern)`,
+            // `t <: (ref null exn)`, and `t <: (ref null cont)` are represented
+            // as `VMGcRef`s.
+            WasmHeapTopType::Any
+            | WasmHeapTopType::Extern
+            | WasmHeapTopType::Exn
+            | WasmHeapTopType::Cont => true,
 
             // All `t <: (ref null func)` are not.
             WasmHeapTopType::Func => false,
@@ -554,6 +578,12 @@ impl WasmHeapType {
             | WasmHeapType::Struct
             | WasmHeapType::ConcreteStruct(_)
             | WasmHeapType::None => WasmHeapTopType::Any,
+
+            WasmHeapType::Exn | WasmHeapType::NoExn => WasmHeapTopType::Exn,
+
+            WasmHeapType::Cont | WasmHeapType::ConcreteCont(_) | WasmHeapType::NoCont => {
+                WasmHeapTopType::Cont
+            }
         }
     }
 
@@ -581,6 +611,12 @@ impl WasmHeapType {
             | WasmHeapType::Struct
             | WasmHeapType::ConcreteStruct(_)
             | WasmHeapType::None => WasmHeapBottomType::None,
+
+            WasmHeapType::Exn | WasmHeapType::NoExn => WasmHeapBottomType::NoExn,
+
+            WasmHeapType::Cont | WasmHeapType::ConcreteCont(_) | WasmHeapType::NoCont => {
+                WasmHeapBottomType::NoCont
+            }
         }
     }
 }
@@ -594,6 +630,10 @@ pub enum WasmHeapTopType {
     Any,
     /// The common supertype of all function references.
     Func,
+    /// The common supertype of all exception references.
+    Exn,
+    /// The common supertype of all continuation references.
+    Cont,
 }
 
 /// A bottom heap type.
@@ -605,6 +645,10 @@ pub enum WasmHeapBottomType {
     None,
     /// The common subtype of all function references.
     NoFunc,
+    /// The common subtype of all exception references.
+    NoExn,
+    /// The common subtype of all continuation references.
+    NoCont,
 }
 
 /// WebAssembly function type -- equivalent of `wasmparser`'s FuncType.
@@ -919,13 +963,43 @@ impl fmt::Display for WasmCompositeType {
     }
 }
 
-/// A function, array, or struct type.
+/// A concrete continuation type.
+///
+/// This is the type of continuations whose underlying computation has the
+/// given function type.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WasmContType(pub EngineOrModuleTypeIndex);
+
+impl fmt::Display for WasmContType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(cont {})", self.0)
+    }
+}
+
+impl TypeTrace for WasmContType {
+    fn trace<F, E>(&self, func: &mut F) -> Result<(), E>
+    where
+        F: FnMut(EngineOrModuleTypeIndex) -> Result<(), E>,
+    {
+        func(self.0)
+    }
+
+    fn trace_mut<F, E>(&mut self, func: &mut F) -> Result<(), E>
+    where
+        F: FnMut(&mut EngineOrModuleTypeIndex) -> Result<(), E>,
+    {
+        func(&mut self.0)
+    }
+}
+
+/// A function, array, struct, or continuation type.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[allow(missing_docs, reason = "self-describing variants")]
 pub enum WasmCompositeInnerType {
     Array(WasmArrayType),
     Func(WasmFuncType),
     Struct(WasmStructType),
+    Cont(WasmContType),
 }
 
 impl fmt::Display for WasmCompositeInnerType {
@@ -934,6 +1008,7 @@ impl fmt::Display for WasmCompositeInnerType {
             Self::Array(ty) => fmt::Display::fmt(ty, f),
             Self::Func(ty) => fmt::Display::fmt(ty, f),
             Self::Struct(ty) => fmt::Display::fmt(ty, f),
+            Self::Cont(ty) => fmt::Display::fmt(ty, f),
         }
     }
 }
@@ -993,6 +1068,24 @@ impl WasmCompositeInnerType {
     pub fn unwrap_struct(&self) -> &WasmStructType {
         self.as_struct().unwrap()
     }
+
+    #[inline]
+    pub fn is_cont(&self) -> bool {
+        matches!(self, Self::Cont(_))
+    }
+
+    #[inline]
+    pub fn as_cont(&self) -> Option<&WasmContType> {
+        match self {
+            Self::Cont(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn unwrap_cont(&self) -> &WasmContType {
+        self.as_cont().unwrap()
+    }
 }
 
 impl TypeTrace for WasmCompositeType {
@@ -1004,6 +1097,7 @@ impl TypeTrace for WasmCompositeType {
             WasmCompositeInnerType::Array(a) => a.trace(func),
             WasmCompositeInnerType::Func(f) => f.trace(func),
             WasmCompositeInnerType::Struct(a) => a.trace(func),
+            WasmCompositeInnerType::Cont(c) => c.trace(func),
         }
     }
 
@@ -1015,6 +1109,7 @@ impl TypeTrace for WasmCompositeType {
             WasmCompositeInnerType::Array(a) => a.trace_mut(func),
             WasmCompositeInnerType::Func(f) => f.trace_mut(func),
             WasmCompositeInnerType::Struct(a) => a.trace_mut(func),
+            WasmCompositeInnerType::Cont(c) => c.trace_mut(func),
         }
     }
 }
@@ -1114,6 +1209,26 @@ impl WasmSubType {
         assert!(!self.composite_type.shared);
         self.composite_type.inner.unwrap_struct()
     }
+
+    #[inline]
+    pub fn is_cont(&self) -> bool {
+        self.composite_type.inner.is_cont() && !self.composite_type.shared
+    }
+
+    #[inline]
+    pub fn as_cont(&self) -> Option<&WasmContType> {
+        if self.composite_type.shared {
+            None
+        } else {
+            self.composite_type.inner.as_cont()
+        }
+    }
+
+    #[inline]
+    pub fn unwrap_cont(&self) -> &WasmContType {
+        assert!(!self.composite_type.shared);
+        self.composite_type.inner.unwrap_cont()
+    }
 }
 
 impl TypeTrace for WasmSubType {
@@ -1306,11 +1421,16 @@ entity_impl!(DataIndex);
 pub struct ElemIndex(u32);
 entity_impl!(ElemIndex);
 
-/// Index type of an event inside the WebAssembly module.
+/// Index type of an exception tag inside the WebAssembly module.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub struct TagIndex(u32);
 entity_impl!(TagIndex);
 
+/// Index type of a defined exception tag inside the WebAssembly module.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub struct DefinedTagIndex(u32);
+entity_impl!(DefinedTagIndex);
+
 /// Index into the global list of modules found within an entire component.
 ///
 /// Module translations are saved on the side to get fully compiled after
@@ -1330,6 +1450,8 @@ pub enum EntityIndex {
     Memory(MemoryIndex),
     /// Global index.
     Global(GlobalIndex),
+    /// Tag index.
+    Tag(TagIndex),
 }
 
 impl From<FuncIndex> for EntityIndex {
@@ -1356,6 +1478,12 @@ impl From<GlobalIndex> for EntityIndex {
     }
 }
 
+impl From<TagIndex> for EntityIndex {
+    fn from(idx: TagIndex) -> EntityIndex {
+        EntityIndex::Tag(idx)
+    }
+}
+
 /// A type of an item in a wasm module where an item is typically something that
 /// can be exported.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1364,7 +1492,7 @@ pub enum EntityType {
     Global(Global),
     /// A linear memory with the specified limits
     Memory(Memory),
-    /// An event definition.
+    /// An exception tag definition.
     Tag(Tag),
     /// A table with the specified element type and limits
     Table(Table),
@@ -1381,8 +1509,9 @@ impl TypeTrace for EntityType {
         match self {
             Self::Global(g) => g.trace(func),
             Self::Table(t) => t.trace(func),
+            Self::Tag(t) => t.trace(func),
             Self::Function(idx) => func(*idx),
-            Self::Memory(_) | Self::Tag(_) => Ok(()),
+            Self::Memory(_) => Ok(()),
         }
     }
 
@@ -1393,8 +1522,9 @@ impl TypeTrace for EntityType {
         match self {
             Self::Global(g) => g.trace_mut(func),
             Self::Table(t) => t.trace_mut(func),
+            Self::Tag(t) => t.trace_mut(func),
             Self::Function(idx) => func(idx),
-            Self::Memory(_) | Self::Tag(_) => Ok(()),
+            Self::Memory(_) => Ok(()),
         }
     }
 }
@@ -1924,20 +2054,26 @@ impl From<wasmparser::MemoryType> for Memory {
     }
 }
 
-/// WebAssembly event.
+/// WebAssembly exception tag.
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Tag {
-    /// The event signature type.
-    pub ty: TypeIndex,
+    /// The function type describing this tag's payload.
+    pub signature: EngineOrModuleTypeIndex,
 }
 
-impl From<wasmparser::TagType> for Tag {
-    fn from(ty: wasmparser::TagType) -> Tag {
-        match ty.kind {
-            wasmparser::TagKind::Exception => Tag {
-                ty: TypeIndex::from_u32(ty.func_type_idx),
-            },
-        }
+impl TypeTrace for Tag {
+    fn trace<F, E>(&self, func: &mut F) -> Result<(), E>
+    where
+        F: FnMut(EngineOrModuleTypeIndex) -> Result<(), E>,
+    {
+        func(self.signature)
+    }
+
+    fn trace_mut<F, E>(&mut self, func: &mut F) -> Result<(), E>
+    where
+        F: FnMut(&mut EngineOrModuleTypeIndex) -> Result<(), E>,
+    {
+        func(&mut self.signature)
     }
 }
 
@@ -1988,8 +2124,8 @@ pub trait TypeConvert {
             wasmparser::CompositeInnerType::Struct(s) => {
                 WasmCompositeInnerType::Struct(self.convert_struct_type(s))
             }
-            wasmparser::CompositeInnerType::Cont(_) => {
-                unimplemented!("continuation types")
+            wasmparser::CompositeInnerType::Cont(c) => {
+                WasmCompositeInnerType::Cont(self.convert_cont_type(c))
             }
         };
         WasmCompositeType {
@@ -2012,6 +2148,11 @@ pub trait TypeConvert {
         WasmArrayType(self.convert_field_type(&ty.0))
     }
 
+    fn convert_cont_type(&self, ty: &wasmparser::ContType) -> WasmContType {
+        let index = ty.0.unpack();
+        WasmContType(self.lookup_type_index(index))
+    }
+
     fn convert_field_type(&self, ty: &wasmparser::FieldType) -> WasmFieldType {
         WasmFieldType {
             element_type: self.convert_storage_type(&ty.element_type),
@@ -2077,13 +2218,10 @@ pub trait TypeConvert {
                 wasmparser::AbstractHeapType::Array => WasmHeapType::Array,
                 wasmparser::AbstractHeapType::Struct => WasmHeapType::Struct,
                 wasmparser::AbstractHeapType::None => WasmHeapType::None,
-
-                wasmparser::AbstractHeapType::Exn
-                | wasmparser::AbstractHeapType::NoExn
-                | wasmparser::AbstractHeapType::Cont
-                | wasmparser::AbstractHeapType::NoCont => {
-                    unimplemented!("unsupported heap type {ty:?}");
-                }
+                wasmparser::AbstractHeapType::Exn => WasmHeapType::Exn,
+                wasmparser::AbstractHeapType::NoExn => WasmHeapType::NoExn,
+                wasmparser::AbstractHeapType::Cont => WasmHeapType::Cont,
+                wasmparser::AbstractHeapType::NoCont => WasmHeapType::NoCont,
             },
             _ => unimplemented!("unsupported heap type {ty:?}"),
         }
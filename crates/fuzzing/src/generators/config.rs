@@ -141,6 +141,8 @@ impl Config {
             component_model_more_flags,
             component_model_async,
             simd,
+            exceptions,
+            stack_switching,
 
             hogs_memory: _,
             nan_canonicalization: _,
@@ -149,10 +151,13 @@ impl Config {
 
         // Enable/disable some proposals that aren't configurable in wasm-smith
         // but are configurable in Wasmtime.
-        self.module_config.function_references_enabled =
-            function_references.or(gc).unwrap_or(false);
+        self.module_config.function_references_enabled = function_references
+            .or(gc)
+            .or(stack_switching)
+            .unwrap_or(false);
         self.module_config.component_model_more_flags = component_model_more_flags.unwrap_or(false);
         self.module_config.component_model_async = component_model_async.unwrap_or(false);
+        self.module_config.stack_switching_enabled = stack_switching.unwrap_or(false);
 
         // Enable/disable proposals that wasm-smith has knobs for which will be
         // read when creating `wasmtime::Config`.
@@ -167,7 +172,10 @@ impl Config {
         config.custom_page_sizes_enabled = custom_page_sizes.unwrap_or(false);
         config.threads_enabled = threads.unwrap_or(false);
         config.gc_enabled = gc.unwrap_or(false);
+        config.exceptions_enabled =
+            self.module_config.stack_switching_enabled || exceptions.unwrap_or(false);
         config.reference_types_enabled = config.gc_enabled
+            || config.exceptions_enabled
             || self.module_config.function_references_enabled
             || reference_types.unwrap_or(false);
         config.extended_const_enabled = extended_const.unwrap_or(false);
@@ -264,6 +272,8 @@ impl Config {
             .wasm_threads(self.module_config.config.threads_enabled)
             .wasm_function_references(self.module_config.function_references_enabled)
             .wasm_gc(self.module_config.config.gc_enabled)
+            .wasm_exceptions(self.module_config.config.exceptions_enabled)
+            .wasm_stack_switching(self.module_config.stack_switching_enabled)
             .wasm_custom_page_sizes(self.module_config.config.custom_page_sizes_enabled)
             .wasm_wide_arithmetic(self.module_config.config.wide_arithmetic_enabled)
             .wasm_extended_const(self.module_config.config.extended_const_enabled)
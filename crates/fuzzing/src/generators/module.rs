@@ -15,6 +15,7 @@ pub struct ModuleConfig {
     // in our `*.wast` testing so keep knobs here so they can be read during
     // config-to-`wasmtime::Config` translation.
     pub function_references_enabled: bool,
+    pub stack_switching_enabled: bool,
     pub component_model_more_flags: bool,
     pub component_model_async: bool,
 }
@@ -65,6 +66,7 @@ impl<'a> Arbitrary<'a> for ModuleConfig {
             component_model_more_flags: false,
             component_model_async: false,
             function_references_enabled: config.gc_enabled,
+            stack_switching_enabled: false,
             config,
         })
     }
@@ -169,6 +169,8 @@ pub fn apply_test_config(config: &mut Config, test_config: &wasmtime_wast_util::
         component_model_async,
         nan_canonicalization,
         simd,
+        exceptions,
+        stack_switching,
 
         hogs_memory: _,
         gc_types: _,
@@ -188,14 +190,16 @@ pub fn apply_test_config(config: &mut Config, test_config: &wasmtime_wast_util::
     let component_model_async = component_model_async.unwrap_or(false);
     let nan_canonicalization = nan_canonicalization.unwrap_or(false);
     let relaxed_simd = relaxed_simd.unwrap_or(false);
+    let stack_switching = stack_switching.unwrap_or(false);
+    let exceptions = stack_switching || exceptions.unwrap_or(false);
 
     // Some proposals in wasm depend on previous proposals. For example the gc
     // proposal depends on function-references which depends on reference-types.
     // To avoid needing to enable all of them at once implicitly enable
     // downstream proposals once the end proposal is enabled (e.g. when enabling
     // gc that also enables function-references and reference-types).
-    let function_references = gc || function_references.unwrap_or(false);
-    let reference_types = function_references || reference_types.unwrap_or(false);
+    let function_references = gc || stack_switching || function_references.unwrap_or(false);
+    let reference_types = function_references || exceptions || reference_types.unwrap_or(false);
     let simd = relaxed_simd || simd.unwrap_or(false);
 
     config
@@ -204,6 +208,8 @@ pub fn apply_test_config(config: &mut Config, test_config: &wasmtime_wast_util::
         .wasm_memory64(memory64)
         .wasm_function_references(function_references)
         .wasm_gc(gc)
+        .wasm_exceptions(exceptions)
+        .wasm_stack_switching(stack_switching)
         .wasm_reference_types(reference_types)
         .wasm_relaxed_simd(relaxed_simd)
         .wasm_simd(simd)
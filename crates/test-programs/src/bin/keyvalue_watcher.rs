@@ -0,0 +1,26 @@
+use test_programs::keyvalue_watcher::exports::wasi::keyvalue::watcher::Guest;
+use test_programs::wasi::keyvalue::store::Bucket;
+
+struct T;
+
+test_programs::keyvalue_watcher::export!(T);
+
+impl Guest for T {
+    // Records each change in a sibling key, ignoring changes to those keys
+    // so that the records don't trigger further records.
+    fn on_set(bucket: Bucket, key: String, value: Vec<u8>) {
+        if key.contains('.') {
+            return;
+        }
+        bucket.set(&format!("{key}.seen"), &value).unwrap();
+    }
+
+    fn on_delete(bucket: Bucket, key: String) {
+        if key.contains('.') {
+            return;
+        }
+        bucket.set(&format!("{key}.deleted"), &[]).unwrap();
+    }
+}
+
+fn main() {}
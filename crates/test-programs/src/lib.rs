@@ -45,3 +45,17 @@ pub mod proxy {
         },
     });
 }
+
+pub mod keyvalue_watcher {
+    wit_bindgen::generate!({
+        path: "../wasi-keyvalue/wit",
+        world: "wasi:keyvalue/watch-service",
+        default_bindings_module: "test_programs::keyvalue_watcher",
+        pub_export_macro: true,
+        with: {
+            "wasi:keyvalue/store@0.2.0-draft": crate::wasi::keyvalue::store,
+            "wasi:keyvalue/atomics@0.2.0-draft": crate::wasi::keyvalue::atomics,
+            "wasi:keyvalue/batch@0.2.0-draft": crate::wasi::keyvalue::batch,
+        },
+    });
+}
@@ -222,6 +222,7 @@ mod types_impl;
 
 pub mod body;
 pub mod io;
+pub mod pool;
 pub mod types;
 
 pub mod bindings;
@@ -230,6 +231,8 @@ pub use crate::error::{
     http_request_error, hyper_request_error, hyper_response_error, HttpError, HttpResult,
 };
 #[doc(inline)]
+pub use crate::pool::ConnectionPool;
+#[doc(inline)]
 pub use crate::types::{
     WasiHttpCtx, WasiHttpImpl, WasiHttpView, DEFAULT_OUTGOING_BODY_BUFFER_CHUNKS,
     DEFAULT_OUTGOING_BODY_CHUNK_SIZE,
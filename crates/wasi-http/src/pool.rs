@@ -0,0 +1,638 @@
+//! A pool of outgoing connections which may be shared between many
+//! [`WasiHttpCtx`](crate::WasiHttpCtx)s.
+
+use crate::bindings::http::types::ErrorCode;
+use crate::body::HyperOutgoingBody;
+use crate::hyper_request_error;
+use crate::io::{TokioExecutor, TokioIo};
+use crate::types::{self, HostFutureIncomingResponse, IncomingResponse, OutgoingRequestConfig};
+use http_body_util::BodyExt;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::time::timeout;
+
+type Http1Sender = hyper::client::conn::http1::SendRequest<HyperOutgoingBody>;
+type Http2Sender = hyper::client::conn::http2::SendRequest<HyperOutgoingBody>;
+
+/// The number of hosts a pool tracks before it first looks for unused ones to
+/// evict.
+const MIN_HOSTS_BEFORE_PRUNE: usize = 64;
+
+/// The default value for [`ConnectionPoolBuilder::idle_timeout`].
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// A pool of keep-alive connections used to send outgoing requests.
+///
+/// Connections are keyed by scheme and authority. HTTP/1.1 connections are
+/// returned to the pool once the body of their response has been fully read,
+/// and HTTP/2 connections, negotiated through ALPN for `https` requests, are
+/// shared between all concurrent requests to the same authority. Requests
+/// made while the first connection to an `https` authority is being opened
+/// wait for it, so that they can share it if it's HTTP/2.
+///
+/// Authorities are forgotten once they have no open connections left.
+///
+/// A `ConnectionPool` is cheap to clone and all clones share the same
+/// connections. Use [`WasiHttpCtx::with_connection_pool`] to send a store's
+/// outgoing requests through a pool.
+///
+/// [`WasiHttpCtx::with_connection_pool`]: crate::WasiHttpCtx::with_connection_pool
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use wasmtime_wasi_http::{ConnectionPool, WasiHttpCtx};
+///
+/// let pool = ConnectionPool::builder()
+///     .idle_timeout(Duration::from_secs(30))
+///     .max_connections_per_host(16)
+///     .build();
+///
+/// // Each store gets its own context but they all share connections.
+/// let ctx1 = WasiHttpCtx::with_connection_pool(pool.clone());
+/// let ctx2 = WasiHttpCtx::with_connection_pool(pool.clone());
+/// ```
+#[derive(Clone)]
+pub struct ConnectionPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    idle_timeout: Duration,
+    max_connections_per_host: usize,
+    /// Whether HTTP/2 is offered when connecting to `https` authorities.
+    http2: bool,
+    #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+    tls: Arc<rustls::ClientConfig>,
+    hosts: Mutex<Hosts>,
+}
+
+struct Hosts {
+    map: HashMap<PoolKey, Arc<Host>>,
+    /// The size `map` may grow to before unused hosts are evicted from it.
+    prune_at: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct PoolKey {
+    use_tls: bool,
+    authority: String,
+}
+
+/// The connections to a single scheme and authority.
+struct Host {
+    /// Limits the number of open connections, with a permit held by the task
+    /// driving each connection.
+    limit: Arc<Semaphore>,
+    /// Signaled whenever an HTTP/1.1 connection is returned to `idle` or a
+    /// connection attempt which other requests wait on finishes.
+    returned: Notify,
+    state: Mutex<HostState>,
+}
+
+#[derive(Default)]
+struct HostState {
+    next_id: u64,
+    idle: Vec<IdleConnection>,
+    http2: Option<Http2Connection>,
+    /// Whether a connection which may turn out to be HTTP/2 is being opened,
+    /// in which case other requests wait for it instead of opening their own.
+    connecting: bool,
+    /// Whether the server only negotiated HTTP/1.1, in which case requests
+    /// open connections in parallel without waiting for each other.
+    http1_only: bool,
+}
+
+struct IdleConnection {
+    id: u64,
+    sender: Http1Sender,
+}
+
+struct Http2Connection {
+    id: u64,
+    sender: Http2Sender,
+    last_used: Instant,
+}
+
+enum Sender {
+    Http1(Http1Sender),
+    Http2(Http2Sender),
+}
+
+impl ConnectionPool {
+    /// Creates a new pool with the default configuration.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Returns a builder to configure a new pool.
+    pub fn builder() -> ConnectionPoolBuilder {
+        ConnectionPoolBuilder::new()
+    }
+
+    /// Returns the number of distinct schemes and authorities this pool
+    /// currently keeps connections for.
+    pub fn hosts(&self) -> usize {
+        self.inner.hosts.lock().unwrap().map.len()
+    }
+
+    /// Sends `request` over a pooled connection, opening a new connection if
+    /// none is available.
+    ///
+    /// This is used by the default implementation of
+    /// [`WasiHttpView::send_request`](crate::WasiHttpView::send_request) when
+    /// the context has a pool.
+    pub fn send_request(
+        &self,
+        request: hyper::Request<HyperOutgoingBody>,
+        config: OutgoingRequestConfig,
+    ) -> HostFutureIncomingResponse {
+        let pool = self.clone();
+        let handle = wasmtime_wasi::runtime::spawn(async move {
+            Ok(pool.send_request_handler(request, config).await)
+        });
+        HostFutureIncomingResponse::pending(handle)
+    }
+
+    /// The underlying implementation of [`ConnectionPool::send_request`],
+    /// which should likely be spawned in a task.
+    pub async fn send_request_handler(
+        &self,
+        mut request: hyper::Request<HyperOutgoingBody>,
+        OutgoingRequestConfig {
+            use_tls,
+            connect_timeout,
+            first_byte_timeout,
+            between_bytes_timeout,
+        }: OutgoingRequestConfig,
+    ) -> Result<IncomingResponse, ErrorCode> {
+        let key = PoolKey {
+            use_tls,
+            authority: types::request_authority(&request, use_tls)?,
+        };
+        let host = self.host(&key);
+        let sender = timeout(connect_timeout, self.checkout(&host, &key))
+            .await
+            .map_err(|_| ErrorCode::ConnectionTimeout)
+            .and_then(|result| result);
+        let sender = match sender {
+            Ok(sender) => sender,
+            Err(e) => {
+                // Don't keep track of authorities which couldn't be connected
+                // to.
+                drop(host);
+                self.evict_if_unused(&key);
+                return Err(e);
+            }
+        };
+
+        let resp = match sender {
+            Sender::Http1(mut sender) => {
+                types::strip_uri_to_path(&mut request);
+                let resp = timeout(first_byte_timeout, sender.send_request(request)).await;
+
+                // Once the response body has been read the connection is
+                // ready for another request, so return it to the pool then.
+                // If the body is dropped early, or the connection otherwise
+                // fails, hyper closes the connection instead.
+                let pool = self.clone();
+                tokio::task::spawn(async move {
+                    if sender.ready().await.is_ok() {
+                        pool.release(host, key, sender);
+                    } else {
+                        drop(host);
+                        pool.evict_if_unused(&key);
+                    }
+                });
+                resp
+            }
+            // HTTP/2 requires the scheme and authority in the URI as they're
+            // sent as pseudo-headers.
+            Sender::Http2(mut sender) => {
+                timeout(first_byte_timeout, sender.send_request(request)).await
+            }
+        };
+        let resp = resp
+            .map_err(|_| ErrorCode::ConnectionReadTimeout)?
+            .map_err(hyper_request_error)?
+            .map(|body| body.map_err(hyper_request_error).boxed());
+
+        Ok(IncomingResponse {
+            resp,
+            // Pooled connections outlive any one response, so they're driven
+            // by their own detached task rather than a worker.
+            worker: None,
+            between_bytes_timeout,
+        })
+    }
+
+    fn host(&self, key: &PoolKey) -> Arc<Host> {
+        let mut hosts = self.inner.hosts.lock().unwrap();
+        if let Some(host) = hosts.map.get(key) {
+            return host.clone();
+        }
+
+        // Evict hosts without connections once the map has doubled in size
+        // since it was last pruned, so that the cost of pruning is amortized
+        // over the hosts added in between.
+        if hosts.map.len() >= hosts.prune_at {
+            let max = self.inner.max_connections_per_host;
+            hosts.map.retain(|_, host| !host.is_unused(max));
+            hosts.prune_at = (hosts.map.len() * 2).max(MIN_HOSTS_BEFORE_PRUNE);
+        }
+
+        let host = Arc::new(Host {
+            limit: Arc::new(Semaphore::new(self.inner.max_connections_per_host)),
+            returned: Notify::new(),
+            state: Mutex::default(),
+        });
+        hosts.map.insert(key.clone(), host.clone());
+        host
+    }
+
+    /// Removes `key` from the pool if it has no connections left.
+    fn evict_if_unused(&self, key: &PoolKey) {
+        let mut hosts = self.inner.hosts.lock().unwrap();
+        let max = self.inner.max_connections_per_host;
+        if hosts.map.get(key).is_some_and(|host| host.is_unused(max)) {
+            hosts.map.remove(key);
+        }
+    }
+
+    /// Returns an existing connection to `host` if one is available, and
+    /// otherwise opens a new one as soon as the connection limit allows.
+    async fn checkout(&self, host: &Arc<Host>, key: &PoolKey) -> Result<Sender, ErrorCode> {
+        loop {
+            // Create the notification future before looking for an idle
+            // connection so that a connection returned in between isn't
+            // missed.
+            let returned = host.returned.notified();
+            if let Some(sender) = host.take() {
+                return Ok(sender);
+            }
+
+            // Until it's known whether the server supports HTTP/2 only one
+            // connection is opened at a time, which all requests share if it
+            // is HTTP/2.
+            let _connecting = if key.use_tls && self.inner.http2 {
+                match host.start_connecting() {
+                    Some(guard) => Some(guard),
+                    None => {
+                        returned.await;
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+
+            tokio::select! {
+                permit = host.limit.clone().acquire_owned() => {
+                    let permit = permit.expect("semaphore is never closed");
+                    return self.connect(host, key, permit).await;
+                }
+                () = returned => {}
+            }
+        }
+    }
+
+    async fn connect(
+        &self,
+        host: &Arc<Host>,
+        key: &PoolKey,
+        permit: OwnedSemaphorePermit,
+    ) -> Result<Sender, ErrorCode> {
+        // The connection timeout for the whole checkout is enforced by the
+        // caller, so no timeout is needed here.
+        let tcp_stream = types::connect_tcp(&key.authority, Duration::MAX).await?;
+        if !key.use_tls {
+            return self
+                .handshake(host, key, TokioIo::new(tcp_stream), false, permit)
+                .await;
+        }
+
+        #[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
+        {
+            return Err(ErrorCode::InternalError(Some(
+                "unsupported architecture for SSL".to_string(),
+            )));
+        }
+
+        #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+        {
+            let stream =
+                types::connect_tls(tcp_stream, &key.authority, self.inner.tls.clone()).await?;
+            let http2 = stream.get_ref().1.alpn_protocol() == Some(b"h2");
+            if !http2 {
+                host.state.lock().unwrap().http1_only = true;
+            }
+            self.handshake(host, key, TokioIo::new(stream), http2, permit)
+                .await
+        }
+    }
+
+    async fn handshake<S>(
+        &self,
+        host: &Arc<Host>,
+        key: &PoolKey,
+        stream: S,
+        http2: bool,
+        permit: OwnedSemaphorePermit,
+    ) -> Result<Sender, ErrorCode>
+    where
+        S: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+    {
+        if !http2 {
+            let (sender, conn) = hyper::client::conn::http1::handshake(stream)
+                .await
+                .map_err(hyper_request_error)?;
+            self.spawn_connection(key, conn, permit);
+            return Ok(Sender::Http1(sender));
+        }
+
+        let (sender, conn) = hyper::client::conn::http2::handshake(TokioExecutor, stream)
+            .await
+            .map_err(hyper_request_error)?;
+        self.spawn_connection(key, conn, permit);
+        let id = {
+            let mut state = host.state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.http2 = Some(Http2Connection {
+                id,
+                sender: sender.clone(),
+                last_used: Instant::now(),
+            });
+            id
+        };
+        self.expire_http2(host, key, id);
+        Ok(Sender::Http2(sender))
+    }
+
+    /// Spawns the task which drives a connection to `key`, holding onto
+    /// `permit` for as long as the connection is open.
+    fn spawn_connection<F>(&self, key: &PoolKey, conn: F, permit: OwnedSemaphorePermit)
+    where
+        F: Future<Output = hyper::Result<()>> + Send + 'static,
+    {
+        let pool = self.clone();
+        let key = key.clone();
+        tokio::task::spawn(async move {
+            if let Err(e) = conn.await {
+                // TODO: shouldn't throw away this error and ideally should
+                // surface somewhere.
+                tracing::warn!("dropping error {e}");
+            }
+            drop(permit);
+            pool.evict_if_unused(&key);
+        });
+    }
+
+    /// Returns an HTTP/1.1 connection which is ready for another request to
+    /// the pool.
+    fn release(&self, host: Arc<Host>, key: PoolKey, sender: Http1Sender) {
+        let id = {
+            let mut state = host.state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.idle.push(IdleConnection { id, sender });
+            id
+        };
+        host.returned.notify_one();
+
+        // Dropping the sender once the connection has been idle for too long
+        // closes it.
+        let pool = self.clone();
+        tokio::task::spawn(async move {
+            tokio::time::sleep(pool.inner.idle_timeout).await;
+            host.state.lock().unwrap().idle.retain(|conn| conn.id != id);
+            drop(host);
+            pool.evict_if_unused(&key);
+        });
+    }
+
+    /// Removes the HTTP/2 connection `id` from the pool once it hasn't been
+    /// used for the idle timeout.
+    ///
+    /// Requests in flight hold their own handle to the connection so it's
+    /// only closed once they've completed.
+    fn expire_http2(&self, host: &Arc<Host>, key: &PoolKey, id: u64) {
+        let pool = self.clone();
+        let host = host.clone();
+        let key = key.clone();
+        let idle_timeout = self.inner.idle_timeout;
+        tokio::task::spawn(async move {
+            let mut deadline = Instant::now() + idle_timeout;
+            loop {
+                tokio::time::sleep_until(deadline.into()).await;
+                let mut state = host.state.lock().unwrap();
+                match &state.http2 {
+                    Some(conn) if conn.id == id => {
+                        deadline = conn.last_used + idle_timeout;
+                        if deadline <= Instant::now() {
+                            state.http2 = None;
+                            break;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+            drop(host);
+            pool.evict_if_unused(&key);
+        });
+    }
+}
+
+impl Default for ConnectionPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ConnectionPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionPool")
+            .field("idle_timeout", &self.inner.idle_timeout)
+            .field(
+                "max_connections_per_host",
+                &self.inner.max_connections_per_host,
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl Host {
+    /// Returns whether this host has no open connections and no requests
+    /// using it, so that it can be removed from the pool.
+    ///
+    /// Every request and background task using a host holds a reference to
+    /// it, so this must be called with the pool's `hosts` locked, and thus
+    /// with no other way to get a new reference.
+    fn is_unused(self: &Arc<Self>, max_connections: usize) -> bool {
+        if Arc::strong_count(self) > 1 || self.limit.available_permits() < max_connections {
+            return false;
+        }
+        let state = self.state.lock().unwrap();
+        state.idle.is_empty() && state.http2.is_none()
+    }
+
+    /// Marks a connection as being opened unless one already is, returning a
+    /// guard which clears the mark once dropped.
+    ///
+    /// Returns `None` if another connection is being opened, in which case
+    /// `returned` is signaled once it has been.
+    fn start_connecting(&self) -> Option<ConnectingGuard<'_>> {
+        let mut state = self.state.lock().unwrap();
+        if state.http1_only {
+            return Some(ConnectingGuard(None));
+        }
+        if state.connecting {
+            return None;
+        }
+        state.connecting = true;
+        Some(ConnectingGuard(Some(self)))
+    }
+
+    /// Takes a usable connection out of the pool, if there is one.
+    fn take(&self) -> Option<Sender> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(conn) = &mut state.http2 {
+            if !conn.sender.is_closed() {
+                conn.last_used = Instant::now();
+                return Some(Sender::Http2(conn.sender.clone()));
+            }
+            state.http2 = None;
+        }
+        while let Some(conn) = state.idle.pop() {
+            if conn.sender.is_ready() {
+                return Some(Sender::Http1(conn.sender));
+            }
+        }
+        None
+    }
+}
+
+/// Clears `HostState::connecting` and wakes up requests waiting on it once
+/// a connection attempt has finished, successfully or not.
+struct ConnectingGuard<'a>(Option<&'a Host>);
+
+impl Drop for ConnectingGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(host) = self.0 {
+            host.state.lock().unwrap().connecting = false;
+            host.returned.notify_waiters();
+        }
+    }
+}
+
+/// A builder for a [`ConnectionPool`].
+pub struct ConnectionPoolBuilder {
+    idle_timeout: Duration,
+    max_connections_per_host: usize,
+    #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+    http2: bool,
+    #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+    root_certificates: Option<rustls::RootCertStore>,
+}
+
+impl ConnectionPoolBuilder {
+    fn new() -> Self {
+        Self {
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            max_connections_per_host: Semaphore::MAX_PERMITS,
+            #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+            http2: true,
+            #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+            root_certificates: None,
+        }
+    }
+
+    /// Configures how long a connection may remain unused before it's closed.
+    ///
+    /// Defaults to [`DEFAULT_IDLE_TIMEOUT`].
+    pub fn idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Configures the maximum number of connections which may be open to a
+    /// single scheme and authority at once.
+    ///
+    /// Requests made while this many connections are busy wait for one to be
+    /// returned to the pool, up to their connect timeout. By default there is
+    /// no limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn max_connections_per_host(&mut self, max: usize) -> &mut Self {
+        assert!(max > 0, "at least one connection per host must be allowed");
+        self.max_connections_per_host = max.min(Semaphore::MAX_PERMITS);
+        self
+    }
+
+    /// Configures whether HTTP/2 is offered through ALPN when connecting to
+    /// `https` authorities.
+    ///
+    /// Defaults to `true`.
+    #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+    pub fn http2(&mut self, enable: bool) -> &mut Self {
+        self.http2 = enable;
+        self
+    }
+
+    /// Configures the root certificates used to verify servers when
+    /// connecting to `https` authorities.
+    ///
+    /// Defaults to the Mozilla root certificates provided by the
+    /// `webpki-roots` crate.
+    #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+    pub fn root_certificates(&mut self, roots: rustls::RootCertStore) -> &mut Self {
+        self.root_certificates = Some(roots);
+        self
+    }
+
+    /// Creates the configured pool.
+    pub fn build(&self) -> ConnectionPool {
+        ConnectionPool {
+            inner: Arc::new(PoolInner {
+                idle_timeout: self.idle_timeout,
+                max_connections_per_host: self.max_connections_per_host,
+                #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+                http2: self.http2,
+                #[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
+                http2: false,
+                #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+                tls: Arc::new(self.tls_config()),
+                hosts: Mutex::new(Hosts {
+                    map: HashMap::new(),
+                    prune_at: MIN_HOSTS_BEFORE_PRUNE,
+                }),
+            }),
+        }
+    }
+
+    #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+    fn tls_config(&self) -> rustls::ClientConfig {
+        let roots = self
+            .root_certificates
+            .clone()
+            .unwrap_or_else(|| rustls::RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+            });
+        let mut config = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        config.alpn_protocols = if self.http2 {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        } else {
+            vec![b"http/1.1".to_vec()]
+        };
+        config
+    }
+}
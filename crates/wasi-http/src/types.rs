@@ -2,6 +2,7 @@
 //! implementation of the wasi-http API.
 
 use crate::io::TokioIo;
+use crate::pool::ConnectionPool;
 use crate::{
     bindings::http::types::{self, Method, Scheme},
     body::{HostIncomingBody, HyperIncomingBody, HyperOutgoingBody},
@@ -23,13 +24,29 @@ use wasmtime_wasi::{runtime::AbortOnDropJoinHandle, IoImpl, IoView, Pollable};
 /// Capture the state necessary for use in the wasi-http API implementation.
 #[derive(Debug)]
 pub struct WasiHttpCtx {
-    _priv: (),
+    pool: Option<ConnectionPool>,
 }
 
 impl WasiHttpCtx {
     /// Create a new context.
+    ///
+    /// Outgoing requests sent with this context each use a fresh connection.
     pub fn new() -> Self {
-        Self { _priv: () }
+        Self { pool: None }
+    }
+
+    /// Create a new context which sends outgoing requests through `pool`.
+    ///
+    /// The same pool may be shared between many contexts, for example one
+    /// per store, so that connections are reused across instances.
+    pub fn with_connection_pool(pool: ConnectionPool) -> Self {
+        Self { pool: Some(pool) }
+    }
+
+    /// Returns the connection pool that outgoing requests are sent through,
+    /// if any.
+    pub fn connection_pool(&self) -> Option<&ConnectionPool> {
+        self.pool.as_ref()
     }
 }
 
@@ -111,12 +128,19 @@ pub trait WasiHttpView: IoView {
     }
 
     /// Send an outgoing request.
+    ///
+    /// By default this sends the request through the
+    /// [`ConnectionPool`] of this view's [`WasiHttpCtx`] if it has one, and
+    /// otherwise with [`default_send_request`].
     fn send_request(
         &mut self,
         request: hyper::Request<HyperOutgoingBody>,
         config: OutgoingRequestConfig,
     ) -> crate::HttpResult<HostFutureIncomingResponse> {
-        Ok(default_send_request(request, config))
+        match self.ctx().connection_pool() {
+            Some(pool) => Ok(pool.send_request(request, config)),
+            None => Ok(default_send_request(request, config)),
+        }
     }
 
     /// Whether a given header should be considered forbidden and not allowed.
@@ -343,34 +367,8 @@ pub async fn default_send_request_handler(
         between_bytes_timeout,
     }: OutgoingRequestConfig,
 ) -> Result<IncomingResponse, types::ErrorCode> {
-    let authority = if let Some(authority) = request.uri().authority() {
-        if authority.port().is_some() {
-            authority.to_string()
-        } else {
-            let port = if use_tls { 443 } else { 80 };
-            format!("{}:{port}", authority.to_string())
-        }
-    } else {
-        return Err(types::ErrorCode::HttpRequestUriInvalid);
-    };
-    let tcp_stream = timeout(connect_timeout, TcpStream::connect(&authority))
-        .await
-        .map_err(|_| types::ErrorCode::ConnectionTimeout)?
-        .map_err(|e| match e.kind() {
-            std::io::ErrorKind::AddrNotAvailable => {
-                dns_error("address not available".to_string(), 0)
-            }
-
-            _ => {
-                if e.to_string()
-                    .starts_with("failed to lookup address information")
-                {
-                    dns_error("address not available".to_string(), 0)
-                } else {
-                    types::ErrorCode::ConnectionRefused
-                }
-            }
-        })?;
+    let authority = request_authority(&request, use_tls)?;
+    let tcp_stream = connect_tcp(&authority, connect_timeout).await?;
 
     let (mut sender, worker) = if use_tls {
         #[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
@@ -382,8 +380,6 @@ pub async fn default_send_request_handler(
 
         #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
         {
-            use rustls::pki_types::ServerName;
-
             // derived from https://github.com/rustls/rustls/blob/main/examples/src/bin/simpleclient.rs
             let root_cert_store = rustls::RootCertStore {
                 roots: webpki_roots::TLS_SERVER_ROOTS.into(),
@@ -391,19 +387,7 @@ pub async fn default_send_request_handler(
             let config = rustls::ClientConfig::builder()
                 .with_root_certificates(root_cert_store)
                 .with_no_client_auth();
-            let connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(config));
-            let mut parts = authority.split(":");
-            let host = parts.next().unwrap_or(&authority);
-            let domain = ServerName::try_from(host)
-                .map_err(|e| {
-                    tracing::warn!("dns lookup error: {e:?}");
-                    dns_error("invalid dns name".to_string(), 0)
-                })?
-                .to_owned();
-            let stream = connector.connect(domain, tcp_stream).await.map_err(|e| {
-                tracing::warn!("tls protocol error: {e:?}");
-                types::ErrorCode::TlsProtocolError
-            })?;
+            let stream = connect_tls(tcp_stream, &authority, std::sync::Arc::new(config)).await?;
             let stream = TokioIo::new(stream);
 
             let (sender, conn) = timeout(
@@ -447,19 +431,7 @@ pub async fn default_send_request_handler(
         (sender, worker)
     };
 
-    // at this point, the request contains the scheme and the authority, but
-    // the http packet should only include those if addressing a proxy, so
-    // remove them here, since SendRequest::send_request does not do it for us
-    *request.uri_mut() = http::Uri::builder()
-        .path_and_query(
-            request
-                .uri()
-                .path_and_query()
-                .map(|p| p.as_str())
-                .unwrap_or("/"),
-        )
-        .build()
-        .expect("comes from valid request");
+    strip_uri_to_path(&mut request);
 
     let resp = timeout(first_byte_timeout, sender.send_request(request))
         .await
@@ -474,6 +446,92 @@ pub async fn default_send_request_handler(
     })
 }
 
+/// Returns the `host:port` that `request` is to be sent to, filling in the
+/// default port for the scheme if one isn't specified.
+pub(crate) fn request_authority(
+    request: &hyper::Request<HyperOutgoingBody>,
+    use_tls: bool,
+) -> Result<String, types::ErrorCode> {
+    let authority = request
+        .uri()
+        .authority()
+        .ok_or(types::ErrorCode::HttpRequestUriInvalid)?;
+    if authority.port().is_some() {
+        Ok(authority.to_string())
+    } else {
+        let port = if use_tls { 443 } else { 80 };
+        Ok(format!("{}:{port}", authority.to_string()))
+    }
+}
+
+/// Opens a TCP connection to `authority`, mapping failures to the
+/// `wasi:http` error they correspond to.
+pub(crate) async fn connect_tcp(
+    authority: &str,
+    connect_timeout: Duration,
+) -> Result<TcpStream, types::ErrorCode> {
+    timeout(connect_timeout, TcpStream::connect(authority))
+        .await
+        .map_err(|_| types::ErrorCode::ConnectionTimeout)?
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AddrNotAvailable => {
+                dns_error("address not available".to_string(), 0)
+            }
+
+            _ => {
+                if e.to_string()
+                    .starts_with("failed to lookup address information")
+                {
+                    dns_error("address not available".to_string(), 0)
+                } else {
+                    types::ErrorCode::ConnectionRefused
+                }
+            }
+        })
+}
+
+/// Performs a TLS handshake with `authority` over `tcp_stream`.
+#[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+pub(crate) async fn connect_tls(
+    tcp_stream: TcpStream,
+    authority: &str,
+    config: std::sync::Arc<rustls::ClientConfig>,
+) -> Result<tokio_rustls::client::TlsStream<TcpStream>, types::ErrorCode> {
+    use rustls::pki_types::ServerName;
+
+    let connector = tokio_rustls::TlsConnector::from(config);
+    let mut parts = authority.split(":");
+    let host = parts.next().unwrap_or(authority);
+    let domain = ServerName::try_from(host)
+        .map_err(|e| {
+            tracing::warn!("dns lookup error: {e:?}");
+            dns_error("invalid dns name".to_string(), 0)
+        })?
+        .to_owned();
+    connector.connect(domain, tcp_stream).await.map_err(|e| {
+        tracing::warn!("tls protocol error: {e:?}");
+        types::ErrorCode::TlsProtocolError
+    })
+}
+
+/// Removes the scheme and authority from the URI of `request`.
+///
+/// At this point the request contains the scheme and the authority, but
+/// the HTTP/1.1 packet should only include those if addressing a proxy, so
+/// remove them here, since `SendRequest::send_request` does not do it for us.
+pub(crate) fn strip_uri_to_path(request: &mut hyper::Request<HyperOutgoingBody>) {
+    *request.uri_mut() = http::Uri::builder()
+        .path_and_query(
+            request
+                .uri()
+                .path_and_query()
+                .map(|p| p.as_str())
+                .unwrap_or("/"),
+        )
+        .build()
+        .expect("comes from valid request");
+}
+
 impl From<http::Method> for types::Method {
     fn from(method: http::Method) -> Self {
         if method == http::Method::GET {
@@ -7,7 +7,7 @@ use std::{
     thread::JoinHandle,
 };
 use tokio::net::TcpListener;
-use wasmtime_wasi_http::io::TokioIo;
+use wasmtime_wasi_http::io::{TokioExecutor, TokioIo};
 
 async fn test(
     mut req: Request<hyper::body::Incoming>,
@@ -78,6 +78,20 @@ impl Server {
         })
     }
 
+    pub fn http1_keep_alive() -> Result<Self> {
+        tracing::debug!("initializing http1 keep-alive server");
+        Self::new(|io| async move {
+            let mut builder = hyper::server::conn::http1::Builder::new();
+            let http = builder.keep_alive(true);
+
+            tracing::debug!("preparing to bind connection to service");
+            let conn = http.serve_connection(io, service_fn(test)).await;
+            tracing::trace!("connection result {:?}", conn);
+            conn?;
+            Ok(())
+        })
+    }
+
     pub fn http2() -> Result<Self> {
         tracing::debug!("initializing http2 server");
         Self::new(|io| async move {
@@ -122,17 +136,3 @@ impl Drop for Server {
         }
     }
 }
-
-#[derive(Clone)]
-/// An Executor that uses the tokio runtime.
-struct TokioExecutor;
-
-impl<F> hyper::rt::Executor<F> for TokioExecutor
-where
-    F: Future + Send + 'static,
-    F::Output: Send + 'static,
-{
-    fn execute(&self, fut: F) {
-        tokio::task::spawn(fut);
-    }
-}
@@ -5,7 +5,7 @@ use http_body::Frame;
 use http_body_util::{combinators::BoxBody, BodyExt, Collected, Empty, StreamBody};
 use hyper::{body::Bytes, server::conn::http1, service::service_fn, Method, StatusCode};
 use sha2::{Digest, Sha256};
-use std::{collections::HashMap, iter, net::Ipv4Addr, str, sync::Arc};
+use std::{collections::HashMap, iter, net::Ipv4Addr, str, sync::Arc, time::Duration};
 use tokio::task;
 use wasmtime::{
     component::{Component, Linker, ResourceTable},
@@ -17,7 +17,7 @@ use wasmtime_wasi_http::{
     body::HyperOutgoingBody,
     io::TokioIo,
     types::{self, HostFutureIncomingResponse, IncomingResponse, OutgoingRequestConfig},
-    HttpResult, WasiHttpCtx, WasiHttpView,
+    ConnectionPool, HttpResult, WasiHttpCtx, WasiHttpView,
 };
 
 mod http_server;
@@ -551,6 +551,104 @@ async fn wasi_http_without_port() -> Result<()> {
     Ok(())
 }
 
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn connection_pool_reuses_connections() -> Result<()> {
+    // The server only accepts a single connection, and only one connection
+    // is allowed by the pool, so each request only succeeds if the connection
+    // made for the first one is reused.
+    let server = Server::http1_keep_alive()?;
+    let pool = ConnectionPool::builder()
+        .idle_timeout(Duration::from_millis(100))
+        .max_connections_per_host(1)
+        .build();
+
+    for path in ["/a", "/b", "/c"] {
+        let request = hyper::Request::builder()
+            .uri(format!("http://{}{path}", server.addr()))
+            .body(Empty::new().map_err(|e| match e {}).boxed())?;
+        let config = OutgoingRequestConfig {
+            use_tls: false,
+            connect_timeout: Duration::from_secs(5),
+            first_byte_timeout: Duration::from_secs(5),
+            between_bytes_timeout: Duration::from_secs(5),
+        };
+        let response = pool
+            .send_request_handler(request, config)
+            .await
+            .map_err(|e| anyhow!("failed to send request: {e:?}"))?;
+        let (parts, body) = response.resp.into_parts();
+        body.collect()
+            .await
+            .map_err(|e| anyhow!("failed to read body: {e:?}"))?;
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts.headers.get("x-wasmtime-test-uri"),
+            Some(&hyper::header::HeaderValue::from_static(path))
+        );
+    }
+
+    // Dropping the server waits for the connection to be closed, which the
+    // pool does once it's been idle for the configured timeout.
+    drop(pool);
+    drop(server);
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn connection_pool_forgets_unused_hosts() -> Result<()> {
+    let pool = ConnectionPool::builder()
+        .idle_timeout(Duration::from_millis(100))
+        .build();
+    let config = || OutgoingRequestConfig {
+        use_tls: false,
+        connect_timeout: Duration::from_secs(5),
+        first_byte_timeout: Duration::from_secs(5),
+        between_bytes_timeout: Duration::from_secs(5),
+    };
+    let request = |addr: String| {
+        hyper::Request::builder()
+            .uri(format!("http://{addr}/"))
+            .body(Empty::new().map_err(|e| match e {}).boxed())
+    };
+
+    // Nothing is listening on this address once the listener is dropped, so
+    // connecting fails and the host isn't kept.
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .to_string();
+    assert!(pool
+        .send_request_handler(request(addr)?, config())
+        .await
+        .is_err());
+    assert_eq!(pool.hosts(), 0);
+
+    // A host is kept while it has an idle connection, and then forgotten
+    // once that connection is closed.
+    let server = Server::http1_keep_alive()?;
+    let response = pool
+        .send_request_handler(request(server.addr())?, config())
+        .await
+        .map_err(|e| anyhow!("failed to send request: {e:?}"))?;
+    response
+        .resp
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| anyhow!("failed to read body: {e:?}"))?;
+    assert_eq!(pool.hosts(), 1);
+
+    let start = std::time::Instant::now();
+    while pool.hosts() > 0 {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "host never evicted"
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    drop(server);
+    Ok(())
+}
+
 mod body {
     use http_body_util::{combinators::BoxBody, BodyExt, Empty, Full};
     use hyper::body::Bytes;
@@ -0,0 +1,213 @@
+use crate::{increment_value, Backend, BackendBucket, Error};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A [`Backend`] which persists buckets to a directory on the host.
+///
+/// Each bucket is stored as a subdirectory of the root directory and each
+/// key-value pair as a file within the bucket's directory. Bucket identifiers
+/// and keys are escaped to produce portable file names, so any identifier,
+/// including the empty identifier, can be opened. Buckets are created on
+/// first use.
+///
+/// Escaping can make a name up to three times longer, and most filesystems
+/// limit file names to 255 bytes. Identifiers and keys whose escaped form is
+/// longer than that are rejected with an [`Error::Other`].
+///
+/// Writes are performed by writing to a temporary file and renaming it into
+/// place, so readers never observe partially written values. Atomic
+/// operations such as `increment` are only atomic with respect to other users
+/// of the same `DirBackend`; share a single backend (for example through an
+/// `Arc`) between all contexts using the same directory.
+pub struct DirBackend {
+    root: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl DirBackend {
+    /// Creates a new backend storing its buckets within `root`.
+    ///
+    /// The directory is created if it does not already exist.
+    pub fn new(root: impl Into<PathBuf>) -> io::Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self {
+            root,
+            lock: Arc::new(Mutex::new(())),
+        })
+    }
+}
+
+impl Backend for DirBackend {
+    fn open(&self, identifier: &str) -> Result<Arc<dyn BackendBucket>, Error> {
+        let dir = self
+            .root
+            .join(escape_checked("bucket identifier", identifier)?);
+        fs::create_dir_all(&dir)?;
+        Ok(Arc::new(DirBucket {
+            dir,
+            lock: self.lock.clone(),
+        }))
+    }
+}
+
+struct DirBucket {
+    dir: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl DirBucket {
+    fn path(&self, key: &str) -> Result<PathBuf, Error> {
+        Ok(self.dir.join(escape_checked("key", key)?))
+    }
+
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+        match fs::read(self.path(key)?) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, key: &str, value: &[u8]) -> Result<(), Error> {
+        static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+        let path = self.path(key)?;
+        // Temporary files start with a `.`, which `escape` never produces,
+        // so they can't collide with keys.
+        let tmp = self.dir.join(format!(
+            ".tmp-{}-{}",
+            std::process::id(),
+            NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = (|| {
+            let mut file = fs::File::create(&tmp)?;
+            file.write_all(value)?;
+            file.sync_data()?;
+            fs::rename(&tmp, &path)
+        })();
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        Ok(result?)
+    }
+}
+
+impl BackendBucket for DirBucket {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+        self.read(key)
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), Error> {
+        let _guard = self.lock.lock().unwrap();
+        self.write(key, &value)
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, Error> {
+        let _guard = self.lock.lock().unwrap();
+        match fs::remove_file(self.path(key)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, Error> {
+        Ok(self.path(key)?.try_exists()?)
+    }
+
+    fn list_keys(&self, cursor: Option<u64>) -> Result<(Vec<String>, Option<u64>), Error> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            if let Some(key) = name.to_str().and_then(unescape) {
+                keys.push(key);
+            }
+        }
+        // Sort keys to provide a stable order for cursors to index into.
+        keys.sort();
+        let cursor = usize::try_from(cursor.unwrap_or(0)).unwrap_or(usize::MAX);
+        Ok((keys.into_iter().skip(cursor).collect(), None))
+    }
+
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, Error> {
+        let _guard = self.lock.lock().unwrap();
+        let value = self.read(key)?;
+        let (new_value, encoded) = increment_value(value.as_deref(), delta)?;
+        self.write(key, &encoded)?;
+        Ok(new_value)
+    }
+}
+
+/// The longest file name [`escape_checked`] produces, which is the limit of
+/// most filesystems.
+const MAX_ESCAPED_LEN: usize = 255;
+
+/// Escapes `name` with [`escape`], returning an error if the result is too
+/// long to be used as a file name. `what` describes `name` in the error.
+fn escape_checked(what: &str, name: &str) -> Result<String, Error> {
+    let escaped = escape(name);
+    if escaped.len() > MAX_ESCAPED_LEN {
+        return Err(Error::Other(format!(
+            "{what} is too long to be stored in a directory: it is {} bytes \
+             once escaped but at most {MAX_ESCAPED_LEN} are supported",
+            escaped.len()
+        )));
+    }
+    Ok(escaped)
+}
+
+/// Escapes `name` into a string which is valid as a file name on all
+/// platforms.
+///
+/// Lowercase ASCII letters, digits, `-` and `_` are kept as-is and all other
+/// bytes are encoded as `%XX`. Uppercase letters are escaped too so that
+/// distinct names don't collide on case-insensitive filesystems. The empty
+/// name is encoded as a lone `%`, which is never produced otherwise.
+fn escape(name: &str) -> String {
+    if name.is_empty() {
+        return "%".to_string();
+    }
+    let mut escaped = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if is_unescaped(byte) {
+            escaped.push(char::from(byte));
+        } else {
+            write!(escaped, "%{byte:02X}").unwrap();
+        }
+    }
+    escaped
+}
+
+/// Inverse of [`escape`], returning `None` for names it could not have
+/// produced.
+fn unescape(name: &str) -> Option<String> {
+    if name == "%" {
+        return Some(String::new());
+    }
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+            }
+            b if is_unescaped(b) => {
+                bytes.push(b);
+                rest = tail;
+            }
+            _ => return None,
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn is_unescaped(byte: u8) -> bool {
+    byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' || byte == b'_'
+}
@@ -0,0 +1,68 @@
+use crate::{increment_value, Backend, BackendBucket, Error};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A [`Backend`] which keeps all data in memory.
+///
+/// Only the empty identifier is recognized by this backend, and each time it
+/// is opened a fresh bucket is created which is seeded with the data this
+/// backend was created with. Data written to a bucket is therefore lost once
+/// the bucket is dropped.
+pub struct InMemoryBackend {
+    data: HashMap<String, Vec<u8>>,
+}
+
+impl InMemoryBackend {
+    /// Creates a new in-memory backend seeded with `data`.
+    pub fn new(data: HashMap<String, Vec<u8>>) -> Self {
+        Self { data }
+    }
+}
+
+impl Backend for InMemoryBackend {
+    fn open(&self, identifier: &str) -> Result<Arc<dyn BackendBucket>, Error> {
+        match identifier {
+            "" => Ok(Arc::new(InMemoryBucket {
+                data: Mutex::new(self.data.clone()),
+            })),
+            _ => Err(Error::NoSuchStore),
+        }
+    }
+}
+
+struct InMemoryBucket {
+    data: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl BackendBucket for InMemoryBucket {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self.data.lock().unwrap().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), Error> {
+        self.data.lock().unwrap().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, Error> {
+        Ok(self.data.lock().unwrap().remove(key).is_some())
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, Error> {
+        Ok(self.data.lock().unwrap().contains_key(key))
+    }
+
+    fn list_keys(&self, cursor: Option<u64>) -> Result<(Vec<String>, Option<u64>), Error> {
+        let data = self.data.lock().unwrap();
+        let cursor = usize::try_from(cursor.unwrap_or(0)).unwrap_or(usize::MAX);
+        let keys = data.keys().skip(cursor).cloned().collect();
+        Ok((keys, None))
+    }
+
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, Error> {
+        let mut data = self.data.lock().unwrap();
+        let (new_value, encoded) = increment_value(data.get(key).map(|v| &v[..]), delta)?;
+        data.insert(key.to_string(), encoded);
+        Ok(new_value)
+    }
+}
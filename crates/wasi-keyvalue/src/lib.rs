@@ -5,7 +5,15 @@
 //! [wasi-keyvalue] and provide components with access to key-value storages.
 //!
 //! Currently supported storage backends:
-//! * In-Memory (empty identifier)
+//! * In-Memory (empty identifier), see [`InMemoryBackend`]
+//! * On-disk, with one directory per bucket, see [`DirBackend`]
+//!
+//! Other storage can be plugged in by implementing the [`Backend`] trait and
+//! configuring it with [`WasiKeyValueCtxBuilder::backend`].
+//!
+//! Changes to a bucket can be observed with [`WasiKeyValueCtx::watch`] and
+//! delivered to components exporting the `wasi:keyvalue/watcher` interface
+//! through the [`WatchService`] bindings.
 //!
 //! # Examples
 //!
@@ -67,10 +75,16 @@
 
 #![deny(missing_docs)]
 
+#[allow(missing_docs, reason = "bindgen-generated code")]
 mod generated {
     wasmtime::component::bindgen!({
         path: "wit",
-        world: "wasi:keyvalue/imports",
+        world: "wasi:keyvalue/watch-service",
+        // Flag this as "possibly async" which will cause the `watcher` exports
+        // to be generated as async, but none of the imports here are async.
+        async: {
+            only_imports: ["nonexistent"],
+        },
         trappable_imports: true,
         with: {
             "wasi:keyvalue/store/bucket": crate::Bucket,
@@ -81,33 +95,132 @@ mod generated {
     });
 }
 
+mod dir;
+mod in_memory;
+mod watch;
+
+pub use self::dir::DirBackend;
+pub use self::in_memory::InMemoryBackend;
+pub use self::watch::{Watch, WatchEvent, WatchEventKind};
+
+/// Bindings to the `wasi:keyvalue/watch-service` world, used to deliver
+/// [`WatchEvent`]s to components exporting `wasi:keyvalue/watcher`.
+pub use self::generated::{WatchService, WatchServiceIndices, WatchServicePre};
+
 use self::generated::wasi::keyvalue;
+use self::watch::Watchers;
 use anyhow::Result;
 use std::collections::HashMap;
+use std::sync::Arc;
 use wasmtime::component::{Resource, ResourceTable, ResourceTableError};
 
-#[doc(hidden)]
+/// Errors which may be returned by a storage [`Backend`].
+#[derive(Debug)]
 pub enum Error {
+    /// The host does not recognize the store identifier requested.
     NoSuchStore,
+    /// The requesting component does not have access to the specified store.
     AccessDenied,
+    /// Some implementation-specific error has occurred.
     Other(String),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NoSuchStore => f.write_str("no such store"),
+            Error::AccessDenied => f.write_str("access denied"),
+            Error::Other(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl From<ResourceTableError> for Error {
     fn from(err: ResourceTableError) -> Self {
         Self::Other(err.to_string())
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+/// A key-value storage backend which buckets are opened from.
+///
+/// Implementations of this trait can be configured with
+/// [`WasiKeyValueCtxBuilder::backend`] to provide components with access to
+/// host-defined storage. The built-in backends are [`InMemoryBackend`] and
+/// [`DirBackend`].
+pub trait Backend: Send + Sync + 'static {
+    /// Opens the bucket named by `identifier`, as requested by
+    /// `wasi:keyvalue/store.open`.
+    fn open(&self, identifier: &str) -> Result<Arc<dyn BackendBucket>, Error>;
+}
+
+impl<T: Backend + ?Sized> Backend for Arc<T> {
+    fn open(&self, identifier: &str) -> Result<Arc<dyn BackendBucket>, Error> {
+        (**self).open(identifier)
+    }
+}
+
+/// A single bucket of key-value pairs opened from a [`Backend`].
+pub trait BackendBucket: Send + Sync + 'static {
+    /// Returns the value associated with `key`, if any.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error>;
+
+    /// Sets the value associated with `key`, overwriting any existing value.
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), Error>;
+
+    /// Deletes the value associated with `key`, if any, returning whether
+    /// there was one.
+    fn delete(&self, key: &str) -> Result<bool, Error>;
+
+    /// Returns whether `key` has a value associated with it.
+    fn exists(&self, key: &str) -> Result<bool, Error> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Lists the keys in this bucket starting at the opaque `cursor`.
+    ///
+    /// Returns the keys along with the cursor to use to fetch the next page of
+    /// keys, or `None` if there are no more keys.
+    fn list_keys(&self, cursor: Option<u64>) -> Result<(Vec<String>, Option<u64>), Error>;
+
+    /// Atomically increments the decimal integer stored at `key` by `delta`,
+    /// treating a missing value as zero, and returns the new value.
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, Error>;
+}
+
+/// Parses a value stored by [`BackendBucket::increment`], adds `delta` to it
+/// and returns the new value along with its encoding.
+fn increment_value(value: Option<&[u8]>, delta: u64) -> Result<(u64, Vec<u8>), Error> {
+    let current_value = match value {
+        Some(value) => std::str::from_utf8(value)
+            .map_err(|e| Error::Other(e.to_string()))?
+            .parse::<u64>()
+            .map_err(|e| Error::Other(e.to_string()))?,
+        None => 0,
+    };
+    let new_value = current_value
+        .checked_add(delta)
+        .ok_or_else(|| Error::Other("increment overflows a u64 value".to_string()))?;
+    Ok((new_value, new_value.to_string().into_bytes()))
+}
+
 #[doc(hidden)]
 pub struct Bucket {
-    in_memory_data: HashMap<String, Vec<u8>>,
+    inner: Arc<dyn BackendBucket>,
 }
 
 /// Builder-style structure used to create a [`WasiKeyValueCtx`].
 #[derive(Default)]
 pub struct WasiKeyValueCtxBuilder {
     in_memory_data: HashMap<String, Vec<u8>>,
+    backend: Option<Arc<dyn Backend>>,
 }
 
 impl WasiKeyValueCtxBuilder {
@@ -117,6 +230,9 @@ impl WasiKeyValueCtxBuilder {
     }
 
     /// Preset data for the In-Memory provider.
+    ///
+    /// This is ignored if a different [`backend`](Self::backend) is
+    /// configured.
     pub fn in_memory_data<I, K, V>(mut self, data: I) -> Self
     where
         I: IntoIterator<Item = (K, V)>,
@@ -130,17 +246,37 @@ impl WasiKeyValueCtxBuilder {
         self
     }
 
+    /// Configures the storage backend that buckets are opened from.
+    ///
+    /// By default an [`InMemoryBackend`] seeded with
+    /// [`in_memory_data`](Self::in_memory_data) is used. Passing an
+    /// `Arc<impl Backend>` allows sharing a single backend between many
+    /// contexts, for example between the instances handling separate requests.
+    pub fn backend(mut self, backend: impl Backend) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
     /// Uses the configured context so far to construct the final [`WasiKeyValueCtx`].
     pub fn build(self) -> WasiKeyValueCtx {
+        let backend = match self.backend {
+            Some(backend) => backend,
+            None => Arc::new(InMemoryBackend::new(self.in_memory_data)),
+        };
         WasiKeyValueCtx {
-            in_memory_data: self.in_memory_data,
+            backend,
+            watchers: Watchers::default(),
         }
     }
 }
 
 /// Capture the state necessary for use in the `wasi-keyvalue` API implementation.
+///
+/// Clones of a context share the same backend and watchers.
+#[derive(Clone)]
 pub struct WasiKeyValueCtx {
-    in_memory_data: HashMap<String, Vec<u8>>,
+    backend: Arc<dyn Backend>,
+    watchers: Watchers,
 }
 
 impl WasiKeyValueCtx {
@@ -148,6 +284,24 @@ impl WasiKeyValueCtx {
     pub fn builder() -> WasiKeyValueCtxBuilder {
         WasiKeyValueCtxBuilder::new()
     }
+
+    /// Opens the bucket named by `identifier` for use by the host.
+    ///
+    /// Changes made through the returned bucket are reported to any
+    /// [`Watch`]es on the bucket, just like changes made by guests.
+    pub fn open(&self, identifier: &str) -> Result<Arc<dyn BackendBucket>, Error> {
+        let bucket = self.backend.open(identifier)?;
+        Ok(self.watchers.wrap(identifier, bucket))
+    }
+
+    /// Subscribes to changes made to the bucket named by `identifier`.
+    ///
+    /// Events received from the returned [`Watch`] can be delivered to a
+    /// component exporting `wasi:keyvalue/watcher` with
+    /// [`WatchEvent::dispatch`].
+    pub fn watch(&self, identifier: &str) -> Watch {
+        self.watchers.watch(identifier)
+    }
 }
 
 /// A wrapper capturing the needed internal `wasi-keyvalue` state.
@@ -165,12 +319,8 @@ impl<'a> WasiKeyValue<'a> {
 
 impl keyvalue::store::Host for WasiKeyValue<'_> {
     fn open(&mut self, identifier: String) -> Result<Resource<Bucket>, Error> {
-        match identifier.as_str() {
-            "" => Ok(self.table.push(Bucket {
-                in_memory_data: self.ctx.in_memory_data.clone(),
-            })?),
-            _ => Err(Error::NoSuchStore),
-        }
+        let inner = self.ctx.open(&identifier)?;
+        Ok(self.table.push(Bucket { inner })?)
     }
 
     fn convert_error(&mut self, err: Error) -> Result<keyvalue::store::Error> {
@@ -184,25 +334,24 @@ impl keyvalue::store::Host for WasiKeyValue<'_> {
 
 impl keyvalue::store::HostBucket for WasiKeyValue<'_> {
     fn get(&mut self, bucket: Resource<Bucket>, key: String) -> Result<Option<Vec<u8>>, Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        Ok(bucket.in_memory_data.get(&key).cloned())
+        let bucket = self.table.get(&bucket)?;
+        bucket.inner.get(&key)
     }
 
     fn set(&mut self, bucket: Resource<Bucket>, key: String, value: Vec<u8>) -> Result<(), Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        bucket.in_memory_data.insert(key, value);
-        Ok(())
+        let bucket = self.table.get(&bucket)?;
+        bucket.inner.set(&key, value)
     }
 
     fn delete(&mut self, bucket: Resource<Bucket>, key: String) -> Result<(), Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        bucket.in_memory_data.remove(&key);
+        let bucket = self.table.get(&bucket)?;
+        bucket.inner.delete(&key)?;
         Ok(())
     }
 
     fn exists(&mut self, bucket: Resource<Bucket>, key: String) -> Result<bool, Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        Ok(bucket.in_memory_data.contains_key(&key))
+        let bucket = self.table.get(&bucket)?;
+        bucket.inner.exists(&key)
     }
 
     fn list_keys(
@@ -210,14 +359,9 @@ impl keyvalue::store::HostBucket for WasiKeyValue<'_> {
         bucket: Resource<Bucket>,
         cursor: Option<u64>,
     ) -> Result<keyvalue::store::KeyResponse, Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        let keys: Vec<String> = bucket.in_memory_data.keys().cloned().collect();
-        let cursor = cursor.unwrap_or(0) as usize;
-        let keys_slice = &keys[cursor..];
-        Ok(keyvalue::store::KeyResponse {
-            keys: keys_slice.to_vec(),
-            cursor: None,
-        })
+        let bucket = self.table.get(&bucket)?;
+        let (keys, cursor) = bucket.inner.list_keys(cursor)?;
+        Ok(keyvalue::store::KeyResponse { keys, cursor })
     }
 
     fn drop(&mut self, bucket: Resource<Bucket>) -> Result<()> {
@@ -233,18 +377,8 @@ impl keyvalue::atomics::Host for WasiKeyValue<'_> {
         key: String,
         delta: u64,
     ) -> Result<u64, Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        let value = bucket
-            .in_memory_data
-            .entry(key.clone())
-            .or_insert("0".to_string().into_bytes());
-        let current_value = String::from_utf8(value.clone())
-            .map_err(|e| Error::Other(e.to_string()))?
-            .parse::<u64>()
-            .map_err(|e| Error::Other(e.to_string()))?;
-        let new_value = current_value + delta;
-        *value = new_value.to_string().into_bytes();
-        Ok(new_value)
+        let bucket = self.table.get(&bucket)?;
+        bucket.inner.increment(&key, delta)
     }
 }
 
@@ -254,16 +388,10 @@ impl keyvalue::batch::Host for WasiKeyValue<'_> {
         bucket: Resource<Bucket>,
         keys: Vec<String>,
     ) -> Result<Vec<Option<(String, Vec<u8>)>>, Error> {
-        let bucket = self.table.get_mut(&bucket)?;
-        Ok(keys
-            .into_iter()
-            .map(|key| {
-                bucket
-                    .in_memory_data
-                    .get(&key)
-                    .map(|value| (key.clone(), value.clone()))
-            })
-            .collect())
+        let bucket = self.table.get(&bucket)?;
+        keys.into_iter()
+            .map(|key| Ok(bucket.inner.get(&key)?.map(|value| (key, value))))
+            .collect()
     }
 
     fn set_many(
@@ -271,17 +399,17 @@ impl keyvalue::batch::Host for WasiKeyValue<'_> {
         bucket: Resource<Bucket>,
         key_values: Vec<(String, Vec<u8>)>,
     ) -> Result<(), Error> {
-        let bucket = self.table.get_mut(&bucket)?;
+        let bucket = self.table.get(&bucket)?;
         for (key, value) in key_values {
-            bucket.in_memory_data.insert(key, value);
+            bucket.inner.set(&key, value)?;
         }
         Ok(())
     }
 
     fn delete_many(&mut self, bucket: Resource<Bucket>, keys: Vec<String>) -> Result<(), Error> {
-        let bucket = self.table.get_mut(&bucket)?;
+        let bucket = self.table.get(&bucket)?;
         for key in keys {
-            bucket.in_memory_data.remove(&key);
+            bucket.inner.delete(&key)?;
         }
         Ok(())
     }
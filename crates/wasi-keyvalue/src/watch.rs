@@ -0,0 +1,189 @@
+use crate::{BackendBucket, Bucket, Error, WasiKeyValue, WatchService};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use wasmtime::AsContextMut;
+
+/// A change made to a key in a bucket, as observed by a [`Watch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    /// The identifier of the bucket which was changed.
+    pub identifier: String,
+    /// The key which was changed.
+    pub key: String,
+    /// The kind of change made to `key`.
+    pub kind: WatchEventKind,
+}
+
+/// The kind of change described by a [`WatchEvent`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchEventKind {
+    /// The key was set to the given value.
+    Set(Vec<u8>),
+    /// The key was deleted. Deleting a key which had no value isn't
+    /// reported.
+    Delete,
+}
+
+impl WatchEvent {
+    /// Delivers this event to the `wasi:keyvalue/watcher` export of
+    /// `instance`, calling either `on-set` or `on-delete`.
+    ///
+    /// The bucket passed to the guest is freshly opened from the context
+    /// returned by `f`. Note that any changes the guest makes to that bucket
+    /// are themselves reported to watchers of the bucket.
+    pub async fn dispatch<T: Send>(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        instance: &WatchService,
+        f: impl Fn(&mut T) -> WasiKeyValue<'_>,
+    ) -> Result<()> {
+        let mut store = store.as_context_mut();
+        let bucket = {
+            let view = f(store.data_mut());
+            let inner = view
+                .ctx
+                .open(&self.identifier)
+                .map_err(|e| anyhow::anyhow!("failed to open bucket: {e}"))?;
+            view.table.push(Bucket { inner })?
+        };
+        let watcher = instance.wasi_keyvalue_watcher();
+        match &self.kind {
+            WatchEventKind::Set(value) => {
+                watcher
+                    .call_on_set(&mut store, bucket, &self.key, value)
+                    .await
+            }
+            WatchEventKind::Delete => watcher.call_on_delete(&mut store, bucket, &self.key).await,
+        }
+    }
+}
+
+/// A subscription to changes made to a bucket, created with
+/// [`WasiKeyValueCtx::watch`](crate::WasiKeyValueCtx::watch).
+///
+/// Changes made through the context the `Watch` was created from, or any
+/// clone of it, are reported whether they are made by a guest or by the host.
+/// Dropping a `Watch` unsubscribes it.
+pub struct Watch {
+    events: mpsc::UnboundedReceiver<WatchEvent>,
+}
+
+impl Watch {
+    /// Waits for the next change made to the watched bucket.
+    pub async fn next(&mut self) -> Option<WatchEvent> {
+        self.events.recv().await
+    }
+
+    /// Returns the next change made to the watched bucket, if one is
+    /// immediately available.
+    pub fn try_next(&mut self) -> Option<WatchEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+/// The set of active [`Watch`]es, keyed by bucket identifier.
+#[derive(Clone, Default)]
+pub(crate) struct Watchers {
+    senders: Arc<Mutex<HashMap<String, Vec<mpsc::UnboundedSender<WatchEvent>>>>>,
+}
+
+impl Watchers {
+    pub(crate) fn watch(&self, identifier: &str) -> Watch {
+        let (sender, events) = mpsc::unbounded_channel();
+        self.senders
+            .lock()
+            .unwrap()
+            .entry(identifier.to_string())
+            .or_default()
+            .push(sender);
+        Watch { events }
+    }
+
+    /// Wraps `bucket` such that changes made through it are reported to
+    /// watchers of `identifier`.
+    pub(crate) fn wrap(
+        &self,
+        identifier: &str,
+        bucket: Arc<dyn BackendBucket>,
+    ) -> Arc<dyn BackendBucket> {
+        Arc::new(WatchedBucket {
+            identifier: identifier.to_string(),
+            inner: bucket,
+            watchers: self.clone(),
+        })
+    }
+
+    fn is_watched(&self, identifier: &str) -> bool {
+        self.senders.lock().unwrap().contains_key(identifier)
+    }
+
+    fn notify(&self, identifier: &str, key: &str, kind: WatchEventKind) {
+        let mut senders = self.senders.lock().unwrap();
+        let Some(list) = senders.get_mut(identifier) else {
+            return;
+        };
+        let event = WatchEvent {
+            identifier: identifier.to_string(),
+            key: key.to_string(),
+            kind,
+        };
+        // Senders whose `Watch` has been dropped are pruned here.
+        list.retain(|sender| sender.send(event.clone()).is_ok());
+        if list.is_empty() {
+            senders.remove(identifier);
+        }
+    }
+}
+
+struct WatchedBucket {
+    identifier: String,
+    inner: Arc<dyn BackendBucket>,
+    watchers: Watchers,
+}
+
+impl BackendBucket for WatchedBucket {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+        self.inner.get(key)
+    }
+
+    fn set(&self, key: &str, value: Vec<u8>) -> Result<(), Error> {
+        // Avoid copying the value when nothing is watching this bucket.
+        let event_value = self
+            .watchers
+            .is_watched(&self.identifier)
+            .then(|| value.clone());
+        self.inner.set(key, value)?;
+        if let Some(value) = event_value {
+            self.watchers
+                .notify(&self.identifier, key, WatchEventKind::Set(value));
+        }
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, Error> {
+        let deleted = self.inner.delete(key)?;
+        if deleted {
+            self.watchers
+                .notify(&self.identifier, key, WatchEventKind::Delete);
+        }
+        Ok(deleted)
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, Error> {
+        self.inner.exists(key)
+    }
+
+    fn list_keys(&self, cursor: Option<u64>) -> Result<(Vec<String>, Option<u64>), Error> {
+        self.inner.list_keys(cursor)
+    }
+
+    fn increment(&self, key: &str, delta: u64) -> Result<u64, Error> {
+        let value = self.inner.increment(key, delta)?;
+        let encoded = value.to_string().into_bytes();
+        self.watchers
+            .notify(&self.identifier, key, WatchEventKind::Set(encoded));
+        Ok(value)
+    }
+}
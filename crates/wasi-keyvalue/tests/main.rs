@@ -1,11 +1,17 @@
 use anyhow::{anyhow, Result};
-use test_programs_artifacts::{foreach_keyvalue, KEYVALUE_MAIN_COMPONENT};
+use std::sync::Arc;
+use test_programs_artifacts::{
+    foreach_keyvalue, KEYVALUE_MAIN_COMPONENT, KEYVALUE_WATCHER_COMPONENT,
+};
 use wasmtime::{
     component::{Component, Linker, ResourceTable},
     Store,
 };
 use wasmtime_wasi::{bindings::Command, IoView, WasiCtx, WasiCtxBuilder, WasiView};
-use wasmtime_wasi_keyvalue::{WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder};
+use wasmtime_wasi_keyvalue::{
+    Backend, DirBackend, WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder, WatchEventKind,
+    WatchService,
+};
 
 struct Ctx {
     table: ResourceTable,
@@ -68,3 +74,131 @@ async fn keyvalue_main() -> Result<()> {
     )
     .await
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keyvalue_main_dir_backend() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let backend = Arc::new(DirBackend::new(dir.path())?);
+    backend.open("")?.set("atomics_key", b"5".to_vec())?;
+
+    run_wasi(
+        KEYVALUE_MAIN_COMPONENT,
+        Ctx {
+            table: ResourceTable::new(),
+            wasi_ctx: WasiCtxBuilder::new().inherit_stderr().build(),
+            wasi_keyvalue_ctx: WasiKeyValueCtxBuilder::new()
+                .backend(backend.clone())
+                .build(),
+        },
+    )
+    .await?;
+
+    // Data written by the guest outlives the store and is visible to a new
+    // backend using the same directory.
+    let bucket = DirBackend::new(dir.path())?.open("")?;
+    assert_eq!(bucket.get("atomics_key")?.as_deref(), Some(&b"6"[..]));
+    assert_eq!(bucket.get("b1")?.as_deref(), Some(&b"v1"[..]));
+    assert!(!bucket.exists("a1")?);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keyvalue_watcher() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let wasi_keyvalue_ctx = WasiKeyValueCtxBuilder::new()
+        .backend(DirBackend::new(dir.path())?)
+        .build();
+    let mut watch = wasi_keyvalue_ctx.watch("");
+
+    let engine = test_programs_artifacts::engine(|config| {
+        config.async_support(true);
+    });
+    let mut store = Store::new(
+        &engine,
+        Ctx {
+            table: ResourceTable::new(),
+            wasi_ctx: WasiCtxBuilder::new().inherit_stderr().build(),
+            wasi_keyvalue_ctx: wasi_keyvalue_ctx.clone(),
+        },
+    );
+    let component = Component::from_file(&engine, KEYVALUE_WATCHER_COMPONENT)?;
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker_async(&mut linker)?;
+    wasmtime_wasi_keyvalue::add_to_linker(&mut linker, |h: &mut Ctx| {
+        WasiKeyValue::new(&h.wasi_keyvalue_ctx, &mut h.table)
+    })?;
+    let watcher = WatchService::instantiate_async(&mut store, &component, &linker).await?;
+
+    // Changes made by the host are observed and delivered to the guest, and
+    // changes made by the guest in response are observed as well.
+    let bucket = wasi_keyvalue_ctx.open("")?;
+    bucket.set("a", b"1".to_vec())?;
+    assert!(bucket.delete("a")?);
+    // Deleting a key which doesn't exist isn't a change.
+    assert!(!bucket.delete("a")?);
+    // Watches are per-bucket, so this is not observed.
+    wasi_keyvalue_ctx.open("other")?.set("b", b"2".to_vec())?;
+
+    let mut events = Vec::new();
+    while let Some(event) = watch.try_next() {
+        event
+            .dispatch(&mut store, &watcher, |h: &mut Ctx| {
+                WasiKeyValue::new(&h.wasi_keyvalue_ctx, &mut h.table)
+            })
+            .await?;
+        events.push((event.key, event.kind));
+    }
+    assert_eq!(
+        events,
+        [
+            ("a".to_string(), WatchEventKind::Set(b"1".to_vec())),
+            ("a".to_string(), WatchEventKind::Delete),
+            ("a.seen".to_string(), WatchEventKind::Set(b"1".to_vec())),
+            ("a.deleted".to_string(), WatchEventKind::Set(Vec::new())),
+        ]
+    );
+    assert_eq!(bucket.get("a.seen")?.as_deref(), Some(&b"1"[..]));
+    assert!(bucket.exists("a.deleted")?);
+    Ok(())
+}
+
+#[test]
+fn dir_backend_increment_overflow() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let bucket = DirBackend::new(dir.path())?.open("")?;
+    bucket.set("counter", u64::MAX.to_string().into_bytes())?;
+    assert!(bucket.increment("counter", 1).is_err());
+    assert_eq!(
+        bucket.get("counter")?,
+        Some(u64::MAX.to_string().into_bytes())
+    );
+    Ok(())
+}
+
+#[test]
+fn dir_backend_long_keys() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let bucket = DirBackend::new(dir.path())?.open("")?;
+
+    // Keys are escaped to at most three times their length, so this is the
+    // longest key made of bytes which are always escaped.
+    let longest = "A".repeat(85);
+    bucket.set(&longest, b"1".to_vec())?;
+    assert_eq!(bucket.get(&longest)?.as_deref(), Some(&b"1"[..]));
+    assert_eq!(bucket.list_keys(None)?.0, [longest]);
+
+    let key = "A".repeat(86);
+    let err = bucket.set(&key, b"1".to_vec()).unwrap_err();
+    assert!(err.to_string().contains("key is too long"), "{err}");
+    assert!(bucket.get(&key).is_err());
+
+    let err = DirBackend::new(dir.path())?
+        .open(&"a".repeat(256))
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("bucket identifier is too long"),
+        "{err}"
+    );
+    Ok(())
+}
@@ -1,5 +1,6 @@
 pub mod host;
 use cap_std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub trait HostWallClock: Send {
     fn resolution(&self) -> Duration;
@@ -10,3 +11,61 @@ pub trait HostMonotonicClock: Send {
     fn resolution(&self) -> u64;
     fn now(&self) -> u64;
 }
+
+/// A [`HostWallClock`] which never consults the host's clock.
+///
+/// The clock starts at `start` and every read of it advances it by `step`, so
+/// a guest observes the same sequence of times on every host.
+pub struct VirtualWallClock {
+    /// Nanoseconds since the Unix epoch returned by the next `now`.
+    now: AtomicU64,
+    step: u64,
+}
+
+impl VirtualWallClock {
+    pub fn new(start: Duration, step: Duration) -> Self {
+        Self {
+            now: AtomicU64::new(start.as_nanos().try_into().unwrap()),
+            step: step.as_nanos().try_into().unwrap(),
+        }
+    }
+}
+
+impl HostWallClock for VirtualWallClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(self.step.max(1))
+    }
+
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.now.fetch_add(self.step, Ordering::Relaxed))
+    }
+}
+
+/// A [`HostMonotonicClock`] which never consults the host's clock.
+///
+/// The clock starts at zero and every read of it advances it by `step`
+/// nanoseconds, so a guest observes the same sequence of instants on every
+/// host.
+pub struct VirtualMonotonicClock {
+    now: AtomicU64,
+    step: u64,
+}
+
+impl VirtualMonotonicClock {
+    pub fn new(step: u64) -> Self {
+        Self {
+            now: AtomicU64::new(0),
+            step,
+        }
+    }
+}
+
+impl HostMonotonicClock for VirtualMonotonicClock {
+    fn resolution(&self) -> u64 {
+        self.step.max(1)
+    }
+
+    fn now(&self) -> u64 {
+        self.now.fetch_add(self.step, Ordering::Relaxed)
+    }
+}
@@ -1,17 +1,19 @@
 use crate::{
     clocks::{
         host::{monotonic_clock, wall_clock},
-        HostMonotonicClock, HostWallClock,
+        HostMonotonicClock, HostWallClock, VirtualMonotonicClock, VirtualWallClock,
     },
-    filesystem::{Dir, OpenMode},
+    filesystem::{Descriptor, Dir, OpenMode, OpenVirtualDir, VirtualDir},
     network::{SocketAddrCheck, SocketAddrUse},
     pipe, random, stdio,
     stdio::{StdinStream, StdoutStream},
     DirPerms, FilePerms,
 };
-use anyhow::Result;
+use anyhow::{bail, Result};
 use cap_rand::{Rng, RngCore, SeedableRng};
 use cap_std::ambient_authority;
+use cap_std::time::Duration;
+use rand_chacha::ChaCha20Rng;
 use std::path::Path;
 use std::sync::Arc;
 use std::{future::Future, pin::Pin};
@@ -43,7 +45,7 @@ pub struct WasiCtxBuilder {
     stderr: Box<dyn StdoutStream>,
     env: Vec<(String, String)>,
     args: Vec<String>,
-    preopens: Vec<(Dir, String)>,
+    preopens: Vec<(Descriptor, String)>,
     socket_addr_check: SocketAddrCheck,
     random: Box<dyn RngCore + Send>,
     insecure_random: Box<dyn RngCore + Send>,
@@ -52,9 +54,41 @@ pub struct WasiCtxBuilder {
     monotonic_clock: Box<dyn HostMonotonicClock + Send>,
     allowed_network_uses: AllowedNetworkUses,
     allow_blocking_current_thread: bool,
+    host_sources: HostSources,
     built: bool,
 }
 
+/// Tracks which parts of a [`WasiCtxBuilder`] are sourced from the host, used
+/// to report leaks of nondeterminism in
+/// [`WasiCtxBuilder::check_deterministic`].
+struct HostSources {
+    stdin: bool,
+    env: bool,
+    args: bool,
+    preopens: Vec<String>,
+    random: bool,
+    insecure_random: bool,
+    insecure_random_seed: bool,
+    wall_clock: bool,
+    monotonic_clock: bool,
+}
+
+impl Default for HostSources {
+    fn default() -> Self {
+        Self {
+            stdin: false,
+            env: false,
+            args: false,
+            preopens: Vec::new(),
+            random: true,
+            insecure_random: true,
+            insecure_random_seed: true,
+            wall_clock: true,
+            monotonic_clock: true,
+        }
+    }
+}
+
 impl WasiCtxBuilder {
     /// Creates a builder for a new context with default parameters set.
     ///
@@ -101,6 +135,7 @@ impl WasiCtxBuilder {
             monotonic_clock: monotonic_clock(),
             allowed_network_uses: AllowedNetworkUses::default(),
             allow_blocking_current_thread: false,
+            host_sources: HostSources::default(),
             built: false,
         }
     }
@@ -121,6 +156,7 @@ impl WasiCtxBuilder {
     /// [`inherit_stdin`](WasiCtxBuilder::inherit_stdin).
     pub fn stdin(&mut self, stdin: impl StdinStream + 'static) -> &mut Self {
         self.stdin = Box::new(stdin);
+        self.host_sources.stdin = false;
         self
     }
 
@@ -143,7 +179,9 @@ impl WasiCtxBuilder {
     /// when using this it's typically best to have a single wasm instance in
     /// the process using this.
     pub fn inherit_stdin(&mut self) -> &mut Self {
-        self.stdin(stdio::stdin())
+        self.stdin(stdio::stdin());
+        self.host_sources.stdin = true;
+        self
     }
 
     /// Configures this context's stdout stream to write to the host process's
@@ -257,7 +295,9 @@ impl WasiCtxBuilder {
     /// This will use [`envs`](WasiCtxBuilder::envs) to append all host-defined
     /// environment variables.
     pub fn inherit_env(&mut self) -> &mut Self {
-        self.envs(&std::env::vars().collect::<Vec<(String, String)>>())
+        self.envs(&std::env::vars().collect::<Vec<(String, String)>>());
+        self.host_sources.env = true;
+        self
     }
 
     /// Appends a list of arguments to the argument array to pass to wasm.
@@ -275,7 +315,9 @@ impl WasiCtxBuilder {
     /// Appends all host process arguments to the list of arguments to get
     /// passed to wasm.
     pub fn inherit_args(&mut self) -> &mut Self {
-        self.args(&std::env::args().collect::<Vec<String>>())
+        self.args(&std::env::args().collect::<Vec<String>>());
+        self.host_sources.args = true;
+        self
     }
 
     /// Configures a "preopened directory" to be available to WebAssembly.
@@ -339,18 +381,65 @@ impl WasiCtxBuilder {
             open_mode |= OpenMode::WRITE;
         }
         self.preopens.push((
-            Dir::new(
+            Descriptor::Dir(Dir::new(
                 dir,
                 dir_perms,
                 file_perms,
                 open_mode,
                 self.allow_blocking_current_thread,
-            ),
+            )),
             guest_path.as_ref().to_owned(),
         ));
+        self.host_sources
+            .preopens
+            .push(guest_path.as_ref().to_owned());
         Ok(self)
     }
 
+    /// Configures a "preopened directory" backed by a filesystem implemented
+    /// by the embedder rather than by a directory on the host.
+    ///
+    /// This behaves like [`WasiCtxBuilder::preopened_dir`] except that all
+    /// operations on `dir`, and on any files and directories opened through
+    /// it, are dispatched to the [`VirtualDir`] trait. Guest paths are always
+    /// resolved within `dir`, so they can't reach the host filesystem.
+    ///
+    /// See [`MemoryDir`](crate::MemoryDir) for an implementation which keeps
+    /// its contents in memory, optionally layered over a read-only host
+    /// directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use wasmtime_wasi::{WasiCtxBuilder, DirPerms, FilePerms, MemoryDir};
+    ///
+    /// let mut wasi = WasiCtxBuilder::new();
+    ///
+    /// // Make an empty in-memory directory available in the guest as `/tmp`
+    /// wasi.preopened_virtual_dir(Arc::new(MemoryDir::new()), "/tmp", DirPerms::all(), FilePerms::all());
+    /// ```
+    pub fn preopened_virtual_dir(
+        &mut self,
+        dir: Arc<dyn VirtualDir>,
+        guest_path: impl AsRef<str>,
+        dir_perms: DirPerms,
+        file_perms: FilePerms,
+    ) -> &mut Self {
+        let mut open_mode = OpenMode::empty();
+        if dir_perms.contains(DirPerms::READ) {
+            open_mode |= OpenMode::READ;
+        }
+        if dir_perms.contains(DirPerms::MUTATE) {
+            open_mode |= OpenMode::WRITE;
+        }
+        self.preopens.push((
+            Descriptor::VirtualDir(OpenVirtualDir::new(dir, dir_perms, file_perms, open_mode)),
+            guest_path.as_ref().to_owned(),
+        ));
+        self
+    }
+
     /// Set the generator for the `wasi:random/random` number generator to the
     /// custom generator specified.
     ///
@@ -363,6 +452,7 @@ impl WasiCtxBuilder {
     /// prerecorded or otherwise predictable data may compromise security.
     pub fn secure_random(&mut self, random: impl RngCore + Send + 'static) -> &mut Self {
         self.random = Box::new(random);
+        self.host_sources.random = false;
         self
     }
 
@@ -372,6 +462,7 @@ impl WasiCtxBuilder {
     /// requested by the `wasi:random/insecure` interface.
     pub fn insecure_random(&mut self, insecure_random: impl RngCore + Send + 'static) -> &mut Self {
         self.insecure_random = Box::new(insecure_random);
+        self.host_sources.insecure_random = false;
         self
     }
 
@@ -381,6 +472,7 @@ impl WasiCtxBuilder {
     /// By default this number is randomly generated when a builder is created.
     pub fn insecure_random_seed(&mut self, insecure_random_seed: u128) -> &mut Self {
         self.insecure_random_seed = insecure_random_seed;
+        self.host_sources.insecure_random_seed = false;
         self
     }
 
@@ -389,6 +481,7 @@ impl WasiCtxBuilder {
     /// By default the host's wall clock is used.
     pub fn wall_clock(&mut self, clock: impl HostWallClock + 'static) -> &mut Self {
         self.wall_clock = Box::new(clock);
+        self.host_sources.wall_clock = false;
         self
     }
 
@@ -397,6 +490,7 @@ impl WasiCtxBuilder {
     /// By default the host's monotonic clock is used.
     pub fn monotonic_clock(&mut self, clock: impl HostMonotonicClock + 'static) -> &mut Self {
         self.monotonic_clock = Box::new(clock);
+        self.host_sources.monotonic_clock = false;
         self
     }
 
@@ -452,6 +546,134 @@ impl WasiCtxBuilder {
         self
     }
 
+    /// Configures this context to behave deterministically, such that a guest
+    /// observes the same results from WASI on every host.
+    ///
+    /// This replaces everything that the context sources from the host by
+    /// default:
+    ///
+    /// * `wasi:clocks/wall-clock` and `wasi:clocks/monotonic-clock` are backed
+    ///   by a [`VirtualWallClock`] and [`VirtualMonotonicClock`] which start at
+    ///   the Unix epoch and zero, respectively, and advance by one microsecond
+    ///   each time they're read.
+    /// * `wasi:random/random`, `wasi:random/insecure` and
+    ///   `wasi:random/insecure-seed` are all derived from `seed` with the
+    ///   ChaCha20 algorithm, so they're the same on every host.
+    /// * TCP, UDP and `wasi:sockets/ip-name-lookup` are disabled and all
+    ///   socket addresses are denied.
+    ///
+    /// Any of these can be overridden afterwards, for example with a custom
+    /// [`wall_clock`](WasiCtxBuilder::wall_clock), in which case the embedder
+    /// is responsible for the determinism of what it provides. Use
+    /// [`check_deterministic`](WasiCtxBuilder::check_deterministic) to
+    /// validate the final configuration before building it.
+    ///
+    /// Note that blocking on a pollable still waits for the requested amount
+    /// of time on the host, but the guest only observes virtual time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmtime_wasi::WasiCtxBuilder;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut wasi = WasiCtxBuilder::new();
+    /// wasi.deterministic(42);
+    /// wasi.arg("./foo.wasm");
+    /// wasi.inherit_stdout();
+    /// wasi.check_deterministic()?;
+    /// let wasi = wasi.build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deterministic(&mut self, seed: u64) -> &mut Self {
+        // The output of `StdRng` and `SmallRng` may differ between platforms
+        // and `rand` versions, so a named algorithm is used for both the
+        // secure and insecure generators to keep runs reproducible.
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let insecure_random = ChaCha20Rng::from_rng(&mut rng).unwrap();
+        let insecure_random_seed = rng.r#gen::<u128>();
+        self.secure_random(rng)
+            .insecure_random(insecure_random)
+            .insecure_random_seed(insecure_random_seed)
+            .wall_clock(VirtualWallClock::new(
+                Duration::ZERO,
+                Duration::from_micros(1),
+            ))
+            .monotonic_clock(VirtualMonotonicClock::new(1_000))
+            .socket_addr_check(|_, _| Box::pin(async { false }))
+            .allow_ip_name_lookup(false)
+            .allow_udp(false)
+            .allow_tcp(false)
+    }
+
+    /// Checks that the context configured so far can't leak nondeterminism
+    /// from the host into the guest.
+    ///
+    /// This is typically used after
+    /// [`deterministic`](WasiCtxBuilder::deterministic) to validate that
+    /// nothing configured since has reintroduced a dependency on the host.
+    /// Things supplied by the embedder, such as custom streams, clocks or
+    /// [`VirtualDir`]s, are assumed to be deterministic.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing everything which is still sourced from the
+    /// host, for example host clocks or randomness, inherited stdin,
+    /// environment variables or arguments, preopened host directories, and
+    /// enabled networking.
+    pub fn check_deterministic(&self) -> Result<()> {
+        let sources = &self.host_sources;
+        let mut leaks = Vec::new();
+        if sources.wall_clock {
+            leaks.push("the wall clock is the host's clock".to_string());
+        }
+        if sources.monotonic_clock {
+            leaks.push("the monotonic clock is the host's clock".to_string());
+        }
+        if sources.random {
+            leaks.push("`wasi:random/random` is seeded by the host".to_string());
+        }
+        if sources.insecure_random {
+            leaks.push("`wasi:random/insecure` is seeded by the host".to_string());
+        }
+        if sources.insecure_random_seed {
+            leaks.push("`wasi:random/insecure-seed` is generated by the host".to_string());
+        }
+        if sources.stdin {
+            leaks.push("stdin is inherited from the host".to_string());
+        }
+        if sources.env {
+            leaks.push("environment variables are inherited from the host".to_string());
+        }
+        if sources.args {
+            leaks.push("arguments are inherited from the host".to_string());
+        }
+        for guest_path in sources.preopens.iter() {
+            leaks.push(format!(
+                "the host directory preopened as `{guest_path}` can be observed"
+            ));
+        }
+        if self.allowed_network_uses.tcp {
+            leaks.push("TCP is allowed".to_string());
+        }
+        if self.allowed_network_uses.udp {
+            leaks.push("UDP is allowed".to_string());
+        }
+        if self.allowed_network_uses.ip_name_lookup {
+            leaks.push("`wasi:sockets/ip-name-lookup` is allowed".to_string());
+        }
+        if leaks.is_empty() {
+            return Ok(());
+        }
+        let mut msg = String::from("WASI context is not deterministic:");
+        for leak in leaks {
+            msg.push_str("\n  * ");
+            msg.push_str(&leak);
+        }
+        bail!(msg)
+    }
+
     /// Uses the configured context so far to construct the final [`WasiCtx`].
     ///
     /// Note that each `WasiCtxBuilder` can only be used to "build" once, and
@@ -480,6 +702,7 @@ impl WasiCtxBuilder {
             monotonic_clock,
             allowed_network_uses,
             allow_blocking_current_thread,
+            host_sources: _,
             built: _,
         } = mem::replace(self, Self::new());
         self.built = true;
@@ -576,7 +799,7 @@ pub struct WasiCtx {
     pub(crate) monotonic_clock: Box<dyn HostMonotonicClock + Send>,
     pub(crate) env: Vec<(String, String)>,
     pub(crate) args: Vec<String>,
-    pub(crate) preopens: Vec<(Dir, String)>,
+    pub(crate) preopens: Vec<(Descriptor, String)>,
     pub(crate) stdin: Box<dyn StdinStream>,
     pub(crate) stdout: Box<dyn StdoutStream>,
     pub(crate) stderr: Box<dyn StdoutStream>,
@@ -631,3 +854,24 @@ impl AllowedNetworkUses {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // These values must stay the same across platforms and upgrades of the
+    // `rand` crates, otherwise previously recorded runs can't be reproduced.
+    #[test]
+    fn deterministic_random() {
+        let mut wasi = WasiCtxBuilder::new().deterministic(42).build();
+        let mut buf = [0; 8];
+        wasi.random.fill_bytes(&mut buf);
+        assert_eq!(buf, [197, 33, 10, 45, 228, 168, 212, 211]);
+        wasi.insecure_random.fill_bytes(&mut buf);
+        assert_eq!(buf, [220, 75, 105, 17, 232, 129, 52, 158]);
+        assert_eq!(
+            wasi.insecure_random_seed,
+            0x58657584fbf586aa29c45da7a992f255
+        );
+    }
+}
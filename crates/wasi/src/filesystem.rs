@@ -7,6 +7,13 @@ use std::io;
 use std::mem;
 use std::sync::Arc;
 
+mod memory;
+mod vfs;
+
+pub use self::memory::MemoryDir;
+pub use self::vfs::{OpenVirtualDir, OpenVirtualFile, VirtualDir, VirtualFile, VirtualNode};
+pub(crate) use self::vfs::{VirtualFileInputStream, VirtualFileOutputStream};
+
 pub type FsResult<T> = Result<T, FsError>;
 
 pub type FsError = TrappableError<types::ErrorCode>;
@@ -23,37 +30,49 @@ impl From<io::Error> for FsError {
     }
 }
 
+#[derive(Clone)]
 pub enum Descriptor {
     File(File),
     Dir(Dir),
+    /// A file of a filesystem implemented by the embedder.
+    VirtualFile(OpenVirtualFile),
+    /// A directory of a filesystem implemented by the embedder.
+    VirtualDir(OpenVirtualDir),
 }
 
 impl Descriptor {
+    /// Returns the host file of this descriptor, failing for directories and
+    /// virtual files.
     pub fn file(&self) -> Result<&File, types::ErrorCode> {
         match self {
             Descriptor::File(f) => Ok(f),
-            Descriptor::Dir(_) => Err(types::ErrorCode::BadDescriptor),
+            Descriptor::Dir(_) | Descriptor::VirtualFile(_) | Descriptor::VirtualDir(_) => {
+                Err(types::ErrorCode::BadDescriptor)
+            }
         }
     }
 
+    /// Returns the host directory of this descriptor, failing for files and
+    /// virtual directories.
     pub fn dir(&self) -> Result<&Dir, types::ErrorCode> {
         match self {
             Descriptor::Dir(d) => Ok(d),
-            Descriptor::File(_) => Err(types::ErrorCode::NotDirectory),
+            Descriptor::File(_) | Descriptor::VirtualFile(_) => Err(types::ErrorCode::NotDirectory),
+            Descriptor::VirtualDir(_) => Err(types::ErrorCode::BadDescriptor),
         }
     }
 
     pub fn is_file(&self) -> bool {
         match self {
-            Descriptor::File(_) => true,
-            Descriptor::Dir(_) => false,
+            Descriptor::File(_) | Descriptor::VirtualFile(_) => true,
+            Descriptor::Dir(_) | Descriptor::VirtualDir(_) => false,
         }
     }
 
     pub fn is_dir(&self) -> bool {
         match self {
-            Descriptor::File(_) => false,
-            Descriptor::Dir(_) => true,
+            Descriptor::File(_) | Descriptor::VirtualFile(_) => false,
+            Descriptor::Dir(_) | Descriptor::VirtualDir(_) => true,
         }
     }
 }
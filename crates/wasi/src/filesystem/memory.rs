@@ -0,0 +1,705 @@
+use super::vfs::{datetime_from, resolve_timestamp, VirtualDir, VirtualFile, VirtualNode};
+use super::FsResult;
+use crate::bindings::filesystem::types::{
+    DescriptorStat, DescriptorType, DirectoryEntry, ErrorCode, NewTimestamp,
+};
+use cap_std::ambient_authority;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::path::{Component, Path};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+/// A [`VirtualDir`] which keeps its contents in memory.
+///
+/// A `MemoryDir` can start out empty, be seeded with files through
+/// [`MemoryDir::from_files`], or be layered over a host directory with
+/// [`MemoryDir::overlay`]. Changes made by the guest are visible to the host
+/// through methods such as [`MemoryDir::read_file`] as long as the host keeps
+/// an `Arc` of the directory it preopened.
+///
+/// By default file contents may grow until memory is exhausted. When running
+/// untrusted guests, [`MemoryDir::set_max_file_size`] and
+/// [`MemoryDir::set_max_total_size`] should be used to bound how much memory
+/// a guest can consume through the directory.
+///
+/// ```
+/// use std::sync::Arc;
+/// use wasmtime_wasi::{DirPerms, FilePerms, MemoryDir, WasiCtxBuilder};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let root = Arc::new(MemoryDir::from_files([
+///     ("config.toml", b"verbose = true".to_vec()),
+///     ("data/input.txt", b"hello".to_vec()),
+/// ])?);
+///
+/// let mut wasi = WasiCtxBuilder::new();
+/// wasi.preopened_virtual_dir(root.clone(), "/", DirPerms::all(), FilePerms::all());
+///
+/// // ... run the guest, then inspect what it wrote ...
+/// # Ok(())
+/// # }
+/// ```
+pub struct MemoryDir {
+    state: Mutex<DirState>,
+    usage: Arc<Usage>,
+}
+
+/// The size limits of a tree of [`MemoryDir`]s along with the number of
+/// bytes its files currently hold, shared by all of the tree's nodes.
+struct Usage {
+    max_file_size: AtomicU64,
+    max_total_size: AtomicU64,
+    total_size: AtomicU64,
+}
+
+impl Usage {
+    fn new() -> Usage {
+        Usage {
+            max_file_size: AtomicU64::new(u64::MAX),
+            max_total_size: AtomicU64::new(u64::MAX),
+            total_size: AtomicU64::new(0),
+        }
+    }
+
+    /// Accounts for a file's contents growing or shrinking from `old` to
+    /// `new` bytes, failing if that would exceed a limit.
+    fn resize(&self, old: usize, new: usize) -> FsResult<()> {
+        if new <= old {
+            self.release(old - new);
+            return Ok(());
+        }
+        if new as u64 > self.max_file_size.load(Ordering::Relaxed) {
+            return Err(ErrorCode::FileTooLarge.into());
+        }
+        let max_total_size = self.max_total_size.load(Ordering::Relaxed);
+        let grow = (new - old) as u64;
+        self.total_size
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+                total.checked_add(grow).filter(|t| *t <= max_total_size)
+            })
+            .map_err(|_| ErrorCode::InsufficientMemory)?;
+        Ok(())
+    }
+
+    fn release(&self, bytes: usize) {
+        self.total_size.fetch_sub(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+struct DirState {
+    entries: BTreeMap<String, MemoryNode>,
+    /// The host directory this directory is layered over, whose entries
+    /// haven't yet been copied into `entries`.
+    lower: Option<cap_std::fs::Dir>,
+    times: Times,
+}
+
+#[derive(Clone)]
+enum MemoryNode {
+    File(Arc<MemoryFile>),
+    Dir(Arc<MemoryDir>),
+}
+
+impl MemoryNode {
+    fn to_virtual(&self) -> VirtualNode {
+        match self {
+            MemoryNode::File(f) => VirtualNode::File(f.clone()),
+            MemoryNode::Dir(d) => VirtualNode::Dir(d.clone()),
+        }
+    }
+
+    fn descriptor_type(&self) -> DescriptorType {
+        match self {
+            MemoryNode::File(_) => DescriptorType::RegularFile,
+            MemoryNode::Dir(_) => DescriptorType::Directory,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Times {
+    accessed: SystemTime,
+    modified: SystemTime,
+}
+
+impl Times {
+    fn now() -> Times {
+        let now = SystemTime::now();
+        Times {
+            accessed: now,
+            modified: now,
+        }
+    }
+
+    fn from_metadata(meta: &cap_std::fs::Metadata) -> Times {
+        let now = Times::now();
+        Times {
+            accessed: meta.accessed().map_or(now.accessed, |t| t.into_std()),
+            modified: meta.modified().map_or(now.modified, |t| t.into_std()),
+        }
+    }
+
+    fn set(&mut self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        if let Some(t) = resolve_timestamp(atim)? {
+            self.accessed = t;
+        }
+        if let Some(t) = resolve_timestamp(mtim)? {
+            self.modified = t;
+        }
+        Ok(())
+    }
+
+    fn stat(&self, type_: DescriptorType, size: u64) -> DescriptorStat {
+        DescriptorStat {
+            type_,
+            link_count: 1,
+            size,
+            data_access_timestamp: datetime_from(self.accessed),
+            data_modification_timestamp: datetime_from(self.modified),
+            status_change_timestamp: datetime_from(self.modified),
+        }
+    }
+}
+
+impl MemoryDir {
+    /// Creates a new empty directory.
+    pub fn new() -> MemoryDir {
+        MemoryDir::with_lower(None, Times::now(), Arc::new(Usage::new()))
+    }
+
+    /// Creates a new directory containing `files`.
+    ///
+    /// Each path is relative to the new directory and parent directories are
+    /// created as needed. This can be used to seed a directory from an
+    /// archive by iterating over its entries.
+    pub fn from_files<P, C>(files: impl IntoIterator<Item = (P, C)>) -> io::Result<MemoryDir>
+    where
+        P: AsRef<Path>,
+        C: Into<Vec<u8>>,
+    {
+        let dir = MemoryDir::new();
+        for (path, contents) in files {
+            dir.insert_file(path, contents)?;
+        }
+        Ok(dir)
+    }
+
+    /// Creates a new directory layered copy-on-write over the host directory
+    /// at `host_path`.
+    ///
+    /// The host directory is only ever read from: its contents are copied
+    /// into memory the first time they are accessed, and all changes are
+    /// made to the in-memory copy. Entries of the host directory which are
+    /// neither regular files nor directories, such as symlinks, are not
+    /// visible.
+    pub fn overlay(host_path: impl AsRef<Path>) -> io::Result<MemoryDir> {
+        let dir = cap_std::fs::Dir::open_ambient_dir(host_path, ambient_authority())?;
+        let times = Times::from_metadata(&dir.dir_metadata()?);
+        Ok(MemoryDir::with_lower(
+            Some(dir),
+            times,
+            Arc::new(Usage::new()),
+        ))
+    }
+
+    fn with_lower(lower: Option<cap_std::fs::Dir>, times: Times, usage: Arc<Usage>) -> MemoryDir {
+        MemoryDir {
+            state: Mutex::new(DirState {
+                entries: BTreeMap::new(),
+                lower,
+                times,
+            }),
+            usage,
+        }
+    }
+
+    /// Creates a new empty subdirectory sharing this directory's limits.
+    fn new_child(&self) -> MemoryDir {
+        MemoryDir::with_lower(None, Times::now(), self.usage.clone())
+    }
+
+    /// Sets the maximum size in bytes of any one file.
+    ///
+    /// Limits are shared by the whole tree of directories this directory
+    /// belongs to. Guest writes or truncations which would grow a file past
+    /// this size fail with `file-too-large`. Files which are already larger
+    /// are left as-is.
+    pub fn set_max_file_size(&self, bytes: u64) {
+        self.usage.max_file_size.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Sets the maximum number of bytes which all files together may hold.
+    ///
+    /// Limits are shared by the whole tree of directories this directory
+    /// belongs to, including the contents of files copied in from the host
+    /// directory of an [overlay](MemoryDir::overlay). Guest writes which
+    /// would exceed this limit fail with `insufficient-memory`.
+    pub fn set_max_total_size(&self, bytes: u64) {
+        self.usage.max_total_size.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Creates a file at `path` containing `contents`, replacing any existing
+    /// file and creating parent directories as needed.
+    pub fn insert_file(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl Into<Vec<u8>>,
+    ) -> io::Result<()> {
+        let contents = contents.into();
+        self.with_parent(&components(path.as_ref())?, true, |dir, name| {
+            // Loading the state copies in the entries of the host directory
+            // this is layered over, if any, so this also sees directories of
+            // the lower layer.
+            let mut state = dir.state()?;
+            if let Some(MemoryNode::Dir(_)) = state.entries.get(name) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("`{name}` is a directory"),
+                ));
+            }
+            let file = MemoryFile::with_data(contents, dir.usage.clone()).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::OutOfMemory,
+                    format!("`{name}` exceeds the size limits of this directory"),
+                )
+            })?;
+            state
+                .entries
+                .insert(name.to_string(), MemoryNode::File(Arc::new(file)));
+            state.times.modified = SystemTime::now();
+            Ok(())
+        })
+    }
+
+    /// Creates a directory at `path` along with any missing parents.
+    pub fn create_dir_all(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let components = components(path.as_ref())?;
+        if components.is_empty() {
+            return Ok(());
+        }
+        self.with_parent(&components, true, |dir, name| {
+            dir.child_dir(name, true)?;
+            Ok(())
+        })
+    }
+
+    /// Returns the contents of the file at `path`.
+    pub fn read_file(&self, path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        self.with_parent(&components(path.as_ref())?, false, |dir, name| {
+            match dir.state()?.entries.get(name) {
+                Some(MemoryNode::File(f)) => Ok(f.state()?.data.clone()),
+                Some(MemoryNode::Dir(_)) => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("`{name}` is a directory"),
+                )),
+                None => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("`{name}` not found"),
+                )),
+            }
+        })
+    }
+
+    /// Locks this directory's state, first copying in the entries of the
+    /// host directory it's layered over if that hasn't happened yet.
+    fn state(&self) -> io::Result<MutexGuard<'_, DirState>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(lower) = &state.lower {
+            let mut entries = BTreeMap::new();
+            for entry in lower.entries()? {
+                let entry = entry?;
+                let Ok(name) = entry.file_name().into_string() else {
+                    continue;
+                };
+                let file_type = entry.file_type()?;
+                let node = if file_type.is_dir() {
+                    let dir = entry.open_dir()?;
+                    let times = Times::from_metadata(&dir.dir_metadata()?);
+                    MemoryNode::Dir(Arc::new(MemoryDir::with_lower(
+                        Some(dir),
+                        times,
+                        self.usage.clone(),
+                    )))
+                } else if file_type.is_file() {
+                    MemoryNode::File(Arc::new(MemoryFile::with_lower(
+                        entry.open()?,
+                        self.usage.clone(),
+                    )?))
+                } else {
+                    continue;
+                };
+                entries.insert(name, node);
+            }
+            state.entries = entries;
+            state.lower = None;
+        }
+        Ok(state)
+    }
+
+    /// Returns the subdirectory `name`, creating it if `create` is set.
+    fn child_dir(&self, name: &str, create: bool) -> io::Result<Arc<MemoryDir>> {
+        let mut state = self.state()?;
+        match state.entries.get(name) {
+            Some(MemoryNode::Dir(d)) => Ok(d.clone()),
+            Some(MemoryNode::File(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("`{name}` is not a directory"),
+            )),
+            None if create => {
+                let dir = Arc::new(self.new_child());
+                state
+                    .entries
+                    .insert(name.to_string(), MemoryNode::Dir(dir.clone()));
+                state.times.modified = SystemTime::now();
+                Ok(dir)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("`{name}` not found"),
+            )),
+        }
+    }
+
+    /// Calls `f` with the directory containing the last of `components` and
+    /// the name of that component.
+    fn with_parent<R>(
+        &self,
+        components: &[String],
+        create: bool,
+        f: impl FnOnce(&MemoryDir, &str) -> io::Result<R>,
+    ) -> io::Result<R> {
+        match components {
+            [] => Err(io::Error::new(io::ErrorKind::InvalidInput, "empty path")),
+            [name] => f(self, name),
+            [first, rest @ ..] => self.child_dir(first, create)?.with_parent(rest, create, f),
+        }
+    }
+
+    /// Returns whether `target` is this directory or one of its descendants.
+    ///
+    /// Directories which haven't been loaded from the host yet can't contain
+    /// `target`, since it was reached by a lookup which would have loaded
+    /// them, so this doesn't load anything.
+    fn contains(&self, target: &MemoryDir) -> bool {
+        if std::ptr::eq(self, target) {
+            return true;
+        }
+        let children = self
+            .state
+            .lock()
+            .unwrap()
+            .entries
+            .values()
+            .filter_map(|node| match node {
+                MemoryNode::Dir(d) => Some(d.clone()),
+                MemoryNode::File(_) => None,
+            })
+            .collect::<Vec<_>>();
+        children.iter().any(|d| d.contains(target))
+    }
+}
+
+impl Default for MemoryDir {
+    fn default() -> MemoryDir {
+        MemoryDir::new()
+    }
+}
+
+/// Splits a host-provided path into its names, rejecting any which could
+/// refer outside of the directory it's relative to.
+fn components(path: &Path) -> io::Result<Vec<String>> {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| match c {
+            Component::Normal(name) => name.to_str().map(str::to_string).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "path is not valid UTF-8")
+            }),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "path `{}` must be relative and not contain `..`",
+                    path.display()
+                ),
+            )),
+        })
+        .collect()
+}
+
+impl VirtualDir for MemoryDir {
+    fn lookup(&self, name: &str) -> FsResult<VirtualNode> {
+        match self.state()?.entries.get(name) {
+            Some(node) => Ok(node.to_virtual()),
+            None => Err(ErrorCode::NoEntry.into()),
+        }
+    }
+
+    fn entries(&self) -> FsResult<Vec<DirectoryEntry>> {
+        Ok(self
+            .state()?
+            .entries
+            .iter()
+            .map(|(name, node)| DirectoryEntry {
+                type_: node.descriptor_type(),
+                name: name.clone(),
+            })
+            .collect())
+    }
+
+    fn stat(&self) -> FsResult<DescriptorStat> {
+        let state = self.state()?;
+        Ok(state.times.stat(DescriptorType::Directory, 0))
+    }
+
+    fn create_file(&self, name: &str) -> FsResult<Arc<dyn VirtualFile>> {
+        let mut state = self.state()?;
+        if state.entries.contains_key(name) {
+            return Err(ErrorCode::Exist.into());
+        }
+        let file = Arc::new(MemoryFile::with_data(Vec::new(), self.usage.clone())?);
+        state
+            .entries
+            .insert(name.to_string(), MemoryNode::File(file.clone()));
+        state.times.modified = SystemTime::now();
+        Ok(file)
+    }
+
+    fn create_dir(&self, name: &str) -> FsResult<()> {
+        let mut state = self.state()?;
+        if state.entries.contains_key(name) {
+            return Err(ErrorCode::Exist.into());
+        }
+        state.entries.insert(
+            name.to_string(),
+            MemoryNode::Dir(Arc::new(self.new_child())),
+        );
+        state.times.modified = SystemTime::now();
+        Ok(())
+    }
+
+    fn remove(&self, name: &str) -> FsResult<()> {
+        let mut state = self.state()?;
+        if state.entries.remove(name).is_none() {
+            return Err(ErrorCode::NoEntry.into());
+        }
+        state.times.modified = SystemTime::now();
+        Ok(())
+    }
+
+    fn rename(&self, name: &str, new_dir: &dyn VirtualDir, new_name: &str) -> FsResult<()> {
+        let new_dir = new_dir
+            .as_any()
+            .downcast_ref::<MemoryDir>()
+            .filter(|d| Arc::ptr_eq(&self.usage, &d.usage))
+            .ok_or(ErrorCode::CrossDevice)?;
+
+        let source = self
+            .state()?
+            .entries
+            .get(name)
+            .cloned()
+            .ok_or(ErrorCode::NoEntry)?;
+        let target = new_dir.state()?.entries.get(new_name).cloned();
+        match (&source, &target) {
+            (MemoryNode::File(a), Some(MemoryNode::File(b))) if Arc::ptr_eq(a, b) => return Ok(()),
+            (MemoryNode::Dir(a), Some(MemoryNode::Dir(b))) if Arc::ptr_eq(a, b) => return Ok(()),
+            (MemoryNode::File(_), Some(MemoryNode::Dir(_))) => {
+                return Err(ErrorCode::IsDirectory.into());
+            }
+            (MemoryNode::Dir(_), Some(MemoryNode::File(_))) => {
+                return Err(ErrorCode::NotDirectory.into());
+            }
+            (MemoryNode::Dir(_), Some(MemoryNode::Dir(d))) if !d.state()?.entries.is_empty() => {
+                return Err(ErrorCode::NotEmpty.into());
+            }
+            _ => {}
+        }
+        if let MemoryNode::Dir(d) = &source {
+            if d.contains(new_dir) {
+                return Err(ErrorCode::Invalid.into());
+            }
+        }
+
+        // Both directories have been loaded above, so their states can be
+        // locked directly. Lock them in a consistent order to avoid deadlocks.
+        let now = SystemTime::now();
+        if std::ptr::eq(self, new_dir) {
+            let mut state = self.state.lock().unwrap();
+            let node = state.entries.remove(name).ok_or(ErrorCode::NoEntry)?;
+            state.entries.insert(new_name.to_string(), node);
+            state.times.modified = now;
+        } else {
+            let self_first = (self as *const MemoryDir) < (new_dir as *const MemoryDir);
+            let (first, second) = if self_first {
+                (self, new_dir)
+            } else {
+                (new_dir, self)
+            };
+            let mut first = first.state.lock().unwrap();
+            let mut second = second.state.lock().unwrap();
+            let (old_state, new_state) = if self_first {
+                (&mut *first, &mut *second)
+            } else {
+                (&mut *second, &mut *first)
+            };
+            let node = old_state.entries.remove(name).ok_or(ErrorCode::NoEntry)?;
+            new_state.entries.insert(new_name.to_string(), node);
+            old_state.times.modified = now;
+            new_state.times.modified = now;
+        }
+        Ok(())
+    }
+
+    fn set_times(&self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        self.state()?.times.set(atim, mtim)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A regular file of a [`MemoryDir`].
+struct MemoryFile {
+    state: Mutex<FileState>,
+    usage: Arc<Usage>,
+}
+
+struct FileState {
+    data: Vec<u8>,
+    /// The host file this file is layered over, whose contents haven't yet
+    /// been copied into `data`.
+    lower: Option<cap_std::fs::File>,
+    times: Times,
+}
+
+impl MemoryFile {
+    fn with_data(data: Vec<u8>, usage: Arc<Usage>) -> FsResult<MemoryFile> {
+        usage.resize(0, data.len())?;
+        Ok(MemoryFile {
+            state: Mutex::new(FileState {
+                data,
+                lower: None,
+                times: Times::now(),
+            }),
+            usage,
+        })
+    }
+
+    fn with_lower(lower: cap_std::fs::File, usage: Arc<Usage>) -> io::Result<MemoryFile> {
+        let times = Times::from_metadata(&lower.metadata()?);
+        Ok(MemoryFile {
+            state: Mutex::new(FileState {
+                data: Vec::new(),
+                lower: Some(lower),
+                times,
+            }),
+            usage,
+        })
+    }
+
+    /// Locks this file's state, first copying in the contents of the host
+    /// file it's layered over if that hasn't happened yet.
+    fn state(&self) -> io::Result<MutexGuard<'_, FileState>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(lower) = &mut state.lower {
+            let mut data = Vec::new();
+            lower.read_to_end(&mut data)?;
+            self.usage.resize(0, data.len()).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::OutOfMemory,
+                    "host file exceeds the size limits of this directory",
+                )
+            })?;
+            state.data = data;
+            state.lower = None;
+        }
+        Ok(state)
+    }
+
+    /// Resizes `state`'s contents to `len` bytes, zero-filling any new bytes,
+    /// if that is within the directory's limits and memory can be allocated.
+    fn resize(&self, state: &mut FileState, len: usize) -> FsResult<()> {
+        let old = state.data.len();
+        self.usage.resize(old, len)?;
+        if len > old && state.data.try_reserve(len - old).is_err() {
+            self.usage.release(len - old);
+            return Err(ErrorCode::InsufficientMemory.into());
+        }
+        state.data.resize(len, 0);
+        Ok(())
+    }
+}
+
+impl Drop for MemoryFile {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap();
+        self.usage.release(state.data.len());
+    }
+}
+
+impl VirtualFile for MemoryFile {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> FsResult<usize> {
+        let state = self.state()?;
+        let start = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(state.data.len());
+        let n = buf.len().min(state.data.len() - start);
+        buf[..n].copy_from_slice(&state.data[start..][..n]);
+        Ok(n)
+    }
+
+    fn write_at(&self, buf: &[u8], offset: u64) -> FsResult<usize> {
+        // Like `pwrite`, an empty write doesn't extend the file.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut state = self.state()?;
+        let start = usize::try_from(offset).map_err(|_| ErrorCode::FileTooLarge)?;
+        let end = start
+            .checked_add(buf.len())
+            .ok_or(ErrorCode::FileTooLarge)?;
+        if state.data.len() < end {
+            self.resize(&mut state, end)?;
+        }
+        state.data[start..end].copy_from_slice(buf);
+        state.times.modified = SystemTime::now();
+        Ok(buf.len())
+    }
+
+    fn append(&self, buf: &[u8]) -> FsResult<usize> {
+        let mut state = self.state()?;
+        let start = state.data.len();
+        let end = start
+            .checked_add(buf.len())
+            .ok_or(ErrorCode::FileTooLarge)?;
+        self.resize(&mut state, end)?;
+        state.data[start..].copy_from_slice(buf);
+        state.times.modified = SystemTime::now();
+        Ok(buf.len())
+    }
+
+    fn set_size(&self, size: u64) -> FsResult<()> {
+        let mut state = self.state()?;
+        let size = usize::try_from(size).map_err(|_| ErrorCode::FileTooLarge)?;
+        self.resize(&mut state, size)?;
+        if size < state.data.capacity() / 2 {
+            state.data.shrink_to_fit();
+        }
+        state.times.modified = SystemTime::now();
+        Ok(())
+    }
+
+    fn stat(&self) -> FsResult<DescriptorStat> {
+        let state = self.state()?;
+        Ok(state
+            .times
+            .stat(DescriptorType::RegularFile, state.data.len() as u64))
+    }
+
+    fn set_times(&self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        self.state()?.times.set(atim, mtim)
+    }
+}
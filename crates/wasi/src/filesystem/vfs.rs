@@ -0,0 +1,688 @@
+//! Support for filesystems implemented by the embedder rather than the host
+//! operating system.
+//!
+//! A [`VirtualDir`] can be preopened with
+//! [`WasiCtxBuilder::preopened_virtual_dir`](crate::WasiCtxBuilder::preopened_virtual_dir)
+//! in place of a host directory. Guest paths are resolved against it one
+//! component at a time, so implementations only deal with single names and
+//! never see `.`, `..` or separators. Paths can never escape the preopened
+//! directory.
+
+use super::{Descriptor, DirPerms, FilePerms, FsResult, OpenMode, ReaddirIterator};
+use crate::bindings::filesystem::types::{
+    DescriptorFlags, DescriptorStat, DirectoryEntry, ErrorCode, NewTimestamp, OpenFlags, PathFlags,
+};
+use crate::{InputStream, OutputStream, Pollable, StreamError, StreamResult};
+use bytes::{Bytes, BytesMut};
+use std::any::Any;
+use std::sync::Arc;
+
+/// A directory of a filesystem implemented by the embedder.
+///
+/// All methods are called directly from the async context executing the
+/// guest, so implementations should avoid blocking for long periods of time.
+pub trait VirtualDir: Send + Sync + 'static {
+    /// Returns the entry of this directory named `name`, or
+    /// [`ErrorCode::NoEntry`] if there is none.
+    fn lookup(&self, name: &str) -> FsResult<VirtualNode>;
+
+    /// Returns all entries of this directory, excluding `.` and `..`.
+    fn entries(&self) -> FsResult<Vec<DirectoryEntry>>;
+
+    /// Returns metadata for this directory.
+    fn stat(&self) -> FsResult<DescriptorStat>;
+
+    /// Creates a new empty file named `name` in this directory, failing with
+    /// [`ErrorCode::Exist`] if an entry of that name already exists.
+    fn create_file(&self, name: &str) -> FsResult<Arc<dyn VirtualFile>>;
+
+    /// Creates a new empty directory named `name` in this directory, failing
+    /// with [`ErrorCode::Exist`] if an entry of that name already exists.
+    fn create_dir(&self, name: &str) -> FsResult<()>;
+
+    /// Removes the entry named `name` from this directory.
+    ///
+    /// Callers have already checked that the entry exists, is of the
+    /// expected type and, for directories, is empty.
+    fn remove(&self, name: &str) -> FsResult<()>;
+
+    /// Moves the entry named `name` in this directory to `new_name` in
+    /// `new_dir`, replacing any existing entry there following POSIX
+    /// `rename` semantics.
+    ///
+    /// Implementations should fail with [`ErrorCode::CrossDevice`] if
+    /// `new_dir` belongs to a different filesystem, which can be detected
+    /// with [`VirtualDir::as_any`].
+    fn rename(&self, name: &str, new_dir: &dyn VirtualDir, new_name: &str) -> FsResult<()>;
+
+    /// Updates the timestamps of this directory.
+    fn set_times(&self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        let _ = (atim, mtim);
+        Err(ErrorCode::Unsupported.into())
+    }
+
+    /// Returns `self` as [`Any`], used to downcast the destination of
+    /// [`VirtualDir::rename`].
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// A regular file of a filesystem implemented by the embedder.
+///
+/// All methods are called directly from the async context executing the
+/// guest, so implementations should avoid blocking for long periods of time.
+pub trait VirtualFile: Send + Sync + 'static {
+    /// Reads from the file at `offset` into `buf`, returning the number of
+    /// bytes read. Zero is returned at the end of the file.
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> FsResult<usize>;
+
+    /// Writes `buf` to the file at `offset`, extending the file if needed and
+    /// returning the number of bytes written.
+    fn write_at(&self, buf: &[u8], offset: u64) -> FsResult<usize>;
+
+    /// Writes `buf` to the end of the file, returning the number of bytes
+    /// written.
+    fn append(&self, buf: &[u8]) -> FsResult<usize>;
+
+    /// Truncates or extends the file to `size` bytes.
+    fn set_size(&self, size: u64) -> FsResult<()>;
+
+    /// Returns metadata for this file.
+    fn stat(&self) -> FsResult<DescriptorStat>;
+
+    /// Updates the timestamps of this file.
+    fn set_times(&self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        let _ = (atim, mtim);
+        Err(ErrorCode::Unsupported.into())
+    }
+}
+
+/// An entry of a [`VirtualDir`].
+///
+/// Entries are identified by the address of their `Arc`, which is what
+/// `is-same-object` and `metadata-hash` report, so implementations should
+/// return the same `Arc` each time an entry is looked up.
+#[derive(Clone)]
+pub enum VirtualNode {
+    /// A regular file.
+    File(Arc<dyn VirtualFile>),
+    /// A directory.
+    Dir(Arc<dyn VirtualDir>),
+}
+
+impl VirtualNode {
+    fn stat(&self) -> FsResult<DescriptorStat> {
+        match self {
+            VirtualNode::File(f) => f.stat(),
+            VirtualNode::Dir(d) => d.stat(),
+        }
+    }
+
+    /// A value uniquely identifying this node for as long as it's alive.
+    pub(crate) fn id(&self) -> u64 {
+        let ptr = match self {
+            VirtualNode::File(f) => Arc::as_ptr(f).cast::<()>(),
+            VirtualNode::Dir(d) => Arc::as_ptr(d).cast::<()>(),
+        };
+        ptr as usize as u64
+    }
+}
+
+/// The result of resolving a path relative to a [`VirtualDir`].
+enum Resolved {
+    /// The path named a directory directly, via a trailing `.` or `..`.
+    Dir(Arc<dyn VirtualDir>),
+    /// The path named the entry `name` of `parent`, which may not exist.
+    Entry {
+        parent: Arc<dyn VirtualDir>,
+        name: String,
+        /// Whether the path had a trailing slash, requiring the entry to be
+        /// a directory.
+        must_be_dir: bool,
+    },
+}
+
+impl Resolved {
+    fn lookup(&self) -> FsResult<VirtualNode> {
+        match self {
+            Resolved::Dir(d) => Ok(VirtualNode::Dir(d.clone())),
+            Resolved::Entry {
+                parent,
+                name,
+                must_be_dir,
+            } => {
+                let node = parent.lookup(name)?;
+                if *must_be_dir && matches!(node, VirtualNode::File(_)) {
+                    return Err(ErrorCode::NotDirectory.into());
+                }
+                Ok(node)
+            }
+        }
+    }
+}
+
+/// Resolves `path` relative to `root`, following the same sandboxing rules as
+/// host directories: absolute paths and paths which would escape `root` are
+/// rejected.
+fn resolve(root: &Arc<dyn VirtualDir>, path: &str) -> FsResult<Resolved> {
+    if path.is_empty() {
+        return Err(ErrorCode::NoEntry.into());
+    }
+    if path.starts_with('/') {
+        return Err(ErrorCode::NotPermitted.into());
+    }
+    if path.contains('\0') {
+        return Err(ErrorCode::Invalid.into());
+    }
+    let must_be_dir = path.ends_with('/');
+    let components = path
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<_>>();
+    let (last, init) = components
+        .split_last()
+        .expect("relative non-empty path has a component");
+
+    let mut stack = vec![root.clone()];
+    let step = |stack: &mut Vec<Arc<dyn VirtualDir>>, component: &str| -> FsResult<()> {
+        match component {
+            "." => {}
+            ".." => {
+                if stack.len() == 1 {
+                    return Err(ErrorCode::NotPermitted.into());
+                }
+                stack.pop();
+            }
+            name => match stack.last().unwrap().lookup(name)? {
+                VirtualNode::Dir(d) => stack.push(d),
+                VirtualNode::File(_) => return Err(ErrorCode::NotDirectory.into()),
+            },
+        }
+        Ok(())
+    };
+    for component in init {
+        step(&mut stack, component)?;
+    }
+    match *last {
+        "." | ".." => {
+            step(&mut stack, last)?;
+            Ok(Resolved::Dir(stack.pop().unwrap()))
+        }
+        name => Ok(Resolved::Entry {
+            parent: stack.pop().unwrap(),
+            name: name.to_string(),
+            must_be_dir,
+        }),
+    }
+}
+
+/// A [`VirtualDir`] opened as a descriptor.
+#[derive(Clone)]
+pub struct OpenVirtualDir {
+    /// The directory this struct is mediating access to.
+    pub dir: Arc<dyn VirtualDir>,
+    /// Permissions to enforce on access to this directory, and any
+    /// directories opened under it.
+    pub perms: DirPerms,
+    /// Permissions to enforce on any files opened under this directory.
+    pub file_perms: FilePerms,
+    /// The mode the directory was opened under.
+    pub open_mode: OpenMode,
+}
+
+impl OpenVirtualDir {
+    pub(crate) fn new(
+        dir: Arc<dyn VirtualDir>,
+        perms: DirPerms,
+        file_perms: FilePerms,
+        open_mode: OpenMode,
+    ) -> Self {
+        OpenVirtualDir {
+            dir,
+            perms,
+            file_perms,
+            open_mode,
+        }
+    }
+
+    fn require(&self, perms: DirPerms) -> FsResult<()> {
+        if self.perms.contains(perms) {
+            Ok(())
+        } else {
+            Err(ErrorCode::NotPermitted.into())
+        }
+    }
+
+    pub(crate) fn node(&self) -> VirtualNode {
+        VirtualNode::Dir(self.dir.clone())
+    }
+
+    pub(crate) fn get_flags(&self) -> DescriptorFlags {
+        let mut flags = DescriptorFlags::empty();
+        if self.open_mode.contains(OpenMode::READ) {
+            flags |= DescriptorFlags::READ;
+        }
+        if self.open_mode.contains(OpenMode::WRITE) {
+            flags |= DescriptorFlags::MUTATE_DIRECTORY;
+        }
+        flags
+    }
+
+    pub(crate) fn set_times(&self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        self.require(DirPerms::MUTATE)?;
+        self.dir.set_times(atim, mtim)
+    }
+
+    pub(crate) fn read_directory(&self) -> FsResult<ReaddirIterator> {
+        self.require(DirPerms::READ)?;
+        let entries = self.dir.entries()?;
+        Ok(ReaddirIterator::new(entries.into_iter().map(Ok)))
+    }
+
+    pub(crate) fn create_directory_at(&self, path: &str) -> FsResult<()> {
+        self.require(DirPerms::MUTATE)?;
+        match resolve(&self.dir, path)? {
+            Resolved::Dir(_) => Err(ErrorCode::Exist.into()),
+            Resolved::Entry { parent, name, .. } => parent.create_dir(&name),
+        }
+    }
+
+    pub(crate) fn stat(&self) -> FsResult<DescriptorStat> {
+        self.dir.stat()
+    }
+
+    /// Returns the node at `path`, for operations such as `stat-at` which
+    /// don't modify it.
+    ///
+    /// There are no symlinks in virtual filesystems, so path flags are
+    /// irrelevant.
+    pub(crate) fn lookup_at(&self, path: &str) -> FsResult<VirtualNode> {
+        resolve(&self.dir, path)?.lookup()
+    }
+
+    pub(crate) fn stat_at(&self, path: &str) -> FsResult<DescriptorStat> {
+        self.require(DirPerms::READ)?;
+        self.lookup_at(path)?.stat()
+    }
+
+    pub(crate) fn set_times_at(
+        &self,
+        path: &str,
+        atim: NewTimestamp,
+        mtim: NewTimestamp,
+    ) -> FsResult<()> {
+        self.require(DirPerms::MUTATE)?;
+        match self.lookup_at(path)? {
+            VirtualNode::File(f) => f.set_times(atim, mtim),
+            VirtualNode::Dir(d) => d.set_times(atim, mtim),
+        }
+    }
+
+    pub(crate) fn open_at(
+        &self,
+        path_flags: PathFlags,
+        path: &str,
+        oflags: OpenFlags,
+        flags: DescriptorFlags,
+    ) -> FsResult<Descriptor> {
+        let _ = path_flags;
+        self.require(DirPerms::READ)?;
+
+        let create = oflags.contains(OpenFlags::CREATE);
+        let truncate = oflags.contains(OpenFlags::TRUNCATE);
+        let write = flags.contains(DescriptorFlags::WRITE);
+        if !self.perms.contains(DirPerms::MUTATE) && (create || truncate || write) {
+            return Err(ErrorCode::NotPermitted.into());
+        }
+        if flags.contains(DescriptorFlags::FILE_INTEGRITY_SYNC)
+            || flags.contains(DescriptorFlags::DATA_INTEGRITY_SYNC)
+            || flags.contains(DescriptorFlags::REQUESTED_WRITE_SYNC)
+        {
+            return Err(ErrorCode::Unsupported.into());
+        }
+        if oflags.contains(OpenFlags::DIRECTORY)
+            && (create || truncate || oflags.contains(OpenFlags::EXCLUSIVE))
+        {
+            return Err(ErrorCode::Invalid.into());
+        }
+
+        let mut open_mode = OpenMode::empty();
+        if flags.contains(DescriptorFlags::READ) || !write {
+            open_mode |= OpenMode::READ;
+        }
+        if write || create {
+            open_mode |= OpenMode::WRITE;
+        }
+        if !self.file_perms.contains(FilePerms::WRITE) && open_mode.contains(OpenMode::WRITE) {
+            return Err(ErrorCode::NotPermitted.into());
+        }
+
+        let resolved = resolve(&self.dir, path)?;
+        let node = match resolved.lookup() {
+            Ok(_) if create && oflags.contains(OpenFlags::EXCLUSIVE) => {
+                return Err(ErrorCode::Exist.into());
+            }
+            Ok(node) => node,
+            Err(e) if create && e.downcast_ref() == Some(&ErrorCode::NoEntry) => match resolved {
+                Resolved::Entry {
+                    must_be_dir: true, ..
+                } => return Err(ErrorCode::IsDirectory.into()),
+                Resolved::Entry { parent, name, .. } => {
+                    VirtualNode::File(parent.create_file(&name)?)
+                }
+                Resolved::Dir(_) => return Err(e),
+            },
+            Err(e) => return Err(e),
+        };
+
+        match node {
+            VirtualNode::Dir(dir) => {
+                if write || truncate || create {
+                    return Err(ErrorCode::IsDirectory.into());
+                }
+                Ok(Descriptor::VirtualDir(OpenVirtualDir::new(
+                    dir,
+                    self.perms,
+                    self.file_perms,
+                    open_mode,
+                )))
+            }
+            VirtualNode::File(file) => {
+                if oflags.contains(OpenFlags::DIRECTORY) {
+                    return Err(ErrorCode::NotDirectory.into());
+                }
+                if truncate {
+                    file.set_size(0)?;
+                }
+                Ok(Descriptor::VirtualFile(OpenVirtualFile::new(
+                    file,
+                    self.file_perms,
+                    open_mode,
+                )))
+            }
+        }
+    }
+
+    pub(crate) fn readlink_at(&self, path: &str) -> FsResult<String> {
+        self.require(DirPerms::READ)?;
+        // There are no symlinks, so whatever is at `path` isn't one.
+        self.lookup_at(path)?;
+        Err(ErrorCode::Invalid.into())
+    }
+
+    pub(crate) fn remove_directory_at(&self, path: &str) -> FsResult<()> {
+        self.require(DirPerms::MUTATE)?;
+        let resolved = resolve(&self.dir, path)?;
+        match (resolved.lookup()?, resolved) {
+            (VirtualNode::File(_), _) => Err(ErrorCode::NotDirectory.into()),
+            (VirtualNode::Dir(dir), _) if !dir.entries()?.is_empty() => {
+                Err(ErrorCode::NotEmpty.into())
+            }
+            (VirtualNode::Dir(_), Resolved::Entry { parent, name, .. }) => parent.remove(&name),
+            (VirtualNode::Dir(_), Resolved::Dir(_)) => Err(ErrorCode::Invalid.into()),
+        }
+    }
+
+    pub(crate) fn rename_at(
+        &self,
+        old_path: &str,
+        new_dir: &OpenVirtualDir,
+        new_path: &str,
+    ) -> FsResult<()> {
+        self.require(DirPerms::MUTATE)?;
+        new_dir.require(DirPerms::MUTATE)?;
+        let old = resolve(&self.dir, old_path)?;
+        let node = old.lookup()?;
+        let Resolved::Entry { parent, name, .. } = old else {
+            return Err(ErrorCode::Busy.into());
+        };
+        let Resolved::Entry {
+            parent: new_parent,
+            name: new_name,
+            must_be_dir,
+        } = resolve(&new_dir.dir, new_path)?
+        else {
+            return Err(ErrorCode::Busy.into());
+        };
+        if must_be_dir && matches!(node, VirtualNode::File(_)) {
+            return Err(ErrorCode::NotDirectory.into());
+        }
+        parent.rename(&name, &*new_parent, &new_name)
+    }
+
+    pub(crate) fn unlink_file_at(&self, path: &str) -> FsResult<()> {
+        self.require(DirPerms::MUTATE)?;
+        let resolved = resolve(&self.dir, path)?;
+        match (resolved.lookup()?, resolved) {
+            (VirtualNode::File(_), Resolved::Entry { parent, name, .. }) => parent.remove(&name),
+            _ => Err(ErrorCode::IsDirectory.into()),
+        }
+    }
+}
+
+/// A [`VirtualFile`] opened as a descriptor.
+#[derive(Clone)]
+pub struct OpenVirtualFile {
+    /// The file this struct is mediating access to.
+    pub file: Arc<dyn VirtualFile>,
+    /// Permissions to enforce on access to the file.
+    pub perms: FilePerms,
+    /// The mode the file was opened under.
+    pub open_mode: OpenMode,
+}
+
+impl OpenVirtualFile {
+    pub(crate) fn new(file: Arc<dyn VirtualFile>, perms: FilePerms, open_mode: OpenMode) -> Self {
+        OpenVirtualFile {
+            file,
+            perms,
+            open_mode,
+        }
+    }
+
+    pub(crate) fn node(&self) -> VirtualNode {
+        VirtualNode::File(self.file.clone())
+    }
+
+    pub(crate) fn get_flags(&self) -> DescriptorFlags {
+        let mut flags = DescriptorFlags::empty();
+        if self.open_mode.contains(OpenMode::READ) {
+            flags |= DescriptorFlags::READ;
+        }
+        if self.open_mode.contains(OpenMode::WRITE) {
+            flags |= DescriptorFlags::WRITE;
+        }
+        flags
+    }
+
+    /// Checks that this file may be read from, both according to the
+    /// permissions it was preopened with and the mode it was opened in.
+    pub(crate) fn check_readable(&self) -> FsResult<()> {
+        if !self.perms.contains(FilePerms::READ) {
+            return Err(ErrorCode::NotPermitted.into());
+        }
+        if !self.open_mode.contains(OpenMode::READ) {
+            return Err(ErrorCode::BadDescriptor.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that this file may be written to, both according to the
+    /// permissions it was preopened with and the mode it was opened in.
+    pub(crate) fn check_writable(&self) -> FsResult<()> {
+        if !self.perms.contains(FilePerms::WRITE) {
+            return Err(ErrorCode::NotPermitted.into());
+        }
+        if !self.open_mode.contains(OpenMode::WRITE) {
+            return Err(ErrorCode::BadDescriptor.into());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_size(&self, size: u64) -> FsResult<()> {
+        if !self.perms.contains(FilePerms::WRITE) {
+            return Err(ErrorCode::NotPermitted.into());
+        }
+        // Like `ftruncate`, fail with `EINVAL` if the file isn't open for
+        // writing.
+        if !self.open_mode.contains(OpenMode::WRITE) {
+            return Err(ErrorCode::Invalid.into());
+        }
+        self.file.set_size(size)
+    }
+
+    pub(crate) fn set_times(&self, atim: NewTimestamp, mtim: NewTimestamp) -> FsResult<()> {
+        if !self.perms.contains(FilePerms::WRITE) {
+            return Err(ErrorCode::NotPermitted.into());
+        }
+        self.file.set_times(atim, mtim)
+    }
+
+    pub(crate) fn read(&self, len: u64, offset: u64) -> FsResult<(Vec<u8>, bool)> {
+        self.check_readable()?;
+        let mut buffer = vec![0; len.try_into().unwrap_or(usize::MAX)];
+        let n = self.file.read_at(&mut buffer, offset)?;
+        buffer.truncate(n);
+        Ok((buffer, n == 0))
+    }
+
+    pub(crate) fn write(&self, buf: &[u8], offset: u64) -> FsResult<u64> {
+        self.check_writable()?;
+        let n = self.file.write_at(buf, offset)?;
+        Ok(u64::try_from(n).expect("usize fits in Filesize"))
+    }
+
+    #[cfg(feature = "preview1")]
+    pub(crate) fn append(&self, buf: &[u8]) -> FsResult<u64> {
+        self.check_writable()?;
+        let n = self.file.append(buf)?;
+        Ok(u64::try_from(n).expect("usize fits in Filesize"))
+    }
+
+    pub(crate) fn stat(&self) -> FsResult<DescriptorStat> {
+        self.file.stat()
+    }
+}
+
+/// An input stream reading from a [`VirtualFile`].
+pub(crate) struct VirtualFileInputStream {
+    file: Arc<dyn VirtualFile>,
+    position: u64,
+}
+
+impl VirtualFileInputStream {
+    pub(crate) fn new(file: &OpenVirtualFile, position: u64) -> Self {
+        Self {
+            file: file.file.clone(),
+            position,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InputStream for VirtualFileInputStream {
+    fn read(&mut self, size: usize) -> StreamResult<Bytes> {
+        let mut buf = BytesMut::zeroed(size);
+        let n = self
+            .file
+            .read_at(&mut buf, self.position)
+            .map_err(stream_error)?;
+        if n == 0 && size > 0 {
+            return Err(StreamError::Closed);
+        }
+        buf.truncate(n);
+        self.position += n as u64;
+        Ok(buf.freeze())
+    }
+}
+
+#[async_trait::async_trait]
+impl Pollable for VirtualFileInputStream {
+    async fn ready(&mut self) {}
+}
+
+/// An output stream writing to a [`VirtualFile`].
+pub(crate) struct VirtualFileOutputStream {
+    file: Arc<dyn VirtualFile>,
+    /// The position to write at, or `None` to append.
+    position: Option<u64>,
+}
+
+impl VirtualFileOutputStream {
+    pub(crate) fn write_at(file: &OpenVirtualFile, position: u64) -> Self {
+        Self {
+            file: file.file.clone(),
+            position: Some(position),
+        }
+    }
+
+    pub(crate) fn append(file: &OpenVirtualFile) -> Self {
+        Self {
+            file: file.file.clone(),
+            position: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl OutputStream for VirtualFileOutputStream {
+    fn write(&mut self, mut buf: Bytes) -> StreamResult<()> {
+        while !buf.is_empty() {
+            let n = match &mut self.position {
+                Some(p) => {
+                    let n = self.file.write_at(&buf, *p).map_err(stream_error)?;
+                    *p += n as u64;
+                    n
+                }
+                None => self.file.append(&buf).map_err(stream_error)?,
+            };
+            let _ = buf.split_to(n);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> StreamResult<()> {
+        Ok(())
+    }
+
+    fn check_write(&mut self) -> StreamResult<usize> {
+        Ok(super::FILE_WRITE_CAPACITY)
+    }
+}
+
+#[async_trait::async_trait]
+impl Pollable for VirtualFileOutputStream {
+    async fn ready(&mut self) {}
+}
+
+fn stream_error(e: crate::FsError) -> StreamError {
+    match e.downcast() {
+        Ok(code) => StreamError::LastOperationFailed(code.into()),
+        Err(e) => StreamError::Trap(e),
+    }
+}
+
+/// Converts a timestamp of the host into a WASI `datetime`, for use in
+/// [`DescriptorStat`].
+pub(crate) fn datetime_from(
+    t: std::time::SystemTime,
+) -> Option<crate::bindings::clocks::wall_clock::Datetime> {
+    let d = t.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some(crate::bindings::clocks::wall_clock::Datetime {
+        seconds: d.as_secs(),
+        nanoseconds: d.subsec_nanos(),
+    })
+}
+
+/// Resolves a [`NewTimestamp`] into the time it refers to, or `None` if the
+/// timestamp should be left unchanged.
+pub(crate) fn resolve_timestamp(t: NewTimestamp) -> FsResult<Option<std::time::SystemTime>> {
+    use std::time::{Duration, SystemTime};
+    match t {
+        NewTimestamp::NoChange => Ok(None),
+        NewTimestamp::Now => Ok(Some(SystemTime::now())),
+        NewTimestamp::Timestamp(t) => SystemTime::UNIX_EPOCH
+            .checked_add(Duration::new(t.seconds, t.nanoseconds))
+            .map(Some)
+            .ok_or_else(|| ErrorCode::Overflow.into()),
+    }
+}
@@ -5,6 +5,7 @@ use crate::bindings::filesystem::types::{
 };
 use crate::filesystem::{
     Descriptor, Dir, File, FileInputStream, FileOutputStream, OpenMode, ReaddirIterator,
+    VirtualFileInputStream, VirtualFileOutputStream, VirtualNode,
 };
 use crate::{DirPerms, FilePerms, FsError, FsResult, IoView, WasiImpl, WasiView};
 use anyhow::Context;
@@ -24,7 +25,7 @@ where
         for (dir, name) in self.ctx().preopens.clone() {
             let fd = self
                 .table()
-                .push(Descriptor::Dir(dir))
+                .push(dir)
                 .with_context(|| format!("failed to push preopen {name}"))?;
             results.push((fd, name));
         }
@@ -51,6 +52,10 @@ where
         if let Some(err) = err.downcast_ref::<std::io::Error>() {
             return Ok(Some(ErrorCode::from(err)));
         }
+        // Streams of virtual files report error codes directly.
+        if let Some(code) = err.downcast_ref::<ErrorCode>() {
+            return Ok(Some(*code));
+        }
 
         Ok(None)
     }
@@ -79,7 +84,11 @@ where
             Advice::NoReuse => A::NoReuse,
         };
 
-        let f = self.table().get(&fd)?.file()?;
+        let descriptor = self.table().get(&fd)?;
+        if let Descriptor::VirtualFile(_) = descriptor {
+            return Ok(());
+        }
+        let f = descriptor.file()?;
         f.run_blocking(move |f| f.advise(offset, len, advice))
             .await?;
         Ok(())
@@ -109,6 +118,8 @@ where
                 d.run_blocking(|d| Ok(d.open(std::path::Component::CurDir)?.sync_data()?))
                     .await
             }
+            // Virtual filesystems have no storage to synchronize with.
+            Descriptor::VirtualFile(_) | Descriptor::VirtualDir(_) => Ok(()),
         }
     }
 
@@ -157,6 +168,8 @@ where
                 }
                 Ok(flags)
             }
+            Descriptor::VirtualFile(f) => Ok(f.get_flags()),
+            Descriptor::VirtualDir(d) => Ok(d.get_flags()),
         }
     }
 
@@ -171,7 +184,8 @@ where
                 let meta = f.run_blocking(|f| f.metadata()).await?;
                 Ok(descriptortype_from(meta.file_type()))
             }
-            Descriptor::Dir(_) => Ok(types::DescriptorType::Directory),
+            Descriptor::Dir(_) | Descriptor::VirtualDir(_) => Ok(types::DescriptorType::Directory),
+            Descriptor::VirtualFile(_) => Ok(types::DescriptorType::RegularFile),
         }
     }
 
@@ -180,7 +194,11 @@ where
         fd: Resource<types::Descriptor>,
         size: types::Filesize,
     ) -> FsResult<()> {
-        let f = self.table().get(&fd)?.file()?;
+        let descriptor = self.table().get(&fd)?;
+        if let Descriptor::VirtualFile(f) = descriptor {
+            return f.set_size(size);
+        }
+        let f = descriptor.file()?;
         if !f.perms.contains(FilePerms::WRITE) {
             Err(ErrorCode::NotPermitted)?;
         }
@@ -216,6 +234,8 @@ where
                 d.run_blocking(|d| d.set_times(atim, mtim)).await?;
                 Ok(())
             }
+            Descriptor::VirtualFile(f) => f.set_times(atim, mtim),
+            Descriptor::VirtualDir(d) => d.set_times(atim, mtim),
         }
     }
 
@@ -229,8 +249,12 @@ where
         use system_interface::fs::FileIoExt;
 
         let table = self.table();
+        let descriptor = table.get(&fd)?;
+        if let Descriptor::VirtualFile(f) = descriptor {
+            return f.read(len, offset);
+        }
 
-        let f = table.get(&fd)?.file()?;
+        let f = descriptor.file()?;
         if !f.perms.contains(FilePerms::READ) {
             return Err(ErrorCode::NotPermitted.into());
         }
@@ -267,7 +291,11 @@ where
         use system_interface::fs::FileIoExt;
 
         let table = self.table();
-        let f = table.get(&fd)?.file()?;
+        let descriptor = table.get(&fd)?;
+        if let Descriptor::VirtualFile(f) = descriptor {
+            return f.write(&buf, offset);
+        }
+        let f = descriptor.file()?;
         if !f.perms.contains(FilePerms::WRITE) {
             return Err(ErrorCode::NotPermitted.into());
         }
@@ -284,6 +312,10 @@ where
         fd: Resource<types::Descriptor>,
     ) -> FsResult<Resource<types::DirectoryEntryStream>> {
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            let entries = d.read_directory()?;
+            return Ok(table.push(entries)?);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::READ) {
             return Err(ErrorCode::NotPermitted.into());
@@ -368,6 +400,7 @@ where
                 d.run_blocking(|d| Ok(d.open(std::path::Component::CurDir)?.sync_all()?))
                     .await
             }
+            Descriptor::VirtualFile(_) | Descriptor::VirtualDir(_) => Ok(()),
         }
     }
 
@@ -377,6 +410,9 @@ where
         path: String,
     ) -> FsResult<()> {
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return d.create_directory_at(&path);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -398,6 +434,8 @@ where
                 let meta = d.run_blocking(|d| d.dir_metadata()).await?;
                 Ok(descriptorstat_from(meta))
             }
+            Descriptor::VirtualFile(f) => f.stat(),
+            Descriptor::VirtualDir(d) => d.stat(),
         }
     }
 
@@ -408,6 +446,9 @@ where
         path: String,
     ) -> FsResult<types::DescriptorStat> {
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return d.stat_at(&path);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::READ) {
             return Err(ErrorCode::NotPermitted.into());
@@ -432,6 +473,9 @@ where
         use cap_fs_ext::DirExt;
 
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return d.set_times_at(&path, atim, mtim);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -470,6 +514,17 @@ where
         new_path: String,
     ) -> FsResult<()> {
         let table = self.table();
+        match (table.get(&fd)?, table.get(&new_descriptor)?) {
+            // Virtual filesystems don't support hard links.
+            (Descriptor::VirtualDir(_), Descriptor::VirtualDir(_)) => {
+                return Err(ErrorCode::Unsupported.into());
+            }
+            (Descriptor::VirtualDir(_), Descriptor::Dir(_))
+            | (Descriptor::Dir(_), Descriptor::VirtualDir(_)) => {
+                return Err(ErrorCode::CrossDevice.into());
+            }
+            _ => {}
+        }
         let old_dir = table.get(&fd)?.dir()?;
         if !old_dir.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -502,6 +557,10 @@ where
 
         let allow_blocking_current_thread = self.ctx().allow_blocking_current_thread;
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            let opened = d.open_at(path_flags, &path, oflags, flags)?;
+            return Ok(table.push(opened)?);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::READ) {
             Err(ErrorCode::NotPermitted)?;
@@ -650,6 +709,9 @@ where
         path: String,
     ) -> FsResult<String> {
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return d.readlink_at(&path);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::READ) {
             return Err(ErrorCode::NotPermitted.into());
@@ -667,6 +729,9 @@ where
         path: String,
     ) -> FsResult<()> {
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return d.remove_directory_at(&path);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -682,6 +747,16 @@ where
         new_path: String,
     ) -> FsResult<()> {
         let table = self.table();
+        match (table.get(&fd)?, table.get(&new_fd)?) {
+            (Descriptor::VirtualDir(old_dir), Descriptor::VirtualDir(new_dir)) => {
+                return old_dir.rename_at(&old_path, new_dir, &new_path);
+            }
+            (Descriptor::VirtualDir(_), Descriptor::Dir(_))
+            | (Descriptor::Dir(_), Descriptor::VirtualDir(_)) => {
+                return Err(ErrorCode::CrossDevice.into());
+            }
+            _ => {}
+        }
         let old_dir = table.get(&fd)?.dir()?;
         if !old_dir.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -707,6 +782,10 @@ where
         use cap_fs_ext::DirExt;
 
         let table = self.table();
+        if let Descriptor::VirtualDir(_) = table.get(&fd)? {
+            // Virtual filesystems don't support symlinks.
+            return Err(ErrorCode::Unsupported.into());
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -723,6 +802,9 @@ where
         use cap_fs_ext::DirExt;
 
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return d.unlink_file_at(&path);
+        }
         let d = table.get(&fd)?.dir()?;
         if !d.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted.into());
@@ -737,14 +819,24 @@ where
         offset: types::Filesize,
     ) -> FsResult<Resource<DynInputStream>> {
         // Trap if fd lookup fails:
-        let f = self.table().get(&fd)?.file()?;
-
-        if !f.perms.contains(FilePerms::READ) {
-            Err(types::ErrorCode::BadDescriptor)?;
-        }
+        let descriptor = self.table().get(&fd)?;
+        let reader: DynInputStream = match descriptor {
+            Descriptor::VirtualFile(f) => {
+                if f.check_readable().is_err() {
+                    Err(types::ErrorCode::BadDescriptor)?;
+                }
+                Box::new(VirtualFileInputStream::new(f, offset))
+            }
+            _ => {
+                let f = descriptor.file()?;
+                if !f.perms.contains(FilePerms::READ) {
+                    Err(types::ErrorCode::BadDescriptor)?;
+                }
 
-        // Create a stream view for it.
-        let reader: DynInputStream = Box::new(FileInputStream::new(f, offset));
+                // Create a stream view for it.
+                Box::new(FileInputStream::new(f, offset))
+            }
+        };
 
         // Insert the stream view into the table. Trap if the table is full.
         let index = self.table().push(reader)?;
@@ -758,15 +850,24 @@ where
         offset: types::Filesize,
     ) -> FsResult<Resource<DynOutputStream>> {
         // Trap if fd lookup fails:
-        let f = self.table().get(&fd)?.file()?;
-
-        if !f.perms.contains(FilePerms::WRITE) {
-            Err(types::ErrorCode::BadDescriptor)?;
-        }
+        let descriptor = self.table().get(&fd)?;
+        let writer: DynOutputStream = match descriptor {
+            Descriptor::VirtualFile(f) => {
+                if f.check_writable().is_err() {
+                    Err(types::ErrorCode::BadDescriptor)?;
+                }
+                Box::new(VirtualFileOutputStream::write_at(f, offset))
+            }
+            _ => {
+                let f = descriptor.file()?;
+                if !f.perms.contains(FilePerms::WRITE) {
+                    Err(types::ErrorCode::BadDescriptor)?;
+                }
 
-        // Create a stream view for it.
-        let writer = FileOutputStream::write_at(f, offset);
-        let writer: DynOutputStream = Box::new(writer);
+                // Create a stream view for it.
+                Box::new(FileOutputStream::write_at(f, offset))
+            }
+        };
 
         // Insert the stream view into the table. Trap if the table is full.
         let index = self.table().push(writer)?;
@@ -779,15 +880,24 @@ where
         fd: Resource<types::Descriptor>,
     ) -> FsResult<Resource<DynOutputStream>> {
         // Trap if fd lookup fails:
-        let f = self.table().get(&fd)?.file()?;
-
-        if !f.perms.contains(FilePerms::WRITE) {
-            Err(types::ErrorCode::BadDescriptor)?;
-        }
+        let descriptor = self.table().get(&fd)?;
+        let appender: DynOutputStream = match descriptor {
+            Descriptor::VirtualFile(f) => {
+                if f.check_writable().is_err() {
+                    Err(types::ErrorCode::BadDescriptor)?;
+                }
+                Box::new(VirtualFileOutputStream::append(f))
+            }
+            _ => {
+                let f = descriptor.file()?;
+                if !f.perms.contains(FilePerms::WRITE) {
+                    Err(types::ErrorCode::BadDescriptor)?;
+                }
 
-        // Create a stream view for it.
-        let appender = FileOutputStream::append(f);
-        let appender: DynOutputStream = Box::new(appender);
+                // Create a stream view for it.
+                Box::new(FileOutputStream::append(f))
+            }
+        };
 
         // Insert the stream view into the table. Trap if the table is full.
         let index = self.table().push(appender)?;
@@ -801,6 +911,12 @@ where
         b: Resource<types::Descriptor>,
     ) -> anyhow::Result<bool> {
         use cap_fs_ext::MetadataExt;
+        let table = self.table();
+        match (virtual_node(table.get(&a)?), virtual_node(table.get(&b)?)) {
+            (Some(a), Some(b)) => return Ok(a.id() == b.id()),
+            (Some(_), None) | (None, Some(_)) => return Ok(false),
+            (None, None) => {}
+        }
         let descriptor_a = self.table().get(&a)?;
         let meta_a = get_descriptor_metadata(descriptor_a).await?;
         let descriptor_b = self.table().get(&b)?;
@@ -827,6 +943,9 @@ where
         fd: Resource<types::Descriptor>,
     ) -> FsResult<types::MetadataHashValue> {
         let descriptor_a = self.table().get(&fd)?;
+        if let Some(node) = virtual_node(descriptor_a) {
+            return Ok(calculate_virtual_metadata_hash(&node));
+        }
         let meta = get_descriptor_metadata(descriptor_a).await?;
         Ok(calculate_metadata_hash(&meta))
     }
@@ -837,6 +956,9 @@ where
         path: String,
     ) -> FsResult<types::MetadataHashValue> {
         let table = self.table();
+        if let Descriptor::VirtualDir(d) = table.get(&fd)? {
+            return Ok(calculate_virtual_metadata_hash(&d.lookup_at(&path)?));
+        }
         let d = table.get(&fd)?.dir()?;
         // No permissions check on metadata: if dir opened, allowed to stat it
         let meta = d
@@ -881,19 +1003,42 @@ async fn get_descriptor_metadata(fd: &types::Descriptor) -> FsResult<cap_std::fs
             // No permissions check on metadata: if opened, allowed to stat it
             Ok(d.run_blocking(|d| d.dir_metadata()).await?)
         }
+        // Virtual descriptors have no host metadata, and are handled by
+        // `virtual_node` instead.
+        Descriptor::VirtualFile(_) | Descriptor::VirtualDir(_) => {
+            Err(ErrorCode::BadDescriptor.into())
+        }
+    }
+}
+
+fn virtual_node(fd: &types::Descriptor) -> Option<VirtualNode> {
+    match fd {
+        Descriptor::VirtualFile(f) => Some(f.node()),
+        Descriptor::VirtualDir(d) => Some(d.node()),
+        Descriptor::File(_) | Descriptor::Dir(_) => None,
     }
 }
 
 fn calculate_metadata_hash(meta: &cap_std::fs::Metadata) -> types::MetadataHashValue {
     use cap_fs_ext::MetadataExt;
+    hash_dev_ino(meta.dev(), meta.ino())
+}
+
+fn calculate_virtual_metadata_hash(node: &VirtualNode) -> types::MetadataHashValue {
+    // Virtual nodes don't live on any host device, so use a device number
+    // which no host device is expected to have.
+    hash_dev_ino(u64::MAX, node.id())
+}
+
+fn hash_dev_ino(dev: u64, ino: u64) -> types::MetadataHashValue {
     // Without incurring any deps, std provides us with a 64 bit hash
     // function:
     use std::hash::Hasher;
     // Note that this means that the metadata hash (which becomes a preview1 ino) may
     // change when a different rustc release is used to build this host implementation:
     let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    hasher.write_u64(meta.dev());
-    hasher.write_u64(meta.ino());
+    hasher.write_u64(dev);
+    hasher.write_u64(ino);
     let lower = hasher.finish();
     // MetadataHashValue has a pair of 64-bit members for representing a
     // single 128-bit number. However, we only have 64 bits of entropy. To
@@ -995,6 +1140,7 @@ impl<'a> From<&'a std::io::Error> for ErrorCode {
                     std::io::ErrorKind::PermissionDenied => ErrorCode::NotPermitted,
                     std::io::ErrorKind::AlreadyExists => ErrorCode::Exist,
                     std::io::ErrorKind::InvalidInput => ErrorCode::Invalid,
+                    std::io::ErrorKind::OutOfMemory => ErrorCode::InsufficientMemory,
                     _ => ErrorCode::Io,
                 }
             }
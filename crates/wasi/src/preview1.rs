@@ -601,8 +601,27 @@ impl WasiP1Ctx {
                 let pos = position.load(Ordering::Relaxed);
                 let append = *append;
                 drop(t);
-                let f = self.table().get(&fd)?.file()?;
                 let buf = first_non_empty_ciovec(memory, ciovs)?;
+                let f = match self.table().get(&fd)? {
+                    crate::filesystem::Descriptor::VirtualFile(f) => {
+                        let buf = memory.as_cow(buf)?;
+                        let nwritten = match (append, write) {
+                            (true, _) => f.append(&buf)?,
+                            (false, FdWrite::At(pos)) => f.write(&buf, pos)?,
+                            (false, FdWrite::AtCur) => f.write(&buf, pos)?,
+                        };
+                        if let FdWrite::AtCur = write {
+                            let pos = if append {
+                                f.stat()?.size
+                            } else {
+                                pos.checked_add(nwritten).ok_or(types::Errno::Overflow)?
+                            };
+                            position.store(pos, Ordering::Relaxed);
+                        }
+                        return Ok(nwritten.try_into()?);
+                    }
+                    descriptor => descriptor.file()?,
+                };
 
                 let do_write = move |f: &cap_std::fs::File, buf: &[u8]| match (append, write) {
                     // Note that this is implementing Linux semantics of
@@ -875,10 +894,13 @@ impl From<StreamError> for types::Error {
             StreamError::Closed => types::Errno::Io.into(),
             StreamError::LastOperationFailed(e) => match e.downcast::<std::io::Error>() {
                 Ok(err) => filesystem::ErrorCode::from(err).into(),
-                Err(e) => {
-                    tracing::debug!("dropping error {e:?}");
-                    types::Errno::Io.into()
-                }
+                Err(e) => match e.downcast::<filesystem::ErrorCode>() {
+                    Ok(code) => code.into(),
+                    Err(e) => {
+                        tracing::debug!("dropping error {e:?}");
+                        types::Errno::Io.into()
+                    }
+                },
             },
             StreamError::Trap(e) => types::Error::trap(e),
         }
@@ -1648,8 +1670,20 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiP1Ctx {
                 let position = position.clone();
                 drop(t);
                 let pos = position.load(Ordering::Relaxed);
-                let file = self.table().get(&fd)?.file()?;
                 let iov = first_non_empty_iovec(memory, iovs)?;
+                let file = match self.table().get(&fd)? {
+                    crate::filesystem::Descriptor::VirtualFile(f) => {
+                        let (buf, _) = f.read(iov.len().into(), pos)?;
+                        let iov = iov.get_range(0..u32::try_from(buf.len())?).unwrap();
+                        memory.copy_from_slice(&buf, iov)?;
+                        let pos = pos
+                            .checked_add(buf.len().try_into()?)
+                            .ok_or(types::Errno::Overflow)?;
+                        position.store(pos, Ordering::Relaxed);
+                        return Ok(buf.len().try_into()?);
+                    }
+                    descriptor => descriptor.file()?,
+                };
                 let bytes_read = match (file.as_blocking_file(), memory.as_slice_mut(iov)?) {
                     // Try to read directly into wasm memory where possible
                     // when the current thread can block and additionally wasm
@@ -2157,11 +2191,13 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiP1Ctx {
             .await?;
         let mut t = self.transact()?;
         let desc = match t.view.table().get(&fd)? {
-            crate::filesystem::Descriptor::Dir(_) => Descriptor::Directory {
+            crate::filesystem::Descriptor::Dir(_)
+            | crate::filesystem::Descriptor::VirtualDir(_) => Descriptor::Directory {
                 fd,
                 preopen_path: None,
             },
-            crate::filesystem::Descriptor::File(_) => Descriptor::File(File {
+            crate::filesystem::Descriptor::File(_)
+            | crate::filesystem::Descriptor::VirtualFile(_) => Descriptor::File(File {
                 fd,
                 position: Default::default(),
                 append: fdflags.contains(types::Fdflags::APPEND),
@@ -10,8 +10,8 @@ use wasmtime_wasi::bindings::Command;
 use wasmtime_wasi::{
     add_to_linker_async,
     bindings::{clocks::wall_clock, filesystem::types as filesystem},
-    DirPerms, FilePerms, HostMonotonicClock, HostWallClock, IoView, WasiCtx, WasiCtxBuilder,
-    WasiView,
+    DirPerms, FilePerms, HostMonotonicClock, HostWallClock, IoView, VirtualMonotonicClock,
+    VirtualWallClock, WasiCtx, WasiCtxBuilder, WasiView,
 };
 
 struct CommandCtx {
@@ -93,6 +93,56 @@ async fn api_time() -> Result<()> {
         .map_err(|()| anyhow::anyhow!("command returned with failing exit status"))
 }
 
+#[test]
+fn api_check_deterministic() -> Result<()> {
+    // The default context sources clocks and randomness from the host.
+    let err = WasiCtxBuilder::new().check_deterministic().unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("wall clock"), "{msg}");
+    assert!(msg.contains("wasi:random/random"), "{msg}");
+    assert!(msg.contains("TCP is allowed"), "{msg}");
+
+    let mut builder = WasiCtxBuilder::new();
+    builder.deterministic(42).arg("foo").env("FOO", "bar");
+    builder.check_deterministic()?;
+
+    // Anything reintroducing host state afterwards is reported.
+    let dir = tempfile::tempdir()?;
+    builder.inherit_stdin().allow_udp(true).preopened_dir(
+        dir.path(),
+        "/",
+        DirPerms::READ,
+        FilePerms::READ,
+    )?;
+    let msg = builder.check_deterministic().unwrap_err().to_string();
+    assert!(msg.contains("stdin"), "{msg}");
+    assert!(msg.contains("UDP is allowed"), "{msg}");
+    assert!(msg.contains("preopened as `/`"), "{msg}");
+    assert!(!msg.contains("clock"), "{msg}");
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn api_deterministic_time() -> Result<()> {
+    let table = ResourceTable::new();
+    let wasi = WasiCtxBuilder::new()
+        .deterministic(0)
+        .monotonic_clock(VirtualMonotonicClock::new(42 * 1_000_000_000))
+        .wall_clock(VirtualWallClock::new(
+            Duration::new(1431648000, 100),
+            Duration::ZERO,
+        ))
+        .build();
+
+    let (mut store, command) = instantiate(API_TIME_COMPONENT, CommandCtx { table, wasi }).await?;
+
+    command
+        .wasi_cli_run()
+        .call_run(&mut store)
+        .await?
+        .map_err(|()| anyhow::anyhow!("command returned with failing exit status"))
+}
+
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
 async fn api_read_only() -> Result<()> {
     let dir = tempfile::tempdir()?;
@@ -91,3 +91,4 @@ mod api;
 mod async_;
 mod preview1;
 mod sync;
+mod virtual_fs;
@@ -0,0 +1,270 @@
+use super::*;
+use std::sync::Arc;
+use test_programs_artifacts::*;
+use wasmtime::{Linker as CoreLinker, Module};
+use wasmtime_wasi::bindings::filesystem::types::ErrorCode;
+use wasmtime_wasi::bindings::Command;
+use wasmtime_wasi::{MemoryDir, VirtualDir, VirtualNode};
+
+fn virtual_store(
+    engine: &Engine,
+    name: &str,
+    configure: impl FnOnce(&mut WasiCtxBuilder),
+) -> Store<Ctx> {
+    let stdout = MemoryOutputPipe::new(4096);
+    let stderr = MemoryOutputPipe::new(4096);
+    let mut builder = WasiCtxBuilder::new();
+    builder
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .args(&[name, "."]);
+    for (var, val) in test_programs_artifacts::wasi_tests_environment() {
+        builder.env(var, val);
+    }
+    configure(&mut builder);
+    let ctx = Ctx {
+        wasi: builder.build_p1(),
+        stderr,
+        stdout,
+    };
+    Store::new(engine, ctx)
+}
+
+fn preopen_empty(builder: &mut WasiCtxBuilder) {
+    builder.preopened_virtual_dir(
+        Arc::new(MemoryDir::new()),
+        ".",
+        DirPerms::all(),
+        FilePerms::all(),
+    );
+}
+
+async fn run_component(path: &str, configure: impl FnOnce(&mut WasiCtxBuilder)) -> Result<()> {
+    let engine = test_programs_artifacts::engine(|config| {
+        config.async_support(true);
+    });
+    let mut linker = Linker::new(&engine);
+    wasmtime_wasi::add_to_linker_async(&mut linker)?;
+    let mut store = virtual_store(&engine, "test", configure);
+    let component = Component::from_file(&engine, path)?;
+    let command = Command::instantiate_async(&mut store, &component, &linker).await?;
+    command
+        .wasi_cli_run()
+        .call_run(&mut store)
+        .await?
+        .map_err(|()| anyhow::anyhow!("run returned a failure"))
+}
+
+/// Runs a preview1 program both as a core module and as a component through
+/// the adapter, each time against a fresh in-memory directory.
+async fn run(module: &str, component: &str) -> Result<()> {
+    let engine = test_programs_artifacts::engine(|config| {
+        config.async_support(true);
+    });
+    let name = std::path::Path::new(module)
+        .file_stem()
+        .unwrap()
+        .to_str()
+        .unwrap();
+
+    let mut linker = CoreLinker::<Ctx>::new(&engine);
+    wasmtime_wasi::preview1::add_to_linker_async(&mut linker, |t| &mut t.wasi)?;
+    let mut store = virtual_store(&engine, name, preopen_empty);
+    let module = Module::from_file(&engine, module)?;
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+    start.call_async(&mut store, ()).await?;
+
+    run_component(component, preopen_empty).await
+}
+
+macro_rules! tests {
+    ($($name:ident => $module:ident, $component:ident;)*) => {$(
+        #[test_log::test(tokio::test(flavor = "multi_thread"))]
+        async fn $name() {
+            run($module, $component).await.unwrap()
+        }
+    )*};
+}
+
+tests! {
+    close_preopen => PREVIEW1_CLOSE_PREOPEN, PREVIEW1_CLOSE_PREOPEN_COMPONENT;
+    dangling_fd => PREVIEW1_DANGLING_FD, PREVIEW1_DANGLING_FD_COMPONENT;
+    dir_fd_op_failures => PREVIEW1_DIR_FD_OP_FAILURES, PREVIEW1_DIR_FD_OP_FAILURES_COMPONENT;
+    directory_seek => PREVIEW1_DIRECTORY_SEEK, PREVIEW1_DIRECTORY_SEEK_COMPONENT;
+    fd_advise => PREVIEW1_FD_ADVISE, PREVIEW1_FD_ADVISE_COMPONENT;
+    fd_filestat_get => PREVIEW1_FD_FILESTAT_GET, PREVIEW1_FD_FILESTAT_GET_COMPONENT;
+    fd_filestat_set => PREVIEW1_FD_FILESTAT_SET, PREVIEW1_FD_FILESTAT_SET_COMPONENT;
+    fd_flags_set => PREVIEW1_FD_FLAGS_SET, PREVIEW1_FD_FLAGS_SET_COMPONENT;
+    fd_readdir => PREVIEW1_FD_READDIR, PREVIEW1_FD_READDIR_COMPONENT;
+    file_allocate => PREVIEW1_FILE_ALLOCATE, PREVIEW1_FILE_ALLOCATE_COMPONENT;
+    file_pread_pwrite => PREVIEW1_FILE_PREAD_PWRITE, PREVIEW1_FILE_PREAD_PWRITE_COMPONENT;
+    file_read_write => PREVIEW1_FILE_READ_WRITE, PREVIEW1_FILE_READ_WRITE_COMPONENT;
+    file_seek_tell => PREVIEW1_FILE_SEEK_TELL, PREVIEW1_FILE_SEEK_TELL_COMPONENT;
+    file_truncation => PREVIEW1_FILE_TRUNCATION, PREVIEW1_FILE_TRUNCATION_COMPONENT;
+    file_unbuffered_write => PREVIEW1_FILE_UNBUFFERED_WRITE, PREVIEW1_FILE_UNBUFFERED_WRITE_COMPONENT;
+    file_write => PREVIEW1_FILE_WRITE, PREVIEW1_FILE_WRITE_COMPONENT;
+    interesting_paths => PREVIEW1_INTERESTING_PATHS, PREVIEW1_INTERESTING_PATHS_COMPONENT;
+    overwrite_preopen => PREVIEW1_OVERWRITE_PREOPEN, PREVIEW1_OVERWRITE_PREOPEN_COMPONENT;
+    path_filestat => PREVIEW1_PATH_FILESTAT, PREVIEW1_PATH_FILESTAT_COMPONENT;
+    path_open_create_existing => PREVIEW1_PATH_OPEN_CREATE_EXISTING, PREVIEW1_PATH_OPEN_CREATE_EXISTING_COMPONENT;
+    path_open_dirfd_not_dir => PREVIEW1_PATH_OPEN_DIRFD_NOT_DIR, PREVIEW1_PATH_OPEN_DIRFD_NOT_DIR_COMPONENT;
+    path_open_lots => PREVIEW1_PATH_OPEN_LOTS, PREVIEW1_PATH_OPEN_LOTS_COMPONENT;
+    path_open_missing => PREVIEW1_PATH_OPEN_MISSING, PREVIEW1_PATH_OPEN_MISSING_COMPONENT;
+    path_open_nonblock => PREVIEW1_PATH_OPEN_NONBLOCK, PREVIEW1_PATH_OPEN_NONBLOCK_COMPONENT;
+    path_open_preopen => PREVIEW1_PATH_OPEN_PREOPEN, PREVIEW1_PATH_OPEN_PREOPEN_COMPONENT;
+    path_open_read_write => PREVIEW1_PATH_OPEN_READ_WRITE, PREVIEW1_PATH_OPEN_READ_WRITE_COMPONENT;
+    path_rename => PREVIEW1_PATH_RENAME, PREVIEW1_PATH_RENAME_COMPONENT;
+    path_rename_dir_trailing_slashes => PREVIEW1_PATH_RENAME_DIR_TRAILING_SLASHES, PREVIEW1_PATH_RENAME_DIR_TRAILING_SLASHES_COMPONENT;
+    poll_oneoff_files => PREVIEW1_POLL_ONEOFF_FILES, PREVIEW1_POLL_ONEOFF_FILES_COMPONENT;
+    regular_file_isatty => PREVIEW1_REGULAR_FILE_ISATTY, PREVIEW1_REGULAR_FILE_ISATTY_COMPONENT;
+    remove_directory => PREVIEW1_REMOVE_DIRECTORY, PREVIEW1_REMOVE_DIRECTORY_COMPONENT;
+    remove_nonempty_directory => PREVIEW1_REMOVE_NONEMPTY_DIRECTORY, PREVIEW1_REMOVE_NONEMPTY_DIRECTORY_COMPONENT;
+    renumber => PREVIEW1_RENUMBER, PREVIEW1_RENUMBER_COMPONENT;
+    unlink_file_trailing_slashes => PREVIEW1_UNLINK_FILE_TRAILING_SLASHES, PREVIEW1_UNLINK_FILE_TRAILING_SLASHES_COMPONENT;
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn read_only() -> Result<()> {
+    let root = MemoryDir::from_files([("bar.txt", "And stood awhile in thought")])?;
+    root.create_dir_all("sub")?;
+    let root = Arc::new(root);
+
+    run_component(API_READ_ONLY_COMPONENT, |builder| {
+        builder.preopened_virtual_dir(root.clone(), "/", DirPerms::READ, FilePerms::READ);
+    })
+    .await?;
+
+    assert_eq!(root.read_file("bar.txt")?, b"And stood awhile in thought");
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn guest_writes_are_visible_to_host() -> Result<()> {
+    let root = Arc::new(MemoryDir::new());
+    run_component(PREVIEW1_FILE_WRITE_COMPONENT, |builder| {
+        builder.preopened_virtual_dir(root.clone(), ".", DirPerms::all(), FilePerms::all());
+    })
+    .await?;
+
+    let contents = root.read_file("long_write.txt")?;
+    assert_eq!(contents.len(), 4096 * 16);
+    assert!(contents.starts_with(b"123456789 00000 "));
+    Ok(())
+}
+
+#[test]
+fn overlay_is_copy_on_write() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("bar.txt"), "original")?;
+    std::fs::create_dir(dir.path().join("sub"))?;
+    std::fs::write(dir.path().join("sub/baz.txt"), "nested")?;
+
+    let overlay = MemoryDir::overlay(dir.path())?;
+    assert_eq!(overlay.read_file("sub/baz.txt")?, b"nested");
+
+    let VirtualNode::File(bar) = overlay.lookup("bar.txt")? else {
+        panic!("expected `bar.txt` to be a file");
+    };
+    bar.write_at(b"modified", 0)?;
+    let VirtualNode::Dir(sub) = overlay.lookup("sub")? else {
+        panic!("expected `sub` to be a directory");
+    };
+    sub.remove("baz.txt")?;
+    overlay.insert_file("new.txt", "new")?;
+
+    assert_eq!(overlay.read_file("bar.txt")?, b"modified");
+    assert!(overlay.read_file("sub/baz.txt").is_err());
+    assert_eq!(overlay.read_file("new.txt")?, b"new");
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("bar.txt"))?,
+        "original"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("sub/baz.txt"))?,
+        "nested"
+    );
+    assert!(!dir.path().join("new.txt").exists());
+    Ok(())
+}
+
+fn error_code<T>(result: wasmtime_wasi::FsResult<T>) -> ErrorCode {
+    match result {
+        Ok(_) => panic!("expected an error"),
+        Err(e) => e.downcast().unwrap(),
+    }
+}
+
+#[test]
+fn file_size_is_limited() -> Result<()> {
+    let root = MemoryDir::new();
+    let file = root.create_file("big.bin")?;
+
+    // Without limits, sizes which can't be allocated fail instead of aborting.
+    assert_eq!(
+        error_code(file.set_size(u64::MAX)),
+        ErrorCode::InsufficientMemory
+    );
+    assert_eq!(
+        error_code(file.write_at(b"x", u64::MAX)),
+        ErrorCode::FileTooLarge
+    );
+
+    root.set_max_file_size(1024);
+    assert_eq!(error_code(file.set_size(1025)), ErrorCode::FileTooLarge);
+    assert_eq!(
+        error_code(file.write_at(b"xy", 1023)),
+        ErrorCode::FileTooLarge
+    );
+    assert_eq!(
+        error_code(file.write_at(b"x", 1 << 40)),
+        ErrorCode::FileTooLarge
+    );
+    file.set_size(1024)?;
+    file.write_at(b"x", 1023)?;
+    assert_eq!(error_code(file.append(b"x")), ErrorCode::FileTooLarge);
+    assert_eq!(file.stat()?.size, 1024);
+    Ok(())
+}
+
+#[test]
+fn total_size_is_limited() -> Result<()> {
+    let root = MemoryDir::new();
+    root.set_max_total_size(1024);
+    root.create_dir_all("sub")?;
+    let VirtualNode::Dir(sub) = root.lookup("sub")? else {
+        panic!("expected `sub` to be a directory");
+    };
+
+    let a = root.create_file("a.bin")?;
+    let b = sub.create_file("b.bin")?;
+    a.set_size(1000)?;
+    assert_eq!(error_code(b.set_size(100)), ErrorCode::InsufficientMemory);
+    assert_eq!(
+        error_code(b.write_at(&[0; 25], 0)),
+        ErrorCode::InsufficientMemory
+    );
+    assert!(root.insert_file("c.bin", vec![0; 100]).is_err());
+    b.write_at(&[0; 24], 0)?;
+
+    // Shrinking and removing files frees up space for others.
+    a.set_size(500)?;
+    b.set_size(100)?;
+    root.remove("a.bin")?;
+    drop(a);
+    b.set_size(1024)?;
+    Ok(())
+}
+
+#[test]
+fn insert_file_rejects_host_directory() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("sub"))?;
+
+    let overlay = MemoryDir::overlay(dir.path())?;
+    let err = overlay.insert_file("sub", "contents").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert!(matches!(overlay.lookup("sub")?, VirtualNode::Dir(_)));
+    Ok(())
+}
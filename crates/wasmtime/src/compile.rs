@@ -38,8 +38,8 @@ use wasmtime_environ::component::Translator;
 use wasmtime_environ::{
     BuiltinFunctionIndex, CompiledFunctionInfo, CompiledModuleInfo, Compiler, DefinedFuncIndex,
     FinishedObject, FunctionBodyData, ModuleEnvironment, ModuleInternedTypeIndex,
-    ModuleTranslation, ModuleTypes, ModuleTypesBuilder, ObjectKind, PrimaryMap, RelocationTarget,
-    StaticModuleIndex, WasmFunctionInfo,
+    ModuleTranslation, ModuleTypes, ModuleTypesBuilder, ObjectKind, PrimaryMap, ProfileData,
+    RelocationTarget, StaticModuleIndex, WasmFunctionInfo,
 };
 
 mod code_builder;
@@ -47,6 +47,8 @@ pub use self::code_builder::{CodeBuilder, CodeHint, HashedEngineCompileEnv};
 
 #[cfg(feature = "runtime")]
 mod runtime;
+#[cfg(all(feature = "runtime", feature = "cranelift", feature = "winch"))]
+pub(crate) use self::runtime::compile_tier_up;
 
 /// Converts an input binary-encoded WebAssembly module to compilation
 /// artifacts and type information.
@@ -64,9 +66,52 @@ pub(crate) fn build_artifacts<T: FinishedObject>(
     engine: &Engine,
     wasm: &[u8],
     dwarf_package: Option<&[u8]>,
+    profile_data: Option<&ProfileData>,
+    obj_state: &T::State,
+) -> Result<(T, Option<(CompiledModuleInfo, ModuleTypes)>)> {
+    build_module_artifacts(engine, wasm, dwarf_package, profile_data, None, obj_state)
+}
+
+/// Same as [`build_artifacts`], but compiles the `hot` functions of the module
+/// with the Cranelift compiler of an engine using
+/// [`Strategy::Tiered`](crate::Strategy::Tiered) instead of its Winch
+/// compiler.
+///
+/// The resulting artifact doesn't contain the original wasm, so it's never
+/// tiered up any further itself.
+#[cfg(all(feature = "cranelift", feature = "winch"))]
+pub(crate) fn build_tier_up_artifacts<T: FinishedObject>(
+    engine: &Engine,
+    wasm: &[u8],
+    hot: &BTreeSet<DefinedFuncIndex>,
+    obj_state: &T::State,
+) -> Result<(T, Option<(CompiledModuleInfo, ModuleTypes)>)> {
+    let compiler = engine
+        .tier_up_compiler()
+        .context("engine is not configured for tiered compilation")?;
+    let hot = (compiler, StaticModuleIndex::from_u32(0), hot);
+    build_module_artifacts(engine, wasm, None, None, Some(hot), obj_state)
+}
+
+/// Functions within one module which are compiled with a different compiler
+/// than the rest of a module or component, used to recompile hot functions
+/// with Cranelift when tiering.
+type HotFunctions<'a> = (
+    &'a dyn Compiler,
+    StaticModuleIndex,
+    &'a BTreeSet<DefinedFuncIndex>,
+);
+
+fn build_module_artifacts<'a, T: FinishedObject>(
+    engine: &Engine,
+    wasm: &'a [u8],
+    dwarf_package: Option<&[u8]>,
+    profile_data: Option<&'a ProfileData>,
+    hot: Option<HotFunctions<'a>>,
     obj_state: &T::State,
 ) -> Result<(T, Option<(CompiledModuleInfo, ModuleTypes)>)> {
     let tunables = engine.tunables();
+    check_profile_data(profile_data, wasm)?;
 
     // First a `ModuleEnvironment` is created which records type information
     // about the wasm module. This is where the WebAssembly is parsed and
@@ -79,9 +124,10 @@ pub(crate) fn build_artifacts<T: FinishedObject>(
     let mut translation = ModuleEnvironment::new(tunables, &mut validator, &mut types)
         .translate(parser, wasm)
         .context("failed to parse WebAssembly module")?;
+    translation.profile_data = profile_data;
     let functions = mem::take(&mut translation.function_body_inputs);
 
-    let compile_inputs = CompileInputs::for_module(&types, &translation, functions);
+    let compile_inputs = CompileInputs::for_module(&types, &translation, functions, hot);
     let unlinked_compile_outputs = compile_inputs.compile(engine)?;
     let (compiled_funcs, function_indices) = unlinked_compile_outputs.pre_link();
 
@@ -99,6 +145,10 @@ pub(crate) fn build_artifacts<T: FinishedObject>(
     // it's left as an exercise for later.
     engine.append_compiler_info(&mut object);
     engine.append_bti(&mut object);
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    if hot.is_none() {
+        engine.append_tier_up_wasm(&mut object, wasm);
+    }
 
     let (mut object, compilation_artifacts) = function_indices.link_and_append_code(
         &types,
@@ -128,7 +178,57 @@ pub(crate) fn build_artifacts<T: FinishedObject>(
 pub(crate) fn build_component_artifacts<T: FinishedObject>(
     engine: &Engine,
     binary: &[u8],
+    dwarf_package: Option<&[u8]>,
+    profile_data: Option<&ProfileData>,
+    obj_state: &T::State,
+) -> Result<(T, Option<wasmtime_environ::component::ComponentArtifacts>)> {
+    check_profile_data(profile_data, binary)?;
+    build_component_artifacts_impl(engine, binary, dwarf_package, profile_data, None, obj_state)
+}
+
+/// Checks that `profile_data`, if any, was collected from the `wasm` binary
+/// being compiled.
+fn check_profile_data(profile_data: Option<&ProfileData>, wasm: &[u8]) -> Result<()> {
+    match profile_data {
+        Some(profile) if !profile.matches(wasm) => {
+            bail!("profile data was collected from a different wasm binary")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Same as [`build_component_artifacts`], but compiles the `hot` functions of
+/// the core wasm module `module` within the component with the Cranelift
+/// compiler of an engine using [`Strategy::Tiered`](crate::Strategy::Tiered)
+/// instead of its Winch compiler.
+#[cfg(all(feature = "component-model", feature = "cranelift", feature = "winch"))]
+pub(crate) fn build_tier_up_component_artifacts<T: FinishedObject>(
+    engine: &Engine,
+    binary: &[u8],
+    module: StaticModuleIndex,
+    hot: &BTreeSet<DefinedFuncIndex>,
+    obj_state: &T::State,
+) -> Result<(T, Option<wasmtime_environ::component::ComponentArtifacts>)> {
+    let compiler = engine
+        .tier_up_compiler()
+        .context("engine is not configured for tiered compilation")?;
+    build_component_artifacts_impl(
+        engine,
+        binary,
+        None,
+        None,
+        Some((compiler, module, hot)),
+        obj_state,
+    )
+}
+
+#[cfg(feature = "component-model")]
+fn build_component_artifacts_impl<'a, T: FinishedObject>(
+    engine: &Engine,
+    binary: &'a [u8],
     _dwarf_package: Option<&[u8]>,
+    profile_data: Option<&'a ProfileData>,
+    hot: Option<HotFunctions<'a>>,
     obj_state: &T::State,
 ) -> Result<(T, Option<wasmtime_environ::component::ComponentArtifacts>)> {
     use wasmtime_environ::component::{
@@ -147,14 +247,27 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
             .translate(binary)
             .context("failed to parse WebAssembly module")?;
 
+    // Modules generated for fused adapters aren't part of `binary`, so their
+    // offsets aren't covered by its profile.
+    let binary_range = binary.as_ptr_range();
+    let from_binary = module_translations
+        .iter()
+        .filter(|(_, translation)| binary_range.contains(&translation.wasm.as_ptr()))
+        .map(|(i, _)| i)
+        .collect::<HashSet<_>>();
+
     let compile_inputs = CompileInputs::for_component(
         engine,
         &types,
         &component,
         module_translations.iter_mut().map(|(i, translation)| {
+            if from_binary.contains(&i) {
+                translation.profile_data = profile_data;
+            }
             let functions = mem::take(&mut translation.function_body_inputs);
             (i, &*translation, functions)
         }),
+        hot,
     );
     let unlinked_compile_outputs = compile_inputs.compile(&engine)?;
 
@@ -163,6 +276,10 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
     let mut object = compiler.object(ObjectKind::Component)?;
     engine.append_compiler_info(&mut object);
     engine.append_bti(&mut object);
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    if hot.is_none() {
+        engine.append_tier_up_wasm(&mut object, binary);
+    }
 
     let (mut object, compilation_artifacts) = function_indices.link_and_append_code(
         types.module_types_builder(),
@@ -174,17 +291,27 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
     )?;
     let (types, ty) = types.finish(&component.component);
 
+    // The offsets within the component's own core modules are relative to
+    // the component, so that's the binary any profile of them is collected
+    // from.
+    let mut static_modules = compilation_artifacts.modules;
+    let wasm_hash = ProfileData::hash_wasm(binary);
+    for i in from_binary {
+        static_modules[i].meta.wasm_hash = wasm_hash;
+    }
+
     let info = CompiledComponentInfo {
         component: component.component,
         trampolines: compilation_artifacts.trampolines,
         resource_drop_wasm_to_array_trampoline: compilation_artifacts
             .resource_drop_wasm_to_array_trampoline,
+        wasm_hash,
     };
     let artifacts = ComponentArtifacts {
         info,
         ty,
         types,
-        static_modules: compilation_artifacts.modules,
+        static_modules,
     };
     object.serialize_info(&artifacts);
 
@@ -338,11 +465,12 @@ impl<'a> CompileInputs<'a> {
         types: &'a ModuleTypesBuilder,
         translation: &'a ModuleTranslation<'a>,
         functions: PrimaryMap<DefinedFuncIndex, FunctionBodyData<'a>>,
+        hot: Option<HotFunctions<'a>>,
     ) -> Self {
         let mut ret = CompileInputs { inputs: vec![] };
 
         let module_index = StaticModuleIndex::from_u32(0);
-        ret.collect_inputs_in_translations(types, [(module_index, translation, functions)]);
+        ret.collect_inputs_in_translations(types, [(module_index, translation, functions)], hot);
 
         ret
     }
@@ -360,10 +488,11 @@ impl<'a> CompileInputs<'a> {
                 PrimaryMap<DefinedFuncIndex, FunctionBodyData<'a>>,
             ),
         >,
+        hot: Option<HotFunctions<'a>>,
     ) -> Self {
         let mut ret = CompileInputs { inputs: vec![] };
 
-        ret.collect_inputs_in_translations(types.module_types_builder(), module_translations);
+        ret.collect_inputs_in_translations(types.module_types_builder(), module_translations, hot);
         let tunables = engine.tunables();
 
         for (idx, trampoline) in component.trampolines.iter() {
@@ -444,10 +573,17 @@ impl<'a> CompileInputs<'a> {
                 PrimaryMap<DefinedFuncIndex, FunctionBodyData<'a>>,
             ),
         >,
+        hot: Option<HotFunctions<'a>>,
     ) {
         for (module, translation, functions) in translations {
             for (def_func_index, func_body) in functions {
+                let hot_compiler = hot
+                    .filter(|(_, hot_module, funcs)| {
+                        *hot_module == module && funcs.contains(&def_func_index)
+                    })
+                    .map(|(compiler, _, _)| compiler);
                 self.push_input(move |compiler| {
+                    let compiler = hot_compiler.unwrap_or(compiler);
                     let func_index = translation.module.func_index(def_func_index);
                     let (info, function) =
                         compiler.compile_function(translation, def_func_index, func_body, types)?;
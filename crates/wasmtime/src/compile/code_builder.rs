@@ -2,6 +2,7 @@ use crate::prelude::*;
 use crate::Engine;
 use std::borrow::Cow;
 use std::path::Path;
+use wasmtime_environ::ProfileData;
 
 /// Builder-style structure used to create a [`Module`](crate::module::Module) or
 /// pre-compile a module to a serialized list of bytes.
@@ -40,6 +41,7 @@ pub struct CodeBuilder<'a> {
     wasm_path: Option<Cow<'a, Path>>,
     dwarf_package: Option<Cow<'a, [u8]>>,
     dwarf_package_path: Option<Cow<'a, Path>>,
+    profile_data: Option<&'a ProfileData>,
 }
 
 /// Return value of [`CodeBuilder::hint`]
@@ -60,6 +62,7 @@ impl<'a> CodeBuilder<'a> {
             wasm_path: None,
             dwarf_package: None,
             dwarf_package_path: None,
+            profile_data: None,
         }
     }
 
@@ -236,6 +239,32 @@ impl<'a> CodeBuilder<'a> {
         Ok(self)
     }
 
+    /// Configures a profile of the module's execution to guide how it's
+    /// compiled.
+    ///
+    /// Cranelift uses the profile to lay out code which was never sampled
+    /// away from code which was, and to prefer inlining calls which were
+    /// sampled when [`Config::cranelift_inlining`] is enabled. Register
+    /// allocation isn't affected by the profile. Profiles can be collected
+    /// with [`GuestProfiler::profile_data`], or with
+    /// [`GuestProfiler::component_profile_data`] for components.
+    ///
+    /// The profile must have been collected from a module or component
+    /// compiled from the same wasm binary as this one, otherwise compilation
+    /// fails.
+    ///
+    /// [`Config::cranelift_inlining`]: crate::Config::cranelift_inlining
+    /// [`GuestProfiler::profile_data`]: crate::GuestProfiler::profile_data
+    /// [`GuestProfiler::component_profile_data`]: crate::GuestProfiler::component_profile_data
+    pub fn profile_data(&mut self, profile_data: &'a ProfileData) -> &mut Self {
+        self.profile_data = Some(profile_data);
+        self
+    }
+
+    pub(super) fn get_profile_data(&self) -> Option<&'a ProfileData> {
+        self.profile_data
+    }
+
     /// Returns a hint, if possible, of what the provided bytes are.
     ///
     /// This method can be use to detect what the previously supplied bytes to
@@ -274,7 +303,13 @@ impl<'a> CodeBuilder<'a> {
     pub fn compile_module_serialized(&self) -> Result<Vec<u8>> {
         let wasm = self.get_wasm()?;
         let dwarf_package = self.get_dwarf_package();
-        let (v, _) = super::build_artifacts(self.engine, &wasm, dwarf_package.as_deref(), &())?;
+        let (v, _) = super::build_artifacts(
+            self.engine,
+            &wasm,
+            dwarf_package.as_deref(),
+            self.get_profile_data(),
+            &(),
+        )?;
         Ok(v)
     }
 
@@ -284,7 +319,13 @@ impl<'a> CodeBuilder<'a> {
     #[cfg(feature = "component-model")]
     pub fn compile_component_serialized(&self) -> Result<Vec<u8>> {
         let bytes = self.get_wasm()?;
-        let (v, _) = super::build_component_artifacts(self.engine, &bytes, None, &())?;
+        let (v, _) = super::build_component_artifacts(
+            self.engine,
+            &bytes,
+            None,
+            self.get_profile_data(),
+            &(),
+        )?;
         Ok(v)
     }
 }
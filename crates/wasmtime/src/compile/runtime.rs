@@ -5,8 +5,12 @@ use crate::prelude::*;
 use crate::runtime::vm::MmapVec;
 use crate::{CodeBuilder, CodeMemory, Engine, Module};
 use object::write::WritableBuffer;
+#[cfg(all(feature = "cranelift", feature = "winch"))]
+use std::collections::BTreeSet;
 use std::sync::Arc;
-use wasmtime_environ::{FinishedObject, ObjectBuilder, ObjectKind};
+#[cfg(all(feature = "cranelift", feature = "winch"))]
+use wasmtime_environ::{DefinedFuncIndex, StaticModuleIndex};
+use wasmtime_environ::{FinishedObject, ObjectBuilder, ObjectKind, ProfileData};
 
 impl<'a> CodeBuilder<'a> {
     fn compile_cached<T, S>(
@@ -15,12 +19,14 @@ impl<'a> CodeBuilder<'a> {
             &Engine,
             &[u8],
             Option<&[u8]>,
+            Option<&ProfileData>,
             &S,
         ) -> Result<(MmapVecWrapper, Option<T>)>,
         state: &S,
     ) -> Result<(Arc<CodeMemory>, Option<T>)> {
         let wasm = self.get_wasm()?;
         let dwarf_package = self.get_dwarf_package();
+        let profile_data = self.get_profile_data();
 
         self.engine
             .check_compatible_with_native_host()
@@ -32,6 +38,7 @@ impl<'a> CodeBuilder<'a> {
                 HashedEngineCompileEnv(self.engine),
                 &wasm,
                 &dwarf_package,
+                profile_data,
                 // Don't hash this as it's just its own "pure" function pointer.
                 NotHashed(build_artifacts),
                 // Don't hash the FinishedObject state: this contains
@@ -39,43 +46,49 @@ impl<'a> CodeBuilder<'a> {
                 // not impact the compilation result itself.
                 NotHashed(state),
             );
-            let (code, info_and_types) =
-                wasmtime_cache::ModuleCacheEntry::new("wasmtime", self.engine.cache_config())
-                    .get_data_raw(
-                        &state,
-                        // Cache miss, compute the actual artifacts
-                        |(engine, wasm, dwarf_package, build_artifacts, state)| -> Result<_> {
-                            let (mmap, info) = (build_artifacts.0)(
-                                engine.0,
-                                wasm,
-                                dwarf_package.as_deref(),
-                                state.0,
-                            )?;
-                            let code = publish_mmap(engine.0, mmap.0)?;
-                            Ok((code, info))
-                        },
-                        // Implementation of how to serialize artifacts
-                        |(_engine, _wasm, _, _, _), (code, _info_and_types)| {
-                            Some(code.mmap().to_vec())
-                        },
-                        // Cache hit, deserialize the provided artifacts
-                        |(engine, wasm, _, _, _), serialized_bytes| {
-                            let kind = if wasmparser::Parser::is_component(&wasm) {
-                                ObjectKind::Component
-                            } else {
-                                ObjectKind::Module
-                            };
-                            let code = engine.0.load_code_bytes(&serialized_bytes, kind).ok()?;
-                            Some((code, None))
-                        },
+            let (code, info_and_types) = wasmtime_cache::ModuleCacheEntry::new(
+                "wasmtime",
+                self.engine.cache_config(),
+            )
+            .get_data_raw(
+                &state,
+                // Cache miss, compute the actual artifacts
+                |(engine, wasm, dwarf_package, profile_data, build_artifacts, state)| -> Result<_> {
+                    let (mmap, info) = (build_artifacts.0)(
+                        engine.0,
+                        wasm,
+                        dwarf_package.as_deref(),
+                        *profile_data,
+                        state.0,
                     )?;
+                    let code = publish_mmap(engine.0, mmap.0)?;
+                    Ok((code, info))
+                },
+                // Implementation of how to serialize artifacts
+                |(_engine, _wasm, _, _, _, _), (code, _info_and_types)| Some(code.mmap().to_vec()),
+                // Cache hit, deserialize the provided artifacts
+                |(engine, wasm, _, _, _, _), serialized_bytes| {
+                    let kind = if wasmparser::Parser::is_component(&wasm) {
+                        ObjectKind::Component
+                    } else {
+                        ObjectKind::Module
+                    };
+                    let code = engine.0.load_code_bytes(&serialized_bytes, kind).ok()?;
+                    Some((code, None))
+                },
+            )?;
             return Ok((code, info_and_types));
         }
 
         #[cfg(not(feature = "cache"))]
         {
-            let (mmap, info_and_types) =
-                build_artifacts(self.engine, &wasm, dwarf_package.as_deref(), state)?;
+            let (mmap, info_and_types) = build_artifacts(
+                self.engine,
+                &wasm,
+                dwarf_package.as_deref(),
+                profile_data,
+                state,
+            )?;
             let code = publish_mmap(self.engine, mmap.0)?;
             return Ok((code, info_and_types));
         }
@@ -110,13 +123,52 @@ impl<'a> CodeBuilder<'a> {
     }
 
     fn custom_alignment(&self) -> CustomAlignment {
-        CustomAlignment {
-            alignment: self
-                .engine
-                .custom_code_memory()
-                .map(|c| c.required_alignment())
-                .unwrap_or(1),
+        CustomAlignment::new(self.engine)
+    }
+}
+
+/// Recompiles the `hot` functions of a module with the Cranelift compiler of
+/// an engine using [`Strategy::Tiered`](crate::Strategy::Tiered), compiling
+/// all of its other functions with Winch again.
+///
+/// The `wasm` is either the module's original binary or, if `module_in_component`
+/// is specified, the binary of the component the module was found in.
+///
+/// Unlike [`CodeBuilder::compile_module`] this never consults the compilation
+/// cache.
+#[cfg(all(feature = "cranelift", feature = "winch"))]
+pub(crate) fn compile_tier_up(
+    engine: &Engine,
+    wasm: &[u8],
+    module_in_component: Option<StaticModuleIndex>,
+    hot: &BTreeSet<DefinedFuncIndex>,
+) -> Result<Module> {
+    let custom_alignment = CustomAlignment::new(engine);
+    match module_in_component {
+        None => {
+            let (mmap, info_and_types) = super::build_tier_up_artifacts::<MmapVecWrapper>(
+                engine,
+                wasm,
+                hot,
+                &custom_alignment,
+            )?;
+            let code = publish_mmap(engine, mmap.0)?;
+            Module::from_parts(engine, code, info_and_types)
+        }
+        #[cfg(feature = "component-model")]
+        Some(module) => {
+            let (mmap, artifacts) = super::build_tier_up_component_artifacts::<MmapVecWrapper>(
+                engine,
+                wasm,
+                module,
+                hot,
+                &custom_alignment,
+            )?;
+            let code = publish_mmap(engine, mmap.0)?;
+            Component::static_module_from_parts(engine, code, artifacts, module)
         }
+        #[cfg(not(feature = "component-model"))]
+        Some(_) => unreachable!(),
     }
 }
 
@@ -134,6 +186,17 @@ pub(crate) struct CustomAlignment {
     alignment: usize,
 }
 
+impl CustomAlignment {
+    fn new(engine: &Engine) -> CustomAlignment {
+        CustomAlignment {
+            alignment: engine
+                .custom_code_memory()
+                .map(|c| c.required_alignment())
+                .unwrap_or(1),
+        }
+    }
+}
+
 impl FinishedObject for MmapVecWrapper {
     type State = CustomAlignment;
     fn finish_object(obj: ObjectBuilder<'_>, align: &CustomAlignment) -> Result<Self> {
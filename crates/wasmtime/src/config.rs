@@ -129,6 +129,10 @@ pub struct Config {
     target: Option<target_lexicon::Triple>,
     #[cfg(feature = "gc")]
     collector: Collector,
+    #[cfg(feature = "gc")]
+    pub(crate) gc_heap_initial_size: usize,
+    #[cfg(feature = "gc")]
+    pub(crate) gc_heap_maximum_size: usize,
     profiling_strategy: ProfilingStrategy,
     tunables: ConfigTunables,
 
@@ -167,6 +171,7 @@ pub struct Config {
     pub(crate) coredump_on_trap: bool,
     pub(crate) macos_use_mach_ports: bool,
     pub(crate) detect_host_feature: Option<fn(&str) -> Option<bool>>,
+    pub(crate) deterministic: bool,
 }
 
 /// User-provided configuration for the compiler.
@@ -180,6 +185,7 @@ struct CompilerConfig {
     cache_store: Option<Arc<dyn CacheStore>>,
     clif_dir: Option<std::path::PathBuf>,
     wmemcheck: bool,
+    tier_up_samples: u32,
 }
 
 #[cfg(any(feature = "cranelift", feature = "winch"))]
@@ -193,6 +199,7 @@ impl CompilerConfig {
             cache_store: None,
             clif_dir: None,
             wmemcheck: false,
+            tier_up_samples: 10,
         }
     }
 
@@ -234,6 +241,10 @@ impl Config {
             target: None,
             #[cfg(feature = "gc")]
             collector: Collector::default(),
+            #[cfg(feature = "gc")]
+            gc_heap_initial_size: DEFAULT_GC_HEAP_INITIAL_SIZE,
+            #[cfg(feature = "gc")]
+            gc_heap_maximum_size: DEFAULT_GC_HEAP_MAXIMUM_SIZE,
             #[cfg(feature = "cache")]
             cache_config: CacheConfig::new_cache_disabled(),
             profiling_strategy: ProfilingStrategy::None,
@@ -274,6 +285,7 @@ impl Config {
             detect_host_feature: Some(detect_host_feature),
             #[cfg(not(feature = "std"))]
             detect_host_feature: None,
+            deterministic: false,
         };
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         {
@@ -925,6 +937,55 @@ impl Config {
         self
     }
 
+    /// Configures whether the [WebAssembly exception-handling
+    /// proposal][proposal] will be enabled for compilation.
+    ///
+    /// This feature gates tags, the `exnref` type, and the `try_table`,
+    /// `throw`, and `throw_ref` instructions. Exceptions can also be thrown
+    /// and caught by the host with [`Tag`](crate::Tag) and
+    /// [`ThrownException`](crate::ThrownException). Exceptions are allocated
+    /// in the GC heap, so this requires the `gc` feature.
+    ///
+    /// The legacy exception-handling instructions (`try`, `catch`, `rethrow`,
+    /// and `delegate`) are not supported.
+    ///
+    /// This feature is `false` by default.
+    ///
+    /// [proposal]: https://github.com/WebAssembly/exception-handling
+    #[cfg(feature = "gc")]
+    pub fn wasm_exceptions(&mut self, enable: bool) -> &mut Self {
+        self.wasm_feature(WasmFeatures::EXCEPTIONS, enable);
+        self
+    }
+
+    /// Configures whether the [WebAssembly stack-switching
+    /// proposal][proposal] will be enabled for compilation.
+    ///
+    /// This feature gates continuation types and the `cont.new`, `cont.bind`,
+    /// `resume`, `resume_throw`, `suspend`, and `switch` instructions. Each
+    /// continuation runs on its own stack of
+    /// [`Config::async_stack_size`] bytes, which is allocated by the engine's
+    /// instance allocator when the continuation is first resumed and is
+    /// subject to
+    /// [`ResourceLimiter::fiber_stack_allocating`](crate::ResourceLimiter::fiber_stack_allocating).
+    ///
+    /// Suspending a continuation through host frames is not supported: a
+    /// `suspend` or `switch` whose handler is only reachable through a host
+    /// function traps as if no handler existed.
+    ///
+    /// This requires [`Config::wasm_function_references`] and
+    /// [`Config::wasm_exceptions`] to be enabled and is not compatible with
+    /// [`Config::async_support`].
+    ///
+    /// This feature is `false` by default.
+    ///
+    /// [proposal]: https://github.com/WebAssembly/stack-switching
+    #[cfg(feature = "stack-switching")]
+    pub fn wasm_stack_switching(&mut self, enable: bool) -> &mut Self {
+        self.wasm_feature(WasmFeatures::STACK_SWITCHING, enable);
+        self
+    }
+
     /// Configures whether the WebAssembly SIMD proposal will be
     /// enabled for compilation.
     ///
@@ -994,6 +1055,44 @@ impl Config {
         self
     }
 
+    /// Configures whether compiled code is required to execute
+    /// deterministically, producing bit-for-bit identical results across all
+    /// hosts and platforms.
+    ///
+    /// WebAssembly is almost entirely deterministic, but there are a few
+    /// well-known sources of nondeterminism in the specification. When this
+    /// option is enabled Wasmtime will close all of them:
+    ///
+    /// * NaN values produced by floating-point operations are canonicalized,
+    ///   as with [`Config::cranelift_nan_canonicalization`].
+    /// * Relaxed SIMD instructions use their deterministic lowering, as with
+    ///   [`Config::relaxed_simd_deterministic`].
+    /// * The [threads proposal][threads] is disabled by default, since shared
+    ///   memories can be observed racing with other threads.
+    ///
+    /// Configurations which contradict deterministic execution are rejected
+    /// when an [`Engine`](crate::Engine) is created. This includes explicitly
+    /// enabling [`Config::wasm_threads`], explicitly disabling
+    /// [`Config::relaxed_simd_deterministic`] or
+    /// [`Config::cranelift_nan_canonicalization`], or selecting the Winch
+    /// compiler, which does not canonicalize NaNs. Precompiled modules are
+    /// additionally only loaded if they were compiled with NaN
+    /// canonicalization enabled.
+    ///
+    /// Note that this only covers the execution of WebAssembly itself. Any
+    /// host functions, including WASI, must also behave deterministically for
+    /// a guest as a whole to do so. Resource exhaustion, such as running out
+    /// of stack or failing to grow a memory, can also differ between hosts and
+    /// should be bounded with identical limits on all of them.
+    ///
+    /// This is `false` by default.
+    ///
+    /// [threads]: https://github.com/webassembly/threads
+    pub fn deterministic(&mut self, enable: bool) -> &mut Self {
+        self.deterministic = enable;
+        self
+    }
+
     /// Configures whether the [WebAssembly bulk memory operations
     /// proposal][proposal] will be enabled for compilation.
     ///
@@ -1052,6 +1151,17 @@ impl Config {
     /// may also have bugs for this feature since it hasn't been exercised
     /// much.
     ///
+    /// Accesses to 64-bit memories generally require explicit bounds checks,
+    /// as their indices can't be covered by a virtual memory reservation.
+    /// The exception is indices that are zero-extended from 32-bit values,
+    /// whose bounds checks are elided like those of 32-bit memories when
+    /// [`Config::memory_reservation`] is at least 4GiB.
+    ///
+    /// When using the pooling allocator, 64-bit memories may grow beyond 4GiB
+    /// if [`PoolingAllocationConfig::max_memory_size`] and
+    /// [`Config::memory_reservation`] are both configured to be larger than
+    /// that.
+    ///
     /// This is `false` by default.
     ///
     /// [proposal]: https://github.com/webassembly/memory64
@@ -1137,6 +1247,31 @@ impl Config {
         self
     }
 
+    /// Configures how many times a function must be sampled before it is
+    /// recompiled with Cranelift when using [`Strategy::Tiered`].
+    ///
+    /// Functions compiled with [`Strategy::Tiered`] are sampled each time a
+    /// [`Store`](crate::Store) reaches its epoch deadline, see
+    /// [`Config::epoch_interruption`], by counting the function that was
+    /// running at the time. Once a function has been sampled this many times
+    /// it's recompiled with Cranelift on a background thread. Afterwards new
+    /// instances of its module call the Cranelift-compiled function, and
+    /// existing instances switch to it the next time they reach their epoch
+    /// deadline. See [`Module::is_tiered_up`](crate::Module::is_tiered_up).
+    ///
+    /// This has no effect for other strategies. The default value for this is
+    /// 10 and a value of 0 is treated the same as 1.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub fn tier_up_samples(&mut self, samples: u32) -> &mut Self {
+        self.compiler_config.tier_up_samples = samples;
+        self
+    }
+
+    #[cfg(all(feature = "runtime", feature = "cranelift", feature = "winch"))]
+    pub(crate) fn get_tier_up_samples(&self) -> u32 {
+        self.compiler_config.tier_up_samples.max(1)
+    }
+
     /// Configures which garbage collector will be used for Wasm modules.
     ///
     /// This method can be used to configure which garbage collector
@@ -1150,6 +1285,42 @@ impl Config {
         self
     }
 
+    /// Configures the initial size, in bytes, of each store's GC heap.
+    ///
+    /// A store's GC heap starts out at this size and is grown, up to
+    /// [`Config::gc_heap_maximum_size`], when an allocation fails even after
+    /// a garbage collection. Each growth is first reported to the store's
+    /// [`ResourceLimiter::gc_heap_growing`](crate::ResourceLimiter::gc_heap_growing),
+    /// if any, which may deny it.
+    ///
+    /// This value is rounded up to the host's page size.
+    ///
+    /// The default value for this is 512KiB.
+    #[cfg(feature = "gc")]
+    pub fn gc_heap_initial_size(&mut self, bytes: usize) -> &mut Self {
+        self.gc_heap_initial_size = bytes;
+        self
+    }
+
+    /// Configures the maximum size, in bytes, that each store's GC heap may
+    /// grow to.
+    ///
+    /// This much virtual memory is reserved up front for each GC heap, so that
+    /// growing a GC heap never moves it. Only the portion of the reservation
+    /// that is actually in use by the heap is made accessible. This value
+    /// must be at least [`Config::gc_heap_initial_size`] and cannot exceed
+    /// 4GiB, since GC references are 32-bit indices into the heap.
+    ///
+    /// This value is rounded up to the host's page size.
+    ///
+    /// The default value for this is 1GiB on 64-bit platforms and 512KiB, i.e.
+    /// no growth, on other platforms.
+    #[cfg(feature = "gc")]
+    pub fn gc_heap_maximum_size(&mut self, bytes: usize) -> &mut Self {
+        self.gc_heap_maximum_size = bytes;
+        self
+    }
+
     /// Creates a default profiler based on the profiling strategy chosen.
     ///
     /// Profiler creation calls the type's default initializer where the purpose is
@@ -1271,6 +1442,58 @@ impl Config {
         self
     }
 
+    /// Configures whether Cranelift inlines small functions into their
+    /// callers.
+    ///
+    /// When enabled, direct calls between functions defined in the same
+    /// module are candidates for inlining: the callee's body is copied into
+    /// the caller before the caller is optimized, removing the overhead of the
+    /// call and allowing the callee to be optimized along with the caller.
+    /// This is mostly useful for the small accessor and wrapper functions
+    /// which toolchains frequently emit. Which calls get inlined is controlled
+    /// by [`Config::cranelift_inlining_small_callee_size`] and
+    /// [`Config::cranelift_inlining_sum_size_threshold`].
+    ///
+    /// Traps and backtraces within inlined code still report the inlined
+    /// function, as a frame of its own, followed by a frame for the caller it
+    /// was inlined into.
+    ///
+    /// Inlining is not performed when native DWARF debug information is
+    /// generated, and has no effect when Winch is used.
+    ///
+    /// The default value for this is `false`.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub fn cranelift_inlining(&mut self, enable: bool) -> &mut Self {
+        self.tunables.inlining = Some(enable);
+        self
+    }
+
+    /// Configures the size, in bytes of wasm, under which functions are
+    /// always inlined into their callers when
+    /// [`Config::cranelift_inlining`] is enabled.
+    ///
+    /// The default value for this is 50.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub fn cranelift_inlining_small_callee_size(&mut self, size: u32) -> &mut Self {
+        self.tunables.inlining_small_callee_size = Some(size);
+        self
+    }
+
+    /// Configures how large, in bytes of wasm, a function may grow by
+    /// inlining functions which are larger than
+    /// [`Config::cranelift_inlining_small_callee_size`] into it.
+    ///
+    /// Such functions are only inlined if the size of the caller, plus
+    /// everything already inlined into it, plus the size of the callee is at
+    /// most this threshold.
+    ///
+    /// The default value for this is 2000.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub fn cranelift_inlining_sum_size_threshold(&mut self, size: u32) -> &mut Self {
+        self.tunables.inlining_sum_size_threshold = Some(size);
+        self
+    }
+
     /// Allows setting a Cranelift boolean flag or preset. This allows
     /// fine-tuning of Cranelift settings.
     ///
@@ -2023,20 +2246,30 @@ impl Config {
                 // Pulley at this time fundamentally doesn't support the
                 // `threads` proposal, notably shared memory, because Rust can't
                 // safely implement loads/stores in the face of shared memory.
+                //
+                // Stack switching isn't supported either, since the
+                // interpreter's own stack isn't switched along with the native
+                // one.
                 if self.compiler_target().is_pulley() {
-                    return WasmFeatures::THREADS;
+                    return WasmFeatures::THREADS | WasmFeatures::STACK_SWITCHING;
                 }
 
                 // Other Cranelift backends are either 100% missing or complete
                 // at this time, so no need to further filter.
                 WasmFeatures::empty()
             }
-            Some(Strategy::Winch) => {
+            // Everything must be compiled with Winch first when tiering.
+            //
+            // GC, function references, relaxed SIMD and tail calls are out of
+            // scope for Winch at this time, see `docs/stability-tiers.md`.
+            Some(Strategy::Winch) | Some(Strategy::Tiered) => {
                 let mut unsupported = WasmFeatures::GC
                     | WasmFeatures::FUNCTION_REFERENCES
                     | WasmFeatures::RELAXED_SIMD
                     | WasmFeatures::TAIL_CALL
-                    | WasmFeatures::GC_TYPES;
+                    | WasmFeatures::GC_TYPES
+                    | WasmFeatures::EXCEPTIONS
+                    | WasmFeatures::STACK_SWITCHING;
                 match self.compiler_target().architecture {
                     target_lexicon::Architecture::Aarch64(_) => {
                         // no support for simd on aarch64
@@ -2049,9 +2282,16 @@ impl Config {
                         unsupported |= WasmFeatures::THREADS
                     }
 
-                    // Winch doesn't support other non-x64 architectures at this
-                    // time either but will return an first-class error for
-                    // them.
+                    target_lexicon::Architecture::Riscv64(_) => {
+                        // no support for simd, atomics or 128-bit arithmetic
+                        // on riscv64
+                        unsupported |= WasmFeatures::SIMD;
+                        unsupported |= WasmFeatures::THREADS;
+                        unsupported |= WasmFeatures::WIDE_ARITHMETIC;
+                    }
+
+                    // Winch doesn't support other architectures at this time
+                    // either but will return an first-class error for them.
                     _ => {}
                 }
                 unsupported
@@ -2093,7 +2333,10 @@ impl Config {
         // Set some features to their conditionally-enabled defaults depending
         // on crate compile-time features.
         features.set(WasmFeatures::GC_TYPES, cfg!(feature = "gc"));
-        features.set(WasmFeatures::THREADS, cfg!(feature = "threads"));
+        features.set(
+            WasmFeatures::THREADS,
+            cfg!(feature = "threads") && !self.deterministic,
+        );
         features.set(
             WasmFeatures::COMPONENT_MODEL,
             cfg!(feature = "component-model"),
@@ -2160,6 +2403,15 @@ impl Config {
         if self.max_wasm_stack == 0 {
             bail!("max_wasm_stack size cannot be zero");
         }
+        #[cfg(feature = "gc")]
+        {
+            if self.gc_heap_initial_size > self.gc_heap_maximum_size {
+                bail!("gc_heap_initial_size cannot exceed the gc_heap_maximum_size");
+            }
+            if u64::try_from(self.gc_heap_maximum_size).unwrap() > 1 << 32 {
+                bail!("gc_heap_maximum_size cannot exceed 4GiB");
+            }
+        }
         #[cfg(not(feature = "wmemcheck"))]
         if self.wmemcheck {
             bail!("wmemcheck (memory checker) was requested but is not enabled in this build");
@@ -2186,10 +2438,39 @@ impl Config {
 
         self.tunables.configure(&mut tunables);
 
+        if self.deterministic {
+            if features.contains(WasmFeatures::THREADS) {
+                bail!("the wasm_threads feature is not compatible with deterministic execution");
+            }
+            if self.tunables.relaxed_simd_deterministic == Some(false) {
+                bail!("relaxed_simd_deterministic cannot be disabled with deterministic execution");
+            }
+            #[cfg(any(feature = "cranelift", feature = "winch"))]
+            if matches!(
+                self.compiler_config.strategy,
+                Some(Strategy::Winch | Strategy::Tiered)
+            ) {
+                bail!("deterministic execution is not supported by the Winch compiler");
+            }
+            tunables.relaxed_simd_deterministic = true;
+        }
+
+        // Hot functions are found by sampling when the epoch deadline is
+        // reached, so tiering is pointless without epoch interruption.
+        #[cfg(any(feature = "cranelift", feature = "winch"))]
+        if self.compiler_config.strategy == Some(Strategy::Tiered) && !tunables.epoch_interruption {
+            bail!("tiered compilation requires epoch interruption to be enabled");
+        }
+
         // If we're going to compile with winch, we must use the winch calling convention.
+        // This includes code compiled with Cranelift when tiering so it can
+        // freely call, and be called from, Winch-compiled code.
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         {
-            tunables.winch_callable = self.compiler_config.strategy == Some(Strategy::Winch);
+            tunables.winch_callable = matches!(
+                self.compiler_config.strategy,
+                Some(Strategy::Winch | Strategy::Tiered)
+            );
         }
 
         tunables.collector = if features.gc_types() {
@@ -2207,6 +2488,26 @@ impl Config {
         } else {
             None
         };
+        if features.contains(WasmFeatures::EXCEPTIONS) && !features.gc_types() {
+            bail!("the wasm_exceptions feature requires GC types to be enabled");
+        }
+        if features.contains(WasmFeatures::STACK_SWITCHING) {
+            if !features.contains(WasmFeatures::FUNCTION_REFERENCES) {
+                bail!(
+                    "the wasm_stack_switching feature requires function references to be enabled"
+                );
+            }
+            if !features.contains(WasmFeatures::EXCEPTIONS) {
+                bail!("the wasm_stack_switching feature requires exceptions to be enabled");
+            }
+            if self.async_support {
+                bail!("the wasm_stack_switching feature is not compatible with async support");
+            }
+            #[cfg(feature = "async")]
+            if self.max_wasm_stack > self.async_stack_size {
+                bail!("max_wasm_stack size cannot exceed the async_stack_size");
+            }
+        }
 
         // Double-check that this configuration isn't requesting capabilities
         // that this build of Wasmtime doesn't support.
@@ -2335,6 +2636,12 @@ impl Config {
             Some(Strategy::Winch) => wasmtime_winch::builder(target_for_builder)?,
             #[cfg(not(feature = "winch"))]
             Some(Strategy::Winch) => bail!("winch support not compiled in"),
+            #[cfg(all(feature = "cranelift", feature = "winch"))]
+            Some(Strategy::Tiered) => wasmtime_winch::builder(target_for_builder)?,
+            #[cfg(not(all(feature = "cranelift", feature = "winch")))]
+            Some(Strategy::Tiered) => {
+                bail!("tiered compilation requires both cranelift and winch support")
+            }
 
             None | Some(Strategy::Auto) => unreachable!(),
         };
@@ -2427,6 +2734,15 @@ impl Config {
             bail!("cannot disable the simd proposal but enable the relaxed simd proposal");
         }
 
+        if self.deterministic {
+            if !self
+                .compiler_config
+                .ensure_setting_unset_or_given("enable_nan_canonicalization", "true")
+            {
+                bail!("NaN canonicalization cannot be disabled with deterministic execution");
+            }
+        }
+
         // Apply compiler settings and flags
         for (k, v) in self.compiler_config.settings.iter() {
             compiler.set(k, v)?;
@@ -2446,6 +2762,27 @@ impl Config {
         Ok((self, compiler.build()?))
     }
 
+    /// Builds the Cranelift compiler which hot modules are recompiled with
+    /// when using [`Strategy::Tiered`], or `None` for other strategies.
+    ///
+    /// This is expected to be called on the configuration returned by
+    /// `build_compiler` with the same `tunables`, which notably make Cranelift
+    /// use the Winch calling convention.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn build_tier_up_compiler(
+        &self,
+        tunables: &Tunables,
+        features: WasmFeatures,
+    ) -> Result<Option<Box<dyn wasmtime_environ::Compiler>>> {
+        if self.compiler_config.strategy != Some(Strategy::Tiered) {
+            return Ok(None);
+        }
+        let mut config = self.clone();
+        config.compiler_config.strategy = Some(Strategy::Cranelift);
+        let (_, compiler) = config.build_compiler(tunables, features)?;
+        Ok(Some(compiler))
+    }
+
     /// Internal setting for whether adapter modules for components will have
     /// extra WebAssembly instructions inserted performing more debug checks
     /// then are necessary.
@@ -2595,6 +2932,7 @@ impl fmt::Debug for Config {
         }
 
         f.field("parallel_compilation", &self.parallel_compilation);
+        f.field("deterministic", &self.deterministic);
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         {
             f.field("compiler_config", &self.compiler_config);
@@ -2629,6 +2967,31 @@ pub enum Strategy {
     /// A baseline compiler for WebAssembly, currently under active development and not ready for
     /// production applications.
     Winch,
+
+    /// Compile modules with Winch for fast startup and recompile hot
+    /// functions with Cranelift in the background.
+    ///
+    /// Functions are found to be hot by sampling which function is running
+    /// whenever a store reaches its epoch deadline, so this strategy requires
+    /// [`Config::epoch_interruption`] to be enabled and only tiers up code
+    /// while epochs are being incremented. Functions sampled as many times as
+    /// configured with [`Config::tier_up_samples`] are recompiled with
+    /// Cranelift, after which new instances use the Cranelift-compiled
+    /// functions and existing instances switch their tables, exports and
+    /// `ref.func` values over to them the next time they reach their epoch
+    /// deadline. Frames which are already running, direct calls from
+    /// Winch-compiled functions of existing instances, and functions already
+    /// imported into other instances, keep running Winch-compiled code. Code
+    /// compiled with Cranelift uses the same calling convention as Winch, so
+    /// code of either tier can be freely linked together.
+    ///
+    /// Modules and components compiled in-process, loaded from the
+    /// compilation cache, or deserialized from artifacts compiled with this
+    /// strategy are all tiered, since their artifacts contain the original
+    /// wasm. This strategy is subject to the same limitations as
+    /// [`Strategy::Winch`] and requires both the `cranelift` and `winch`
+    /// features.
+    Tiered,
 }
 
 impl Strategy {
@@ -2648,6 +3011,20 @@ impl Strategy {
     }
 }
 
+/// The default value of [`Config::gc_heap_initial_size`]: 512KiB, or 64KiB
+/// under MIRI.
+#[cfg(feature = "gc")]
+const DEFAULT_GC_HEAP_INITIAL_SIZE: usize = if cfg!(miri) { 1 << 16 } else { 1 << 19 };
+
+/// The default value of [`Config::gc_heap_maximum_size`]: 1GiB on 64-bit
+/// platforms, and no growth beyond the initial size elsewhere.
+#[cfg(feature = "gc")]
+const DEFAULT_GC_HEAP_MAXIMUM_SIZE: usize = if cfg!(target_pointer_width = "64") && !cfg!(miri) {
+    1 << 30
+} else {
+    DEFAULT_GC_HEAP_INITIAL_SIZE
+};
+
 /// Possible garbage collector implementations for Wasm.
 ///
 /// This is used as an argument to the [`Config::collector`] method.
@@ -2657,7 +3034,7 @@ impl Strategy {
 ///
 /// | Collector                   | Collects Garbage[^1] | Latency[^2] | Throughput[^3] | Allocation Speed[^4] | Heap Utilization[^5] |
 /// |-----------------------------|----------------------|-------------|----------------|----------------------|----------------------|
-/// | `DeferredReferenceCounting` | Yes, but not cycles  | 🙂         | 🙁             | 😐                   | 😐                  |
+/// | `DeferredReferenceCounting` | Yes                  | 🙂         | 🙁             | 😐                   | 😐                  |
 /// | `Null`                      | No                   | 🙂         | 🙂             | 🙂                   | 🙂                  |
 ///
 /// [^1]: Whether or not the collector is capable of collecting garbage and cyclic garbage.
@@ -2706,8 +3083,10 @@ pub enum Collector {
     /// refcount-increment and -decrement operations. The cost is the increased
     /// latency associated with tracing the stack.
     ///
-    /// This collector cannot currently collect cycles; they will leak until the
-    /// GC heap's store is dropped.
+    /// Reference counting alone cannot collect cycles, so once the GC heap is
+    /// more than half full, collections additionally run a backup cycle
+    /// collector that traces the whole heap to find and reclaim garbage
+    /// cycles.
     DeferredReferenceCounting,
 
     /// The null collector.
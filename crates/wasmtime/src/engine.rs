@@ -53,6 +53,9 @@ struct EngineInner {
     tunables: Tunables,
     #[cfg(any(feature = "cranelift", feature = "winch"))]
     compiler: Box<dyn wasmtime_environ::Compiler>,
+    /// The compiler hot functions are recompiled with when tiering.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    tier_up_compiler: Option<Box<dyn wasmtime_environ::Compiler>>,
     #[cfg(feature = "runtime")]
     allocator: Box<dyn crate::runtime::vm::InstanceAllocator + Send + Sync>,
     #[cfg(feature = "runtime")]
@@ -117,11 +120,15 @@ impl Engine {
 
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         let (config, compiler) = config.build_compiler(&tunables, features)?;
+        #[cfg(all(feature = "cranelift", feature = "winch"))]
+        let tier_up_compiler = config.build_tier_up_compiler(&tunables, features)?;
 
         Ok(Engine {
             inner: Arc::new(EngineInner {
                 #[cfg(any(feature = "cranelift", feature = "winch"))]
                 compiler,
+                #[cfg(all(feature = "cranelift", feature = "winch"))]
+                tier_up_compiler,
                 #[cfg(feature = "runtime")]
                 allocator: config.build_allocator(&tunables)?,
                 #[cfg(feature = "runtime")]
@@ -365,6 +372,16 @@ impl Engine {
                 }
             }
 
+            // Deterministic execution relies on NaNs being canonicalized,
+            // otherwise this setting can have any value.
+            "enable_nan_canonicalization" => {
+                if self.config().deterministic {
+                    *value == FlagValue::Bool(true)
+                } else {
+                    return Ok(())
+                }
+            }
+
             // Windows requires unwind info as part of its ABI.
             "unwind_info" => {
                 if target.operating_system == target_lexicon::OperatingSystem::Windows {
@@ -379,7 +396,6 @@ impl Engine {
             // matter.
             "enable_heap_access_spectre_mitigation"
             | "enable_table_access_spectre_mitigation"
-            | "enable_nan_canonicalization"
             | "enable_jump_tables"
             | "enable_float"
             | "enable_verifier"
@@ -558,6 +574,13 @@ impl Engine {
         &*self.inner.compiler
     }
 
+    /// Returns the compiler hot functions are recompiled with, if this engine
+    /// uses [`Strategy::Tiered`](crate::Strategy::Tiered).
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn tier_up_compiler(&self) -> Option<&dyn wasmtime_environ::Compiler> {
+        self.inner.tier_up_compiler.as_deref()
+    }
+
     /// Ahead-of-time (AOT) compiles a WebAssembly module.
     ///
     /// The `bytes` provided must be in one of two formats:
@@ -619,6 +642,22 @@ impl Engine {
         };
         obj.append_section_data(section, &[contents], 1);
     }
+
+    /// Appends the original `wasm` to `obj` if this engine uses
+    /// [`Strategy::Tiered`](crate::Strategy::Tiered), so its hot functions can
+    /// be recompiled later on.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn append_tier_up_wasm(&self, obj: &mut Object<'_>, wasm: &[u8]) {
+        if self.tier_up_compiler().is_none() {
+            return;
+        }
+        let section = obj.add_section(
+            obj.segment_name(StandardSegment::Data).to_vec(),
+            obj::ELF_WASMTIME_TIER_UP.as_bytes().to_vec(),
+            SectionKind::ReadOnlyData,
+        );
+        obj.append_section_data(section, wasm, 1);
+    }
 }
 
 /// Return value from the [`Engine::detect_precompiled`] API.
@@ -657,6 +696,22 @@ impl Engine {
         self.inner.allocator.as_ref()
     }
 
+    /// Returns a snapshot of the state of this engine's pooling allocator.
+    ///
+    /// This reports, for each of the allocator's pools, how many slots are
+    /// live, unused but warm, and free, as well as how many bytes are being
+    /// kept resident, how much memory is waiting to be decommitted, and how
+    /// many allocations have failed and why.
+    ///
+    /// Returns `None` if this engine was not configured with
+    /// [`InstanceAllocationStrategy::Pooling`](crate::InstanceAllocationStrategy::Pooling).
+    #[cfg(feature = "pooling-allocator")]
+    pub fn pooling_allocator_metrics(&self) -> Option<crate::PoolingAllocatorMetrics> {
+        self.allocator()
+            .as_pooling()
+            .map(|pooling| pooling.metrics())
+    }
+
     pub(crate) fn gc_runtime(&self) -> Result<&Arc<dyn GcRuntime>> {
         if let Some(rt) = &self.inner.gc_runtime {
             Ok(rt)
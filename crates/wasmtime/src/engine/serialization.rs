@@ -204,6 +204,7 @@ struct WasmFeatures {
     component_model_multiple_returns: bool,
     component_model_async: bool,
     gc_types: bool,
+    stack_switching: bool,
     wide_arithmetic: bool,
 }
 
@@ -253,7 +254,6 @@ impl Metadata<'_> {
         assert!(!component_model_nested_names);
         assert!(!shared_everything_threads);
         assert!(!legacy_exceptions);
-        assert!(!stack_switching);
 
         Metadata {
             target: engine.compiler().triple().to_string(),
@@ -280,6 +280,7 @@ impl Metadata<'_> {
                 component_model_multiple_returns,
                 component_model_async,
                 gc_types,
+                stack_switching,
                 wide_arithmetic,
             },
         }
@@ -389,6 +390,12 @@ impl Metadata<'_> {
 
             // Just a debugging aid, doesn't affect functionality at all.
             debug_adapter_modules: _,
+
+            // Inlining is an optimization which doesn't change how compiled
+            // code interacts with the runtime.
+            inlining: _,
+            inlining_small_callee_size: _,
+            inlining_sum_size_threshold: _,
         } = self.tunables;
 
         Self::check_collector(collector, other.collector)?;
@@ -491,6 +498,7 @@ impl Metadata<'_> {
             component_model_multiple_returns,
             component_model_async,
             gc_types,
+            stack_switching,
             wide_arithmetic,
         } = self.features;
 
@@ -588,6 +596,13 @@ impl Metadata<'_> {
             other.contains(F::GC_TYPES),
             "support for WebAssembly gc types",
         )?;
+        Self::check_cfg_bool(
+            cfg!(feature = "stack-switching"),
+            "stack-switching",
+            stack_switching,
+            other.contains(F::STACK_SWITCHING),
+            "WebAssembly stack-switching support",
+        )?;
         Self::check_bool(
             wide_arithmetic,
             other.contains(F::WIDE_ARITHMETIC),
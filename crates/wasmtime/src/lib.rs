@@ -233,6 +233,15 @@
 //!   a core dump when a trap happens. This can be configured via
 //!   [`Config::coredump_on_trap`].
 //!
+//! * `snapshot` - Enabled by default, this will provide support for taking
+//!   snapshots of instances with [`Instance::snapshot`] and restoring them or
+//!   preinitializing modules from them with [`InstanceSnapshot`].
+//!
+//! * `record-replay` - Enabled by default, this will provide support for
+//!   recording the host calls made by WebAssembly with
+//!   [`Store::record_host_calls`] and replaying them without the host with
+//!   [`Store::replay_host_calls`].
+//!
 //! * `addr2line` - Enabled by default, this feature configures whether traps
 //!   will attempt to parse DWARF debug information and convert WebAssembly
 //!   addresses to source filenames and line numbers.
@@ -380,6 +389,7 @@ mod profiling_agent;
 
 pub use crate::config::*;
 pub use crate::engine::*;
+pub use wasmtime_environ::ProfileData;
 
 #[cfg(feature = "std")]
 mod sync_std;
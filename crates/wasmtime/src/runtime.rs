@@ -32,6 +32,7 @@ pub(crate) mod func;
 pub(crate) mod code;
 pub(crate) mod code_memory;
 pub(crate) mod debug;
+pub(crate) mod exception;
 pub(crate) mod externals;
 pub(crate) mod gc;
 pub(crate) mod instance;
@@ -67,6 +68,7 @@ cfg_if::cfg_if! {
 }
 
 pub use code_memory::CodeMemory;
+pub use exception::ThrownException;
 pub use externals::*;
 pub use func::*;
 pub use gc::*;
@@ -80,7 +82,8 @@ pub use resources::*;
 #[cfg(all(feature = "async", feature = "call-hook"))]
 pub use store::CallHookHandler;
 pub use store::{
-    AsContext, AsContextMut, CallHook, Store, StoreContext, StoreContextMut, UpdateDeadline,
+    AsContext, AsContextMut, CallHook, ResourceUsage, Store, StoreContext, StoreContextMut,
+    UpdateDeadline,
 };
 pub use trap::*;
 pub use types::*;
@@ -90,7 +93,9 @@ pub use values::*;
 pub(crate) use uninhabited::*;
 
 #[cfg(feature = "pooling-allocator")]
-pub use vm::PoolConcurrencyLimitError;
+pub use vm::{
+    PoolConcurrencyLimitError, PoolMetrics, PoolingAllocationFailures, PoolingAllocatorMetrics,
+};
 
 #[cfg(feature = "profiling")]
 mod profiling;
@@ -102,11 +107,22 @@ pub(crate) mod stack;
 #[cfg(feature = "async")]
 pub use stack::*;
 
+#[cfg(feature = "stack-switching")]
+pub(crate) mod stack_switching;
+
 #[cfg(feature = "coredump")]
 mod coredump;
 #[cfg(feature = "coredump")]
 pub use coredump::*;
 
+#[cfg(feature = "snapshot")]
+mod snapshot;
+#[cfg(feature = "snapshot")]
+pub use snapshot::InstanceSnapshot;
+
+#[cfg(feature = "record-replay")]
+pub(crate) mod record_replay;
+
 #[cfg(feature = "wave")]
 mod wave;
 
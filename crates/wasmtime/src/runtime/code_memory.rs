@@ -34,9 +34,11 @@ pub struct CodeMemory {
     trap_data: Range<usize>,
     wasm_data: Range<usize>,
     address_map_data: Range<usize>,
+    inlined_calls_data: Range<usize>,
     func_name_data: Range<usize>,
     info_data: Range<usize>,
     wasm_dwarf: Range<usize>,
+    tier_up_wasm: Range<usize>,
 }
 
 impl Drop for CodeMemory {
@@ -119,9 +121,11 @@ impl CodeMemory {
         let mut trap_data = 0..0;
         let mut wasm_data = 0..0;
         let mut address_map_data = 0..0;
+        let mut inlined_calls_data = 0..0;
         let mut func_name_data = 0..0;
         let mut info_data = 0..0;
         let mut wasm_dwarf = 0..0;
+        let mut tier_up_wasm = 0..0;
         for section in obj.sections() {
             let data = section.data().map_err(obj::ObjectCrateErrorWrapper)?;
             let name = section.name().map_err(obj::ObjectCrateErrorWrapper)?;
@@ -178,10 +182,12 @@ impl CodeMemory {
                 UnwindRegistration::SECTION_NAME => unwind = range,
                 obj::ELF_WASM_DATA => wasm_data = range,
                 obj::ELF_WASMTIME_ADDRMAP => address_map_data = range,
+                obj::ELF_WASMTIME_INLINED_CALLS => inlined_calls_data = range,
                 obj::ELF_WASMTIME_TRAPS => trap_data = range,
                 obj::ELF_NAME_DATA => func_name_data = range,
                 obj::ELF_WASMTIME_INFO => info_data = range,
                 obj::ELF_WASMTIME_DWARF => wasm_dwarf = range,
+                obj::ELF_WASMTIME_TIER_UP => tier_up_wasm = range,
                 #[cfg(feature = "debug-builtins")]
                 ".debug_info" => has_native_debug_info = true,
 
@@ -205,8 +211,10 @@ impl CodeMemory {
             unwind,
             trap_data,
             address_map_data,
+            inlined_calls_data,
             func_name_data,
             wasm_dwarf,
+            tier_up_wasm,
             info_data,
             wasm_data,
             relocations,
@@ -232,6 +240,13 @@ impl CodeMemory {
         &self.mmap[self.wasm_dwarf.clone()]
     }
 
+    /// Returns the contents of the `ELF_WASMTIME_TIER_UP` section, or an empty
+    /// slice if it wasn't found.
+    #[inline]
+    pub fn tier_up_wasm(&self) -> &[u8] {
+        &self.mmap[self.tier_up_wasm.clone()]
+    }
+
     /// Returns the data in the `ELF_NAME_DATA` section.
     #[inline]
     pub fn func_name_data(&self) -> &[u8] {
@@ -255,6 +270,14 @@ impl CodeMemory {
         &self.mmap[self.address_map_data.clone()]
     }
 
+    /// Returns the encoded inlined calls section, also used to pass to
+    /// `wasmtime_environ::lookup_file_pos`, or an empty slice if no calls were
+    /// inlined.
+    #[inline]
+    pub fn inlined_calls_data(&self) -> &[u8] {
+        &self.mmap[self.inlined_calls_data.clone()]
+    }
+
     /// Returns the contents of the `ELF_WASMTIME_INFO` section, or an empty
     /// slice if it wasn't found.
     #[inline]
@@ -414,7 +414,12 @@ impl Component {
         // `types` type information, and the code memory to a runtime object.
         let static_modules = static_modules
             .into_iter()
-            .map(|(_, info)| Module::from_parts_raw(engine, code.clone(), info, false))
+            .map(|(_index, info)| {
+                let module = Module::from_parts_raw(engine, code.clone(), info, false)?;
+                #[cfg(all(feature = "cranelift", feature = "winch"))]
+                let module = module.with_tier_up(Some(_index));
+                Ok(module)
+            })
             .collect::<Result<_>>()?;
 
         let realloc_func_type = Arc::new(FuncType::new(
@@ -436,6 +441,34 @@ impl Component {
         })
     }
 
+    /// Creates just the static core wasm module `index` of a component
+    /// recompiled for [`Strategy::Tiered`](crate::Strategy::Tiered), without
+    /// assembling the rest of the component.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn static_module_from_parts(
+        engine: &Engine,
+        code_memory: Arc<CodeMemory>,
+        artifacts: Option<ComponentArtifacts>,
+        index: StaticModuleIndex,
+    ) -> Result<Module> {
+        let ComponentArtifacts {
+            types,
+            static_modules,
+            ..
+        } = match artifacts {
+            Some(artifacts) => artifacts,
+            None => postcard::from_bytes(code_memory.wasmtime_info())?,
+        };
+        let signatures = TypeCollection::new_for_module(engine, types.module_types());
+        let types = Arc::new(types);
+        let code = Arc::new(CodeObject::new(code_memory, signatures, types.into()));
+        let info = static_modules
+            .into_iter()
+            .find_map(|(i, info)| (i == index).then_some(info))
+            .context("recompiled component is missing a core wasm module")?;
+        Module::from_parts_raw(engine, code, info, false)
+    }
+
     pub(crate) fn ty(&self) -> TypeComponentIndex {
         self.inner.ty
     }
@@ -448,6 +481,40 @@ impl Component {
         &self.inner.static_modules[idx]
     }
 
+    /// Returns all core wasm modules defined within this component, including
+    /// the modules generated for fused adapters.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn static_modules(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (StaticModuleIndex, &Module)> {
+        self.inner.static_modules.iter()
+    }
+
+    /// Returns the hash of the binary this component was compiled from.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn wasm_hash(&self) -> u64 {
+        self.inner.info.wasm_hash
+    }
+
+    /// Returns the location, within `self.text()`, of every trampoline
+    /// compiled into this component.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn trampoline_locs(
+        &self,
+    ) -> impl Iterator<Item = (TrampolineIndex, &AllCallFunc<FunctionLoc>)> {
+        self.inner.info.trampolines.iter()
+    }
+
+    /// Returns the location, within `self.text()`, of the `resource.drop`
+    /// wasm-to-array trampoline if one was compiled.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn resource_drop_trampoline_loc(&self) -> Option<&FunctionLoc> {
+        self.inner
+            .info
+            .resource_drop_wasm_to_array_trampoline
+            .as_ref()
+    }
+
     #[inline]
     pub(crate) fn types(&self) -> &Arc<ComponentTypes> {
         self.inner.component_types()
@@ -57,7 +57,8 @@ impl HostFunc {
     {
         let data = data.as_ptr() as *const F;
         unsafe {
-            call_host_and_handle_result::<T>(cx, |instance, types, store| {
+            let storage = NonNull::slice_from_raw_parts(storage, storage_len);
+            call_host_and_handle_result::<T>(cx, ty, storage, |instance, types, store| {
                 call_host::<_, _, _, _>(
                     instance,
                     types,
@@ -68,7 +69,7 @@ impl HostFunc {
                     realloc,
                     StringEncoding::from_u8(string_encoding).unwrap(),
                     async_ != 0,
-                    NonNull::slice_from_raw_parts(storage, storage_len).as_mut(),
+                    &mut *storage.as_ptr(),
                     |store, args| (*data)(store, args),
                 )
             })
@@ -298,6 +299,8 @@ fn validate_inbounds<T: ComponentType>(memory: &[u8], ptr: &ValRaw) -> Result<us
 
 unsafe fn call_host_and_handle_result<T>(
     cx: NonNull<VMOpaqueContext>,
+    ty: u32,
+    storage: NonNull<[MaybeUninit<ValRaw>]>,
     func: impl FnOnce(
         *mut ComponentInstance,
         &Arc<ComponentTypes>,
@@ -311,13 +314,62 @@ unsafe fn call_host_and_handle_result<T>(
     let mut store = StoreContextMut(&mut *raw_store.cast());
 
     crate::runtime::vm::catch_unwind_and_record_trap(|| {
-        store.0.call_hook(CallHook::CallingHost)?;
-        let res = func(instance, types, store.as_context_mut());
-        store.0.call_hook(CallHook::ReturningFromHost)?;
-        res
+        traced_host_call(&mut store, instance, types, ty, storage, |mut store| {
+            store.0.call_hook(CallHook::CallingHost)?;
+            let res = func(instance, types, store.as_context_mut());
+            store.0.call_hook(CallHook::ReturningFromHost)?;
+            res
+        })
     })
 }
 
+/// Runs the host function `call` of type `ty`, recording or replaying it if
+/// the store is recording or replaying host calls.
+#[cfg(feature = "record-replay")]
+unsafe fn traced_host_call<T>(
+    store: &mut StoreContextMut<'_, T>,
+    instance: *mut ComponentInstance,
+    types: &ComponentTypes,
+    ty: u32,
+    storage: NonNull<[MaybeUninit<ValRaw>]>,
+    call: impl FnOnce(StoreContextMut<'_, T>) -> Result<()>,
+) -> Result<()> {
+    use crate::runtime::record_replay::{self, HostCall, HostCallSite};
+
+    // Results which don't fit in `storage` are written to linear memory and
+    // are recorded as part of the changes made to it.
+    let results = types[types[TypeFuncIndex::from_u32(ty)].results]
+        .abi
+        .flat_count(MAX_FLAT_RESULTS)
+        .unwrap_or(0);
+    let site = HostCallSite::Component {
+        ty,
+        instance,
+        results,
+        storage,
+    };
+    match record_replay::enter_host_call(store, &site)? {
+        HostCall::Untraced => call(store.as_context_mut()),
+        HostCall::Replayed => Ok(()),
+        HostCall::Recording(pending) => {
+            let result = call(store.as_context_mut());
+            record_replay::exit_host_call(store, &site, pending, result)
+        }
+    }
+}
+
+#[cfg(not(feature = "record-replay"))]
+unsafe fn traced_host_call<T>(
+    store: &mut StoreContextMut<'_, T>,
+    _instance: *mut ComponentInstance,
+    _types: &ComponentTypes,
+    _ty: u32,
+    _storage: NonNull<[MaybeUninit<ValRaw>]>,
+    call: impl FnOnce(StoreContextMut<'_, T>) -> Result<()>,
+) -> Result<()> {
+    call(store.as_context_mut())
+}
+
 unsafe fn call_host_dynamic<T, F>(
     instance: *mut ComponentInstance,
     types: &Arc<ComponentTypes>,
@@ -450,7 +502,8 @@ where
 {
     let data = data.as_ptr() as *const F;
     unsafe {
-        call_host_and_handle_result(cx, |instance, types, store| {
+        let storage = NonNull::slice_from_raw_parts(storage, storage_len);
+        call_host_and_handle_result(cx, ty, storage, |instance, types, store| {
             call_host_dynamic::<T, _>(
                 instance,
                 types,
@@ -461,7 +514,7 @@ where
                 realloc,
                 StringEncoding::from_u8(string_encoding).unwrap(),
                 async_ != 0,
-                NonNull::slice_from_raw_parts(storage, storage_len).as_mut(),
+                &mut *storage.as_ptr(),
                 |store, params, results| (*data)(store, params, results),
             )
         })
@@ -840,6 +840,7 @@ impl<T> InstancePre<T> {
 
     fn instantiate_impl(&self, mut store: impl AsContextMut<Data = T>) -> Result<Instance> {
         let mut store = store.as_context_mut();
+        store.0.check_component_instance_limit()?;
         store
             .engine()
             .allocator()
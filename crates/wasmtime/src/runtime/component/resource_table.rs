@@ -1,5 +1,6 @@
 use super::Resource;
 use crate::prelude::*;
+use crate::ResourceLimiter;
 use alloc::collections::{BTreeMap, BTreeSet};
 use core::any::Any;
 use core::fmt;
@@ -36,6 +37,11 @@ impl core::error::Error for ResourceTableError {}
 pub struct ResourceTable {
     entries: Vec<Entry>,
     free_head: Option<usize>,
+    /// Number of `Entry::Occupied` entries in `entries`.
+    len: usize,
+    /// Maximum value of `len`, beyond which pushes fail, as configured by
+    /// [`ResourceLimiter::resource_handles`].
+    limit: usize,
 }
 
 #[derive(Debug)]
@@ -103,6 +109,8 @@ impl ResourceTable {
         ResourceTable {
             entries: Vec::new(),
             free_head: None,
+            len: 0,
+            limit: usize::MAX,
         }
     }
 
@@ -111,9 +119,34 @@ impl ResourceTable {
         ResourceTable {
             entries: Vec::with_capacity(capacity),
             free_head: None,
+            len: 0,
+            limit: usize::MAX,
         }
     }
 
+    /// Create an empty table whose size is limited by `limiter`.
+    ///
+    /// The table can hold at most [`ResourceLimiter::resource_handles`]
+    /// resources at once, the same limit that applies to the resource tables
+    /// of a [`Store`](crate::Store) using `limiter`. Inserting a resource into
+    /// a full table fails with [`ResourceTableError::Full`].
+    pub fn with_limiter(limiter: &dyn ResourceLimiter) -> Self {
+        ResourceTable {
+            limit: limiter.resource_handles(),
+            ..ResourceTable::new()
+        }
+    }
+
+    /// Returns the number of resources currently held in this table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether this table holds no resources.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     /// Inserts a new value `T` into this table, returning a corresponding
     /// `Resource<T>` which can be used to refer to it after it was inserted.
     pub fn push<T>(&mut self, entry: T) -> Result<Resource<T>, ResourceTableError>
@@ -151,6 +184,7 @@ impl ResourceTable {
         };
 
         self.free_head = Some(ix);
+        self.len -= 1;
 
         entry
     }
@@ -158,9 +192,12 @@ impl ResourceTable {
     /// Push a new entry into the table, returning its handle. This will prefer to use free entries
     /// if they exist, falling back on pushing new entries onto the end of the table.
     fn push_(&mut self, e: TableEntry) -> Result<u32, ResourceTableError> {
-        if let Some(free) = self.pop_free_list() {
+        if self.len >= self.limit {
+            return Err(ResourceTableError::Full);
+        }
+        let ix = if let Some(free) = self.pop_free_list() {
             self.entries[free] = Entry::Occupied { entry: e };
-            Ok(free.try_into().unwrap())
+            free.try_into().unwrap()
         } else {
             let ix = self
                 .entries
@@ -168,8 +205,10 @@ impl ResourceTable {
                 .try_into()
                 .map_err(|_| ResourceTableError::Full)?;
             self.entries.push(Entry::Occupied { entry: e });
-            Ok(ix)
-        }
+            ix
+        };
+        self.len += 1;
+        Ok(ix)
     }
 
     fn occupied(&self, key: u32) -> Result<&TableEntry, ResourceTableError> {
@@ -350,4 +389,39 @@ pub fn test_free_list() {
     // As the free list is empty, this entry will have a new id.
     let x = table.push(()).unwrap();
     assert_eq!(x.rep(), 2);
+    assert_eq!(table.len(), 3);
+}
+
+#[test]
+pub fn test_len() {
+    let mut table = ResourceTable::new();
+    assert!(table.is_empty());
+
+    let x = table.push(()).unwrap();
+    let y = table.push_child((), &x).unwrap();
+    assert_eq!(table.len(), 2);
+
+    // Failing to delete a parent leaves the count untouched.
+    assert!(table.delete(Resource::<()>::new_own(x.rep())).is_err());
+    assert_eq!(table.len(), 2);
+
+    table.delete(y).unwrap();
+    table.delete(x).unwrap();
+    assert!(table.is_empty());
+}
+
+#[test]
+pub fn test_limiter() {
+    let limits = crate::StoreLimitsBuilder::new().resource_handles(2).build();
+    let mut table = ResourceTable::with_limiter(&limits);
+
+    let x = table.push(()).unwrap();
+    let y = table.push_child((), &x).unwrap();
+    assert!(matches!(table.push(()), Err(ResourceTableError::Full)));
+    assert_eq!(table.len(), 2);
+
+    // Deleting an entry makes room for another.
+    table.delete(y).unwrap();
+    table.push(()).unwrap();
+    assert!(matches!(table.push(()), Err(ResourceTableError::Full)));
 }
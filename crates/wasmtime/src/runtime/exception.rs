@@ -0,0 +1,363 @@
+//! Support for the WebAssembly exception-handling proposal.
+//!
+//! An exception is represented in the GC heap as an `externref` whose host
+//! data is an [`ExceptionObject`] holding the exception's tag and payload, and
+//! `exnref` values are references to such `externref`s. Throwing an exception,
+//! from WebAssembly or from the host, makes it the store's pending exception
+//! (see `store/exception.rs`) until a catch clause takes it or it reaches the
+//! host's call into WebAssembly, where it becomes a [`ThrownException`] error.
+
+use crate::prelude::*;
+use crate::runtime::vm::{ExportTag, ValRaw};
+use crate::{StoreContextMut, Tag, Val};
+use core::fmt;
+#[cfg(feature = "gc")]
+use {
+    crate::runtime::vm::{VMExternRef, VMGcRef, VMStore},
+    crate::store::{AutoAssertNoGc, StoreOpaque},
+    crate::{FuncType, GcHeapOutOfMemory},
+    core::any::Any,
+    wasmtime_environ::{WasmHeapTopType, WasmValType},
+};
+
+/// A WebAssembly exception, thrown either by WebAssembly or by the host.
+///
+/// An exception carries a [`Tag`] and a payload of values whose types are the
+/// parameters of the tag's type.
+///
+/// When an exception thrown by WebAssembly is not caught by any WebAssembly
+/// frame, the host's call into WebAssembly, such as
+/// [`Func::call`](crate::Func::call), returns an error which can be downcast
+/// to a [`ThrownException`] to inspect its tag and payload.
+///
+/// Conversely, a host function can return an error created from a
+/// [`ThrownException`] to throw it to its WebAssembly caller, where it can be
+/// caught by a `try_table` like any exception thrown by WebAssembly. This
+/// requires [`Config::wasm_exceptions`](crate::Config::wasm_exceptions) to be
+/// enabled; otherwise the error is reported to the caller as a trap.
+///
+/// # Example
+///
+/// ```
+/// # use wasmtime::*;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut config = Config::new();
+/// config.wasm_exceptions(true);
+/// let engine = Engine::new(&config)?;
+/// let mut store = Store::new(&engine, ());
+///
+/// let ty = TagType::new(FuncType::new(&engine, [ValType::I32], []));
+/// let tag = Tag::new(&mut store, &ty)?;
+///
+/// let module = Module::new(
+///     &engine,
+///     r#"
+///         (module
+///             (import "" "tag" (tag $tag (param i32)))
+///             (import "" "host" (func $host))
+///             (func (export "run") (result i32)
+///                 (block $catch (result i32)
+///                     (try_table (catch $tag $catch)
+///                         call $host)
+///                     i32.const 0))
+///         )
+///     "#,
+/// )?;
+///
+/// let host = Func::wrap(&mut store, move || -> Result<()> {
+///     Err(ThrownException::new(tag, [Val::I32(42)]).into())
+/// });
+/// let instance = Instance::new(&mut store, &module, &[tag.into(), host.into()])?;
+/// let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+/// assert_eq!(run.call(&mut store, ())?, 42);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ThrownException {
+    tag: Tag,
+    payload: Vec<Val>,
+}
+
+impl ThrownException {
+    /// Creates a new exception with the given tag and payload.
+    ///
+    /// The payload is checked against the type of `tag` when the exception is
+    /// thrown to WebAssembly.
+    pub fn new(tag: Tag, payload: impl IntoIterator<Item = Val>) -> ThrownException {
+        ThrownException {
+            tag,
+            payload: payload.into_iter().collect(),
+        }
+    }
+
+    /// Returns this exception's tag.
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns this exception's payload.
+    pub fn payload(&self) -> &[Val] {
+        &self.payload
+    }
+}
+
+impl fmt::Display for ThrownException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "uncaught WebAssembly exception")
+    }
+}
+
+impl core::error::Error for ThrownException {}
+
+/// The host data of the `externref` that represents an exception in the GC
+/// heap.
+///
+/// The GC references in the payload are owned by the `externref`'s entry in
+/// the host data table, which the collector traces and releases once the
+/// exception is no longer referenced.
+pub(crate) struct ExceptionObject {
+    tag: ExportTag,
+    payload: Box<[ValRaw]>,
+}
+
+/// Returns the GC reference in `raw`, if `ty` is a GC reference type and the
+/// reference is not null.
+#[cfg(feature = "gc")]
+pub(crate) fn payload_gc_ref(raw: &ValRaw, ty: &WasmValType) -> Option<VMGcRef> {
+    match ty {
+        WasmValType::Ref(r) if ty.is_vmgcref_type() => match r.heap_type.top() {
+            WasmHeapTopType::Any => VMGcRef::from_raw_u32(raw.get_anyref()),
+            _ => VMGcRef::from_raw_u32(raw.get_externref()),
+        },
+        _ => None,
+    }
+}
+
+/// Throws a new exception with the given tag and payload, making it the
+/// store's pending exception.
+///
+/// The GC references in `payload` are cloned before anything is allocated, so
+/// they need only be alive on entry.
+#[cfg(feature = "gc")]
+pub(crate) fn throw(store: &mut dyn VMStore, tag: ExportTag, payload: &[ValRaw]) -> Result<()> {
+    let ty = store
+        .engine()
+        .signatures()
+        .borrow(tag.signature)
+        .expect("tag types are registered");
+    let params = ty.unwrap_func().params();
+    debug_assert_eq!(params.len(), payload.len());
+    let gc_store = store.store_opaque_mut().gc_store_mut()?;
+    let gc_refs = payload
+        .iter()
+        .zip(params)
+        .filter_map(|(raw, ty)| payload_gc_ref(raw, ty))
+        .filter(|gc_ref| !gc_ref.is_i31())
+        .map(|gc_ref| gc_store.clone_gc_ref(&gc_ref))
+        .collect();
+
+    let exn = alloc_host_externref(
+        store,
+        Box::new(ExceptionObject {
+            tag,
+            payload: payload.into(),
+        }),
+        gc_refs,
+    )?;
+    store.set_pending_exception(exn.into());
+    Ok(())
+}
+
+/// Allocates an `externref` with the given host data for use by WebAssembly,
+/// collecting garbage and growing the GC heap if necessary.
+///
+/// The host data owns `gc_refs`, which are released when the `externref` is
+/// collected, or immediately if the allocation fails.
+#[cfg(feature = "gc")]
+pub(crate) fn alloc_host_externref(
+    store: &mut dyn VMStore,
+    data: Box<dyn Any + Send + Sync>,
+    gc_refs: Box<[VMGcRef]>,
+) -> Result<VMExternRef> {
+    let mut host_data = (data, gc_refs);
+    for attempt in 0..3 {
+        // Before retrying, collect garbage to hopefully free up space, and
+        // then grow the GC heap for one last try. An `externref` object is
+        // only a handful of words in any collector. The references in
+        // `gc_refs` are still counted, so they survive the collection.
+        let retry = match attempt {
+            0 => Ok(true),
+            1 => store.maybe_async_gc(None).map(|_| true),
+            _ => store.maybe_grow_gc_heap(64),
+        };
+        match retry {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                release_gc_refs(store.store_opaque_mut(), host_data.1);
+                return Err(e);
+            }
+        }
+
+        let (data, gc_refs) = host_data;
+        host_data = match store
+            .unwrap_gc_store_mut()
+            .alloc_externref_with_gc_refs(data, gc_refs)?
+        {
+            Ok(x) => return Ok(x),
+            Err(host_data) => host_data,
+        };
+    }
+    release_gc_refs(store.store_opaque_mut(), host_data.1);
+    Err(GcHeapOutOfMemory::new(()).into())
+}
+
+#[cfg(feature = "gc")]
+fn release_gc_refs(store: &mut StoreOpaque, gc_refs: Box<[VMGcRef]>) {
+    let gc_store = store.unwrap_gc_store_mut();
+    for gc_ref in gc_refs.into_vec() {
+        gc_store.drop_gc_ref(gc_ref);
+    }
+}
+
+/// Returns the tag of the given exception.
+#[cfg(feature = "gc")]
+fn exception_tag(store: &StoreOpaque, exn: &VMGcRef) -> ExportTag {
+    exception_object(store, exn).tag
+}
+
+#[cfg(feature = "gc")]
+fn exception_object<'a>(store: &'a StoreOpaque, exn: &VMGcRef) -> &'a ExceptionObject {
+    let gc_store = store.unwrap_gc_store();
+    let externref = exn
+        .as_externref(&*gc_store.gc_heap)
+        .expect("exceptions are externrefs");
+    gc_store
+        .externref_host_data(externref)
+        .downcast_ref()
+        .expect("exceptions have `ExceptionObject` host data")
+}
+
+/// Attempts to catch the store's pending exception for a catch clause of
+/// compiled Wasm.
+///
+/// If `tag` is `None` any exception matches; otherwise only exceptions with
+/// that tag match, and their payload is written to `payload`. On a match the
+/// exception stops being pending and its raw reference, exposed to Wasm, is
+/// returned. Otherwise zero is returned.
+///
+/// # Safety
+///
+/// When `tag` is `Some`, `payload` must be valid for writing as many values as
+/// the tag has parameters.
+#[cfg(feature = "gc")]
+pub(crate) unsafe fn catch(
+    store: &mut StoreOpaque,
+    tag: Option<ExportTag>,
+    payload: *mut ValRaw,
+) -> u32 {
+    let exn = store
+        .pending_exception()
+        .expect("should only attempt to catch a pending exception");
+
+    if let Some(tag) = tag {
+        let object = exception_object(store, exn);
+        if object.tag != tag {
+            return 0;
+        }
+        let values = object.payload.clone();
+        let ty = store
+            .engine()
+            .signatures()
+            .borrow(tag.signature)
+            .expect("tag types are registered");
+        let gc_store = store.unwrap_gc_store_mut();
+        for (i, (raw, ty)) in values.iter().zip(ty.unwrap_func().params()).enumerate() {
+            if let Some(gc_ref) = payload_gc_ref(raw, ty) {
+                let gc_ref = gc_store.clone_gc_ref(&gc_ref);
+                gc_store.expose_gc_ref_to_wasm(gc_ref);
+            }
+            payload.add(i).write(*raw);
+        }
+    }
+
+    let exn = store.take_pending_exception().unwrap();
+    let raw = exn.as_raw_u32();
+    store.unwrap_gc_store_mut().expose_gc_ref_to_wasm(exn);
+    raw
+}
+
+/// Takes the store's pending exception, if any, and converts it into a
+/// [`ThrownException`] for the host.
+#[cfg(feature = "gc")]
+pub(crate) fn take_uncaught(store: &mut StoreOpaque) -> Option<ThrownException> {
+    let exn = store.take_pending_exception()?;
+    let tag = exception_tag(store, &exn);
+    let ty = FuncType::from_shared_type_index(store.engine(), tag.signature);
+    let values = exception_object(store, &exn).payload.clone();
+
+    let mut store = AutoAssertNoGc::new(store);
+    let payload = values
+        .iter()
+        .zip(ty.params())
+        .map(|(raw, ty)| unsafe { Val::_from_raw(&mut store, *raw, &ty) })
+        .collect();
+    let tag = Tag::from_wasmtime_tag(tag, &store);
+    store.unwrap_gc_store_mut().drop_gc_ref(exn);
+    Some(ThrownException { tag, payload })
+}
+
+/// Throws the exception in `result` to the WebAssembly caller of a host
+/// function, if there is one.
+///
+/// When `result` is a [`ThrownException`] error and exceptions are enabled,
+/// the exception becomes the store's pending exception and `Ok(())` is
+/// returned, in which case the host function's results are ignored by its
+/// caller. Otherwise `result` is returned unchanged.
+pub(crate) fn catch_host_exception<T>(
+    store: &mut StoreContextMut<'_, T>,
+    result: Result<()>,
+) -> Result<()> {
+    #[cfg(feature = "gc")]
+    if let Err(e) = &result {
+        if store
+            .engine()
+            .features()
+            .contains(wasmparser::WasmFeatures::EXCEPTIONS)
+        {
+            if let Some(exn) = e.downcast_ref::<ThrownException>() {
+                return throw_host_exception(store, exn);
+            }
+        }
+    }
+    #[cfg(not(feature = "gc"))]
+    let _ = store;
+    result
+}
+
+#[cfg(feature = "gc")]
+fn throw_host_exception<T>(
+    store: &mut StoreContextMut<'_, T>,
+    exn: &ThrownException,
+) -> Result<()> {
+    if !exn.tag.comes_from_same_store(store.0) {
+        bail!("exception tag used with wrong store");
+    }
+    let tag = exn.tag.vmimport(store.0);
+    let ty = FuncType::from_shared_type_index(store.engine(), tag.signature);
+    if ty.params().len() != exn.payload.len() {
+        bail!(
+            "exception payload has {} values but its tag expects {}",
+            exn.payload.len(),
+            ty.params().len()
+        );
+    }
+    let mut payload = Vec::with_capacity(exn.payload.len());
+    for (val, ty) in exn.payload.iter().zip(ty.params()) {
+        val.ensure_matches_ty(store.0, &ty)
+            .context("exception payload does not match its tag's type")?;
+        payload.push(unsafe { val.to_raw(&mut *store)? });
+    }
+    throw(store.0, tag, &payload)
+}
@@ -123,7 +123,10 @@ impl Global {
 
                         HeapType::NoFunc => Ref::Func(None),
 
-                        HeapType::Extern => Ref::Extern(
+                        HeapType::Extern
+                        | HeapType::Exn
+                        | HeapType::Cont
+                        | HeapType::ConcreteCont(_) => Ref::Extern(
                             definition
                                 .as_gc_ref()
                                 .map(|r| {
@@ -133,7 +136,9 @@ impl Global {
                                 .into(),
                         ),
 
-                        HeapType::NoExtern => Ref::Extern(None),
+                        HeapType::NoExtern | HeapType::NoExn | HeapType::NoCont => {
+                            Ref::Extern(None)
+                        }
 
                         HeapType::Any
                         | HeapType::Eq
@@ -0,0 +1,93 @@
+use crate::prelude::*;
+use crate::runtime::vm::ExportTag;
+use crate::store::{StoreOpaque, Stored};
+use crate::{AsContext, AsContextMut, FuncType, TagType};
+
+/// A WebAssembly exception tag.
+///
+/// Exceptions thrown by WebAssembly, or by the host with
+/// [`ThrownException`](crate::ThrownException), carry a tag and a payload whose
+/// types are described by the tag's type. The catch clauses of a `try_table`
+/// match exceptions by their tag, and two tags are only the same if they come
+/// from the same definition, even if their types are equal.
+///
+/// A [`Tag`] "belongs" to the store that it was originally created within
+/// (either via [`Tag::new`] or via instantiating a
+/// [`Module`](crate::Module)). Operations on a [`Tag`] only work with the
+/// store it belongs to, and if another store is passed in by accident then
+/// methods will panic.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)] // here for the C API
+pub struct Tag(pub(super) Stored<ExportTag>);
+
+impl Tag {
+    /// Creates a new WebAssembly tag with the given type in `store`.
+    ///
+    /// The returned tag is distinct from all other tags, including those with
+    /// the same type, and can be provided as an import to instances in `store`
+    /// so that the host and WebAssembly can throw and catch exceptions with
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ty` is associated with a different engine than
+    /// `store`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let engine = Engine::default();
+    /// let mut store = Store::new(&engine, ());
+    ///
+    /// let ty = TagType::new(FuncType::new(&engine, [ValType::I32], []));
+    /// let tag = Tag::new(&mut store, &ty)?;
+    /// assert!(Tag::eq(&tag, &tag, &store));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(mut store: impl AsContextMut, ty: &TagType) -> Result<Tag> {
+        let store = store.as_context_mut().0;
+        if !ty.ty().comes_from_same_engine(store.engine()) {
+            bail!("tag type used with wrong engine");
+        }
+        store.push_host_tag_type(ty.ty().clone());
+        let tag = store.store_data_mut().alloc_tag(ty.ty().type_index());
+        Ok(Tag::from_wasmtime_tag(tag, store))
+    }
+
+    /// Returns the type of this tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this tag.
+    pub fn ty(&self, store: impl AsContext) -> TagType {
+        let store = store.as_context();
+        let signature = store[self.0].signature;
+        TagType::new(FuncType::from_shared_type_index(store.engine(), signature))
+    }
+
+    /// Returns whether `a` and `b` are the same tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either tag does not belong to `store`.
+    pub fn eq(a: &Tag, b: &Tag, store: impl AsContext) -> bool {
+        let store = store.as_context().0;
+        assert!(a.comes_from_same_store(store) && b.comes_from_same_store(store));
+        store[a.0].index == store[b.0].index
+    }
+
+    pub(crate) fn from_wasmtime_tag(tag: ExportTag, store: &StoreOpaque) -> Tag {
+        Tag(store.store_data().tag(tag))
+    }
+
+    pub(crate) fn vmimport(&self, store: &StoreOpaque) -> ExportTag {
+        store[self.0]
+    }
+
+    pub(crate) fn comes_from_same_store(&self, store: &StoreOpaque) -> bool {
+        store.store_data().contains(self.0)
+    }
+}
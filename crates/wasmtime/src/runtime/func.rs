@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use crate::runtime::exception::catch_host_exception;
 use crate::runtime::vm::{
     ExportFunction, InterpreterRef, SendSyncPtr, StoreBox, VMArrayCallHostFuncContext, VMContext,
     VMFuncRef, VMFunctionImport, VMOpaqueContext,
@@ -17,7 +18,7 @@ use core::mem::{self, MaybeUninit};
 use core::num::NonZeroUsize;
 use core::pin::Pin;
 use core::ptr::NonNull;
-use wasmtime_environ::VMSharedTypeIndex;
+use wasmtime_environ::{VMSharedTypeIndex, WasmFuncType};
 
 /// A reference to the abstract `nofunc` heap value.
 ///
@@ -1604,10 +1605,23 @@ pub(crate) fn invoke_wasm_and_catch_traps<T>(
             exit_wasm(store, exit);
             return Err(trap);
         }
-        let result = crate::runtime::vm::catch_traps(store, closure);
+        let result = crate::runtime::vm::catch_traps(store.0, closure);
         exit_wasm(store, exit);
+
+        // An exception that was not caught by any Wasm frame is still pending
+        // once Wasm returns, and is reported to the host as an error. If Wasm
+        // trapped instead, the trap takes precedence.
+        #[cfg(feature = "gc")]
+        let exn = crate::runtime::exception::take_uncaught(store.0);
+
         store.0.call_hook(CallHook::ReturningFromWasm)?;
-        result.map_err(|t| crate::trap::from_runtime_box(store.0, t))
+        let result = result.map_err(|t| crate::trap::from_runtime_box(store.0, t));
+        #[cfg(feature = "gc")]
+        if let Some(exn) = exn {
+            result?;
+            return Err(exn.into());
+        }
+        result
     }
 }
 
@@ -2064,6 +2078,38 @@ impl<T> Caller<'_, T> {
         }
     }
 
+    /// Runs the host function `call` of type `ty` with `args`, recording or
+    /// replaying it if the store is recording or replaying host calls.
+    #[cfg(feature = "record-replay")]
+    unsafe fn traced_host_call(
+        &mut self,
+        ty: &WasmFuncType,
+        args: NonNull<[ValRaw]>,
+        call: impl FnOnce(Caller<'_, T>) -> Result<()>,
+    ) -> Result<()> {
+        use crate::runtime::record_replay::{self, HostCall, HostCallSite};
+
+        let site = HostCallSite::Core { ty, args };
+        match record_replay::enter_host_call(&mut self.store, &site)? {
+            HostCall::Untraced => call(self.sub_caller()),
+            HostCall::Replayed => Ok(()),
+            HostCall::Recording(pending) => {
+                let result = call(self.sub_caller());
+                record_replay::exit_host_call(&mut self.store, &site, pending, result)
+            }
+        }
+    }
+
+    #[cfg(not(feature = "record-replay"))]
+    unsafe fn traced_host_call(
+        &mut self,
+        _ty: &WasmFuncType,
+        _args: NonNull<[ValRaw]>,
+        call: impl FnOnce(Caller<'_, T>) -> Result<()>,
+    ) -> Result<()> {
+        call(self.sub_caller())
+    }
+
     /// Looks up an export from the caller's module by the `name` given.
     ///
     /// This is a low-level function that's typically used to implement passing
@@ -2252,7 +2298,6 @@ struct HostFuncState<F> {
 
     // NB: We have to keep our `VMSharedTypeIndex` registered in the engine for
     // as long as this function exists.
-    #[allow(dead_code)]
     ty: RegisteredType,
 }
 
@@ -2310,17 +2355,18 @@ impl HostContext {
         // destructors. As a result anything requiring a destructor
         // should be part of this closure, and the long-jmp-ing
         // happens after the closure in handling the result.
+        let vmctx = VMArrayCallHostFuncContext::from_opaque(callee_vmctx);
+        let state = vmctx.as_ref().host_state();
+
+        // Double-check ourselves in debug mode, but we control
+        // the `Any` here so an unsafe downcast should also
+        // work.
+        debug_assert!(state.is::<HostFuncState<F>>());
+        let state = &*(state as *const _ as *const HostFuncState<F>);
+
         let run = move |mut caller: Caller<'_, T>| {
             let mut args =
                 NonNull::slice_from_raw_parts(args.cast::<MaybeUninit<ValRaw>>(), args_len);
-            let vmctx = VMArrayCallHostFuncContext::from_opaque(callee_vmctx);
-            let state = vmctx.as_ref().host_state();
-
-            // Double-check ourselves in debug mode, but we control
-            // the `Any` here so an unsafe downcast should also
-            // work.
-            debug_assert!(state.is::<HostFuncState<F>>());
-            let state = &*(state as *const _ as *const HostFuncState<F>);
             let func = &state.func;
 
             let ret = 'ret: {
@@ -2361,7 +2407,11 @@ impl HostContext {
         // closure and then run it as part of `Caller::with`.
         crate::runtime::vm::catch_unwind_and_record_trap(move || {
             let caller_vmctx = VMContext::from_opaque(caller_vmctx);
-            Caller::with(caller_vmctx, run)
+            Caller::with(caller_vmctx, |mut caller| {
+                let args = NonNull::slice_from_raw_parts(args, args_len);
+                let result = caller.traced_host_call(state.ty.unwrap_func(), args, run);
+                catch_host_exception(&mut caller.store, result)
+            })
         })
     }
 }
@@ -2417,12 +2467,17 @@ impl HostFunc {
         func: impl Fn(Caller<'_, T>, &mut [ValRaw]) -> Result<()> + Send + Sync + 'static,
     ) -> Self {
         assert!(ty.comes_from_same_engine(engine));
+        let wasm_ty = ty.as_wasm_func_type().clone();
         let func = move |caller_vmctx, values: &mut [ValRaw]| {
             Caller::<T>::with(caller_vmctx, |mut caller| {
-                caller.store.0.call_hook(CallHook::CallingHost)?;
-                let result = func(caller.sub_caller(), values)?;
-                caller.store.0.call_hook(CallHook::ReturningFromHost)?;
-                Ok(result)
+                let args = NonNull::from(&mut *values);
+                caller.traced_host_call(&wasm_ty, args, |mut caller| {
+                    caller.store.0.call_hook(CallHook::CallingHost)?;
+                    let result = func(caller.sub_caller(), &mut *args.as_ptr());
+                    catch_host_exception(&mut caller.store, result)?;
+                    caller.store.0.call_hook(CallHook::ReturningFromHost)?;
+                    Ok(())
+                })
             })
         };
         let ctx = crate::trampoline::create_array_call_function(&ty, func)
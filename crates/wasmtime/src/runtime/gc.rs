@@ -57,9 +57,9 @@ where
 /// was passed to [`ExternRef::new`][crate::ExternRef::new] -- and you can
 /// recover this value via the
 /// [`into_inner`][crate::GcHeapOutOfMemory::into_inner] method. This lets you
-/// try to allocate the `externref` again, after performing a GC to hopefully
-/// free up space in the heap, or otherwise do whatever you want with the inner
-/// value.
+/// try to allocate the `externref` again later, for example after unrooting
+/// other objects so that a collection can reclaim them, or otherwise do
+/// whatever you want with the inner value.
 ///
 /// For errors that occur when attempting to allocate non-`externref` objects
 /// when the GC heap is at capacity, the `T` type parameter is just the unit
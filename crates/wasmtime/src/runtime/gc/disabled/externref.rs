@@ -1,6 +1,7 @@
 use crate::runtime::vm::VMGcRef;
 use crate::{
-    store::AutoAssertNoGc, AsContextMut, GcRefImpl, Result, Rooted, StoreContext, StoreContextMut,
+    store::{AutoAssertNoGc, StoreOpaque},
+    AsContextMut, GcRefImpl, Result, Rooted, StoreContext, StoreContextMut,
 };
 use core::any::Any;
 
@@ -28,6 +29,10 @@ impl ExternRef {
         match *self {}
     }
 
+    pub(crate) fn _host_data_is<T: 'static>(&self, _store: &StoreOpaque) -> Result<bool> {
+        match *self {}
+    }
+
     pub fn data_mut<'a, T>(
         &self,
         _store: impl Into<StoreContextMut<'a, T>>,
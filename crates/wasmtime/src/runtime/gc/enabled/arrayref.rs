@@ -242,10 +242,10 @@ impl ArrayRef {
     /// If the given `elem` value's type does not match the `allocator`'s array
     /// type's element type, an error is returned.
     ///
-    /// If the allocation cannot be satisfied because the GC heap is currently
-    /// out of memory, but performing a garbage collection might free up space
-    /// such that retrying the allocation afterwards might succeed, then a
-    /// [`GcHeapOutOfMemory<()>`][crate::GcHeapOutOfMemory] error is returned.
+    /// If the allocation cannot be satisfied because the GC heap is out of
+    /// memory even after performing a garbage collection and attempting to
+    /// grow the heap, then a [`GcHeapOutOfMemory<()>`][crate::GcHeapOutOfMemory]
+    /// error is returned.
     ///
     /// # Panics
     ///
@@ -318,9 +318,11 @@ impl ArrayRef {
 
         // Allocate the array and write each field value into the appropriate
         // offset.
+        let layout = allocator.layout();
         let arrayref = store
-            .gc_store_mut()?
-            .alloc_uninit_array(allocator.type_index(), len, allocator.layout())
+            .retry_host_gc_alloc(u64::from(layout.size_for_len(len)), |gc_store| {
+                gc_store.alloc_uninit_array(allocator.type_index(), len, layout)
+            })
             .context("unrecoverable error when allocating new `arrayref`")?
             .ok_or_else(|| GcHeapOutOfMemory::new(()))?;
 
@@ -358,10 +360,10 @@ impl ArrayRef {
     /// If any of the `elems` values' type does not match the `allocator`'s
     /// array type's element type, an error is returned.
     ///
-    /// If the allocation cannot be satisfied because the GC heap is currently
-    /// out of memory, but performing a garbage collection might free up space
-    /// such that retrying the allocation afterwards might succeed, then a
-    /// [`GcHeapOutOfMemory<()>`][crate::GcHeapOutOfMemory] error is returned.
+    /// If the allocation cannot be satisfied because the GC heap is out of
+    /// memory even after performing a garbage collection and attempting to
+    /// grow the heap, then a [`GcHeapOutOfMemory<()>`][crate::GcHeapOutOfMemory]
+    /// error is returned.
     ///
     /// # Panics
     ///
@@ -727,7 +729,12 @@ unsafe impl WasmTy for Rooted<ArrayRef> {
             | HeapType::I31
             | HeapType::Struct
             | HeapType::ConcreteStruct(_)
-            | HeapType::None => bail!(
+            | HeapType::None
+            | HeapType::Exn
+            | HeapType::NoExn
+            | HeapType::Cont
+            | HeapType::ConcreteCont(_)
+            | HeapType::NoCont => bail!(
                 "type mismatch: expected `(ref {ty})`, got `(ref {})`",
                 self._ty(store)?,
             ),
@@ -821,7 +828,12 @@ unsafe impl WasmTy for ManuallyRooted<ArrayRef> {
             | HeapType::I31
             | HeapType::Struct
             | HeapType::ConcreteStruct(_)
-            | HeapType::None => bail!(
+            | HeapType::None
+            | HeapType::Exn
+            | HeapType::NoExn
+            | HeapType::Cont
+            | HeapType::ConcreteCont(_)
+            | HeapType::NoCont => bail!(
                 "type mismatch: expected `(ref {ty})`, got `(ref {})`",
                 self._ty(store)?,
             ),
@@ -143,22 +143,21 @@ impl ExternRef {
     /// scope is exited. See [`Rooted<T>`][crate::Rooted]'s documentation for
     /// more details.
     ///
-    /// This method will *not* automatically trigger a GC to free up space in
-    /// the GC heap; instead it will return an error. This gives you more
-    /// precise control over when collections happen and allows you to choose
-    /// between performing synchronous and asynchronous collections.
+    /// If the GC heap is out of space, this method performs a synchronous
+    /// garbage collection and, if that doesn't free up enough space, grows the
+    /// GC heap before giving up, just like allocations made by Wasm.
     ///
     /// # Errors
     ///
-    /// If the allocation cannot be satisfied because the GC heap is currently
-    /// out of memory, but performing a garbage collection might free up space
-    /// such that retrying the allocation afterwards might succeed, then a
-    /// `GcHeapOutOfMemory<T>` error is returned.
+    /// If the allocation cannot be satisfied because the GC heap is out of
+    /// memory even after a garbage collection, and it can't be grown because
+    /// it's already at [`Config::gc_heap_maximum_size`][crate::Config::gc_heap_maximum_size]
+    /// or the store's [`ResourceLimiter`][crate::ResourceLimiter] denied the
+    /// growth, then a `GcHeapOutOfMemory<T>` error is returned.
     ///
     /// The `GcHeapOutOfMemory<T>` error contains the host value that the
     /// `externref` would have wrapped. You can extract that value from this
-    /// error and reuse it when attempting to allocate an `externref` again
-    /// after GC or otherwise do with it whatever you see fit.
+    /// error and do with it whatever you see fit.
     ///
     /// # Example
     ///
@@ -173,21 +172,15 @@ impl ExternRef {
     ///     // Create an `externref` wrapping a `str`.
     ///     let externref = match ExternRef::new(&mut scope, "hello!") {
     ///         Ok(x) => x,
-    ///         // If the heap is out of memory, then do a GC and try again.
+    ///         // If the heap is out of memory, then extract the original host
+    ///         // value from the error.
     ///         Err(e) if e.is::<GcHeapOutOfMemory<&'static str>>() => {
-    ///             // Do a GC! Note: in an async context, you'd want to do
-    ///             // `scope.as_context_mut().gc_async().await`.
-    ///             scope.as_context_mut().gc();
-    ///
-    ///             // Extract the original host value from the error.
     ///             let host_value = e
     ///                 .downcast::<GcHeapOutOfMemory<&'static str>>()
     ///                 .unwrap()
     ///                 .into_inner();
-    ///
-    ///             // Try to allocate the `externref` again, now that the GC
-    ///             // has hopefully freed up some space.
-    ///             ExternRef::new(&mut scope, host_value)?
+    ///             println!("couldn't allocate an `externref` for {host_value:?}");
+    ///             return Ok(());
     ///         }
     ///         Err(e) => return Err(e),
     ///     };
@@ -205,12 +198,21 @@ impl ExternRef {
     {
         let ctx = context.as_context_mut().0;
 
-        let value: Box<dyn Any + Send + Sync> = Box::new(value);
+        // The host data is handed back when the allocation fails so that it
+        // can be retried, and ultimately returned in the error.
+        let mut value = Some(Box::new(value) as Box<dyn Any + Send + Sync>);
         let gc_ref = ctx
-            .gc_store_mut()?
-            .alloc_externref(value)
+            .retry_host_gc_alloc(64, |gc_store| {
+                match gc_store.alloc_externref(value.take().unwrap())? {
+                    Ok(x) => Ok(Some(x)),
+                    Err(v) => {
+                        value = Some(v);
+                        Ok(None)
+                    }
+                }
+            })
             .context("unrecoverable error when allocating new `externref`")?
-            .map_err(|x| GcHeapOutOfMemory::<T>::new(*x.downcast().unwrap()))
+            .ok_or_else(|| GcHeapOutOfMemory::<T>::new(*value.unwrap().downcast().unwrap()))
             .context("failed to allocate `externref`")?;
 
         let mut ctx = AutoAssertNoGc::new(ctx);
@@ -314,12 +316,21 @@ impl ExternRef {
     {
         let ctx = store.as_context_mut().0;
 
-        let value: Box<dyn Any + Send + Sync> = Box::new(value);
+        // The host data is handed back when the allocation fails so that it
+        // can be retried, and ultimately returned in the error.
+        let mut value = Some(Box::new(value) as Box<dyn Any + Send + Sync>);
         let gc_ref = ctx
-            .gc_store_mut()?
-            .alloc_externref(value)
+            .retry_host_gc_alloc(64, |gc_store| {
+                match gc_store.alloc_externref(value.take().unwrap())? {
+                    Ok(x) => Ok(Some(x)),
+                    Err(v) => {
+                        value = Some(v);
+                        Ok(None)
+                    }
+                }
+            })
             .context("unrecoverable error when allocating new `externref`")?
-            .map_err(|x| GcHeapOutOfMemory::<T>::new(*x.downcast().unwrap()))
+            .ok_or_else(|| GcHeapOutOfMemory::<T>::new(*value.unwrap().downcast().unwrap()))
             .context("failed to allocate `externref`")?;
 
         let mut ctx = AutoAssertNoGc::new(ctx);
@@ -393,6 +404,16 @@ impl ExternRef {
         }
     }
 
+    /// Returns whether this `externref` has host data of type `T`.
+    pub(crate) fn _host_data_is<T: 'static>(&self, store: &StoreOpaque) -> Result<bool> {
+        let gc_ref = self.inner.try_gc_ref(store)?;
+        let gc_store = store.gc_store()?;
+        Ok(match gc_ref.as_externref(&*gc_store.gc_heap) {
+            Some(externref) => gc_store.externref_host_data(externref).is::<T>(),
+            None => false,
+        })
+    }
+
     /// Get an exclusive borrow of the underlying data for this `ExternRef`.
     ///
     /// Returns `None` if this is an `externref` wrapper of an `anyref` created
@@ -219,10 +219,10 @@ impl StructRef {
     /// If the given `fields` values' types do not match the field types of the
     /// `allocator`'s struct type, an error is returned.
     ///
-    /// If the allocation cannot be satisfied because the GC heap is currently
-    /// out of memory, but performing a garbage collection might free up space
-    /// such that retrying the allocation afterwards might succeed, then a
-    /// [`GcHeapOutOfMemory<()>`][crate::GcHeapOutOfMemory] error is returned.
+    /// If the allocation cannot be satisfied because the GC heap is out of
+    /// memory even after performing a garbage collection and attempting to
+    /// grow the heap, then a [`GcHeapOutOfMemory<()>`][crate::GcHeapOutOfMemory]
+    /// error is returned.
     ///
     /// # Panics
     ///
@@ -266,9 +266,11 @@ impl StructRef {
 
         // Allocate the struct and write each field value into the appropriate
         // offset.
+        let layout = allocator.layout();
         let structref = store
-            .gc_store_mut()?
-            .alloc_uninit_struct(allocator.type_index(), &allocator.layout())
+            .retry_host_gc_alloc(u64::from(layout.size), |gc_store| {
+                gc_store.alloc_uninit_struct(allocator.type_index(), &layout)
+            })
             .context("unrecoverable error when allocating new `structref`")?
             .ok_or_else(|| GcHeapOutOfMemory::new(()))?;
 
@@ -594,7 +596,12 @@ unsafe impl WasmTy for Rooted<StructRef> {
             | HeapType::I31
             | HeapType::Array
             | HeapType::ConcreteArray(_)
-            | HeapType::None => bail!(
+            | HeapType::None
+            | HeapType::Exn
+            | HeapType::NoExn
+            | HeapType::Cont
+            | HeapType::ConcreteCont(_)
+            | HeapType::NoCont => bail!(
                 "type mismatch: expected `(ref {ty})`, got `(ref {})`",
                 self._ty(store)?,
             ),
@@ -688,7 +695,12 @@ unsafe impl WasmTy for ManuallyRooted<StructRef> {
             | HeapType::I31
             | HeapType::Array
             | HeapType::ConcreteArray(_)
-            | HeapType::None => bail!(
+            | HeapType::None
+            | HeapType::Exn
+            | HeapType::NoExn
+            | HeapType::Cont
+            | HeapType::ConcreteCont(_)
+            | HeapType::NoCont => bail!(
                 "type mismatch: expected `(ref {ty})`, got `(ref {})`",
                 self._ty(store)?,
             ),
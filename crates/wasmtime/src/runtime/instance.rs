@@ -1,8 +1,8 @@
 use crate::linker::{Definition, DefinitionType};
 use crate::prelude::*;
 use crate::runtime::vm::{
-    Imports, InstanceAllocationRequest, ModuleRuntimeInfo, StorePtr, VMFuncRef, VMFunctionImport,
-    VMGlobalImport, VMMemoryImport, VMOpaqueContext, VMTableImport,
+    ExportTag, Imports, InstanceAllocationRequest, ModuleRuntimeInfo, StorePtr, VMFuncRef,
+    VMFunctionImport, VMGlobalImport, VMMemoryImport, VMOpaqueContext, VMTableImport,
 };
 use crate::store::{InstanceId, StoreOpaque, Stored};
 use crate::types::matching;
@@ -14,7 +14,8 @@ use alloc::sync::Arc;
 use core::ptr::NonNull;
 use wasmparser::WasmFeatures;
 use wasmtime_environ::{
-    EntityIndex, EntityType, FuncIndex, GlobalIndex, MemoryIndex, PrimaryMap, TableIndex, TypeTrace,
+    EntityIndex, EntityType, FuncIndex, GlobalIndex, MemoryIndex, PrimaryMap, TableIndex, TagIndex,
+    TypeTrace,
 };
 
 /// An instantiated WebAssembly module.
@@ -255,6 +256,11 @@ impl Instance {
         if !Engine::same(store.engine(), module.engine()) {
             bail!("cross-`Engine` instantiation is not currently supported");
         }
+        // A tiered module may be instantiated with its Cranelift-compiled
+        // version, but the instance still reports the module it was created
+        // from so `Instance::module` and `ModuleExport`s work as expected.
+        let original = module;
+        let module = &module.for_instantiation();
         store.bump_resource_counts(module)?;
 
         // Allocate the GC heap, if necessary.
@@ -266,7 +272,11 @@ impl Instance {
 
         // Register the module just before instantiation to ensure we keep the module
         // properly referenced while in use by the store.
-        let module_id = store.modules_mut().register_module(module);
+        let mut module_id = store.modules_mut().register_module(module);
+        if original.id() != module.id() {
+            module_id = store.modules_mut().register_module(original);
+        }
+        store.register_gc_types(module.signatures().as_module_map().values().copied());
         store.fill_func_refs();
 
         // The first thing we do is issue an instance allocation request
@@ -294,6 +304,18 @@ impl Instance {
                     tunables: store.engine().tunables(),
                 })?;
 
+        // Each defined tag is a new tag, distinct from all others in the
+        // store, so allocate them now that the instance exists.
+        let env_module = compiled_module.module();
+        for (_, tag) in env_module.tags.iter().skip(env_module.num_imported_tags) {
+            let signature = module
+                .signatures()
+                .shared_type(tag.signature.unwrap_module_type_index())
+                .expect("tag types are registered");
+            let tag = store.store_data_mut().alloc_tag(signature);
+            instance_handle.push_defined_tag(tag);
+        }
+
         // The instance still has lots of setup, for example
         // data/elements/start/etc. This can all fail, but even on failure
         // the instance may persist some state via previous successful
@@ -593,7 +615,7 @@ impl Instance {
         self.get_export(store, name)?.into_global()
     }
 
-    #[cfg(feature = "component-model")]
+    #[cfg(any(feature = "component-model", feature = "snapshot"))]
     pub(crate) fn id(&self, store: &StoreOpaque) -> InstanceId {
         store[self.0].id
     }
@@ -644,6 +666,7 @@ pub(crate) struct OwnedImports {
     tables: PrimaryMap<TableIndex, VMTableImport>,
     memories: PrimaryMap<MemoryIndex, VMMemoryImport>,
     globals: PrimaryMap<GlobalIndex, VMGlobalImport>,
+    tags: PrimaryMap<TagIndex, ExportTag>,
 }
 
 impl OwnedImports {
@@ -659,6 +682,7 @@ impl OwnedImports {
             tables: PrimaryMap::new(),
             memories: PrimaryMap::new(),
             globals: PrimaryMap::new(),
+            tags: PrimaryMap::new(),
         }
     }
 
@@ -668,6 +692,7 @@ impl OwnedImports {
         self.tables.reserve(raw.num_imported_tables);
         self.memories.reserve(raw.num_imported_memories);
         self.globals.reserve(raw.num_imported_globals);
+        self.tags.reserve(raw.num_imported_tags);
     }
 
     #[cfg(feature = "component-model")]
@@ -676,6 +701,7 @@ impl OwnedImports {
         self.tables.clear();
         self.memories.clear();
         self.globals.clear();
+        self.tags.clear();
     }
 
     fn push(&mut self, item: &Extern, store: &mut StoreOpaque, module: &Module) {
@@ -695,6 +721,9 @@ impl OwnedImports {
             Extern::SharedMemory(i) => {
                 self.memories.push(i.vmimport(store));
             }
+            Extern::Tag(i) => {
+                self.tags.push(i.vmimport(store));
+            }
         }
     }
 
@@ -729,6 +758,9 @@ impl OwnedImports {
                     index: m.index,
                 });
             }
+            crate::runtime::vm::Export::Tag(t) => {
+                self.tags.push(*t);
+            }
         }
     }
 
@@ -738,6 +770,7 @@ impl OwnedImports {
             globals: self.globals.values().as_slice(),
             memories: self.memories.values().as_slice(),
             functions: self.functions.values().as_slice(),
+            tags: self.tags.values().as_slice(),
         }
     }
 }
@@ -222,6 +222,20 @@ impl CompiledModule {
         Some((index, text_offset - wasm_func_loc.start))
     }
 
+    /// Looks up the defined function whose body contains `file_offset`, an
+    /// offset within the original wasm module.
+    pub fn func_by_file_offset(&self, file_offset: u32) -> Option<DefinedFuncIndex> {
+        // Function bodies are laid out in the code section in the same order
+        // as their indices, so each body extends to the start of the next.
+        let index = match self.funcs.binary_search_values_by_key(&file_offset, |f| {
+            f.wasm_func_info.start_srcloc.file_offset().unwrap_or(0)
+        }) {
+            Ok(k) => k,
+            Err(k) => DefinedFuncIndex::from_u32(k.as_u32().checked_sub(1)?),
+        };
+        Some(index)
+    }
+
     /// Gets the function location information for a given function index.
     pub fn func_loc(&self, index: DefinedFuncIndex) -> &FunctionLoc {
         &self
@@ -285,6 +299,12 @@ impl CompiledModule {
         self.meta.has_unparsed_debuginfo
     }
 
+    /// Returns the hash of the wasm binary this module was compiled from,
+    /// which for modules within a component is the component itself.
+    pub fn wasm_hash(&self) -> u64 {
+        self.meta.wasm_hash
+    }
+
     /// Indicates whether this module came with n address map such that lookups
     /// via `wasmtime_environ::lookup_file_pos` will succeed.
     ///
@@ -1,4 +1,6 @@
 use crate::prelude::*;
+#[cfg(feature = "async")]
+use core::{future::Future, pin::Pin};
 
 /// Value returned by [`ResourceLimiter::instances`] default method
 pub const DEFAULT_INSTANCE_LIMIT: usize = 10000;
@@ -6,6 +8,10 @@ pub const DEFAULT_INSTANCE_LIMIT: usize = 10000;
 pub const DEFAULT_TABLE_LIMIT: usize = 10000;
 /// Value returned by [`ResourceLimiter::memories`] default method
 pub const DEFAULT_MEMORY_LIMIT: usize = 10000;
+/// Value returned by [`ResourceLimiter::component_instances`] default method
+pub const DEFAULT_COMPONENT_INSTANCE_LIMIT: usize = 10000;
+/// Value returned by [`ResourceLimiter::resource_handles`] default method
+pub const DEFAULT_RESOURCE_HANDLE_LIMIT: usize = 1 << 30;
 
 /// Used by hosts to limit resource consumption of instances.
 ///
@@ -125,6 +131,71 @@ pub trait ResourceLimiter {
         Ok(())
     }
 
+    /// Notifies the resource limiter that the store's GC heap has been
+    /// requested to grow.
+    ///
+    /// * `current` is the current size of the GC heap in bytes.
+    /// * `desired` is the desired size of the GC heap in bytes.
+    /// * `maximum` is the maximum size of the GC heap in bytes, as configured
+    ///   with `Config::gc_heap_maximum_size`.
+    ///
+    /// A GC heap is grown when allocating a GC object fails even after a
+    /// garbage collection. Like `memory_growing`, this function is still
+    /// called when `desired` exceeds `maximum`.
+    ///
+    /// If `Ok(false)` is returned then the GC heap is not grown and the
+    /// allocation fails: Wasm code will trap, and host APIs will return a
+    /// [`GcHeapOutOfMemory`](crate::GcHeapOutOfMemory) error. If `Err(e)` is
+    /// returned then the allocation raises `e` as a trap.
+    ///
+    /// By default, GC heap growth is always allowed.
+    fn gc_heap_growing(&mut self, current: usize, desired: usize, maximum: usize) -> Result<bool> {
+        let _ = (current, desired, maximum);
+        Ok(true)
+    }
+
+    /// Notifies the resource limiter that growing the store's GC heap,
+    /// permitted by the `gc_heap_growing` method, has failed.
+    ///
+    /// Note that this method is not called if `gc_heap_growing` returns an
+    /// error.
+    ///
+    /// See the details on the return values for `memory_grow_failed` for what
+    /// the return value of this function indicates.
+    fn gc_heap_grow_failed(&mut self, error: anyhow::Error) -> Result<()> {
+        log::debug!("ignoring GC heap growth failure error: {error:?}");
+        Ok(())
+    }
+
+    /// Notifies the resource limiter that a new fiber stack is about to be
+    /// allocated for a `Store`.
+    ///
+    /// * `current` is the total size, in bytes, of the fiber stacks the store
+    ///   currently holds. This includes the stacks of in-progress
+    ///   asynchronous calls, the stack the store keeps for its next
+    ///   asynchronous call, and the stacks of live continuations.
+    /// * `desired` is `current` plus the size of the new stack, as configured
+    ///   with [`Config::async_stack_size`](crate::Config::async_stack_size).
+    ///
+    /// This is called when an asynchronous call or instantiation needs a stack
+    /// and the store has no unused stack of its own, e.g. for the first
+    /// asynchronous call into a store. It is also called when a continuation
+    /// created by WebAssembly with the stack-switching proposal is first
+    /// resumed, since every continuation executes on a stack of its own of the
+    /// same size. Stacks are released, and stop counting towards `current`,
+    /// when the call or continuation that needed them finishes.
+    ///
+    /// If `Ok(false)` is returned then the stack is not allocated and the
+    /// asynchronous call or instantiation that required it returns an error,
+    /// or the `resume` instruction that required it traps. If `Err(e)` is
+    /// returned then that call returns `e` instead.
+    ///
+    /// By default, fiber stack allocation is always allowed.
+    fn fiber_stack_allocating(&mut self, current: usize, desired: usize) -> Result<bool> {
+        let _ = (current, desired);
+        Ok(true)
+    }
+
     /// The maximum number of instances that can be created for a `Store`.
     ///
     /// Module instantiation will fail if this limit is exceeded.
@@ -151,13 +222,46 @@ pub trait ResourceLimiter {
     fn memories(&self) -> usize {
         DEFAULT_MEMORY_LIMIT
     }
+
+    /// The maximum number of component instances that can be created for a
+    /// `Store`.
+    ///
+    /// Component instantiation will fail with an error if this limit is
+    /// exceeded. Note that core instances created as part of a component's
+    /// instantiation are additionally limited by [`ResourceLimiter::instances`].
+    ///
+    /// This value defaults to 10,000.
+    fn component_instances(&self) -> usize {
+        DEFAULT_COMPONENT_INSTANCE_LIMIT
+    }
+
+    /// The maximum number of resource handles that each of a `Store`'s
+    /// resource tables can hold at once.
+    ///
+    /// Each component instance has one table per resource type it uses, and
+    /// the store has one table for handles owned by the host through
+    /// [`ResourceAny`](crate::component::ResourceAny). Creating or lowering a
+    /// handle into a full table will trap in WebAssembly or return an error
+    /// to the host.
+    ///
+    /// Host [`ResourceTable`](crate::component::ResourceTable)s, such as the
+    /// one owned by a WASI context, live outside of the store and are limited
+    /// by this value when they are created with
+    /// [`ResourceTable::with_limiter`](crate::component::ResourceTable::with_limiter).
+    ///
+    /// This value defaults to 2<sup>30</sup>, the maximum permitted by the
+    /// component model.
+    fn resource_handles(&self) -> usize {
+        DEFAULT_RESOURCE_HANDLE_LIMIT
+    }
 }
 
 /// Used by hosts to limit resource consumption of instances, blocking
 /// asynchronously if necessary.
 ///
 /// This trait is identical to [`ResourceLimiter`], except that the
-/// `memory_growing` and `table_growing` functions are `async`. Must be used
+/// `memory_growing`, `table_growing`, `gc_heap_growing`, and
+/// `fiber_stack_allocating` functions are `async`. Must be used
 /// with an async [`Store`](`crate::Store`) configured via
 /// [`Config::async_support`](crate::Config::async_support).
 ///
@@ -201,6 +305,49 @@ pub trait ResourceLimiterAsync {
         Ok(())
     }
 
+    /// Asynchronous version of [`ResourceLimiter::gc_heap_growing`]
+    ///
+    /// This may be implemented with an `async fn` in an
+    /// `#[async_trait::async_trait]` impl like the other methods of this
+    /// trait. The default implementation is spelled out by hand so that it
+    /// does not require `Self: Send`.
+    fn gc_heap_growing<'life0, 'async_trait>(
+        &'life0 mut self,
+        current: usize,
+        desired: usize,
+        maximum: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<bool>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let _ = (current, desired, maximum);
+        Box::pin(async { Ok(true) })
+    }
+
+    /// Identical to [`ResourceLimiter::gc_heap_grow_failed`]
+    fn gc_heap_grow_failed(&mut self, error: anyhow::Error) -> Result<()> {
+        log::debug!("ignoring GC heap growth failure error: {error:?}");
+        Ok(())
+    }
+
+    /// Asynchronous version of [`ResourceLimiter::fiber_stack_allocating`]
+    ///
+    /// Like `gc_heap_growing`, the default implementation is spelled out by
+    /// hand so that it does not require `Self: Send`.
+    fn fiber_stack_allocating<'life0, 'async_trait>(
+        &'life0 mut self,
+        current: usize,
+        desired: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<bool>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let _ = (current, desired);
+        Box::pin(async { Ok(true) })
+    }
+
     /// Identical to [`ResourceLimiter::instances`]`
     fn instances(&self) -> usize {
         DEFAULT_INSTANCE_LIMIT
@@ -215,6 +362,16 @@ pub trait ResourceLimiterAsync {
     fn memories(&self) -> usize {
         DEFAULT_MEMORY_LIMIT
     }
+
+    /// Identical to [`ResourceLimiter::component_instances`]
+    fn component_instances(&self) -> usize {
+        DEFAULT_COMPONENT_INSTANCE_LIMIT
+    }
+
+    /// Identical to [`ResourceLimiter::resource_handles`]
+    fn resource_handles(&self) -> usize {
+        DEFAULT_RESOURCE_HANDLE_LIMIT
+    }
 }
 
 /// Used to build [`StoreLimits`].
@@ -254,6 +411,18 @@ impl StoreLimitsBuilder {
         self
     }
 
+    /// The maximum number of bytes the store's GC heap can grow to.
+    ///
+    /// Growing the GC heap beyond this limit will fail, causing the GC
+    /// allocation that needed the space to fail.
+    ///
+    /// By default, the GC heap will not be limited beyond its configured
+    /// maximum size.
+    pub fn gc_heap_size(mut self, limit: usize) -> Self {
+        self.0.gc_heap_size = Some(limit);
+        self
+    }
+
     /// The maximum number of instances that can be created for a [`Store`](crate::Store).
     ///
     /// Module instantiation will fail if this limit is exceeded.
@@ -284,11 +453,53 @@ impl StoreLimitsBuilder {
         self
     }
 
+    /// The maximum number of component instances that can be created for a
+    /// [`Store`](crate::Store).
+    ///
+    /// Component instantiation will fail with an error if this limit is
+    /// exceeded.
+    ///
+    /// This value defaults to 10,000.
+    pub fn component_instances(mut self, limit: usize) -> Self {
+        self.0.component_instances = limit;
+        self
+    }
+
+    /// The maximum number of handles each resource table in a
+    /// [`Store`](crate::Store) can hold.
+    ///
+    /// Creating a resource handle beyond this limit will trap.
+    ///
+    /// This value defaults to 2<sup>30</sup>.
+    pub fn resource_handles(mut self, limit: usize) -> Self {
+        self.0.resource_handles = limit;
+        self
+    }
+
+    /// The maximum total size, in bytes, of the fiber stacks a
+    /// [`Store`](crate::Store) can hold at once.
+    ///
+    /// This covers the stacks of in-progress asynchronous calls, the stack a
+    /// store keeps for its next asynchronous call, and the stacks of live
+    /// continuations created with the stack-switching proposal, as reported by
+    /// [`ResourceUsage::fiber_stack_bytes`](crate::ResourceUsage::fiber_stack_bytes).
+    /// Asynchronous calls into WebAssembly will fail with an error, and
+    /// resuming a new continuation will trap, if the stack they need would
+    /// exceed this limit.
+    ///
+    /// By default, fiber stacks will not be limited.
+    pub fn fiber_stack_bytes(mut self, limit: usize) -> Self {
+        self.0.fiber_stack_bytes = Some(limit);
+        self
+    }
+
     /// Indicates that a trap should be raised whenever a growth operation
     /// would fail.
     ///
     /// This operation will force `memory.grow` and `table.grow` instructions
-    /// to raise a trap on failure instead of returning -1. This is not
+    /// to raise a trap on failure instead of returning -1, failures to grow the
+    /// GC heap to raise a trap with this as their cause, and denied fiber stack
+    /// allocations to return an error describing the limit. This is not
     /// necessarily spec-compliant, but it can be quite handy when debugging a
     /// module that fails to allocate memory and might behave oddly as a result.
     ///
@@ -316,9 +527,13 @@ impl StoreLimitsBuilder {
 pub struct StoreLimits {
     memory_size: Option<usize>,
     table_elements: Option<usize>,
+    gc_heap_size: Option<usize>,
+    fiber_stack_bytes: Option<usize>,
     instances: usize,
     tables: usize,
     memories: usize,
+    component_instances: usize,
+    resource_handles: usize,
     trap_on_grow_failure: bool,
 }
 
@@ -327,9 +542,13 @@ impl Default for StoreLimits {
         Self {
             memory_size: None,
             table_elements: None,
+            gc_heap_size: None,
+            fiber_stack_bytes: None,
             instances: DEFAULT_INSTANCE_LIMIT,
             tables: DEFAULT_TABLE_LIMIT,
             memories: DEFAULT_MEMORY_LIMIT,
+            component_instances: DEFAULT_COMPONENT_INSTANCE_LIMIT,
+            resource_handles: DEFAULT_RESOURCE_HANDLE_LIMIT,
             trap_on_grow_failure: false,
         }
     }
@@ -394,6 +613,39 @@ impl ResourceLimiter for StoreLimits {
         }
     }
 
+    fn gc_heap_growing(&mut self, _current: usize, desired: usize, maximum: usize) -> Result<bool> {
+        let allow = match self.gc_heap_size {
+            Some(limit) if desired > limit => false,
+            _ => desired <= maximum,
+        };
+        if !allow && self.trap_on_grow_failure {
+            bail!("forcing trap when growing GC heap to {desired} bytes")
+        } else {
+            Ok(allow)
+        }
+    }
+
+    fn gc_heap_grow_failed(&mut self, error: anyhow::Error) -> Result<()> {
+        if self.trap_on_grow_failure {
+            Err(error.context("forcing a GC heap growth failure to be a trap"))
+        } else {
+            log::debug!("ignoring GC heap growth failure error: {error:?}");
+            Ok(())
+        }
+    }
+
+    fn fiber_stack_allocating(&mut self, _current: usize, desired: usize) -> Result<bool> {
+        let allow = match self.fiber_stack_bytes {
+            Some(limit) => desired <= limit,
+            None => true,
+        };
+        if !allow && self.trap_on_grow_failure {
+            bail!("forcing error when growing fiber stacks to {desired} bytes")
+        } else {
+            Ok(allow)
+        }
+    }
+
     fn instances(&self) -> usize {
         self.instances
     }
@@ -405,4 +657,12 @@ impl ResourceLimiter for StoreLimits {
     fn memories(&self) -> usize {
         self.memories
     }
+
+    fn component_instances(&self) -> usize {
+        self.component_instances
+    }
+
+    fn resource_handles(&self) -> usize {
+        self.resource_handles
+    }
 }
@@ -19,11 +19,15 @@ use core::ptr::NonNull;
 #[cfg(feature = "std")]
 use std::{fs::File, path::Path};
 use wasmparser::{Parser, ValidPayload, Validator};
+#[cfg(all(feature = "cranelift", feature = "winch"))]
+use wasmtime_environ::StaticModuleIndex;
 use wasmtime_environ::{
     CompiledModuleInfo, EntityIndex, HostPtr, ModuleTypes, ObjectKind, TypeTrace, VMOffsets,
     VMSharedTypeIndex,
 };
 mod registry;
+#[cfg(all(feature = "cranelift", feature = "winch"))]
+pub(crate) mod tier_up;
 
 pub use registry::*;
 
@@ -43,11 +47,12 @@ pub use registry::*;
 /// call to [`Module::deserialize`] will quickly load the module to execute and
 /// does not need to compile any code, representing a more AOT-style use case.
 ///
-/// Currently a `Module` does not implement any form of tiering or dynamic
-/// optimization of compiled code. Creation of a `Module` via [`Module::new`] or
-/// related APIs will perform the entire compilation step synchronously. When
-/// finished no further compilation will happen at runtime or later during
-/// execution of WebAssembly instances for example.
+/// Unless [`Strategy::Tiered`](crate::Strategy::Tiered) is used a `Module`
+/// does not implement any form of tiering or dynamic optimization of compiled
+/// code. Creation of a `Module` via [`Module::new`] or related APIs will
+/// perform the entire compilation step synchronously. When finished no further
+/// compilation will happen at runtime or later during execution of WebAssembly
+/// instances for example.
 ///
 /// Compilation of WebAssembly by default goes through Cranelift and is
 /// recommended to be done once-per-module. The same WebAssembly binary need not
@@ -157,6 +162,11 @@ struct ModuleInner {
 
     /// Runtime offset information for `VMContext`.
     offsets: VMOffsets<HostPtr>,
+
+    /// State for recompiling this module's hot functions with Cranelift, if
+    /// it was compiled with `Strategy::Tiered`.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    tier_up: Option<tier_up::TierUpRef>,
 }
 
 impl fmt::Debug for Module {
@@ -492,7 +502,10 @@ impl Module {
         // Package up all our data into a `CodeObject` and delegate to the final
         // step of module compilation.
         let code = Arc::new(CodeObject::new(code_memory, signatures, types.into()));
-        Module::from_parts_raw(engine, code, info, true)
+        let module = Module::from_parts_raw(engine, code, info, true)?;
+        #[cfg(all(feature = "cranelift", feature = "winch"))]
+        let module = module.with_tier_up(None);
+        Ok(module)
     }
 
     pub(crate) fn from_parts_raw(
@@ -518,10 +531,77 @@ impl Module {
                 module,
                 serializable,
                 offsets,
+                #[cfg(all(feature = "cranelift", feature = "winch"))]
+                tier_up: None,
             }),
         })
     }
 
+    /// Enables recompiling the hot functions of this freshly-created module
+    /// with Cranelift, if its engine uses
+    /// [`Strategy::Tiered`](crate::Strategy::Tiered) and its artifact contains
+    /// the original wasm.
+    ///
+    /// The `module_in_component` is the index of this module within the
+    /// component it was found in, if any.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn with_tier_up(mut self, module_in_component: Option<StaticModuleIndex>) -> Module {
+        if self.engine().tier_up_compiler().is_some()
+            && !self.code_object().code_memory().tier_up_wasm().is_empty()
+        {
+            let tier_up = tier_up::TierUp::new(&self, module_in_component);
+            self.set_tier_up(tier_up::TierUpRef::Baseline(tier_up));
+        }
+        self
+    }
+
+    /// Sets the tiering state of this freshly-created module.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn set_tier_up(&mut self, tier_up: tier_up::TierUpRef) {
+        Arc::get_mut(&mut self.inner)
+            .expect("module should not be shared yet")
+            .tier_up = Some(tier_up);
+    }
+
+    /// Returns the tiering state this module belongs to, if it was compiled
+    /// with [`Strategy::Tiered`](crate::Strategy::Tiered).
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn tier_up(&self) -> Option<Arc<tier_up::TierUp>> {
+        self.inner.tier_up.as_ref()?.get()
+    }
+
+    /// Returns whether any of this module's functions, compiled with
+    /// [`Strategy::Tiered`](crate::Strategy::Tiered), have been recompiled
+    /// with Cranelift.
+    ///
+    /// Functions are recompiled once they've been found running often enough
+    /// when their store reaches its epoch deadline, see
+    /// [`Config::tier_up_samples`](crate::Config::tier_up_samples). Once this
+    /// returns `true` new instances of this module run the recompiled code.
+    /// Existing instances switch to it the next time they reach their epoch
+    /// deadline. Instances of either kind report this module from
+    /// [`Instance::module`](crate::Instance::module). This always returns
+    /// `false` for modules compiled with other strategies.
+    pub fn is_tiered_up(&self) -> bool {
+        #[cfg(all(feature = "cranelift", feature = "winch"))]
+        if let Some(tier_up::TierUpRef::Baseline(tier_up)) = &self.inner.tier_up {
+            return tier_up.latest().is_some();
+        }
+        false
+    }
+
+    /// Returns the module to use when instantiating this module, which is
+    /// its latest recompilation if any of its functions have been tiered up.
+    pub(crate) fn for_instantiation(&self) -> Module {
+        #[cfg(all(feature = "cranelift", feature = "winch"))]
+        if let Some(tier_up::TierUpRef::Baseline(tier_up)) = &self.inner.tier_up {
+            if let Some(latest) = tier_up.latest() {
+                return latest;
+            }
+        }
+        self.clone()
+    }
+
     /// Validates `binary` input data as a WebAssembly binary given the
     /// configuration in `engine`.
     ///
@@ -0,0 +1,230 @@
+//! Recompiling hot functions with Cranelift for `Strategy::Tiered`.
+//!
+//! Modules compiled with `Strategy::Tiered` are first compiled with Winch,
+//! and the original wasm is kept in their artifact. Hot functions are found
+//! by sampling: each time a store reaches its epoch deadline the function
+//! which was running is counted, and once a function has been sampled
+//! `Config::tier_up_samples` times it's considered hot.
+//!
+//! Hot functions are recompiled on a single background thread, shared by all
+//! engines, which is fed through a bounded queue. Each recompilation produces
+//! a new module in which all hot functions found so far are compiled with
+//! Cranelift and all others with Winch again. New instances use the latest
+//! such module, and existing instances switch their escaping functions over
+//! to it the next time they reach their epoch deadline, see
+//! `vm::Instance::replace_code`.
+//!
+//! This is sound because Cranelift compiles code with the Winch calling
+//! convention for tiered engines, so code from either compiler can call each
+//! other, and all recompilations of a module have identical imports, exports,
+//! types and `VMContext` layouts.
+
+use crate::runtime::vm;
+use crate::store::StoreOpaque;
+use crate::{CodeMemory, Engine, Module};
+use alloc::sync::{Arc, Weak};
+use core::sync::atomic::{AtomicU32, Ordering};
+use std::collections::BTreeSet;
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Mutex, OnceLock};
+use wasmtime_environ::{DefinedFuncIndex, PrimaryMap, StaticModuleIndex};
+
+/// The maximum number of modules which may be waiting to be recompiled at
+/// once. Modules which don't fit are queued again the next time one of their
+/// hot functions is sampled.
+const MAX_QUEUED: usize = 16;
+
+/// How a module refers to the tiering state it belongs to.
+pub(crate) enum TierUpRef {
+    /// The module was compiled with Winch and owns the tiering state.
+    Baseline(Arc<TierUp>),
+    /// The module is a recompilation, which is owned by the tiering state.
+    Optimized(Weak<TierUp>),
+}
+
+impl TierUpRef {
+    pub(crate) fn get(&self) -> Option<Arc<TierUp>> {
+        match self {
+            TierUpRef::Baseline(tier_up) => Some(tier_up.clone()),
+            TierUpRef::Optimized(tier_up) => tier_up.upgrade(),
+        }
+    }
+}
+
+pub(crate) struct TierUp {
+    engine: Engine,
+    /// The index of the module within the component whose binary is kept in
+    /// `State::source`, if the module was found in a component.
+    module_in_component: Option<StaticModuleIndex>,
+    /// How many times each defined function has been sampled so far.
+    samples: PrimaryMap<DefinedFuncIndex, AtomicU32>,
+    state: Mutex<State>,
+}
+
+struct State {
+    /// The artifact containing the original wasm, or `None` once no more
+    /// functions will be recompiled.
+    source: Option<Arc<CodeMemory>>,
+    /// Functions which have been sampled often enough to be recompiled.
+    hot: BTreeSet<DefinedFuncIndex>,
+    /// Whether a recompilation has been queued and hasn't finished yet.
+    queued: bool,
+    /// The latest recompilation of the module, if any.
+    latest: Option<Module>,
+    /// How many of the `hot` functions are compiled with Cranelift in
+    /// `latest`.
+    compiled: usize,
+}
+
+impl TierUp {
+    /// Creates the tiering state for `baseline`, a module compiled with Winch
+    /// whose artifact contains the original wasm.
+    pub(crate) fn new(
+        baseline: &Module,
+        module_in_component: Option<StaticModuleIndex>,
+    ) -> Arc<TierUp> {
+        let num_defined_funcs = baseline.env_module().num_defined_funcs();
+        Arc::new(TierUp {
+            engine: baseline.engine().clone(),
+            module_in_component,
+            samples: (0..num_defined_funcs).map(|_| AtomicU32::new(0)).collect(),
+            state: Mutex::new(State {
+                source: Some(baseline.code_object().code_memory().clone()),
+                hot: BTreeSet::new(),
+                queued: false,
+                latest: None,
+                compiled: 0,
+            }),
+        })
+    }
+
+    /// Returns the latest recompilation of the module, if any.
+    pub(crate) fn latest(&self) -> Option<Module> {
+        self.state.lock().unwrap().latest.clone()
+    }
+
+    /// Records that the function `index` was running when its store reached
+    /// its epoch deadline, queueing a recompilation once it's hot.
+    fn sample(self: &Arc<Self>, index: DefinedFuncIndex) {
+        let samples = self.samples[index].fetch_add(1, Ordering::Relaxed);
+        if samples.saturating_add(1) < self.engine.config().get_tier_up_samples() {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.hot.insert(index);
+        if state.source.is_some() && !state.queued && state.hot.len() > state.compiled {
+            state.queued = queue(Arc::downgrade(self));
+        }
+    }
+
+    /// Recompiles the module with all functions found to be hot so far.
+    ///
+    /// This is only ever called on the background thread.
+    fn recompile(self: &Arc<Self>) {
+        let (source, hot) = {
+            let state = self.state.lock().unwrap();
+            match &state.source {
+                Some(source) => (source.clone(), state.hot.clone()),
+                None => return,
+            }
+        };
+        let result = crate::compile::compile_tier_up(
+            &self.engine,
+            source.tier_up_wasm(),
+            self.module_in_component,
+            &hot,
+        );
+
+        let mut state = self.state.lock().unwrap();
+        state.queued = false;
+        match result {
+            Ok(mut module) => {
+                module.set_tier_up(TierUpRef::Optimized(Arc::downgrade(self)));
+                state.latest = Some(module);
+                state.compiled = hot.len();
+            }
+            Err(e) => {
+                log::warn!("failed to recompile hot functions with cranelift: {e:?}");
+                state.source = None;
+                return;
+            }
+        }
+
+        if state.compiled == self.samples.len() {
+            // Every function has been recompiled so nothing is left to do.
+            state.source = None;
+        } else if state.hot.len() > state.compiled {
+            // More functions became hot while this recompilation was running.
+            state.queued = queue(Arc::downgrade(self));
+        }
+    }
+}
+
+/// Queues `tier_up` to be recompiled on the background thread, returning
+/// whether it was queued.
+fn queue(tier_up: Weak<TierUp>) -> bool {
+    static WORKER: OnceLock<Option<SyncSender<Weak<TierUp>>>> = OnceLock::new();
+
+    let worker = WORKER.get_or_init(|| {
+        let (sender, receiver) = mpsc::sync_channel::<Weak<TierUp>>(MAX_QUEUED);
+        let result = std::thread::Builder::new()
+            .name("wasmtime-tier-up".into())
+            .spawn(move || {
+                for tier_up in receiver {
+                    if let Some(tier_up) = tier_up.upgrade() {
+                        tier_up.recompile();
+                    }
+                }
+            });
+        match result {
+            Ok(_) => Some(sender),
+            Err(e) => {
+                log::warn!("failed to spawn thread to recompile hot functions with cranelift: {e}");
+                None
+            }
+        }
+    });
+    match worker {
+        Some(sender) => sender.try_send(tier_up).is_ok(),
+        None => false,
+    }
+}
+
+/// Samples the function `instance` was running when its store reached its
+/// epoch deadline, and switches `instance` over to the latest recompilation
+/// of its module if it isn't using it already.
+pub(crate) fn sample(store: &mut StoreOpaque, instance: &mut vm::Instance) {
+    let Some(module) = instance.runtime_module().cloned() else {
+        return;
+    };
+    let Some(tier_up) = module.tier_up() else {
+        return;
+    };
+
+    // The epoch check which called into the host is within the running
+    // function, which may belong to any recompilation of `module`.
+    //
+    // Safety: the last exit pc is only written when exiting wasm, and we're
+    // on the host side of that exit right now.
+    let pc = unsafe { *store.runtime_limits().last_wasm_exit_pc.get() };
+    if let Some(running) = store.modules().lookup_module_by_pc(pc) {
+        if running.tier_up().is_some_and(|t| Arc::ptr_eq(&t, &tier_up)) {
+            let text_offset = pc - running.text().as_ptr() as usize;
+            if let Some((index, _)) = running.compiled_module().func_by_text_offset(text_offset) {
+                tier_up.sample(index);
+            }
+        }
+    }
+
+    let Some(latest) = tier_up.latest() else {
+        return;
+    };
+    if latest.id() == module.id() {
+        return;
+    }
+    // Keep the new code alive for as long as the store, like the code
+    // `instance` was using so far.
+    store.modules_mut().register_module(&latest);
+    store.register_gc_types(latest.signatures().as_module_map().values().copied());
+    instance.replace_code(latest);
+}
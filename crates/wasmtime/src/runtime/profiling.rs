@@ -1,3 +1,5 @@
+#[cfg(feature = "component-model")]
+use crate::component::Component;
 use crate::instantiate::CompiledModule;
 use crate::prelude::*;
 use crate::runtime::vm::Backtrace;
@@ -10,7 +12,7 @@ use fxprof_processed_profile::{
 use std::ops::Range;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use wasmtime_environ::demangle_function_name_or_index;
+use wasmtime_environ::{demangle_function_name_or_index, FunctionLoc, ProfileData};
 
 // TODO: collect more data
 // - On non-Windows, measure thread-local CPU usage between events with
@@ -76,10 +78,15 @@ pub struct GuestProfiler {
     process: fxprof_processed_profile::ProcessHandle,
     thread: fxprof_processed_profile::ThreadHandle,
     start: Instant,
+    data: Vec<CodeData>,
 }
 
 type Modules = Vec<(Range<usize>, fxprof_processed_profile::LibraryHandle)>;
 
+/// The `ProfileData` of either a core module or of the core modules defined
+/// within a component, along with the text section they're compiled into.
+type CodeData = (Range<usize>, Vec<Module>, ProfileData);
+
 impl GuestProfiler {
     /// Begin profiling a new guest. When this function is called, the current
     /// wall-clock time is recorded as the start time for the guest.
@@ -97,23 +104,63 @@ impl GuestProfiler {
     /// "Security" section of the [`GuestProfiler`] documentation for guidance
     /// on what modules should not be included in this list.
     pub fn new(module_name: &str, interval: Duration, modules: Vec<(String, Module)>) -> Self {
+        let data = module_data(&modules);
+        let libs = modules
+            .into_iter()
+            .filter_map(|(name, module)| module_library(name, &module))
+            .collect();
+        Self::from_libraries(module_name, interval, libs, data)
+    }
+
+    /// Begin profiling a new component guest. This is the same as
+    /// [`GuestProfiler::new`] except that every core module defined within
+    /// `component`, including any fused adapters, and the trampolines compiled
+    /// into it are symbolized under a single library named `component_name`.
+    ///
+    /// The `extra_modules` parameter is the same as the `modules` parameter of
+    /// [`GuestProfiler::new`] and may be used to include frames from modules
+    /// which live outside of the component.
+    #[cfg(feature = "component-model")]
+    pub fn new_component(
+        component_name: &str,
+        interval: Duration,
+        component: Component,
+        extra_modules: Vec<(String, Module)>,
+    ) -> Self {
+        let data = core::iter::once(component_data(&component))
+            .chain(module_data(&extra_modules))
+            .collect();
+        let libs = component_library(component_name.to_string(), &component)
+            .into_iter()
+            .chain(
+                extra_modules
+                    .into_iter()
+                    .filter_map(|(name, module)| module_library(name, &module)),
+            )
+            .collect();
+        Self::from_libraries(component_name, interval, libs, data)
+    }
+
+    fn from_libraries(
+        name: &str,
+        interval: Duration,
+        libs: Vec<(Range<usize>, LibraryInfo)>,
+        data: Vec<CodeData>,
+    ) -> Self {
         let zero = ReferenceTimestamp::from_millis_since_unix_epoch(0.0);
-        let mut profile = Profile::new(module_name, zero, interval.into());
+        let mut profile = Profile::new(name, zero, interval.into());
 
-        let mut modules: Vec<_> = modules
+        let mut modules: Modules = libs
             .into_iter()
-            .filter_map(|(name, module)| {
-                let compiled = module.compiled_module();
-                let text = compiled.text().as_ptr_range();
-                let address_range = text.start as usize..text.end as usize;
-                module_symbols(name, compiled).map(|lib| (address_range, profile.add_lib(lib)))
-            })
+            .map(|(address_range, lib)| (address_range, profile.add_lib(lib)))
             .collect();
 
-        modules.sort_unstable_by_key(|(range, _)| range.start);
+        // Sort by descending start address so `lookup_frames` can find the
+        // last library starting at or before a given PC.
+        modules.sort_unstable_by_key(|(range, _)| core::cmp::Reverse(range.start));
 
         profile.set_reference_timestamp(std::time::SystemTime::now().into());
-        let process = profile.add_process(module_name, 0, Timestamp::from_nanos_since_reference(0));
+        let process = profile.add_process(name, 0, Timestamp::from_nanos_since_reference(0));
         let thread = profile.add_thread(process, 0, Timestamp::from_nanos_since_reference(0), true);
         let start = Instant::now();
         Self {
@@ -122,6 +169,7 @@ impl GuestProfiler {
             process,
             thread,
             start,
+            data,
         }
     }
 
@@ -141,6 +189,9 @@ impl GuestProfiler {
         let frames = lookup_frames(&self.modules, &backtrace);
         self.profile
             .add_sample(self.thread, now, frames, delta.into(), 1);
+        for (range, modules, data) in &mut self.data {
+            add_profile_data_sample(range, modules, data, &backtrace);
+        }
     }
 
     /// Add a marker for transitions between guest and host to the profile.
@@ -175,6 +226,36 @@ impl GuestProfiler {
         }
     }
 
+    /// Returns the samples collected so far for `module`, in a form which can
+    /// be used to guide its compilation with
+    /// [`CodeBuilder::profile_data`](crate::CodeBuilder::profile_data).
+    ///
+    /// This returns `None` if `module` isn't one of the modules this profiler
+    /// was created with.
+    pub fn profile_data(&self, module: &Module) -> Option<&ProfileData> {
+        let range = text_range(module.compiled_module().text());
+        self.data
+            .iter()
+            .find(|(r, _, _)| *r == range)
+            .map(|(_, _, data)| data)
+    }
+
+    /// Returns the samples collected so far for the core modules defined
+    /// within `component`, in a form which can be used to guide its
+    /// compilation with
+    /// [`CodeBuilder::profile_data`](crate::CodeBuilder::profile_data).
+    ///
+    /// This returns `None` if `component` isn't the component this profiler
+    /// was created with.
+    #[cfg(feature = "component-model")]
+    pub fn component_profile_data(&self, component: &Component) -> Option<&ProfileData> {
+        let range = text_range(component.text());
+        self.data
+            .first()
+            .filter(|(r, _, _)| *r == range)
+            .map(|(_, _, data)| data)
+    }
+
     /// When the guest finishes running, call this function to write the
     /// profile to the given `output`. The output is a JSON-formatted object in
     /// the [Firefox "processed profile format"][fmt]. Files in this format may
@@ -193,23 +274,138 @@ impl GuestProfiler {
     }
 }
 
-fn module_symbols(name: String, compiled: &CompiledModule) -> Option<LibraryInfo> {
-    let symbols = Vec::from_iter(compiled.finished_functions().map(|(defined_idx, _)| {
+fn text_range(text: &[u8]) -> Range<usize> {
+    let text = text.as_ptr_range();
+    text.start as usize..text.end as usize
+}
+
+fn module_data(modules: &[(String, Module)]) -> Vec<CodeData> {
+    modules
+        .iter()
+        .map(|(_, module)| {
+            let compiled = module.compiled_module();
+            let data = ProfileData::new(compiled.wasm_hash());
+            (text_range(compiled.text()), vec![module.clone()], data)
+        })
+        .collect()
+}
+
+#[cfg(feature = "component-model")]
+fn component_data(component: &Component) -> CodeData {
+    let wasm_hash = component.wasm_hash();
+    // The modules generated for fused adapters aren't part of the component's
+    // binary, so they're left out of its profile.
+    let modules = component
+        .static_modules()
+        .map(|(_, module)| module)
+        .filter(|module| module.compiled_module().wasm_hash() == wasm_hash)
+        .cloned()
+        .collect();
+    let data = ProfileData::new(wasm_hash);
+    (text_range(component.text()), modules, data)
+}
+
+fn add_profile_data_sample(
+    range: &Range<usize>,
+    modules: &[Module],
+    data: &mut ProfileData,
+    backtrace: &Backtrace,
+) {
+    let mut frames = Vec::new();
+    for frame in backtrace.frames() {
+        if !range.contains(&frame.pc()) {
+            continue;
+        }
+        // Every frame is at a return address, so look up the instruction
+        // just before it, which is the call.
+        let text_offset = frame.pc() - 1 - range.start;
+        let Some(info) = modules
+            .iter()
+            .find_map(|module| crate::FrameInfo::new(module.clone(), text_offset))
+        else {
+            continue;
+        };
+        for info in info.inlined().into_iter().chain([&info]) {
+            let Some(func) = info.func_start() else {
+                continue;
+            };
+            let offset = info.module_offset().and_then(|o| u32::try_from(o).ok());
+            frames.push((func, offset));
+        }
+    }
+    if !frames.is_empty() {
+        data.add_sample(&frames);
+    }
+}
+
+fn module_library(name: String, module: &Module) -> Option<(Range<usize>, LibraryInfo)> {
+    let compiled = module.compiled_module();
+    let symbols = Vec::from_iter(function_symbols(compiled, None));
+    library_info(name, symbols).map(|lib| (text_range(compiled.text()), lib))
+}
+
+#[cfg(feature = "component-model")]
+fn component_library(name: String, component: &Component) -> Option<(Range<usize>, LibraryInfo)> {
+    // All core modules within a component share the component's text section,
+    // so their function locations are already relative to it.
+    let mut symbols = Vec::new();
+    for (index, module) in component.static_modules() {
+        let compiled = module.compiled_module();
+        debug_assert_eq!(compiled.text().as_ptr(), component.text().as_ptr());
+        let prefix = match module.name() {
+            Some(name) => name.to_string(),
+            None => format!("module{}", index.as_u32()),
+        };
+        symbols.extend(function_symbols(compiled, Some(&prefix)));
+    }
+    for (index, locs) in component.trampoline_locs() {
+        let index = index.as_u32();
+        symbols.push(loc_symbol(
+            &locs.wasm_call,
+            format!("component-trampoline[{index}] (wasm-call)"),
+        ));
+        symbols.push(loc_symbol(
+            &locs.array_call,
+            format!("component-trampoline[{index}] (array-call)"),
+        ));
+    }
+    if let Some(loc) = component.resource_drop_trampoline_loc() {
+        symbols.push(loc_symbol(loc, "component-resource-drop".to_string()));
+    }
+    library_info(name, symbols).map(|lib| (text_range(component.text()), lib))
+}
+
+fn function_symbols<'a>(
+    compiled: &'a CompiledModule,
+    prefix: Option<&'a str>,
+) -> impl Iterator<Item = Symbol> + 'a {
+    compiled.finished_functions().map(move |(defined_idx, _)| {
         let loc = compiled.func_loc(defined_idx);
         let func_idx = compiled.module().func_index(defined_idx);
         let mut name = String::new();
+        if let Some(prefix) = prefix {
+            name.push_str(prefix);
+            name.push_str("::");
+        }
         demangle_function_name_or_index(
             &mut name,
             compiled.func_name(func_idx),
             defined_idx.as_u32() as usize,
         )
         .unwrap();
-        Symbol {
-            address: loc.start,
-            size: Some(loc.length),
-            name,
-        }
-    }));
+        loc_symbol(loc, name)
+    })
+}
+
+fn loc_symbol(loc: &FunctionLoc, name: String) -> Symbol {
+    Symbol {
+        address: loc.start,
+        size: Some(loc.length),
+        name,
+    }
+}
+
+fn library_info(name: String, symbols: Vec<Symbol>) -> Option<LibraryInfo> {
     if symbols.is_empty() {
         return None;
     }
@@ -236,7 +432,8 @@ fn lookup_frames<'a>(
         // first, so iterate in reverse.
         .rev()
         .filter_map(|frame| {
-            // Find the first module whose start address includes this PC.
+            // Find the last module whose start address is at or before this
+            // PC, and then check that it actually contains it.
             let module_idx = modules.partition_point(|(range, _)| range.start > frame.pc());
             if let Some((range, lib)) = modules.get(module_idx) {
                 if range.contains(&frame.pc()) {
@@ -0,0 +1,651 @@
+//! Recording and replaying the host calls made by WebAssembly in a [`Store`].
+//!
+//! In record mode each host call made from WebAssembly is logged, along with
+//! its results and every side effect it had on the store, to a trace. In
+//! replay mode host functions aren't invoked at all and instead the next call
+//! in the trace is checked against the call being made and its results and
+//! side effects are applied to the store.
+//!
+//! A trace starts with [`MAGIC`] and [`VERSION`] and is then a sequence of
+//! postcard-encoded [`HostCallEvent`]s, each prefixed with its length as a
+//! little-endian `u32`.
+//!
+//! Side effects are captured by comparing the contents of all memories and the
+//! values of all mutable globals in the store before and after a host call.
+//! Memories are compared page by page against a snapshot kept for the whole
+//! recording, so only the pages which changed since the previous host call are
+//! copied.
+//! For component host calls the resource tables of the calling instance are
+//! captured as well. Only the outermost host call is recorded: anything
+//! happening while it runs, such as the host calling back into WebAssembly,
+//! is part of its side effects.
+
+use crate::prelude::*;
+use crate::runtime::vm::ValRaw;
+use crate::{AsContextMut, Global, Memory, Mutability, Store, StoreContextMut, Trap, Val, ValType};
+use core::mem;
+use core::ptr::NonNull;
+use serde_derive::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use wasmtime_environ::{WasmFuncType, WasmValType};
+
+#[cfg(feature = "component-model")]
+use crate::runtime::vm::component::{ComponentInstance, ResourceTable};
+#[cfg(feature = "component-model")]
+use core::mem::MaybeUninit;
+#[cfg(feature = "component-model")]
+use wasmtime_environ::{component::TypeResourceTableIndex, PrimaryMap};
+
+/// Bytes at the start of every trace.
+const MAGIC: &[u8; 8] = b"\0wasmrr\0";
+
+/// Version of the trace format, bumped whenever [`HostCallEvent`] changes.
+const VERSION: u32 = 1;
+
+/// Two writes to a memory which are fewer than this many bytes apart are
+/// recorded as one write.
+const MERGE_GAP: usize = 16;
+
+/// Granularity at which memories are compared against their snapshots.
+const SNAPSHOT_PAGE_SIZE: usize = 4096;
+
+impl<T> Store<T> {
+    /// Starts recording every host call made by WebAssembly in this store to
+    /// `trace`.
+    ///
+    /// Each host call, whether defined with [`Func`](crate::Func),
+    /// [`Linker`](crate::Linker) or
+    /// [`component::Linker`](crate::component::Linker), is written to `trace`
+    /// along with its results and its side effects on the store once it
+    /// returns. Side effects are the changes made to any memory or mutable
+    /// global in the store and, for component host functions, the resource
+    /// handles of the calling component instance. The resulting trace can
+    /// later be fed back with [`Store::replay_host_calls`] to reproduce this
+    /// execution without the host.
+    ///
+    /// Each call is written with a single call to [`Write::write_all`], so it
+    /// is recommended to provide a buffered writer if recording a large number
+    /// of host calls.
+    ///
+    /// Note that recording has a cost proportional to the size of the
+    /// memories in the store: they are compared page by page against a
+    /// snapshot before and after every host call, and the pages which changed
+    /// are copied into the snapshot.
+    ///
+    /// # Limitations
+    ///
+    /// Host calls with parameters or results of reference types and changes
+    /// to tables are not supported. Stores with shared memories can't be
+    /// recorded at all. Host errors are recorded as messages, except for
+    /// [`Trap`]s which are recorded exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this store is already recording or replaying, if
+    /// it has a shared memory, or if writing to `trace` fails. Host calls
+    /// made while recording return an error if a shared memory has been
+    /// added to the store since.
+    pub fn record_host_calls(
+        &mut self,
+        mut trace: impl Write + Send + Sync + 'static,
+    ) -> Result<()> {
+        let mut store = self.as_context_mut();
+        if store.0.host_call_trace.is_some() {
+            bail!("store is already recording or replaying host calls");
+        }
+        state(&mut store)?;
+        trace.write_all(MAGIC)?;
+        trace.write_all(&VERSION.to_le_bytes())?;
+        store.0.host_call_trace = Some(Box::new(HostCallTrace {
+            mode: Mode::Record {
+                output: Box::new(trace),
+                snapshots: Vec::new(),
+            },
+            depth: 0,
+        }));
+        Ok(())
+    }
+
+    /// Starts replaying host calls made by WebAssembly in this store from a
+    /// `trace` produced by [`Store::record_host_calls`].
+    ///
+    /// While replaying, host functions and call hooks are not invoked. Instead
+    /// each host call is checked against the next call in the trace, and its
+    /// recorded results and side effects are applied. This requires that the
+    /// same modules or components are instantiated in the same order, and
+    /// called with the same arguments, as when recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this store is already recording or replaying, if
+    /// it has a shared memory, or if `trace` isn't a host call trace. Host
+    /// calls made while replaying return an error if they don't match the
+    /// trace, if the trace has been exhausted, or if a shared memory has been
+    /// added to the store since.
+    pub fn replay_host_calls(
+        &mut self,
+        mut trace: impl Read + Send + Sync + 'static,
+    ) -> Result<()> {
+        let mut store = self.as_context_mut();
+        if store.0.host_call_trace.is_some() {
+            bail!("store is already recording or replaying host calls");
+        }
+        state(&mut store)?;
+        let mut header = [0; MAGIC.len() + 4];
+        trace
+            .read_exact(&mut header)
+            .context("failed to read host call trace header")?;
+        if &header[..MAGIC.len()] != MAGIC {
+            bail!("not a host call trace");
+        }
+        let version = u32::from_le_bytes(header[MAGIC.len()..].try_into().unwrap());
+        if version != VERSION {
+            bail!("unsupported host call trace version {version}, expected {VERSION}");
+        }
+        store.0.host_call_trace = Some(Box::new(HostCallTrace {
+            mode: Mode::Replay(Box::new(trace)),
+            depth: 0,
+        }));
+        Ok(())
+    }
+}
+
+/// Per-store state for recording or replaying host calls.
+pub(crate) struct HostCallTrace {
+    mode: Mode,
+    /// Number of recorded host calls currently executing, used to only record
+    /// the outermost one.
+    depth: u32,
+}
+
+enum Mode {
+    Record {
+        output: Box<dyn Write + Send + Sync>,
+        /// The contents of each memory as of the last host call, used to find
+        /// what the next host call writes.
+        snapshots: Vec<Vec<u8>>,
+    },
+    Replay(Box<dyn Read + Send + Sync>),
+}
+
+/// A host call as seen from WebAssembly.
+pub(crate) enum HostCallSite<'a> {
+    /// A core host function of type `ty` whose arguments and results live in
+    /// `args`.
+    Core {
+        ty: &'a WasmFuncType,
+        args: NonNull<[ValRaw]>,
+    },
+    /// A component host function of type `ty` called from `instance`.
+    /// Arguments and `results` flat results live in `storage`.
+    #[cfg(feature = "component-model")]
+    Component {
+        ty: u32,
+        instance: *mut ComponentInstance,
+        results: usize,
+        storage: NonNull<[MaybeUninit<ValRaw>]>,
+    },
+}
+
+/// What to do about a host call, as determined by [`enter_host_call`].
+pub(crate) enum HostCall {
+    /// The host function should be called as usual.
+    Untraced,
+    /// The host function should be called and then passed to
+    /// [`exit_host_call`] to record it.
+    Recording(Box<PendingHostCall>),
+    /// The host call was replayed from the trace, so the host function must
+    /// not be called.
+    Replayed,
+}
+
+/// State captured before a host call is made while recording.
+pub(crate) struct PendingHostCall {
+    callee: Callee,
+    /// The snapshots of [`Mode::Record`], updated to the contents of each
+    /// memory before the call.
+    snapshots: Vec<Vec<u8>>,
+    globals: Vec<Option<RawVal>>,
+    #[cfg(feature = "component-model")]
+    resource_tables: Option<PrimaryMap<TypeResourceTableIndex, ResourceTable>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HostCallEvent {
+    callee: Callee,
+    outcome: Outcome,
+    memories: Vec<MemoryWrites>,
+    globals: Vec<(u32, RawVal)>,
+    #[cfg(feature = "component-model")]
+    resource_tables: Option<PrimaryMap<TypeResourceTableIndex, ResourceTable>>,
+}
+
+/// Identifies the host function called, used to detect divergence when
+/// replaying.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Callee {
+    Core { params: Vec<RawVal> },
+    Component { ty: u32 },
+}
+
+#[derive(Serialize, Deserialize)]
+enum Outcome {
+    Return(Vec<RawVal>),
+    Trap(u8),
+    Error(String),
+}
+
+/// The changes made to the memory at `index` in the store.
+#[derive(Serialize, Deserialize)]
+struct MemoryWrites {
+    index: u32,
+    /// The new size of the memory in bytes, if it grew.
+    grown: Option<u64>,
+    writes: Vec<(u64, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum RawVal {
+    I32(u32),
+    I64(u64),
+    F32(u32),
+    F64(u64),
+    V128(u128),
+}
+
+impl RawVal {
+    fn load(ty: &WasmValType, raw: &ValRaw) -> Result<RawVal> {
+        Ok(match ty {
+            WasmValType::I32 => RawVal::I32(raw.get_u32()),
+            WasmValType::I64 => RawVal::I64(raw.get_u64()),
+            WasmValType::F32 => RawVal::F32(raw.get_f32()),
+            WasmValType::F64 => RawVal::F64(raw.get_f64()),
+            WasmValType::V128 => RawVal::V128(raw.get_v128()),
+            WasmValType::Ref(_) => {
+                bail!("host calls with reference types cannot be recorded or replayed")
+            }
+        })
+    }
+
+    fn to_raw(self) -> ValRaw {
+        match self {
+            RawVal::I32(x) => ValRaw::u32(x),
+            RawVal::I64(x) => ValRaw::u64(x),
+            RawVal::F32(x) => ValRaw::f32(x),
+            RawVal::F64(x) => ValRaw::f64(x),
+            RawVal::V128(x) => ValRaw::v128(x),
+        }
+    }
+
+    fn from_val(val: Val) -> Option<RawVal> {
+        match val {
+            Val::I32(x) => Some(RawVal::I32(x as u32)),
+            Val::I64(x) => Some(RawVal::I64(x as u64)),
+            Val::F32(x) => Some(RawVal::F32(x)),
+            Val::F64(x) => Some(RawVal::F64(x)),
+            Val::V128(x) => Some(RawVal::V128(x.as_u128())),
+            Val::FuncRef(_) | Val::ExternRef(_) | Val::AnyRef(_) => None,
+        }
+    }
+
+    fn to_val(self) -> Val {
+        match self {
+            RawVal::I32(x) => Val::I32(x as i32),
+            RawVal::I64(x) => Val::I64(x as i64),
+            RawVal::F32(x) => Val::F32(x),
+            RawVal::F64(x) => Val::F64(x),
+            RawVal::V128(x) => Val::V128(x.into()),
+        }
+    }
+}
+
+impl HostCallSite<'_> {
+    unsafe fn callee(&self) -> Result<Callee> {
+        match self {
+            HostCallSite::Core { ty, args } => {
+                let args = args.as_ref();
+                let params = ty
+                    .params()
+                    .iter()
+                    .zip(args)
+                    .map(|(ty, raw)| RawVal::load(ty, raw))
+                    .collect::<Result<_>>()?;
+                Ok(Callee::Core { params })
+            }
+            #[cfg(feature = "component-model")]
+            HostCallSite::Component { ty, .. } => Ok(Callee::Component { ty: *ty }),
+        }
+    }
+
+    /// Reads the results of this host call after the host function returned.
+    unsafe fn results(&self) -> Result<Vec<RawVal>> {
+        match self {
+            HostCallSite::Core { ty, args } => {
+                let args = args.as_ref();
+                ty.returns()
+                    .iter()
+                    .zip(args)
+                    .map(|(ty, raw)| RawVal::load(ty, raw))
+                    .collect()
+            }
+            // Flat results of component functions are never `v128` and are
+            // always written with `ValRaw` constructors which initialize at
+            // least 64 bits.
+            #[cfg(feature = "component-model")]
+            HostCallSite::Component {
+                results, storage, ..
+            } => Ok(storage.as_ref()[..*results]
+                .iter()
+                .map(|raw| RawVal::I64(raw.assume_init_ref().get_u64()))
+                .collect()),
+        }
+    }
+
+    /// Writes the recorded `results` of this host call.
+    unsafe fn set_results(&self, results: &[RawVal]) -> Result<()> {
+        match self {
+            HostCallSite::Core { ty, args } => {
+                if results.len() != ty.returns().len() {
+                    bail!("host call trace has the wrong number of results");
+                }
+                let args = &mut *args.as_ptr();
+                for (dst, src) in args.iter_mut().zip(results) {
+                    *dst = src.to_raw();
+                }
+            }
+            #[cfg(feature = "component-model")]
+            HostCallSite::Component {
+                results: count,
+                storage,
+                ..
+            } => {
+                if results.len() != *count {
+                    bail!("host call trace has the wrong number of results");
+                }
+                let storage = &mut *storage.as_ptr();
+                for (dst, src) in storage.iter_mut().zip(results) {
+                    dst.write(src.to_raw());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "component-model")]
+    unsafe fn resource_tables(
+        &self,
+    ) -> Option<&mut PrimaryMap<TypeResourceTableIndex, ResourceTable>> {
+        match self {
+            HostCallSite::Core { .. } => None,
+            HostCallSite::Component { instance, .. } => {
+                Some((**instance).component_resource_tables())
+            }
+        }
+    }
+}
+
+/// Called before a host function is invoked, returning whether it should be
+/// invoked.
+///
+/// # Safety
+///
+/// The pointers in `site` must be valid for the duration of the host call.
+pub(crate) unsafe fn enter_host_call<T>(
+    store: &mut StoreContextMut<'_, T>,
+    site: &HostCallSite<'_>,
+) -> Result<HostCall> {
+    let Some(trace) = &mut store.0.host_call_trace else {
+        return Ok(HostCall::Untraced);
+    };
+    if trace.depth > 0 {
+        return Ok(HostCall::Untraced);
+    }
+    match &mut trace.mode {
+        Mode::Record { snapshots, .. } => {
+            let callee = site.callee()?;
+            let mut snapshots = mem::take(snapshots);
+            let (memories, globals) = state(store)?;
+            snapshots.resize_with(memories.len(), Vec::new);
+            for (memory, snapshot) in memories.iter().zip(&mut snapshots) {
+                update_snapshot(snapshot, memory.data(&*store));
+            }
+            store.0.host_call_trace.as_mut().unwrap().depth += 1;
+            Ok(HostCall::Recording(Box::new(PendingHostCall {
+                callee,
+                snapshots,
+                globals: globals
+                    .iter()
+                    .map(|g| g.and_then(|g| RawVal::from_val(g.get(&mut *store))))
+                    .collect(),
+                #[cfg(feature = "component-model")]
+                resource_tables: site.resource_tables().map(|t| t.clone()),
+            })))
+        }
+        Mode::Replay(input) => {
+            let event = read_event(input)?;
+            let callee = site.callee()?;
+            if event.callee != callee {
+                bail!(
+                    "host call diverged from the trace: expected {:?} but found {callee:?}",
+                    event.callee
+                );
+            }
+            replay(store, site, event)?;
+            Ok(HostCall::Replayed)
+        }
+    }
+}
+
+/// Called after a host function returned `result` to record it.
+///
+/// # Safety
+///
+/// Same as [`enter_host_call`].
+pub(crate) unsafe fn exit_host_call<T>(
+    store: &mut StoreContextMut<'_, T>,
+    site: &HostCallSite<'_>,
+    call: Box<PendingHostCall>,
+    result: Result<()>,
+) -> Result<()> {
+    store.0.host_call_trace.as_mut().unwrap().depth -= 1;
+    let outcome = match &result {
+        Ok(()) => Outcome::Return(site.results()?),
+        Err(e) => match e.downcast_ref::<Trap>() {
+            Some(trap) => Outcome::Trap(*trap as u8),
+            None => Outcome::Error(format!("{e:#}")),
+        },
+    };
+
+    let mut snapshots = call.snapshots;
+    let (memories, globals) = state(store)?;
+    snapshots.resize_with(memories.len(), Vec::new);
+    let memories = memories
+        .iter()
+        .zip(&mut snapshots)
+        .enumerate()
+        .filter_map(|(index, (memory, snapshot))| {
+            let after = memory.data(&*store);
+            let grown = after.len() > snapshot.len();
+            let writes = update_snapshot(snapshot, after);
+            if writes.is_empty() && !grown {
+                return None;
+            }
+            Some(MemoryWrites {
+                index: u32::try_from(index).unwrap(),
+                grown: grown.then(|| u64::try_from(after.len()).unwrap()),
+                writes,
+            })
+        })
+        .collect();
+    let globals = globals
+        .iter()
+        .zip(call.globals)
+        .enumerate()
+        .filter_map(|(index, (global, before))| {
+            let after = RawVal::from_val(global.as_ref()?.get(&mut *store))?;
+            (Some(after) != before).then(|| (u32::try_from(index).unwrap(), after))
+        })
+        .collect();
+    #[cfg(feature = "component-model")]
+    let resource_tables = match (call.resource_tables, site.resource_tables()) {
+        (Some(before), Some(after)) if before != *after => Some(after.clone()),
+        _ => None,
+    };
+
+    let event = HostCallEvent {
+        callee: call.callee,
+        outcome,
+        memories,
+        globals,
+        #[cfg(feature = "component-model")]
+        resource_tables,
+    };
+    let Mode::Record {
+        output,
+        snapshots: trace_snapshots,
+    } = &mut store.0.host_call_trace.as_mut().unwrap().mode
+    else {
+        unreachable!()
+    };
+    *trace_snapshots = snapshots;
+    write_event(output, &event).context("failed to record host call")?;
+    result
+}
+
+/// Returns the memories and mutable globals of the store, in a deterministic
+/// order. Immutable globals are `None`.
+///
+/// Returns an error if the store has a shared memory, since writes to those
+/// can't be attributed to a host call.
+fn state<T>(store: &mut StoreContextMut<'_, T>) -> Result<(Vec<Memory>, Vec<Option<Global>>)> {
+    let memories = store.0.all_memories().collect::<Vec<_>>();
+    if memories.iter().any(|m| m.ty(&*store).is_shared()) {
+        bail!("host calls cannot be recorded or replayed in a store with shared memories");
+    }
+    let mut globals = Vec::new();
+    store.0.for_each_global(|store, global| {
+        let ty = global._ty(store);
+        let recordable =
+            ty.mutability() == Mutability::Var && !matches!(ty.content(), ValType::Ref(_));
+        globals.push(recordable.then_some(global));
+    });
+    Ok((memories, globals))
+}
+
+/// Updates `snapshot` to the contents of `after`, returning the regions which
+/// differed. `snapshot` is zero-extended to the length of `after` first.
+///
+/// Pages which are unchanged are skipped with a single comparison, so only
+/// the pages which were written to are compared byte by byte and copied.
+fn update_snapshot(snapshot: &mut Vec<u8>, after: &[u8]) -> Vec<(u64, Vec<u8>)> {
+    snapshot.resize(after.len(), 0);
+    let mut writes: Vec<(usize, Vec<u8>)> = Vec::new();
+    let pages = snapshot
+        .chunks_mut(SNAPSHOT_PAGE_SIZE)
+        .zip(after.chunks(SNAPSHOT_PAGE_SIZE));
+    for (page, (old, new)) in pages.enumerate() {
+        if old == new {
+            continue;
+        }
+        let start = page * SNAPSHOT_PAGE_SIZE;
+        for i in (0..new.len()).filter(|&i| old[i] != new[i]) {
+            let i = start + i;
+            match writes.last_mut() {
+                Some((offset, bytes)) if *offset + bytes.len() + MERGE_GAP > i => {
+                    bytes.extend_from_slice(&after[*offset + bytes.len()..=i]);
+                }
+                _ => writes.push((i, vec![after[i]])),
+            }
+        }
+        old.copy_from_slice(new);
+    }
+    writes
+        .into_iter()
+        .map(|(offset, bytes)| (u64::try_from(offset).unwrap(), bytes))
+        .collect()
+}
+
+/// Applies a recorded host call to the store.
+unsafe fn replay<T>(
+    store: &mut StoreContextMut<'_, T>,
+    site: &HostCallSite<'_>,
+    event: HostCallEvent,
+) -> Result<()> {
+    let (memories, globals) = state(store)?;
+    for writes in event.memories {
+        let memory = memories
+            .get(usize::try_from(writes.index)?)
+            .copied()
+            .context("host call trace refers to an unknown memory")?;
+        if let Some(size) = writes.grown {
+            let page_size = memory.page_size(&*store);
+            let current = u64::try_from(memory.data_size(&*store))?;
+            if size > current {
+                memory.grow(&mut *store, (size - current) / page_size)?;
+            }
+        }
+        let data = memory.data_mut(&mut *store);
+        for (offset, bytes) in writes.writes {
+            let offset = usize::try_from(offset)?;
+            data.get_mut(offset..)
+                .and_then(|d| d.get_mut(..bytes.len()))
+                .context("host call trace writes out of bounds of memory")?
+                .copy_from_slice(&bytes);
+        }
+    }
+    for (index, value) in event.globals {
+        let global = globals
+            .get(usize::try_from(index)?)
+            .copied()
+            .flatten()
+            .context("host call trace refers to an unknown global")?;
+        global.set(&mut *store, value.to_val())?;
+    }
+    #[cfg(feature = "component-model")]
+    if let Some(tables) = event.resource_tables {
+        *site
+            .resource_tables()
+            .context("host call trace has resource tables for a core host call")? = tables;
+    }
+    match event.outcome {
+        Outcome::Return(results) => site.set_results(&results),
+        Outcome::Trap(code) => match Trap::from_u8(code) {
+            Some(trap) => Err(trap.into()),
+            None => bail!("host call trace has an invalid trap code {code}"),
+        },
+        Outcome::Error(message) => Err(anyhow!(message)),
+    }
+}
+
+fn write_event(output: &mut dyn Write, event: &HostCallEvent) -> Result<()> {
+    let bytes = postcard::to_allocvec(event)?;
+    let mut buf = Vec::with_capacity(bytes.len() + 4);
+    buf.extend_from_slice(&u32::try_from(bytes.len())?.to_le_bytes());
+    buf.extend_from_slice(&bytes);
+    output.write_all(&buf)?;
+    Ok(())
+}
+
+fn read_event(input: &mut dyn Read) -> Result<HostCallEvent> {
+    let mut len = [0; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            bail!("host call trace exhausted")
+        }
+        Err(e) => return Err(e).context("failed to read host call trace"),
+    }
+    let mut bytes = vec![0; usize::try_from(u32::from_le_bytes(len))?];
+    input
+        .read_exact(&mut bytes)
+        .context("failed to read host call trace")?;
+    Ok(postcard::from_bytes(&bytes)?)
+}
+
+impl Drop for HostCallTrace {
+    fn drop(&mut self) {
+        if let Mode::Record { output, .. } = &mut self.mode {
+            let _ = output.flush();
+        }
+    }
+}
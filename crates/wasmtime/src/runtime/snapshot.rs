@@ -0,0 +1,551 @@
+use crate::prelude::*;
+use crate::runtime::vm::TableElement;
+use crate::store::StoreOpaque;
+use crate::{AsContextMut, Instance, Mutability, Val, V128};
+use core::ops::Range;
+use serde_derive::{Deserialize, Serialize};
+use wasm_encoder::Encode;
+use wasmparser::{ExternalKind, Parser, Payload};
+use wasmtime_environ::{packed_option::ReservedValue, FuncIndex, TableInitialValue};
+
+/// A snapshot of the state of an [`Instance`] of a core wasm module.
+///
+/// Snapshots are taken with [`Instance::snapshot`] and capture the contents
+/// of all memories and the values of all mutable globals that the instance
+/// defines. A snapshot can later be used in one of two ways:
+///
+/// * It can be applied to a fresh instance of the same module, possibly in
+///   another [`Store`](crate::Store), with [`InstanceSnapshot::restore`].
+///   Snapshots can be moved between processes with
+///   [`InstanceSnapshot::serialize`] and [`InstanceSnapshot::deserialize`].
+///
+/// * It can be baked into the original module with
+///   [`InstanceSnapshot::preinitialize`], producing a new module whose
+///   instances start out in the snapshotted state. This is what `wasmtime
+///   compile --init-func` does, and allows running an expensive initialization
+///   function once ahead of time rather than on every instantiation.
+///
+/// # Limitations
+///
+/// Only state which the instance defines is captured. Imported memories and
+/// globals are not part of the snapshot. Tables are not captured either, so
+/// snapshotting fails unless every table defined by the instance still has the
+/// size and contents produced by the module's element segments. Shared
+/// memories and mutable globals of reference types are not supported and cause
+/// snapshotting to fail. Together these ensure that no GC objects are
+/// reachable from the snapshotted state, so the GC heap is never captured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceSnapshot {
+    memories: Vec<MemorySnapshot>,
+    globals: Vec<Option<GlobalValue>>,
+}
+
+/// The contents of one defined memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MemorySnapshot {
+    /// Size of the memory, in pages.
+    size: u64,
+    /// Whether this is a 64-bit memory.
+    is_64: bool,
+    /// Non-zero regions of the memory as `(offset, bytes)` pairs. Everything
+    /// else is zero.
+    segments: Vec<(u64, Vec<u8>)>,
+}
+
+/// The value of a defined mutable global.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum GlobalValue {
+    I32(i32),
+    I64(i64),
+    F32(u32),
+    F64(u64),
+    V128(u128),
+}
+
+impl Instance {
+    /// Takes a snapshot of the memories and mutable globals defined by this
+    /// instance.
+    ///
+    /// See [`InstanceSnapshot`] for what is, and is not, captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this instance defines a shared memory, a mutable
+    /// global of a reference type, or a table which was modified after
+    /// instantiation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this instance.
+    pub fn snapshot(&self, mut store: impl AsContextMut) -> Result<InstanceSnapshot> {
+        let mut store = store.as_context_mut();
+        let module = self.module(&store).clone();
+        let env_module = module.env_module();
+
+        check_tables_unmodified(store.0, self)?;
+
+        let mut memories = Vec::new();
+        for (index, memory) in self.all_memories(store.0).collect::<Vec<_>>() {
+            if env_module.is_imported_memory(index) {
+                continue;
+            }
+            let ty = memory.ty(&store);
+            if ty.is_shared() {
+                bail!("cannot snapshot shared memory {}", index.as_u32());
+            }
+            let segments = nonzero_segments(memory.data(&store))
+                .map(|(offset, bytes)| (offset as u64, bytes.to_vec()))
+                .collect();
+            memories.push(MemorySnapshot {
+                size: memory.size(&store),
+                is_64: ty.is_64(),
+                segments,
+            });
+        }
+
+        let mut globals = Vec::new();
+        for (index, global) in self.all_globals(store.0).collect::<Vec<_>>() {
+            if env_module.is_imported_global(index) {
+                continue;
+            }
+            let ty = global.ty(&store);
+            if ty.mutability() == Mutability::Const {
+                globals.push(None);
+                continue;
+            }
+            let value = match global.get(&mut store) {
+                Val::I32(x) => GlobalValue::I32(x),
+                Val::I64(x) => GlobalValue::I64(x),
+                Val::F32(x) => GlobalValue::F32(x),
+                Val::F64(x) => GlobalValue::F64(x),
+                Val::V128(x) => GlobalValue::V128(x.as_u128()),
+                Val::FuncRef(_) | Val::ExternRef(_) | Val::AnyRef(_) => bail!(
+                    "cannot snapshot mutable global {} of reference type",
+                    index.as_u32()
+                ),
+            };
+            globals.push(Some(value));
+        }
+
+        Ok(InstanceSnapshot { memories, globals })
+    }
+}
+
+impl InstanceSnapshot {
+    /// Applies this snapshot to `instance`, which must be an instance of the
+    /// same module that the snapshot was taken from.
+    ///
+    /// Each defined memory is grown to its snapshotted size and its contents
+    /// are overwritten, and each defined mutable global is set to its
+    /// snapshotted value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `instance` doesn't define the same memories and
+    /// globals as the snapshotted instance, or if a memory is already larger
+    /// than its snapshotted size or fails to grow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own `instance`.
+    pub fn restore(&self, mut store: impl AsContextMut, instance: &Instance) -> Result<()> {
+        let mut store = store.as_context_mut();
+        let module = instance.module(&store).clone();
+        let env_module = module.env_module();
+
+        let memories = instance
+            .all_memories(store.0)
+            .filter(|(index, _)| !env_module.is_imported_memory(*index))
+            .collect::<Vec<_>>();
+        if memories.len() != self.memories.len() {
+            bail!(
+                "snapshot has {} memories but the instance defines {}",
+                self.memories.len(),
+                memories.len()
+            );
+        }
+        for ((index, memory), snapshot) in memories.into_iter().zip(&self.memories) {
+            let ty = memory.ty(&store);
+            if ty.is_shared() || ty.is_64() != snapshot.is_64 {
+                bail!("memory {} does not match the snapshot", index.as_u32());
+            }
+            let size = memory.size(&store);
+            if size > snapshot.size {
+                bail!(
+                    "memory {} is larger than its snapshot ({size} > {} pages)",
+                    index.as_u32(),
+                    snapshot.size
+                );
+            }
+            if size < snapshot.size {
+                memory.grow(&mut store, snapshot.size - size)?;
+            }
+            let data = memory.data_mut(&mut store);
+            data.fill(0);
+            for (offset, bytes) in snapshot.segments.iter() {
+                let offset = usize::try_from(*offset)?;
+                data[offset..][..bytes.len()].copy_from_slice(bytes);
+            }
+        }
+
+        let globals = instance
+            .all_globals(store.0)
+            .filter(|(index, _)| !env_module.is_imported_global(*index))
+            .collect::<Vec<_>>();
+        if globals.len() != self.globals.len() {
+            bail!(
+                "snapshot has {} globals but the instance defines {}",
+                self.globals.len(),
+                globals.len()
+            );
+        }
+        for ((index, global), value) in globals.into_iter().zip(&self.globals) {
+            let Some(value) = value else {
+                continue;
+            };
+            global
+                .set(&mut store, value.to_val())
+                .with_context(|| format!("failed to restore global {}", index.as_u32()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Serializes this snapshot into bytes which can be turned back into an
+    /// [`InstanceSnapshot`] with [`InstanceSnapshot::deserialize`].
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        Ok(postcard::to_allocvec(self)?)
+    }
+
+    /// Deserializes a snapshot previously produced by
+    /// [`InstanceSnapshot::serialize`].
+    pub fn deserialize(bytes: &[u8]) -> Result<InstanceSnapshot> {
+        Ok(postcard::from_bytes(bytes)?)
+    }
+
+    /// Rewrites the WebAssembly binary `wasm`, which must be the module this
+    /// snapshot was taken from, into a module whose instances start out in the
+    /// snapshotted state.
+    ///
+    /// The returned module has the following changes:
+    ///
+    /// * Memories defined by the module have their minimum size set to the
+    ///   snapshotted size, and their active data segments are replaced with
+    ///   the snapshotted contents.
+    /// * Mutable globals defined by the module are initialized to their
+    ///   snapshotted values.
+    /// * The start function, if any, is removed as it has already run.
+    /// * If `init_func` is provided then the export of that name is removed,
+    ///   so the initialization function can't accidentally be run again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `wasm` isn't a valid module binary or doesn't
+    /// define the memories and globals captured in this snapshot.
+    pub fn preinitialize(&self, wasm: &[u8], init_func: Option<&str>) -> Result<Vec<u8>> {
+        let mut module = wasm_encoder::Module::new();
+        let mut num_imported_memories = 0;
+        let mut data_emitted = false;
+        let mut memories_seen = false;
+        let mut globals_seen = false;
+
+        // Passive data segments, and those which initialize imported
+        // memories, are kept as-is. The data count section precedes the data
+        // section so the original segments are found up-front.
+        let original_data = original_data_segments(wasm)?;
+        let snapshot_data = self
+            .memories
+            .iter()
+            .map(|memory| memory.segments.len())
+            .sum::<usize>();
+        let data_count =
+            u32::try_from(original_data.len() + snapshot_data).context("too many data segments")?;
+
+        for payload in Parser::new(0).parse_all(wasm) {
+            let payload = payload?;
+            match payload {
+                Payload::Version { encoding, .. } => {
+                    if encoding != wasmparser::Encoding::Module {
+                        bail!("only core wasm modules can be preinitialized");
+                    }
+                }
+                Payload::ImportSection(ref reader) => {
+                    for import in reader.clone() {
+                        if let wasmparser::TypeRef::Memory(_) = import?.ty {
+                            num_imported_memories += 1;
+                        }
+                    }
+                    copy_section(&mut module, wasm, &payload);
+                }
+                Payload::MemorySection(reader) => {
+                    if usize::try_from(reader.count()).ok() != Some(self.memories.len()) {
+                        bail!("module does not define the snapshotted memories");
+                    }
+                    memories_seen = true;
+                    let mut memories = wasm_encoder::MemorySection::new();
+                    for (ty, snapshot) in reader.into_iter().zip(&self.memories) {
+                        let ty = ty?;
+                        memories.memory(wasm_encoder::MemoryType {
+                            minimum: snapshot.size,
+                            maximum: ty.maximum,
+                            memory64: ty.memory64,
+                            shared: ty.shared,
+                            page_size_log2: ty.page_size_log2,
+                        });
+                    }
+                    module.section(&memories);
+                }
+                Payload::GlobalSection(reader) => {
+                    let mut globals = wasm_encoder::GlobalSection::new();
+                    let end = reader.range().end;
+                    let entries = reader
+                        .into_iter_with_offsets()
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if entries.len() != self.globals.len() {
+                        bail!("module does not define the snapshotted globals");
+                    }
+                    globals_seen = true;
+                    for (i, ((start, global), value)) in
+                        entries.iter().zip(&self.globals).enumerate()
+                    {
+                        let entry_end = entries.get(i + 1).map_or(end, |(offset, _)| *offset);
+                        let entry = &wasm[*start..entry_end];
+                        match value {
+                            Some(value) => {
+                                // Keep the encoded global type, replacing only
+                                // its initializer.
+                                let init = global.init_expr.get_binary_reader().original_position();
+                                let mut bytes = wasm[*start..init].to_vec();
+                                value.const_expr().encode(&mut bytes);
+                                globals.raw(&bytes);
+                            }
+                            None => {
+                                globals.raw(entry);
+                            }
+                        }
+                    }
+                    module.section(&globals);
+                }
+                Payload::ExportSection(reader) => {
+                    let mut exports = wasm_encoder::ExportSection::new();
+                    for export in reader {
+                        let export = export?;
+                        if Some(export.name) == init_func {
+                            continue;
+                        }
+                        let kind = match export.kind {
+                            ExternalKind::Func => wasm_encoder::ExportKind::Func,
+                            ExternalKind::Table => wasm_encoder::ExportKind::Table,
+                            ExternalKind::Memory => wasm_encoder::ExportKind::Memory,
+                            ExternalKind::Global => wasm_encoder::ExportKind::Global,
+                            ExternalKind::Tag => wasm_encoder::ExportKind::Tag,
+                        };
+                        exports.export(export.name, kind, export.index);
+                    }
+                    module.section(&exports);
+                }
+                Payload::StartSection { .. } => {}
+                Payload::DataCountSection { .. } => {
+                    module.section(&wasm_encoder::DataCountSection { count: data_count });
+                }
+                Payload::DataSection(_) => {
+                    module.section(&self.data_section(wasm, &original_data, num_imported_memories));
+                    data_emitted = true;
+                }
+                Payload::End(_) => {
+                    if !memories_seen && !self.memories.is_empty() {
+                        bail!("module does not define the snapshotted memories");
+                    }
+                    if !globals_seen && !self.globals.is_empty() {
+                        bail!("module does not define the snapshotted globals");
+                    }
+                    if !data_emitted && snapshot_data > 0 {
+                        // The data section is the last non-custom section, so
+                        // it's valid to append one at the end.
+                        module.section(&self.data_section(wasm, &[], num_imported_memories));
+                    }
+                }
+                _ => copy_section(&mut module, wasm, &payload),
+            }
+        }
+
+        Ok(module.finish())
+    }
+
+    fn data_section(
+        &self,
+        wasm: &[u8],
+        original: &[Option<Range<usize>>],
+        num_imported_memories: u32,
+    ) -> wasm_encoder::DataSection {
+        let mut data = wasm_encoder::DataSection::new();
+        // Segments which are replaced by the snapshot become empty passive
+        // segments so that the indices of all other segments are preserved.
+        for range in original {
+            match range {
+                Some(range) => data.raw(&wasm[range.clone()]),
+                None => data.passive([]),
+            };
+        }
+        for (i, memory) in self.memories.iter().enumerate() {
+            let memory_index = num_imported_memories + u32::try_from(i).unwrap();
+            for (offset, bytes) in memory.segments.iter() {
+                let offset = if memory.is_64 {
+                    wasm_encoder::ConstExpr::i64_const(*offset as i64)
+                } else {
+                    let offset = u32::try_from(*offset).unwrap();
+                    wasm_encoder::ConstExpr::i32_const(offset as i32)
+                };
+                data.active(memory_index, &offset, bytes.iter().copied());
+            }
+        }
+        data
+    }
+}
+
+impl GlobalValue {
+    fn to_val(self) -> Val {
+        match self {
+            GlobalValue::I32(x) => Val::I32(x),
+            GlobalValue::I64(x) => Val::I64(x),
+            GlobalValue::F32(x) => Val::F32(x),
+            GlobalValue::F64(x) => Val::F64(x),
+            GlobalValue::V128(x) => Val::V128(V128::from(x)),
+        }
+    }
+
+    fn const_expr(self) -> wasm_encoder::ConstExpr {
+        match self {
+            GlobalValue::I32(x) => wasm_encoder::ConstExpr::i32_const(x),
+            GlobalValue::I64(x) => wasm_encoder::ConstExpr::i64_const(x),
+            GlobalValue::F32(x) => wasm_encoder::ConstExpr::f32_const(f32::from_bits(x)),
+            GlobalValue::F64(x) => wasm_encoder::ConstExpr::f64_const(f64::from_bits(x)),
+            GlobalValue::V128(x) => wasm_encoder::ConstExpr::v128_const(x as i128),
+        }
+    }
+}
+
+/// Returns an error if any table defined by `instance` differs in size or
+/// contents from how instantiation initialized it, as tables aren't captured by
+/// snapshots.
+///
+/// Only tables whose initial contents are statically known, which is the case
+/// for those initialized by element segments with constant offsets, can be
+/// checked. Other tables are conservatively rejected.
+fn check_tables_unmodified(store: &mut StoreOpaque, instance: &Instance) -> Result<()> {
+    let id = instance.id(store);
+    let module = store.instance_mut(id).module().clone();
+    for (defined, init) in module.table_initialization.initial_values.iter() {
+        let index = module.table_index(defined);
+        let error = |reason: &str| anyhow!("cannot snapshot table {}: {reason}", index.as_u32());
+        let TableInitialValue::Null { precomputed } = init else {
+            return Err(error(
+                "tables with an initializer expression are not supported",
+            ));
+        };
+        if module
+            .table_initialization
+            .segments
+            .iter()
+            .any(|segment| segment.table_index == index)
+        {
+            return Err(error(
+                "tables initialized by element segments with non-constant offsets are not supported",
+            ));
+        }
+
+        let table = store.instance_mut(id).get_defined_table(defined);
+        let size = unsafe { (*table).size() };
+        if u64::try_from(size).unwrap() != module.tables[index].limits.min {
+            return Err(error("the table was resized after instantiation"));
+        }
+        for i in 0..size {
+            let element = unsafe { (*table).get(store.optional_gc_store_mut()?, i as u64) };
+            let modified = match element {
+                // Lazily-initialized elements haven't been touched yet.
+                None | Some(TableElement::UninitFunc) => false,
+                Some(TableElement::FuncRef(func_ref)) => {
+                    let expected = precomputed
+                        .get(i)
+                        .copied()
+                        .filter(|func| *func != FuncIndex::reserved_value())
+                        .map(|func| store.instance_mut(id).get_exported_func(func).func_ref);
+                    func_ref != expected
+                }
+                Some(TableElement::GcRef(None)) => false,
+                Some(TableElement::GcRef(Some(gc_ref))) => {
+                    store.gc_store_mut()?.drop_gc_ref(gc_ref);
+                    true
+                }
+            };
+            if modified {
+                return Err(error(&format!(
+                    "element {i} was modified after instantiation"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copies the section that `payload` represents from `wasm` into `module`
+/// unchanged. Payloads which aren't sections themselves, such as individual
+/// function bodies within the code section, are skipped.
+fn copy_section(module: &mut wasm_encoder::Module, wasm: &[u8], payload: &Payload<'_>) {
+    if let Some((id, range)) = payload.as_section() {
+        module.section(&wasm_encoder::RawSection {
+            id,
+            data: &wasm[range],
+        });
+    }
+}
+
+/// Returns, for each data segment in `wasm`, the byte range of the segment if
+/// it's kept when preinitializing. Passive segments and active segments for
+/// imported memories are kept, while active segments for defined memories are
+/// superseded by the snapshot.
+fn original_data_segments(wasm: &[u8]) -> Result<Vec<Option<Range<usize>>>> {
+    let mut num_imported_memories = 0;
+    let mut segments = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    if let wasmparser::TypeRef::Memory(_) = import?.ty {
+                        num_imported_memories += 1;
+                    }
+                }
+            }
+            Payload::DataSection(reader) => {
+                for data in reader {
+                    let data = data?;
+                    let keep = match data.kind {
+                        wasmparser::DataKind::Passive => true,
+                        wasmparser::DataKind::Active { memory_index, .. } => {
+                            memory_index < num_imported_memories
+                        }
+                    };
+                    segments.push(if keep { Some(data.range) } else { None });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(segments)
+}
+
+/// Splits `data` into regions of non-zero bytes, balancing the number of
+/// segments against the size of the zeroes they include.
+fn nonzero_segments(data: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    // Encoding only non-zero bytes could exceed implementation limits on the
+    // number of data segments, so fixed-size chunks are trimmed of leading and
+    // trailing zeroes instead.
+    const CHUNK_SIZE: usize = 4096;
+    data.chunks(CHUNK_SIZE)
+        .enumerate()
+        .filter_map(|(i, chunk)| {
+            let start = chunk.iter().position(|byte| *byte != 0)?;
+            let end = chunk.iter().rposition(|byte| *byte != 0).unwrap() + 1;
+            Some((i * CHUNK_SIZE + start, &chunk[start..end]))
+        })
+}
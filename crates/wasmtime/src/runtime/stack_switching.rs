@@ -0,0 +1,777 @@
+//! Support for the WebAssembly stack-switching proposal.
+//!
+//! Every continuation runs on its own fiber, whose stack is allocated from the
+//! engine's instance allocator when the continuation is first resumed. A
+//! continuation is represented in the GC heap as an `externref` whose host
+//! data is a [`ContinuationHandle`] naming the continuation in its store's
+//! [`StackSwitchingState`]. Continuations are one-shot: resuming, binding or
+//! switching to a continuation consumes its handle, after which any other use
+//! of a reference to it traps.
+//!
+//! Values are passed between continuations through the store's mailbox: the
+//! sending side clones them there before switching stacks, and the receiving
+//! side writes them into its own `ValRaw` buffer afterwards.
+//!
+//! Suspending through host frames is not supported. A `suspend` or `switch`
+//! only finds the handlers of `resume` instructions that are on the same chain
+//! of Wasm activations as the suspending continuation, and otherwise traps
+//! with an unhandled tag.
+//!
+//! Continuations whose handles have been garbage collected, and all remaining
+//! continuations when their store is dropped, are cancelled: a suspended
+//! continuation is resumed with an error from its `suspend` or `switch`
+//! instruction, which unwinds its stack so that it can be deallocated.
+
+use crate::prelude::*;
+use crate::runtime::exception::{alloc_host_externref, payload_gc_ref};
+use crate::runtime::vm::{
+    self, tls, AsyncWasmCallState, ExportTag, Instance, PreviousAsyncWasmCallState, VMFuncRef,
+    VMGcRef, VMOpaqueContext, VMRuntimeLimits, VMStore, ValRaw,
+};
+use crate::store::StoreOpaque;
+use crate::{ContType, ExternRef, ValType};
+use core::mem;
+use core::ptr::{self, NonNull};
+use std::sync::{Arc, Mutex};
+use wasmtime_environ::{
+    EngineOrModuleTypeIndex, TagIndex, Trap, WasmHeapType, WasmRefType, WasmValType,
+    RESUME_SWITCH_HANDLER_BIT,
+};
+use wasmtime_fiber::{Fiber, Suspend};
+use wasmtime_slab::{Id, Slab};
+
+type ContinuationFiber = Fiber<'static, ResumeAction, Suspension, Result<()>>;
+type ContinuationSuspend = Suspend<ResumeAction, Suspension, Result<()>>;
+
+/// The continuations of a store.
+#[derive(Default)]
+pub(crate) struct StackSwitchingState {
+    /// All continuations that have not yet returned or been cancelled.
+    ///
+    /// These are boxed so that they don't move while their fiber is running.
+    continuations: Slab<Box<Continuation>>,
+    /// The `resume` instructions that are currently executing, from the
+    /// outermost to the innermost.
+    active: Vec<ActiveResume>,
+    /// Values in transit between two continuations.
+    mailbox: Vec<(ValRaw, WasmValType)>,
+    /// Continuations whose handles have been dropped without being consumed.
+    dropped: Arc<Mutex<Vec<Id>>>,
+}
+
+// SAFETY: the raw pointers within continuations are only used while the
+// store is exclusively borrowed, and a continuation's fiber can be resumed on
+// any thread because its activations are saved and restored around every
+// switch just like for async calls.
+unsafe impl Send for StackSwitchingState {}
+unsafe impl Sync for StackSwitchingState {}
+
+struct Continuation {
+    func_ref: NonNull<VMFuncRef>,
+    /// The types of the values this continuation expects when it is next
+    /// resumed, excluding those already bound with `cont.bind`.
+    params: Box<[WasmValType]>,
+    results: Box<[WasmValType]>,
+    /// Arguments bound with `cont.bind`, each owning its GC reference, if any.
+    bound: Vec<(ValRaw, WasmValType)>,
+    state: State,
+    fiber: Option<ContinuationFiber>,
+    suspend: *mut ContinuationSuspend,
+    /// The activations on this continuation's stack while it is suspended.
+    call_state: Option<AsyncWasmCallState>,
+    /// The runtime limits of this continuation while it is suspended.
+    limits: SavedLimits,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// Created with `cont.new` and not yet resumed.
+    Fresh,
+    /// Currently executing, or suspended within a `resume` of a nested
+    /// continuation.
+    Running,
+    /// Suspended. A `nested` continuation was resumed by another continuation
+    /// that is itself suspended, and is resumed along with it.
+    Suspended { nested: bool },
+}
+
+struct ActiveResume {
+    /// The continuation being resumed.
+    cont: Id,
+    /// The handled tags, and whether each is handled with `switch`.
+    handlers: Vec<(ExportTag, bool)>,
+    /// The activation that executed the `resume`.
+    resumer_state: tls::Ptr,
+}
+
+/// How a suspended continuation is resumed.
+enum ResumeAction {
+    /// Normally, with the values in the mailbox.
+    Resume,
+    /// By throwing the store's pending exception from its suspension point.
+    Throw,
+    /// By unwinding its stack so that it can be deallocated.
+    Cancel,
+}
+
+/// The reason a continuation suspended, passed to the handler that resumed it.
+struct Suspension {
+    /// The index in `StackSwitchingState::active` of the handling `resume`.
+    target: usize,
+    /// The index of the handler within that `resume`'s handlers.
+    handler: usize,
+    /// The types of the values the suspended continuation expects when it is
+    /// resumed.
+    resume_types: Box<[WasmValType]>,
+    /// The continuation to switch to, for `switch`.
+    switch_to: Option<Id>,
+}
+
+/// The fields of `VMRuntimeLimits` that describe the stack that is executing.
+#[derive(Clone, Copy, Default)]
+struct SavedLimits {
+    stack_limit: usize,
+    last_wasm_exit_pc: usize,
+    last_wasm_exit_fp: usize,
+    last_wasm_entry_fp: usize,
+}
+
+impl SavedLimits {
+    unsafe fn capture(limits: &VMRuntimeLimits) -> SavedLimits {
+        SavedLimits {
+            stack_limit: *limits.stack_limit.get(),
+            last_wasm_exit_pc: *limits.last_wasm_exit_pc.get(),
+            last_wasm_exit_fp: *limits.last_wasm_exit_fp.get(),
+            last_wasm_entry_fp: *limits.last_wasm_entry_fp.get(),
+        }
+    }
+
+    unsafe fn install(&self, limits: &VMRuntimeLimits) {
+        *limits.stack_limit.get() = self.stack_limit;
+        *limits.last_wasm_exit_pc.get() = self.last_wasm_exit_pc;
+        *limits.last_wasm_exit_fp.get() = self.last_wasm_exit_fp;
+        *limits.last_wasm_entry_fp.get() = self.last_wasm_entry_fp;
+    }
+}
+
+/// The host data of the `externref` that represents a continuation in the GC
+/// heap.
+pub(crate) struct ContinuationHandle {
+    /// The continuation, or `None` once this handle has been consumed.
+    id: Option<Id>,
+    dropped: Arc<Mutex<Vec<Id>>>,
+}
+
+impl Drop for ContinuationHandle {
+    fn drop(&mut self) {
+        // Handles are dropped by the collector, when the continuation can't
+        // be cancelled yet, so defer that to the next `cont.new`.
+        if let Some(id) = self.id {
+            self.dropped.lock().unwrap().push(id);
+        }
+    }
+}
+
+/// The type of the continuation references passed to handlers.
+const CONTREF: WasmValType = WasmValType::Ref(WasmRefType {
+    nullable: false,
+    heap_type: WasmHeapType::Cont,
+});
+
+/// Creates a new handle for the given continuation, returning an owned
+/// reference to it.
+fn new_handle(store: &mut dyn VMStore, id: Id) -> Result<VMGcRef> {
+    store.gc_store_mut()?;
+    let dropped = store.stack_switching().dropped.clone();
+    let handle = alloc_host_externref(
+        store,
+        Box::new(ContinuationHandle {
+            id: Some(id),
+            dropped,
+        }),
+        Box::new([]),
+    )?;
+    Ok(handle.into())
+}
+
+/// Consumes the handle of the continuation referenced by `contref`.
+fn take_handle(store: &mut StoreOpaque, contref: u32) -> Result<Id> {
+    let gc_ref = VMGcRef::from_raw_u32(contref).ok_or(Trap::NullReference)?;
+    let gc_store = store.unwrap_gc_store_mut();
+    let externref = gc_ref
+        .as_externref(&*gc_store.gc_heap)
+        .expect("continuations are externrefs");
+    let handle = gc_store
+        .externref_host_data_mut(externref)
+        .downcast_mut::<ContinuationHandle>()
+        .expect("continuations have `ContinuationHandle` host data");
+    Ok(handle.id.take().ok_or(Trap::ContinuationAlreadyConsumed)?)
+}
+
+/// Clones the values at `values`, of the given types, into the mailbox.
+unsafe fn send(store: &mut StoreOpaque, values: *const ValRaw, types: &[WasmValType]) {
+    for (i, ty) in types.iter().enumerate() {
+        let raw = *values.add(i);
+        if let Some(gc_ref) = payload_gc_ref(&raw, ty) {
+            // This clone is owned by the mailbox until it is received.
+            let _ = store.unwrap_gc_store_mut().clone_gc_ref(&gc_ref);
+        }
+        store.stack_switching_mut().mailbox.push((raw, *ty));
+    }
+}
+
+/// Moves the values in the mailbox to `values`, exposing them to Wasm, and
+/// returns how many there were.
+unsafe fn receive(store: &mut StoreOpaque, values: *mut ValRaw) -> usize {
+    let mailbox = mem::take(&mut store.stack_switching_mut().mailbox);
+    for (i, (raw, ty)) in mailbox.iter().enumerate() {
+        if let Some(gc_ref) = payload_gc_ref(raw, ty) {
+            store.unwrap_gc_store_mut().expose_gc_ref_to_wasm(gc_ref);
+        }
+        values.add(i).write(*raw);
+    }
+    mailbox.len()
+}
+
+/// Sends the arguments of the given continuation: its bound arguments followed
+/// by the first `len` of its parameters, read from `values`.
+unsafe fn send_arguments(store: &mut StoreOpaque, id: Id, values: *const ValRaw, len: usize) {
+    let state = store.stack_switching_mut();
+    let cont = &mut state.continuations[id];
+    let bound = mem::take(&mut cont.bound);
+    let types = cont.params[..len].to_vec();
+    state.mailbox.extend(bound);
+    send(store, values, &types);
+}
+
+/// Deallocates a continuation that has returned or was never resumed.
+fn free(store: &mut StoreOpaque, id: Id) {
+    let cont = store.stack_switching_mut().continuations.dealloc(id);
+    let Continuation { fiber, bound, .. } = *cont;
+    if let Some(fiber) = fiber {
+        unsafe {
+            store
+                .engine()
+                .allocator()
+                .deallocate_fiber_stack(fiber.into_stack());
+        }
+    }
+    for (raw, ty) in bound {
+        if let Some(gc_ref) = payload_gc_ref(&raw, &ty) {
+            store.unwrap_gc_store_mut().drop_gc_ref(gc_ref);
+        }
+    }
+}
+
+/// Runs the given continuation until it returns or suspends to a handler
+/// outside of it.
+///
+/// A continuation that returns, normally or with a trap, is deallocated.
+unsafe fn run(store: &mut dyn VMStore, id: Id, action: ResumeAction) -> Result<Option<Suspension>> {
+    let cont: *mut Continuation = &mut *store.stack_switching_mut().continuations[id];
+
+    if (*cont).fiber.is_none() {
+        let size = store.engine().config().async_stack_size;
+        store.continuation_stack_allocating(size)?;
+        let stack = store.engine().allocator().allocate_fiber_stack()?;
+        let store_ptr = store.traitobj();
+        (*cont).fiber = Some(Fiber::new(stack, move |action, suspend| {
+            start(&mut *store_ptr.as_ptr(), id, action, suspend)
+        })?);
+    }
+
+    // Switch the runtime limits and activations over to the continuation's
+    // stack for the duration of its execution. A suspended continuation's
+    // oldest activation now returns to the current one, which is not
+    // necessarily the one it suspended from.
+    let limits = store.vmruntime_limits();
+    let resumer = SavedLimits::capture(limits.as_ref());
+    let call_state = (*cont)
+        .call_state
+        .take()
+        .unwrap_or_else(AsyncWasmCallState::new);
+    if let State::Suspended { .. } = (*cont).state {
+        call_state.reparent();
+        (*cont).limits.install(limits.as_ref());
+    }
+    (*cont).state = State::Running;
+    let restore = Restore {
+        cont,
+        limits,
+        resumer,
+        state: Some(call_state.push()),
+    };
+    let result = (*cont).fiber.as_ref().unwrap().resume(action);
+    drop(restore);
+
+    return match result {
+        Ok(result) => {
+            free(store, id);
+            result.map(|()| None)
+        }
+        Err(suspension) => {
+            (*cont).state = State::Suspended { nested: false };
+            Ok(Some(suspension))
+        }
+    };
+
+    struct Restore {
+        cont: *mut Continuation,
+        limits: NonNull<VMRuntimeLimits>,
+        resumer: SavedLimits,
+        state: Option<PreviousAsyncWasmCallState>,
+    }
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            unsafe {
+                let cont = &mut *self.cont;
+                cont.call_state = Some(self.state.take().unwrap().restore());
+                cont.limits = SavedLimits::capture(self.limits.as_ref());
+                self.resumer.install(self.limits.as_ref());
+            }
+        }
+    }
+}
+
+/// The entry point of a continuation's fiber.
+unsafe fn start(
+    store: &mut dyn VMStore,
+    id: Id,
+    action: ResumeAction,
+    suspend: &mut ContinuationSuspend,
+) -> Result<()> {
+    debug_assert!(matches!(action, ResumeAction::Resume));
+    let cont = &mut store.stack_switching_mut().continuations[id];
+    cont.suspend = suspend;
+    let func_ref = cont.func_ref;
+    let results = cont.results.clone();
+
+    let len = store.stack_switching().mailbox.len().max(results.len());
+    let mut args = vec![ValRaw::i32(0); len];
+    receive(store, args.as_mut_ptr());
+
+    // Wasm gets the same amount of stack on a continuation's stack as it does
+    // on the host's.
+    let stack_limit = vm::get_stack_pointer() - store.engine().config().max_wasm_stack;
+    *store.vmruntime_limits().as_ref().stack_limit.get() = stack_limit;
+
+    let result = vm::catch_traps(store.store_opaque_mut(), |caller, vm| {
+        func_ref.as_ref().array_call(
+            vm,
+            VMOpaqueContext::from_vmcontext(caller),
+            NonNull::from(&mut args[..]),
+        )
+    });
+    if let Err(trap) = result {
+        return Err(crate::trap::from_runtime_box(store, trap));
+    }
+
+    // A continuation that returned with an exception has no results; the
+    // exception propagates to its resumer instead.
+    if store.pending_exception().is_none() {
+        send(store, args.as_ptr(), &results);
+    }
+    Ok(())
+}
+
+/// Suspends the currently running continuation, returning how it is resumed.
+unsafe fn suspend_current(store: &mut StoreOpaque, suspension: Suspension) -> ResumeAction {
+    let state = store.stack_switching_mut();
+    let id = state
+        .active
+        .last()
+        .expect("should only suspend from a continuation")
+        .cont;
+    let suspend = state.continuations[id].suspend;
+    (*suspend).suspend(suspension)
+}
+
+/// Handles the action a continuation that suspended with `suspend` or `switch`
+/// was resumed with.
+unsafe fn resumed(
+    store: &mut StoreOpaque,
+    action: ResumeAction,
+    values: *mut ValRaw,
+) -> Result<()> {
+    match action {
+        ResumeAction::Resume => {
+            receive(store, values);
+            Ok(())
+        }
+        // The exception is now pending, and compiled code dispatches it once
+        // this returns.
+        ResumeAction::Throw => Ok(()),
+        ResumeAction::Cancel => bail!("continuation cancelled"),
+    }
+}
+
+/// Finds the innermost handler for `tag` among the `resume`s that the current
+/// continuation is nested within, returning the index of the `resume` and of
+/// the handler within it.
+unsafe fn find_handler(
+    store: &StoreOpaque,
+    tag: ExportTag,
+    switch: bool,
+) -> Result<(usize, usize)> {
+    let mut current = tls::current();
+    for (i, entry) in store.stack_switching().active.iter().enumerate().rev() {
+        // Stop at the first continuation that did not directly call the
+        // `resume` of the next one, for example because there are host frames
+        // in between.
+        if current.is_null() || !ptr::eq((*current).prev(), entry.resumer_state) {
+            break;
+        }
+        if let Some(handler) = entry
+            .handlers
+            .iter()
+            .position(|&(t, s)| t == tag && s == switch)
+        {
+            return Ok((i, handler));
+        }
+        current = entry.resumer_state;
+    }
+    Err(Trap::UnhandledTag.into())
+}
+
+/// Returns the parameter and result types of the given tag.
+fn tag_type(store: &StoreOpaque, tag: ExportTag) -> (Vec<WasmValType>, Vec<WasmValType>) {
+    let ty = store
+        .engine()
+        .signatures()
+        .borrow(tag.signature)
+        .expect("tag types are registered");
+    let ty = ty.unwrap_func();
+    (ty.params().to_vec(), ty.returns().to_vec())
+}
+
+/// Implementation of `cont.new`.
+pub(crate) unsafe fn cont_new(store: &mut dyn VMStore, func_ref: *mut VMFuncRef) -> Result<u32> {
+    cancel_dropped(store);
+
+    let func_ref = NonNull::new(func_ref).ok_or(Trap::NullReference)?;
+    let ty = store
+        .engine()
+        .signatures()
+        .borrow(func_ref.as_ref().type_index)
+        .expect("function types are registered");
+    let ty = ty.unwrap_func();
+    let id = store
+        .stack_switching_mut()
+        .continuations
+        .alloc(Box::new(Continuation {
+            func_ref,
+            params: ty.params().into(),
+            results: ty.returns().into(),
+            bound: Vec::new(),
+            state: State::Fresh,
+            fiber: None,
+            suspend: ptr::null_mut(),
+            call_state: None,
+            limits: SavedLimits::default(),
+        }));
+
+    let handle = new_handle(store, id)?;
+    let raw = handle.as_raw_u32();
+    store.unwrap_gc_store_mut().expose_gc_ref_to_wasm(handle);
+    Ok(raw)
+}
+
+/// Implementation of `cont.bind`.
+pub(crate) unsafe fn cont_bind(
+    store: &mut dyn VMStore,
+    contref: u32,
+    num_args: u32,
+    args: *const ValRaw,
+) -> Result<u32> {
+    let id = take_handle(store, contref)?;
+    let cont = &mut store.stack_switching_mut().continuations[id];
+    let num_args = num_args as usize;
+    let types = cont.params[..num_args].to_vec();
+    cont.params = cont.params[num_args..].into();
+    for (i, ty) in types.into_iter().enumerate() {
+        let raw = *args.add(i);
+        if let Some(gc_ref) = payload_gc_ref(&raw, &ty) {
+            // This clone is owned by the continuation; see `Continuation`.
+            let _ = store.unwrap_gc_store_mut().clone_gc_ref(&gc_ref);
+        }
+        store.stack_switching_mut().continuations[id]
+            .bound
+            .push((raw, ty));
+    }
+
+    let handle = new_handle(store, id)?;
+    let raw = handle.as_raw_u32();
+    store.unwrap_gc_store_mut().expose_gc_ref_to_wasm(handle);
+    Ok(raw)
+}
+
+/// Implementation of `resume` and `resume_throw`.
+///
+/// See the `resume` builtin for the meaning of the arguments and result.
+pub(crate) unsafe fn resume(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    contref: u32,
+    throw_tag: u32,
+    handlers: &[u32],
+    values: *mut ValRaw,
+) -> Result<u32> {
+    let finished = u32::try_from(handlers.len()).unwrap();
+    let mut handlers = handlers
+        .iter()
+        .map(|&h| {
+            let tag = instance.get_exported_tag(TagIndex::from_u32(h & !RESUME_SWITCH_HANDLER_BIT));
+            (tag, h & RESUME_SWITCH_HANDLER_BIT != 0)
+        })
+        .collect::<Vec<_>>();
+
+    let mut id = take_handle(store, contref)?;
+    let mut action = if throw_tag == u32::MAX {
+        let len = store.stack_switching().continuations[id].params.len();
+        send_arguments(store, id, values, len);
+        ResumeAction::Resume
+    } else {
+        let tag = instance.get_exported_tag(TagIndex::from_u32(throw_tag));
+        let (params, _) = tag_type(store, tag);
+        crate::runtime::exception::throw(
+            store,
+            tag,
+            core::slice::from_raw_parts(values, params.len()),
+        )?;
+        // A continuation that hasn't started has nowhere to throw the
+        // exception from, so it's thrown from the `resume_throw` instead.
+        if store.stack_switching().continuations[id].state == State::Fresh {
+            free(store, id);
+            return Ok(finished);
+        }
+        ResumeAction::Throw
+    };
+
+    loop {
+        let state = store.stack_switching_mut();
+        let index = state.active.len();
+        state.active.push(ActiveResume {
+            cont: id,
+            handlers: mem::take(&mut handlers),
+            resumer_state: tls::current(),
+        });
+        let result = run(store, id, action);
+        handlers = store.stack_switching_mut().active.pop().unwrap().handlers;
+
+        let Some(suspension) = result? else {
+            if store.pending_exception().is_none() {
+                receive(store, values);
+            }
+            return Ok(finished);
+        };
+
+        // The continuation suspended to a handler of an outer `resume`, so
+        // suspend the continuation executing this `resume` too. When it is
+        // resumed, resume the nested continuation in the same way.
+        if suspension.target < index {
+            store.stack_switching_mut().continuations[id].state = State::Suspended { nested: true };
+            action = suspend_current(store, suspension);
+            continue;
+        }
+
+        store.stack_switching_mut().continuations[id].params = suspension.resume_types;
+        let handle = new_handle(store, id)?;
+        match suspension.switch_to {
+            None => {
+                let n = receive(store, values);
+                values.add(n).write(ValRaw::externref(handle.as_raw_u32()));
+                store.unwrap_gc_store_mut().expose_gc_ref_to_wasm(handle);
+                return Ok(u32::try_from(suspension.handler).unwrap());
+            }
+            Some(to) => {
+                // The switched-to continuation receives the suspended one as
+                // its last argument, owned by the mailbox.
+                let raw = ValRaw::externref(handle.as_raw_u32());
+                store.stack_switching_mut().mailbox.push((raw, CONTREF));
+                id = to;
+                action = ResumeAction::Resume;
+            }
+        }
+    }
+}
+
+/// Implementation of `suspend`.
+pub(crate) unsafe fn suspend(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    tag: u32,
+    values: *mut ValRaw,
+) -> Result<()> {
+    let tag = instance.get_exported_tag(TagIndex::from_u32(tag));
+    let (target, handler) = find_handler(store, tag, false)?;
+    let (params, results) = tag_type(store, tag);
+    send(store, values, &params);
+    let action = suspend_current(
+        store,
+        Suspension {
+            target,
+            handler,
+            resume_types: results.into(),
+            switch_to: None,
+        },
+    );
+    resumed(store, action, values)
+}
+
+/// Implementation of `switch`.
+pub(crate) unsafe fn switch(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    contref: u32,
+    tag: u32,
+    values: *mut ValRaw,
+) -> Result<()> {
+    let tag = instance.get_exported_tag(TagIndex::from_u32(tag));
+    let (target, handler) = find_handler(store, tag, true)?;
+    let to = take_handle(store, contref)?;
+
+    // The last parameter of the continuation switched to is the type of the
+    // current continuation once suspended, which determines the values it
+    // expects when it is resumed.
+    let params = &store.stack_switching().continuations[to].params;
+    let len = params.len() - 1;
+    let resume_types = match params[len] {
+        WasmValType::Ref(WasmRefType {
+            heap_type: WasmHeapType::ConcreteCont(EngineOrModuleTypeIndex::Engine(index)),
+            ..
+        }) => {
+            let signatures = store.engine().signatures();
+            let cont_ty = signatures.borrow(index).expect("types are registered");
+            let func_ty = signatures
+                .borrow(cont_ty.unwrap_cont().0.unwrap_engine_type_index())
+                .expect("types are registered");
+            func_ty.unwrap_func().params().into()
+        }
+        ref ty => unreachable!("`switch` to a continuation with a last parameter of type {ty}"),
+    };
+
+    send_arguments(store, to, values, len);
+    let action = suspend_current(
+        store,
+        Suspension {
+            target,
+            handler,
+            resume_types,
+            switch_to: Some(to),
+        },
+    );
+    resumed(store, action, values)
+}
+
+/// Cancels the given continuation, whose handle was never consumed.
+unsafe fn cancel(store: &mut dyn VMStore, id: Id) {
+    match store.stack_switching().continuations[id].state {
+        State::Fresh => free(store, id),
+        State::Suspended { nested: false } => {
+            // Nested continuations are cancelled along with this one, and the
+            // error that unwinds its stack is of no interest.
+            let _ = run(store, id, ResumeAction::Cancel);
+        }
+        State::Running | State::Suspended { nested: true } => {
+            unreachable!("continuations with handles are fresh or suspended")
+        }
+    }
+}
+
+/// Cancels the continuations whose handles have been dropped.
+unsafe fn cancel_dropped(store: &mut dyn VMStore) {
+    let dropped = mem::take(&mut *store.stack_switching().dropped.lock().unwrap());
+    for id in dropped {
+        cancel(store, id);
+    }
+}
+
+/// Cancels all continuations of a store that is being dropped.
+pub(crate) fn cancel_all(store: &mut dyn VMStore) {
+    loop {
+        let ids = store
+            .stack_switching()
+            .continuations
+            .iter()
+            .filter(|(_, cont)| cont.state != State::Suspended { nested: true })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            break;
+        }
+        for id in ids {
+            // Cancelling a continuation cancels those nested within it.
+            if store.stack_switching().continuations.contains(id) {
+                unsafe { cancel(store, id) };
+            }
+        }
+    }
+    store.stack_switching().dropped.lock().unwrap().clear();
+}
+
+/// Returns the saved exit PC and FP and entry FP of the Wasm frames on the
+/// stacks of all suspended continuations, for tracing GC roots.
+pub(crate) fn suspended_activations(store: &StoreOpaque) -> Vec<(usize, usize, usize)> {
+    store
+        .stack_switching()
+        .continuations
+        .iter()
+        .filter(|(_, cont)| matches!(cont.state, State::Suspended { .. }))
+        .map(|(_, cont)| {
+            (
+                cont.limits.last_wasm_exit_pc,
+                cont.limits.last_wasm_exit_fp,
+                cont.limits.last_wasm_entry_fp,
+            )
+        })
+        .collect()
+}
+
+/// Returns the number of bytes of stack held by the continuations of a store.
+pub(crate) fn stack_bytes(store: &StoreOpaque) -> usize {
+    let stacks = store
+        .stack_switching()
+        .continuations
+        .iter()
+        .filter(|(_, cont)| cont.fiber.is_some())
+        .count();
+    stacks * store.engine().config().async_stack_size
+}
+
+/// Returns whether the continuation referenced by `externref` on the host
+/// matches the given continuation type.
+pub(crate) fn matches_ty(
+    store: &StoreOpaque,
+    externref: &ExternRef,
+    ty: &ContType,
+) -> Result<bool> {
+    let gc_ref = externref.inner.try_gc_ref(store)?;
+    let gc_store = store.gc_store()?;
+    let Some(handle) = gc_ref.as_externref(&*gc_store.gc_heap).and_then(|e| {
+        gc_store
+            .externref_host_data(e)
+            .downcast_ref::<ContinuationHandle>()
+    }) else {
+        return Ok(false);
+    };
+    // A consumed continuation traps when used, regardless of its type.
+    let Some(id) = handle.id else {
+        return Ok(true);
+    };
+    let cont = &store.stack_switching().continuations[id];
+    let engine = store.engine();
+    let expected = ty.func_type();
+    let ty = |t: &WasmValType| ValType::from_wasm_type(engine, t);
+    Ok(expected.params().len() == cont.params.len()
+        && expected.results().len() == cont.results.len()
+        && expected
+            .params()
+            .zip(cont.params.iter())
+            .all(|(expected, actual)| expected.matches(&ty(actual)))
+        && cont
+            .results
+            .iter()
+            .zip(expected.results())
+            .all(|(actual, expected)| ty(actual).matches(&expected)))
+}
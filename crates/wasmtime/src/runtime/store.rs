@@ -91,7 +91,7 @@ use crate::runtime::vm::{
 use crate::trampoline::VMHostGlobalContext;
 use crate::type_registry::RegisteredType;
 use crate::RootSet;
-use crate::{module::ModuleRegistry, Engine, Module, Trap, Val, ValRaw};
+use crate::{module::ModuleRegistry, Engine, FuncType, Module, Trap, Val, ValRaw};
 use crate::{Global, Instance, Memory, RootScope, Table, Uninhabited};
 use alloc::sync::Arc;
 use core::cell::UnsafeCell;
@@ -105,7 +105,7 @@ use core::pin::Pin;
 use core::ptr;
 use core::ptr::NonNull;
 use core::task::{Context, Poll};
-use wasmtime_environ::TripleExt;
+use wasmtime_environ::{TripleExt, VMSharedTypeIndex};
 
 mod context;
 pub use self::context::*;
@@ -113,6 +113,10 @@ mod data;
 pub use self::data::*;
 mod func_refs;
 use func_refs::FuncRefs;
+mod resource_usage;
+pub use self::resource_usage::ResourceUsage;
+#[cfg(feature = "gc")]
+mod exception;
 
 /// A [`Store`] is a collection of WebAssembly instances and host-defined state.
 ///
@@ -317,10 +321,15 @@ pub struct StoreOpaque {
     instances: Vec<StoreInstance>,
     #[cfg(feature = "component-model")]
     num_component_instances: usize,
+    #[cfg(feature = "component-model")]
+    component_instance_limit: usize,
     signal_handler: Option<SignalHandler>,
     modules: ModuleRegistry,
     func_refs: FuncRefs,
     host_globals: Vec<StoreBox<VMHostGlobalContext>>,
+    // The types of tags created by the host, which are kept registered for as
+    // long as the tags may be used.
+    host_tag_types: Vec<RegisteredType>,
 
     // GC-related fields.
     gc_store: Option<GcStore>,
@@ -328,6 +337,14 @@ pub struct StoreOpaque {
     gc_roots_list: GcRootsList,
     // Types for which the embedder has created an allocator for.
     gc_host_alloc_types: HashSet<RegisteredType>,
+    // The exception currently being thrown, if any. See the `exception` module
+    // for details.
+    #[cfg(feature = "gc")]
+    pending_exception: Option<VMGcRef>,
+    // The continuations created by Wasm in this store. See the
+    // `stack_switching` module for details.
+    #[cfg(feature = "stack-switching")]
+    stack_switching: crate::runtime::stack_switching::StackSwitchingState,
 
     // Numbers of resources instantiated in this store, and their limits
     instance_count: usize,
@@ -395,6 +412,11 @@ pub struct StoreOpaque {
     /// for this store's `Engine`. This is `None` if pulley was disabled at
     /// compile time or if it's not being used by the `Engine`.
     interpreter: Option<Interpreter>,
+
+    /// State for recording or replaying host calls, configured with
+    /// `Store::record_host_calls` or `Store::replay_host_calls`.
+    #[cfg(feature = "record-replay")]
+    pub(crate) host_call_trace: Option<Box<crate::runtime::record_replay::HostCallTrace>>,
 }
 
 #[cfg(feature = "async")]
@@ -403,6 +425,12 @@ struct AsyncState {
     current_poll_cx: UnsafeCell<PollContext>,
     /// The last fiber stack that was in use by this store.
     last_fiber_stack: Option<wasmtime_fiber::FiberStack>,
+    /// The number of fiber stacks this store has allocated from the engine
+    /// and not yet returned, including `last_fiber_stack`.
+    ///
+    /// This is updated through a raw pointer when a `FiberFuture` is dropped,
+    /// hence the `UnsafeCell`.
+    fiber_stacks: UnsafeCell<usize>,
 }
 
 #[cfg(feature = "async")]
@@ -549,14 +577,21 @@ impl<T> Store<T> {
                 instances: Vec::new(),
                 #[cfg(feature = "component-model")]
                 num_component_instances: 0,
+                #[cfg(feature = "component-model")]
+                component_instance_limit: crate::DEFAULT_COMPONENT_INSTANCE_LIMIT,
                 signal_handler: None,
                 gc_store: None,
                 gc_roots: RootSet::default(),
                 gc_roots_list: GcRootsList::default(),
                 gc_host_alloc_types: HashSet::default(),
+                #[cfg(feature = "gc")]
+                pending_exception: None,
+                #[cfg(feature = "stack-switching")]
+                stack_switching: Default::default(),
                 modules: ModuleRegistry::default(),
                 func_refs: FuncRefs::default(),
                 host_globals: Vec::new(),
+                host_tag_types: Vec::new(),
                 instance_count: 0,
                 instance_limit: crate::DEFAULT_INSTANCE_LIMIT,
                 memory_count: 0,
@@ -568,6 +603,7 @@ impl<T> Store<T> {
                     current_suspend: UnsafeCell::new(ptr::null_mut()),
                     current_poll_cx: UnsafeCell::new(PollContext::default()),
                     last_fiber_stack: None,
+                    fiber_stacks: UnsafeCell::new(0),
                 },
                 fuel_reserve: 0,
                 fuel_yield_interval: None,
@@ -588,6 +624,8 @@ impl<T> Store<T> {
                 } else {
                     None
                 },
+                #[cfg(feature = "record-replay")]
+                host_call_trace: None,
             },
             limiter: None,
             call_hook: None,
@@ -656,6 +694,7 @@ impl<T> Store<T> {
 
     /// Consumes this [`Store`], destroying it, and returns the underlying data.
     pub fn into_data(mut self) -> T {
+        self.inner.cancel_continuations();
         self.inner.flush_fiber_stack();
 
         // This is an unsafe operation because we want to avoid having a runtime
@@ -746,14 +785,22 @@ impl<T> Store<T> {
     ) {
         // Apply the limits on instances, tables, and memory given by the limiter:
         let inner = &mut self.inner;
-        let (instance_limit, table_limit, memory_limit) = {
-            let l = limiter(&mut inner.data);
-            (l.instances(), l.tables(), l.memories())
-        };
+        let l = limiter(&mut inner.data);
+        let (instance_limit, table_limit, memory_limit) = (l.instances(), l.tables(), l.memories());
+        #[cfg(feature = "component-model")]
+        let (component_instance_limit, resource_handle_limit) =
+            (l.component_instances(), l.resource_handles());
         let innermost = &mut inner.inner;
         innermost.instance_limit = instance_limit;
         innermost.table_limit = table_limit;
         innermost.memory_limit = memory_limit;
+        #[cfg(feature = "component-model")]
+        {
+            innermost.component_instance_limit = component_instance_limit;
+            innermost
+                .component_calls
+                .set_handle_limit(resource_handle_limit);
+        }
 
         // Save the limiter accessor function:
         inner.limiter = Some(ResourceLimiterInner::Sync(Box::new(limiter)));
@@ -793,14 +840,22 @@ impl<T> Store<T> {
         debug_assert!(self.inner.async_support());
         // Apply the limits on instances, tables, and memory given by the limiter:
         let inner = &mut self.inner;
-        let (instance_limit, table_limit, memory_limit) = {
-            let l = limiter(&mut inner.data);
-            (l.instances(), l.tables(), l.memories())
-        };
+        let l = limiter(&mut inner.data);
+        let (instance_limit, table_limit, memory_limit) = (l.instances(), l.tables(), l.memories());
+        #[cfg(feature = "component-model")]
+        let (component_instance_limit, resource_handle_limit) =
+            (l.component_instances(), l.resource_handles());
         let innermost = &mut inner.inner;
         innermost.instance_limit = instance_limit;
         innermost.table_limit = table_limit;
         innermost.memory_limit = memory_limit;
+        #[cfg(feature = "component-model")]
+        {
+            innermost.component_instance_limit = component_instance_limit;
+            innermost
+                .component_calls
+                .set_handle_limit(resource_handle_limit);
+        }
 
         // Save the limiter accessor function:
         inner.limiter = Some(ResourceLimiterInner::Async(Box::new(limiter)));
@@ -891,6 +946,21 @@ impl<T> Store<T> {
         self.inner.get_fuel()
     }
 
+    /// Returns a snapshot of the host resources currently accounted to this
+    /// [`Store`].
+    ///
+    /// This aggregates the sizes of all linear memories, tables, the GC heap,
+    /// and fiber stacks owned by this store along with the number of
+    /// instances and other entities within it. See [`ResourceUsage`] for more
+    /// details on what is measured.
+    ///
+    /// This is intended to be cheap enough to call periodically in order to,
+    /// for example, bill tenants for their usage or evict the stores using the
+    /// most memory.
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.inner.resource_usage()
+    }
+
     /// Set the fuel to this [`Store`] for wasm to consume while executing.
     ///
     /// For this method to work fuel consumption must be enabled via
@@ -1092,6 +1162,13 @@ impl<'a, T> StoreContext<'a, T> {
     pub fn get_fuel(&self) -> Result<u64> {
         self.0.get_fuel()
     }
+
+    /// Returns a snapshot of the host resources accounted to this store.
+    ///
+    /// For more information see [`Store::resource_usage`].
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.0.resource_usage()
+    }
 }
 
 impl<'a, T> StoreContextMut<'a, T> {
@@ -1144,6 +1221,13 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.get_fuel()
     }
 
+    /// Returns a snapshot of the host resources accounted to this store.
+    ///
+    /// For more information see [`Store::resource_usage`].
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.0.resource_usage()
+    }
+
     /// Set the amount of fuel in this store.
     ///
     /// For more information see [`Store::set_fuel`]
@@ -1553,6 +1637,16 @@ impl StoreOpaque {
         assert!(self.gc_store.is_none());
         let gc_store = allocate_gc_store(self.engine())?;
         self.gc_store = Some(gc_store);
+
+        // Types may have been registered with this store before its GC heap
+        // was allocated.
+        let types = self
+            .modules
+            .all_modules()
+            .flat_map(|module| module.signatures().as_module_map().values().copied())
+            .chain(self.gc_host_alloc_types.iter().map(|ty| ty.index()))
+            .collect::<Vec<_>>();
+        self.register_gc_types(types);
         return Ok(());
 
         #[cfg(feature = "gc")]
@@ -1563,7 +1657,7 @@ impl StoreOpaque {
             );
             let (index, heap) = engine
                 .allocator()
-                .allocate_gc_heap(&**engine.gc_runtime()?)?;
+                .allocate_gc_heap(engine, &**engine.gc_runtime()?)?;
             Ok(GcStore::new(index, heap))
         }
 
@@ -1582,6 +1676,18 @@ impl StoreOpaque {
         }
     }
 
+    #[cfg(feature = "stack-switching")]
+    pub(crate) fn stack_switching(&self) -> &crate::runtime::stack_switching::StackSwitchingState {
+        &self.stack_switching
+    }
+
+    #[cfg(feature = "stack-switching")]
+    pub(crate) fn stack_switching_mut(
+        &mut self,
+    ) -> &mut crate::runtime::stack_switching::StackSwitchingState {
+        &mut self.stack_switching
+    }
+
     #[inline]
     pub(crate) fn gc_store_mut(&mut self) -> Result<&mut GcStore> {
         if self.gc_store.is_none() {
@@ -1609,6 +1715,25 @@ impl StoreOpaque {
             .expect("attempted to access the store's GC heap before it has been allocated")
     }
 
+    /// Update every instance's copy of the GC heap's bound after the GC heap
+    /// has grown.
+    #[cfg(feature = "gc")]
+    fn update_gc_heap_bounds(&mut self) {
+        let bound = self.unwrap_gc_store().gc_heap.heap_slice().len();
+        let handles = self
+            .instances
+            .iter_mut()
+            .map(|i| &mut i.handle)
+            .chain(Some(&mut self.default_caller));
+        for handle in handles {
+            // Safety: the GC heap only ever grows in place, so this instance's
+            // GC heap base remains valid.
+            unsafe {
+                handle.instance_mut().set_gc_heap_bound(bound);
+            }
+        }
+    }
+
     #[inline]
     pub(crate) fn unwrap_gc_store_mut(&mut self) -> &mut GcStore {
         self.gc_store
@@ -1743,7 +1868,7 @@ impl StoreOpaque {
 
         log::trace!("Begin trace GC roots :: Wasm stack");
 
-        Backtrace::trace(self, |frame| {
+        let mut trace_frame = |frame: crate::runtime::vm::Frame| {
             let pc = frame.pc();
             debug_assert!(pc != 0, "we should always get a valid PC for Wasm frames");
 
@@ -1786,7 +1911,18 @@ impl StoreOpaque {
             }
 
             core::ops::ControlFlow::Continue(())
-        });
+        };
+
+        Backtrace::trace(self, &mut trace_frame);
+
+        // The stacks of suspended continuations are not part of the current
+        // activations but may still hold GC references.
+        #[cfg(feature = "stack-switching")]
+        for (pc, fp, entry_fp) in crate::runtime::stack_switching::suspended_activations(self) {
+            unsafe {
+                Backtrace::trace_suspended(self.unwinder(), pc, fp, entry_fp, &mut trace_frame);
+            }
+        }
 
         log::trace!("End trace GC roots :: Wasm stack");
     }
@@ -1803,6 +1939,12 @@ impl StoreOpaque {
     fn trace_user_roots(&mut self, gc_roots_list: &mut GcRootsList) {
         log::trace!("Begin trace GC roots :: user");
         self.gc_roots.trace_roots(gc_roots_list);
+        if let Some(exn) = &mut self.pending_exception {
+            let exn = crate::runtime::vm::SendSyncPtr::new(NonNull::from(exn));
+            unsafe {
+                gc_roots_list.add_root(exn, "pending exception");
+            }
+        }
         log::trace!("End trace GC roots :: user");
     }
 
@@ -1813,9 +1955,59 @@ impl StoreOpaque {
     /// reclaimed (since it is possible that none of the Wasm modules in this
     /// store are holding it alive).
     pub(crate) fn insert_gc_host_alloc_type(&mut self, ty: RegisteredType) {
+        self.register_gc_types([ty.index()]);
         self.gc_host_alloc_types.insert(ty);
     }
 
+    /// Performs an allocation requested by the host with `alloc`, collecting
+    /// garbage and then growing the GC heap by at least `bytes_needed` bytes
+    /// if the heap is out of space, just like allocations made by Wasm.
+    ///
+    /// Returns `Ok(None)` if the allocation still can't be satisfied after
+    /// growing the heap, or if growing the heap was denied.
+    #[cfg(feature = "gc")]
+    pub(crate) fn retry_host_gc_alloc<T>(
+        &mut self,
+        bytes_needed: u64,
+        mut alloc: impl FnMut(&mut GcStore) -> Result<Option<T>>,
+    ) -> Result<Option<T>> {
+        if let Some(x) = alloc(self.gc_store_mut()?)? {
+            return Ok(Some(x));
+        }
+
+        self.gc();
+        if let Some(x) = alloc(self.unwrap_gc_store_mut())? {
+            return Ok(Some(x));
+        }
+
+        // Safety: the store is not otherwise borrowed while growing its heap,
+        // which may call into the store's resource limiter.
+        let grew = unsafe { self.traitobj().as_mut().maybe_grow_gc_heap(bytes_needed)? };
+        if !grew {
+            return Ok(None);
+        }
+        alloc(self.unwrap_gc_store_mut())
+    }
+
+    /// Prepare this store's GC heap, if it has been allocated, for allocating
+    /// objects of the given types.
+    ///
+    /// When the GC heap is allocated later on, all types registered with the
+    /// store up to that point are registered with it.
+    pub(crate) fn register_gc_types(&mut self, types: impl IntoIterator<Item = VMSharedTypeIndex>) {
+        if let Some(gc_store) = &mut self.gc_store {
+            for ty in types {
+                gc_store.gc_heap.register_type(ty);
+            }
+        }
+    }
+
+    /// Keep the type of a host-created tag registered for as long as this
+    /// store lives, since tags can't be deallocated.
+    pub(crate) fn push_host_tag_type(&mut self, ty: FuncType) {
+        self.host_tag_types.push(ty.into_registered_type());
+    }
+
     /// Yields the async context, assuming that we are executing on a fiber and
     /// that fiber is not in the process of dying. This function will return
     /// None in the latter case (the fiber is dying), and panic if
@@ -2101,6 +2293,19 @@ at https://bytecodealliance.org/security.
         )
     }
 
+    /// Checks that another component instance may be created within this
+    /// store without exceeding the limit set by its resource limiter.
+    #[cfg(feature = "component-model")]
+    pub(crate) fn check_component_instance_limit(&self) -> Result<()> {
+        if self.num_component_instances >= self.component_instance_limit {
+            bail!(
+                "resource limit exceeded: component instance count too high at {}",
+                self.num_component_instances + 1
+            );
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "component-model")]
     pub(crate) fn push_component_instance(&mut self, instance: crate::component::Instance) {
         // We don't actually need the instance itself right now, but it seems
@@ -2128,7 +2333,9 @@ at https://bytecodealliance.org/security.
         if let Some(stack) = self.async_state.last_fiber_stack.take() {
             return Ok(stack);
         }
-        self.engine().allocator().allocate_fiber_stack()
+        let stack = self.engine().allocator().allocate_fiber_stack()?;
+        *self.async_state.fiber_stacks.get_mut() += 1;
+        Ok(stack)
     }
 
     #[cfg(feature = "async")]
@@ -2145,9 +2352,27 @@ at https://bytecodealliance.org/security.
             unsafe {
                 self.engine.allocator().deallocate_fiber_stack(stack);
             }
+            *self.async_state.fiber_stacks.get_mut() -= 1;
         }
     }
 
+    /// Returns the total size, in bytes, of the fiber stacks currently held by
+    /// this store, both for asynchronous calls and for continuations.
+    pub(crate) fn fiber_stack_bytes(&self) -> usize {
+        #[allow(unused_mut)]
+        let mut bytes = 0;
+        #[cfg(feature = "async")]
+        {
+            let stacks = unsafe { *self.async_state.fiber_stacks.get() };
+            bytes += stacks * self.engine.config().async_stack_size;
+        }
+        #[cfg(feature = "stack-switching")]
+        {
+            bytes += crate::runtime::stack_switching::stack_bytes(self);
+        }
+        bytes
+    }
+
     pub(crate) fn interpreter(&mut self) -> Option<InterpreterRef<'_>> {
         let i = self.interpreter.as_mut()?;
         Some(i.as_interpreter_ref())
@@ -2184,10 +2409,17 @@ impl<T> StoreContextMut<'_, T> {
         debug_assert!(self.0.async_support());
         debug_assert!(config.async_stack_size > 0);
 
+        if self.0.async_state.last_fiber_stack.is_none() {
+            self.0
+                .fiber_stack_allocating(config.async_stack_size)
+                .await?;
+        }
+
         let mut slot = None;
         let mut future = {
             let current_poll_cx = self.0.async_state.current_poll_cx.get();
             let current_suspend = self.0.async_state.current_suspend.get();
+            let fiber_stacks = self.0.async_state.fiber_stacks.get();
             let stack = self.0.allocate_fiber_stack()?;
 
             let engine = self.engine().clone();
@@ -2221,6 +2453,7 @@ impl<T> StoreContextMut<'_, T> {
             FiberFuture {
                 fiber: Some(fiber),
                 current_poll_cx,
+                fiber_stacks,
                 engine,
                 state: Some(crate::runtime::vm::AsyncWasmCallState::new()),
             }
@@ -2237,6 +2470,9 @@ impl<T> StoreContextMut<'_, T> {
         struct FiberFuture<'a> {
             fiber: Option<wasmtime_fiber::Fiber<'a, Result<()>, (), Result<()>>>,
             current_poll_cx: *mut PollContext,
+            // The store's count of fiber stacks, decremented if the fiber's
+            // stack is deallocated on drop rather than returned to the store.
+            fiber_stacks: *mut usize,
             engine: Engine,
             // See comments in `FiberFuture::resume` for this
             state: Option<crate::runtime::vm::AsyncWasmCallState>,
@@ -2459,6 +2695,7 @@ impl<T> StoreContextMut<'_, T> {
                     self.engine
                         .allocator()
                         .deallocate_fiber_stack(self.fiber.take().unwrap().into_stack());
+                    *self.fiber_stacks -= 1;
                 }
             }
         }
@@ -2736,6 +2973,79 @@ unsafe impl<T> crate::runtime::vm::VMStore for StoreInner<T> {
         Ok(root)
     }
 
+    #[cfg(feature = "gc")]
+    fn maybe_grow_gc_heap(&mut self, bytes_needed: u64) -> Result<bool> {
+        let (current, maximum) = {
+            let gc_heap = &self.inner.unwrap_gc_store().gc_heap;
+            (gc_heap.heap_slice().len(), gc_heap.max_heap_size())
+        };
+
+        // Grow by at least doubling the heap, so that the cost of growing is
+        // amortized, but never past the maximum unless even the bytes needed
+        // won't fit.
+        let needed = usize::try_from(bytes_needed).unwrap_or(usize::MAX);
+        let mut desired = current.saturating_add(needed);
+        if desired <= maximum {
+            desired = desired.max(current.saturating_mul(2).min(maximum));
+        }
+        if let Ok(rounded) = crate::runtime::vm::HostAlignedByteCount::new_rounded_up(desired) {
+            desired = rounded.byte_count();
+        }
+
+        if !self.gc_heap_growing(current, desired, maximum)? {
+            return Ok(false);
+        }
+        let result = if desired > maximum {
+            Err(anyhow!(
+                "GC heap cannot grow to {desired:#x} bytes; its maximum size is {maximum:#x} bytes"
+            ))
+        } else {
+            self.inner.unwrap_gc_store_mut().gc_heap.grow(desired)
+        };
+        if let Err(e) = result {
+            self.gc_heap_grow_failed(e)?;
+            return Ok(false);
+        }
+
+        log::trace!("grew GC heap from {current:#x} to {desired:#x} bytes");
+        self.inner.update_gc_heap_bounds();
+        Ok(true)
+    }
+
+    #[cfg(not(feature = "gc"))]
+    fn maybe_grow_gc_heap(&mut self, _bytes_needed: u64) -> Result<bool> {
+        Ok(false)
+    }
+
+    #[cfg(feature = "stack-switching")]
+    fn continuation_stack_allocating(&mut self, size: usize) -> Result<()> {
+        let current = self.inner.fiber_stack_bytes();
+        let desired = current.saturating_add(size);
+        let allow = match self.limiter {
+            Some(ResourceLimiterInner::Sync(ref mut limiter)) => {
+                limiter(&mut self.data).fiber_stack_allocating(current, desired)?
+            }
+            Some(ResourceLimiterInner::Async(ref mut limiter)) => unsafe {
+                self.inner
+                    .async_cx()
+                    .expect("ResourceLimiterAsync requires async Store")
+                    .block_on(
+                        limiter(&mut self.data)
+                            .fiber_stack_allocating(current, desired)
+                            .as_mut(),
+                    )??
+            },
+            None => true,
+        };
+        if !allow {
+            bail!(
+                "resource limit exceeded: continuation stack of {size} bytes would grow \
+                 fiber stacks to {desired} bytes"
+            );
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "component-model")]
     fn component_calls(&mut self) -> &mut crate::runtime::vm::component::CallContexts {
         &mut self.component_calls
@@ -2743,6 +3053,77 @@ unsafe impl<T> crate::runtime::vm::VMStore for StoreInner<T> {
 }
 
 impl<T> StoreInner<T> {
+    #[cfg(feature = "gc")]
+    fn gc_heap_growing(&mut self, current: usize, desired: usize, maximum: usize) -> Result<bool> {
+        match self.limiter {
+            Some(ResourceLimiterInner::Sync(ref mut limiter)) => {
+                limiter(&mut self.data).gc_heap_growing(current, desired, maximum)
+            }
+            #[cfg(feature = "async")]
+            Some(ResourceLimiterInner::Async(ref mut limiter)) => unsafe {
+                self.inner
+                    .async_cx()
+                    .expect("ResourceLimiterAsync requires async Store")
+                    .block_on(
+                        limiter(&mut self.data)
+                            .gc_heap_growing(current, desired, maximum)
+                            .as_mut(),
+                    )?
+            },
+            None => Ok(true),
+        }
+    }
+
+    #[cfg(feature = "gc")]
+    fn gc_heap_grow_failed(&mut self, error: anyhow::Error) -> Result<()> {
+        match self.limiter {
+            Some(ResourceLimiterInner::Sync(ref mut limiter)) => {
+                limiter(&mut self.data).gc_heap_grow_failed(error)
+            }
+            #[cfg(feature = "async")]
+            Some(ResourceLimiterInner::Async(ref mut limiter)) => {
+                limiter(&mut self.data).gc_heap_grow_failed(error)
+            }
+            None => {
+                log::debug!("ignoring GC heap growth failure: {error:?}");
+                Ok(())
+            }
+        }
+    }
+
+    /// Cancels all of this store's continuations so that their stacks can be
+    /// deallocated.
+    fn cancel_continuations(&mut self) {
+        #[cfg(feature = "stack-switching")]
+        crate::runtime::stack_switching::cancel_all(self);
+    }
+
+    /// Consults the resource limiter, if any, before allocating a new fiber
+    /// stack of `size` bytes, returning an error if the allocation is denied.
+    #[cfg(feature = "async")]
+    async fn fiber_stack_allocating(&mut self, size: usize) -> Result<()> {
+        let current = self.inner.fiber_stack_bytes();
+        let desired = current.saturating_add(size);
+        let allow = match self.limiter {
+            Some(ResourceLimiterInner::Sync(ref mut limiter)) => {
+                limiter(&mut self.data).fiber_stack_allocating(current, desired)?
+            }
+            Some(ResourceLimiterInner::Async(ref mut limiter)) => {
+                limiter(&mut self.data)
+                    .fiber_stack_allocating(current, desired)
+                    .await?
+            }
+            None => true,
+        };
+        if !allow {
+            bail!(
+                "resource limit exceeded: fiber stack of {size} bytes would grow \
+                 fiber stacks to {desired} bytes"
+            );
+        }
+        Ok(())
+    }
+
     pub(crate) fn set_epoch_deadline(&mut self, delta: u64) {
         // Set a new deadline based on the "epoch deadline delta".
         //
@@ -2808,6 +3189,7 @@ impl<T: fmt::Debug> fmt::Debug for Store<T> {
 
 impl<T> Drop for Store<T> {
     fn drop(&mut self) {
+        self.inner.cancel_continuations();
         self.inner.flush_fiber_stack();
 
         // for documentation on this `unsafe`, see `into_data`.
@@ -27,6 +27,7 @@ pub struct StoreData {
     globals: Vec<crate::runtime::vm::ExportGlobal>,
     instances: Vec<crate::instance::InstanceData>,
     memories: Vec<crate::runtime::vm::ExportMemory>,
+    tags: Vec<crate::runtime::vm::ExportTag>,
     #[cfg(feature = "component-model")]
     pub(crate) components: crate::component::ComponentStoreData,
 }
@@ -53,6 +54,7 @@ impl_store_data! {
     globals => crate::runtime::vm::ExportGlobal,
     instances => crate::instance::InstanceData,
     memories => crate::runtime::vm::ExportMemory,
+    tags => crate::runtime::vm::ExportTag,
 }
 
 impl StoreData {
@@ -64,6 +66,7 @@ impl StoreData {
             globals: Vec::new(),
             instances: Vec::new(),
             memories: Vec::new(),
+            tags: Vec::new(),
             #[cfg(feature = "component-model")]
             components: Default::default(),
         }
@@ -111,6 +114,31 @@ impl StoreData {
         (0..T::list(self).len()).map(move |i| Stored::new(id, i))
     }
 
+    /// Allocates a new tag with the given signature in this store.
+    ///
+    /// Tags are identified by their index in this store, which is recorded in
+    /// the returned `ExportTag`.
+    pub(crate) fn alloc_tag(
+        &mut self,
+        signature: wasmtime_environ::VMSharedTypeIndex,
+    ) -> crate::runtime::vm::ExportTag {
+        let tag = crate::runtime::vm::ExportTag {
+            index: self.tags.len(),
+            signature,
+        };
+        self.tags.push(tag);
+        tag
+    }
+
+    /// Returns the handle for a tag previously allocated with `alloc_tag`.
+    pub(crate) fn tag(
+        &self,
+        tag: crate::runtime::vm::ExportTag,
+    ) -> Stored<crate::runtime::vm::ExportTag> {
+        debug_assert_eq!(self.tags[tag.index], tag);
+        Stored::new(self.id, tag.index)
+    }
+
     pub(crate) fn reserve_funcs(&mut self, count: usize) {
         self.funcs.reserve(count);
     }
@@ -0,0 +1,36 @@
+//! The exception that is currently being thrown in a store.
+//!
+//! When WebAssembly or the host throws an exception it becomes the store's
+//! pending exception and the `exception_pending` flag of its
+//! `VMRuntimeLimits` is set. Compiled code checks that flag after every call
+//! and, when it is set, dispatches to the innermost `try_table`'s catch
+//! clauses or returns to its caller.
+
+use super::StoreOpaque;
+use crate::runtime::vm::VMGcRef;
+
+impl StoreOpaque {
+    /// Makes `exn` the exception that is currently being thrown.
+    pub(crate) fn set_pending_exception(&mut self, exn: VMGcRef) {
+        if let Some(old) = self.pending_exception.replace(exn) {
+            self.unwrap_gc_store_mut().drop_gc_ref(old);
+        }
+        unsafe {
+            *self.runtime_limits.exception_pending.get() = 1;
+        }
+    }
+
+    /// Returns the exception that is currently being thrown, if any.
+    pub(crate) fn pending_exception(&self) -> Option<&VMGcRef> {
+        self.pending_exception.as_ref()
+    }
+
+    /// Takes the exception that is currently being thrown, if any, so that it
+    /// is no longer pending.
+    pub(crate) fn take_pending_exception(&mut self) -> Option<VMGcRef> {
+        unsafe {
+            *self.runtime_limits.exception_pending.get() = 0;
+        }
+        self.pending_exception.take()
+    }
+}
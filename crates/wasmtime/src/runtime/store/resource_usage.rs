@@ -0,0 +1,124 @@
+use super::{StoreInner, StoreOpaque};
+
+/// A snapshot of the host resources accounted to a [`Store`](crate::Store).
+///
+/// Obtained from [`Store::resource_usage`](crate::Store::resource_usage). This
+/// can be used to bill embedded tenants for their resource usage or to pick
+/// which stores to evict when the host is under memory pressure.
+///
+/// Byte counts are the sizes that are currently allocated and accessible to
+/// WebAssembly, not the sizes of the virtual memory reservations backing them.
+/// A shared memory is accounted to every store that has instantiated or
+/// created it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResourceUsage {
+    /// The number of core instances in this store.
+    ///
+    /// This is the count that is checked against
+    /// [`ResourceLimiter::instances`](crate::ResourceLimiter::instances).
+    pub instances: usize,
+    /// The number of linear memories defined by instances in this store.
+    ///
+    /// This is the count that is checked against
+    /// [`ResourceLimiter::memories`](crate::ResourceLimiter::memories).
+    pub memories: usize,
+    /// The number of tables defined by instances in this store.
+    ///
+    /// This is the count that is checked against
+    /// [`ResourceLimiter::tables`](crate::ResourceLimiter::tables).
+    pub tables: usize,
+    /// The number of component instances in this store.
+    pub component_instances: usize,
+    /// The total size, in bytes, of all linear memories in this store,
+    /// including those created by the host.
+    pub linear_memory_bytes: usize,
+    /// The total size, in bytes, of the elements of all tables in this store,
+    /// including those created by the host.
+    pub table_bytes: usize,
+    /// The size, in bytes, of this store's GC heap, or zero if it has not been
+    /// allocated yet.
+    pub gc_heap_bytes: usize,
+    /// The size, in bytes, of the fiber stacks held by this store for
+    /// executing WebAssembly asynchronously and for the continuations created
+    /// by WebAssembly.
+    pub fiber_stack_bytes: usize,
+    /// The number of resource handles the host holds in this store through
+    /// [`ResourceAny`](crate::component::ResourceAny).
+    pub host_resource_handles: usize,
+    /// The number of resources held in host
+    /// [`ResourceTable`](crate::component::ResourceTable)s, such as the one
+    /// owned by a WASI context.
+    ///
+    /// These tables live within the `T` of a `Store<T>` where the store can't
+    /// see them, so this is zero unless the embedder adds its tables with
+    /// [`ResourceUsage::with_resource_table`].
+    pub resource_table_entries: usize,
+    /// The size, in bytes, of the `T` data stored within the `Store<T>`.
+    ///
+    /// This is only `size_of::<T>()` and does not include any heap allocations
+    /// owned by `T`, such as a `ResourceTable` held by a WASI context, which
+    /// the embedder must account for itself.
+    pub host_data_bytes: usize,
+}
+
+impl ResourceUsage {
+    /// Returns the total number of bytes accounted for by this snapshot.
+    pub fn total_bytes(&self) -> usize {
+        self.linear_memory_bytes
+            + self.table_bytes
+            + self.gc_heap_bytes
+            + self.fiber_stack_bytes
+            + self.host_data_bytes
+    }
+
+    /// Accounts the resources held in `table` to this snapshot, adding to
+    /// [`ResourceUsage::resource_table_entries`].
+    #[cfg(feature = "component-model")]
+    pub fn with_resource_table(mut self, table: &crate::component::ResourceTable) -> Self {
+        self.resource_table_entries += table.len();
+        self
+    }
+}
+
+impl StoreOpaque {
+    pub(crate) fn resource_usage(&self) -> ResourceUsage {
+        let mut usage = ResourceUsage {
+            instances: self.instance_count,
+            memories: self.memory_count,
+            tables: self.table_count,
+            ..ResourceUsage::default()
+        };
+
+        // NB: Host-created memories and tables have dummy instances, so this
+        // accounts for them as well.
+        for instance in self.instances.iter() {
+            usage.linear_memory_bytes += instance.handle.defined_memories_byte_size();
+            usage.table_bytes += instance.handle.defined_tables_byte_size();
+        }
+
+        #[cfg(feature = "gc")]
+        if let Some(gc_store) = &self.gc_store {
+            usage.gc_heap_bytes = gc_store.gc_heap.heap_slice().len();
+        }
+
+        usage.fiber_stack_bytes = self.fiber_stack_bytes();
+
+        #[cfg(feature = "component-model")]
+        {
+            usage.component_instances = self.num_component_instances;
+            usage.host_resource_handles = self.component_host_table.len();
+        }
+
+        usage
+    }
+}
+
+impl<T> StoreInner<T> {
+    pub(crate) fn resource_usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            host_data_bytes: core::mem::size_of::<T>(),
+            ..self.inner.resource_usage()
+        }
+    }
+}
@@ -4,7 +4,9 @@ use crate::prelude::*;
 use crate::store::StoreOpaque;
 use crate::{AsContext, Module};
 use core::fmt;
-use wasmtime_environ::{demangle_function_name, demangle_function_name_or_index, FilePos};
+use wasmtime_environ::{
+    demangle_function_name, demangle_function_name_or_index, DefinedFuncIndex, FilePos,
+};
 
 /// Representation of a WebAssembly trap and what caused it to occur.
 ///
@@ -313,7 +315,10 @@ impl WasmBacktrace {
             // let Some(..)` instead of the `unwrap` you might otherwise expect
             // and we ignore frames from modules that were not registered in
             // this store's module registry.
-            if let Some((info, module)) = store.modules().lookup_frame_info(pc_to_lookup) {
+            if let Some((mut info, module)) = store.modules().lookup_frame_info(pc_to_lookup) {
+                if let Some(inlined) = info.inlined.take() {
+                    wasm_trace.push(*inlined);
+                }
                 wasm_trace.push(info);
 
                 // If this frame has unparsed debug information and the
@@ -413,6 +418,9 @@ pub struct FrameInfo {
     func_start: FilePos,
     instr: Option<FilePos>,
     symbols: Vec<FrameSymbol>,
+    /// The frame of a function inlined into this one which the pc is within,
+    /// in which case `instr` is the call which was inlined.
+    inlined: Option<Box<FrameInfo>>,
 }
 
 impl FrameInfo {
@@ -423,15 +431,10 @@ impl FrameInfo {
     pub(crate) fn new(module: Module, text_offset: usize) -> Option<FrameInfo> {
         let compiled_module = module.compiled_module();
         let (index, _func_offset) = compiled_module.func_by_text_offset(text_offset)?;
-        let info = compiled_module.wasm_func_info(index);
-        let func_start = info.start_srcloc;
         let instr = wasmtime_environ::lookup_file_pos(
             compiled_module.code_memory().address_map_data(),
             text_offset,
         );
-        let index = compiled_module.module().func_index(index);
-        let func_index = index.as_u32();
-        let func_name = compiled_module.func_name(index).map(|s| s.to_string());
 
         // In debug mode for now assert that we found a mapping for `pc` within
         // the function, because otherwise something is buggy along the way and
@@ -445,6 +448,37 @@ impl FrameInfo {
             "failed to find instruction for {text_offset:#x}"
         );
 
+        // Code inlined from another function keeps the source locations of
+        // that function, and the location of the call it was inlined at is
+        // recorded separately. Such code is reported as being within the
+        // inlined function, followed by a frame for the function it was
+        // inlined into which is at the call.
+        let call = wasmtime_environ::lookup_file_pos(
+            compiled_module.code_memory().inlined_calls_data(),
+            text_offset,
+        )
+        .filter(|call| call.file_offset().is_some());
+        let callee = instr
+            .and_then(|i| i.file_offset())
+            .and_then(|offset| compiled_module.func_by_file_offset(offset));
+        match (call, callee) {
+            (Some(call), Some(callee)) => {
+                let mut frame = FrameInfo::new_at(module.clone(), index, Some(call));
+                frame.inlined = Some(Box::new(FrameInfo::new_at(module, callee, instr)));
+                Some(frame)
+            }
+            _ => Some(FrameInfo::new_at(module, index, instr)),
+        }
+    }
+
+    fn new_at(module: Module, index: DefinedFuncIndex, instr: Option<FilePos>) -> FrameInfo {
+        let compiled_module = module.compiled_module();
+        let info = compiled_module.wasm_func_info(index);
+        let func_start = info.start_srcloc;
+        let index = compiled_module.module().func_index(index);
+        let func_index = index.as_u32();
+        let func_name = compiled_module.func_name(index).map(|s| s.to_string());
+
         // Use our wasm-relative pc to symbolize this frame. If there's a
         // symbolication context (dwarf debug info) available then we can try to
         // look this up there.
@@ -481,14 +515,21 @@ impl FrameInfo {
             }
         }
 
-        Some(FrameInfo {
+        FrameInfo {
             module,
             func_index,
             func_name,
             instr,
             func_start,
             symbols,
-        })
+            inlined: None,
+        }
+    }
+
+    /// Returns the frame of the function inlined into this one which was
+    /// executing, if any.
+    pub(crate) fn inlined(&self) -> Option<&FrameInfo> {
+        self.inlined.as_deref()
     }
 
     /// Returns the WebAssembly function index for this frame.
@@ -556,6 +597,13 @@ impl FrameInfo {
         Some((instr_offset - self.func_start.file_offset()?) as usize)
     }
 
+    /// Returns the offset of the body of this frame's function within the
+    /// original wasm binary, if known.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn func_start(&self) -> Option<u32> {
+        self.func_start.file_offset()
+    }
+
     /// Returns the debug symbols found, if any, for this function frame.
     ///
     /// When a wasm program is compiled with DWARF debug information then this
@@ -814,7 +814,8 @@ impl TypeRegistryInner {
 
         assert!(!ty.composite_type.shared);
         let gc_layout = match &ty.composite_type.inner {
-            wasmtime_environ::WasmCompositeInnerType::Func(_) => None,
+            wasmtime_environ::WasmCompositeInnerType::Func(_)
+            | wasmtime_environ::WasmCompositeInnerType::Cont(_) => None,
             wasmtime_environ::WasmCompositeInnerType::Array(a) => Some(
                 gc_runtime
                     .expect("must have a GC runtime to register array types")
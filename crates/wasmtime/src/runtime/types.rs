@@ -3,8 +3,8 @@ use core::fmt::{self, Display, Write};
 use wasmtime_environ::{
     EngineOrModuleTypeIndex, EntityType, Global, IndexType, Limits, Memory, ModuleTypes, Table,
     TypeTrace, VMSharedTypeIndex, WasmArrayType, WasmCompositeInnerType, WasmCompositeType,
-    WasmFieldType, WasmFuncType, WasmHeapType, WasmRefType, WasmStorageType, WasmStructType,
-    WasmSubType, WasmValType,
+    WasmContType, WasmFieldType, WasmFuncType, WasmHeapType, WasmRefType, WasmStorageType,
+    WasmStructType, WasmSubType, WasmValType,
 };
 
 use crate::{type_registry::RegisteredType, Engine};
@@ -155,6 +155,12 @@ impl ValType {
     /// The `nullref` type, aka `(ref null none)`.
     pub const NULLREF: Self = ValType::Ref(RefType::NULLREF);
 
+    /// The `exnref` type, aka `(ref null exn)`.
+    pub const EXNREF: Self = ValType::Ref(RefType::EXNREF);
+
+    /// The `nullexnref` type, aka `(ref null noexn)`.
+    pub const NULLEXNREF: Self = ValType::Ref(RefType::NULLEXNREF);
+
     /// Returns true if `ValType` matches any of the numeric types. (e.g. `I32`,
     /// `I64`, `F32`, `F64`).
     #[inline]
@@ -438,6 +444,30 @@ impl RefType {
         heap_type: HeapType::None,
     };
 
+    /// The `exnref` type, aka `(ref null exn)`.
+    pub const EXNREF: Self = RefType {
+        is_nullable: true,
+        heap_type: HeapType::Exn,
+    };
+
+    /// The `nullexnref` type, aka `(ref null noexn)`.
+    pub const NULLEXNREF: Self = RefType {
+        is_nullable: true,
+        heap_type: HeapType::NoExn,
+    };
+
+    /// The `contref` type, aka `(ref null cont)`.
+    pub const CONTREF: Self = RefType {
+        is_nullable: true,
+        heap_type: HeapType::Cont,
+    };
+
+    /// The `nullcontref` type, aka `(ref null nocont)`.
+    pub const NULLCONTREF: Self = RefType {
+        is_nullable: true,
+        heap_type: HeapType::NoCont,
+    };
+
     /// Construct a new reference type.
     pub fn new(is_nullable: bool, heap_type: HeapType) -> RefType {
         RefType {
@@ -703,6 +733,39 @@ pub enum HeapType {
     /// This is the bottom type for the internal type hierarchy, and therefore
     /// `none` is a subtype of internal types.
     None,
+
+    /// The abstract `exn` heap type represents a reference to a caught
+    /// exception.
+    ///
+    /// This is the top type for the exception type hierarchy, and therefore is
+    /// the common supertype of all exception reference types.
+    Exn,
+
+    /// The abstract `noexn` heap type represents the null exception
+    /// reference.
+    ///
+    /// This is the bottom type for the exception type hierarchy, and therefore
+    /// is the common subtype of all exception reference types.
+    NoExn,
+
+    /// The abstract `cont` heap type represents a reference to any kind of
+    /// continuation.
+    ///
+    /// This is the top type for the continuation type hierarchy, and therefore
+    /// is the common supertype of all continuation reference types.
+    Cont,
+
+    /// A reference to a continuation of a specific, concrete type.
+    ///
+    /// These are subtypes of `cont` and supertypes of `nocont`.
+    ConcreteCont(ContType),
+
+    /// The abstract `nocont` heap type represents the null continuation
+    /// reference.
+    ///
+    /// This is the bottom type for the continuation type hierarchy, and
+    /// therefore is the common subtype of all continuation reference types.
+    NoCont,
 }
 
 impl Display for HeapType {
@@ -718,9 +781,14 @@ impl Display for HeapType {
             HeapType::Array => write!(f, "array"),
             HeapType::Struct => write!(f, "struct"),
             HeapType::None => write!(f, "none"),
+            HeapType::Exn => write!(f, "exn"),
+            HeapType::NoExn => write!(f, "noexn"),
+            HeapType::Cont => write!(f, "cont"),
+            HeapType::NoCont => write!(f, "nocont"),
             HeapType::ConcreteFunc(ty) => write!(f, "(concrete func {:?})", ty.type_index()),
             HeapType::ConcreteArray(ty) => write!(f, "(concrete array {:?})", ty.type_index()),
             HeapType::ConcreteStruct(ty) => write!(f, "(concrete struct {:?})", ty.type_index()),
+            HeapType::ConcreteCont(ty) => write!(f, "(concrete cont {:?})", ty.type_index()),
         }
     }
 }
@@ -746,6 +814,13 @@ impl From<StructType> for HeapType {
     }
 }
 
+impl From<ContType> for HeapType {
+    #[inline]
+    fn from(c: ContType) -> Self {
+        HeapType::ConcreteCont(c)
+    }
+}
+
 impl HeapType {
     /// Is this the abstract `extern` heap type?
     pub fn is_extern(&self) -> bool {
@@ -777,6 +852,16 @@ impl HeapType {
         matches!(self, HeapType::None)
     }
 
+    /// Is this the abstract `exn` heap type?
+    pub fn is_exn(&self) -> bool {
+        matches!(self, HeapType::Exn)
+    }
+
+    /// Is this the abstract `cont` heap type?
+    pub fn is_cont(&self) -> bool {
+        matches!(self, HeapType::Cont)
+    }
+
     /// Is this an abstract type?
     ///
     /// Types that are not abstract are concrete, user-defined types.
@@ -791,7 +876,10 @@ impl HeapType {
     pub fn is_concrete(&self) -> bool {
         matches!(
             self,
-            HeapType::ConcreteFunc(_) | HeapType::ConcreteArray(_) | HeapType::ConcreteStruct(_)
+            HeapType::ConcreteFunc(_)
+                | HeapType::ConcreteArray(_)
+                | HeapType::ConcreteStruct(_)
+                | HeapType::ConcreteCont(_)
         )
     }
 
@@ -858,6 +946,27 @@ impl HeapType {
         self.as_concrete_struct().unwrap()
     }
 
+    /// Is this a concrete, user-defined continuation type?
+    pub fn is_concrete_cont(&self) -> bool {
+        matches!(self, HeapType::ConcreteCont(_))
+    }
+
+    /// Get the underlying concrete, user-defined continuation type, if any.
+    ///
+    /// Returns `None` if this is not a concrete continuation type.
+    pub fn as_concrete_cont(&self) -> Option<&ContType> {
+        match self {
+            HeapType::ConcreteCont(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Get the underlying concrete, user-defined type, panicking if this is not
+    /// a concrete continuation type.
+    pub fn unwrap_concrete_cont(&self) -> &ContType {
+        self.as_concrete_cont().unwrap()
+    }
+
     /// Get the top type of this heap type's type hierarchy.
     ///
     /// The returned heap type is a supertype of all types in this heap type's
@@ -869,6 +978,10 @@ impl HeapType {
 
             HeapType::Extern | HeapType::NoExtern => HeapType::Extern,
 
+            HeapType::Exn | HeapType::NoExn => HeapType::Exn,
+
+            HeapType::Cont | HeapType::ConcreteCont(_) | HeapType::NoCont => HeapType::Cont,
+
             HeapType::Any
             | HeapType::Eq
             | HeapType::I31
@@ -884,7 +997,9 @@ impl HeapType {
     #[inline]
     pub fn is_top(&self) -> bool {
         match self {
-            HeapType::Any | HeapType::Extern | HeapType::Func => true,
+            HeapType::Any | HeapType::Extern | HeapType::Func | HeapType::Exn | HeapType::Cont => {
+                true
+            }
             _ => false,
         }
     }
@@ -898,6 +1013,10 @@ impl HeapType {
         match self {
             HeapType::Extern | HeapType::NoExtern => HeapType::NoExtern,
 
+            HeapType::Exn | HeapType::NoExn => HeapType::NoExn,
+
+            HeapType::Cont | HeapType::ConcreteCont(_) | HeapType::NoCont => HeapType::NoCont,
+
             HeapType::Func | HeapType::ConcreteFunc(_) | HeapType::NoFunc => HeapType::NoFunc,
 
             HeapType::Any
@@ -915,7 +1034,11 @@ impl HeapType {
     #[inline]
     pub fn is_bottom(&self) -> bool {
         match self {
-            HeapType::None | HeapType::NoExtern | HeapType::NoFunc => true,
+            HeapType::None
+            | HeapType::NoExtern
+            | HeapType::NoFunc
+            | HeapType::NoExn
+            | HeapType::NoCont => true,
             _ => false,
         }
     }
@@ -936,6 +1059,29 @@ impl HeapType {
             (HeapType::NoExtern, HeapType::NoExtern | HeapType::Extern) => true,
             (HeapType::NoExtern, _) => false,
 
+            (HeapType::Exn, HeapType::Exn) => true,
+            (HeapType::Exn, _) => false,
+
+            (HeapType::NoExn, HeapType::NoExn | HeapType::Exn) => true,
+            (HeapType::NoExn, _) => false,
+
+            (HeapType::NoCont, HeapType::NoCont | HeapType::ConcreteCont(_) | HeapType::Cont) => {
+                true
+            }
+            (HeapType::NoCont, _) => false,
+
+            (HeapType::ConcreteCont(_), HeapType::Cont) => true,
+            (HeapType::ConcreteCont(a), HeapType::ConcreteCont(b)) => {
+                assert!(a.comes_from_same_engine(b.engine()));
+                a.engine()
+                    .signatures()
+                    .is_subtype(a.type_index(), b.type_index())
+            }
+            (HeapType::ConcreteCont(_), _) => false,
+
+            (HeapType::Cont, HeapType::Cont) => true,
+            (HeapType::Cont, _) => false,
+
             (HeapType::NoFunc, HeapType::NoFunc | HeapType::ConcreteFunc(_) | HeapType::Func) => {
                 true
             }
@@ -1036,10 +1182,15 @@ impl HeapType {
             | HeapType::I31
             | HeapType::Array
             | HeapType::Struct
-            | HeapType::None => true,
+            | HeapType::None
+            | HeapType::Exn
+            | HeapType::NoExn
+            | HeapType::Cont
+            | HeapType::NoCont => true,
             HeapType::ConcreteFunc(ty) => ty.comes_from_same_engine(engine),
             HeapType::ConcreteArray(ty) => ty.comes_from_same_engine(engine),
             HeapType::ConcreteStruct(ty) => ty.comes_from_same_engine(engine),
+            HeapType::ConcreteCont(ty) => ty.comes_from_same_engine(engine),
         }
     }
 
@@ -1055,6 +1206,10 @@ impl HeapType {
             HeapType::Array => WasmHeapType::Array,
             HeapType::Struct => WasmHeapType::Struct,
             HeapType::None => WasmHeapType::None,
+            HeapType::Exn => WasmHeapType::Exn,
+            HeapType::NoExn => WasmHeapType::NoExn,
+            HeapType::Cont => WasmHeapType::Cont,
+            HeapType::NoCont => WasmHeapType::NoCont,
             HeapType::ConcreteFunc(f) => {
                 WasmHeapType::ConcreteFunc(EngineOrModuleTypeIndex::Engine(f.type_index()))
             }
@@ -1064,6 +1219,9 @@ impl HeapType {
             HeapType::ConcreteStruct(a) => {
                 WasmHeapType::ConcreteStruct(EngineOrModuleTypeIndex::Engine(a.type_index()))
             }
+            HeapType::ConcreteCont(c) => {
+                WasmHeapType::ConcreteCont(EngineOrModuleTypeIndex::Engine(c.type_index()))
+            }
         }
     }
 
@@ -1079,6 +1237,10 @@ impl HeapType {
             WasmHeapType::Array => HeapType::Array,
             WasmHeapType::Struct => HeapType::Struct,
             WasmHeapType::None => HeapType::None,
+            WasmHeapType::Exn => HeapType::Exn,
+            WasmHeapType::NoExn => HeapType::NoExn,
+            WasmHeapType::Cont => HeapType::Cont,
+            WasmHeapType::NoCont => HeapType::NoCont,
             WasmHeapType::ConcreteFunc(EngineOrModuleTypeIndex::Engine(idx)) => {
                 HeapType::ConcreteFunc(FuncType::from_shared_type_index(engine, *idx))
             }
@@ -1088,13 +1250,18 @@ impl HeapType {
             WasmHeapType::ConcreteStruct(EngineOrModuleTypeIndex::Engine(idx)) => {
                 HeapType::ConcreteStruct(StructType::from_shared_type_index(engine, *idx))
             }
+            WasmHeapType::ConcreteCont(EngineOrModuleTypeIndex::Engine(idx)) => {
+                HeapType::ConcreteCont(ContType::from_shared_type_index(engine, *idx))
+            }
 
             WasmHeapType::ConcreteFunc(EngineOrModuleTypeIndex::Module(_))
             | WasmHeapType::ConcreteFunc(EngineOrModuleTypeIndex::RecGroup(_))
             | WasmHeapType::ConcreteArray(EngineOrModuleTypeIndex::Module(_))
             | WasmHeapType::ConcreteArray(EngineOrModuleTypeIndex::RecGroup(_))
             | WasmHeapType::ConcreteStruct(EngineOrModuleTypeIndex::Module(_))
-            | WasmHeapType::ConcreteStruct(EngineOrModuleTypeIndex::RecGroup(_)) => {
+            | WasmHeapType::ConcreteStruct(EngineOrModuleTypeIndex::RecGroup(_))
+            | WasmHeapType::ConcreteCont(EngineOrModuleTypeIndex::Module(_))
+            | WasmHeapType::ConcreteCont(EngineOrModuleTypeIndex::RecGroup(_)) => {
                 panic!("HeapType::from_wasm_type on non-canonicalized-for-runtime-usage heap type")
             }
         }
@@ -1105,6 +1272,7 @@ impl HeapType {
             HeapType::ConcreteFunc(f) => Some(&f.registered_type),
             HeapType::ConcreteArray(a) => Some(&a.registered_type),
             HeapType::ConcreteStruct(a) => Some(&a.registered_type),
+            HeapType::ConcreteCont(c) => Some(&c.registered_type),
 
             HeapType::Extern
             | HeapType::NoExtern
@@ -1115,14 +1283,18 @@ impl HeapType {
             | HeapType::I31
             | HeapType::Array
             | HeapType::Struct
-            | HeapType::None => None,
+            | HeapType::None
+            | HeapType::Exn
+            | HeapType::NoExn
+            | HeapType::Cont
+            | HeapType::NoCont => None,
         }
     }
 
     #[inline]
     pub(crate) fn is_vmgcref_type(&self) -> bool {
         match self.top() {
-            Self::Any | Self::Extern => true,
+            Self::Any | Self::Extern | Self::Exn | Self::Cont => true,
             Self::Func => false,
             ty => unreachable!("not a top type: {ty:?}"),
         }
@@ -1135,7 +1307,12 @@ impl HeapType {
         self.is_vmgcref_type()
             && !matches!(
                 self,
-                HeapType::I31 | HeapType::NoExtern | HeapType::NoFunc | HeapType::None
+                HeapType::I31
+                    | HeapType::NoExtern
+                    | HeapType::NoFunc
+                    | HeapType::None
+                    | HeapType::NoExn
+                    | HeapType::NoCont
             )
     }
 }
@@ -1157,6 +1334,8 @@ pub enum ExternType {
     Table(TableType),
     /// This external type is the type of a WebAssembly memory.
     Memory(MemoryType),
+    /// This external type is the type of a WebAssembly exception tag.
+    Tag(TagType),
 }
 
 macro_rules! extern_type_accessors {
@@ -1189,6 +1368,7 @@ impl ExternType {
         (Global(GlobalType) global unwrap_global)
         (Table(TableType) table unwrap_table)
         (Memory(MemoryType) memory unwrap_memory)
+        (Tag(TagType) tag unwrap_tag)
     }
 
     pub(crate) fn from_wasmtime(
@@ -1197,26 +1377,13 @@ impl ExternType {
         ty: &EntityType,
     ) -> ExternType {
         match ty {
-            EntityType::Function(idx) => match idx {
-                EngineOrModuleTypeIndex::Engine(e) => {
-                    FuncType::from_shared_type_index(engine, *e).into()
-                }
-                EngineOrModuleTypeIndex::Module(m) => {
-                    let subty = &types[*m];
-                    FuncType::from_wasm_func_type(
-                        engine,
-                        subty.is_final,
-                        subty.supertype,
-                        subty.unwrap_func().clone(),
-                    )
-                    .into()
-                }
-                EngineOrModuleTypeIndex::RecGroup(_) => unreachable!(),
-            },
+            EntityType::Function(idx) => FuncType::from_wasmtime_index(engine, types, idx).into(),
             EntityType::Global(ty) => GlobalType::from_wasmtime_global(engine, ty).into(),
             EntityType::Memory(ty) => MemoryType::from_wasmtime_memory(ty).into(),
             EntityType::Table(ty) => TableType::from_wasmtime_table(engine, ty).into(),
-            EntityType::Tag(_) => unimplemented!("wasm tag support"),
+            EntityType::Tag(ty) => {
+                TagType::new(FuncType::from_wasmtime_index(engine, types, &ty.signature)).into()
+            }
         }
     }
 }
@@ -1245,6 +1412,12 @@ impl From<TableType> for ExternType {
     }
 }
 
+impl From<TagType> for ExternType {
+    fn from(ty: TagType) -> ExternType {
+        ExternType::Tag(ty)
+    }
+}
+
 /// The storage type of a `struct` field or `array` element.
 ///
 /// This is either a packed 8- or -16 bit integer, or else it is some unpacked
@@ -2037,6 +2210,125 @@ impl ArrayType {
     }
 }
 
+/// The type of a WebAssembly continuation.
+///
+/// Continuations are created from function references by the `cont.new`
+/// instruction of the [stack switching proposal][proposal], and a continuation
+/// type is described by the type of the function underlying it.
+///
+/// # Subtyping and Equality
+///
+/// `ContType` does not implement `Eq`, because reference types have a
+/// subtyping relationship, and so 99.99% of the time you actually want to check
+/// whether one type matches (i.e. is a subtype of) another type. You can use
+/// the [`ContType::matches`] method to perform these types of checks. If,
+/// however, you are in that 0.01% scenario where you need to check precise
+/// equality between types, you can use the [`ContType::eq`] method.
+///
+/// [proposal]: https://github.com/WebAssembly/stack-switching
+#[derive(Debug, Clone, Hash)]
+pub struct ContType {
+    registered_type: RegisteredType,
+}
+
+impl fmt::Display for ContType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(cont {})", self.func_type())
+    }
+}
+
+impl ContType {
+    /// Construct a new `ContType` for continuations of functions of the given
+    /// type.
+    ///
+    /// The new `ContType` will be final and without a supertype.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given function type is not associated with the given
+    /// engine.
+    pub fn new(engine: &Engine, func_type: &FuncType) -> Self {
+        assert!(func_type.comes_from_same_engine(engine));
+        let ty = RegisteredType::new(
+            engine,
+            WasmSubType {
+                is_final: true,
+                supertype: None,
+                composite_type: WasmCompositeType {
+                    shared: false,
+                    inner: WasmCompositeInnerType::Cont(WasmContType(
+                        EngineOrModuleTypeIndex::Engine(func_type.type_index()),
+                    )),
+                },
+            },
+        );
+        Self {
+            registered_type: ty,
+        }
+    }
+
+    /// Get the engine that this continuation type is associated with.
+    pub fn engine(&self) -> &Engine {
+        self.registered_type.engine()
+    }
+
+    /// Get the type of the function underlying continuations of this type.
+    pub fn func_type(&self) -> FuncType {
+        let index = self.registered_type.unwrap_cont().0;
+        FuncType::from_shared_type_index(self.engine(), index.unwrap_engine_type_index())
+    }
+
+    /// Does this continuation type match the other continuation type?
+    ///
+    /// That is, is this continuation type a subtype of the other continuation
+    /// type?
+    ///
+    /// # Panics
+    ///
+    /// Panics if either type is associated with a different engine from the
+    /// other.
+    pub fn matches(&self, other: &ContType) -> bool {
+        assert!(self.comes_from_same_engine(other.engine()));
+
+        self.engine()
+            .signatures()
+            .is_subtype(self.type_index(), other.type_index())
+    }
+
+    /// Is continuation type `a` precisely equal to continuation type `b`?
+    ///
+    /// Returns `false` even if `a` is a subtype of `b` or vice versa, if they
+    /// are not exactly the same continuation type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either type is associated with a different engine from the
+    /// other.
+    pub fn eq(a: &ContType, b: &ContType) -> bool {
+        assert!(a.comes_from_same_engine(b.engine()));
+        a.type_index() == b.type_index()
+    }
+
+    pub(crate) fn comes_from_same_engine(&self, engine: &Engine) -> bool {
+        Engine::same(self.registered_type.engine(), engine)
+    }
+
+    pub(crate) fn type_index(&self) -> VMSharedTypeIndex {
+        self.registered_type.index()
+    }
+
+    pub(crate) fn from_shared_type_index(engine: &Engine, index: VMSharedTypeIndex) -> ContType {
+        let ty = RegisteredType::root(engine, index).expect(
+            "VMSharedTypeIndex is not registered in the Engine! Wrong \
+             engine? Didn't root the index somewhere?",
+        );
+        debug_assert!(ty.is_cont());
+        Self {
+            registered_type: ty,
+        }
+    }
+}
+
 /// The type of a WebAssembly function.
 ///
 /// WebAssembly functions can have 0 or more parameters and results.
@@ -2382,6 +2674,26 @@ impl FuncType {
         }
     }
 
+    pub(crate) fn from_wasmtime_index(
+        engine: &Engine,
+        types: &ModuleTypes,
+        index: &EngineOrModuleTypeIndex,
+    ) -> FuncType {
+        match index {
+            EngineOrModuleTypeIndex::Engine(e) => FuncType::from_shared_type_index(engine, *e),
+            EngineOrModuleTypeIndex::Module(m) => {
+                let subty = &types[*m];
+                FuncType::from_wasm_func_type(
+                    engine,
+                    subty.is_final,
+                    subty.supertype,
+                    subty.unwrap_func().clone(),
+                )
+            }
+            EngineOrModuleTypeIndex::RecGroup(_) => unreachable!(),
+        }
+    }
+
     pub(crate) fn from_shared_type_index(engine: &Engine, index: VMSharedTypeIndex) -> FuncType {
         let ty = RegisteredType::root(engine, index).expect(
             "VMSharedTypeIndex is not registered in the Engine! Wrong \
@@ -2884,6 +3196,38 @@ impl MemoryType {
     }
 }
 
+// Tag Types
+
+/// A descriptor for an exception tag in a WebAssembly module.
+///
+/// A tag's type is a function type whose parameters are the types of the
+/// payload carried by exceptions thrown with the tag, and which has no results.
+#[derive(Debug, Clone)]
+pub struct TagType {
+    ty: FuncType,
+}
+
+impl TagType {
+    /// Creates a new tag descriptor whose payload is described by the
+    /// parameters of `ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` has any results.
+    pub fn new(ty: FuncType) -> TagType {
+        assert!(
+            ty.results().len() == 0,
+            "the function type of a tag must not have any results"
+        );
+        TagType { ty }
+    }
+
+    /// Returns the function type describing this tag's payload.
+    pub fn ty(&self) -> &FuncType {
+        &self.ty
+    }
+}
+
 // Import Types
 
 /// A descriptor for an imported value into a wasm module.
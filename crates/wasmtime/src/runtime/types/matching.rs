@@ -58,8 +58,28 @@ impl MatchCx<'_> {
                 }
                 _ => bail!("expected func, but found {}", actual.desc()),
             },
-            EntityType::Tag(_) => unimplemented!(),
+            EntityType::Tag(expected) => match actual {
+                DefinitionType::Tag(actual) => {
+                    self.tag_type(expected.signature.unwrap_engine_type_index(), *actual)
+                }
+                _ => bail!("expected tag, but found {}", actual.desc()),
+            },
+        }
+    }
+
+    fn tag_type(&self, expected: VMSharedTypeIndex, actual: VMSharedTypeIndex) -> Result<()> {
+        // Exception payloads flow in both directions, so tag types must be
+        // equal rather than subtypes of one another.
+        if expected == actual {
+            return Ok(());
         }
+        let expected = self.engine.signatures().borrow(expected).unwrap();
+        let actual = self.engine.signatures().borrow(actual).unwrap();
+        Err(concrete_type_mismatch(
+            "tag types incompatible",
+            &expected,
+            &actual,
+        ))
     }
 }
 
@@ -99,7 +119,22 @@ pub fn entity_ty(
             }
             _ => bail!("expected func found {}", entity_desc(actual)),
         },
-        EntityType::Tag(_) => unimplemented!(),
+        EntityType::Tag(expected) => match actual {
+            EntityType::Tag(actual) => {
+                let expected = &expected_types[expected.signature.unwrap_module_type_index()];
+                let actual = &actual_types[actual.signature.unwrap_module_type_index()];
+                if expected == actual {
+                    Ok(())
+                } else {
+                    Err(concrete_type_mismatch(
+                        "tag types incompatible",
+                        expected,
+                        actual,
+                    ))
+                }
+            }
+            _ => bail!("expected tag found {}", entity_desc(actual)),
+        },
     }
 }
 
@@ -182,6 +217,7 @@ fn match_heap(expected: WasmHeapType, actual: WasmHeapType, desc: &str) -> Resul
         (H::ConcreteArray(actual), H::ConcreteArray(expected)) => actual == expected,
         (H::ConcreteFunc(actual), H::ConcreteFunc(expected)) => actual == expected,
         (H::ConcreteStruct(actual), H::ConcreteStruct(expected)) => actual == expected,
+        (H::ConcreteCont(actual), H::ConcreteCont(expected)) => actual == expected,
 
         (H::NoFunc, H::NoFunc) => true,
         (_, H::NoFunc) => false,
@@ -198,6 +234,21 @@ fn match_heap(expected: WasmHeapType, actual: WasmHeapType, desc: &str) -> Resul
         (H::NoExtern, H::NoExtern) => true,
         (_, H::NoExtern) => false,
 
+        (H::Exn | H::NoExn, H::Exn) => true,
+        (_, H::Exn) => false,
+
+        (H::NoExn, H::NoExn) => true,
+        (_, H::NoExn) => false,
+
+        (H::NoCont, H::ConcreteCont(_)) => true,
+        (_, H::ConcreteCont(_)) => false,
+
+        (H::Cont | H::ConcreteCont(_) | H::NoCont, H::Cont) => true,
+        (_, H::Cont) => false,
+
+        (H::NoCont, H::NoCont) => true,
+        (_, H::NoCont) => false,
+
         (
             H::Any
             | H::Eq
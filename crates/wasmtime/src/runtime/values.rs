@@ -1,3 +1,6 @@
+use crate::runtime::exception::ExceptionObject;
+#[cfg(feature = "stack-switching")]
+use crate::runtime::stack_switching::ContinuationHandle;
 use crate::runtime::vm::TableElement;
 use crate::store::{AutoAssertNoGc, StoreOpaque};
 use crate::{
@@ -280,9 +283,16 @@ impl Val {
 
                     HeapType::NoFunc => Ref::Func(None),
 
-                    HeapType::Extern => ExternRef::_from_raw(store, raw.get_externref()).into(),
+                    // Exception and continuation references are represented as
+                    // `externref`s on the host.
+                    HeapType::Extern
+                    | HeapType::Exn
+                    | HeapType::Cont
+                    | HeapType::ConcreteCont(_) => {
+                        ExternRef::_from_raw(store, raw.get_externref()).into()
+                    }
 
-                    HeapType::NoExtern => Ref::Extern(None),
+                    HeapType::NoExtern | HeapType::NoExn | HeapType::NoCont => Ref::Extern(None),
 
                     HeapType::Any
                     | HeapType::Eq
@@ -727,7 +737,7 @@ impl Ref {
     pub fn null(heap_type: &HeapType) -> Self {
         match heap_type.top() {
             HeapType::Any => Ref::Any(None),
-            HeapType::Extern => Ref::Extern(None),
+            HeapType::Extern | HeapType::Exn | HeapType::Cont => Ref::Extern(None),
             HeapType::Func => Ref::Func(None),
             ty => unreachable!("not a heap type: {ty:?}"),
         }
@@ -871,6 +881,11 @@ impl Ref {
             // `HeapType::Func`.
             match self {
                 Ref::Extern(None) => HeapType::NoExtern,
+                Ref::Extern(Some(e)) if e._host_data_is::<ExceptionObject>(store)? => HeapType::Exn,
+                #[cfg(feature = "stack-switching")]
+                Ref::Extern(Some(e)) if e._host_data_is::<ContinuationHandle>(store)? => {
+                    HeapType::Cont
+                }
                 Ref::Extern(Some(_)) => HeapType::Extern,
 
                 Ref::Func(None) => HeapType::NoFunc,
@@ -902,6 +917,19 @@ impl Ref {
         Ok(match (self, ty.heap_type()) {
             (Ref::Extern(_), HeapType::Extern) => true,
             (Ref::Extern(None), HeapType::NoExtern) => true,
+            (Ref::Extern(None), HeapType::Exn | HeapType::NoExn) => true,
+            (Ref::Extern(Some(e)), HeapType::Exn) => e._host_data_is::<ExceptionObject>(store)?,
+            (Ref::Extern(None), HeapType::Cont | HeapType::ConcreteCont(_) | HeapType::NoCont) => {
+                true
+            }
+            #[cfg(feature = "stack-switching")]
+            (Ref::Extern(Some(e)), HeapType::Cont) => {
+                e._host_data_is::<ContinuationHandle>(store)?
+            }
+            #[cfg(feature = "stack-switching")]
+            (Ref::Extern(Some(e)), HeapType::ConcreteCont(ty)) => {
+                crate::runtime::stack_switching::matches_ty(store, e, ty)?
+            }
             (Ref::Extern(_), _) => false,
 
             (Ref::Func(_), HeapType::Func) => true,
@@ -986,7 +1014,7 @@ impl Ref {
                 Ok(TableElement::FuncRef(Some(f.vm_func_ref(&mut store))))
             }
 
-            (Ref::Extern(e), HeapType::Extern) => match e {
+            (Ref::Extern(e), HeapType::Extern | HeapType::Exn | HeapType::Cont) => match e {
                 None => {
                     assert!(ty.is_nullable());
                     Ok(TableElement::GcRef(None))
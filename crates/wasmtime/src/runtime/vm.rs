@@ -69,8 +69,8 @@ pub use crate::runtime::vm::instance::{
 };
 #[cfg(feature = "pooling-allocator")]
 pub use crate::runtime::vm::instance::{
-    InstanceLimits, PoolConcurrencyLimitError, PoolingInstanceAllocator,
-    PoolingInstanceAllocatorConfig,
+    InstanceLimits, PoolConcurrencyLimitError, PoolMetrics, PoolingAllocationFailures,
+    PoolingAllocatorMetrics, PoolingInstanceAllocator, PoolingInstanceAllocatorConfig,
 };
 pub use crate::runtime::vm::interpreter::*;
 pub use crate::runtime::vm::memory::{
@@ -186,6 +186,19 @@ pub unsafe trait VMStore {
     /// as a trap to clean up Wasm execution.
     fn maybe_async_gc(&mut self, root: Option<VMGcRef>) -> Result<Option<VMGcRef>>;
 
+    /// Callback invoked when an allocation of `bytes_needed` bytes failed even
+    /// after a GC, to grow the GC heap, if the store's resource limiter allows
+    /// it.
+    ///
+    /// Returns whether the heap was grown. Errors from the resource limiter
+    /// should be raised as a trap.
+    fn maybe_grow_gc_heap(&mut self, bytes_needed: u64) -> Result<bool>;
+
+    /// Callback invoked to allow the store's resource limiter to reject the
+    /// allocation of a stack for a continuation.
+    #[cfg(feature = "stack-switching")]
+    fn continuation_stack_allocating(&mut self, size: usize) -> Result<()>;
+
     /// Metadata required for resources for the component model.
     #[cfg(feature = "component-model")]
     fn component_calls(&mut self) -> &mut component::CallContexts;
@@ -72,14 +72,25 @@ pub struct ResourceTables<'a> {
 
 /// An individual slab of resources used for a single table within a component.
 /// Not much fancier than a general slab data structure.
-#[derive(Default)]
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "record-replay",
+    derive(serde_derive::Serialize, serde_derive::Deserialize)
+)]
 pub struct ResourceTable {
     /// Next slot to allocate, or `self.slots.len()` if they're all full.
     next: u32,
     /// Runtime state of all slots.
     slots: Vec<Slot>,
+    /// Number of slots which are not `Slot::Free`.
+    len: usize,
 }
 
+#[derive(Clone, PartialEq)]
+#[cfg_attr(
+    feature = "record-replay",
+    derive(serde_derive::Serialize, serde_derive::Deserialize)
+)]
 enum Slot {
     /// This slot is free and points to the next free slot, forming a linked
     /// list of free slots.
@@ -102,9 +113,28 @@ enum Slot {
 /// This is created once per `Store` and updated and modified throughout the
 /// lifetime of the store. This primarily tracks borrow counts and what slots
 /// should be updated when calls go out of scope.
-#[derive(Default)]
 pub struct CallContexts {
     scopes: Vec<CallContext>,
+    /// The maximum number of handles any single table may hold, as configured
+    /// by the store's resource limiter.
+    handle_limit: usize,
+}
+
+impl Default for CallContexts {
+    fn default() -> CallContexts {
+        CallContexts {
+            scopes: Vec::new(),
+            handle_limit: crate::DEFAULT_RESOURCE_HANDLE_LIMIT,
+        }
+    }
+}
+
+impl CallContexts {
+    /// Sets the maximum number of handles that any single resource table may
+    /// hold.
+    pub fn set_handle_limit(&mut self, limit: usize) {
+        self.handle_limit = limit;
+    }
 }
 
 #[derive(Default)]
@@ -131,7 +161,9 @@ impl ResourceTables<'_> {
     ///
     /// Note that this is the same as `resource_lower_own`.
     pub fn resource_new(&mut self, ty: Option<TypeResourceTableIndex>, rep: u32) -> Result<u32> {
-        self.table(ty).insert(Slot::Own { rep, lend_count: 0 })
+        let limit = self.calls.handle_limit;
+        self.table(ty)
+            .insert(Slot::Own { rep, lend_count: 0 }, limit)
     }
 
     /// Implementation of the `resource.rep` canonical intrinsic.
@@ -183,7 +215,9 @@ impl ResourceTables<'_> {
         ty: Option<TypeResourceTableIndex>,
         rep: u32,
     ) -> Result<u32> {
-        self.table(ty).insert(Slot::Own { rep, lend_count: 0 })
+        let limit = self.calls.handle_limit;
+        self.table(ty)
+            .insert(Slot::Own { rep, lend_count: 0 }, limit)
     }
 
     /// Attempts to remove an "own" handle from the specified table and its
@@ -254,7 +288,8 @@ impl ResourceTables<'_> {
         let scope = self.calls.scopes.len() - 1;
         let borrow_count = &mut self.calls.scopes.last_mut().unwrap().borrow_count;
         *borrow_count = borrow_count.checked_add(1).unwrap();
-        self.table(ty).insert(Slot::Borrow { rep, scope })
+        let limit = self.calls.handle_limit;
+        self.table(ty).insert(Slot::Borrow { rep, scope }, limit)
     }
 
     /// Enters a new calling context, starting a fresh count of borrows and
@@ -291,7 +326,18 @@ impl ResourceTables<'_> {
 }
 
 impl ResourceTable {
-    fn insert(&mut self, new: Slot) -> Result<u32> {
+    /// Returns the number of handles currently held in this table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    fn insert(&mut self, new: Slot, limit: usize) -> Result<u32> {
+        if self.len >= limit {
+            bail!(
+                "resource limit exceeded: resource handle count too high at {}",
+                self.len + 1
+            );
+        }
         let next = self.next as usize;
         if next == self.slots.len() {
             self.slots.push(Slot::Free {
@@ -303,6 +349,7 @@ impl ResourceTable {
             Slot::Free { next } => next,
             _ => unreachable!(),
         };
+        self.len += 1;
 
         // The component model reserves index 0 as never allocatable so add one
         // to the table index to start the numbering at 1 instead. Also note
@@ -346,6 +393,7 @@ impl ResourceTable {
         let to_fill = Slot::Free { next: self.next };
         let ret = mem::replace(self.get_mut(idx)?, to_fill);
         self.next = idx - 1;
+        self.len -= 1;
         Ok(ret)
     }
 }
@@ -717,6 +717,12 @@ impl MemoryImageSlot {
         self.image.is_some()
     }
 
+    /// The size of the prefix of this slot that is readable and writable.
+    #[allow(dead_code)] // ignore warnings as this is only used in some cfgs
+    pub(crate) fn accessible(&self) -> HostAlignedByteCount {
+        self.accessible
+    }
+
     #[allow(dead_code)] // ignore warnings as this is only used in some cfgs
     pub(crate) fn is_dirty(&self) -> bool {
         self.dirty
@@ -2,7 +2,7 @@ use crate::runtime::vm::vmcontext::{
     VMContext, VMFuncRef, VMGlobalDefinition, VMMemoryDefinition, VMTableDefinition,
 };
 use core::ptr::NonNull;
-use wasmtime_environ::{DefinedMemoryIndex, Global, Memory, Table};
+use wasmtime_environ::{DefinedMemoryIndex, Global, Memory, Table, VMSharedTypeIndex};
 
 /// The value of an export passed from one instance to another.
 pub enum Export {
@@ -17,6 +17,9 @@ pub enum Export {
 
     /// A global export value.
     Global(ExportGlobal),
+
+    /// A tag export value.
+    Tag(ExportTag),
 }
 
 /// A function export value.
@@ -106,3 +109,21 @@ impl From<ExportGlobal> for Export {
         Export::Global(func)
     }
 }
+
+/// A tag export value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportTag {
+    /// The index of this tag within its store.
+    ///
+    /// This is what identifies the tag: two exceptions have the same tag if
+    /// and only if their tags have the same index.
+    pub index: usize,
+    /// The signature of the tag's payload, used for compatibility checking.
+    pub signature: VMSharedTypeIndex,
+}
+
+impl From<ExportTag> for Export {
+    fn from(tag: ExportTag) -> Export {
+        Export::Tag(tag)
+    }
+}
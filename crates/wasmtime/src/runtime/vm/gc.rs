@@ -156,11 +156,39 @@ impl GcStore {
         &mut self,
         value: Box<dyn Any + Send + Sync>,
     ) -> Result<Result<VMExternRef, Box<dyn Any + Send + Sync>>> {
-        let host_data_id = self.host_data_table.alloc(value);
-        match self.gc_heap.alloc_externref(host_data_id)? {
+        Ok(self
+            .alloc_externref_with_gc_refs(value, Box::new([]))?
+            .map_err(|(value, _)| value))
+    }
+
+    /// Allocate a new `externref` whose host data owns the given GC
+    /// references, keeping them alive until the `externref` is collected.
+    ///
+    /// This is like [`GcStore::alloc_externref`] except that both `value` and
+    /// `gc_refs` are returned as the error payload when the allocation may be
+    /// retried after a GC.
+    pub fn alloc_externref_with_gc_refs(
+        &mut self,
+        value: Box<dyn Any + Send + Sync>,
+        gc_refs: Box<[VMGcRef]>,
+    ) -> Result<Result<VMExternRef, HostData>> {
+        let host_data_id = self.host_data_table.alloc_with_gc_refs(value, gc_refs);
+        let error = match self.gc_heap.alloc_externref(host_data_id) {
             #[cfg_attr(not(feature = "gc"), allow(unreachable_patterns))]
-            Some(x) => Ok(Ok(x)),
-            None => Ok(Err(self.host_data_table.dealloc(host_data_id))),
+            Ok(Some(x)) => return Ok(Ok(x)),
+            Ok(None) => None,
+            Err(e) => Some(e),
+        };
+        let mut gc_refs = Vec::new();
+        let value = self.host_data_table.dealloc(host_data_id, &mut gc_refs);
+        match error {
+            None => Ok(Err((value, gc_refs.into_boxed_slice()))),
+            Some(e) => {
+                for gc_ref in gc_refs {
+                    self.drop_gc_ref(gc_ref);
+                }
+                Err(e)
+            }
         }
     }
 
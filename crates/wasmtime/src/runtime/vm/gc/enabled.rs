@@ -4,6 +4,7 @@ mod arrayref;
 mod data;
 mod externref;
 mod free_list;
+mod heap_memory;
 mod structref;
 
 pub use arrayref::*;
@@ -23,14 +24,6 @@ pub use null::*;
 
 use crate::runtime::vm::GcRuntime;
 
-/// The default GC heap capacity: 512KiB.
-#[cfg(not(miri))]
-const DEFAULT_GC_HEAP_CAPACITY: usize = 1 << 19;
-
-/// The default GC heap capacity for miri: 64KiB.
-#[cfg(miri)]
-const DEFAULT_GC_HEAP_CAPACITY: usize = 1 << 16;
-
 // Explicit methods with `#[allow]` to clearly indicate that truncation is
 // desired when used.
 #[allow(clippy::cast_possible_truncation)]
@@ -1,8 +1,5 @@
 //! The deferred reference-counting (DRC) collector.
 //!
-//! Warning: this ref-counting collector does not have a tracing cycle
-//! collector, and therefore cannot collect cycles between GC objects!
-//!
 //! For host VM code, we use plain reference counting, where cloning increments
 //! the reference count, and dropping decrements it. We can avoid many of the
 //! on-stack increment/decrement operations that typically plague the
@@ -37,18 +34,30 @@
 //! "borrowed" from the `VMGcRefActivationsTable` and the reference count from
 //! the table will be dropped at the next GC).
 //!
+//! Reference counting alone cannot reclaim cycles between GC objects, so when
+//! the heap is more than half full after a collection's sweep, we additionally
+//! run a backup cycle collector over the whole heap. This uses trial deletion:
+//! we subtract every reference from inside the heap from its referent's
+//! reference count, and whatever is left is a reference from outside the heap
+//! (the stack, a global, a table, or the host). Objects with such external
+//! references, and everything transitively reachable from them, are live; all
+//! other objects are only referenced by each other and are reclaimed
+//! together. Because this only relies on reference counts, it doesn't need to
+//! know where every root is.
+//!
 //! For more general information on deferred reference counting, see *An
 //! Examination of Deferred Reference Counting and Cycle Detection* by Quinane:
 //! <https://openresearch-repository.anu.edu.au/bitstream/1885/42030/2/hon-thesis.pdf>
 
-use super::free_list::FreeList;
+use super::free_list::{self, FreeList};
+use super::heap_memory::GcHeapMemory;
 use super::{VMArrayRef, VMGcObjectDataMut, VMStructRef};
+use crate::hash_map::HashMap;
 use crate::hash_set::HashSet;
 use crate::prelude::*;
 use crate::runtime::vm::{
-    mmap::AlignedLength, ExternRefHostDataId, ExternRefHostDataTable, GarbageCollection, GcHeap,
-    GcHeapObject, GcProgress, GcRootsIter, GcRuntime, Mmap, TypedGcRef, VMExternRef, VMGcHeader,
-    VMGcRef,
+    ExternRefHostDataId, ExternRefHostDataTable, GarbageCollection, GcHeap, GcHeapObject,
+    GcProgress, GcRootsIter, GcRuntime, TypedGcRef, VMExternRef, VMGcHeader, VMGcRef,
 };
 use core::ops::{Deref, DerefMut, Range};
 use core::{
@@ -56,16 +65,19 @@ use core::{
     any::Any,
     cell::UnsafeCell,
     mem,
-    num::NonZeroUsize,
+    num::{NonZeroU32, NonZeroUsize},
     ptr::{self, NonNull},
 };
 use wasmtime_environ::drc::DrcTypeLayouts;
-use wasmtime_environ::{GcArrayLayout, GcStructLayout, GcTypeLayouts, VMGcKind, VMSharedTypeIndex};
+use wasmtime_environ::{
+    GcArrayLayout, GcLayout, GcStructLayout, GcTypeLayouts, VMGcKind, VMSharedTypeIndex,
+    WasmCompositeInnerType, WasmStorageType,
+};
 
 /// The deferred reference-counting (DRC) collector.
 ///
-/// This reference-counting collector does not have a cycle collector, and so it
-/// will not be able to reclaim garbage cycles.
+/// Garbage cycles are reclaimed by a backup trial-deletion cycle collector
+/// that runs when the heap is filling up.
 ///
 /// This is not a moving collector; it doesn't have a nursery or do any
 /// compaction.
@@ -79,37 +91,53 @@ unsafe impl GcRuntime for DrcCollector {
         &self.layouts
     }
 
-    fn new_gc_heap(&self) -> Result<Box<dyn GcHeap>> {
-        let heap = DrcHeap::new()?;
+    fn new_gc_heap(&self, engine: &crate::Engine) -> Result<Box<dyn GcHeap>> {
+        let heap = DrcHeap::new(engine)?;
         Ok(Box::new(heap) as _)
     }
 }
 
 /// A deferred reference-counting (DRC) heap.
 struct DrcHeap {
+    engine: crate::EngineWeak,
     no_gc_count: u64,
     // NB: this box shouldn't be strictly necessary, but it makes upholding the
     // safety invariants of the `vmctx_gc_heap_data` more obviously correct.
     activations_table: Box<VMGcRefActivationsTable>,
-    heap: Mmap<AlignedLength>,
+    memory: GcHeapMemory,
     free_list: FreeList,
+
+    /// The start of every object currently allocated in this heap, so that the
+    /// cycle collector can enumerate them.
+    object_starts: ObjectStarts,
+
+    /// The total size of the objects currently allocated in this heap.
+    allocated_bytes: usize,
+
+    /// Where the GC references are inside objects of each struct and array
+    /// type that has been allocated in this heap.
+    trace_infos: HashMap<VMSharedTypeIndex, TraceInfo>,
+
+    /// A reusable worklist for `dec_ref_and_maybe_dealloc`.
+    dec_ref_stack: Vec<VMGcRef>,
 }
 
 impl DrcHeap {
     /// Construct a new, default DRC heap.
-    fn new() -> Result<Self> {
-        Self::with_capacity(super::DEFAULT_GC_HEAP_CAPACITY)
-    }
-
-    /// Create a new DRC heap with the given capacity.
-    fn with_capacity(capacity: usize) -> Result<Self> {
-        let heap = Mmap::with_at_least(capacity)?;
-        let free_list = FreeList::new(heap.len());
+    fn new(engine: &crate::Engine) -> Result<Self> {
+        let memory = GcHeapMemory::new(engine)?;
+        let free_list = FreeList::new(memory.len());
+        let object_starts = ObjectStarts::new(memory.len());
         Ok(Self {
+            engine: engine.weak(),
             no_gc_count: 0,
             activations_table: Box::new(VMGcRefActivationsTable::default()),
-            heap,
+            memory,
             free_list,
+            object_starts,
+            allocated_bytes: 0,
+            trace_infos: HashMap::new(),
+            dec_ref_stack: Vec::new(),
         })
     }
 
@@ -117,8 +145,122 @@ impl DrcHeap {
         let drc_ref = drc_ref(&gc_ref);
         let size = self.index(drc_ref).object_size();
         let layout = FreeList::layout(size);
-        self.free_list
-            .dealloc(gc_ref.as_heap_index().unwrap(), layout);
+        let index = gc_ref.as_heap_index().unwrap();
+        self.object_starts.remove(index);
+        self.allocated_bytes -= size;
+        self.free_list.dealloc(index, layout);
+    }
+
+    /// Record where the GC references are inside objects of the given type, if
+    /// it's a GC type and we haven't already.
+    fn insert_trace_info(&mut self, ty: VMSharedTypeIndex) {
+        if self.trace_infos.contains_key(&ty) {
+            return;
+        }
+
+        let engine = self
+            .engine
+            .upgrade()
+            .expect("the engine outlives its stores' GC heaps");
+        let is_gc_ref = |ty: &WasmStorageType| match ty {
+            WasmStorageType::Val(ty) => ty.is_vmgcref_type(),
+            WasmStorageType::I8 | WasmStorageType::I16 => false,
+        };
+        let Some(layout) = engine.signatures().layout(ty) else {
+            // Not a GC type, so there are never any objects to trace.
+            return;
+        };
+        let sub_ty = engine
+            .signatures()
+            .borrow(ty)
+            .expect("registered types are live in the engine");
+
+        let info = match (&sub_ty.composite_type.inner, layout) {
+            (WasmCompositeInnerType::Struct(s), GcLayout::Struct(layout)) => TraceInfo::Struct {
+                gc_ref_offsets: s
+                    .fields
+                    .iter()
+                    .zip(&layout.fields)
+                    .filter(|(field, _)| is_gc_ref(&field.element_type))
+                    .map(|(_, offset)| *offset)
+                    .collect(),
+            },
+            (WasmCompositeInnerType::Array(a), GcLayout::Array(layout)) => TraceInfo::Array {
+                gc_ref_elems: is_gc_ref(&a.0.element_type),
+                elems_offset: layout.base_size,
+            },
+            _ => unreachable!("GC layout does not match its type: {sub_ty}"),
+        };
+        self.trace_infos.insert(ty, info);
+    }
+
+    /// Like `trace_gc_ref`, but additionally pushes the GC references owned by
+    /// an `externref`'s host data.
+    fn trace_gc_ref_and_host_data(
+        &self,
+        host_data_table: &ExternRefHostDataTable,
+        gc_ref: &VMGcRef,
+        children: &mut Vec<VMGcRef>,
+    ) {
+        self.trace_gc_ref(gc_ref, children);
+        if let Some(externref) = gc_ref.as_typed::<VMDrcExternRef>(self) {
+            let host_data_id = self.index(externref).host_data;
+            children.extend(
+                host_data_table
+                    .gc_refs(host_data_id)
+                    .iter()
+                    .map(|r| r.unchecked_copy()),
+            );
+        }
+    }
+
+    /// Push the non-null, non-i31 GC references inside the given object onto
+    /// `children`.
+    fn trace_gc_ref(&self, gc_ref: &VMGcRef, children: &mut Vec<VMGcRef>) {
+        debug_assert!(!gc_ref.is_i31());
+
+        // Objects without a type, i.e. `externref`s, never contain GC
+        // references.
+        let Some(ty) = self.header(gc_ref).ty() else {
+            return;
+        };
+        let start = usize::try_from(gc_ref.as_heap_index().unwrap().get()).unwrap();
+        let mut push = |offset: u32| {
+            let offset = usize::try_from(offset).unwrap();
+            let bytes = &self.heap_slice()[start + offset..][..mem::size_of::<u32>()];
+            let raw = u32::from_le_bytes(core::array::from_fn(|i| {
+                // Safety: we have a shared borrow of the heap, so it isn't
+                // being mutated.
+                unsafe { *bytes[i].get() }
+            }));
+            if let Some(child) = VMGcRef::from_raw_u32(raw) {
+                if !child.is_i31() {
+                    children.push(child);
+                }
+            }
+        };
+
+        match &self.trace_infos[&ty] {
+            TraceInfo::Struct { gc_ref_offsets } => {
+                for offset in gc_ref_offsets.iter() {
+                    push(*offset);
+                }
+            }
+            TraceInfo::Array {
+                gc_ref_elems,
+                elems_offset,
+            } => {
+                if *gc_ref_elems {
+                    let len = self
+                        .index::<VMDrcArrayHeader>(gc_ref.as_typed_unchecked())
+                        .length;
+                    let elem_size = u32::try_from(mem::size_of::<u32>()).unwrap();
+                    for i in 0..len {
+                        push(elems_offset + i * elem_size);
+                    }
+                }
+            }
+        }
     }
 
     fn object_range(&self, gc_ref: &VMGcRef) -> Range<usize> {
@@ -181,27 +323,130 @@ impl DrcHeap {
 
     /// Decrement the ref count for the associated object.
     ///
-    /// If the ref count reached zero, then deallocate the object and remove its
-    /// associated entry from the `host_data_table` if necessary.
+    /// If the ref count reached zero, then deallocate the object, remove its
+    /// associated entry from the `host_data_table` if necessary, and decrement
+    /// the ref counts of the objects it references in turn.
     fn dec_ref_and_maybe_dealloc(
         &mut self,
         host_data_table: &mut ExternRefHostDataTable,
         gc_ref: &VMGcRef,
     ) {
-        if self.dec_ref(gc_ref) {
+        // Use an explicit worklist, rather than recursion, so that dropping
+        // a long linked list can't overflow the native stack.
+        let mut stack = mem::take(&mut self.dec_ref_stack);
+        debug_assert!(stack.is_empty());
+        stack.push(gc_ref.unchecked_copy());
+
+        while let Some(gc_ref) = stack.pop() {
+            if !self.dec_ref(&gc_ref) {
+                continue;
+            }
+
             // If this was an `externref`, remove its associated entry from
-            // the host data table.
+            // the host data table, and release the references it owned.
             if let Some(externref) = gc_ref.as_typed::<VMDrcExternRef>(self) {
                 let host_data_id = self.index(externref).host_data;
-                host_data_table.dealloc(host_data_id);
+                host_data_table.dealloc(host_data_id, &mut stack);
             }
 
-            // TODO: `dec_ref_and_maybe_dealloc` each `VMGcRef` inside this
-            // object.
+            // This object's references to other objects are going away, so
+            // decrement their ref counts as well.
+            self.trace_gc_ref(&gc_ref, &mut stack);
 
             // Deallocate this GC object.
-            self.dealloc(gc_ref.unchecked_copy());
+            self.dealloc(gc_ref);
+        }
+
+        self.dec_ref_stack = stack;
+    }
+
+    /// Whether to run the backup cycle collector at the end of this
+    /// collection.
+    ///
+    /// Tracing the whole heap is much more expensive than the rest of a DRC
+    /// collection, which only looks at the stack, so we only do it once the
+    /// heap is filling up.
+    fn should_collect_cycles(&self) -> bool {
+        self.allocated_bytes > self.memory.len() / 2
+    }
+
+    /// Reclaim garbage cycles via trial deletion.
+    ///
+    /// This must only be called after sweeping, so that the only ref counts
+    /// held on behalf of Wasm activations are for the precise set of on-stack
+    /// roots.
+    fn collect_cycles(&mut self, host_data_table: &mut ExternRefHostDataTable) {
+        let objects = self
+            .object_starts
+            .iter()
+            .map(|index| VMGcRef::from_heap_index(index).unwrap())
+            .collect::<Vec<_>>();
+
+        // Subtract each reference from inside the heap from its referent's ref
+        // count. Whatever remains are references from outside the heap.
+        let mut external_ref_counts = HashMap::with_capacity(objects.len());
+        for gc_ref in &objects {
+            let ref_count = *self.index_mut(drc_ref(gc_ref)).ref_count.get_mut();
+            external_ref_counts.insert(gc_ref.unchecked_copy(), ref_count);
+        }
+        let mut children = Vec::new();
+        for gc_ref in &objects {
+            self.trace_gc_ref_and_host_data(host_data_table, gc_ref, &mut children);
+            for child in children.drain(..) {
+                let count = external_ref_counts
+                    .get_mut(&child)
+                    .expect("GC references inside the heap point to allocated objects");
+                *count -= 1;
+            }
+        }
+
+        // Everything reachable from an externally-referenced object is live.
+        let mut worklist = external_ref_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(gc_ref, _)| gc_ref.unchecked_copy())
+            .collect::<Vec<_>>();
+        let mut live = worklist
+            .iter()
+            .map(|gc_ref| gc_ref.unchecked_copy())
+            .collect::<HashSet<_>>();
+        while let Some(gc_ref) = worklist.pop() {
+            self.trace_gc_ref_and_host_data(host_data_table, &gc_ref, &mut children);
+            for child in children.drain(..) {
+                if live.insert(child.unchecked_copy()) {
+                    worklist.push(child);
+                }
+            }
+        }
+
+        // Everything else is garbage that is only referenced by other
+        // garbage. Free it all at once, only decrementing the ref counts of
+        // live objects that garbage referenced.
+        let mut num_collected = 0;
+        for gc_ref in objects {
+            if live.contains(&gc_ref) {
+                continue;
+            }
+            num_collected += 1;
+
+            self.trace_gc_ref_and_host_data(host_data_table, &gc_ref, &mut children);
+            for child in children.drain(..) {
+                if live.contains(&child) {
+                    let needs_dealloc = self.dec_ref(&child);
+                    debug_assert!(!needs_dealloc, "live objects are externally referenced");
+                }
+            }
+
+            // The host data's references were already released along with
+            // the rest of this object's children above.
+            if let Some(externref) = gc_ref.as_typed::<VMDrcExternRef>(self) {
+                let host_data_id = self.index(externref).host_data;
+                host_data_table.dealloc(host_data_id, &mut children);
+                children.clear();
+            }
+            self.dealloc(gc_ref);
         }
+        log::trace!("Collected {num_collected} objects in garbage cycles");
     }
 
     fn trace(&mut self, roots: &mut GcRootsIter<'_>) {
@@ -508,7 +753,14 @@ unsafe impl GcHeap for DrcHeap {
     }
 
     fn expose_gc_ref_to_wasm(&mut self, gc_ref: VMGcRef) {
-        self.activations_table.insert_without_gc(gc_ref);
+        if let Some(gc_ref) = self.activations_table.insert_without_gc(gc_ref) {
+            // The table already holds this reference in its hash set, which
+            // only keeps one entry (and therefore releases one reference
+            // count) per object when swept. Release the duplicate's reference
+            // count now so that it doesn't leak.
+            let is_zero = self.dec_ref(&gc_ref);
+            debug_assert!(!is_zero);
+        }
     }
 
     fn need_gc_before_entering_wasm(&self, num_gc_refs: NonZeroUsize) -> bool {
@@ -544,6 +796,10 @@ unsafe impl GcHeap for DrcHeap {
         usize::try_from(size).unwrap()
     }
 
+    fn register_type(&mut self, ty: VMSharedTypeIndex) {
+        self.insert_trace_info(ty);
+    }
+
     fn alloc_raw(&mut self, mut header: VMGcHeader, layout: Layout) -> Result<Option<VMGcRef>> {
         debug_assert!(layout.size() >= core::mem::size_of::<VMDrcHeader>());
         debug_assert!(layout.align() >= core::mem::align_of::<VMDrcHeader>());
@@ -553,9 +809,20 @@ unsafe impl GcHeap for DrcHeap {
             return Err(crate::Trap::AllocationTooLarge.into());
         }
 
+        debug_assert!(
+            header
+                .ty()
+                .map_or(true, |ty| self.trace_infos.contains_key(&ty)),
+            "objects are only allocated with registered types"
+        );
+
         let gc_ref = match self.free_list.alloc(layout)? {
             None => return Ok(None),
-            Some(index) => VMGcRef::from_heap_index(index).unwrap(),
+            Some(index) => {
+                self.object_starts.insert(index);
+                self.allocated_bytes += layout.size();
+                VMGcRef::from_heap_index(index).unwrap()
+            }
         };
 
         debug_assert_eq!(header.reserved_u27(), 0);
@@ -675,27 +942,46 @@ unsafe impl GcHeap for DrcHeap {
     #[cfg(feature = "pooling-allocator")]
     fn reset(&mut self) {
         let DrcHeap {
+            engine: _,
             no_gc_count,
             activations_table,
             free_list,
-            heap: _,
+            memory,
+            object_starts,
+            allocated_bytes,
+            trace_infos,
+            dec_ref_stack,
         } = self;
 
         *no_gc_count = 0;
-        free_list.reset();
+        memory.reset();
+        *free_list = FreeList::new(memory.len());
         activations_table.reset();
+        object_starts.clear();
+        *allocated_bytes = 0;
+        // Type indices may be reused for other types once this heap's store's
+        // types are unregistered.
+        trace_infos.clear();
+        debug_assert!(dec_ref_stack.is_empty());
     }
 
     fn heap_slice(&self) -> &[UnsafeCell<u8>] {
-        let ptr = self.heap.as_ptr().cast();
-        let len = self.heap.len();
-        unsafe { core::slice::from_raw_parts(ptr, len) }
+        self.memory.slice()
     }
 
     fn heap_slice_mut(&mut self) -> &mut [u8] {
-        let ptr = self.heap.as_mut_ptr();
-        let len = self.heap.len();
-        unsafe { core::slice::from_raw_parts_mut(ptr, len) }
+        self.memory.slice_mut()
+    }
+
+    fn max_heap_size(&self) -> usize {
+        self.memory.max_len()
+    }
+
+    fn grow(&mut self, new_size: usize) -> Result<()> {
+        self.memory.grow(new_size)?;
+        self.free_list.add_capacity(self.memory.len());
+        self.object_starts.grow(self.memory.len());
+        Ok(())
     }
 }
 
@@ -709,6 +995,7 @@ struct DrcCollection<'a> {
 enum DrcCollectionPhase {
     Trace,
     Sweep,
+    CollectCycles,
     Done,
 }
 
@@ -726,6 +1013,15 @@ impl<'a> GarbageCollection<'a> for DrcCollection<'a> {
                 log::trace!("Begin DRC sweep");
                 self.heap.sweep(self.host_data_table);
                 log::trace!("End DRC sweep");
+                self.phase = DrcCollectionPhase::CollectCycles;
+                GcProgress::Continue
+            }
+            DrcCollectionPhase::CollectCycles => {
+                if self.heap.should_collect_cycles() {
+                    log::trace!("Begin DRC cycle collection");
+                    self.heap.collect_cycles(self.host_data_table);
+                    log::trace!("End DRC cycle collection");
+                }
                 self.phase = DrcCollectionPhase::Done;
                 GcProgress::Complete
             }
@@ -734,6 +1030,92 @@ impl<'a> GarbageCollection<'a> for DrcCollection<'a> {
     }
 }
 
+/// Where the GC references are inside objects of a particular type.
+enum TraceInfo {
+    /// A struct type with GC references at the given offsets.
+    Struct { gc_ref_offsets: Box<[u32]> },
+
+    /// An array type, whose elements start at `elems_offset` and are GC
+    /// references if `gc_ref_elems` is set.
+    Array {
+        gc_ref_elems: bool,
+        elems_offset: u32,
+    },
+}
+
+/// A bitmap of which heap indices are the start of an allocated object.
+///
+/// Every object is aligned to the free list's alignment, so there is one bit
+/// per aligned index.
+struct ObjectStarts {
+    words: Vec<u64>,
+}
+
+impl ObjectStarts {
+    const BITS_PER_WORD: u32 = u64::BITS;
+
+    fn new(heap_len: usize) -> Self {
+        ObjectStarts {
+            words: vec![0; Self::num_words(heap_len)],
+        }
+    }
+
+    fn num_words(heap_len: usize) -> usize {
+        let align = usize::try_from(free_list::ALIGN_U32).unwrap();
+        let bits = heap_len.div_ceil(align);
+        let bits_per_word = usize::try_from(Self::BITS_PER_WORD).unwrap();
+        bits.div_ceil(bits_per_word)
+    }
+
+    /// Make room for the indices of a heap that has grown to `heap_len` bytes.
+    fn grow(&mut self, heap_len: usize) {
+        let num_words = Self::num_words(heap_len);
+        if num_words > self.words.len() {
+            self.words.resize(num_words, 0);
+        }
+    }
+
+    fn word_and_bit(index: NonZeroU32) -> (usize, u32) {
+        debug_assert_eq!(index.get() % free_list::ALIGN_U32, 0);
+        let bit = index.get() / free_list::ALIGN_U32;
+        let word = usize::try_from(bit / Self::BITS_PER_WORD).unwrap();
+        (word, bit % Self::BITS_PER_WORD)
+    }
+
+    fn insert(&mut self, index: NonZeroU32) {
+        let (word, bit) = Self::word_and_bit(index);
+        debug_assert_eq!(self.words[word] & (1 << bit), 0);
+        self.words[word] |= 1 << bit;
+    }
+
+    fn remove(&mut self, index: NonZeroU32) {
+        let (word, bit) = Self::word_and_bit(index);
+        debug_assert_ne!(self.words[word] & (1 << bit), 0);
+        self.words[word] &= !(1 << bit);
+    }
+
+    #[cfg(feature = "pooling-allocator")]
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = NonZeroU32> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros();
+                word &= word - 1;
+                let i = u32::try_from(i).unwrap();
+                let index = (i * Self::BITS_PER_WORD + bit) * free_list::ALIGN_U32;
+                Some(NonZeroU32::new(index).unwrap())
+            })
+        })
+    }
+}
+
 /// The type of `VMGcRefActivationsTable`'s bump region's elements.
 ///
 /// These are written to by Wasm.
@@ -868,6 +1250,13 @@ impl VMGcRefActivationsTable {
             precise_stack_roots,
         } = self;
 
+        // The heap these references pointed into is being reset as well, so
+        // they are dropped without decrementing their reference counts. Slots
+        // past the bump finger must be empty for the next store to use this
+        // table.
+        for slot in alloc.chunk.iter_mut() {
+            *slot.get_mut() = 0;
+        }
         alloc.reset();
         over_approximated_stack_roots.clear();
         precise_stack_roots.clear();
@@ -914,16 +1303,25 @@ impl VMGcRefActivationsTable {
     }
 
     /// Insert a reference into the table, without ever performing GC.
+    ///
+    /// Returns the given reference back if it was already present in the
+    /// table's hash set, in which case the table did not take ownership of it.
     #[inline]
-    fn insert_without_gc(&mut self, gc_ref: VMGcRef) {
-        if let Err(gc_ref) = self.try_insert(gc_ref) {
-            self.insert_slow_without_gc(gc_ref);
+    fn insert_without_gc(&mut self, gc_ref: VMGcRef) -> Option<VMGcRef> {
+        match self.try_insert(gc_ref) {
+            Ok(()) => None,
+            Err(gc_ref) => self.insert_slow_without_gc(gc_ref),
         }
     }
 
     #[inline(never)]
-    fn insert_slow_without_gc(&mut self, gc_ref: VMGcRef) {
-        self.over_approximated_stack_roots.insert(gc_ref);
+    fn insert_slow_without_gc(&mut self, gc_ref: VMGcRef) -> Option<VMGcRef> {
+        if self.over_approximated_stack_roots.contains(&gc_ref) {
+            Some(gc_ref)
+        } else {
+            self.over_approximated_stack_roots.insert(gc_ref);
+            None
+        }
     }
 
     fn num_filled_in_bump_chunk(&self) -> usize {
@@ -14,7 +14,7 @@ pub(crate) struct FreeList {
 
 /// Our minimum and maximum supported alignment. Every allocation is aligned to
 /// this.
-const ALIGN_U32: u32 = 8;
+pub(crate) const ALIGN_U32: u32 = 8;
 const ALIGN_USIZE: usize = ALIGN_U32 as usize;
 
 /// Our minimum allocation size.
@@ -257,12 +257,17 @@ impl FreeList {
         }
     }
 
-    /// Reset this free list, making the whole range available for allocation.
-    pub fn reset(&mut self) {
-        let end = u32::try_from(self.capacity).unwrap_or_else(|_| {
+    /// The end of the range that this free list may allocate from.
+    fn end(&self) -> u32 {
+        u32::try_from(self.capacity).unwrap_or_else(|_| {
             assert!(self.capacity > usize::try_from(u32::MAX).unwrap());
             u32::MAX
-        });
+        })
+    }
+
+    /// Reset this free list, making the whole range available for allocation.
+    pub fn reset(&mut self) {
+        let end = self.end();
 
         // Don't start at `0`. Reserve that for "null pointers" and this way we
         // can use `NonZeroU32` as out pointer type, giving us some more
@@ -280,6 +285,41 @@ impl FreeList {
         self.free_block_index_to_len.clear();
         self.free_block_index_to_len.extend(entire_range);
     }
+
+    /// Grow the contiguous region of memory we are managing to `new_capacity`
+    /// bytes, making the new space at its end available for allocation.
+    pub fn add_capacity(&mut self, new_capacity: usize) {
+        debug_assert!(new_capacity >= self.capacity);
+
+        // The old range, if it was large enough to be used at all, ended at
+        // the old capacity rounded down to our alignment.
+        let old_end = round_u32_down_to_pow2(self.end(), ALIGN_U32);
+        let start = if old_end.saturating_sub(ALIGN_U32) >= MIN_BLOCK_SIZE {
+            old_end
+        } else {
+            ALIGN_U32
+        };
+
+        self.capacity = new_capacity;
+        let new_end = round_u32_down_to_pow2(self.end(), ALIGN_U32);
+        let len = new_end.saturating_sub(start);
+        if len < MIN_BLOCK_SIZE {
+            return;
+        }
+
+        // Only merge the new block into the last free block if they are
+        // exactly adjacent: any gap between them is occupied by the last
+        // allocated object.
+        match self.free_block_index_to_len.iter_mut().next_back() {
+            Some((index, block_len)) if *index + *block_len == start => *block_len += len,
+            _ => {
+                self.free_block_index_to_len.insert(start, len);
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        self.check_integrity();
+    }
 }
 
 #[inline]
@@ -734,6 +774,36 @@ mod tests {
         let _ = (b, d);
     }
 
+    #[test]
+    fn add_capacity() {
+        let layout =
+            Layout::from_size_align(usize::try_from(MIN_BLOCK_SIZE).unwrap(), ALIGN_USIZE).unwrap();
+
+        // Free list with room for exactly 2 min-sized blocks.
+        let mut free_list =
+            FreeList::new(ALIGN_USIZE + usize::try_from(MIN_BLOCK_SIZE).unwrap() * 2);
+        let a = free_list.alloc(layout).unwrap().unwrap();
+        free_list.alloc(layout).unwrap().unwrap();
+        assert!(free_list.alloc(layout).unwrap().is_none());
+
+        // Growing makes room for 2 more blocks.
+        free_list.add_capacity(ALIGN_USIZE + usize::try_from(MIN_BLOCK_SIZE).unwrap() * 4);
+        assert_eq!(free_list.free_block_index_to_len.len(), 1);
+        let c = free_list.alloc(layout).unwrap().unwrap();
+        assert_eq!(c.get(), ALIGN_U32 + MIN_BLOCK_SIZE * 2);
+
+        // The new space is merged with a free block that ends right where it
+        // starts.
+        free_list.dealloc(a, layout);
+        free_list.alloc(layout).unwrap().unwrap();
+        free_list.add_capacity(ALIGN_USIZE + usize::try_from(MIN_BLOCK_SIZE).unwrap() * 6);
+        assert_eq!(free_list.free_block_index_to_len.len(), 1);
+        assert_eq!(
+            free_list.free_block_index_to_len.values().next(),
+            Some(&(MIN_BLOCK_SIZE * 3))
+        );
+    }
+
     #[test]
     fn alloc_size_too_large() {
         // Free list with room for 10 min-sized blocks.
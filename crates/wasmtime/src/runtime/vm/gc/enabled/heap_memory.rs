@@ -0,0 +1,98 @@
+//! The memory backing a GC heap.
+
+use crate::prelude::*;
+use crate::runtime::vm::{mmap::AlignedLength, HostAlignedByteCount, Mmap};
+use core::cell::UnsafeCell;
+
+/// The memory backing a GC heap.
+///
+/// This is a virtual memory reservation of the heap's maximum size, of which
+/// only a prefix is accessible and part of the heap. Growing the heap makes
+/// more of the reservation accessible, so the heap never moves and compiled
+/// Wasm code only ever needs to observe a new bound.
+pub(crate) struct GcHeapMemory {
+    /// The reservation for the whole heap, up to its maximum size.
+    mmap: Mmap<AlignedLength>,
+
+    /// The size of the prefix of `mmap` that has been made accessible.
+    ///
+    /// This only ever increases, even when `size` is reset, so that a heap
+    /// being reused by the pooling allocator does not need to remap memory to
+    /// grow again.
+    accessible: HostAlignedByteCount,
+
+    /// The current size of the heap.
+    size: HostAlignedByteCount,
+
+    /// The size of the heap upon creation.
+    #[cfg(feature = "pooling-allocator")]
+    initial_size: HostAlignedByteCount,
+}
+
+impl GcHeapMemory {
+    /// Create a new GC heap memory sized according to the engine's
+    /// `gc_heap_initial_size` and `gc_heap_maximum_size` configuration.
+    pub fn new(engine: &crate::Engine) -> Result<Self> {
+        let config = engine.config();
+        let initial_size = HostAlignedByteCount::new_rounded_up(config.gc_heap_initial_size)?;
+        let maximum_size = HostAlignedByteCount::new_rounded_up(config.gc_heap_maximum_size)?;
+        let mmap = Mmap::accessible_reserved(initial_size, maximum_size)?;
+        Ok(Self {
+            mmap,
+            accessible: initial_size,
+            size: initial_size,
+            #[cfg(feature = "pooling-allocator")]
+            initial_size,
+        })
+    }
+
+    /// The current size of the heap, in bytes.
+    pub fn len(&self) -> usize {
+        self.size.byte_count()
+    }
+
+    /// The maximum size the heap may grow to, in bytes.
+    pub fn max_len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Grow the heap to at least `new_size` bytes.
+    pub fn grow(&mut self, new_size: usize) -> Result<()> {
+        let new_size = HostAlignedByteCount::new_rounded_up(new_size)?;
+        ensure!(
+            new_size.byte_count() <= self.max_len(),
+            "cannot grow GC heap to {new_size:#x} bytes; its maximum size is {:#x} bytes",
+            self.max_len()
+        );
+        if new_size > self.accessible {
+            // Safety: the region past `self.accessible` is not part of the heap
+            // yet, so nothing else references it.
+            unsafe {
+                self.mmap
+                    .make_accessible(self.accessible, new_size.checked_sub(self.accessible)?)?;
+            }
+            self.accessible = new_size;
+        }
+        self.size = self.size.max(new_size);
+        Ok(())
+    }
+
+    /// Shrink the heap back to its initial size, retaining the accessible
+    /// memory for future growth.
+    #[cfg(feature = "pooling-allocator")]
+    pub fn reset(&mut self) {
+        self.size = self.initial_size;
+    }
+
+    /// Get a slice of the heap's bytes.
+    pub fn slice(&self) -> &[UnsafeCell<u8>] {
+        let ptr = self.mmap.as_ptr().cast();
+        unsafe { core::slice::from_raw_parts(ptr, self.len()) }
+    }
+
+    /// Get a mutable slice of the heap's bytes.
+    pub fn slice_mut(&mut self) -> &mut [u8] {
+        let ptr = self.mmap.as_mut_ptr();
+        unsafe { core::slice::from_raw_parts_mut(ptr, self.len()) }
+    }
+}
@@ -4,13 +4,13 @@
 //! which point it returns an out-of-memory error. It never collects garbage.
 //! It does not require any GC barriers.
 
+use super::heap_memory::GcHeapMemory;
 use super::*;
 use crate::{
     prelude::*,
     vm::{
-        mmap::AlignedLength, ExternRefHostDataId, ExternRefHostDataTable, GarbageCollection,
-        GcHeap, GcHeapObject, GcProgress, GcRootsIter, Mmap, SendSyncUnsafeCell, TypedGcRef,
-        VMGcHeader, VMGcRef,
+        ExternRefHostDataId, ExternRefHostDataTable, GarbageCollection, GcHeap, GcHeapObject,
+        GcProgress, GcRootsIter, SendSyncUnsafeCell, TypedGcRef, VMGcHeader, VMGcRef,
     },
     GcHeapOutOfMemory,
 };
@@ -37,8 +37,8 @@ unsafe impl GcRuntime for NullCollector {
         &self.layouts
     }
 
-    fn new_gc_heap(&self) -> Result<Box<dyn GcHeap>> {
-        let heap = NullHeap::new()?;
+    fn new_gc_heap(&self, engine: &crate::Engine) -> Result<Box<dyn GcHeap>> {
+        let heap = NullHeap::new(engine)?;
         Ok(Box::new(heap) as _)
     }
 }
@@ -46,7 +46,7 @@ unsafe impl GcRuntime for NullCollector {
 /// A GC heap for the null collector.
 #[repr(C)]
 struct NullHeap {
-    /// Bump-allocation finger indexing within `1..self.heap.len()`.
+    /// Bump-allocation finger indexing within `1..self.memory.len()`.
     ///
     /// NB: this is an `UnsafeCell` because it is written to by compiled Wasm
     /// code.
@@ -56,7 +56,7 @@ struct NullHeap {
     no_gc_count: usize,
 
     /// The actual GC heap.
-    heap: Mmap<AlignedLength>,
+    memory: GcHeapMemory,
 }
 
 /// The common header for all arrays in the null collector.
@@ -117,21 +117,17 @@ fn oom() -> Error {
 
 impl NullHeap {
     /// Construct a new, default heap for the null collector.
-    fn new() -> Result<Self> {
-        Self::with_capacity(super::DEFAULT_GC_HEAP_CAPACITY)
-    }
-
-    /// Create a new DRC heap with the given capacity.
-    fn with_capacity(capacity: usize) -> Result<Self> {
-        let heap = Mmap::with_at_least(capacity)?;
+    fn new(engine: &crate::Engine) -> Result<Self> {
         Ok(Self {
             no_gc_count: 0,
             next: SendSyncUnsafeCell::new(NonZeroU32::new(1).unwrap()),
-            heap,
+            memory: GcHeapMemory::new(engine)?,
         })
     }
 
-    fn alloc(&mut self, mut header: VMGcHeader, layout: Layout) -> Result<VMGcRef> {
+    /// Bump-allocate an object, returning `Ok(None)` if it doesn't fit in the
+    /// heap's current size so that the caller may grow the heap and retry.
+    fn alloc(&mut self, mut header: VMGcHeader, layout: Layout) -> Result<Option<VMGcRef>> {
         debug_assert!(layout.size() >= core::mem::size_of::<VMGcHeader>());
         debug_assert!(layout.align() >= core::mem::align_of::<VMGcHeader>());
 
@@ -164,11 +160,11 @@ impl NullHeap {
             Some(end) => end,
             None => return Err(oom()),
         };
-        if u32::try_from(self.heap.len())
+        if u32::try_from(self.memory.len())
             .ok()
             .map_or(true, |heap_len| end_of_object > heap_len)
         {
-            return Err(oom());
+            return Ok(None);
         }
 
         // Update the bump pointer, write the header, and return the GC ref.
@@ -181,7 +177,7 @@ impl NullHeap {
         header.set_reserved_u27(size);
         *self.header_mut(&gc_ref) = header;
 
-        Ok(gc_ref)
+        Ok(Some(gc_ref))
     }
 }
 
@@ -203,15 +199,20 @@ unsafe impl GcHeap for NullHeap {
     }
 
     fn heap_slice(&self) -> &[UnsafeCell<u8>] {
-        let ptr = self.heap.as_ptr().cast();
-        let len = self.heap.len();
-        unsafe { core::slice::from_raw_parts(ptr, len) }
+        self.memory.slice()
     }
 
     fn heap_slice_mut(&mut self) -> &mut [u8] {
-        let ptr = self.heap.as_mut_ptr();
-        let len = self.heap.len();
-        unsafe { core::slice::from_raw_parts_mut(ptr, len) }
+        self.memory.slice_mut()
+    }
+
+    fn max_heap_size(&self) -> usize {
+        self.memory.max_len()
+    }
+
+    fn grow(&mut self, new_size: usize) -> Result<()> {
+        // The bump region simply extends to the new end of the heap.
+        self.memory.grow(new_size)
     }
 
     fn clone_gc_ref(&mut self, gc_ref: &VMGcRef) -> VMGcRef {
@@ -237,7 +238,10 @@ unsafe impl GcHeap for NullHeap {
     }
 
     fn alloc_externref(&mut self, host_data: ExternRefHostDataId) -> Result<Option<VMExternRef>> {
-        let gc_ref = self.alloc(VMGcHeader::externref(), Layout::new::<VMNullExternRef>())?;
+        let Some(gc_ref) = self.alloc(VMGcHeader::externref(), Layout::new::<VMNullExternRef>())?
+        else {
+            return Ok(None);
+        };
         self.index_mut::<VMNullExternRef>(gc_ref.as_typed_unchecked())
             .host_data = host_data;
         Ok(Some(gc_ref.into_externref_unchecked()))
@@ -262,7 +266,7 @@ unsafe impl GcHeap for NullHeap {
     }
 
     fn alloc_raw(&mut self, header: VMGcHeader, layout: Layout) -> Result<Option<VMGcRef>> {
-        self.alloc(header, layout).map(Some)
+        self.alloc(header, layout)
     }
 
     fn alloc_uninit_struct(
@@ -274,7 +278,7 @@ unsafe impl GcHeap for NullHeap {
             VMGcHeader::from_kind_and_index(VMGcKind::StructRef, ty),
             layout.layout(),
         )?;
-        Ok(Some(gc_ref.into_structref_unchecked()))
+        Ok(gc_ref.map(|r| r.into_structref_unchecked()))
     }
 
     fn dealloc_uninit_struct(&mut self, _struct_ref: VMStructRef) {}
@@ -285,10 +289,13 @@ unsafe impl GcHeap for NullHeap {
         length: u32,
         layout: &GcArrayLayout,
     ) -> Result<Option<VMArrayRef>> {
-        let gc_ref = self.alloc(
+        let Some(gc_ref) = self.alloc(
             VMGcHeader::from_kind_and_index(VMGcKind::ArrayRef, ty),
             layout.layout(length),
-        )?;
+        )?
+        else {
+            return Ok(None);
+        };
         self.index_mut::<VMNullArrayHeader>(gc_ref.as_typed_unchecked())
             .length = length;
         Ok(Some(gc_ref.into_arrayref_unchecked()))
@@ -319,11 +326,12 @@ unsafe impl GcHeap for NullHeap {
         let NullHeap {
             next,
             no_gc_count,
-            heap: _,
+            memory,
         } = self;
 
         *next.get_mut() = NonZeroU32::new(1).unwrap();
         *no_gc_count = 0;
+        memory.reset();
     }
 }
 
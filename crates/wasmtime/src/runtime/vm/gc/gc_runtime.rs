@@ -36,8 +36,8 @@ pub unsafe trait GcRuntime: 'static + Send + Sync {
     /// Get this collector's GC type layouts.
     fn layouts(&self) -> &dyn GcTypeLayouts;
 
-    /// Construct a new GC heap.
-    fn new_gc_heap(&self) -> Result<Box<dyn GcHeap>>;
+    /// Construct a new GC heap for stores of the given engine.
+    fn new_gc_heap(&self, engine: &crate::Engine) -> Result<Box<dyn GcHeap>>;
 }
 
 /// A heap that manages garbage-collected objects.
@@ -245,6 +245,21 @@ pub unsafe trait GcHeap: 'static + Send + Sync {
     ///   alignment is larger than this collector's implementation limit.
     fn alloc_raw(&mut self, header: VMGcHeader, layout: Layout) -> Result<Option<VMGcRef>>;
 
+    /// Prepare this heap for allocating objects of the given type.
+    ///
+    /// This is called for every type which objects may be allocated with in
+    /// this heap's store before any such object is allocated: once for each
+    /// type of every module instantiated in the store and for each type the
+    /// host allocates objects of. It may be called more than once for the same
+    /// type, and also for types which aren't GC types, such as function types.
+    ///
+    /// This allows collectors to precompute per-type metadata instead of
+    /// looking it up on every allocation. It is called again for all types
+    /// after a `reset`.
+    fn register_type(&mut self, ty: VMSharedTypeIndex) {
+        let _ = ty;
+    }
+
     /// Allocate a GC-managed struct of the given type and layout.
     ///
     /// The struct's fields are left uninitialized. It is the caller's
@@ -384,8 +399,8 @@ pub unsafe trait GcHeap: 'static + Send + Sync {
     /// # Implementation Safety
     ///
     /// The heap slice must be the GC heap region, and the region must remain
-    /// valid (i.e. not moved or resized) for JIT code until `self` is dropped
-    /// or `self.reset()` is called.
+    /// valid (i.e. not moved or shrunk) for JIT code until `self` is dropped
+    /// or `self.reset()` is called. It may only grow via `self.grow()`.
     fn heap_slice(&self) -> &[UnsafeCell<u8>];
 
     /// Get a mutable slice of the raw bytes of the GC heap.
@@ -393,10 +408,28 @@ pub unsafe trait GcHeap: 'static + Send + Sync {
     /// # Implementation Safety
     ///
     /// The heap slice must be the GC heap region, and the region must remain
-    /// valid (i.e. not moved or resized) for JIT code until `self` is dropped
-    /// or `self.reset()` is called.
+    /// valid (i.e. not moved or shrunk) for JIT code until `self` is dropped
+    /// or `self.reset()` is called. It may only grow via `self.grow()`.
     fn heap_slice_mut(&mut self) -> &mut [u8];
 
+    ////////////////////////////////////////////////////////////////////////////
+    // Growing the GC heap
+
+    /// Get the maximum size, in bytes, that this heap may grow to.
+    fn max_heap_size(&self) -> usize;
+
+    /// Grow this heap to at least `new_size` bytes, making the new space
+    /// available for allocation.
+    ///
+    /// `new_size` is always greater than the heap's current size and no
+    /// greater than `self.max_heap_size()`.
+    ///
+    /// # Implementation Safety
+    ///
+    /// The heap must not move: `self.heap_slice()` must have the same start
+    /// address after growing as before.
+    fn grow(&mut self, new_size: usize) -> Result<()>;
+
     ////////////////////////////////////////////////////////////////////////////
     // Provided helper methods.
 
@@ -12,15 +12,31 @@
 //! return the wrong (but still valid) host data object or panic. This is way
 //! less catastrophic than doing an indirect call to an attacker-controlled
 //! function pointer.
+//!
+//! Host data created by Wasmtime itself, such as exception objects, may also
+//! own references to other GC objects. Those are kept next to the host data so
+//! that collectors can trace them, and are released when the host data is
+//! deallocated.
 
 use crate::prelude::*;
+use crate::runtime::vm::VMGcRef;
 use core::any::Any;
 use wasmtime_slab::{Id, Slab};
 
 /// Side table for each `externref`'s host data value.
 #[derive(Default)]
 pub struct ExternRefHostDataTable {
-    slab: Slab<Box<dyn Any + Send + Sync>>,
+    slab: Slab<Entry>,
+}
+
+/// An `externref` host data value together with the GC references it owns.
+pub type HostData = (Box<dyn Any + Send + Sync>, Box<[VMGcRef]>);
+
+struct Entry {
+    value: Box<dyn Any + Send + Sync>,
+    /// GC references owned by this host data, each holding its referent alive
+    /// until the host data is deallocated.
+    gc_refs: Box<[VMGcRef]>,
 }
 
 /// ID into the `externref` host data table.
@@ -39,28 +55,56 @@ fn deref_box_mut<T: ?Sized>(b: &mut Box<T>) -> &mut T {
 impl ExternRefHostDataTable {
     /// Allocate a new `externref` host data value.
     pub fn alloc(&mut self, value: Box<dyn Any + Send + Sync>) -> ExternRefHostDataId {
-        let id = self.slab.alloc(value);
+        self.alloc_with_gc_refs(value, Box::new([]))
+    }
+
+    /// Allocate a new `externref` host data value which owns the given GC
+    /// references.
+    ///
+    /// Ownership of `gc_refs` is transferred to the table: they are returned
+    /// by [`ExternRefHostDataTable::dealloc`] for the collector to release.
+    pub fn alloc_with_gc_refs(
+        &mut self,
+        value: Box<dyn Any + Send + Sync>,
+        gc_refs: Box<[VMGcRef]>,
+    ) -> ExternRefHostDataId {
+        let id = self.slab.alloc(Entry { value, gc_refs });
         let id = ExternRefHostDataId(id);
         log::trace!("allocated new externref host data: {id:?}");
         id
     }
 
     /// Deallocate an `externref` host data value.
-    pub fn dealloc(&mut self, id: ExternRefHostDataId) -> Box<dyn Any + Send + Sync> {
+    ///
+    /// The GC references owned by the host data are pushed onto `gc_refs`, and
+    /// it is the caller's responsibility to release them.
+    pub fn dealloc(
+        &mut self,
+        id: ExternRefHostDataId,
+        gc_refs: &mut Vec<VMGcRef>,
+    ) -> Box<dyn Any + Send + Sync> {
         log::trace!("deallocated externref host data: {id:?}");
-        self.slab.dealloc(id.0)
+        let data = self.slab.dealloc(id.0);
+        gc_refs.extend(data.gc_refs.into_vec());
+        data.value
     }
 
     /// Get a shared borrow of the host data associated with the given ID.
     pub fn get(&self, id: ExternRefHostDataId) -> &(dyn Any + Send + Sync) {
-        let data: &Box<dyn Any + Send + Sync> = self.slab.get(id.0).unwrap();
-        deref_box(data)
+        let data: &Entry = self.slab.get(id.0).unwrap();
+        deref_box(&data.value)
     }
 
     /// Get a mutable borrow of the host data associated with the given ID.
     pub fn get_mut(&mut self, id: ExternRefHostDataId) -> &mut (dyn Any + Send + Sync) {
-        let data: &mut Box<dyn Any + Send + Sync> = self.slab.get_mut(id.0).unwrap();
-        deref_box_mut(data)
+        let data: &mut Entry = self.slab.get_mut(id.0).unwrap();
+        deref_box_mut(&mut data.value)
+    }
+
+    /// Get the GC references owned by the host data associated with the given
+    /// ID.
+    pub fn gc_refs(&self, id: ExternRefHostDataId) -> &[VMGcRef] {
+        &self.slab.get(id.0).unwrap().gc_refs
     }
 }
 
@@ -79,4 +123,17 @@ mod tests {
         assert!(table.get_mut(id).is::<u32>());
         assert_eq!(*table.get_mut(id).downcast_ref::<u32>().unwrap(), 42);
     }
+
+    #[test]
+    fn dealloc_returns_gc_refs() {
+        let mut table = ExternRefHostDataTable::default();
+
+        let gc_ref = VMGcRef::from_raw_u32(8).unwrap();
+        let id = table.alloc_with_gc_refs(Box::new(()), Box::new([gc_ref.unchecked_copy()]));
+        assert_eq!(table.gc_refs(id), [gc_ref.unchecked_copy()]);
+
+        let mut gc_refs = vec![];
+        assert!(table.dealloc(id, &mut gc_refs).is::<()>());
+        assert_eq!(gc_refs, [gc_ref]);
+    }
 }
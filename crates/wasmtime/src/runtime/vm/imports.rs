@@ -1,3 +1,4 @@
+use crate::runtime::vm::export::ExportTag;
 use crate::runtime::vm::vmcontext::{
     VMFunctionImport, VMGlobalImport, VMMemoryImport, VMTableImport,
 };
@@ -26,4 +27,7 @@ pub struct Imports<'a> {
 
     /// Resolved addresses for imported globals.
     pub globals: &'a [VMGlobalImport],
+
+    /// Resolved imported tags.
+    pub tags: &'a [ExportTag],
 }
@@ -12,8 +12,9 @@ use crate::runtime::vm::vmcontext::{
     VMTableDefinition, VMTableImport,
 };
 use crate::runtime::vm::{
-    ExportFunction, ExportGlobal, ExportMemory, ExportTable, GcStore, Imports, ModuleRuntimeInfo,
-    SendSyncPtr, VMFunctionBody, VMGcRef, VMStore, VMStoreRawPtr, VmPtr, VmSafe, WasmFault,
+    ExportFunction, ExportGlobal, ExportMemory, ExportTable, ExportTag, GcStore, Imports,
+    ModuleRuntimeInfo, SendSyncPtr, VMFunctionBody, VMGcRef, VMStore, VMStoreRawPtr, VmPtr, VmSafe,
+    WasmFault,
 };
 use crate::store::{StoreInner, StoreOpaque};
 use crate::{prelude::*, StoreContextMut};
@@ -29,8 +30,8 @@ use wasmtime_environ::{
     packed_option::ReservedValue, DataIndex, DefinedGlobalIndex, DefinedMemoryIndex,
     DefinedTableIndex, ElemIndex, EntityIndex, EntityRef, EntitySet, FuncIndex, GlobalIndex,
     HostPtr, MemoryIndex, Module, ModuleInternedTypeIndex, PrimaryMap, PtrSize, TableIndex,
-    TableInitialValue, TableSegmentElements, Trap, VMOffsets, VMSharedTypeIndex, WasmHeapTopType,
-    VMCONTEXT_MAGIC,
+    TableInitialValue, TableSegmentElements, TagIndex, Trap, VMOffsets, VMSharedTypeIndex,
+    WasmHeapTopType, VMCONTEXT_MAGIC,
 };
 #[cfg(feature = "wmemcheck")]
 use wasmtime_wmemcheck::Wmemcheck;
@@ -208,6 +209,13 @@ pub struct Instance {
     /// table.
     tables: PrimaryMap<DefinedTableIndex, (TableAllocationIndex, Table)>,
 
+    /// WebAssembly tags, both imported and defined.
+    ///
+    /// Imported tags are provided at allocation time and defined tags are
+    /// pushed afterwards by the embedder with `push_defined_tag`, since tags
+    /// are identified by their index within the store.
+    tags: PrimaryMap<TagIndex, ExportTag>,
+
     /// Stores the dropped passive element segments in this instantiation by index.
     /// If the index is present in the set, the segment has been dropped.
     dropped_elements: EntitySet<ElemIndex>,
@@ -317,6 +325,7 @@ impl Instance {
                 runtime_info: req.runtime_info.clone(),
                 memories,
                 tables,
+                tags: req.imports.tags.iter().copied().collect(),
                 dropped_elements,
                 dropped_data,
                 host_state: req.host_state,
@@ -621,6 +630,15 @@ impl Instance {
         }
     }
 
+    /// Update this instance's copy of its GC heap's bound after the GC heap has
+    /// grown in place.
+    #[cfg(feature = "gc")]
+    pub(crate) unsafe fn set_gc_heap_bound(&mut self, bound: usize) {
+        if self.gc_heap_base().read().is_some() {
+            self.gc_heap_bound().write(bound);
+        }
+    }
+
     pub(crate) unsafe fn set_callee(&mut self, callee: Option<NonNull<VMFunctionBody>>) {
         let callee = callee.map(|p| VmPtr::from(p));
         self.vmctx_plus_offset_mut(self.offsets().ptr.vmctx_callee())
@@ -695,6 +713,17 @@ impl Instance {
         }
     }
 
+    /// Returns the tag with the given index, which may be imported or defined.
+    pub fn get_exported_tag(&self, index: TagIndex) -> ExportTag {
+        self.tags[index]
+    }
+
+    /// Pushes the next defined tag of this instance.
+    pub fn push_defined_tag(&mut self, tag: ExportTag) {
+        debug_assert!(self.tags.len() < self.env_module().tags.len());
+        self.tags.push(tag);
+    }
+
     fn get_exported_global(&mut self, index: GlobalIndex) -> ExportGlobal {
         ExportGlobal {
             definition: if let Some(def_index) = self.env_module().defined_global_index(index) {
@@ -936,6 +965,43 @@ impl Instance {
         }
     }
 
+    /// Switches this instance over to the code of `module`, a recompilation
+    /// of this instance's module with some functions tiered up.
+    ///
+    /// This rewrites the `VMFuncRef`s of all escaping functions in place, so
+    /// tables, exports and `ref.func` values all call into `module`'s code
+    /// afterwards. Frames already running the previous code, direct calls
+    /// made by the previous code, and imports of these functions copied into
+    /// other instances keep using the previous code, so it must be kept
+    /// alive as long as this instance.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn replace_code(&mut self, module: crate::Module) {
+        debug_assert_eq!(
+            self.offsets().size_of_vmctx(),
+            module.offsets().size_of_vmctx()
+        );
+        self.runtime_info = ModuleRuntimeInfo::Module(module);
+
+        // Safety: we have a `&mut self`, so we have exclusive access to this
+        // Instance, and `module` has the same `VMContext` layout as the
+        // module this instance was created from.
+        unsafe {
+            let types = NonNull::from(self.runtime_info.type_ids());
+            self.type_ids_array().write(types.cast().into());
+
+            let env_module = self.env_module().clone();
+            for (index, func) in env_module.functions.iter() {
+                if !func.is_escaping() || env_module.defined_func_index(index).is_none() {
+                    continue;
+                }
+                let func_ref = self.vmctx_plus_offset_mut::<VMFuncRef>(
+                    self.offsets().vmctx_func_ref(func.func_ref),
+                );
+                self.construct_func_ref(index, func.signature, func_ref.as_ptr());
+            }
+        }
+    }
+
     /// Get the passive elements segment at the given index.
     ///
     /// Returns an empty segment if the index is out of bounds or if the segment
@@ -1033,15 +1099,16 @@ impl Instance {
                     .ok_or(Trap::TableOutOfBounds)?;
                 let mut context = ConstEvalContext::new(self);
                 match module.tables[table_index].ref_type.heap_type.top() {
-                    WasmHeapTopType::Extern => table.init_gc_refs(
-                        dst,
-                        exprs.iter().map(|expr| unsafe {
-                            let raw = const_evaluator
-                                .eval(store, &mut context, expr)
-                                .expect("const expr should be valid");
-                            VMGcRef::from_raw_u32(raw.get_externref())
-                        }),
-                    )?,
+                    WasmHeapTopType::Extern | WasmHeapTopType::Exn | WasmHeapTopType::Cont => table
+                        .init_gc_refs(
+                            dst,
+                            exprs.iter().map(|expr| unsafe {
+                                let raw = const_evaluator
+                                    .eval(store, &mut context, expr)
+                                    .expect("const expr should be valid");
+                                VMGcRef::from_raw_u32(raw.get_externref())
+                            }),
+                        )?,
                     WasmHeapTopType::Any => table.init_gc_refs(
                         dst,
                         exprs.iter().map(|expr| unsafe {
@@ -1503,6 +1570,16 @@ impl InstanceHandle {
         self.instance_mut().get_exported_table(export)
     }
 
+    /// Lookup a tag by index.
+    pub fn get_exported_tag(&self, export: TagIndex) -> ExportTag {
+        self.instance().get_exported_tag(export)
+    }
+
+    /// Pushes the next defined tag of this instance.
+    pub fn push_defined_tag(&mut self, tag: ExportTag) {
+        self.instance_mut().push_defined_tag(tag)
+    }
+
     /// Lookup an item with the given index.
     pub fn get_export_by_index(&mut self, export: EntityIndex) -> Export {
         match export {
@@ -1510,6 +1587,7 @@ impl InstanceHandle {
             EntityIndex::Global(i) => Export::Global(self.get_exported_global(i)),
             EntityIndex::Table(i) => Export::Table(self.get_exported_table(i)),
             EntityIndex::Memory(i) => Export::Memory(self.get_exported_memory(i)),
+            EntityIndex::Tag(i) => Export::Tag(self.instance().get_exported_tag(i)),
         }
     }
 
@@ -1593,6 +1671,26 @@ impl InstanceHandle {
             .map(|(_i, memory)| memory)
     }
 
+    /// Returns the total number of bytes currently allocated to the memories
+    /// defined by this instance.
+    pub fn defined_memories_byte_size(&self) -> usize {
+        self.instance()
+            .memories
+            .values()
+            .map(|(_, memory)| memory.byte_size())
+            .sum()
+    }
+
+    /// Returns the total number of bytes currently used by the elements of the
+    /// tables defined by this instance.
+    pub fn defined_tables_byte_size(&self) -> usize {
+        self.instance()
+            .tables
+            .values()
+            .map(|(_, table)| table.byte_size())
+            .sum()
+    }
+
     /// Get all globals within this instance.
     ///
     /// Returns both import and defined globals.
@@ -32,8 +32,8 @@ pub use self::on_demand::OnDemandInstanceAllocator;
 mod pooling;
 #[cfg(feature = "pooling-allocator")]
 pub use self::pooling::{
-    InstanceLimits, PoolConcurrencyLimitError, PoolingInstanceAllocator,
-    PoolingInstanceAllocatorConfig,
+    InstanceLimits, PoolConcurrencyLimitError, PoolMetrics, PoolingAllocationFailures,
+    PoolingAllocatorMetrics, PoolingInstanceAllocator, PoolingInstanceAllocatorConfig,
 };
 
 /// Represents a request for a new runtime instance.
@@ -303,6 +303,7 @@ pub unsafe trait InstanceAllocatorImpl {
     #[cfg(feature = "gc")]
     fn allocate_gc_heap(
         &self,
+        engine: &crate::Engine,
         gc_runtime: &dyn GcRuntime,
     ) -> Result<(GcHeapAllocationIndex, Box<dyn GcHeap>)>;
 
@@ -335,6 +336,12 @@ pub unsafe trait InstanceAllocatorImpl {
 
     /// Allow access to memory regions protected by any protection key.
     fn allow_all_pkeys(&self);
+
+    /// Get this allocator as a pooling allocator, if it is one.
+    #[cfg(feature = "pooling-allocator")]
+    fn as_pooling(&self) -> Option<&PoolingInstanceAllocator> {
+        None
+    }
 }
 
 /// A thing that can allocate instances.
@@ -576,7 +583,7 @@ fn initialize_tables(
                 let idx = module.table_index(table);
                 let table = unsafe { context.instance.get_defined_table(table).as_mut().unwrap() };
                 match module.tables[idx].ref_type.heap_type.top() {
-                    WasmHeapTopType::Extern => {
+                    WasmHeapTopType::Extern | WasmHeapTopType::Exn | WasmHeapTopType::Cont => {
                         let gc_ref = VMGcRef::from_raw_u32(raw.get_externref());
                         let gc_store = store.gc_store_mut()?;
                         let items = (0..table.size())
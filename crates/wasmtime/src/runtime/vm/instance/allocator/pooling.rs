@@ -21,6 +21,7 @@
 mod decommit_queue;
 mod index_allocator;
 mod memory_pool;
+mod metrics;
 mod table_pool;
 
 #[cfg(feature = "gc")]
@@ -40,8 +41,11 @@ cfg_if::cfg_if! {
     }
 }
 
+pub use self::metrics::{PoolMetrics, PoolingAllocationFailures, PoolingAllocatorMetrics};
+
 use self::decommit_queue::DecommitQueue;
 use self::memory_pool::MemoryPool;
+use self::metrics::{FailureCounters, PoolKind};
 use self::table_pool::TablePool;
 use super::{
     InstanceAllocationRequest, InstanceAllocatorImpl, MemoryAllocationIndex, TableAllocationIndex,
@@ -291,6 +295,8 @@ pub struct PoolingInstanceAllocator {
 
     #[cfg(feature = "async")]
     stacks: StackPool,
+
+    failures: FailureCounters,
 }
 
 #[cfg(debug_assertions)]
@@ -335,9 +341,52 @@ impl PoolingInstanceAllocator {
             gc_heaps: GcHeapPool::new(config)?,
             #[cfg(feature = "async")]
             stacks: StackPool::new(config)?,
+            failures: FailureCounters::default(),
         })
     }
 
+    /// Get a snapshot of this allocator's current state.
+    pub fn metrics(&self) -> PoolingAllocatorMetrics {
+        let memory_stripes = self.memories.stripe_metrics();
+        let mut memories = PoolMetrics::default();
+        for stripe in &memory_stripes {
+            memories.merge(stripe);
+        }
+
+        let (pending_decommit_slots, pending_decommit_bytes) = {
+            let queue = self.decommit_queue.lock().unwrap();
+            (queue.num_slots(), queue.raw_bytes())
+        };
+
+        PoolingAllocatorMetrics {
+            core_instances: self.live_core_instances.load(Ordering::Acquire),
+            component_instances: self.live_component_instances.load(Ordering::Acquire),
+            memories,
+            memory_stripes,
+            tables: self.tables.metrics(),
+            #[cfg(feature = "async")]
+            stacks: self.stacks.metrics(),
+            #[cfg(not(feature = "async"))]
+            stacks: PoolMetrics::default(),
+            #[cfg(feature = "gc")]
+            gc_heaps: self.gc_heaps.metrics(),
+            #[cfg(not(feature = "gc"))]
+            gc_heaps: PoolMetrics::default(),
+            pending_decommit_slots,
+            pending_decommit_bytes,
+            allocation_failures: self.failures.snapshot(),
+        }
+    }
+
+    /// Record the failure of `result`, if any, to allocate an entity of the
+    /// given kind.
+    fn record_failure<T>(&self, kind: PoolKind, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result {
+            self.failures.record(kind, e);
+        }
+        result
+    }
+
     fn core_instance_size(&self) -> usize {
         round_up_to_pow2(self.limits.core_instance_size, mem::align_of::<Instance>())
     }
@@ -565,11 +614,14 @@ unsafe impl InstanceAllocatorImpl for PoolingInstanceAllocator {
         let old_count = self.live_component_instances.fetch_add(1, Ordering::AcqRel);
         if old_count >= u64::from(self.limits.total_component_instances) {
             self.decrement_component_instance_count();
-            return Err(PoolConcurrencyLimitError::new(
-                usize::try_from(self.limits.total_component_instances).unwrap(),
-                "component instances",
-            )
-            .into());
+            return self.record_failure(
+                PoolKind::ComponentInstance,
+                Err(PoolConcurrencyLimitError::new(
+                    usize::try_from(self.limits.total_component_instances).unwrap(),
+                    "component instances",
+                )
+                .into()),
+            );
         }
         Ok(())
     }
@@ -582,11 +634,14 @@ unsafe impl InstanceAllocatorImpl for PoolingInstanceAllocator {
         let old_count = self.live_core_instances.fetch_add(1, Ordering::AcqRel);
         if old_count >= u64::from(self.limits.total_core_instances) {
             self.decrement_core_instance_count();
-            return Err(PoolConcurrencyLimitError::new(
-                usize::try_from(self.limits.total_core_instances).unwrap(),
-                "core instances",
-            )
-            .into());
+            return self.record_failure(
+                PoolKind::CoreInstance,
+                Err(PoolConcurrencyLimitError::new(
+                    usize::try_from(self.limits.total_core_instances).unwrap(),
+                    "core instances",
+                )
+                .into()),
+            );
         }
         Ok(())
     }
@@ -602,7 +657,9 @@ unsafe impl InstanceAllocatorImpl for PoolingInstanceAllocator {
         tunables: &Tunables,
         memory_index: DefinedMemoryIndex,
     ) -> Result<(MemoryAllocationIndex, Memory)> {
-        self.with_flush_and_retry(|| self.memories.allocate(request, ty, tunables, memory_index))
+        let result = self
+            .with_flush_and_retry(|| self.memories.allocate(request, ty, tunables, memory_index));
+        self.record_failure(PoolKind::Memory, result)
     }
 
     unsafe fn deallocate_memory(
@@ -633,7 +690,8 @@ unsafe impl InstanceAllocatorImpl for PoolingInstanceAllocator {
         tunables: &Tunables,
         _table_index: DefinedTableIndex,
     ) -> Result<(super::TableAllocationIndex, Table)> {
-        self.with_flush_and_retry(|| self.tables.allocate(request, ty, tunables))
+        let result = self.with_flush_and_retry(|| self.tables.allocate(request, ty, tunables));
+        self.record_failure(PoolKind::Table, result)
     }
 
     unsafe fn deallocate_table(
@@ -653,7 +711,8 @@ unsafe impl InstanceAllocatorImpl for PoolingInstanceAllocator {
 
     #[cfg(feature = "async")]
     fn allocate_fiber_stack(&self) -> Result<wasmtime_fiber::FiberStack> {
-        self.with_flush_and_retry(|| self.stacks.allocate())
+        let result = self.with_flush_and_retry(|| self.stacks.allocate());
+        self.record_failure(PoolKind::Stack, result)
     }
 
     #[cfg(feature = "async")]
@@ -681,12 +740,18 @@ unsafe impl InstanceAllocatorImpl for PoolingInstanceAllocator {
         mpk::allow(ProtectionMask::all());
     }
 
+    fn as_pooling(&self) -> Option<&PoolingInstanceAllocator> {
+        Some(self)
+    }
+
     #[cfg(feature = "gc")]
     fn allocate_gc_heap(
         &self,
+        engine: &crate::Engine,
         gc_runtime: &dyn GcRuntime,
     ) -> Result<(GcHeapAllocationIndex, Box<dyn GcHeap>)> {
-        self.gc_heaps.allocate(gc_runtime)
+        let result = self.gc_heaps.allocate(engine, gc_runtime);
+        self.record_failure(PoolKind::GcHeap, result)
     }
 
     #[cfg(feature = "gc")]
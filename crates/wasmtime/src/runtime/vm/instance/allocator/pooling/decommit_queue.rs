@@ -96,6 +96,20 @@ impl DecommitQueue {
         self.raw.len()
     }
 
+    /// How many bytes of memory are enqueued for decommit?
+    pub fn raw_bytes(&self) -> usize {
+        self.raw.iter().map(|iovec| iovec.0.iov_len).sum()
+    }
+
+    /// How many memories, tables, and stacks are waiting to be returned to
+    /// their pools?
+    pub fn num_slots(&self) -> usize {
+        let n = self.memories.len() + self.tables.len();
+        #[cfg(feature = "async")]
+        let n = n + self.stacks.len();
+        n
+    }
+
     /// Enqueue a region of memory for decommit.
     ///
     /// It is the caller's responsibility to push the associated data via
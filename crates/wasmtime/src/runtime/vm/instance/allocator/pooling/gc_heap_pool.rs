@@ -1,10 +1,17 @@
 use super::index_allocator::{SimpleIndexAllocator, SlotId};
-use super::GcHeapAllocationIndex;
+use super::{GcHeapAllocationIndex, PoolConcurrencyLimitError, PoolMetrics};
 use crate::prelude::*;
 use crate::runtime::vm::{GcHeap, GcRuntime, PoolingInstanceAllocatorConfig, Result};
 use std::sync::Mutex;
 
 /// A pool of reusable GC heaps.
+///
+/// Slots don't have a fixed size: each heap reserves
+/// [`Config::gc_heap_maximum_size`](crate::Config::gc_heap_maximum_size) bytes
+/// of address space and grows within that reservation exactly like heaps
+/// created by the on-demand allocator. Returning a heap to the pool resets it
+/// to its initial size, but the memory it grew into stays accessible so that
+/// the next store to use the slot can grow again cheaply.
 pub struct GcHeapPool {
     max_gc_heaps: usize,
     index_allocator: SimpleIndexAllocator,
@@ -44,21 +51,22 @@ impl GcHeapPool {
         self.index_allocator.is_empty()
     }
 
+    /// Get a snapshot of this pool's slot usage.
+    pub fn metrics(&self) -> PoolMetrics {
+        self.index_allocator.metrics()
+    }
+
     /// Allocate a single table for the given instance allocation request.
     pub fn allocate(
         &self,
+        engine: &crate::Engine,
         gc_runtime: &dyn GcRuntime,
     ) -> Result<(GcHeapAllocationIndex, Box<dyn GcHeap>)> {
         let allocation_index = self
             .index_allocator
             .alloc()
             .map(|slot| GcHeapAllocationIndex(slot.0))
-            .ok_or_else(|| {
-                anyhow!(
-                    "maximum concurrent GC heap limit of {} reached",
-                    self.max_gc_heaps
-                )
-            })?;
+            .ok_or_else(|| PoolConcurrencyLimitError::new(self.max_gc_heaps, "GC heaps"))?;
         debug_assert_ne!(allocation_index, GcHeapAllocationIndex::default());
 
         let heap = match {
@@ -69,7 +77,7 @@ impl GcHeapPool {
             Some(heap) => heap,
             // Otherwise, we haven't forced this slot's lazily allocated heap
             // yet. So do that now.
-            None => gc_runtime.new_gc_heap()?,
+            None => gc_runtime.new_gc_heap(engine)?,
         };
 
         Ok((allocation_index, heap))
@@ -78,6 +86,10 @@ impl GcHeapPool {
     /// Deallocate a previously-allocated GC heap.
     pub fn deallocate(&self, allocation_index: GcHeapAllocationIndex, mut heap: Box<dyn GcHeap>) {
         debug_assert_ne!(allocation_index, GcHeapAllocationIndex::default());
+
+        // GC heaps are not decommitted when they are returned to the pool, so
+        // all of the memory the heap grew to remains resident.
+        let resident_bytes = heap.heap_slice().len();
         heap.reset();
 
         // NB: Replace the heap before freeing the index. If we did it in the
@@ -90,6 +102,7 @@ impl GcHeapPool {
             debug_assert!(old_entry.is_none());
         }
 
-        self.index_allocator.free(SlotId(allocation_index.0));
+        self.index_allocator
+            .free_keeping_resident(SlotId(allocation_index.0), resident_bytes);
     }
 }
@@ -1,5 +1,6 @@
 #![cfg_attr(not(asan), allow(dead_code))]
 
+use super::PoolMetrics;
 use crate::prelude::*;
 use crate::{runtime::vm::PoolingInstanceAllocatorConfig, PoolConcurrencyLimitError};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -38,6 +39,24 @@ impl StackPool {
         self.live_stacks.load(Ordering::Acquire) == 0
     }
 
+    /// Get a snapshot of this pool's usage.
+    ///
+    /// Stacks are not actually pooled, so there are never any unused warm
+    /// slots.
+    pub fn metrics(&self) -> PoolMetrics {
+        let capacity = usize::try_from(self.stack_limit).unwrap();
+        let live = usize::try_from(self.live_stacks.load(Ordering::Acquire))
+            .unwrap()
+            .min(capacity);
+        PoolMetrics {
+            capacity,
+            live,
+            unused_warm: 0,
+            free: capacity - live,
+            resident_bytes: 0,
+        }
+    }
+
     pub fn allocate(&self) -> Result<wasmtime_fiber::FiberStack> {
         if self.stack_size == 0 {
             bail!("fiber stack allocation not supported")
@@ -1,5 +1,6 @@
 //! Index/slot allocator policies for the pooling allocator.
 
+use super::PoolMetrics;
 use crate::hash_map::{Entry, HashMap};
 use crate::prelude::*;
 use crate::runtime::vm::CompiledModuleId;
@@ -44,6 +45,14 @@ impl SimpleIndexAllocator {
         self.0.free(index);
     }
 
+    pub(crate) fn free_keeping_resident(&self, index: SlotId, resident_bytes: usize) {
+        self.0.free_keeping_resident(index, resident_bytes);
+    }
+
+    pub fn metrics(&self) -> PoolMetrics {
+        self.0.metrics()
+    }
+
     #[cfg(test)]
     #[allow(unused)]
     pub(crate) fn testing_freelist(&self) -> Vec<SlotId> {
@@ -76,6 +85,9 @@ struct Inner {
     /// This is the size of the `warm` list.
     unused_warm_slots: u32,
 
+    /// The sum of the bytes kept resident by all slots in the `warm` list.
+    unused_warm_resident_bytes: usize,
+
     /// A linked list (via indices) which enumerates all "warm and unused"
     /// slots, or those which have previously been allocated and then free'd.
     warm: List,
@@ -149,6 +161,10 @@ struct Unused {
 
     /// Metadata within the `warm` list of the main allocator.
     unused_list_link: Link,
+
+    /// How many bytes of this slot's memory were kept resident, rather than
+    /// decommitted, when it was freed.
+    resident_bytes: usize,
 }
 
 enum AllocMode {
@@ -163,6 +179,7 @@ impl ModuleAffinityIndexAllocator {
             last_cold: 0,
             max_unused_warm_slots,
             unused_warm_slots: 0,
+            unused_warm_resident_bytes: 0,
             module_affine: HashMap::new(),
             slot_state: (0..capacity).map(|_| SlotState::UnusedCold).collect(),
             warm: List::default(),
@@ -268,6 +285,12 @@ impl ModuleAffinityIndexAllocator {
     }
 
     pub(crate) fn free(&self, index: SlotId) {
+        self.free_keeping_resident(index, 0);
+    }
+
+    /// Free the given slot, recording that `resident_bytes` of its memory
+    /// were kept resident rather than decommitted.
+    pub(crate) fn free_keeping_resident(&self, index: SlotId, resident_bytes: usize) {
         let mut inner = self.0.lock().unwrap();
         let inner = &mut *inner;
         let module_memory = match inner.slot_state[index.index()] {
@@ -279,6 +302,7 @@ impl ModuleAffinityIndexAllocator {
         // previously used. Afterwards append it to the linked list of all
         // unused and warm slots.
         inner.unused_warm_slots += 1;
+        inner.unused_warm_resident_bytes += resident_bytes;
         let unused_list_link = inner
             .warm
             .append(index, &mut inner.slot_state, |s| &mut s.unused_list_link);
@@ -305,9 +329,25 @@ impl ModuleAffinityIndexAllocator {
             affinity: module_memory,
             affine_list_link,
             unused_list_link,
+            resident_bytes,
         });
     }
 
+    /// Get a snapshot of how this allocator's slots are being used.
+    pub fn metrics(&self) -> PoolMetrics {
+        let inner = self.0.lock().unwrap();
+        let capacity = inner.slot_state.len();
+        let unused_warm = inner.unused_warm_slots as usize;
+        let free = capacity - inner.last_cold as usize;
+        PoolMetrics {
+            capacity,
+            live: capacity - free - unused_warm,
+            unused_warm,
+            free,
+            resident_bytes: inner.unused_warm_resident_bytes,
+        }
+    }
+
     /// Return the number of empty slots available in this allocator.
     #[cfg(test)]
     pub fn num_empty_slots(&self) -> usize {
@@ -363,6 +403,8 @@ impl Inner {
         // Decrement the size of the warm list, and additionally remove it from
         // the `warm` linked list.
         self.unused_warm_slots -= 1;
+        self.unused_warm_resident_bytes -=
+            self.slot_state[slot.index()].unwrap_unused().resident_bytes;
         self.warm
             .remove(slot, &mut self.slot_state, |u| &mut u.unused_list_link);
 
@@ -699,4 +741,39 @@ mod test {
         // for good measure make sure id3 is still affine
         assert_eq!(state.alloc(Some(id3)), Some(SlotId(0)));
     }
+
+    #[test]
+    fn test_metrics() {
+        let id1 = MemoryInModule(CompiledModuleId::new(), DefinedMemoryIndex::new(0));
+        let id2 = MemoryInModule(CompiledModuleId::new(), DefinedMemoryIndex::new(0));
+        let state = ModuleAffinityIndexAllocator::new(10, 10);
+
+        let metrics = state.metrics();
+        assert_eq!(metrics.capacity, 10);
+        assert_eq!(metrics.live, 0);
+        assert_eq!(metrics.unused_warm, 0);
+        assert_eq!(metrics.free, 10);
+        assert_eq!(metrics.resident_bytes, 0);
+
+        let index1 = state.alloc(Some(id1)).unwrap();
+        let index2 = state.alloc(Some(id2)).unwrap();
+        let metrics = state.metrics();
+        assert_eq!(metrics.live, 2);
+        assert_eq!(metrics.free, 8);
+
+        state.free_keeping_resident(index1, 100);
+        state.free_keeping_resident(index2, 20);
+        let metrics = state.metrics();
+        assert_eq!(metrics.live, 0);
+        assert_eq!(metrics.unused_warm, 2);
+        assert_eq!(metrics.free, 8);
+        assert_eq!(metrics.resident_bytes, 120);
+
+        // Reusing a warm slot no longer counts its resident bytes.
+        assert_eq!(state.alloc(Some(id1)), Some(index1));
+        let metrics = state.metrics();
+        assert_eq!(metrics.live, 1);
+        assert_eq!(metrics.unused_warm, 1);
+        assert_eq!(metrics.resident_bytes, 20);
+    }
 }
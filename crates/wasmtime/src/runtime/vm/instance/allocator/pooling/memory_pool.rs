@@ -52,7 +52,7 @@
 
 use super::{
     index_allocator::{MemoryInModule, ModuleAffinityIndexAllocator, SlotId},
-    MemoryAllocationIndex,
+    MemoryAllocationIndex, PoolMetrics,
 };
 use crate::prelude::*;
 use crate::runtime::vm::{
@@ -313,6 +313,11 @@ impl MemoryPool {
         self.stripes.iter().all(|s| s.allocator.is_empty())
     }
 
+    /// Get a snapshot of the slot usage of each stripe in this pool.
+    pub fn stripe_metrics(&self) -> Vec<PoolMetrics> {
+        self.stripes.iter().map(|s| s.allocator.metrics()).collect()
+    }
+
     /// Allocate a single memory for the given instance allocation request.
     pub fn allocate(
         &self,
@@ -415,13 +420,16 @@ impl MemoryPool {
         allocation_index: MemoryAllocationIndex,
         image: MemoryImageSlot,
     ) {
+        // Clearing the memory zeroed at most `keep_resident` bytes of it in
+        // place and decommitted the rest.
+        let resident_bytes = image.accessible().min(self.keep_resident).byte_count();
         self.return_memory_image_slot(allocation_index, image);
 
         let (stripe_index, striped_allocation_index) =
             StripedAllocationIndex::from_unstriped_slot_index(allocation_index, self.stripes.len());
         self.stripes[stripe_index]
             .allocator
-            .free(SlotId(striped_allocation_index.0));
+            .free_keeping_resident(SlotId(striped_allocation_index.0), resident_bytes);
     }
 
     /// Purging everything related to `module`.
@@ -0,0 +1,151 @@
+//! Runtime metrics for the pooling instance allocator.
+
+use super::PoolConcurrencyLimitError;
+use crate::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A snapshot of the state of a pooling instance allocator.
+///
+/// Obtained from [`Engine::pooling_allocator_metrics`][crate::Engine::pooling_allocator_metrics].
+/// Taking a snapshot only briefly takes each pool's lock, so it is cheap enough
+/// to be polled periodically.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct PoolingAllocatorMetrics {
+    /// The number of live core module instances.
+    pub core_instances: u64,
+    /// The number of live component instances.
+    pub component_instances: u64,
+    /// The state of the linear memory pool, across all stripes.
+    pub memories: PoolMetrics,
+    /// The state of each stripe of the linear memory pool.
+    ///
+    /// When memory protection keys are in use, each stripe corresponds to one
+    /// protection key. Otherwise there is a single stripe.
+    pub memory_stripes: Vec<PoolMetrics>,
+    /// The state of the table pool.
+    pub tables: PoolMetrics,
+    /// The state of the async stack pool.
+    pub stacks: PoolMetrics,
+    /// The state of the GC heap pool.
+    pub gc_heaps: PoolMetrics,
+    /// The number of memories, tables, and stacks that have been deallocated
+    /// but are waiting for a batched decommit before their slots can be
+    /// reused.
+    ///
+    /// These slots are still counted as live in their pool's metrics.
+    pub pending_decommit_slots: usize,
+    /// The number of bytes waiting to be decommitted in the next batch.
+    pub pending_decommit_bytes: usize,
+    /// Counts of allocation failures since the allocator was created.
+    pub allocation_failures: PoolingAllocationFailures,
+}
+
+/// A snapshot of the state of one of the pooling allocator's pools.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PoolMetrics {
+    /// The total number of slots in this pool.
+    pub capacity: usize,
+    /// The number of slots currently in use.
+    pub live: usize,
+    /// The number of slots that were previously used and are now unused, but
+    /// may still have memory resident and be affine to a module.
+    pub unused_warm: usize,
+    /// The number of slots that have never been used.
+    pub free: usize,
+    /// An approximation of how many bytes are kept resident by the unused warm
+    /// slots, rather than having been returned to the operating system.
+    pub resident_bytes: usize,
+}
+
+impl PoolMetrics {
+    pub(super) fn merge(&mut self, other: &PoolMetrics) {
+        self.capacity += other.capacity;
+        self.live += other.live;
+        self.unused_warm += other.unused_warm;
+        self.free += other.free;
+        self.resident_bytes += other.resident_bytes;
+    }
+}
+
+/// Counts of the pooling allocator's allocation failures, by reason.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PoolingAllocationFailures {
+    /// Failures because the maximum number of concurrent core instances was
+    /// reached.
+    pub core_instance_limit: u64,
+    /// Failures because the maximum number of concurrent component instances
+    /// was reached.
+    pub component_instance_limit: u64,
+    /// Failures because no memory slot was available.
+    pub memory_limit: u64,
+    /// Failures because no table slot was available.
+    pub table_limit: u64,
+    /// Failures because no stack slot was available.
+    pub stack_limit: u64,
+    /// Failures because no GC heap slot was available.
+    pub gc_heap_limit: u64,
+    /// Failures for any other reason, such as failing to initialize a memory
+    /// from its image.
+    pub other: u64,
+}
+
+/// The kinds of entities the pooling allocator allocates.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum PoolKind {
+    CoreInstance,
+    ComponentInstance,
+    Memory,
+    Table,
+    #[cfg(feature = "async")]
+    Stack,
+    #[cfg(feature = "gc")]
+    GcHeap,
+}
+
+/// Atomic counters backing `PoolingAllocationFailures`.
+#[derive(Debug, Default)]
+pub(super) struct FailureCounters {
+    core_instance_limit: AtomicU64,
+    component_instance_limit: AtomicU64,
+    memory_limit: AtomicU64,
+    table_limit: AtomicU64,
+    stack_limit: AtomicU64,
+    gc_heap_limit: AtomicU64,
+    other: AtomicU64,
+}
+
+impl FailureCounters {
+    /// Record that allocating an entity of the given kind failed with `error`.
+    pub fn record(&self, kind: PoolKind, error: &Error) {
+        let counter = if error.is::<PoolConcurrencyLimitError>() {
+            match kind {
+                PoolKind::CoreInstance => &self.core_instance_limit,
+                PoolKind::ComponentInstance => &self.component_instance_limit,
+                PoolKind::Memory => &self.memory_limit,
+                PoolKind::Table => &self.table_limit,
+                #[cfg(feature = "async")]
+                PoolKind::Stack => &self.stack_limit,
+                #[cfg(feature = "gc")]
+                PoolKind::GcHeap => &self.gc_heap_limit,
+            }
+        } else {
+            &self.other
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> PoolingAllocationFailures {
+        PoolingAllocationFailures {
+            core_instance_limit: self.core_instance_limit.load(Ordering::Relaxed),
+            component_instance_limit: self.component_instance_limit.load(Ordering::Relaxed),
+            memory_limit: self.memory_limit.load(Ordering::Relaxed),
+            table_limit: self.table_limit.load(Ordering::Relaxed),
+            stack_limit: self.stack_limit.load(Ordering::Relaxed),
+            gc_heap_limit: self.gc_heap_limit.load(Ordering::Relaxed),
+            other: self.other.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
     index_allocator::{SimpleIndexAllocator, SlotId},
-    TableAllocationIndex,
+    PoolMetrics, TableAllocationIndex,
 };
 use crate::runtime::vm::sys::vm::commit_pages;
 use crate::runtime::vm::{
@@ -96,6 +96,11 @@ impl TablePool {
         self.index_allocator.is_empty()
     }
 
+    /// Get a snapshot of this pool's slot usage.
+    pub fn metrics(&self) -> PoolMetrics {
+        self.index_allocator.metrics()
+    }
+
     /// Get the base pointer of the given table allocation.
     fn get(&self, table_index: TableAllocationIndex) -> *mut u8 {
         assert!(table_index.index() < self.max_total_tables);
@@ -171,8 +176,12 @@ impl TablePool {
     /// memory and flushed any enqueued decommits for this table's memory.
     pub unsafe fn deallocate(&self, allocation_index: TableAllocationIndex, table: Table) {
         assert!(table.is_static());
+        // `reset_table_pages_to_zero` zeroed at most `keep_resident` bytes of
+        // the table in place and decommitted the rest.
+        let resident_bytes = self.table_byte_size(&table).min(self.keep_resident);
         drop(table);
-        self.index_allocator.free(SlotId(allocation_index.0));
+        self.index_allocator
+            .free_keeping_resident(SlotId(allocation_index.0), resident_bytes.byte_count());
     }
 
     /// Reset the given table's memory to zero.
@@ -194,12 +203,7 @@ impl TablePool {
         assert!(table.is_static());
         let base = self.get(allocation_index);
 
-        // XXX Should we check that table.size() * mem::size_of::<*mut u8>()
-        // doesn't overflow? The only check that exists is for the boundary
-        // condition that table.size() * mem::size_of::<*mut u8>() is less than
-        // a host page smaller than usize::MAX.
-        let size = HostAlignedByteCount::new_rounded_up(table.size() * mem::size_of::<*mut u8>())
-            .expect("table entry size doesn't overflow");
+        let size = self.table_byte_size(table);
 
         // `memset` the first `keep_resident` bytes.
         let size_to_memset = size.min(self.keep_resident);
@@ -213,6 +217,17 @@ impl TablePool {
                 .byte_count(),
         );
     }
+
+    /// The host-page-aligned number of bytes used by the given table's
+    /// elements.
+    fn table_byte_size(&self, table: &Table) -> HostAlignedByteCount {
+        // XXX Should we check that table.size() * mem::size_of::<*mut u8>()
+        // doesn't overflow? The only check that exists is for the boundary
+        // condition that table.size() * mem::size_of::<*mut u8>() is less than
+        // a host page smaller than usize::MAX.
+        HostAlignedByteCount::new_rounded_up(table.size() * mem::size_of::<*mut u8>())
+            .expect("table entry size doesn't overflow")
+    }
 }
 
 #[cfg(test)]
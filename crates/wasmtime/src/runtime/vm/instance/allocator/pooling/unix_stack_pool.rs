@@ -1,6 +1,7 @@
 #![cfg_attr(asan, allow(dead_code))]
 
 use super::index_allocator::{SimpleIndexAllocator, SlotId};
+use super::PoolMetrics;
 use crate::prelude::*;
 use crate::runtime::vm::sys::vm::commit_pages;
 use crate::runtime::vm::{
@@ -90,6 +91,11 @@ impl StackPool {
         self.index_allocator.is_empty()
     }
 
+    /// Get a snapshot of this pool's slot usage.
+    pub fn metrics(&self) -> PoolMetrics {
+        self.index_allocator.metrics()
+    }
+
     /// Allocate a new fiber.
     pub fn allocate(&self) -> Result<wasmtime_fiber::FiberStack> {
         if self.stack_size.is_zero() {
@@ -235,7 +241,16 @@ impl StackPool {
         assert!(index < self.max_stacks);
         let index = u32::try_from(index).unwrap();
 
-        self.index_allocator.free(SlotId(index));
+        // When zeroing, `zero_stack` kept at most `async_stack_keep_resident`
+        // bytes resident and decommitted the rest. Otherwise everything the
+        // stack touched is still resident.
+        let resident_bytes = if self.async_stack_zeroing {
+            stack_size.min(self.async_stack_keep_resident.byte_count())
+        } else {
+            stack_size
+        };
+        self.index_allocator
+            .free_keeping_resident(SlotId(index), resident_bytes);
     }
 }
 
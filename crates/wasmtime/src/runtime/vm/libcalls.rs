@@ -57,7 +57,9 @@
 use crate::prelude::*;
 use crate::runtime::vm::table::{Table, TableElementType};
 use crate::runtime::vm::vmcontext::VMFuncRef;
-use crate::runtime::vm::{HostResultHasUnwindSentinel, Instance, TrapReason, VMGcRef, VMStore};
+use crate::runtime::vm::{
+    HostResultHasUnwindSentinel, Instance, TrapReason, VMGcRef, VMStore, ValRaw,
+};
 use core::convert::Infallible;
 use core::ptr::NonNull;
 #[cfg(feature = "threads")]
@@ -519,14 +521,45 @@ unsafe fn gc_alloc_raw(
             store.maybe_async_gc(None)?;
 
             // And then try again.
-            store
-                .unwrap_gc_store_mut()
-                .alloc_raw(header, layout)?
-                .ok_or_else(|| GcHeapOutOfMemory::new(()))?
+            match store.unwrap_gc_store_mut().alloc_raw(header, layout)? {
+                Some(r) => r,
+                None => {
+                    // If that still failed, try growing the GC heap and then
+                    // try one last time.
+                    let bytes_needed = u64::try_from(size + align).unwrap();
+                    if !store.maybe_grow_gc_heap(bytes_needed)? {
+                        return Err(GcHeapOutOfMemory::new(()).into());
+                    }
+                    store
+                        .unwrap_gc_store_mut()
+                        .alloc_raw(header, layout)?
+                        .ok_or_else(|| GcHeapOutOfMemory::new(()))?
+                }
+            }
         }
     };
 
-    Ok(gc_ref.as_raw_u32())
+    // The new object's initial reference is owned by the Wasm activation that
+    // allocated it, so hand it to the collector to track along with the rest
+    // of the references on the stack.
+    let raw = gc_ref.as_raw_u32();
+    store.unwrap_gc_store_mut().expose_gc_ref_to_wasm(gc_ref);
+    Ok(raw)
+}
+
+/// Grow the GC heap so that at least `bytes_needed` more bytes are available
+/// past its current bound.
+#[cfg(feature = "gc-null")]
+unsafe fn grow_gc_heap(
+    store: &mut dyn VMStore,
+    _instance: &mut Instance,
+    bytes_needed: u64,
+) -> Result<()> {
+    if store.maybe_grow_gc_heap(bytes_needed)? {
+        Ok(())
+    } else {
+        Err(crate::GcHeapOutOfMemory::new(()).into())
+    }
 }
 
 // Intern a `funcref` into the GC heap, returning its `FuncRefTableId`.
@@ -643,11 +676,27 @@ unsafe fn array_new_data(
             // Collect garbage to hopefully free up space, then try the
             // allocation again.
             store.maybe_async_gc(None)?;
-            store
+            match store
                 .store_opaque_mut()
                 .unwrap_gc_store_mut()
-                .alloc_uninit_array(shared_ty, u32::try_from(byte_len).unwrap(), &array_layout)?
-                .ok_or_else(|| GcHeapOutOfMemory::new(()))?
+                .alloc_uninit_array(shared_ty, len, &array_layout)?
+            {
+                Some(a) => a,
+                None => {
+                    // Grow the GC heap and try one last time.
+                    let bytes_needed = u64::from(array_layout.base_size)
+                        + u64::try_from(byte_len).unwrap()
+                        + u64::from(array_layout.align);
+                    if !store.maybe_grow_gc_heap(bytes_needed)? {
+                        return Err(GcHeapOutOfMemory::new(()).into());
+                    }
+                    store
+                        .store_opaque_mut()
+                        .unwrap_gc_store_mut()
+                        .alloc_uninit_array(shared_ty, len, &array_layout)?
+                        .ok_or_else(|| GcHeapOutOfMemory::new(()))?
+                }
+            }
         }
     };
 
@@ -822,7 +871,26 @@ unsafe fn array_new_elem(
                 // Collect garbage to hopefully free up space, then try the
                 // allocation again.
                 store.maybe_async_gc(None)?;
-                ArrayRef::_new_fixed(store, &pre, &vals)?
+                match ArrayRef::_new_fixed(store, &pre, &vals) {
+                    Ok(a) => a,
+                    Err(e) if e.is::<GcHeapOutOfMemory<()>>() => {
+                        // Grow the GC heap and try one last time.
+                        let layout = store
+                            .engine()
+                            .signatures()
+                            .layout(shared_ty)
+                            .expect("array types have GC layouts");
+                        let layout = layout.unwrap_array();
+                        let len = u32::try_from(vals.len()).unwrap();
+                        let bytes_needed =
+                            u64::from(layout.size_for_len(len)) + u64::from(layout.align);
+                        if !store.maybe_grow_gc_heap(bytes_needed)? {
+                            return Err(e);
+                        }
+                        ArrayRef::_new_fixed(store, &pre, &vals)?
+                    }
+                    Err(e) => return Err(e),
+                }
             }
             Err(e) => return Err(e),
         };
@@ -1018,6 +1086,129 @@ unsafe fn is_subtype(
     is_subtype as u32
 }
 
+/// Implementation of the `throw` instruction.
+#[cfg(feature = "gc")]
+unsafe fn throw(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    tag: u32,
+    payload: *mut u8,
+) -> Result<()> {
+    use wasmtime_environ::TagIndex;
+
+    let tag = instance.get_exported_tag(TagIndex::from_u32(tag));
+    let len = store
+        .engine()
+        .signatures()
+        .borrow(tag.signature)
+        .expect("tag types are registered")
+        .unwrap_func()
+        .params()
+        .len();
+    let payload = core::slice::from_raw_parts(payload.cast::<ValRaw>(), len);
+    log::trace!("throw(tag={tag:?})");
+    crate::runtime::exception::throw(store, tag, payload)
+}
+
+/// Implementation of the `throw_ref` instruction.
+#[cfg(feature = "gc")]
+unsafe fn throw_ref(store: &mut dyn VMStore, _instance: &mut Instance, exnref: u32) -> Result<()> {
+    let exn = VMGcRef::from_raw_u32(exnref).ok_or_else(|| Trap::NullReference)?;
+    let store = store.store_opaque_mut();
+    let exn = store.unwrap_gc_store_mut().clone_gc_ref(&exn);
+    store.set_pending_exception(exn);
+    Ok(())
+}
+
+/// Attempts to catch the pending exception for a `try_table` catch clause.
+#[cfg(feature = "gc")]
+unsafe fn catch_exception(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    tag: u32,
+    payload: *mut u8,
+) -> Result<u32> {
+    use wasmtime_environ::TagIndex;
+
+    let tag = if tag == u32::MAX {
+        None
+    } else {
+        Some(instance.get_exported_tag(TagIndex::from_u32(tag)))
+    };
+    Ok(crate::runtime::exception::catch(
+        store.store_opaque_mut(),
+        tag,
+        payload.cast::<ValRaw>(),
+    ))
+}
+
+/// Implementation of the `cont.new` instruction.
+#[cfg(feature = "stack-switching")]
+unsafe fn cont_new(
+    store: &mut dyn VMStore,
+    _instance: &mut Instance,
+    func_ref: *mut u8,
+) -> Result<u32> {
+    crate::runtime::stack_switching::cont_new(store, func_ref.cast::<VMFuncRef>())
+}
+
+/// Implementation of the `cont.bind` instruction.
+#[cfg(feature = "stack-switching")]
+unsafe fn cont_bind(
+    store: &mut dyn VMStore,
+    _instance: &mut Instance,
+    contref: u32,
+    num_args: u32,
+    args: *mut u8,
+) -> Result<u32> {
+    crate::runtime::stack_switching::cont_bind(store, contref, num_args, args.cast::<ValRaw>())
+}
+
+/// Implementation of the `resume` and `resume_throw` instructions.
+#[cfg(feature = "stack-switching")]
+unsafe fn resume(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    contref: u32,
+    throw_tag: u32,
+    handlers: *mut u8,
+    num_handlers: u32,
+    values: *mut u8,
+) -> Result<u32> {
+    let handlers = core::slice::from_raw_parts(handlers.cast::<u32>(), num_handlers as usize);
+    crate::runtime::stack_switching::resume(
+        store,
+        instance,
+        contref,
+        throw_tag,
+        handlers,
+        values.cast::<ValRaw>(),
+    )
+}
+
+/// Implementation of the `suspend` instruction.
+#[cfg(feature = "stack-switching")]
+unsafe fn suspend(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    tag: u32,
+    values: *mut u8,
+) -> Result<()> {
+    crate::runtime::stack_switching::suspend(store, instance, tag, values.cast::<ValRaw>())
+}
+
+/// Implementation of the `switch` instruction.
+#[cfg(feature = "stack-switching")]
+unsafe fn switch(
+    store: &mut dyn VMStore,
+    instance: &mut Instance,
+    contref: u32,
+    tag: u32,
+    values: *mut u8,
+) -> Result<()> {
+    crate::runtime::stack_switching::switch(store, instance, contref, tag, values.cast::<ValRaw>())
+}
+
 // Implementation of `memory.atomic.notify` for locally defined memories.
 #[cfg(feature = "threads")]
 fn memory_atomic_notify(
@@ -1074,6 +1265,10 @@ fn out_of_gas(store: &mut dyn VMStore, _instance: &mut Instance) -> Result<()> {
 
 // Hook for when an instance observes that the epoch has changed.
 fn new_epoch(store: &mut dyn VMStore, _instance: &mut Instance) -> Result<NextEpoch> {
+    // Reaching the epoch deadline doubles as a sample of which function is
+    // running for tiered compilation.
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    crate::module::tier_up::sample(store.store_opaque_mut(), _instance);
     store.new_epoch().map(NextEpoch)
 }
 
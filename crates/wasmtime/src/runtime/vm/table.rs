@@ -262,7 +262,10 @@ impl From<DynamicGcRefTable> for Table {
 fn wasm_to_table_type(ty: WasmRefType) -> TableElementType {
     match ty.heap_type.top() {
         WasmHeapTopType::Func => TableElementType::Func,
-        WasmHeapTopType::Any | WasmHeapTopType::Extern => TableElementType::GcRef,
+        WasmHeapTopType::Any
+        | WasmHeapTopType::Extern
+        | WasmHeapTopType::Exn
+        | WasmHeapTopType::Cont => TableElementType::GcRef,
     }
 }
 
@@ -420,6 +423,16 @@ impl Table {
         }
     }
 
+    /// Returns the number of bytes used to store this table's allocated
+    /// elements.
+    pub fn byte_size(&self) -> usize {
+        let element_size = match self.element_type() {
+            TableElementType::Func => core::mem::size_of::<FuncTableElem>(),
+            TableElementType::GcRef => core::mem::size_of::<Option<VMGcRef>>(),
+        };
+        self.size() * element_size
+    }
+
     /// Returns the maximum number of elements at runtime.
     ///
     /// Returns `None` if the table is unbounded.
@@ -20,12 +20,12 @@ use crate::runtime::module::lookup_code;
 use crate::runtime::store::StoreOpaque;
 use crate::runtime::vm::sys::traphandlers;
 use crate::runtime::vm::{Instance, InterpreterRef, VMContext, VMRuntimeLimits};
-use crate::{StoreContextMut, WasmBacktrace};
+use crate::WasmBacktrace;
 use core::cell::Cell;
 use core::ops::Range;
 use core::ptr::{self, NonNull};
 
-pub use self::backtrace::Backtrace;
+pub use self::backtrace::{Backtrace, Frame};
 pub use self::coredump::CoreDumpStack;
 pub use self::tls::{tls_eager_initialize, AsyncWasmCallState, PreviousAsyncWasmCallState};
 
@@ -349,15 +349,12 @@ impl From<wasmtime_environ::Trap> for TrapReason {
 ///
 /// This function is unsafe because during the execution of `closure` it may be
 /// longjmp'd over and none of its destructors on the stack may be run.
-pub unsafe fn catch_traps<T, F>(
-    store: &mut StoreContextMut<'_, T>,
-    mut closure: F,
-) -> Result<(), Box<Trap>>
+pub unsafe fn catch_traps<F>(store: &mut StoreOpaque, mut closure: F) -> Result<(), Box<Trap>>
 where
     F: FnMut(NonNull<VMContext>, Option<InterpreterRef<'_>>) -> bool,
 {
-    let caller = store.0.default_caller();
-    let result = CallThreadState::new(store.0, caller).with(|cx| match store.0.interpreter() {
+    let caller = store.default_caller();
+    let result = CallThreadState::new(store, caller).with(|cx| match store.interpreter() {
         // In interpreted mode directly invoke the host closure since we won't
         // be using host-based `setjmp`/`longjmp` as that's not going to save
         // the context we want.
@@ -516,6 +513,23 @@ mod call_thread_state {
             self.prev.get()
         }
 
+        /// Re-captures the saved state of the previous activation from the
+        /// current `VMRuntimeLimits`.
+        ///
+        /// This is used when a suspended activation, such as that of a
+        /// continuation, is resumed on top of a different activation than the
+        /// one it was originally created on.
+        #[cfg(feature = "stack-switching")]
+        pub(crate) unsafe fn reparent(&self) {
+            let limits = self.limits.as_ref();
+            self.old_last_wasm_exit_fp
+                .set(*limits.last_wasm_exit_fp.get());
+            self.old_last_wasm_exit_pc
+                .set(*limits.last_wasm_exit_pc.get());
+            self.old_last_wasm_entry_fp
+                .set(*limits.last_wasm_entry_fp.get());
+        }
+
         #[inline]
         pub(crate) unsafe fn push(&self) {
             assert!(self.prev.get().is_null());
@@ -866,6 +880,18 @@ pub(crate) mod tls {
             assert!(self.state.is_null());
         }
 
+        /// Makes the oldest activation of this saved state, if any, resume on
+        /// top of the activation that the current `VMRuntimeLimits` describe.
+        ///
+        /// This must be called before `push` when this state may be pushed
+        /// onto a different activation than the one it was suspended from.
+        #[cfg(feature = "stack-switching")]
+        pub unsafe fn reparent(&self) {
+            if let Some(state) = self.state.as_ref() {
+                state.reparent();
+            }
+        }
+
         /// Asserts that the current CallThreadState pointer, if present, is not
         /// in the `range` specified.
         ///
@@ -959,6 +985,13 @@ pub(crate) mod tls {
         }
     }
 
+    /// Returns the raw pointer to the current thread's most recent
+    /// `CallThreadState`, which is null if there is none.
+    #[cfg(feature = "stack-switching")]
+    pub fn current() -> Ptr {
+        raw::get()
+    }
+
     /// Returns the last pointer configured with `set` above, if any.
     pub fn with<R>(closure: impl FnOnce(Option<&CallThreadState>) -> R) -> R {
         let p = raw::get();
@@ -163,6 +163,28 @@ impl Backtrace {
         log::trace!("====== Done Capturing Backtrace (reached end of activations) ======");
     }
 
+    /// Walk the Wasm frames of an activation that is not on the current
+    /// thread's stack, such as that of a suspended continuation, given the
+    /// values its `VMRuntimeLimits` had when it was suspended.
+    #[cfg(feature = "stack-switching")]
+    pub(crate) unsafe fn trace_suspended(
+        unwind: &dyn Unwind,
+        last_wasm_exit_pc: usize,
+        last_wasm_exit_fp: usize,
+        last_wasm_entry_fp: usize,
+        f: impl FnMut(Frame) -> ControlFlow<()>,
+    ) {
+        if last_wasm_exit_pc != 0 {
+            let _ = Self::trace_through_wasm(
+                unwind,
+                last_wasm_exit_pc,
+                last_wasm_exit_fp,
+                last_wasm_entry_fp,
+                f,
+            );
+        }
+    }
+
     /// Walk through a contiguous sequence of Wasm frames starting with the
     /// frame at the given PC and FP and ending at `trampoline_sp`.
     unsafe fn trace_through_wasm(
@@ -458,7 +458,7 @@ impl VMGlobalDefinition {
             WasmValType::F64 => *global.as_f64_bits_mut() = raw.get_f64(),
             WasmValType::V128 => global.set_u128(raw.get_v128()),
             WasmValType::Ref(r) => match r.heap_type.top() {
-                WasmHeapTopType::Extern => {
+                WasmHeapTopType::Extern | WasmHeapTopType::Exn | WasmHeapTopType::Cont => {
                     let r = VMGcRef::from_raw_u32(raw.get_externref());
                     global.init_gc_ref(store.gc_store_mut()?, r.as_ref())
                 }
@@ -489,10 +489,12 @@ impl VMGlobalDefinition {
             WasmValType::F64 => ValRaw::f64(*self.as_f64_bits()),
             WasmValType::V128 => ValRaw::v128(self.get_u128()),
             WasmValType::Ref(r) => match r.heap_type.top() {
-                WasmHeapTopType::Extern => ValRaw::externref(match self.as_gc_ref() {
-                    Some(r) => store.gc_store_mut()?.clone_gc_ref(r).as_raw_u32(),
-                    None => 0,
-                }),
+                WasmHeapTopType::Extern | WasmHeapTopType::Exn | WasmHeapTopType::Cont => {
+                    ValRaw::externref(match self.as_gc_ref() {
+                        Some(r) => store.gc_store_mut()?.clone_gc_ref(r).as_raw_u32(),
+                        None => 0,
+                    })
+                }
                 WasmHeapTopType::Any => ValRaw::anyref({
                     match self.as_gc_ref() {
                         Some(r) => store.gc_store_mut()?.clone_gc_ref(r).as_raw_u32(),
@@ -949,6 +951,14 @@ pub struct VMRuntimeLimits {
     /// Used to find the end of a contiguous sequence of Wasm frames when
     /// walking the stack.
     pub last_wasm_entry_fp: UnsafeCell<usize>,
+
+    /// Whether a Wasm exception is currently being thrown.
+    ///
+    /// This is nonzero while the store holds a pending exception that has not
+    /// yet been caught. Compiled code checks it after every call and, when it
+    /// is set, branches to the innermost enclosing `try_table`'s handlers or
+    /// returns to its caller so the exception keeps propagating.
+    pub exception_pending: UnsafeCell<usize>,
 }
 
 // The `VMRuntimeLimits` type is a pod-type with no destructor, and we don't
@@ -970,6 +980,7 @@ impl Default for VMRuntimeLimits {
             last_wasm_exit_fp: UnsafeCell::new(0),
             last_wasm_exit_pc: UnsafeCell::new(0),
             last_wasm_entry_fp: UnsafeCell::new(0),
+            exception_pending: UnsafeCell::new(0),
         }
     }
 }
@@ -1008,6 +1019,10 @@ mod test_vmruntime_limits {
             offset_of!(VMRuntimeLimits, last_wasm_entry_fp),
             usize::from(offsets.ptr.vmruntime_limits_last_wasm_entry_fp())
         );
+        assert_eq!(
+            offset_of!(VMRuntimeLimits, exception_pending),
+            usize::from(offsets.ptr.vmruntime_limits_exception_pending())
+        );
     }
 }
 
@@ -109,6 +109,7 @@ fn spec_test_config(test: &Path) -> TestConfig {
             ret.multi_memory = Some(true);
         }
         Some("exception-handling") => {
+            ret.exceptions = Some(true);
             ret.reference_types = Some(true);
         }
         Some("gc") => {
@@ -188,6 +189,8 @@ macro_rules! foreach_config_option {
             component_model_async
             simd
             gc_types
+            exceptions
+            stack_switching
         }
     };
 }
@@ -292,6 +295,16 @@ impl Compiler {
                     || config.gc()
                     || config.relaxed_simd()
                     || config.gc_types()
+                    || config.exceptions()
+                    || config.stack_switching()
+                {
+                    return true;
+                }
+
+                // The riscv64 backend doesn't implement SIMD, atomics or
+                // 128-bit arithmetic yet.
+                if cfg!(target_arch = "riscv64")
+                    && (config.simd() || config.threads() || config.wide_arithmetic())
                 {
                     return true;
                 }
@@ -300,8 +313,8 @@ impl Compiler {
             Compiler::CraneliftPulley => {
                 // Pulley at this time fundamentally does not support threads
                 // due to being unable to implement non-atomic loads/stores
-                // safely.
-                if config.threads() {
+                // safely. Nor does it support switching stacks.
+                if config.threads() || config.stack_switching() {
                     return true;
                 }
             }
@@ -320,9 +333,7 @@ impl Compiler {
                     || cfg!(target_arch = "riscv64")
                     || cfg!(target_arch = "s390x")
             }
-            Compiler::Winch => {
-                cfg!(target_arch = "x86_64")
-            }
+            Compiler::Winch => cfg!(target_arch = "x86_64") || cfg!(target_arch = "riscv64"),
             Compiler::CraneliftPulley => true,
         }
     }
@@ -500,6 +511,8 @@ impl WastTest {
                 let unsupported = [
                     "misc_testsuite/simd/replace-lane-preserve.wast",
                     "misc_testsuite/simd/spillslot-size-fuzzbug.wast",
+                    "misc_testsuite/winch/_simd_arith.wast",
+                    "misc_testsuite/winch/_simd_cmp.wast",
                     "misc_testsuite/winch/_simd_lane.wast",
                     "misc_testsuite/winch/_simd_splat.wast",
                     "spec_testsuite/simd_align.wast",
@@ -514,9 +527,10 @@ impl WastTest {
         }
 
         for part in self.path.iter() {
-            // Not implemented in Wasmtime yet
-            if part == "exception-handling" {
-                return !self.path.ends_with("binary.wast") && !self.path.ends_with("exports.wast");
+            // The legacy exception-handling instructions are not implemented
+            // in Wasmtime.
+            if part == "legacy" {
+                return true;
             }
 
             if part == "memory64" {
@@ -66,6 +66,10 @@ enum Subcommand {
     #[cfg(feature = "compile")]
     Compile(wasmtime_cli::commands::CompileCommand),
 
+    /// Inspects a WebAssembly core dump.
+    #[cfg(feature = "coredump")]
+    Coredump(wasmtime_cli::commands::CoredumpCommand),
+
     /// Explore the compilation of a WebAssembly module to native code.
     #[cfg(feature = "explore")]
     Explore(wasmtime_cli::commands::ExploreCommand),
@@ -105,6 +109,9 @@ impl Wasmtime {
             #[cfg(feature = "compile")]
             Subcommand::Compile(c) => c.execute(),
 
+            #[cfg(feature = "coredump")]
+            Subcommand::Coredump(c) => c.execute(),
+
             #[cfg(feature = "explore")]
             Subcommand::Explore(c) => c.execute(),
 
@@ -25,6 +25,11 @@ mod config;
 #[cfg(feature = "cache")]
 pub use self::config::*;
 
+#[cfg(feature = "coredump")]
+mod coredump;
+#[cfg(feature = "coredump")]
+pub use self::coredump::*;
+
 #[cfg(feature = "compile")]
 mod compile;
 #[cfg(feature = "compile")]
@@ -4,7 +4,7 @@ use anyhow::{bail, Context, Result};
 use clap::Parser;
 use std::fs;
 use std::path::PathBuf;
-use wasmtime::{CodeBuilder, CodeHint, Engine};
+use wasmtime::{CodeBuilder, CodeHint, Engine, ProfileData};
 use wasmtime_cli_flags::CommonOptions;
 
 const AFTER_HELP: &str =
@@ -43,6 +43,25 @@ pub struct CompileCommand {
     #[arg(long = "emit-clif", value_name = "PATH")]
     pub emit_clif: Option<PathBuf>,
 
+    /// Run the given exported function ahead of time and compile a snapshot
+    /// of the resulting instance instead of the original module.
+    ///
+    /// The module is instantiated with WASI preview1 imports available and the
+    /// function is called. WASI is configured deterministically and without
+    /// arguments, environment variables, preopened directories or stdio, so
+    /// nothing about the host is captured in the snapshot. Calling any other
+    /// import traps. The contents of memories and mutable globals are then
+    /// baked into the module, and the function's export is removed.
+    #[arg(long, value_name = "FUNCTION")]
+    pub init_func: Option<String>,
+
+    /// Use the profile data at the given path, as written by
+    /// `wasmtime run --profile=data`, to guide compilation.
+    ///
+    /// The profile must have been collected by running the same module.
+    #[arg(long, value_name = "PATH")]
+    pub profile_data: Option<PathBuf>,
+
     /// The path of the WebAssembly to compile
     #[arg(index = 1, value_name = "MODULE")]
     pub module: PathBuf,
@@ -55,7 +74,7 @@ impl CompileCommand {
 
         let mut config = self.common.config(None)?;
 
-        if let Some(path) = self.emit_clif {
+        if let Some(path) = self.emit_clif.take() {
             if !path.exists() {
                 std::fs::create_dir(&path)?;
             }
@@ -79,8 +98,31 @@ impl CompileCommand {
             );
         }
 
+        let profile_data = match &self.profile_data {
+            Some(path) => {
+                let data = fs::read(path)
+                    .with_context(|| format!("failed to read profile data: {}", path.display()))?;
+                let data: ProfileData = serde_json::from_slice(&data)
+                    .with_context(|| format!("failed to parse profile data: {}", path.display()))?;
+                Some(data)
+            }
+            None => None,
+        };
+
+        let preinitialized;
         let mut code = CodeBuilder::new(&engine);
-        code.wasm_binary_or_text_file(&self.module)?;
+        if let Some(profile_data) = &profile_data {
+            code.profile_data(profile_data);
+        }
+        match &self.init_func {
+            Some(init_func) => {
+                preinitialized = self.preinitialize(&engine, init_func)?;
+                code.wasm_binary(&preinitialized[..], Some(&self.module))?;
+            }
+            None => {
+                code.wasm_binary_or_text_file(&self.module)?;
+            }
+        }
 
         let output = self.output.take().unwrap_or_else(|| {
             let mut output: PathBuf = self.module.file_name().unwrap().into();
@@ -102,6 +144,46 @@ impl CompileCommand {
 
         Ok(())
     }
+
+    /// Instantiates the module, calls `init_func`, and returns the module
+    /// rewritten to start out in the resulting state.
+    #[cfg(feature = "snapshot")]
+    fn preinitialize(&self, engine: &Engine, init_func: &str) -> Result<Vec<u8>> {
+        use wasmtime::{Linker, Module, Store};
+        use wasmtime_wasi::WasiCtxBuilder;
+
+        if self.common.target.is_some() {
+            bail!("`--init-func` cannot be used when compiling for another target");
+        }
+
+        #[cfg(feature = "wat")]
+        let wasm = wat::parse_file(&self.module)?;
+        #[cfg(not(feature = "wat"))]
+        let wasm = fs::read(&self.module)
+            .with_context(|| format!("failed to read input file: {}", self.module.display()))?;
+
+        let module = Module::new(engine, &wasm)?;
+        let mut linker = Linker::new(engine);
+        wasmtime_wasi::preview1::add_to_linker_sync(&mut linker, |t| t)?;
+        linker.define_unknown_imports_as_traps(&module)?;
+        let mut wasi = WasiCtxBuilder::new();
+        wasi.deterministic(0);
+        let mut store = Store::new(engine, wasi.build_p1());
+        let instance = linker.instantiate(&mut store, &module)?;
+        instance
+            .get_typed_func::<(), ()>(&mut store, init_func)?
+            .call(&mut store, ())
+            .with_context(|| format!("failed to run initialization function `{init_func}`"))?;
+
+        instance
+            .snapshot(&mut store)?
+            .preinitialize(&wasm, Some(init_func))
+    }
+
+    #[cfg(not(feature = "snapshot"))]
+    fn preinitialize(&self, _engine: &Engine, _init_func: &str) -> Result<Vec<u8>> {
+        bail!("support for snapshots disabled at compile time")
+    }
 }
 
 #[cfg(all(test, not(miri)))]
@@ -142,6 +224,106 @@ mod test {
         Ok(())
     }
 
+    #[cfg(feature = "snapshot")]
+    #[test]
+    fn test_init_func_compile() -> Result<()> {
+        let (mut input, input_path) = NamedTempFile::new()?.into_parts();
+        input.write_all(
+            r#"
+                (module
+                    (global $g (mut i32) (i32.const 0))
+                    (memory (export "memory") 1)
+                    (func (export "init")
+                        (global.set $g (i32.const 10))
+                        (i32.store (i32.const 8) (i32.const 20)))
+                    (func (export "f") (result i32)
+                        (i32.add (global.get $g) (i32.load (i32.const 8)))))
+            "#
+            .as_bytes(),
+        )?;
+        drop(input);
+
+        let output_path = NamedTempFile::new()?.into_temp_path();
+
+        let command = CompileCommand::try_parse_from(vec![
+            "compile",
+            "-Dlogging=n",
+            "--init-func=init",
+            "-o",
+            output_path.to_str().unwrap(),
+            input_path.to_str().unwrap(),
+        ])?;
+
+        command.execute()?;
+
+        let engine = Engine::default();
+        let contents = std::fs::read(output_path)?;
+        let module = unsafe { Module::deserialize(&engine, contents)? };
+        assert!(module.get_export("init").is_none());
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let f = instance.get_typed_func::<(), i32>(&mut store, "f")?;
+        assert_eq!(f.call(&mut store, ()).unwrap(), 30);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[test]
+    fn test_init_func_wasi_compile() -> Result<()> {
+        let (mut input, input_path) = NamedTempFile::new()?.into_parts();
+        input.write_all(
+            r#"
+                (module
+                    (import "wasi_snapshot_preview1" "random_get"
+                        (func $random_get (param i32 i32) (result i32)))
+                    (import "env" "missing" (func $missing))
+                    (memory (export "memory") 1)
+                    (func (export "init")
+                        (drop (call $random_get (i32.const 8) (i32.const 16))))
+                    (func (export "init_missing")
+                        (call $missing)))
+            "#
+            .as_bytes(),
+        )?;
+        drop(input);
+
+        // WASI is deterministic, so the snapshot is the same every time.
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let output_path = NamedTempFile::new()?.into_temp_path();
+            let command = CompileCommand::try_parse_from(vec![
+                "compile",
+                "-Dlogging=n",
+                "--init-func=init",
+                "-o",
+                output_path.to_str().unwrap(),
+                input_path.to_str().unwrap(),
+            ])?;
+            command.execute()?;
+            outputs.push(std::fs::read(output_path)?);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+
+        // Imports other than WASI trap.
+        let output_path = NamedTempFile::new()?.into_temp_path();
+        let command = CompileCommand::try_parse_from(vec![
+            "compile",
+            "-Dlogging=n",
+            "--init-func=init_missing",
+            "-o",
+            output_path.to_str().unwrap(),
+            input_path.to_str().unwrap(),
+        ])?;
+        let err = command.execute().unwrap_err();
+        assert!(
+            format!("{err:?}").contains("failed to run initialization function `init_missing`"),
+            "{err:?}"
+        );
+
+        Ok(())
+    }
+
     #[cfg(target_arch = "x86_64")]
     #[test]
     fn test_x64_flags_compile() -> Result<()> {
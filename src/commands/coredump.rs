@@ -0,0 +1,551 @@
+//! The module that implements the `wasmtime coredump` command.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::PathBuf;
+use wasmparser::{
+    CoreDumpInstance, CoreDumpStackFrame, CoreDumpValue, KnownCustom, Name, Operator,
+    Parser as WasmParser, Payload,
+};
+
+type Addr2LineContext<'a> = addr2line::Context<gimli::EndianSlice<'a, gimli::LittleEndian>>;
+
+/// Inspects a WebAssembly core dump.
+///
+/// Core dumps are written by `wasmtime run` when `-D coredump=PATH` is passed
+/// and a trap happens. This prints the stack of the trapping thread along with
+/// the state of the instance that each frame belongs to.
+#[derive(Parser)]
+pub struct CoredumpCommand {
+    /// The original WebAssembly module that was running when the core dump
+    /// was taken, used to symbolicate frames.
+    ///
+    /// This may be passed multiple times, in which case modules are listed in
+    /// the same order as the core dump's modules.
+    #[arg(long = "module", value_name = "MODULE")]
+    modules: Vec<PathBuf>,
+
+    /// Print a range of memory for each frame.
+    ///
+    /// `MEMORY` is the index of a memory within the frame's instance and the
+    /// range bounds are byte offsets, which may be written in hex with a `0x`
+    /// prefix.
+    #[arg(
+        long = "memory",
+        value_name = "MEMORY:START..END",
+        value_parser = parse_memory_range,
+    )]
+    memory_ranges: Vec<(u32, Range<u64>)>,
+
+    /// The path of the core dump to inspect.
+    #[arg(required = true, value_name = "COREDUMP")]
+    coredump: PathBuf,
+}
+
+impl CoredumpCommand {
+    /// Executes the command.
+    pub fn execute(self) -> Result<()> {
+        let bytes = std::fs::read(&self.coredump)
+            .with_context(|| format!("failed to read core dump: {}", self.coredump.display()))?;
+        let coredump = CoreDump::parse(&bytes)
+            .with_context(|| format!("failed to parse core dump: {}", self.coredump.display()))?;
+
+        if self.modules.len() > coredump.modules.len() {
+            bail!(
+                "{} modules were provided but the core dump only lists {}",
+                self.modules.len(),
+                coredump.modules.len()
+            );
+        }
+        let module_bytes = self
+            .modules
+            .iter()
+            .map(|path| {
+                let bytes = std::fs::read(path)
+                    .with_context(|| format!("failed to read Wasm module: {}", path.display()))?;
+                #[cfg(feature = "wat")]
+                let bytes = wat::parse_bytes(&bytes)
+                    .map_err(|mut e| {
+                        e.set_path(path);
+                        e
+                    })?
+                    .into_owned();
+                Ok(bytes)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let modules = module_bytes
+            .iter()
+            .zip(&self.modules)
+            .map(|(bytes, path)| {
+                ModuleInfo::parse(bytes)
+                    .with_context(|| format!("failed to parse Wasm module: {}", path.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        print!("{}", self.render(&coredump, &modules)?);
+        Ok(())
+    }
+
+    fn render(&self, coredump: &CoreDump<'_>, modules: &[ModuleInfo<'_>]) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "core dump of `{}`", coredump.name)?;
+
+        writeln!(out, "\nmodules:")?;
+        for (i, name) in coredump.modules.iter().enumerate() {
+            match self.modules.get(i) {
+                Some(path) => writeln!(out, "  {i}: {name} ({})", path.display())?,
+                None => writeln!(out, "  {i}: {name}")?,
+            }
+        }
+
+        writeln!(out, "\ninstances:")?;
+        for (i, instance) in coredump.instances.iter().enumerate() {
+            writeln!(
+                out,
+                "  {i}: module {}, {} memories, {} globals",
+                instance.module_index,
+                instance.memories.len(),
+                instance.globals.len()
+            )?;
+        }
+
+        writeln!(out, "\nthread `{}`:", coredump.thread)?;
+        for (i, frame) in coredump.frames.iter().enumerate() {
+            let instance = coredump
+                .instances
+                .get(frame.instanceidx as usize)
+                .ok_or_else(|| anyhow!("frame {i} refers to unknown instance"))?;
+            let module = modules.get(instance.module_index as usize);
+
+            let mut name = String::new();
+            wasmtime_environ::demangle_function_name_or_index(
+                &mut name,
+                module.and_then(|m| m.func_names.get(&frame.funcidx).copied()),
+                frame.funcidx as usize,
+            )?;
+            writeln!(
+                out,
+                "\nframe {i}: {name} (instance {}, func {})",
+                frame.instanceidx, frame.funcidx
+            )?;
+
+            match module.and_then(|m| m.module_offset(frame)) {
+                Some(offset) => writeln!(
+                    out,
+                    "  at wasm offset {offset:#x} (function offset {:#x})",
+                    frame.codeoffset
+                )?,
+                None => writeln!(out, "  at function offset {:#x}", frame.codeoffset)?,
+            }
+            if let Some(module) = module {
+                for symbol in module.symbolicate(frame) {
+                    writeln!(out, "  {symbol}")?;
+                }
+            }
+
+            print_values(&mut out, "locals", &frame.locals)?;
+            print_values(&mut out, "stack", &frame.stack)?;
+
+            if !instance.globals.is_empty() {
+                writeln!(out, "  globals:")?;
+            }
+            for (index, global) in instance.globals.iter().enumerate() {
+                let index = u32::try_from(index).unwrap();
+                let value = coredump
+                    .globals
+                    .get(*global as usize)
+                    .ok_or_else(|| anyhow!("instance refers to unknown global {global}"))?;
+                match module.and_then(|m| m.global_names.get(&index)) {
+                    Some(name) => writeln!(out, "    {index} ({name}): {value}")?,
+                    None => writeln!(out, "    {index}: {value}")?,
+                }
+            }
+
+            for (memory, range) in &self.memory_ranges {
+                let Some(memory_idx) = instance.memories.get(*memory as usize) else {
+                    writeln!(out, "  memory {memory}: not present in this instance")?;
+                    continue;
+                };
+                let data = coredump
+                    .memories
+                    .get(*memory_idx as usize)
+                    .ok_or_else(|| anyhow!("instance refers to unknown memory {memory_idx}"))?;
+                writeln!(
+                    out,
+                    "  memory {memory} [{:#x}..{:#x}]:",
+                    range.start, range.end
+                )?;
+                print_memory(&mut out, data, range.clone())?;
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// Parses `MEMORY:START..END` as given to `--memory`.
+fn parse_memory_range(s: &str) -> Result<(u32, Range<u64>)> {
+    fn parse_u64(s: &str) -> Result<u64> {
+        let result = match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse(),
+        };
+        result.with_context(|| format!("invalid memory offset `{s}`"))
+    }
+
+    let (memory, range) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected `MEMORY:START..END`, found `{s}`"))?;
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| anyhow!("expected `START..END`, found `{range}`"))?;
+    let memory = memory
+        .parse()
+        .with_context(|| format!("invalid memory index `{memory}`"))?;
+    let (start, end) = (parse_u64(start)?, parse_u64(end)?);
+    if start > end {
+        bail!("memory range start {start:#x} is after its end {end:#x}");
+    }
+    Ok((memory, start..end))
+}
+
+fn print_values(out: &mut String, label: &str, values: &[CoreDumpValue]) -> Result<()> {
+    if values.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "  {label}:")?;
+    for (i, value) in values.iter().enumerate() {
+        match value {
+            CoreDumpValue::Missing => writeln!(out, "    {i}: <missing>")?,
+            CoreDumpValue::I32(x) => writeln!(out, "    {i}: i32 {x}")?,
+            CoreDumpValue::I64(x) => writeln!(out, "    {i}: i64 {x}")?,
+            CoreDumpValue::F32(x) => writeln!(out, "    {i}: f32 {x}")?,
+            CoreDumpValue::F64(x) => writeln!(out, "    {i}: f64 {x}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Prints `range` of `memory` as a hex dump, clamped to the memory's size.
+fn print_memory(out: &mut String, memory: &Memory<'_>, range: Range<u64>) -> Result<()> {
+    let range = range.start.min(memory.size)..range.end.min(memory.size);
+    if range.is_empty() {
+        writeln!(
+            out,
+            "    <out of bounds, memory is {:#x} bytes>",
+            memory.size
+        )?;
+        return Ok(());
+    }
+    let bytes = memory.read(range.clone());
+    for (i, line) in bytes.chunks(16).enumerate() {
+        write!(out, "    {:08x}:", range.start + 16 * i as u64)?;
+        for byte in line {
+            write!(out, " {byte:02x}")?;
+        }
+        let ascii = line
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    char::from(*b)
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+        writeln!(
+            out,
+            "{:width$}  |{ascii}|",
+            "",
+            width = 3 * (16 - line.len())
+        )?;
+    }
+    Ok(())
+}
+
+/// The contents of a core dump, as described by the [tool conventions].
+///
+/// [tool conventions]: https://github.com/WebAssembly/tool-conventions/blob/main/Coredump.md
+struct CoreDump<'a> {
+    name: &'a str,
+    modules: Vec<&'a str>,
+    instances: Vec<CoreDumpInstance>,
+    thread: &'a str,
+    frames: Vec<CoreDumpStackFrame>,
+    memories: Vec<Memory<'a>>,
+    globals: Vec<GlobalValue>,
+}
+
+impl<'a> CoreDump<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let mut name = None;
+        let mut modules = Vec::new();
+        let mut instances = Vec::new();
+        let mut stack = None;
+        let mut memories = Vec::new();
+        let mut globals = Vec::new();
+
+        for payload in WasmParser::new(0).parse_all(bytes) {
+            match payload? {
+                Payload::MemorySection(reader) => {
+                    for ty in reader {
+                        let ty = ty?;
+                        let page_size = 1u64 << ty.page_size_log2.unwrap_or(16);
+                        memories.push(Memory {
+                            size: ty.initial.saturating_mul(page_size),
+                            segments: Vec::new(),
+                        });
+                    }
+                }
+                Payload::GlobalSection(reader) => {
+                    for global in reader {
+                        globals.push(GlobalValue::eval(&global?.init_expr)?);
+                    }
+                }
+                Payload::DataSection(reader) => {
+                    for data in reader {
+                        let data = data?;
+                        let wasmparser::DataKind::Active {
+                            memory_index,
+                            offset_expr,
+                        } = data.kind
+                        else {
+                            continue;
+                        };
+                        let offset = match GlobalValue::eval(&offset_expr)? {
+                            GlobalValue::I32(x) => u64::from(x as u32),
+                            GlobalValue::I64(x) => x as u64,
+                            _ => bail!("invalid data segment offset"),
+                        };
+                        let memory = memories
+                            .get_mut(memory_index as usize)
+                            .ok_or_else(|| anyhow!("data segment for unknown memory"))?;
+                        match offset.checked_add(data.data.len() as u64) {
+                            Some(end) if end <= memory.size => {}
+                            _ => bail!("data segment at offset {offset:#x} is out of bounds"),
+                        }
+                        memory.segments.push((offset, data.data));
+                    }
+                }
+                Payload::CustomSection(reader) => match reader.as_known() {
+                    KnownCustom::CoreDump(s) => name = Some(s.name),
+                    KnownCustom::CoreDumpModules(s) => modules = s.modules,
+                    KnownCustom::CoreDumpInstances(s) => instances = s.instances,
+                    KnownCustom::CoreDumpStack(s) => stack = Some(s),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        let name = name.ok_or_else(|| anyhow!("missing `core` custom section"))?;
+        let stack = stack.ok_or_else(|| anyhow!("missing `corestack` custom section"))?;
+        Ok(CoreDump {
+            name,
+            modules,
+            instances,
+            thread: stack.name,
+            frames: stack.frames,
+            memories,
+            globals,
+        })
+    }
+}
+
+/// A memory of a core dump, stored as the non-zero data segments which
+/// initialize it.
+struct Memory<'a> {
+    size: u64,
+    segments: Vec<(u64, &'a [u8])>,
+}
+
+impl Memory<'_> {
+    fn read(&self, range: Range<u64>) -> Vec<u8> {
+        let mut bytes = vec![0; (range.end - range.start) as usize];
+        for (offset, data) in &self.segments {
+            let start = range.start.max(*offset);
+            let end = range.end.min(*offset + data.len() as u64);
+            if start >= end {
+                continue;
+            }
+            bytes[(start - range.start) as usize..(end - range.start) as usize]
+                .copy_from_slice(&data[(start - offset) as usize..(end - offset) as usize]);
+        }
+        bytes
+    }
+}
+
+/// The value of a global in a core dump.
+///
+/// References are always recorded as null.
+enum GlobalValue {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    V128(i128),
+    Null,
+}
+
+impl GlobalValue {
+    fn eval(expr: &wasmparser::ConstExpr<'_>) -> Result<Self> {
+        let mut ops = expr.get_operators_reader();
+        let value = match ops.read()? {
+            Operator::I32Const { value } => GlobalValue::I32(value),
+            Operator::I64Const { value } => GlobalValue::I64(value),
+            Operator::F32Const { value } => GlobalValue::F32(f32::from_bits(value.bits())),
+            Operator::F64Const { value } => GlobalValue::F64(f64::from_bits(value.bits())),
+            Operator::V128Const { value } => GlobalValue::V128(value.i128()),
+            Operator::RefNull { .. } => GlobalValue::Null,
+            op => bail!("unsupported constant expression in core dump: {op:?}"),
+        };
+        match ops.read()? {
+            Operator::End => Ok(value),
+            op => bail!("unsupported constant expression in core dump: {op:?}"),
+        }
+    }
+}
+
+impl std::fmt::Display for GlobalValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GlobalValue::I32(x) => write!(f, "i32 {x}"),
+            GlobalValue::I64(x) => write!(f, "i64 {x}"),
+            GlobalValue::F32(x) => write!(f, "f32 {x}"),
+            GlobalValue::F64(x) => write!(f, "f64 {x}"),
+            GlobalValue::V128(x) => write!(f, "v128 {:#034x}", *x as u128),
+            GlobalValue::Null => write!(f, "null"),
+        }
+    }
+}
+
+/// Symbolication information extracted from an original Wasm module.
+struct ModuleInfo<'a> {
+    num_imported_funcs: u32,
+    func_names: HashMap<u32, &'a str>,
+    global_names: HashMap<u32, &'a str>,
+    /// The offset of each defined function's body within the module.
+    func_body_starts: Vec<u64>,
+    code_section_offset: u64,
+    dwarf: Option<Addr2LineContext<'a>>,
+}
+
+impl<'a> ModuleInfo<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let mut num_imported_funcs = 0;
+        let mut func_names = HashMap::new();
+        let mut global_names = HashMap::new();
+        let mut func_body_starts = Vec::new();
+        let mut code_section_offset = 0;
+        let mut debug_sections = HashMap::new();
+
+        for payload in WasmParser::new(0).parse_all(bytes) {
+            match payload? {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        if let wasmparser::TypeRef::Func(_) = import?.ty {
+                            num_imported_funcs += 1;
+                        }
+                    }
+                }
+                Payload::CodeSectionStart { range, .. } => {
+                    code_section_offset = range.start as u64;
+                }
+                Payload::CodeSectionEntry(body) => {
+                    func_body_starts.push(body.range().start as u64);
+                }
+                Payload::CustomSection(reader) => {
+                    if reader.name().starts_with(".debug_") {
+                        debug_sections.insert(reader.name(), reader.data());
+                        continue;
+                    }
+                    let KnownCustom::Name(names) = reader.as_known() else {
+                        continue;
+                    };
+                    // The name section is only informative, so ignore it if
+                    // it's malformed rather than failing to inspect the dump.
+                    for subsection in names.into_iter().flatten() {
+                        let (map, names) = match subsection {
+                            Name::Function(names) => (&mut func_names, names),
+                            Name::Global(names) => (&mut global_names, names),
+                            _ => continue,
+                        };
+                        for naming in names.into_iter().flatten() {
+                            map.insert(naming.index, naming.name);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let dwarf = if debug_sections.contains_key(".debug_info") {
+            let dwarf = gimli::Dwarf::load(|id| -> Result<_> {
+                let data = debug_sections.get(id.name()).copied().unwrap_or(&[]);
+                Ok(gimli::EndianSlice::new(data, gimli::LittleEndian))
+            })?;
+            Some(
+                addr2line::Context::from_dwarf(dwarf)
+                    .context("failed to create addr2line dwarf mapping context")?,
+            )
+        } else {
+            None
+        };
+
+        Ok(ModuleInfo {
+            num_imported_funcs,
+            func_names,
+            global_names,
+            func_body_starts,
+            code_section_offset,
+            dwarf,
+        })
+    }
+
+    /// Returns the offset within the module of the instruction `frame` is at.
+    fn module_offset(&self, frame: &CoreDumpStackFrame) -> Option<u64> {
+        let defined = frame.funcidx.checked_sub(self.num_imported_funcs)?;
+        let body_start = self.func_body_starts.get(defined as usize)?;
+        body_start.checked_add(u64::from(frame.codeoffset))
+    }
+
+    /// Looks up source locations for `frame` in the module's DWARF, returning
+    /// one line per (possibly inlined) function.
+    fn symbolicate(&self, frame: &CoreDumpStackFrame) -> Vec<String> {
+        let mut symbols = Vec::new();
+        let (Some(dwarf), Some(offset)) = (&self.dwarf, self.module_offset(frame)) else {
+            return symbols;
+        };
+
+        // DWARF addresses are relative to the start of the code section.
+        let Some(addr) = offset.checked_sub(self.code_section_offset) else {
+            return symbols;
+        };
+        let Ok(mut frames) = dwarf.find_frames(addr).skip_all_loads() else {
+            return symbols;
+        };
+        while let Ok(Some(frame)) = frames.next() {
+            let mut symbol = String::from("at ");
+            match frame.function.as_ref().and_then(|f| f.raw_name().ok()) {
+                Some(name) => {
+                    let _ = wasmtime_environ::demangle_function_name(&mut symbol, &name);
+                }
+                None => symbol.push_str("<unknown>"),
+            }
+            if let Some(location) = &frame.location {
+                let _ = write!(symbol, " {}", location.file.unwrap_or("<unknown>"));
+                if let Some(line) = location.line {
+                    let _ = write!(symbol, ":{line}");
+                    if let Some(column) = location.column {
+                        let _ = write!(symbol, ":{column}");
+                    }
+                }
+            }
+            symbols.push(symbol);
+        }
+        symbols
+    }
+}
@@ -30,7 +30,7 @@ use wasmtime_wasi_http::{
     WasiHttpCtx, DEFAULT_OUTGOING_BODY_BUFFER_CHUNKS, DEFAULT_OUTGOING_BODY_CHUNK_SIZE,
 };
 #[cfg(feature = "wasi-keyvalue")]
-use wasmtime_wasi_keyvalue::{WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder};
+use wasmtime_wasi_keyvalue::{DirBackend, WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder};
 
 fn parse_preloads(s: &str) -> Result<(String, PathBuf)> {
     let parts: Vec<&str> = s.splitn(2, '=').collect();
@@ -48,6 +48,11 @@ pub struct RunCommand {
     pub run: RunCommon,
 
     /// The name of the function to run
+    ///
+    /// For components this is a WAVE-encoded function call including its
+    /// arguments, for example `--invoke 'add(1, 2)'`. Functions exported
+    /// from nested instances are named by their path of export names joined
+    /// with `.`, for example `--invoke 'my:pkg/iface.add(1, 2)'`.
     #[arg(long, value_name = "FUNCTION")]
     pub invoke: Option<String>,
 
@@ -69,6 +74,26 @@ pub struct RunCommand {
     #[arg(long)]
     pub argv0: Option<String>,
 
+    /// Record every host call made by the WebAssembly program, along with its
+    /// results and its effects on memory, to the given trace file.
+    ///
+    /// The trace can later be passed to `--replay` to reproduce this
+    /// execution without calling into the host. Programs using shared
+    /// memories, such as with wasi-threads, can't be recorded.
+    #[cfg(feature = "record-replay")]
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Replay the host calls recorded with `--record` to the given trace file
+    /// instead of calling into the host.
+    ///
+    /// The same program must be run with the same arguments as when the trace
+    /// was recorded. Note that since the host isn't called no output, such as
+    /// writes to stdout, is produced while replaying.
+    #[cfg(feature = "record-replay")]
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+
     /// The WebAssembly module to run and arguments to pass to it.
     ///
     /// Arguments passed to the wasm module will be configured as WASI CLI
@@ -90,7 +115,11 @@ impl RunCommand {
         self.run.common.init_logging()?;
 
         let mut config = self.run.common.config(None)?;
-        config.async_support(true);
+
+        // Run asynchronously unless the stack-switching proposal is enabled
+        // since it's not compatible with async support.
+        let use_async = self.run.common.wasm.stack_switching != Some(true);
+        config.async_support(use_async);
 
         if self.run.common.wasm.timeout.is_some() {
             config.epoch_interruption(true);
@@ -99,7 +128,7 @@ impl RunCommand {
             Some(Profile::Native(s)) => {
                 config.profiler(s);
             }
-            Some(Profile::Guest { .. }) => {
+            Some(Profile::Guest { .. }) | Some(Profile::Data { .. }) => {
                 // Further configured down below as well.
                 config.epoch_interruption(true);
             }
@@ -113,6 +142,17 @@ impl RunCommand {
             .run
             .load_module(&engine, self.module_and_args[0].as_ref())?;
 
+        // Outgoing connections are only pooled between the requests handled
+        // by `wasmtime serve`.
+        let wasi = &self.run.common.wasi;
+        if wasi.http_connection_pool.is_some()
+            || wasi.http_connection_pool_idle_timeout.is_some()
+            || wasi.http_connection_pool_max_per_host.is_some()
+            || wasi.http_connection_pool_root_certificates.is_some()
+        {
+            bail!("the `-Shttp-connection-pool*` options are only supported by `wasmtime serve`");
+        }
+
         // Validate coredump-on-trap argument
         if let Some(path) = &self.run.common.debug.coredump {
             if path.contains("%") {
@@ -163,78 +203,113 @@ impl RunCommand {
             store.set_fuel(fuel)?;
         }
 
-        // Always run the module asynchronously to ensure that the module can be
-        // interrupted, even if it is blocking on I/O or a timeout or something.
-        let runtime = tokio::runtime::Builder::new_multi_thread()
-            .enable_time()
-            .enable_io()
-            .build()?;
+        // Writes to shared memories, which wasi-threads requires, can't be
+        // attributed to a host call.
+        #[cfg(feature = "record-replay")]
+        if (self.record.is_some() || self.replay.is_some())
+            && self.run.common.wasi.threads == Some(true)
+        {
+            bail!("`--record` and `--replay` cannot be used with wasi-threads");
+        }
+        #[cfg(feature = "record-replay")]
+        if let Some(path) = &self.record {
+            // Each host call is written out as soon as it returns, so the
+            // trace is complete even if the program exits the process.
+            let trace = std::fs::File::create(path)
+                .with_context(|| format!("failed to create trace file {}", path.display()))?;
+            store.record_host_calls(trace)?;
+        }
+        #[cfg(feature = "record-replay")]
+        if let Some(path) = &self.replay {
+            let trace = std::fs::File::open(path)
+                .with_context(|| format!("failed to open trace file {}", path.display()))?;
+            store.replay_host_calls(std::io::BufReader::new(trace))?;
+        }
 
-        let dur = self
-            .run
-            .common
-            .wasm
-            .timeout
-            .unwrap_or(std::time::Duration::MAX);
-        let result = runtime.block_on(async {
-            tokio::time::timeout(dur, async {
-                // Load the preload wasm modules.
-                let mut modules = Vec::new();
-                if let RunTarget::Core(m) = &main {
-                    modules.push((String::new(), m.clone()));
-                }
-                for (name, path) in self.preloads.iter() {
-                    // Read the wasm module binary either as `*.wat` or a raw binary
-                    let module = match self.run.load_module(&engine, path)? {
-                        RunTarget::Core(m) => m,
-                        #[cfg(feature = "component-model")]
-                        RunTarget::Component(_) => {
-                            bail!("components cannot be loaded with `--preload`")
-                        }
-                    };
-                    modules.push((name.clone(), module.clone()));
-
-                    // Add the module's functions to the linker.
-                    match &mut linker {
-                        #[cfg(feature = "cranelift")]
-                        CliLinker::Core(linker) => {
-                            linker
-                                .module_async(&mut store, name, &module)
-                                .await
-                                .context(format!(
-                                    "failed to process preload `{}` at `{}`",
-                                    name,
-                                    path.display()
-                                ))?;
-                        }
-                        #[cfg(not(feature = "cranelift"))]
-                        CliLinker::Core(_) => {
-                            bail!("support for --preload disabled at compile time");
-                        }
-                        #[cfg(feature = "component-model")]
-                        CliLinker::Component(_) => {
-                            bail!("--preload cannot be used with components");
+        let run = async {
+            // Load the preload wasm modules.
+            let mut modules = Vec::new();
+            if let RunTarget::Core(m) = &main {
+                modules.push((String::new(), m.clone()));
+            }
+            for (name, path) in self.preloads.iter() {
+                // Read the wasm module binary either as `*.wat` or a raw binary
+                let module = match self.run.load_module(&engine, path)? {
+                    RunTarget::Core(m) => m,
+                    #[cfg(feature = "component-model")]
+                    RunTarget::Component(_) => {
+                        bail!("components cannot be loaded with `--preload`")
+                    }
+                };
+                modules.push((name.clone(), module.clone()));
+
+                // Add the module's functions to the linker.
+                match &mut linker {
+                    #[cfg(feature = "cranelift")]
+                    CliLinker::Core(linker) => {
+                        if use_async {
+                            linker.module_async(&mut store, name, &module).await
+                        } else {
+                            linker.module(&mut store, name, &module)
                         }
+                        .context(format!(
+                            "failed to process preload `{}` at `{}`",
+                            name,
+                            path.display()
+                        ))?;
+                    }
+                    #[cfg(not(feature = "cranelift"))]
+                    CliLinker::Core(_) => {
+                        bail!("support for --preload disabled at compile time");
+                    }
+                    #[cfg(feature = "component-model")]
+                    CliLinker::Component(_) => {
+                        bail!("--preload cannot be used with components");
                     }
                 }
+            }
 
-                self.load_main_module(&mut store, &mut linker, &main, modules)
-                    .await
-                    .with_context(|| {
-                        format!(
-                            "failed to run main module `{}`",
-                            self.module_and_args[0].to_string_lossy()
-                        )
-                    })
-            })
-            .await
-        });
+            self.load_main_module(&mut store, &mut linker, &main, modules)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to run main module `{}`",
+                        self.module_and_args[0].to_string_lossy()
+                    )
+                })
+        };
+
+        let result = if use_async {
+            // Run the module asynchronously to ensure that the module can be
+            // interrupted, even if it is blocking on I/O or a timeout or
+            // something.
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .enable_time()
+                .enable_io()
+                .build()?;
+
+            let dur = self
+                .run
+                .common
+                .wasm
+                .timeout
+                .unwrap_or(std::time::Duration::MAX);
+            runtime
+                .block_on(async { tokio::time::timeout(dur, run).await })
+                .unwrap_or_else(|elapsed| {
+                    Err(anyhow::Error::from(wasmtime::Trap::Interrupt))
+                        .with_context(|| format!("timed out after {elapsed}"))
+                })
+        } else {
+            // Without async support none of the asynchronous APIs are used, so
+            // the future completes when first polled. The timeout is then only
+            // enforced by epoch interruption while executing WebAssembly.
+            wasmtime_wasi::runtime::poll_noop(std::pin::pin!(run))
+                .expect("synchronous execution should not suspend")
+        };
 
         // Load the main wasm module.
-        match result.unwrap_or_else(|elapsed| {
-            Err(anyhow::Error::from(wasmtime::Trap::Interrupt))
-                .with_context(|| format!("timed out after {elapsed}"))
-        }) {
+        match result {
             Ok(()) => (),
             Err(e) => {
                 // Exit the process if Wasmtime understands the error;
@@ -292,14 +367,18 @@ impl RunCommand {
     fn setup_epoch_handler(
         &self,
         store: &mut Store<Host>,
+        main_target: &RunTarget,
         modules: Vec<(String, Module)>,
     ) -> Result<Box<dyn FnOnce(&mut Store<Host>)>> {
-        if let Some(Profile::Guest { path, interval }) = &self.run.profile {
+        if let Some(Profile::Guest { path, interval } | Profile::Data { path, interval }) =
+            &self.run.profile
+        {
+            let data = matches!(self.run.profile, Some(Profile::Data { .. }));
             #[cfg(feature = "profiling")]
-            return Ok(self.setup_guest_profiler(store, modules, path, *interval));
+            return self.setup_guest_profiler(store, main_target, modules, path, *interval, data);
             #[cfg(not(feature = "profiling"))]
             {
-                let _ = (modules, path, interval);
+                let _ = (main_target, modules, path, interval, data);
                 bail!("support for profiling disabled at compile time");
             }
         }
@@ -320,15 +399,23 @@ impl RunCommand {
     fn setup_guest_profiler(
         &self,
         store: &mut Store<Host>,
+        main_target: &RunTarget,
         modules: Vec<(String, Module)>,
         path: &str,
         interval: std::time::Duration,
-    ) -> Box<dyn FnOnce(&mut Store<Host>)> {
+        data: bool,
+    ) -> Result<Box<dyn FnOnce(&mut Store<Host>)>> {
         use wasmtime::{AsContext, GuestProfiler, StoreContext, StoreContextMut, UpdateDeadline};
 
         let module_name = self.module_and_args[0].to_str().unwrap_or("<main module>");
-        store.data_mut().guest_profiler =
-            Some(Arc::new(GuestProfiler::new(module_name, interval, modules)));
+        let profiler = match main_target {
+            RunTarget::Core(_) => GuestProfiler::new(module_name, interval, modules),
+            #[cfg(feature = "component-model")]
+            RunTarget::Component(component) => {
+                GuestProfiler::new_component(module_name, interval, component.clone(), modules)
+            }
+        };
+        store.data_mut().guest_profiler = Some(Arc::new(profiler));
 
         fn sample(
             mut store: StoreContextMut<Host>,
@@ -377,20 +464,38 @@ impl RunCommand {
         });
 
         let path = path.to_string();
-        return Box::new(move |store| {
+        let main_target = main_target.clone();
+        return Ok(Box::new(move |store| {
             let profiler = Arc::try_unwrap(store.data_mut().guest_profiler.take().unwrap())
                 .expect("profiling doesn't support threads yet");
-            if let Err(e) = std::fs::File::create(&path)
+            let result = std::fs::File::create(&path)
                 .map_err(anyhow::Error::new)
-                .and_then(|output| profiler.finish(std::io::BufWriter::new(output)))
-            {
+                .and_then(|output| {
+                    let output = std::io::BufWriter::new(output);
+                    if !data {
+                        return profiler.finish(output);
+                    }
+                    let profile_data = match &main_target {
+                        RunTarget::Core(module) => profiler.profile_data(module),
+                        #[cfg(feature = "component-model")]
+                        RunTarget::Component(component) => {
+                            profiler.component_profile_data(component)
+                        }
+                    };
+                    Ok(serde_json::to_writer(output, profile_data.unwrap())?)
+                });
+            if let Err(e) = result {
                 eprintln!("failed writing profile at {path}: {e:#}");
+            } else if data {
+                eprintln!();
+                eprintln!("Profile data written to: {path}");
+                eprintln!("Use it with `wasmtime compile --profile-data {path}`.");
             } else {
                 eprintln!();
                 eprintln!("Profile written to: {path}");
                 eprintln!("View this profile at https://profiler.firefox.com/.");
             }
-        });
+        }));
     }
 
     async fn load_main_module(
@@ -424,25 +529,31 @@ impl RunCommand {
             }
         }
 
-        let finish_epoch_handler = self.setup_epoch_handler(store, modules)?;
+        let finish_epoch_handler = self.setup_epoch_handler(store, module, modules)?;
 
+        let use_async = store.engine().is_async();
         let result = match linker {
             CliLinker::Core(linker) => {
                 let module = module.unwrap_core();
-                let instance = linker
-                    .instantiate_async(&mut *store, &module)
-                    .await
-                    .context(format!(
-                        "failed to instantiate {:?}",
-                        self.module_and_args[0]
-                    ))?;
+                let instance = if use_async {
+                    linker.instantiate_async(&mut *store, &module).await
+                } else {
+                    linker.instantiate(&mut *store, &module)
+                }
+                .context(format!(
+                    "failed to instantiate {:?}",
+                    self.module_and_args[0]
+                ))?;
 
                 // If `_initialize` is present, meaning a reactor, then invoke
                 // the function.
                 if let Some(func) = instance.get_func(&mut *store, "_initialize") {
-                    func.typed::<(), ()>(&store)?
-                        .call_async(&mut *store, ())
-                        .await?;
+                    let func = func.typed::<(), ()>(&store)?;
+                    if use_async {
+                        func.call_async(&mut *store, ()).await?;
+                    } else {
+                        func.call(&mut *store, ())?;
+                    }
                 }
 
                 // Look for the specific function provided or otherwise look for
@@ -466,22 +577,33 @@ impl RunCommand {
             }
             #[cfg(feature = "component-model")]
             CliLinker::Component(linker) => {
-                if self.invoke.is_some() {
-                    bail!("using `--invoke` with components is not supported");
-                }
-
                 let component = module.unwrap_component();
 
-                let command = wasmtime_wasi::bindings::Command::instantiate_async(
-                    &mut *store,
-                    component,
-                    linker,
-                )
-                .await?;
-                let result = command
-                    .wasi_cli_run()
-                    .call_run(&mut *store)
-                    .await
+                if let Some(invoke) = &self.invoke {
+                    let result = self
+                        .invoke_component_func(store, linker, component, invoke)
+                        .await;
+                    finish_epoch_handler(store);
+                    return result;
+                }
+
+                let result = if use_async {
+                    let command = wasmtime_wasi::bindings::Command::instantiate_async(
+                        &mut *store,
+                        component,
+                        linker,
+                    )
+                    .await?;
+                    command.wasi_cli_run().call_run(&mut *store).await
+                } else {
+                    let command = wasmtime_wasi::bindings::sync::Command::instantiate(
+                        &mut *store,
+                        component,
+                        linker,
+                    )?;
+                    command.wasi_cli_run().call_run(&mut *store)
+                };
+                let result = result
                     .context("failed to invoke `run` function")
                     .map_err(|e| self.handle_core_dump(&mut *store, e));
 
@@ -537,16 +659,18 @@ impl RunCommand {
         // Invoke the function and then afterwards print all the results that came
         // out, if there are any.
         let mut results = vec![Val::null_func_ref(); ty.results().len()];
-        let invoke_res = func
-            .call_async(&mut *store, &values, &mut results)
-            .await
-            .with_context(|| {
-                if let Some(name) = &self.invoke {
-                    format!("failed to invoke `{name}`")
-                } else {
-                    format!("failed to invoke command default")
-                }
-            });
+        let invoke_res = if store.engine().is_async() {
+            func.call_async(&mut *store, &values, &mut results).await
+        } else {
+            func.call(&mut *store, &values, &mut results)
+        }
+        .with_context(|| {
+            if let Some(name) = &self.invoke {
+                format!("failed to invoke `{name}`")
+            } else {
+                format!("failed to invoke command default")
+            }
+        });
 
         if let Err(err) = invoke_res {
             return Err(self.handle_core_dump(&mut *store, err));
@@ -578,6 +702,88 @@ impl RunCommand {
         Ok(())
     }
 
+    /// Invokes the component function described by `invoke`, which is of the
+    /// form `path.to.func(arg1, arg2, ...)` with WAVE-encoded arguments.
+    ///
+    /// Functions nested within exported instances are named by joining the
+    /// export names with `.` (or `#`), for example
+    /// `wasi:cli/run@0.2.0.run()`. Results are printed to stdout in WAVE
+    /// syntax, one per line.
+    #[cfg(feature = "component-model")]
+    async fn invoke_component_func(
+        &self,
+        store: &mut Store<Host>,
+        linker: &mut wasmtime::component::Linker<Host>,
+        component: &wasmtime::component::Component,
+        invoke: &str,
+    ) -> Result<()> {
+        use wasmtime::component::{
+            types::ComponentItem,
+            wasm_wave::{self, untyped::UntypedFuncCall},
+            Val,
+        };
+
+        // Split off the export path from the WAVE function call. The final
+        // segment of the path is the function's name which, together with
+        // the parenthesized arguments, forms a WAVE function call.
+        let args_start = invoke.find('(').unwrap_or(invoke.len());
+        let (path, _) = invoke.split_at(args_start);
+        let name_start = path.rfind(['.', '#']).map(|i| i + 1).unwrap_or(0);
+        let mut call_source = invoke[name_start..].to_string();
+        if args_start == invoke.len() {
+            call_source.push_str("()");
+        }
+        let call = UntypedFuncCall::parse(&call_source)
+            .map_err(|e| anyhow!("failed to parse `--invoke` argument `{invoke}`: {e}"))?;
+
+        let (ty, index) = resolve_component_export(store.engine(), component, path)?;
+        let ty = match ty {
+            ComponentItem::ComponentFunc(ty) => ty,
+            _ => bail!("export `{path}` is not a function"),
+        };
+
+        let param_types = ty.params().map(|(_, ty)| ty).collect::<Vec<_>>();
+        let params = call
+            .to_wasm_params::<Val>(&param_types)
+            .map_err(|e| anyhow!("failed to parse arguments for `{path}`: {e}"))?;
+
+        let use_async = store.engine().is_async();
+        let instance = if use_async {
+            linker.instantiate_async(&mut *store, component).await
+        } else {
+            linker.instantiate(&mut *store, component)
+        }
+        .context(format!(
+            "failed to instantiate {:?}",
+            self.module_and_args[0]
+        ))?;
+        let func = instance
+            .get_func(&mut *store, &index)
+            .ok_or_else(|| anyhow!("no func export named `{path}` found"))?;
+
+        let mut results = vec![Val::Bool(false); ty.results().len()];
+        let invoke_res = if use_async {
+            func.call_async(&mut *store, &params, &mut results).await
+        } else {
+            func.call(&mut *store, &params, &mut results)
+        }
+        .with_context(|| format!("failed to invoke `{path}`"));
+        if let Err(err) = invoke_res {
+            return Err(self.handle_core_dump(&mut *store, err));
+        }
+        if use_async {
+            func.post_return_async(&mut *store).await?;
+        } else {
+            func.post_return(&mut *store)?;
+        }
+
+        for result in results {
+            println!("{}", wasm_wave::to_string(&result)?);
+        }
+
+        Ok(())
+    }
+
     #[cfg(feature = "coredump")]
     fn handle_core_dump(&self, store: &mut Store<Host>, err: Error) -> Error {
         let coredump_path = match &self.run.common.debug.coredump {
@@ -634,9 +840,15 @@ impl RunCommand {
                         // are enabled, then use the historical preview1
                         // implementation.
                         (Some(false), _) | (None, Some(true)) => {
-                            wasi_common::tokio::add_to_linker(linker, |host| {
-                                host.preview1_ctx.as_mut().unwrap()
-                            })?;
+                            if store.engine().is_async() {
+                                wasi_common::tokio::add_to_linker(linker, |host| {
+                                    host.preview1_ctx.as_mut().unwrap()
+                                })?;
+                            } else {
+                                wasi_common::sync::add_to_linker(linker, |host| {
+                                    host.preview1_ctx.as_mut().unwrap()
+                                })?;
+                            }
                             self.set_preview1_ctx(store)?;
                         }
                         // If preview2 was explicitly requested, always use it.
@@ -646,22 +858,39 @@ impl RunCommand {
                         // default-enabled but this may turn into
                         // default-disabled in the future.
                         (Some(true), _) | (None, Some(false) | None) => {
-                            if self.run.common.wasi.preview0 != Some(false) {
-                                wasmtime_wasi::preview0::add_to_linker_async(linker, |t| {
+                            let preview0 = self.run.common.wasi.preview0 != Some(false);
+                            if store.engine().is_async() {
+                                if preview0 {
+                                    wasmtime_wasi::preview0::add_to_linker_async(linker, |t| {
+                                        t.preview2_ctx()
+                                    })?;
+                                }
+                                wasmtime_wasi::preview1::add_to_linker_async(linker, |t| {
+                                    t.preview2_ctx()
+                                })?;
+                            } else {
+                                if preview0 {
+                                    wasmtime_wasi::preview0::add_to_linker_sync(linker, |t| {
+                                        t.preview2_ctx()
+                                    })?;
+                                }
+                                wasmtime_wasi::preview1::add_to_linker_sync(linker, |t| {
                                     t.preview2_ctx()
                                 })?;
                             }
-                            wasmtime_wasi::preview1::add_to_linker_async(linker, |t| {
-                                t.preview2_ctx()
-                            })?;
                             self.set_preview2_ctx(store)?;
                         }
                     }
                 }
                 #[cfg(feature = "component-model")]
                 CliLinker::Component(linker) => {
-                    let link_options = self.run.compute_wasi_features();
-                    wasmtime_wasi::add_to_linker_with_options_async(linker, &link_options)?;
+                    if store.engine().is_async() {
+                        let link_options = self.run.compute_wasi_features();
+                        wasmtime_wasi::add_to_linker_with_options_async(linker, &link_options)?;
+                    } else {
+                        let link_options = self.run.compute_wasi_sync_features();
+                        wasmtime_wasi::add_to_linker_with_options_sync(linker, &link_options)?;
+                    }
                     self.set_preview2_ctx(store)?;
                 }
             }
@@ -750,16 +979,20 @@ impl RunCommand {
                         bail!("Cannot enable wasi-keyvalue for core wasm modules");
                     }
                     CliLinker::Component(linker) => {
-                        let ctx = WasiKeyValueCtxBuilder::new()
-                            .in_memory_data(
-                                self.run
-                                    .common
-                                    .wasi
-                                    .keyvalue_in_memory_data
-                                    .iter()
-                                    .map(|v| (v.key.clone(), v.value.clone())),
-                            )
-                            .build();
+                        let mut builder = WasiKeyValueCtxBuilder::new().in_memory_data(
+                            self.run
+                                .common
+                                .wasi
+                                .keyvalue_in_memory_data
+                                .iter()
+                                .map(|v| (v.key.clone(), v.value.clone())),
+                        );
+                        if let Some(dir) = &self.run.common.wasi.keyvalue_dir {
+                            builder = builder.backend(DirBackend::new(dir).with_context(|| {
+                                format!("failed to open key-value directory `{dir}`")
+                            })?);
+                        }
+                        let ctx = builder.build();
 
                         wasmtime_wasi_keyvalue::add_to_linker(linker, |h| {
                             let preview2_ctx =
@@ -829,6 +1062,9 @@ impl RunCommand {
     }
 
     fn set_preview1_ctx(&self, store: &mut Store<Host>) -> Result<()> {
+        if self.run.common.wasm.deterministic == Some(true) {
+            bail!("deterministic execution is not supported with the legacy WASIp1 implementation");
+        }
         let mut builder = WasiCtxBuilder::new();
         builder.inherit_stdio().args(&self.compute_argv()?)?;
 
@@ -1026,3 +1262,55 @@ fn write_core_dump(
         .with_context(|| format!("failed to write core dump file at `{path}`"))?;
     Ok(())
 }
+
+/// Resolves a `.`- or `#`-separated export path, such as
+/// `wasi:cli/run@0.2.0.run`, to the type and index of the export it names.
+///
+/// Export names may themselves contain `.` (for example in package
+/// versions), so at each level the longest export name that is a prefix of
+/// the remaining path is selected.
+#[cfg(feature = "component-model")]
+fn resolve_component_export(
+    engine: &Engine,
+    component: &wasmtime::component::Component,
+    path: &str,
+) -> Result<(
+    wasmtime::component::types::ComponentItem,
+    wasmtime::component::ComponentExportIndex,
+)> {
+    use wasmtime::component::types::ComponentItem;
+
+    let mut names = component
+        .component_type()
+        .exports(engine)
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+    let mut instance = None;
+    let mut rest = path;
+    loop {
+        let name = names
+            .iter()
+            .filter(|name| match rest.strip_prefix(name.as_str()) {
+                Some(suffix) => suffix.is_empty() || suffix.starts_with(['.', '#']),
+                None => false,
+            })
+            .max_by_key(|name| name.len())
+            .ok_or_else(|| anyhow!("no export named `{rest}` found in `{path}`"))?;
+        let (item, index) = component
+            .export_index(instance.as_ref(), name)
+            .ok_or_else(|| anyhow!("no export named `{name}` found"))?;
+        rest = &rest[name.len()..];
+        if rest.is_empty() {
+            return Ok((item, index));
+        }
+        rest = &rest[1..];
+        names = match &item {
+            ComponentItem::ComponentInstance(ty) => ty
+                .exports(engine)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            _ => bail!("export `{name}` in `{path}` is not an instance"),
+        };
+        instance = Some(index);
+    }
+}
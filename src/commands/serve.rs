@@ -1,29 +1,32 @@
 use crate::common::{Profile, RunCommon, RunTarget};
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, Context as _, Result};
 use clap::Parser;
 use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
+use tokio::sync::{mpsc, Semaphore};
+use tracing::Instrument;
 use wasmtime::component::Linker;
-use wasmtime::{Engine, Store, StoreLimits};
+use wasmtime::{Engine, Store, StoreLimits, UpdateDeadline};
 use wasmtime_wasi::{IoView, StreamError, StreamResult, WasiCtx, WasiCtxBuilder, WasiView};
 use wasmtime_wasi_http::bindings::http::types::Scheme;
-use wasmtime_wasi_http::bindings::ProxyPre;
-use wasmtime_wasi_http::io::TokioIo;
+use wasmtime_wasi_http::bindings::{Proxy, ProxyPre};
+use wasmtime_wasi_http::io::{TokioExecutor, TokioIo};
 use wasmtime_wasi_http::{
-    body::HyperOutgoingBody, WasiHttpCtx, WasiHttpView, DEFAULT_OUTGOING_BODY_BUFFER_CHUNKS,
-    DEFAULT_OUTGOING_BODY_CHUNK_SIZE,
+    body::HyperOutgoingBody, ConnectionPool, WasiHttpCtx, WasiHttpView,
+    DEFAULT_OUTGOING_BODY_BUFFER_CHUNKS, DEFAULT_OUTGOING_BODY_CHUNK_SIZE,
 };
 
 #[cfg(feature = "wasi-config")]
 use wasmtime_wasi_config::{WasiConfig, WasiConfigVariables};
 #[cfg(feature = "wasi-keyvalue")]
-use wasmtime_wasi_keyvalue::{WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder};
+use wasmtime_wasi_keyvalue::{DirBackend, WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder};
 #[cfg(feature = "wasi-nn")]
 use wasmtime_wasi_nn::wit::WasiNnCtx;
 
@@ -44,6 +47,9 @@ struct Host {
 
     #[cfg(feature = "wasi-keyvalue")]
     wasi_keyvalue: Option<WasiKeyValueCtx>,
+
+    #[cfg(feature = "profiling")]
+    guest_profiler: Option<wasmtime::GuestProfiler>,
 }
 
 impl IoView for Host {
@@ -93,6 +99,35 @@ pub struct ServeCommand {
     #[arg(long = "no-logging-prefix")]
     no_logging_prefix: bool,
 
+    /// Path to a PEM-encoded certificate chain to serve HTTPS with.
+    ///
+    /// When this is specified connections are accepted over TLS and both
+    /// HTTP/2 and HTTP/1.1 are offered to clients through ALPN.
+    #[arg(long = "tls-cert", value_name = "PATH", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Path to the PEM-encoded private key for the certificate passed to
+    /// `--tls-cert`.
+    #[arg(long = "tls-key", value_name = "PATH", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Number of instances of the component to keep pre-instantiated, ready
+    /// to handle incoming requests.
+    ///
+    /// Each request is still handled by a fresh instance: instances are taken
+    /// from the pool as requests arrive and the pool is refilled in the
+    /// background. By default no instances are kept and each request
+    /// instantiates the component when it arrives.
+    #[arg(long = "instance-pool-size", value_name = "N", default_value_t = 0)]
+    instance_pool_size: usize,
+
+    /// Maximum number of requests to handle concurrently.
+    ///
+    /// Requests arriving while this many are in flight wait for one to finish
+    /// before being handled. By default there is no limit.
+    #[arg(long = "max-concurrent-requests", value_name = "N")]
+    max_concurrent_requests: Option<usize>,
+
     /// The WebAssembly component to run.
     #[arg(value_name = "WASM", required = true)]
     component: PathBuf,
@@ -106,7 +141,13 @@ impl ServeCommand {
         // We force cli errors before starting to listen for connections so then
         // we don't accidentally delay them to the first request.
         if let Some(Profile::Guest { .. }) = &self.run.profile {
-            bail!("Cannot use the guest profiler with components");
+            #[cfg(not(feature = "profiling"))]
+            {
+                bail!("support for profiling disabled at compile time");
+            }
+        }
+        if let Some(Profile::Data { .. }) = &self.run.profile {
+            bail!("profile data can only be collected for core wasm modules");
         }
 
         if self.run.common.wasi.nn == Some(true) {
@@ -120,6 +161,19 @@ impl ServeCommand {
             bail!("wasi-threads does not support components yet")
         }
 
+        // Requests are always handled asynchronously, which stack-switching is
+        // not compatible with.
+        if self.run.common.wasm.stack_switching == Some(true) {
+            bail!("stack-switching is not supported with the `serve` subcommand");
+        }
+
+        if self.max_concurrent_requests == Some(0) {
+            bail!("`--max-concurrent-requests` must be greater than zero");
+        }
+        if self.run.common.wasi.http_connection_pool_max_per_host == Some(0) {
+            bail!("`-Shttp-connection-pool-max-per-host` must be greater than zero");
+        }
+
         // The serve command requires both wasi-http and the component model, so
         // we enable those by default here.
         if self.run.common.wasi.http.replace(true) == Some(false) {
@@ -149,105 +203,76 @@ impl ServeCommand {
         Ok(())
     }
 
-    fn new_store(&self, engine: &Engine, req_id: u64) -> Result<Store<Host>> {
-        let mut builder = WasiCtxBuilder::new();
-        self.run.configure_wasip2(&mut builder)?;
-
-        builder.env("REQUEST_ID", req_id.to_string());
-
-        let stdout_prefix: String;
-        let stderr_prefix: String;
-        if self.no_logging_prefix {
-            stdout_prefix = "".to_string();
-            stderr_prefix = "".to_string();
-        } else {
-            stdout_prefix = format!("stdout [{req_id}] :: ");
-            stderr_prefix = format!("stderr [{req_id}] :: ");
+    /// Creates the `wasi-keyvalue` context shared by all requests, if
+    /// `-Skeyvalue` is enabled, so that a storage backend is shared between
+    /// instances.
+    #[cfg(feature = "wasi-keyvalue")]
+    fn wasi_keyvalue_ctx(&self) -> Result<Option<WasiKeyValueCtx>> {
+        if self.run.common.wasi.keyvalue != Some(true) {
+            return Ok(None);
         }
-        builder.stdout(LogStream::new(stdout_prefix, Output::Stdout));
-        builder.stderr(LogStream::new(stderr_prefix, Output::Stderr));
-
-        let mut host = Host {
-            table: wasmtime::component::ResourceTable::new(),
-            ctx: builder.build(),
-            http: WasiHttpCtx::new(),
-            http_outgoing_body_buffer_chunks: self.run.common.wasi.http_outgoing_body_buffer_chunks,
-            http_outgoing_body_chunk_size: self.run.common.wasi.http_outgoing_body_chunk_size,
-
-            limits: StoreLimits::default(),
-
-            #[cfg(feature = "wasi-nn")]
-            nn: None,
-            #[cfg(feature = "wasi-config")]
-            wasi_config: None,
-            #[cfg(feature = "wasi-keyvalue")]
-            wasi_keyvalue: None,
-        };
-
-        if self.run.common.wasi.nn == Some(true) {
-            #[cfg(feature = "wasi-nn")]
-            {
-                let graphs = self
-                    .run
-                    .common
-                    .wasi
-                    .nn_graph
-                    .iter()
-                    .map(|g| (g.format.clone(), g.dir.clone()))
-                    .collect::<Vec<_>>();
-                let (backends, registry) = wasmtime_wasi_nn::preload(&graphs)?;
-                host.nn.replace(WasiNnCtx::new(backends, registry));
-            }
+        let mut builder = WasiKeyValueCtxBuilder::new().in_memory_data(
+            self.run
+                .common
+                .wasi
+                .keyvalue_in_memory_data
+                .iter()
+                .map(|v| (v.key.clone(), v.value.clone())),
+        );
+        if let Some(dir) = &self.run.common.wasi.keyvalue_dir {
+            builder = builder.backend(
+                DirBackend::new(dir)
+                    .with_context(|| format!("failed to open key-value directory `{dir}`"))?,
+            );
         }
+        Ok(Some(builder.build()))
+    }
 
-        if self.run.common.wasi.config == Some(true) {
-            #[cfg(feature = "wasi-config")]
-            {
-                let vars = WasiConfigVariables::from_iter(
-                    self.run
-                        .common
-                        .wasi
-                        .config_var
-                        .iter()
-                        .map(|v| (v.key.clone(), v.value.clone())),
-                );
-                host.wasi_config.replace(vars);
-            }
+    /// Creates the pool of outgoing connections shared between requests as
+    /// configured by the `-Shttp-connection-pool*` options, or `None` if
+    /// pooling is disabled.
+    fn connection_pool(&self) -> Result<Option<ConnectionPool>> {
+        let wasi = &self.run.common.wasi;
+        if wasi.http_connection_pool == Some(false) {
+            return Ok(None);
         }
-
-        if self.run.common.wasi.keyvalue == Some(true) {
-            #[cfg(feature = "wasi-keyvalue")]
-            {
-                let ctx = WasiKeyValueCtxBuilder::new()
-                    .in_memory_data(
-                        self.run
-                            .common
-                            .wasi
-                            .keyvalue_in_memory_data
-                            .iter()
-                            .map(|v| (v.key.clone(), v.value.clone())),
-                    )
-                    .build();
-                host.wasi_keyvalue.replace(ctx);
-            }
+        let mut builder = ConnectionPool::builder();
+        if let Some(timeout) = wasi.http_connection_pool_idle_timeout {
+            builder.idle_timeout(timeout);
         }
-
-        let mut store = Store::new(engine, host);
-
-        if self.run.common.wasm.timeout.is_some() {
-            store.set_epoch_deadline(u64::from(EPOCH_PRECISION) + 1);
+        if let Some(max) = wasi.http_connection_pool_max_per_host {
+            builder.max_connections_per_host(max);
         }
+        if let Some(path) = &wasi.http_connection_pool_root_certificates {
+            #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+            builder.root_certificates(root_certificates(Path::new(path))?);
+            #[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
+            bail!("TLS is not supported on this platform, cannot use `{path}`");
+        }
+        Ok(Some(builder.build()))
+    }
 
-        store.data_mut().limits = self.run.store_limits();
-        store.limiter(|t| &mut t.limits);
-
-        // If fuel has been configured, we want to add the configured
-        // fuel amount to this store.
-        if let Some(fuel) = self.run.common.wasm.fuel {
-            store.set_fuel(fuel)?;
+    /// Returns the sampling interval of the guest profiler, if it's enabled.
+    fn guest_profile_interval(&self) -> Option<Duration> {
+        match &self.run.profile {
+            Some(Profile::Guest { interval, .. }) => Some(*interval),
+            _ => None,
         }
+    }
 
-        Ok(store)
+    /// Returns the number of epoch ticks after which a request times out, if
+    /// a timeout is configured.
+    ///
+    /// Epochs tick at the sampling interval when the guest profiler is
+    /// enabled, and at `timeout / EPOCH_PRECISION` otherwise.
+    fn timeout_ticks(&self) -> Option<u64> {
+        let timeout = self.run.common.wasm.timeout?;
+        Some(match self.guest_profile_interval() {
+            Some(interval) => {
+                ((timeout.as_secs_f64() / interval.as_secs_f64()).ceil() as u64).max(1)
+            }
+            None => u64::from(EPOCH_PRECISION) + 1,
+        })
     }
 
     fn add_to_linker(&self, linker: &mut Linker<Host>) -> Result<()> {
@@ -334,8 +359,6 @@ impl ServeCommand {
     }
 
     async fn serve(mut self) -> Result<()> {
-        use hyper::server::conn::http1;
-
         let mut config = self
             .run
             .common
@@ -352,10 +375,13 @@ impl ServeCommand {
                 config.profiler(s);
             }
 
-            // We bail early in `execute` if the guest profiler is configured.
-            Some(Profile::Guest { .. }) => unreachable!(),
+            Some(Profile::Guest { .. }) => {
+                // Samples are taken from the epoch deadline callback of each
+                // request's store.
+                config.epoch_interruption(true);
+            }
 
-            None => {}
+            Some(Profile::Data { .. }) | None => {}
         }
 
         let engine = Engine::new(&config)?;
@@ -371,7 +397,25 @@ impl ServeCommand {
         let instance = linker.instantiate_pre(&component)?;
         let instance = ProxyPre::new(instance)?;
 
-        let socket = match &self.addr {
+        let tls = match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Some(tls_acceptor(cert, key)?),
+            _ => None,
+        };
+
+        let _epoch_thread = match (self.guest_profile_interval(), self.run.common.wasm.timeout) {
+            (Some(interval), _) => Some(EpochThread::spawn(interval, engine.clone())),
+            (None, Some(timeout)) => Some(EpochThread::spawn(
+                timeout / EPOCH_PRECISION,
+                engine.clone(),
+            )),
+            (None, None) => None,
+        };
+
+        let addr = self.addr;
+        let instance_pool_size = self.instance_pool_size;
+        let (handler, ready) = ProxyHandler::new(self, engine, instance)?;
+
+        let socket = match &addr {
             SocketAddr::V4(_) => tokio::net::TcpSocket::new_v4()?,
             SocketAddr::V6(_) => tokio::net::TcpSocket::new_v6()?,
         };
@@ -384,37 +428,32 @@ impl ServeCommand {
         // this is conditionally set based on the platform (and deviates from
         // Tokio's default from always-on).
         socket.set_reuseaddr(!cfg!(windows))?;
-        socket.bind(self.addr)?;
+        socket.bind(addr)?;
         let listener = socket.listen(100)?;
 
-        eprintln!("Serving HTTP on http://{}/", listener.local_addr()?);
-
-        let _epoch_thread = if let Some(timeout) = self.run.common.wasm.timeout {
-            Some(EpochThread::spawn(
-                timeout / EPOCH_PRECISION,
-                engine.clone(),
-            ))
+        if tls.is_some() {
+            eprintln!("Serving HTTPS on https://{}/", listener.local_addr()?);
         } else {
-            None
-        };
+            eprintln!("Serving HTTP on http://{}/", listener.local_addr()?);
+        }
 
-        log::info!("Listening on {}", self.addr);
+        log::info!("Listening on {addr}");
 
-        let handler = ProxyHandler::new(self, engine, instance);
+        if let Some(ready) = ready {
+            log::info!("Keeping {instance_pool_size} instances ready for requests");
+            tokio::task::spawn(refill_instance_pool(handler.clone(), ready));
+        }
 
         loop {
             let (stream, _) = listener.accept().await?;
-            let stream = TokioIo::new(stream);
             let h = handler.clone();
-            tokio::task::spawn(async {
-                if let Err(e) = http1::Builder::new()
-                    .keep_alive(true)
-                    .serve_connection(
-                        stream,
-                        hyper::service::service_fn(move |req| handle_request(h.clone(), req)),
-                    )
-                    .await
-                {
+            let tls = tls.clone();
+            tokio::task::spawn(async move {
+                let result = match tls {
+                    Some(tls) => serve_tls_connection(tls, stream, h).await,
+                    None => serve_http1(TokioIo::new(stream), h, Scheme::Http).await,
+                };
+                if let Err(e) = result {
                     eprintln!("error: {e:?}");
                 }
             });
@@ -422,6 +461,132 @@ impl ServeCommand {
     }
 }
 
+/// Serves HTTP/1.1 requests arriving on `stream`, reporting them to the guest
+/// with the `scheme` provided.
+async fn serve_http1<S>(stream: S, handler: ProxyHandler, scheme: Scheme) -> Result<()>
+where
+    S: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+{
+    hyper::server::conn::http1::Builder::new()
+        .keep_alive(true)
+        .serve_connection(
+            stream,
+            hyper::service::service_fn(move |req| {
+                handle_request(handler.clone(), scheme.clone(), req)
+            }),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Serves HTTP/2 requests arriving on `stream`, reporting them to the guest
+/// with the `scheme` provided.
+async fn serve_http2<S>(stream: S, handler: ProxyHandler, scheme: Scheme) -> Result<()>
+where
+    S: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+{
+    hyper::server::conn::http2::Builder::new(TokioExecutor)
+        .serve_connection(
+            stream,
+            hyper::service::service_fn(move |req| {
+                handle_request(handler.clone(), scheme.clone(), req)
+            }),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+type TlsAcceptor = tokio_rustls::TlsAcceptor;
+
+// The `ring` crate, used to implement TLS, does not build on riscv64 or s390x
+// so `--tls-cert` is rejected on those platforms.
+#[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
+#[derive(Clone)]
+enum TlsAcceptor {}
+
+/// Creates the acceptor used to terminate TLS connections from the PEM-encoded
+/// certificate chain and private key at `cert` and `key`.
+#[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+fn tls_acceptor(cert: &Path, key: &Path) -> Result<TlsAcceptor> {
+    use tokio_rustls::rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
+    use tokio_rustls::rustls::ServerConfig;
+
+    let read = |path: &Path| {
+        std::fs::read(path).with_context(|| format!("failed to read `{}`", path.display()))
+    };
+
+    let certs = CertificateDer::pem_slice_iter(&read(cert)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow!("invalid certificate in `{}`: {e}", cert.display()))?;
+    if certs.is_empty() {
+        bail!("no certificates found in `{}`", cert.display());
+    }
+
+    let key = PrivateKeyDer::from_pem_slice(&read(key)?)
+        .map_err(|e| anyhow!("no private key found in `{}`: {e}", key.display()))?;
+
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("invalid TLS certificate or private key")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+#[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
+fn tls_acceptor(_cert: &Path, _key: &Path) -> Result<TlsAcceptor> {
+    bail!("TLS is not supported on this platform")
+}
+
+/// Reads the PEM-encoded root certificates at `path` used to verify servers
+/// of outgoing `https` connections.
+#[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+fn root_certificates(path: &Path) -> Result<tokio_rustls::rustls::RootCertStore> {
+    use tokio_rustls::rustls::pki_types::{pem::PemObject, CertificateDer};
+
+    let pem =
+        std::fs::read(path).with_context(|| format!("failed to read `{}`", path.display()))?;
+    let mut roots = tokio_rustls::rustls::RootCertStore::empty();
+    for cert in CertificateDer::pem_slice_iter(&pem) {
+        let cert = cert.map_err(|e| anyhow!("invalid certificate in `{}`: {e}", path.display()))?;
+        roots
+            .add(cert)
+            .with_context(|| format!("invalid root certificate in `{}`", path.display()))?;
+    }
+    if roots.is_empty() {
+        bail!("no certificates found in `{}`", path.display());
+    }
+    Ok(roots)
+}
+
+/// Completes the TLS handshake on `stream` and then serves either HTTP/2 or
+/// HTTP/1.1 depending on the protocol negotiated through ALPN.
+#[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+async fn serve_tls_connection(
+    tls: TlsAcceptor,
+    stream: tokio::net::TcpStream,
+    handler: ProxyHandler,
+) -> Result<()> {
+    let stream = tls.accept(stream).await.context("TLS handshake failed")?;
+    let h2 = stream.get_ref().1.alpn_protocol() == Some(b"h2");
+    let stream = TokioIo::new(stream);
+    if h2 {
+        serve_http2(stream, handler, Scheme::Https).await
+    } else {
+        serve_http1(stream, handler, Scheme::Https).await
+    }
+}
+
+#[cfg(any(target_arch = "riscv64", target_arch = "s390x"))]
+async fn serve_tls_connection(
+    tls: TlsAcceptor,
+    _stream: tokio::net::TcpStream,
+    _handler: ProxyHandler,
+) -> Result<()> {
+    match tls {}
+}
+
 /// This is the number of epochs that we will observe before expiring a request handler. As
 /// instances may be started at any point within an epoch, and epochs are counted globally per
 /// engine, we expire after `EPOCH_PRECISION + 1` epochs have been observed. This gives a maximum
@@ -465,62 +630,404 @@ struct ProxyHandlerInner {
     engine: Engine,
     instance_pre: ProxyPre<Host>,
     next_id: AtomicU64,
+    instance_pool: Option<InstancePool>,
+    concurrency: Option<Arc<Semaphore>>,
+    /// Outgoing connections shared by all requests so that they're reused
+    /// between instances, unless disabled with `-Shttp-connection-pool=n`.
+    connection_pool: Option<ConnectionPool>,
+    #[cfg(feature = "wasi-keyvalue")]
+    wasi_keyvalue: Option<WasiKeyValueCtx>,
 }
 
 impl ProxyHandlerInner {
     fn next_req_id(&self) -> u64 {
         self.next_id.fetch_add(1, Ordering::Relaxed)
     }
+
+    /// Starts collecting a guest profile of the request `req_id`, sampling
+    /// `store` every `interval` until it's written out by
+    /// `finish_guest_profiler`.
+    #[cfg(feature = "profiling")]
+    fn start_guest_profiler(&self, store: &mut Store<Host>, req_id: u64, interval: Duration) {
+        use wasmtime::{AsContext, GuestProfiler, StoreContext, StoreContextMut};
+
+        let component = self.instance_pre.instance_pre().component().clone();
+        let name = format!("{} [{req_id}]", self.cmd.component.display());
+        store.data_mut().guest_profiler = Some(GuestProfiler::new_component(
+            &name,
+            interval,
+            component,
+            Vec::new(),
+        ));
+
+        fn sample(
+            mut store: StoreContextMut<Host>,
+            f: impl FnOnce(&mut GuestProfiler, StoreContext<Host>),
+        ) {
+            let mut profiler = store.data_mut().guest_profiler.take().unwrap();
+            f(&mut profiler, store.as_context());
+            store.data_mut().guest_profiler = Some(profiler);
+        }
+
+        store.call_hook(|store, kind| {
+            sample(store, |profiler, store| profiler.call_hook(store, kind));
+            Ok(())
+        });
+
+        let mut remaining = self.cmd.timeout_ticks();
+        store.epoch_deadline_callback(move |store| {
+            sample(store, |profiler, store| {
+                profiler.sample(store, Duration::ZERO)
+            });
+            if let Some(remaining) = &mut remaining {
+                *remaining -= 1;
+                if *remaining == 0 {
+                    return Err(wasmtime::Trap::Interrupt.into());
+                }
+            }
+            Ok(UpdateDeadline::Continue(1))
+        });
+        store.set_epoch_deadline(1);
+    }
+
+    /// Creates the store in which the request `req_id` is handled.
+    fn new_store(&self, req_id: u64) -> Result<Store<Host>> {
+        let mut builder = WasiCtxBuilder::new();
+        self.cmd.run.configure_wasip2(&mut builder)?;
+
+        builder.env("REQUEST_ID", req_id.to_string());
+
+        let stdout_prefix: String;
+        let stderr_prefix: String;
+        if self.cmd.no_logging_prefix {
+            stdout_prefix = "".to_string();
+            stderr_prefix = "".to_string();
+        } else {
+            stdout_prefix = format!("stdout [{req_id}] :: ");
+            stderr_prefix = format!("stderr [{req_id}] :: ");
+        }
+        builder.stdout(LogStream::new(stdout_prefix, Output::Stdout));
+        builder.stderr(LogStream::new(stderr_prefix, Output::Stderr));
+
+        let mut host = Host {
+            table: wasmtime::component::ResourceTable::new(),
+            ctx: builder.build(),
+            http: match &self.connection_pool {
+                Some(pool) => WasiHttpCtx::with_connection_pool(pool.clone()),
+                None => WasiHttpCtx::new(),
+            },
+            http_outgoing_body_buffer_chunks: self
+                .cmd
+                .run
+                .common
+                .wasi
+                .http_outgoing_body_buffer_chunks,
+            http_outgoing_body_chunk_size: self.cmd.run.common.wasi.http_outgoing_body_chunk_size,
+
+            limits: StoreLimits::default(),
+
+            #[cfg(feature = "wasi-nn")]
+            nn: None,
+            #[cfg(feature = "wasi-config")]
+            wasi_config: None,
+            #[cfg(feature = "wasi-keyvalue")]
+            wasi_keyvalue: self.wasi_keyvalue.clone(),
+            #[cfg(feature = "profiling")]
+            guest_profiler: None,
+        };
+
+        if self.cmd.run.common.wasi.nn == Some(true) {
+            #[cfg(feature = "wasi-nn")]
+            {
+                let graphs = self
+                    .cmd
+                    .run
+                    .common
+                    .wasi
+                    .nn_graph
+                    .iter()
+                    .map(|g| (g.format.clone(), g.dir.clone()))
+                    .collect::<Vec<_>>();
+                let (backends, registry) = wasmtime_wasi_nn::preload(&graphs)?;
+                host.nn.replace(WasiNnCtx::new(backends, registry));
+            }
+        }
+
+        if self.cmd.run.common.wasi.config == Some(true) {
+            #[cfg(feature = "wasi-config")]
+            {
+                let vars = WasiConfigVariables::from_iter(
+                    self.cmd
+                        .run
+                        .common
+                        .wasi
+                        .config_var
+                        .iter()
+                        .map(|v| (v.key.clone(), v.value.clone())),
+                );
+                host.wasi_config.replace(vars);
+            }
+        }
+
+        let mut store = Store::new(&self.engine, host);
+
+        if let Some(ticks) = self.cmd.timeout_ticks() {
+            store.set_epoch_deadline(ticks);
+        } else if self.cmd.guest_profile_interval().is_some() {
+            // Epochs only tick for the guest profiler here, which isn't
+            // started until a request is dispatched to this store.
+            store.set_epoch_deadline(1);
+            store.epoch_deadline_callback(|_| Ok(UpdateDeadline::Continue(1)));
+        }
+
+        store.data_mut().limits = self.cmd.run.store_limits();
+        store.limiter(|t| &mut t.limits);
+
+        // If fuel has been configured, we want to add the configured
+        // fuel amount to this store.
+        if let Some(fuel) = self.cmd.run.common.wasm.fuel {
+            store.set_fuel(fuel)?;
+        }
+
+        Ok(store)
+    }
+
+    /// Creates a new store and instance of the component which will handle
+    /// the request `req_id`.
+    async fn instantiate(&self, req_id: u64) -> Result<ReadyInstance> {
+        let mut store = self.new_store(req_id)?;
+        let proxy = self.instance_pre.instantiate_async(&mut store).await?;
+        Ok(ReadyInstance {
+            req_id,
+            store,
+            proxy,
+        })
+    }
 }
 
 #[derive(Clone)]
 struct ProxyHandler(Arc<ProxyHandlerInner>);
 
 impl ProxyHandler {
-    fn new(cmd: ServeCommand, engine: Engine, instance_pre: ProxyPre<Host>) -> Self {
-        Self(Arc::new(ProxyHandlerInner {
+    /// Creates a new handler, additionally returning the sending half of its
+    /// instance pool if `--instance-pool-size` is in use.
+    fn new(
+        cmd: ServeCommand,
+        engine: Engine,
+        instance_pre: ProxyPre<Host>,
+    ) -> Result<(Self, Option<mpsc::Sender<ReadyInstance>>)> {
+        let (instance_pool, ready) = if cmd.instance_pool_size > 0 {
+            let (sender, receiver) = mpsc::channel(cmd.instance_pool_size);
+            let pool = InstancePool {
+                ready: Mutex::new(receiver),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            };
+            (Some(pool), Some(sender))
+        } else {
+            (None, None)
+        };
+        #[cfg(feature = "wasi-keyvalue")]
+        let wasi_keyvalue = cmd.wasi_keyvalue_ctx()?;
+        let concurrency = cmd
+            .max_concurrent_requests
+            .map(|max| Arc::new(Semaphore::new(max)));
+        let connection_pool = cmd.connection_pool()?;
+        let handler = Self(Arc::new(ProxyHandlerInner {
             cmd,
             engine,
             instance_pre,
             next_id: AtomicU64::from(0),
-        }))
+            instance_pool,
+            concurrency,
+            connection_pool,
+            #[cfg(feature = "wasi-keyvalue")]
+            wasi_keyvalue,
+        }));
+        Ok((handler, ready))
+    }
+}
+
+/// A store and instance of the component ready to handle a request.
+struct ReadyInstance {
+    req_id: u64,
+    store: Store<Host>,
+    proxy: Proxy,
+}
+
+/// Instances created ahead of time by `refill_instance_pool`.
+struct InstancePool {
+    ready: Mutex<mpsc::Receiver<ReadyInstance>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl InstancePool {
+    /// Takes a ready instance out of the pool, if one is available.
+    fn take(&self) -> Option<ReadyInstance> {
+        match self.ready.lock().unwrap().try_recv() {
+            Ok(instance) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(instance)
+            }
+            Err(_) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+}
+
+/// Initial delay before retrying a failed pre-instantiation in
+/// `refill_instance_pool`, doubled after each consecutive failure.
+const REFILL_MIN_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Upper bound on the delay between retries of a failed pre-instantiation.
+const REFILL_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Keeps the instance pool of `handler` full, instantiating the component
+/// whenever an instance is taken out of the pool.
+///
+/// Failed instantiations are retried with exponential backoff so that a
+/// transient failure, such as running out of pooling allocator slots under
+/// load, doesn't permanently disable the pool.
+async fn refill_instance_pool(handler: ProxyHandler, ready: mpsc::Sender<ReadyInstance>) {
+    let mut backoff = REFILL_MIN_BACKOFF;
+    while let Ok(slot) = ready.reserve().await {
+        let req_id = handler.0.next_req_id();
+        match handler.0.instantiate(req_id).await {
+            Ok(instance) => {
+                slot.send(instance);
+                backoff = REFILL_MIN_BACKOFF;
+            }
+            Err(e) => {
+                // Requests fall back to instantiating the component when they
+                // arrive while the pool is empty.
+                log::error!("failed to pre-instantiate component, retrying in {backoff:?}: {e:?}");
+                drop(slot);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(REFILL_MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Writes the guest profile collected in `store` to a file derived from
+/// `path` and the request's id.
+#[cfg(feature = "profiling")]
+fn finish_guest_profiler(store: &mut Store<Host>, path: &str, req_id: u64) {
+    let Some(profiler) = store.data_mut().guest_profiler.take() else {
+        return;
+    };
+    let path = guest_profile_path(Path::new(path), req_id);
+    match std::fs::File::create(&path)
+        .map_err(anyhow::Error::new)
+        .and_then(|output| profiler.finish(std::io::BufWriter::new(output)))
+    {
+        Ok(()) => log::info!("[{req_id}] :: profile written to {}", path.display()),
+        Err(e) => log::error!(
+            "[{req_id}] :: failed writing profile at {}: {e:#}",
+            path.display()
+        ),
     }
 }
 
+/// Returns the path that the guest profile of request `req_id` is written
+/// to, which inserts the request id before the extension of `path`. For
+/// example `profile.json` becomes `profile.3.json` for request 3.
+#[cfg(feature = "profiling")]
+fn guest_profile_path(path: &Path, req_id: u64) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{req_id}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{req_id}"),
+    };
+    path.with_file_name(name)
+}
+
 type Request = hyper::Request<hyper::body::Incoming>;
 
 async fn handle_request(
     ProxyHandler(inner): ProxyHandler,
+    scheme: Scheme,
     req: Request,
 ) -> Result<hyper::Response<HyperOutgoingBody>> {
+    let start = Instant::now();
     let (sender, receiver) = tokio::sync::oneshot::channel();
 
-    let req_id = inner.next_req_id();
+    // Apply back-pressure by waiting for a slot before doing any work for
+    // this request. The permit is released once the handler has finished.
+    let permit = match &inner.concurrency {
+        Some(semaphore) => Some(semaphore.clone().acquire_owned().await?),
+        None => None,
+    };
+
+    let pooled = inner.instance_pool.as_ref().and_then(|pool| pool.take());
+    let from_pool = pooled.is_some();
+    let ReadyInstance {
+        req_id,
+        mut store,
+        proxy,
+    } = match pooled {
+        Some(instance) => instance,
+        None => inner.instantiate(inner.next_req_id()).await?,
+    };
 
     log::info!(
         "Request {req_id} handling {} to {}",
         req.method(),
         req.uri()
     );
+    let span = tracing::info_span!("request", id = req_id);
 
-    let mut store = inner.cmd.new_store(&inner.engine, req_id)?;
+    // Pooled instances may have been created some time ago, so the timeout
+    // and the guest profile start from when the request is dispatched to the
+    // instance.
+    if let Some(ticks) = inner.cmd.timeout_ticks() {
+        store.set_epoch_deadline(ticks);
+    }
+    #[cfg(feature = "profiling")]
+    if let Some(interval) = inner.cmd.guest_profile_interval() {
+        inner.start_guest_profiler(&mut store, req_id, interval);
+    }
 
-    let req = store.data_mut().new_incoming_request(Scheme::Http, req)?;
+    let req = store.data_mut().new_incoming_request(scheme, req)?;
     let out = store.data_mut().new_response_outparam(sender)?;
-    let proxy = inner.instance_pre.instantiate_async(&mut store).await?;
 
-    let task = tokio::task::spawn(async move {
-        if let Err(e) = proxy
-            .wasi_http_incoming_handler()
-            .call_handle(store, req, out)
-            .await
-        {
-            log::error!("[{req_id}] :: {:#?}", e);
-            return Err(e);
-        }
+    let stats = inner.clone();
+    let task = tokio::task::spawn(
+        async move {
+            let result = proxy
+                .wasi_http_incoming_handler()
+                .call_handle(&mut store, req, out)
+                .await;
+            drop(permit);
+
+            #[cfg(feature = "profiling")]
+            if let Some(Profile::Guest { path, .. }) = &stats.cmd.run.profile {
+                finish_guest_profiler(&mut store, path, req_id);
+            }
 
-        Ok(())
-    });
+            match &stats.instance_pool {
+                Some(pool) => tracing::info!(
+                    latency = ?start.elapsed(),
+                    from_pool,
+                    pool_hits = pool.hits.load(Ordering::Relaxed),
+                    pool_misses = pool.misses.load(Ordering::Relaxed),
+                    "request finished"
+                ),
+                None => tracing::info!(latency = ?start.elapsed(), "request finished"),
+            }
+
+            if let Err(e) = result {
+                log::error!("[{req_id}] :: {:#?}", e);
+                return Err(e);
+            }
+
+            Ok(())
+        }
+        .instrument(span),
+    );
 
     match receiver.await {
         Ok(Ok(resp)) => Ok(resp),
@@ -12,6 +12,7 @@ use wasmtime_wasi::WasiCtxBuilder;
 #[cfg(feature = "component-model")]
 use wasmtime::component::Component;
 
+#[derive(Clone)]
 pub enum RunTarget {
     Core(Module),
 
@@ -52,7 +53,8 @@ pub struct RunCommon {
     #[arg(long = "allow-precompiled")]
     pub allow_precompiled: bool,
 
-    /// Profiling strategy (valid options are: perfmap, jitdump, vtune, guest)
+    /// Profiling strategy (valid options are: perfmap, jitdump, vtune, guest,
+    /// data)
     ///
     /// The perfmap, jitdump, and vtune profiling strategies integrate Wasmtime
     /// with external profilers such as `perf`. The guest profiling strategy
@@ -67,6 +69,11 @@ pub struct RunCommon {
     /// where `path` is where to write the profile and `interval` is the
     /// duration between samples. When used with `--wasm-timeout` the timeout
     /// will be rounded up to the nearest multiple of this interval.
+    ///
+    /// The `data` strategy samples the main module in the same way, and is
+    /// configured in the same way, but writes the samples to
+    /// `wasmtime-profile-data.json` by default in a form which can be passed
+    /// to `wasmtime compile --profile-data` to guide compilation.
     #[arg(
         long,
         value_name = "STRATEGY",
@@ -268,10 +275,18 @@ impl RunCommon {
         // something like `sleep(FOREVER)`.
         builder.allow_blocking_current_thread(self.common.wasm.timeout.is_none());
 
+        // In deterministic mode nothing about the host may be observed by the
+        // guest, so clocks, randomness, and networking are all virtualized and
+        // stdin is closed. Anything else which is configured below that could
+        // leak nondeterminism is reported as an error at the end.
+        let deterministic = self.common.wasm.deterministic == Some(true);
+        if deterministic {
+            builder.deterministic(0);
+            builder.stdin(wasmtime_wasi::pipe::ClosedInputStream);
+        }
+
         if self.common.wasi.inherit_env == Some(true) {
-            for (k, v) in std::env::vars() {
-                builder.env(&k, &v);
-            }
+            builder.inherit_env();
         }
         for (key, value) in self.vars.iter() {
             let value = match value {
@@ -318,6 +333,10 @@ impl RunCommon {
             builder.allow_udp(enable);
         }
 
+        if deterministic {
+            builder.check_deterministic()?;
+        }
+
         Ok(())
     }
 
@@ -341,12 +360,22 @@ impl RunCommon {
         options.network_error_code(self.common.wasi.network_error_code.unwrap_or(false));
         options
     }
+
+    /// Same as [`RunCommon::compute_wasi_features`] but for linking WASI
+    /// synchronously.
+    pub fn compute_wasi_sync_features(&self) -> wasmtime_wasi::bindings::sync::LinkOptions {
+        let mut options = wasmtime_wasi::bindings::sync::LinkOptions::default();
+        options.cli_exit_with_code(self.common.wasi.cli_exit_with_code.unwrap_or(false));
+        options.network_error_code(self.common.wasi.network_error_code.unwrap_or(false));
+        options
+    }
 }
 
 #[derive(Clone, PartialEq)]
 pub enum Profile {
     Native(wasmtime::ProfilingStrategy),
     Guest { path: String, interval: Duration },
+    Data { path: String, interval: Duration },
 }
 
 impl Profile {
@@ -370,6 +399,18 @@ impl Profile {
                 path: path.to_string(),
                 interval: WasmtimeOptionValue::parse(Some(dur))?,
             }),
+            ["data"] => Ok(Profile::Data {
+                path: "wasmtime-profile-data.json".to_string(),
+                interval: Duration::from_millis(10),
+            }),
+            ["data", path] => Ok(Profile::Data {
+                path: path.to_string(),
+                interval: Duration::from_millis(10),
+            }),
+            ["data", path, dur] => Ok(Profile::Data {
+                path: path.to_string(),
+                interval: WasmtimeOptionValue::parse(Some(dur))?,
+            }),
             _ => bail!("unknown profiling strategy: {s}"),
         }
     }
@@ -111,6 +111,26 @@ fn run_wasmtime_simple_fail_no_args() -> Result<()> {
     Ok(())
 }
 
+// Connection pool options are only meaningful for `wasmtime serve`.
+#[test]
+fn run_rejects_http_connection_pool_options() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/simple.wat")?;
+    let err = run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        "-Shttp-connection-pool-max-per-host=4",
+        "--invoke",
+        "simple",
+        wasm.path().to_str().unwrap(),
+        "4",
+    ])
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("only supported by `wasmtime serve`"));
+    Ok(())
+}
+
 #[test]
 fn run_coredump_smoketest() -> Result<()> {
     let wasm = build_wasm("tests/all/cli_tests/coredump_smoketest.wat")?;
@@ -132,6 +152,66 @@ fn run_coredump_smoketest() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn inspect_coredump_rejects_out_of_bounds_data() -> Result<()> {
+    let coredump = wat::parse_str(
+        r#"
+            (module
+                (memory i64 1)
+                (data (i64.const -1) "ab")
+                (@custom "core" "\00\04test")
+                (@custom "corestack" "\00\04main\00"))
+        "#,
+    )?;
+    let coredump_file = NamedTempFile::new()?;
+    std::fs::write(coredump_file.path(), coredump)?;
+    let err = run_wasmtime(&["coredump", coredump_file.path().to_str().unwrap()]).unwrap_err();
+    assert!(err.to_string().contains("out of bounds"), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn inspect_coredump() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/coredump_smoketest.wat")?;
+    let coredump_file = NamedTempFile::new()?;
+    let coredump_arg = format!("-Dcoredump={}", coredump_file.path().display());
+    run_wasmtime(&[
+        "run",
+        "--invoke",
+        "a",
+        "-Ccache=n",
+        &coredump_arg,
+        wasm.path().to_str().unwrap(),
+    ])
+    .unwrap_err();
+
+    let output = run_wasmtime(&[
+        "coredump",
+        "--module",
+        wasm.path().to_str().unwrap(),
+        "--memory",
+        "0:0x0..0x20",
+        coredump_file.path().to_str().unwrap(),
+    ])?;
+    let frames = output
+        .lines()
+        .filter(|l| l.starts_with("frame "))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        frames,
+        [
+            "frame 0: innermost (instance 0, func 2)",
+            "frame 1: middle (instance 0, func 1)",
+            "frame 2: <wasm function 0> (instance 0, func 0)",
+        ],
+        "{output}"
+    );
+    assert!(output.contains("    0 (counter): i32 42"), "{output}");
+    assert!(output.contains(" 68 65 6c 6c 6f "), "{output}");
+    assert!(output.contains("|hello"), "{output}");
+    Ok(())
+}
+
 // Running simple wat
 #[test]
 fn run_wasmtime_simple_wat() -> Result<()> {
@@ -280,6 +360,48 @@ fn exit2_wasi_snapshot1() -> Result<()> {
     Ok(())
 }
 
+// Stack-switching isn't compatible with async support so `run` executes
+// synchronously when it's enabled.
+#[test]
+#[cfg(feature = "stack-switching")]
+fn run_stack_switching() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/stack-switching.wat")?;
+    for preview2 in ["-Spreview2=n", "-Spreview2=y"] {
+        let output = run_wasmtime_for_output(
+            &[
+                "-Ccache=n",
+                "-Wstack-switching,function-references,exceptions",
+                preview2,
+                wasm.path().to_str().unwrap(),
+            ],
+            None,
+        )?;
+        assert_eq!(output.status.code().unwrap(), 7);
+    }
+    Ok(())
+}
+
+#[test]
+fn serve_rejects_stack_switching() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/empty-component.wat")?;
+    let output = run_wasmtime_for_output(
+        &[
+            "serve",
+            "-Ccache=n",
+            "-Wstack-switching",
+            wasm.path().to_str().unwrap(),
+        ],
+        None,
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("stack-switching is not supported with the `serve` subcommand"),
+        "bad stderr: {stderr}"
+    );
+    Ok(())
+}
+
 // Exit with a valid non-zero exit code, snapshot0 edition.
 #[test]
 fn exit125_wasi_snapshot0() -> Result<()> {
@@ -453,6 +575,71 @@ fn run_cwasm() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(not(feature = "profiling"), ignore)]
+fn compile_with_profile_data() -> Result<()> {
+    let td = TempDir::new()?;
+    let profile = td.path().join("profile.json");
+    let cwasm = td.path().join("foo.cwasm");
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--profile=data,{},1ms", profile.display()),
+        "tests/all/cli_tests/simple.wat",
+    ])?;
+    let contents = std::fs::read_to_string(&profile)?;
+    serde_json::from_str::<serde_json::Value>(&contents)?;
+
+    run_wasmtime(&[
+        "compile",
+        "-Cinlining=y",
+        "--profile-data",
+        profile.to_str().unwrap(),
+        "tests/all/cli_tests/simple.wat",
+        "-o",
+        cwasm.to_str().unwrap(),
+    ])?;
+    let stdout = run_wasmtime(&["run", "--allow-precompiled", cwasm.to_str().unwrap()])?;
+    assert_eq!(stdout, "");
+
+    // Components are profiled the same way.
+    let component_profile = td.path().join("component-profile.json");
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--profile=data,{},1ms", component_profile.display()),
+        "tests/all/cli_tests/component-basic.wat",
+    ])?;
+    run_wasmtime(&[
+        "compile",
+        "--profile-data",
+        component_profile.to_str().unwrap(),
+        "tests/all/cli_tests/component-basic.wat",
+        "-o",
+        cwasm.to_str().unwrap(),
+    ])?;
+
+    // A profile can't be used to compile anything else.
+    let output = run_wasmtime_for_output(
+        &[
+            "compile",
+            "--profile-data",
+            component_profile.to_str().unwrap(),
+            "tests/all/cli_tests/simple.wat",
+            "-o",
+            cwasm.to_str().unwrap(),
+        ],
+        None,
+    )?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("profile data was collected from a different wasm binary"),
+        "bad stderr: {stderr}"
+    );
+    Ok(())
+}
+
 #[cfg(unix)]
 #[test]
 fn hello_wasi_snapshot0_from_stdin() -> Result<()> {
@@ -531,6 +718,101 @@ fn specify_env() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn deterministic_wasi() -> Result<()> {
+    // Explicitly configured env vars are still passed through
+    let output = get_wasmtime_command()?
+        .args(&[
+            "run",
+            "-Wdeterministic",
+            "--env",
+            "FOO=bar",
+            "tests/all/cli_tests/print_env.wat",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "FOO=bar\n");
+
+    // Anything which could leak host state into the guest is rejected
+    let output = get_wasmtime_command()?
+        .args(&[
+            "run",
+            "-Wdeterministic",
+            "-Sinherit-env",
+            "-Sinherit-network",
+            "-Stcp",
+            "--dir",
+            ".::/",
+            "tests/all/cli_tests/print_env.wat",
+        ])
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("WASI context is not deterministic"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("environment variables"), "{stderr}");
+    assert!(stderr.contains("preopened as `/`"), "{stderr}");
+    assert!(stderr.contains("TCP is allowed"), "{stderr}");
+
+    // The legacy WASIp1 implementation isn't supported
+    let output = get_wasmtime_command()?
+        .args(&[
+            "run",
+            "-Wdeterministic",
+            "-Spreview2=n",
+            "tests/all/cli_tests/print_env.wat",
+        ])
+        .output()?;
+    assert!(!output.status.success());
+
+    Ok(())
+}
+
+#[test]
+fn record_and_replay() -> Result<()> {
+    let td = TempDir::new()?;
+    let trace = td.path().join("trace.bin");
+    let trace = trace.to_str().unwrap();
+
+    let stdout = run_wasmtime(&[
+        "run",
+        "--record",
+        trace,
+        "--env",
+        "FOO=bar",
+        "tests/all/cli_tests/print_env.wat",
+    ])?;
+    assert_eq!(stdout, "FOO=bar\n");
+
+    // The host isn't called while replaying so nothing is printed, and the
+    // environment comes from the trace.
+    let stdout = run_wasmtime(&[
+        "run",
+        "--replay",
+        trace,
+        "--env",
+        "FOO=baz",
+        "tests/all/cli_tests/print_env.wat",
+    ])?;
+    assert_eq!(stdout, "");
+
+    // Replaying a different program diverges from the trace.
+    let output = get_wasmtime_command()?
+        .args(&[
+            "run",
+            "--replay",
+            trace,
+            "tests/all/cli_tests/hello_wasi_snapshot1.wat",
+        ])
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("diverged"), "{stderr}");
+    Ok(())
+}
+
 #[cfg(unix)]
 #[test]
 fn run_cwasm_from_stdin() -> Result<()> {
@@ -856,6 +1138,44 @@ fn run_basic_component() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(not(feature = "component-model"), ignore)]
+fn run_invoke_component() -> Result<()> {
+    let path = "tests/all/cli_tests/component-invoke.wat";
+
+    let stdout = run_wasmtime(&["-Ccache=n", "--invoke", "add(1, 2)", path])?;
+    assert_eq!(stdout, "3\n");
+
+    let stdout = run_wasmtime(&[
+        "-Ccache=n",
+        "--invoke",
+        "test:math/ops@1.0.0.nested.is-even(4)",
+        path,
+    ])?;
+    assert_eq!(stdout, "true\n");
+
+    let stdout = run_wasmtime(&[
+        "-Ccache=n",
+        "--invoke",
+        "test:math/ops@1.0.0#nested#add(40, 2)",
+        path,
+    ])?;
+    assert_eq!(stdout, "42\n");
+
+    let output = run_wasmtime_for_output(&["-Ccache=n", "--invoke", "add(1)", path], None)?;
+    assert!(!output.status.success());
+
+    let output = run_wasmtime_for_output(&["-Ccache=n", "--invoke", "missing()", path], None)?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no export named `missing`"),
+        "bad stderr: {stderr}"
+    );
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(not(feature = "component-model"), ignore)]
 fn run_precompiled_component() -> Result<()> {
@@ -880,6 +1200,32 @@ fn run_precompiled_component() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn run_precompiled_memory64_with_pooling() -> Result<()> {
+    let td = TempDir::new()?;
+    let cwasm = td.path().join("memory64.cwasm");
+    let flags = [
+        "-Wmemory64",
+        "-Omemory-reservation=0x2_0000_0000",
+        "-Opooling-allocator",
+        "-Opooling-total-memories=1",
+        "-Opooling-max-memory-size=0x2_0000_0000",
+    ];
+    let mut args = vec!["compile", "tests/all/cli_tests/memory64.wat"];
+    args.extend(flags);
+    args.extend(["-o", cwasm.to_str().unwrap()]);
+    let stdout = run_wasmtime(&args)?;
+    assert_eq!(stdout, "");
+
+    let mut args = vec!["run", "--allow-precompiled"];
+    args.extend(flags);
+    args.push(cwasm.to_str().unwrap());
+    let stdout = run_wasmtime(&args)?;
+    assert_eq!(stdout, "");
+    Ok(())
+}
+
 #[test]
 fn memory_growth_failure() -> Result<()> {
     let output = get_wasmtime_command()?
@@ -1647,6 +1993,69 @@ mod test_programs {
                 .context("failed http handshake")?;
             Ok((send, tokio::task::spawn(conn)))
         }
+
+        /// Send a request to this server over TLS, negotiating `alpn` as the
+        /// protocol to speak, and wait for the response.
+        #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+        async fn send_tls_request(
+            &self,
+            alpn: &[u8],
+            req: http::Request<String>,
+        ) -> Result<http::Response<String>> {
+            use std::sync::Arc;
+            use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+            use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+            use tokio_rustls::TlsConnector;
+
+            let mut roots = RootCertStore::empty();
+            roots.add(CertificateDer::from(
+                &include_bytes!("cli_tests/tls-ca.der")[..],
+            ))?;
+            let mut config = ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            config.alpn_protocols = vec![alpn.to_vec()];
+
+            let tcp = TcpStream::connect(&self.addr)
+                .await
+                .context("failed to connect")?;
+            let stream = TlsConnector::from(Arc::new(config))
+                .connect(ServerName::try_from("localhost")?, tcp)
+                .await
+                .context("failed TLS handshake")?;
+            assert_eq!(stream.get_ref().1.alpn_protocol(), Some(alpn));
+            let stream = wasmtime_wasi_http::io::TokioIo::new(stream);
+
+            let response = if alpn == b"h2" {
+                let (mut send, conn) = hyper::client::conn::http2::handshake(
+                    wasmtime_wasi_http::io::TokioExecutor,
+                    stream,
+                )
+                .await
+                .context("failed http handshake")?;
+                let conn_task = tokio::task::spawn(conn);
+                let response = send.send_request(req).await;
+                drop(send);
+                conn_task.await??;
+                response
+            } else {
+                let (mut send, conn) = hyper::client::conn::http1::handshake(stream)
+                    .await
+                    .context("failed http handshake")?;
+                let conn_task = tokio::task::spawn(conn);
+                let response = send.send_request(req).await;
+                drop(send);
+                conn_task.await??;
+                response
+            }
+            .context("error sending request")?;
+            let (parts, body) = response.into_parts();
+
+            let body = body.collect().await.context("failed to read body")?;
+            let body = std::str::from_utf8(&body.to_bytes())?.to_string();
+
+            Ok(http::Response::from_parts(parts, body))
+        }
     }
 
     // Don't leave child processes running by accident so kill the child process
@@ -1724,6 +2133,95 @@ mod test_programs {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn cli_serve_instance_pool() -> Result<()> {
+        let server = WasmtimeServe::new(CLI_SERVE_ECHO_ENV_COMPONENT, |cmd| {
+            cmd.arg("-Scli");
+            cmd.arg("--instance-pool-size=2");
+            cmd.arg("--max-concurrent-requests=1");
+        })?;
+
+        let request = || {
+            server.send_request(
+                hyper::Request::builder()
+                    .uri("http://localhost/")
+                    .header("env", "REQUEST_ID")
+                    .body(String::new())
+                    .unwrap(),
+            )
+        };
+
+        // Send some requests concurrently, which are handled one at a time,
+        // and then some more once the pool has had a chance to refill.
+        let (a, b, c) = tokio::join!(request(), request(), request());
+        let mut responses = vec![a?, b?, c?];
+        for _ in 0..3 {
+            responses.push(request().await?);
+        }
+
+        // Every request must have been handled by a fresh instance.
+        let mut ids = Vec::new();
+        for response in responses {
+            assert!(response.status().is_success());
+            let id = response.headers().get("env").unwrap().to_str()?;
+            ids.push(id.to_string());
+        }
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 6);
+
+        server.finish()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cli_serve_guest_profiler() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let profile = dir.path().join("profile.json");
+        let server = WasmtimeServe::new(CLI_SERVE_ECHO_ENV_COMPONENT, |cmd| {
+            cmd.arg("-Scli");
+            cmd.arg(format!("--profile=guest,{},1ms", profile.display()));
+        })?;
+
+        for _ in 0..2 {
+            let resp = server
+                .send_request(
+                    hyper::Request::builder()
+                        .uri("http://localhost/")
+                        .header("env", "REQUEST_ID")
+                        .body(String::new())
+                        .context("failed to make request")?,
+                )
+                .await?;
+            assert!(resp.status().is_success());
+        }
+
+        // Each request gets its own profile, which is written out once the
+        // handler has returned, possibly shortly after the response is sent.
+        for id in 0..2 {
+            let path = dir.path().join(format!("profile.{id}.json"));
+            let mut contents = String::new();
+            for _ in 0..100 {
+                contents = std::fs::read_to_string(&path).unwrap_or_default();
+                if serde_json::from_str::<serde_json::Value>(&contents).is_ok() {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            let json: serde_json::Value = serde_json::from_str(&contents)
+                .with_context(|| format!("invalid profile at {}", path.display()))?;
+            let libs = json["libs"].as_array().unwrap();
+            assert_eq!(libs.len(), 1, "{libs:?}");
+            assert!(libs[0]["name"]
+                .as_str()
+                .unwrap()
+                .ends_with(&format!("[{id}]")));
+        }
+
+        server.finish()?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn cli_serve_outgoing_body_config() -> Result<()> {
         let server = WasmtimeServe::new(CLI_SERVE_ECHO_ENV_COMPONENT, |cmd| {
@@ -1997,6 +2495,50 @@ after empty
         Ok(())
     }
 
+    #[tokio::test]
+    #[cfg_attr(
+        any(target_arch = "riscv64", target_arch = "s390x"),
+        ignore = "TLS is not supported on this platform"
+    )]
+    async fn cli_serve_https() -> Result<()> {
+        #[cfg(not(any(target_arch = "riscv64", target_arch = "s390x")))]
+        {
+            let server = WasmtimeServe::new(CLI_SERVE_HTTPS_COMPONENT, |cmd| {
+                cmd.arg("-Scli");
+                cmd.arg("--tls-cert=tests/all/cli_tests/tls-cert.pem");
+                cmd.arg("--tls-key=tests/all/cli_tests/tls-key.pem");
+            })?;
+
+            let resp = server
+                .send_tls_request(
+                    b"h2",
+                    hyper::Request::builder()
+                        .uri("https://localhost/")
+                        .body(String::new())
+                        .context("failed to make request")?,
+                )
+                .await?;
+            assert!(resp.status().is_success());
+            assert_eq!(resp.version(), hyper::Version::HTTP_2);
+
+            let resp = server
+                .send_tls_request(
+                    b"http/1.1",
+                    hyper::Request::builder()
+                        .uri("/")
+                        .header("Host", "localhost")
+                        .body(String::new())
+                        .context("failed to make request")?,
+                )
+                .await?;
+            assert!(resp.status().is_success());
+            assert_eq!(resp.version(), hyper::Version::HTTP_11);
+
+            server.finish()?;
+        }
+        Ok(())
+    }
+
     #[test]
     fn cli_argv0() -> Result<()> {
         run_wasmtime(&["run", "--argv0=a", CLI_ARGV0, "a"])?;
@@ -2071,6 +2613,23 @@ after empty
         Ok(())
     }
 
+    #[test]
+    fn cli_keyvalue_dir() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        // The empty bucket identifier is stored in the `%` directory.
+        let bucket = dir.path().join("%");
+        std::fs::create_dir(&bucket)?;
+        std::fs::write(bucket.join("atomics_key"), "5")?;
+
+        let arg = format!("-Skeyvalue-dir={}", dir.path().display());
+        run_wasmtime(&["run", "-Skeyvalue", &arg, KEYVALUE_MAIN_COMPONENT])?;
+
+        assert_eq!(std::fs::read_to_string(bucket.join("atomics_key"))?, "6");
+        assert_eq!(std::fs::read_to_string(bucket.join("b1"))?, "v1");
+        assert!(!bucket.join("a1").exists());
+        Ok(())
+    }
+
     #[test]
     fn cli_multiple_preopens() -> Result<()> {
         run_wasmtime(&[
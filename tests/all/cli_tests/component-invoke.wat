@@ -0,0 +1,25 @@
+(component
+  (core module $m
+    (func (export "add") (param i32 i32) (result i32)
+      local.get 0
+      local.get 1
+      i32.add)
+    (func (export "is-even") (param i32) (result i32)
+      local.get 0
+      i32.const 1
+      i32.and
+      i32.eqz)
+  )
+  (core instance $i (instantiate $m))
+  (func $add (param "a" u32) (param "b" u32) (result u32)
+    (canon lift (core func $i "add")))
+  (func $is-even (param "x" u32) (result bool)
+    (canon lift (core func $i "is-even")))
+
+  (export "add" (func $add))
+  (instance $math
+    (export "is-even" (func $is-even))
+    (export "add" (func $add)))
+  (instance (export (interface "test:math/ops@1.0.0"))
+    (export "nested" (instance $math)))
+)
@@ -1,11 +1,14 @@
 (module
+    (memory 1)
+    (data (i32.const 0x10) "hello")
+    (global $counter (mut i32) (i32.const 42))
     (func (export "a")
-        call $b
+        call $middle
     )
-    (func $b
-        call $c
+    (func $middle
+        call $innermost
     )
-    (func $c 
+    (func $innermost
         unreachable
     )
 )
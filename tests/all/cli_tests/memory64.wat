@@ -0,0 +1,10 @@
+(module
+  (memory i64 1)
+  (func (export "_start")
+    ;; Grow memory past 4GiB and access its last bytes.
+    (if (i64.ne (memory.grow (i64.const 0x1_0000)) (i64.const 1))
+      (then unreachable))
+    (i64.store (i64.const 0x1_0000_fff8) (i64.const 1))
+    (if (i64.ne (i64.load (i64.const 0x1_0000_fff8)) (i64.const 1))
+      (then unreachable)))
+)
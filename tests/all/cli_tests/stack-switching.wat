@@ -0,0 +1,11 @@
+(module
+  (type $ft (func))
+  (type $ct (cont $ft))
+  (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+  (memory (export "memory") 1)
+  (func $f)
+  (elem declare func $f)
+  (func (export "_start")
+    (resume $ct (cont.new $ct (ref.func $f)))
+    (call $exit (i32.const 7)))
+)
@@ -205,6 +205,56 @@ fn resource_any() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn resource_usage_counts_host_handles() -> Result<()> {
+    let engine = super::engine();
+    let c = Component::new(
+        &engine,
+        r#"
+            (component
+                (type $t' (resource (rep i32)))
+                (export $t "t" (type $t'))
+
+                (core func $t_ctor (canon resource.new $t))
+                (func (export "[constructor]t") (param "x" u32) (result (own $t))
+                    (canon lift (core func $t_ctor)))
+
+                (core func $t_drop (canon resource.drop $t))
+                (func (export "drop-t") (param "x" (own $t))
+                    (canon lift (core func $t_drop)))
+            )
+        "#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let i = Linker::new(&engine).instantiate(&mut store, &c)?;
+    let t_ctor = i.get_typed_func::<(u32,), (ResourceAny,)>(&mut store, "[constructor]t")?;
+    let t_dtor = i.get_typed_func::<(ResourceAny,), ()>(&mut store, "drop-t")?;
+
+    let usage = store.resource_usage();
+    assert_eq!(usage.component_instances, 1);
+    assert_eq!(usage.host_resource_handles, 0);
+
+    let mut handles = Vec::new();
+    for x in 0..3 {
+        let (t,) = t_ctor.call(&mut store, (x,))?;
+        t_ctor.post_return(&mut store)?;
+        handles.push(t);
+    }
+    assert_eq!(store.resource_usage().host_resource_handles, 3);
+
+    t_dtor.call(&mut store, (handles.pop().unwrap(),))?;
+    t_dtor.post_return(&mut store)?;
+    assert_eq!(store.resource_usage().host_resource_handles, 2);
+
+    let mut table = ResourceTable::new();
+    table.push(())?;
+    let usage = store.resource_usage().with_resource_table(&table);
+    assert_eq!(usage.resource_table_entries, 1);
+
+    Ok(())
+}
+
 #[test]
 fn mismatch_intrinsics() -> Result<()> {
     let engine = super::engine();
@@ -0,0 +1,114 @@
+use wasmtime::*;
+
+fn deterministic_config() -> Config {
+    let mut config = Config::new();
+    config.deterministic(true);
+    config
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn nans_are_canonicalized() -> Result<()> {
+    let engine = Engine::new(&deterministic_config())?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (func (export "f32") (param f32 f32) (result i32)
+                    (i32.reinterpret_f32 (f32.div (local.get 0) (local.get 1))))
+                (func (export "f64") (param f64 f64) (result i64)
+                    (i64.reinterpret_f64 (f64.div (local.get 0) (local.get 1))))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let f32 = instance.get_typed_func::<(f32, f32), u32>(&mut store, "f32")?;
+    assert_eq!(f32.call(&mut store, (0.0, 0.0))?, 0x7fc0_0000);
+    let f64 = instance.get_typed_func::<(f64, f64), u64>(&mut store, "f64")?;
+    assert_eq!(f64.call(&mut store, (0.0, 0.0))?, 0x7ff8_0000_0000_0000);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn relaxed_simd_is_deterministic() -> Result<()> {
+    let engine = Engine::new(&deterministic_config())?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (func (export "trunc") (param f32) (result i32)
+                    (i32x4.extract_lane 0
+                        (i32x4.relaxed_trunc_f32x4_s (f32x4.splat (local.get 0)))))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let trunc = instance.get_typed_func::<f32, i32>(&mut store, "trunc")?;
+    assert_eq!(trunc.call(&mut store, f32::NAN)?, 0);
+    assert_eq!(trunc.call(&mut store, 1e10)?, i32::MAX);
+    Ok(())
+}
+
+#[test]
+fn threads_disabled_by_default() -> Result<()> {
+    let engine = Engine::new(&deterministic_config())?;
+    let err = Module::new(&engine, r#"(module (memory 1 1 shared))"#).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("threads must be enabled"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn nondeterministic_config_rejected() {
+    let mut config = deterministic_config();
+    config.wasm_threads(true);
+    let err = Engine::new(&config).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("not compatible with deterministic execution"),
+        "{err}"
+    );
+
+    let mut config = deterministic_config();
+    config.relaxed_simd_deterministic(false);
+    let err = Engine::new(&config).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("cannot be disabled with deterministic execution"),
+        "{err}"
+    );
+
+    let mut config = deterministic_config();
+    config.cranelift_nan_canonicalization(false);
+    let err = Engine::new(&config).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("cannot be disabled with deterministic execution"),
+        "{err}"
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn precompiled_module_requires_nan_canonicalization() -> Result<()> {
+    let bytes = Engine::default().precompile_module(b"(module)")?;
+    let engine = Engine::new(&deterministic_config())?;
+    let err = unsafe { Module::deserialize(&engine, &bytes) }
+        .err()
+        .unwrap();
+    assert!(
+        format!("{err:?}").contains("enable_nan_canonicalization"),
+        "{err:?}"
+    );
+
+    let bytes = engine.precompile_module(b"(module)")?;
+    unsafe { Module::deserialize(&engine, &bytes)? };
+    Ok(())
+}
@@ -0,0 +1,444 @@
+use wasmtime::*;
+
+fn engine() -> Result<Engine> {
+    let mut config = Config::new();
+    config.wasm_exceptions(true);
+    Engine::new(&config)
+}
+
+#[test]
+fn catch_in_wasm() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (tag $e (param i32 i64))
+                (func $thrower (param i32)
+                    local.get 0
+                    i64.const 100
+                    throw $e)
+                (func (export "run") (param i32) (result i32 i64)
+                    (block $catch (result i32 i64)
+                        (try_table (catch $e $catch)
+                            local.get 0
+                            call $thrower)
+                        unreachable))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, (i32, i64)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, 7)?, (7, 100));
+    assert_eq!(run.call(&mut store, 8)?, (8, 100));
+    Ok(())
+}
+
+#[test]
+fn catch_ref_and_rethrow() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (tag $e (param i32))
+                (tag $other)
+                (func $rethrow (param i32)
+                    (block $catch (result exnref)
+                        (try_table (catch_all_ref $catch)
+                            local.get 0
+                            throw $e)
+                        unreachable)
+                    throw_ref)
+                (func (export "run") (param i32) (result i32)
+                    (block $catch_other
+                        (block $catch (result i32)
+                            (try_table (catch $other $catch_other) (catch $e $catch)
+                                local.get 0
+                                call $rethrow)
+                            unreachable)
+                        return)
+                    unreachable)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, 42)?, 42);
+    Ok(())
+}
+
+#[test]
+fn uncaught_exception_is_reported_to_host() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "tag" (tag $e (param i32 f64)))
+                (func (export "run")
+                    i32.const 1
+                    f64.const 2.5
+                    throw $e)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let ty = TagType::new(FuncType::new(&engine, [ValType::I32, ValType::F64], []));
+    let tag = Tag::new(&mut store, &ty)?;
+    let instance = Instance::new(&mut store, &module, &[tag.into()])?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+
+    let err = run.call(&mut store, ()).unwrap_err();
+    let exn = err.downcast_ref::<ThrownException>().unwrap();
+    assert!(Tag::eq(&exn.tag(), &tag, &store));
+    assert_eq!(exn.payload().len(), 2);
+    assert_eq!(exn.payload()[0].unwrap_i32(), 1);
+    assert_eq!(exn.payload()[1].unwrap_f64(), 2.5);
+
+    // The exception is no longer pending, so the store can keep being used.
+    let err = run.call(&mut store, ()).unwrap_err();
+    assert!(err.is::<ThrownException>());
+    Ok(())
+}
+
+#[test]
+fn host_throws_to_wasm() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "host" "tag" (tag $e (param i32)))
+                (import "host" "throw" (func $throw (param i32) (result i32)))
+                (func (export "run") (param i32) (result i32)
+                    (block $catch (result i32)
+                        (try_table (result i32) (catch $e $catch)
+                            local.get 0
+                            call $throw)
+                        i32.const 1000
+                        i32.add))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let ty = TagType::new(FuncType::new(&engine, [ValType::I32], []));
+    let tag = Tag::new(&mut store, &ty)?;
+    let mut linker = Linker::new(&engine);
+    linker.define(&store, "host", "tag", tag)?;
+    linker.func_wrap("host", "throw", move |x: i32| -> Result<i32> {
+        if x < 0 {
+            Err(ThrownException::new(tag, [Val::I32(-x)]).into())
+        } else {
+            Ok(x)
+        }
+    })?;
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, 5)?, 1005);
+    assert_eq!(run.call(&mut store, -5)?, 5);
+    Ok(())
+}
+
+#[test]
+fn exception_through_host_frames() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (tag $e (export "e") (param i32))
+                (import "" "host" (func $host))
+                (func (export "throw") (param i32)
+                    local.get 0
+                    throw $e)
+                (func (export "run") (result i32)
+                    (block $catch (result i32)
+                        (try_table (catch $e $catch)
+                            call $host)
+                        i32.const 0))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap("", "host", |mut caller: Caller<'_, ()>| -> Result<()> {
+        let throw = caller
+            .get_export("throw")
+            .unwrap()
+            .into_func()
+            .unwrap()
+            .typed::<i32, ()>(&caller)?;
+        // The exception thrown by the nested call propagates back into the
+        // outer Wasm frames when returned from this host function.
+        throw.call(&mut caller, 99)
+    })?;
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 99);
+    Ok(())
+}
+
+#[test]
+fn gc_refs_in_payload() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (tag $e (param externref))
+                (func (export "run") (param externref) (result externref)
+                    (block $catch (result externref)
+                        (try_table (catch $e $catch)
+                            local.get 0
+                            throw $e)
+                        unreachable))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<Option<Rooted<ExternRef>>, Option<Rooted<ExternRef>>>(
+        &mut store, "run",
+    )?;
+    for i in 0..10 {
+        let x = ExternRef::new(&mut store, i)?;
+        let y = run.call(&mut store, Some(x))?.unwrap();
+        assert_eq!(*y.data(&store)?.unwrap().downcast_ref::<i32>().unwrap(), i);
+        store.gc();
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gc_refs_in_payload_are_released() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_exceptions(true);
+    config.wasm_gc(true);
+    config.wasm_function_references(true);
+    config.collector(Collector::DeferredReferenceCounting);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $s (struct (field i32)))
+                (tag $e (param (ref $s)))
+                (func (export "run") (param $n i32) (result i32)
+                    (local $sum i32)
+                    (loop $loop
+                        (block $catch (result (ref $s))
+                            (try_table (catch $e $catch)
+                                (throw $e (struct.new $s (local.get $n))))
+                            unreachable)
+                        (local.set $sum (i32.add (local.get $sum) (struct.get $s 0)))
+                        (br_if $loop (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+                    local.get $sum)
+            )
+        "#,
+    )?;
+
+    // Every exception and its payload are garbage once caught, so throwing
+    // many of them must fit in a small GC heap.
+    const LIMIT: usize = 1 << 20;
+    let mut store = Store::new(
+        &engine,
+        StoreLimitsBuilder::new().gc_heap_size(LIMIT).build(),
+    );
+    store.limiter(|limits| limits);
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+    let n = 100_000;
+    let expected = (1..=n).fold(0_i32, |sum, i| sum.wrapping_add(i));
+    assert_eq!(run.call(&mut store, n)?, expected);
+    assert!(store.resource_usage().gc_heap_bytes <= LIMIT);
+    Ok(())
+}
+
+#[test]
+fn exnref_on_host() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (tag $e (param i32))
+                (func (export "catch") (param i32) (result exnref)
+                    (local $exn exnref)
+                    (block $catch (result i32 exnref)
+                        (try_table (catch_ref $e $catch)
+                            local.get 0
+                            throw $e)
+                        unreachable)
+                    local.set $exn
+                    drop
+                    local.get $exn)
+                (func (export "rethrow") (param exnref)
+                    local.get 0
+                    throw_ref)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let catch = instance.get_func(&mut store, "catch").unwrap();
+    let rethrow = instance.get_func(&mut store, "rethrow").unwrap();
+
+    let mut results = [Val::null_extern_ref()];
+    catch.call(&mut store, &[Val::I32(3)], &mut results)?;
+    assert!(results[0]
+        .ref_()
+        .unwrap()
+        .ty(&store)?
+        .matches(&RefType::EXNREF));
+
+    let err = rethrow.call(&mut store, &results, &mut []).unwrap_err();
+    let exn = err.downcast_ref::<ThrownException>().unwrap();
+    assert_eq!(exn.payload()[0].unwrap_i32(), 3);
+
+    // Host `externref`s are not exceptions.
+    let x = ExternRef::new(&mut store, 1)?;
+    assert!(rethrow.call(&mut store, &[x.into()], &mut []).is_err());
+    assert!(rethrow
+        .call(&mut store, &[Val::null_extern_ref()], &mut [])
+        .unwrap_err()
+        .downcast_ref::<Trap>()
+        .is_some_and(|t| *t == Trap::NullReference));
+    Ok(())
+}
+
+#[test]
+fn tag_identity() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "a" (tag $a))
+                (import "" "b" (tag $b))
+                (func (export "run") (result i32)
+                    (block $a
+                        (block $b
+                            (try_table (catch $b $b) (catch $a $a)
+                                throw $a)
+                            unreachable)
+                        i32.const 2
+                        return)
+                    i32.const 1)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let ty = TagType::new(FuncType::new(&engine, [], []));
+    let a = Tag::new(&mut store, &ty)?;
+    let b = Tag::new(&mut store, &ty)?;
+    assert!(!Tag::eq(&a, &b, &store));
+
+    let instance = Instance::new(&mut store, &module, &[a.into(), b.into()])?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 1);
+
+    let instance = Instance::new(&mut store, &module, &[a.into(), a.into()])?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 2);
+    Ok(())
+}
+
+#[test]
+fn tag_import_type_mismatch() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(&engine, r#"(module (import "" "" (tag (param i32))))"#)?;
+    let mut store = Store::new(&engine, ());
+    let ty = TagType::new(FuncType::new(&engine, [ValType::I64], []));
+    let tag = Tag::new(&mut store, &ty)?;
+    let err = Instance::new(&mut store, &module, &[tag.into()]).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("tag types incompatible"),
+        "bad error: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn host_exception_without_exceptions_enabled_is_a_trap() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "host" (func $host))
+                (func (export "run") call $host)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let ty = TagType::new(FuncType::new(&engine, [], []));
+    let tag = Tag::new(&mut store, &ty)?;
+    let host = Func::wrap(&mut store, move || -> Result<()> {
+        Err(ThrownException::new(tag, []).into())
+    });
+    let instance = Instance::new(&mut store, &module, &[host.into()])?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    let err = run.call(&mut store, ()).unwrap_err();
+    assert!(err.is::<ThrownException>());
+    Ok(())
+}
+
+#[test]
+fn host_exception_with_mismatched_payload() -> Result<()> {
+    let engine = engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "tag" (tag $e (param i32)))
+                (import "" "host" (func $host))
+                (func (export "run")
+                    (block $catch (result i32)
+                        (try_table (catch $e $catch)
+                            call $host)
+                        return)
+                    drop)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let ty = TagType::new(FuncType::new(&engine, [ValType::I32], []));
+    let tag = Tag::new(&mut store, &ty)?;
+    let host = Func::wrap(&mut store, move || -> Result<()> {
+        Err(ThrownException::new(tag, [Val::I64(1)]).into())
+    });
+    let instance = Instance::new(&mut store, &module, &[tag.into(), host.into()])?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    let err = run.call(&mut store, ()).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("exception payload does not match"),
+        "bad error: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn components_reject_tags() -> Result<()> {
+    let engine = engine()?;
+    let err = component::Component::new(
+        &engine,
+        r#"
+            (component
+                (core module $m (tag (export "e")))
+                (core instance $i (instantiate $m))
+                (core instance (export "e" (tag $i "e")))
+            )
+        "#,
+    )
+    .err()
+    .unwrap();
+    assert!(
+        format!("{err:?}").contains("aliasing a tag exported from a core instance"),
+        "bad error: {err:?}"
+    );
+    Ok(())
+}
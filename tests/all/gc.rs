@@ -1003,3 +1003,383 @@ fn ref_matches() -> Result<()> {
 
     Ok(())
 }
+
+fn drc_gc_engine() -> Result<Engine> {
+    let mut config = Config::new();
+    config.wasm_function_references(true);
+    config.wasm_gc(true);
+    config.collector(Collector::DeferredReferenceCounting);
+    Engine::new(&config)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_collects_garbage_cycles() -> Result<()> {
+    let _ = env_logger::try_init();
+
+    let engine = drc_gc_engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $node (struct (field $next (mut (ref null $node)))))
+
+                (func (export "run") (param i32)
+                    (local $a (ref null $node))
+                    (local $b (ref null $node))
+                    (loop $loop
+                        (local.set $a (struct.new $node (ref.null $node)))
+                        (local.set $b (struct.new $node (local.get $a)))
+                        (struct.set $node $next (local.get $a) (local.get $b))
+                        (br_if $loop (local.tee 0 (i32.sub (local.get 0) (i32.const 1))))
+                    )
+                )
+            )
+        "#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, ()>(&mut store, "run")?;
+
+    // Allocate many times more cyclic garbage than fits in the GC heap.
+    run.call(&mut store, 1_000_000)?;
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_dropping_object_drops_its_fields() -> Result<()> {
+    let _ = env_logger::try_init();
+
+    let engine = drc_gc_engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $node (struct (field $next (ref null $node))))
+                (global $list (mut (ref null $node)) (ref.null $node))
+
+                (func (export "run") (param i32)
+                    (local $n i32)
+                    (loop $outer
+                        (global.set $list (ref.null $node))
+                        (local.set $n (i32.const 1000))
+                        (loop $inner
+                            (global.set $list (struct.new $node (global.get $list)))
+                            (br_if $inner (local.tee $n (i32.sub (local.get $n) (i32.const 1))))
+                        )
+                        (br_if $outer (local.tee 0 (i32.sub (local.get 0) (i32.const 1))))
+                    )
+                )
+            )
+        "#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, ()>(&mut store, "run")?;
+
+    // Every time the global is overwritten, the whole list it pointed to
+    // becomes garbage.
+    run.call(&mut store, 1_000)?;
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_cycle_collection_keeps_live_objects() -> Result<()> {
+    let _ = env_logger::try_init();
+
+    let engine = drc_gc_engine()?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $node (struct
+                    (field $next (mut (ref null $node)))
+                    (field $value i32)
+                    (field $data externref)))
+                (global $live (mut (ref null $node)) (ref.null $node))
+
+                (func (export "make-live") (param externref externref)
+                    (local $a (ref null $node))
+                    (local.set $a (struct.new $node (ref.null $node) (i32.const 1) (local.get 0)))
+                    (struct.set $node $next
+                        (local.get $a)
+                        (struct.new $node (local.get $a) (i32.const 2) (local.get 1)))
+                    (global.set $live (local.get $a))
+                )
+
+                (func (export "make-garbage") (param i32 externref)
+                    (local $a (ref null $node))
+                    (loop $loop
+                        (local.set $a (struct.new $node (ref.null $node) (i32.const 0) (local.get 1)))
+                        (struct.set $node $next
+                            (local.get $a)
+                            (struct.new $node (local.get $a) (i32.const 0) (ref.null extern)))
+                        (br_if $loop (local.tee 0 (i32.sub (local.get 0) (i32.const 1))))
+                    )
+                )
+
+                (func (export "sum") (result i32)
+                    (i32.add
+                        (struct.get $node $value (global.get $live))
+                        (struct.get $node $value
+                            (struct.get $node $next (global.get $live))))
+                )
+            )
+        "#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let make_live = instance
+        .get_typed_func::<(Option<Rooted<ExternRef>>, Option<Rooted<ExternRef>>), ()>(
+            &mut store,
+            "make-live",
+        )?;
+    let make_garbage = instance
+        .get_typed_func::<(i32, Option<Rooted<ExternRef>>), ()>(&mut store, "make-garbage")?;
+    let sum = instance.get_typed_func::<(), i32>(&mut store, "sum")?;
+
+    let live_a = Arc::new(AtomicBool::new(false));
+    let live_b = Arc::new(AtomicBool::new(false));
+    let garbage = Arc::new(AtomicBool::new(false));
+    {
+        let mut scope = RootScope::new(&mut store);
+        let a = ExternRef::new(&mut scope, SetFlagOnDrop(live_a.clone()))?;
+        let b = ExternRef::new(&mut scope, SetFlagOnDrop(live_b.clone()))?;
+        make_live.call(&mut scope, (Some(a), Some(b)))?;
+        let g = ExternRef::new(&mut scope, SetFlagOnDrop(garbage.clone()))?;
+        make_garbage.call(&mut scope, (100_000, Some(g)))?;
+    }
+
+    // Fill the heap with more garbage so that the cycles referencing the
+    // `externref` above are all collected.
+    make_garbage.call(&mut store, (100_000, None))?;
+
+    // The garbage cycles, and the `externref` only they referenced, were
+    // collected, but the cycle reachable from the global was not.
+    assert!(garbage.load(SeqCst));
+    assert!(!live_a.load(SeqCst));
+    assert!(!live_b.load(SeqCst));
+    assert_eq!(sum.call(&mut store, ())?, 3);
+    Ok(())
+}
+
+const GROW_GC_HEAP_WAT: &str = r#"
+    (module
+        (type $bytes (array (mut i8)))
+        (table $live 100 (ref null $bytes))
+
+        (func (export "run") (param i32)
+            (loop $loop
+                (local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+                (table.set $live
+                    (local.get 0)
+                    (array.new_default $bytes (i32.const 4096)))
+                (br_if $loop (local.get 0))
+            )
+        )
+    )
+"#;
+
+fn small_gc_heap_config(collector: Collector) -> Config {
+    let mut config = Config::new();
+    config.wasm_function_references(true);
+    config.wasm_gc(true);
+    config.collector(collector);
+    config.gc_heap_initial_size(1 << 16);
+    config.gc_heap_maximum_size(1 << 20);
+    config
+}
+
+fn gc_heap_grows(collector: Collector) -> Result<()> {
+    let _ = env_logger::try_init();
+
+    let engine = Engine::new(&small_gc_heap_config(collector))?;
+    let module = Module::new(&engine, GROW_GC_HEAP_WAT)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, ()>(&mut store, "run")?;
+
+    // Keep about 400KiB of arrays live, which does not fit in the initial
+    // 64KiB GC heap.
+    run.call(&mut store, 100)?;
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_gc_heap_grows() -> Result<()> {
+    gc_heap_grows(Collector::DeferredReferenceCounting)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn null_gc_heap_grows() -> Result<()> {
+    gc_heap_grows(Collector::Null)
+}
+
+fn gc_heap_growth_limited(collector: Collector) -> Result<()> {
+    let _ = env_logger::try_init();
+
+    let engine = Engine::new(&small_gc_heap_config(collector))?;
+    let module = Module::new(&engine, GROW_GC_HEAP_WAT)?;
+    let mut store = Store::new(
+        &engine,
+        StoreLimitsBuilder::new().gc_heap_size(1 << 17).build(),
+    );
+    store.limiter(|limits| limits);
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, ()>(&mut store, "run")?;
+
+    // A handful of arrays fit within the limit...
+    run.call(&mut store, 10)?;
+
+    // ...but the limiter denies growing far enough for all of them.
+    let err = run.call(&mut store, 100).unwrap_err();
+    assert!(
+        err.is::<GcHeapOutOfMemory<()>>(),
+        "unexpected error: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_gc_heap_growth_limited() -> Result<()> {
+    gc_heap_growth_limited(Collector::DeferredReferenceCounting)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn null_gc_heap_growth_limited() -> Result<()> {
+    gc_heap_growth_limited(Collector::Null)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gc_heap_maximum_size_is_a_hard_limit() -> Result<()> {
+    let mut config = small_gc_heap_config(Collector::DeferredReferenceCounting);
+    config.gc_heap_maximum_size(1 << 17);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, GROW_GC_HEAP_WAT)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, ()>(&mut store, "run")?;
+
+    let err = run.call(&mut store, 100).unwrap_err();
+    assert!(
+        err.is::<GcHeapOutOfMemory<()>>(),
+        "unexpected error: {err:?}"
+    );
+    Ok(())
+}
+
+fn host_allocations_grow_gc_heap(collector: Collector) -> Result<()> {
+    let _ = env_logger::try_init();
+
+    let engine = Engine::new(&small_gc_heap_config(collector))?;
+    let mut store = Store::new(&engine, ());
+    let ty = ArrayType::new(&engine, FieldType::new(Mutability::Var, StorageType::I8));
+    let pre = ArrayRefPre::new(&mut store, ty);
+
+    // Keep about 400KiB of arrays live, which does not fit in the initial
+    // 64KiB GC heap, while also creating garbage for collections to reclaim.
+    let mut live = Vec::new();
+    for i in 0..200 {
+        let array = ArrayRef::new(&mut store, &pre, &Val::I32(0), 4096)?;
+        if i % 2 == 0 {
+            live.push(array.to_manually_rooted(&mut store)?);
+        }
+    }
+    for array in live {
+        array.unroot(&mut store);
+    }
+
+    // Host data isn't stored in the GC heap, but each `externref` is.
+    for _ in 0..10_000 {
+        let mut scope = RootScope::new(&mut store);
+        ExternRef::new(&mut scope, 42_u32)?;
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_host_allocations_grow_gc_heap() -> Result<()> {
+    host_allocations_grow_gc_heap(Collector::DeferredReferenceCounting)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn null_host_allocations_grow_gc_heap() -> Result<()> {
+    host_allocations_grow_gc_heap(Collector::Null)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn host_allocations_respect_gc_heap_maximum_size() -> Result<()> {
+    let mut config = small_gc_heap_config(Collector::DeferredReferenceCounting);
+    config.gc_heap_maximum_size(1 << 17);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+    let ty = ArrayType::new(&engine, FieldType::new(Mutability::Var, StorageType::I8));
+    let pre = ArrayRefPre::new(&mut store, ty);
+
+    let mut live = Vec::new();
+    let err = loop {
+        match ArrayRef::new(&mut store, &pre, &Val::I32(0), 4096) {
+            Ok(array) => live.push(array.to_manually_rooted(&mut store)?),
+            Err(e) => break e,
+        }
+    };
+    assert!(
+        err.is::<GcHeapOutOfMemory<()>>(),
+        "unexpected error: {err:?}"
+    );
+    assert!(live.len() < 32);
+    for array in live {
+        array.unroot(&mut store);
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn pooled_gc_heap_grows() -> Result<()> {
+    let mut pool = PoolingAllocationConfig::default();
+    pool.total_gc_heaps(1);
+    let mut config = small_gc_heap_config(Collector::DeferredReferenceCounting);
+    config.allocation_strategy(pool);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, GROW_GC_HEAP_WAT)?;
+
+    // The single pooled heap grows for every store that reuses it, after
+    // being reset back to its initial size in between.
+    for _ in 0..3 {
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let run = instance.get_typed_func::<i32, ()>(&mut store, "run")?;
+        run.call(&mut store, 100)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn gc_heap_size_config_validation() {
+    let mut config = Config::new();
+    config.wasm_gc(true);
+    config.gc_heap_initial_size(1 << 20);
+    config.gc_heap_maximum_size(1 << 16);
+    assert!(Engine::new(&config).is_err());
+
+    if cfg!(target_pointer_width = "64") {
+        let mut config = Config::new();
+        config.wasm_gc(true);
+        config.gc_heap_maximum_size(usize::MAX);
+        assert!(Engine::new(&config).is_err());
+    }
+}
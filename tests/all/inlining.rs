@@ -0,0 +1,107 @@
+use wasmtime::*;
+
+const WAT: &str = r#"
+    (module
+        (memory 1)
+        (func $load (param i32) (result i32)
+            (i32.load (local.get 0)))
+        (func $divmod (param i32 i32) (result i32 i32)
+            (i32.div_u (local.get 0) (local.get 1))
+            (i32.rem_u (local.get 0) (local.get 1)))
+        (func $abs (param i32) (result i32)
+            (if (result i32) (i32.lt_s (local.get 0) (i32.const 0))
+                (then (i32.sub (i32.const 0) (local.get 0)))
+                (else (local.get 0))))
+        (func (export "store") (param i32 i32)
+            (i32.store (local.get 0) (local.get 1)))
+        (func (export "run") (param i32 i32) (result i32)
+            (call $divmod (call $abs (local.get 0)) (local.get 1))
+            i32.add
+            (call $load (i32.const 8))
+            i32.add)
+        (func (export "twice") (param i32 i32 i32) (result i32)
+            (call $divmod (local.get 0) (local.get 1))
+            drop
+            drop
+            (call $divmod (local.get 0) (local.get 2))
+            i32.add)
+    )
+"#;
+
+fn instantiate(inlining: bool) -> Result<(Store<()>, Instance)> {
+    let mut config = Config::new();
+    config.cranelift_inlining(inlining);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, WAT)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    Ok((store, instance))
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn inlined_calls_behave_the_same() -> Result<()> {
+    for inlining in [false, true] {
+        let (mut store, instance) = instantiate(inlining)?;
+        let store_fn = instance.get_typed_func::<(i32, i32), ()>(&mut store, "store")?;
+        let run = instance.get_typed_func::<(i32, i32), i32>(&mut store, "run")?;
+        store_fn.call(&mut store, (8, 100))?;
+        assert_eq!(run.call(&mut store, (17, 5))?, 105);
+        assert_eq!(run.call(&mut store, (-17, 5))?, 105);
+        assert_eq!(run.call(&mut store, (3, 7))?, 103);
+    }
+    Ok(())
+}
+
+/// Returns the function index and module offset of each frame of the trap
+/// which calling `name` with `args` results in.
+fn trap_frames(inlining: bool, name: &str, args: &[Val]) -> Result<Vec<(u32, usize)>> {
+    let (mut store, instance) = instantiate(inlining)?;
+    let func = instance.get_func(&mut store, name).unwrap();
+    let mut results = vec![Val::I32(0); func.ty(&store).results().len()];
+    let err = func.call(&mut store, args, &mut results).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Trap>(),
+        Some(&Trap::IntegerDivisionByZero)
+    );
+    let trace = err.downcast_ref::<WasmBacktrace>().unwrap();
+    Ok(trace
+        .frames()
+        .iter()
+        .map(|f| (f.func_index(), f.module_offset().unwrap()))
+        .collect())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn traps_in_inlined_calls() -> Result<()> {
+    // The trap is reported within the callee regardless of whether it was
+    // inlined, followed by its caller at the call.
+    let args = [Val::I32(17), Val::I32(0)];
+    let frames = trap_frames(false, "run", &args)?;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0, 1);
+    assert_eq!(frames[1].0, 4);
+    assert_eq!(trap_frames(true, "run", &args)?, frames);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn backtraces_through_calls_inlined_twice() -> Result<()> {
+    // `$divmod` is inlined at both calls in `twice`, and the second one traps.
+    let args = [Val::I32(17), Val::I32(5), Val::I32(0)];
+    let frames = trap_frames(false, "twice", &args)?;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0, 1);
+    assert_eq!(frames[1].0, 5);
+    assert_eq!(trap_frames(true, "twice", &args)?, frames);
+
+    // Trapping in the first call reports a different call site.
+    let args = [Val::I32(17), Val::I32(0), Val::I32(5)];
+    let first = trap_frames(true, "twice", &args)?;
+    assert_eq!(first, trap_frames(false, "twice", &args)?);
+    assert_eq!(first[0], frames[0]);
+    assert!(first[1].1 < frames[1].1);
+    Ok(())
+}
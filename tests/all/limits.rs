@@ -1175,3 +1175,257 @@ fn growth_trap() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn fiber_stack_limit() -> Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    const STACK_SIZE: usize = 1 << 20;
+
+    let mut config = Config::new();
+    config.async_support(true);
+    config.async_stack_size(STACK_SIZE);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "reenter" (func $reenter))
+                (func (export "leaf"))
+                (func (export "reenter") call $reenter))
+        "#,
+    )?;
+
+    // Creates a store whose `reenter` export calls back into `leaf` from the
+    // host, which needs a second fiber stack while the first is still in use.
+    let new_store = |limits: StoreLimits| -> Result<_> {
+        let mut store = Store::new(&engine, limits);
+        store.limiter(|s| s as &mut dyn ResourceLimiter);
+        let leaf = Arc::new(Mutex::new(None::<TypedFunc<(), ()>>));
+        let reenter = {
+            let leaf = leaf.clone();
+            Func::wrap_async(&mut store, move |mut caller, _: ()| {
+                let leaf = leaf.lock().unwrap().clone().unwrap();
+                Box::new(async move { leaf.call_async(&mut caller, ()).await })
+            })
+        };
+        Ok((store, leaf, reenter))
+    };
+
+    // Each store holds at most one stack, so nested calls are denied.
+    let (mut store, leaf, reenter) = new_store(
+        StoreLimitsBuilder::new()
+            .fiber_stack_bytes(STACK_SIZE)
+            .build(),
+    )?;
+    let instance = Instance::new_async(&mut store, &module, &[reenter.into()]).await?;
+    *leaf.lock().unwrap() = Some(instance.get_typed_func(&mut store, "leaf")?);
+    let f = instance.get_typed_func::<(), ()>(&mut store, "leaf")?;
+    f.call_async(&mut store, ()).await?;
+    assert_eq!(store.resource_usage().fiber_stack_bytes, STACK_SIZE);
+    let f = instance.get_typed_func::<(), ()>(&mut store, "reenter")?;
+    let err = f.call_async(&mut store, ()).await.unwrap_err();
+    assert!(
+        format!("{err:?}")
+            .contains("fiber stack of 1048576 bytes would grow fiber stacks to 2097152 bytes"),
+        "bad error: {err:?}"
+    );
+    // The failed call's stack is released rather than kept for reuse.
+    assert_eq!(store.resource_usage().fiber_stack_bytes, 0);
+
+    let (mut store, leaf, reenter) = new_store(
+        StoreLimitsBuilder::new()
+            .fiber_stack_bytes(STACK_SIZE)
+            .trap_on_grow_failure(true)
+            .build(),
+    )?;
+    let instance = Instance::new_async(&mut store, &module, &[reenter.into()]).await?;
+    *leaf.lock().unwrap() = Some(instance.get_typed_func(&mut store, "leaf")?);
+    let f = instance.get_typed_func::<(), ()>(&mut store, "reenter")?;
+    let err = f.call_async(&mut store, ()).await.unwrap_err();
+    assert!(
+        format!("{err:?}").contains("forcing error when growing fiber stacks to 2097152 bytes"),
+        "bad error: {err:?}"
+    );
+
+    // Room for two stacks allows one level of reentrancy.
+    let (mut store, leaf, reenter) = new_store(
+        StoreLimitsBuilder::new()
+            .fiber_stack_bytes(2 * STACK_SIZE)
+            .build(),
+    )?;
+    let instance = Instance::new_async(&mut store, &module, &[reenter.into()]).await?;
+    *leaf.lock().unwrap() = Some(instance.get_typed_func(&mut store, "leaf")?);
+    let f = instance.get_typed_func::<(), ()>(&mut store, "reenter")?;
+    for _ in 0..3 {
+        f.call_async(&mut store, ()).await?;
+    }
+
+    // Only the stack kept for the next call remains once calls return.
+    assert_eq!(store.resource_usage().fiber_stack_bytes, STACK_SIZE);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn fiber_stack_limiter_async() -> Result<()> {
+    struct Limiter {
+        stacks_allocated: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl ResourceLimiterAsync for Limiter {
+        async fn memory_growing(
+            &mut self,
+            _current: usize,
+            _desired: usize,
+            _maximum: Option<usize>,
+        ) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn table_growing(
+            &mut self,
+            _current: usize,
+            _desired: usize,
+            _maximum: Option<usize>,
+        ) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn fiber_stack_allocating(
+            &mut self,
+            _current: usize,
+            _desired: usize,
+        ) -> Result<bool> {
+            tokio::task::yield_now().await;
+            self.stacks_allocated += 1;
+            Ok(self.stacks_allocated <= 1)
+        }
+    }
+
+    let mut config = Config::new();
+    config.async_support(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, r#"(module (func (export "f")))"#)?;
+
+    let mut store = Store::new(
+        &engine,
+        Limiter {
+            stacks_allocated: 0,
+        },
+    );
+    store.limiter_async(|s| s as &mut dyn ResourceLimiterAsync);
+
+    // The store reuses its stack, so only the first call allocates one.
+    let instance = Instance::new_async(&mut store, &module, &[]).await?;
+    let f = instance.get_typed_func::<(), ()>(&mut store, "f")?;
+    for _ in 0..3 {
+        f.call_async(&mut store, ()).await?;
+    }
+    assert_eq!(store.data().stacks_allocated, 1);
+
+    // A new store needs a new stack, which is denied.
+    let mut store = Store::new(
+        &engine,
+        Limiter {
+            stacks_allocated: 1,
+        },
+    );
+    store.limiter_async(|s| s as &mut dyn ResourceLimiterAsync);
+    assert!(Instance::new_async(&mut store, &module, &[]).await.is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn component_instances_limit() -> Result<()> {
+    let engine = Engine::default();
+    let component = component::Component::new(&engine, "(component)")?;
+    let linker = component::Linker::new(&engine);
+
+    let mut store = Store::new(
+        &engine,
+        StoreLimitsBuilder::new().component_instances(2).build(),
+    );
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+    linker.instantiate(&mut store, &component)?;
+    linker.instantiate(&mut store, &component)?;
+    match linker.instantiate(&mut store, &component) {
+        Ok(_) => panic!("should have hit the component instance limit"),
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "resource limit exceeded: component instance count too high at 3"
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resource_handles_limit() -> Result<()> {
+    let engine = Engine::default();
+    let c = component::Component::new(
+        &engine,
+        r#"
+            (component
+                (type $t' (resource (rep i32)))
+                (export $t "t" (type $t'))
+
+                (core func $new (canon resource.new $t))
+                (core module $m
+                    (import "" "new" (func $new (param i32) (result i32)))
+                    (func (export "new-many") (param i32)
+                        loop
+                            (drop (call $new (local.get 0)))
+                            (local.tee 0 (i32.sub (local.get 0) (i32.const 1)))
+                            br_if 0
+                        end)
+                )
+                (core instance $i (instantiate $m
+                    (with "" (instance (export "new" (func $new))))
+                ))
+
+                (func (export "[constructor]t") (param "x" u32) (result (own $t))
+                    (canon lift (core func $new)))
+                (func (export "new-many") (param "n" u32)
+                    (canon lift (core func $i "new-many")))
+            )
+        "#,
+    )?;
+    let linker = component::Linker::new(&engine);
+
+    let mut store = Store::new(
+        &engine,
+        StoreLimitsBuilder::new().resource_handles(3).build(),
+    );
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+    let i = linker.instantiate(&mut store, &c)?;
+
+    // Handles created by the guest in its own table trap when the table is
+    // full.
+    let new_many = i.get_typed_func::<(u32,), ()>(&mut store, "new-many")?;
+    let err = new_many.call(&mut store, (4,)).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("resource handle count too high at 4"),
+        "bad error: {err:?}"
+    );
+
+    // Handles passed to the host fill the host's table.
+    let mut store = Store::new(&engine, store.into_data());
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+    let i = linker.instantiate(&mut store, &c)?;
+    let ctor =
+        i.get_typed_func::<(u32,), (component::ResourceAny,)>(&mut store, "[constructor]t")?;
+    for x in 0..3 {
+        ctor.call(&mut store, (x,))?;
+        ctor.post_return(&mut store)?;
+    }
+    assert!(ctor.call(&mut store, (3,)).is_err());
+
+    Ok(())
+}
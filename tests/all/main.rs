@@ -11,7 +11,9 @@ mod coredump;
 mod custom_code_memory;
 mod debug;
 mod defaults;
+mod deterministic;
 mod epoch_interruption;
+mod exceptions;
 mod externals;
 mod fuel;
 mod func;
@@ -23,6 +25,7 @@ mod i31ref;
 mod iloop;
 mod import_calling_export;
 mod import_indexes;
+mod inlining;
 mod instance;
 mod invoke_func_via_table;
 mod limits;
@@ -35,14 +38,19 @@ mod name;
 mod noextern;
 mod piped_tests;
 mod pooling_allocator;
+mod profile_data;
 mod pulley;
+mod record_replay;
 mod relocs;
+mod snapshot;
 mod stack_creator;
 mod stack_overflow;
+mod stack_switching;
 mod store;
 mod structs;
 mod table;
 mod threads;
+mod tiered;
 mod traps;
 mod types;
 mod wait_notify;
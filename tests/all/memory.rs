@@ -490,6 +490,60 @@ fn dynamic_extra_growth_unchanged_pointer(config: &mut Config) -> Result<()> {
     Ok(())
 }
 
+#[wasmtime_test]
+#[cfg_attr(miri, ignore)]
+fn memory64_zero_extended_index(config: &mut Config) -> Result<()> {
+    // Bounds checks for zero-extended 32-bit indices into 64-bit memories may
+    // be elided by relying on guard pages, so make sure that out-of-bounds
+    // accesses with such indices still trap.
+    config.wasm_memory64(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory i64 1 2)
+
+                (func (export "grow") (param i64) (result i64)
+                    local.get 0
+                    memory.grow)
+
+                (func (export "i32.load") (param i32) (result i32)
+                    local.get 0
+                    i64.extend_i32_u
+                    i32.load)
+
+                (func (export "i32.store") (param i32 i32)
+                    local.get 0
+                    i64.extend_i32_u
+                    local.get 1
+                    i32.store)
+            )
+        "#,
+    )?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let grow = instance.get_typed_func::<u64, i64>(&mut store, "grow")?;
+    let i32_load = instance.get_typed_func::<u32, i32>(&mut store, "i32.load")?;
+    let i32_store = instance.get_typed_func::<(u32, i32), ()>(&mut store, "i32.store")?;
+
+    let page = 64 * 1024;
+    i32_store.call(&mut store, (page - 4, 1))?;
+    assert_eq!(i32_load.call(&mut store, page - 4)?, 1);
+    assert!(i32_load.call(&mut store, page - 3).is_err());
+    assert!(i32_load.call(&mut store, page).is_err());
+    assert!(i32_store.call(&mut store, (u32::MAX - 3, 1)).is_err());
+    assert!(i32_load.call(&mut store, u32::MAX).is_err());
+
+    assert_eq!(grow.call(&mut store, 1)?, 1);
+    i32_store.call(&mut store, (2 * page - 4, 2))?;
+    assert_eq!(i32_load.call(&mut store, 2 * page - 4)?, 2);
+    assert!(i32_load.call(&mut store, 2 * page).is_err());
+
+    Ok(())
+}
+
 // This test exercises trying to create memories of the maximum 64-bit memory
 // size of `1 << 48` pages. This should always fail but in the process of
 // determining this failure we shouldn't hit any overflows or anything like that
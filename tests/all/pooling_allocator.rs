@@ -636,19 +636,19 @@ fn instance_too_large() -> Result<()> {
     let engine = Engine::new(&config)?;
     let expected = if cfg!(feature = "wmemcheck") {
         "\
-        instance allocation for this module requires 336 bytes which exceeds the \
+        instance allocation for this module requires 352 bytes which exceeds the \
 configured maximum of 16 bytes; breakdown of allocation requirement:
 
- * 76.19% - 256 bytes - instance state management
- * 21.43% - 72 bytes - static vmctx data
+ * 77.27% - 272 bytes - instance state management
+ * 20.45% - 72 bytes - static vmctx data
 "
     } else {
         "\
-        instance allocation for this module requires 240 bytes which exceeds the \
+        instance allocation for this module requires 256 bytes which exceeds the \
 configured maximum of 16 bytes; breakdown of allocation requirement:
 
- * 66.67% - 160 bytes - instance state management
- * 30.00% - 72 bytes - static vmctx data
+ * 68.75% - 176 bytes - instance state management
+ * 28.12% - 72 bytes - static vmctx data
 "
     };
     match Module::new(&engine, "(module)") {
@@ -664,19 +664,19 @@ configured maximum of 16 bytes; breakdown of allocation requirement:
 
     let expected = if cfg!(feature = "wmemcheck") {
         "\
-instance allocation for this module requires 1936 bytes which exceeds the \
+instance allocation for this module requires 1952 bytes which exceeds the \
 configured maximum of 16 bytes; breakdown of allocation requirement:
 
- * 13.22% - 256 bytes - instance state management
- * 82.64% - 1600 bytes - defined globals
+ * 13.93% - 272 bytes - instance state management
+ * 81.97% - 1600 bytes - defined globals
 "
     } else {
         "\
-instance allocation for this module requires 1840 bytes which exceeds the \
+instance allocation for this module requires 1856 bytes which exceeds the \
 configured maximum of 16 bytes; breakdown of allocation requirement:
 
- * 8.70% - 160 bytes - instance state management
- * 86.96% - 1600 bytes - defined globals
+ * 9.48% - 176 bytes - instance state management
+ * 86.21% - 1600 bytes - defined globals
 "
     };
     match Module::new(&engine, &lots_of_globals) {
@@ -795,6 +795,67 @@ fn dynamic_memory_pooling_allocator() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn memory64_larger_than_4gib() -> Result<()> {
+    if skip_pooling_allocator_tests() {
+        return Ok(());
+    }
+
+    let max_size = 6 << 30;
+    let mut pool = crate::small_pool_config();
+    pool.max_memory_size(max_size);
+    let mut config = Config::new();
+    config.wasm_memory64(true);
+    config.memory_reservation(max_size as u64);
+    config.allocation_strategy(pool);
+
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory (export "memory") i64 1)
+
+                (func (export "grow") (param i64) (result i64)
+                    local.get 0
+                    memory.grow)
+
+                (func (export "i32.load") (param i64) (result i32)
+                    local.get 0
+                    i32.load)
+
+                (func (export "i32.store") (param i64 i32)
+                    local.get 0
+                    local.get 1
+                    i32.store)
+            )
+        "#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let grow = instance.get_typed_func::<u64, i64>(&mut store, "grow")?;
+    let i32_load = instance.get_typed_func::<u64, i32>(&mut store, "i32.load")?;
+    let i32_store = instance.get_typed_func::<(u64, i32), ()>(&mut store, "i32.store")?;
+
+    // Grow the memory past 4GiB and access its last bytes.
+    let page = 64 * 1024;
+    let pages = (5 << 30) / page;
+    assert_eq!(grow.call(&mut store, pages - 1)?, 1);
+    let last = (5 << 30) - 4;
+    i32_store.call(&mut store, (last, 100))?;
+    assert_eq!(i32_load.call(&mut store, last)?, 100);
+    assert!(i32_load.call(&mut store, last + 4).is_err());
+
+    // The memory can grow up to the maximum size of the pool's slots, but no
+    // further.
+    assert_eq!(grow.call(&mut store, (1 << 30) / page)?, pages as i64);
+    assert_eq!(grow.call(&mut store, 1)?, -1);
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn zero_memory_pages_disallows_oob() -> Result<()> {
@@ -1216,6 +1277,78 @@ fn decommit_batching() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn pooling_allocator_metrics() -> Result<()> {
+    const TOTAL_MEMORIES: u32 = 4;
+
+    let mut pool = crate::small_pool_config();
+    pool.total_memories(TOTAL_MEMORIES)
+        .total_tables(TOTAL_MEMORIES)
+        .total_core_instances(TOTAL_MEMORIES + 1)
+        .linear_memory_keep_resident(1 << 16)
+        .decommit_batch_size(1)
+        .memory_protection_keys(MpkEnabled::Disable);
+    let mut config = Config::new();
+    config.allocation_strategy(pool);
+
+    let engine = Engine::new(&config)?;
+    let linker = Linker::new(&engine);
+    let module = Module::new(&engine, "(module (memory 1 1) (table 1 funcref))")?;
+
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    assert_eq!(metrics.core_instances, 0);
+    assert_eq!(metrics.memories.capacity, TOTAL_MEMORIES as usize);
+    assert_eq!(metrics.memories.live, 0);
+    assert_eq!(metrics.memories.free, TOTAL_MEMORIES as usize);
+    assert_eq!(metrics.memory_stripes.len(), 1);
+
+    let mut store = Store::new(&engine, ());
+    for _ in 0..2 {
+        linker.instantiate(&mut store, &module)?;
+    }
+
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    assert_eq!(metrics.core_instances, 2);
+    assert_eq!(metrics.memories.live, 2);
+    assert_eq!(metrics.memories.free, TOTAL_MEMORIES as usize - 2);
+    assert_eq!(metrics.tables.live, 2);
+
+    drop(store);
+
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    assert_eq!(metrics.core_instances, 0);
+    assert_eq!(metrics.memories.live, 0);
+    assert_eq!(metrics.memories.unused_warm, 2);
+    assert_eq!(metrics.memories.resident_bytes, 2 << 16);
+    assert_eq!(metrics.tables.live, 0);
+    assert_eq!(metrics.tables.unused_warm, 2);
+    assert_eq!(metrics.pending_decommit_slots, 0);
+    assert_eq!(metrics.allocation_failures, Default::default());
+
+    // Reusing a warm slot no longer counts its bytes as resident-but-unused.
+    let mut store = Store::new(&engine, ());
+    linker.instantiate(&mut store, &module)?;
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    assert_eq!(metrics.memories.live, 1);
+    assert_eq!(metrics.memories.unused_warm, 1);
+    assert_eq!(metrics.memories.resident_bytes, 1 << 16);
+
+    for _ in 1..TOTAL_MEMORIES {
+        linker.instantiate(&mut store, &module)?;
+    }
+    assert!(linker.instantiate(&mut store, &module).is_err());
+
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    assert_eq!(metrics.memories.live, TOTAL_MEMORIES as usize);
+    assert_eq!(metrics.allocation_failures.memory_limit, 1);
+    assert_eq!(metrics.allocation_failures.core_instance_limit, 0);
+
+    assert!(Engine::default().pooling_allocator_metrics().is_none());
+
+    Ok(())
+}
+
 #[test]
 fn tricky_empty_table_with_empty_virtual_memory_alloc() -> Result<()> {
     // Configure the pooling allocator to have no access to virtual memory, e.g.
@@ -0,0 +1,168 @@
+use std::time::Duration;
+use wasmtime::component::{Component, Linker};
+use wasmtime::*;
+
+const FUNCS: &str = r#"
+    (func $inc (param i32) (result i32)
+        (i32.add (local.get 0) (i32.const 1)))
+    (func (export "run") (param i32) (result i32)
+        (local $i i32)
+        (local $sum i32)
+        (loop $hot
+            (local.set $sum (call $inc (local.get $sum)))
+            (local.set $i (i32.add (local.get $i) (i32.const 1)))
+            (br_if $hot (i32.lt_u (local.get $i) (local.get 0))))
+        (if (i32.eqz (local.get 0))
+            (then
+                (loop $cold
+                    (local.set $sum (i32.add (local.get $sum) (i32.const 2)))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br_if $cold (i32.lt_u (local.get $i) (i32.const 10))))))
+        (local.get $sum))
+"#;
+
+fn module_wat() -> String {
+    format!("(module {FUNCS})")
+}
+
+fn component_wat() -> String {
+    format!(
+        r#"
+            (component
+                (core module $m {FUNCS})
+                (core instance $i (instantiate $m))
+                (func (export "run") (param "n" u32) (result u32)
+                    (canon lift (core func $i "run"))))
+        "#
+    )
+}
+
+/// Takes a sample at every epoch check of the profiler in `store`.
+fn sample_at_epoch_checks(store: &mut Store<Option<GuestProfiler>>) {
+    store.set_epoch_deadline(0);
+    store.epoch_deadline_callback(|mut store| {
+        let mut profiler = store.data_mut().take().unwrap();
+        profiler.sample(&store, Duration::ZERO);
+        *store.data_mut() = Some(profiler);
+        Ok(UpdateDeadline::Continue(0))
+    });
+}
+
+fn profile(engine: &Engine, module: &Module) -> Result<ProfileData> {
+    let profiler = GuestProfiler::new(
+        "test",
+        Duration::from_millis(1),
+        vec![("test".to_string(), module.clone())],
+    );
+    let mut store = Store::new(engine, Some(profiler));
+    sample_at_epoch_checks(&mut store);
+
+    let instance = Instance::new(&mut store, module, &[])?;
+    let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, 100)?, 100);
+
+    let profiler = store.data_mut().take().unwrap();
+    assert!(profiler
+        .profile_data(&Module::new(engine, "(module)")?)
+        .is_none());
+    Ok(profiler.profile_data(module).unwrap().clone())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn profile_guided_compilation() -> Result<()> {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, module_wat())?;
+    let data = profile(&engine, &module)?;
+    assert!(!data.is_empty());
+
+    // Profiles can be stored and loaded again.
+    let data: ProfileData = serde_json::from_str(&serde_json::to_string(&data)?)?;
+
+    for inlining in [false, true] {
+        let mut config = Config::new();
+        config.cranelift_inlining(inlining);
+        let engine = Engine::new(&config)?;
+        let wasm = wat::parse_str(module_wat())?;
+        let module = CodeBuilder::new(&engine)
+            .wasm_binary(&wasm, None)?
+            .profile_data(&data)
+            .compile_module()?;
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+        assert_eq!(run.call(&mut store, 100)?, 100);
+        assert_eq!(run.call(&mut store, 0)?, 19);
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn profile_of_other_binary_rejected() -> Result<()> {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, module_wat())?;
+    let data = profile(&engine, &module)?;
+
+    let wasm = wat::parse_str(format!("(module {FUNCS} (func))"))?;
+    let err = CodeBuilder::new(&Engine::default())
+        .wasm_binary(&wasm, None)?
+        .profile_data(&data)
+        .compile_module()
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("profile data was collected from a different wasm binary"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn profile_guided_component_compilation() -> Result<()> {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+    let component = Component::new(&engine, component_wat())?;
+    let profiler =
+        GuestProfiler::new_component("test", Duration::from_millis(1), component.clone(), vec![]);
+    let mut store = Store::new(&engine, Some(profiler));
+    sample_at_epoch_checks(&mut store);
+
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(u32,), (u32,)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, (100,))?, (100,));
+
+    let profiler = store.data_mut().take().unwrap();
+    let data = profiler.component_profile_data(&component).unwrap().clone();
+    assert!(!data.is_empty());
+
+    // The profile covers the component, not the core module within it.
+    let wasm = wat::parse_str(component_wat())?;
+    let engine = Engine::default();
+    let err = CodeBuilder::new(&engine)
+        .wasm_binary(&wat::parse_str(module_wat())?, None)?
+        .profile_data(&data)
+        .compile_module()
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("profile data was collected from a different wasm binary"),
+        "{err:?}"
+    );
+
+    let component = CodeBuilder::new(&engine)
+        .wasm_binary(&wasm, None)?
+        .profile_data(&data)
+        .compile_component()?;
+    let mut store = Store::new(&engine, ());
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(u32,), (u32,)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, (100,))?, (100,));
+    Ok(())
+}
@@ -0,0 +1,342 @@
+use anyhow::bail;
+use std::io::{Cursor, Write};
+use std::sync::{Arc, Mutex};
+use wasmtime::*;
+
+const WAT: &str = r#"
+    (module
+        (import "host" "read" (func $read (param i32 i32) (result i32)))
+        (import "host" "now" (func $now (result i64 f64)))
+        (import "host" "fail" (func $fail (param i32)))
+        (memory (export "memory") 1 10)
+        (global (export "counter") (mut i32) (i32.const 0))
+
+        (func (export "read") (param i32 i32) (result i32)
+            (call $read (local.get 0) (local.get 1)))
+        (func (export "now") (result i64 f64)
+            call $now)
+        (func (export "fail") (param i32)
+            (call $fail (local.get 0)))
+        (func (export "sum") (param i32 i32) (result i32)
+            (local $sum i32)
+            (block $done
+                (loop $loop
+                    (br_if $done (i32.eqz (local.get 1)))
+                    (local.set $sum
+                        (i32.add (local.get $sum) (i32.load8_u (local.get 0))))
+                    (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+                    (local.set 1 (i32.sub (local.get 1) (i32.const 1)))
+                    (br $loop)))
+            local.get $sum)
+    )
+"#;
+
+/// A trace shared between the test and the store recording into it.
+#[derive(Clone, Default)]
+struct Trace(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Trace {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Trace {
+    fn reader(&self) -> Cursor<Vec<u8>> {
+        Cursor::new(self.0.lock().unwrap().clone())
+    }
+}
+
+/// Defines the host functions used by `WAT`, where `seed` determines what
+/// they return.
+fn linker(engine: &Engine, seed: u8) -> Result<Linker<u32>> {
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(
+        "host",
+        "read",
+        move |mut caller: Caller<'_, u32>, ptr: u32, len: u32| {
+            *caller.data_mut() += 1;
+            let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+            if len > 0 {
+                memory.grow(&mut caller, 1)?;
+            }
+            let data = memory.data_mut(&mut caller);
+            for (i, byte) in data[ptr as usize..][..len as usize].iter_mut().enumerate() {
+                *byte = seed.wrapping_add(i as u8);
+            }
+            let counter = caller.get_export("counter").unwrap().into_global().unwrap();
+            counter.set(&mut caller, Val::I32(seed.into()))?;
+            Ok(len)
+        },
+    )?;
+    linker.func_new(
+        "host",
+        "now",
+        FuncType::new(engine, [], [ValType::I64, ValType::F64]),
+        move |mut caller, _params, results| {
+            *caller.data_mut() += 1;
+            results[0] = Val::I64(i64::from(seed) << 40);
+            results[1] = Val::F64(f64::from(seed).to_bits());
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "host",
+        "fail",
+        move |mut caller: Caller<'_, u32>, trap: i32| -> Result<()> {
+            *caller.data_mut() += 1;
+            if trap != 0 {
+                bail!(Trap::UnreachableCodeReached)
+            } else {
+                bail!("host error {seed}")
+            }
+        },
+    )?;
+    Ok(linker)
+}
+
+fn instantiate(engine: &Engine, module: &Module, seed: u8) -> Result<(Store<u32>, Instance)> {
+    let mut store = Store::new(engine, 0);
+    let instance = linker(engine, seed)?.instantiate(&mut store, module)?;
+    Ok((store, instance))
+}
+
+fn run(store: &mut Store<u32>, instance: &Instance) -> Result<Vec<String>> {
+    let mut log = Vec::new();
+    let read = instance.get_typed_func::<(u32, u32), u32>(&mut *store, "read")?;
+    let sum = instance.get_typed_func::<(u32, u32), u32>(&mut *store, "sum")?;
+    let now = instance.get_typed_func::<(), (i64, f64)>(&mut *store, "now")?;
+    let fail = instance.get_typed_func::<i32, ()>(&mut *store, "fail")?;
+    let counter = instance.get_global(&mut *store, "counter").unwrap();
+    let memory = instance.get_memory(&mut *store, "memory").unwrap();
+
+    log.push(format!("read {}", read.call(&mut *store, (100, 70_000))?));
+    log.push(format!("sum {}", sum.call(&mut *store, (65_600, 16))?));
+    log.push(format!("size {}", memory.size(&*store)));
+    log.push(format!("counter {:?}", counter.get(&mut *store).i32()));
+    log.push(format!("now {:?}", now.call(&mut *store, ())?));
+    let err = fail.call(&mut *store, 0).unwrap_err();
+    log.push(format!("fail {}", err.root_cause()));
+    let err = fail.call(&mut *store, 1).unwrap_err();
+    log.push(format!("trap {:?}", err.downcast_ref::<Trap>()));
+    Ok(log)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn replay_core_host_calls() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, WAT)?;
+
+    let trace = Trace::default();
+    let (mut store, instance) = instantiate(&engine, &module, 1)?;
+    store.record_host_calls(trace.clone())?;
+    let recorded = run(&mut store, &instance)?;
+    assert_eq!(*store.data(), 4);
+    drop(store);
+
+    // Host functions returning something else must not be called.
+    let (mut store, instance) = instantiate(&engine, &module, 2)?;
+    store.replay_host_calls(trace.reader())?;
+    let replayed = run(&mut store, &instance)?;
+    assert_eq!(*store.data(), 0);
+    assert_eq!(recorded, replayed);
+
+    let (mut store, instance) = instantiate(&engine, &module, 2)?;
+    assert_ne!(recorded, run(&mut store, &instance)?);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn replay_diverges() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, WAT)?;
+
+    let trace = Trace::default();
+    let (mut store, instance) = instantiate(&engine, &module, 1)?;
+    store.record_host_calls(trace.clone())?;
+    let read = instance.get_typed_func::<(u32, u32), u32>(&mut store, "read")?;
+    read.call(&mut store, (0, 4))?;
+    drop(store);
+
+    let (mut store, instance) = instantiate(&engine, &module, 1)?;
+    store.replay_host_calls(trace.reader())?;
+    let read = instance.get_typed_func::<(u32, u32), u32>(&mut store, "read")?;
+    let err = read.call(&mut store, (0, 5)).unwrap_err();
+    assert!(format!("{err:?}").contains("diverged"), "{err:?}");
+
+    let (mut store, instance) = instantiate(&engine, &module, 1)?;
+    store.replay_host_calls(trace.reader())?;
+    let read = instance.get_typed_func::<(u32, u32), u32>(&mut store, "read")?;
+    assert_eq!(read.call(&mut store, (0, 4))?, 4);
+    let err = read.call(&mut store, (0, 4)).unwrap_err();
+    assert!(format!("{err:?}").contains("exhausted"), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn invalid_trace() -> Result<()> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let err = store
+        .replay_host_calls(Cursor::new(b"this is not a trace".to_vec()))
+        .unwrap_err();
+    assert!(
+        format!("{err:?}").contains("not a host call trace"),
+        "{err:?}"
+    );
+
+    store.record_host_calls(Trace::default())?;
+    assert!(store.record_host_calls(Trace::default()).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn replay_component_host_calls() -> Result<()> {
+    use wasmtime::component::{Component, Linker};
+
+    let engine = Engine::default();
+    let component = Component::new(
+        &engine,
+        r#"
+            (component
+                (import "greet" (func $greet (param "name" string) (result string)))
+                (import "random" (func $random (result u64)))
+
+                (core module $libc
+                    (memory (export "memory") 1)
+                    (global $next (mut i32) (i32.const 1024))
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        (local $ret i32)
+                        (local.set $ret (global.get $next))
+                        (global.set $next (i32.add (global.get $next) (local.get 3)))
+                        local.get $ret)
+                )
+                (core instance $libc (instantiate $libc))
+                (core func $greet (canon lower (func $greet)
+                    (memory $libc "memory") (realloc (func $libc "realloc"))))
+                (core func $random (canon lower (func $random)))
+
+                (core module $m
+                    (import "libc" "memory" (memory 1))
+                    (import "host" "greet" (func $greet (param i32 i32 i32)))
+                    (import "host" "random" (func $random (result i64)))
+                    (data (i32.const 100) "wasm")
+                    (func (export "run") (result i64)
+                        (call $greet (i32.const 100) (i32.const 4) (i32.const 200))
+                        (i64.add
+                            (i64.extend_i32_u (i32.load (i32.const 204)))
+                            (call $random)))
+                )
+                (core instance $i (instantiate $m
+                    (with "libc" (instance $libc))
+                    (with "host" (instance
+                        (export "greet" (func $greet))
+                        (export "random" (func $random))))))
+                (func (export "run") (result u64)
+                    (canon lift (core func $i "run")))
+            )
+        "#,
+    )?;
+
+    let run = |seed: u64, trace: Option<&Trace>| -> Result<u64> {
+        let mut linker = Linker::new(&engine);
+        linker
+            .root()
+            .func_wrap("greet", move |_, (name,): (String,)| {
+                Ok((format!("hello {name} {seed}"),))
+            })?;
+        linker
+            .root()
+            .func_wrap("random", move |_, ()| Ok((seed,)))?;
+        let mut store = Store::new(&engine, ());
+        match trace {
+            Some(trace) if seed == 1 => store.record_host_calls(trace.clone())?,
+            Some(trace) => store.replay_host_calls(trace.reader())?,
+            None => {}
+        }
+        let instance = linker.instantiate(&mut store, &component)?;
+        let run = instance.get_typed_func::<(), (u64,)>(&mut store, "run")?;
+        let (result,) = run.call(&mut store, ())?;
+        run.post_return(&mut store)?;
+        Ok(result)
+    };
+
+    let trace = Trace::default();
+    let recorded = run(1, Some(&trace))?;
+    assert_eq!(recorded, "hello wasm 1".len() as u64 + 1);
+    assert_eq!(run(22, Some(&trace))?, recorded);
+    assert_ne!(run(22, None)?, recorded);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn replay_restored_memory() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, WAT)?;
+
+    // WebAssembly overwrites what the host wrote before the host writes the
+    // same bytes again, which must still be part of the trace.
+    let run = |store: &mut Store<u32>, instance: &Instance| -> Result<Vec<u8>> {
+        let read = instance.get_typed_func::<(u32, u32), u32>(&mut *store, "read")?;
+        let memory = instance.get_memory(&mut *store, "memory").unwrap();
+        read.call(&mut *store, (10_000, 8))?;
+        memory.data_mut(&mut *store)[10_000..10_008].fill(0xff);
+        read.call(&mut *store, (10_000, 8))?;
+        Ok(memory.data(&*store)[10_000..10_008].to_vec())
+    };
+
+    let trace = Trace::default();
+    let (mut store, instance) = instantiate(&engine, &module, 1)?;
+    store.record_host_calls(trace.clone())?;
+    let recorded = run(&mut store, &instance)?;
+    assert_eq!(recorded, [1, 2, 3, 4, 5, 6, 7, 8]);
+    drop(store);
+
+    let (mut store, instance) = instantiate(&engine, &module, 2)?;
+    store.replay_host_calls(trace.reader())?;
+    assert_eq!(run(&mut store, &instance)?, recorded);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn shared_memories_rejected() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_threads(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, r#"(module (memory 1 1 shared))"#)?;
+
+    let mut store = Store::new(&engine, ());
+    Instance::new(&mut store, &module, &[])?;
+    let err = store.record_host_calls(Trace::default()).unwrap_err();
+    assert!(format!("{err:?}").contains("shared memories"), "{err:?}");
+    let err = store
+        .replay_host_calls(Trace::default().reader())
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("shared memories"), "{err:?}");
+
+    // Shared memories added after recording started fail the next host call.
+    let linker = linker(&engine, 1)?;
+    let module = Module::new(&engine, WAT)?;
+    let mut store = Store::new(&engine, 0);
+    store.record_host_calls(Trace::default())?;
+    let shared = SharedMemory::new(&engine, MemoryType::shared(1, 1))?;
+    let instance = linker.instantiate(&mut store, &module)?;
+    Instance::new(
+        &mut store,
+        &Module::new(&engine, r#"(module (import "" "" (memory 1 1 shared)))"#)?,
+        &[shared.into()],
+    )?;
+    let now = instance.get_typed_func::<(), (i64, f64)>(&mut store, "now")?;
+    let err = now.call(&mut store, ()).unwrap_err();
+    assert!(format!("{err:?}").contains("shared memories"), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use wasmtime::*;
+
+const WAT: &str = r#"
+    (module
+        (import "" "memory" (memory 1))
+        (memory (export "memory") 1 10)
+        (global $counter (mut i32) (i32.const 0))
+        (global $wide (mut i64) (i64.const 0))
+        (global (export "constant") i32 (i32.const 42))
+        (data (memory 1) (i32.const 0) "hello")
+        (data $passive "passive")
+
+        (func (export "init")
+            ;; Overwrite the data segment and write past the initial size
+            ;; after growing.
+            (i32.store8 1 (i32.const 0) (i32.const 0))
+            (drop (memory.grow 1 (i32.const 2)))
+            (i32.store 1 (i32.const 131072) (i32.const 0xdeadbeef))
+            (global.set $counter (i32.const 7))
+            (global.set $wide (i64.const -1)))
+
+        (func (export "counter") (result i32) global.get $counter)
+        (func (export "wide") (result i64) global.get $wide)
+        (func (export "load") (param i32) (result i32)
+            (i32.load8_u 1 (local.get 0)))
+        (func (export "init-passive") (param i32)
+            (memory.init 1 $passive (local.get 0) (i32.const 0) (i32.const 7))))
+"#;
+
+fn instantiate(
+    store: &mut Store<()>,
+    module: &Module,
+    initialized: bool,
+) -> Result<(Instance, Memory)> {
+    let imported = Memory::new(&mut *store, MemoryType::new(1, None))?;
+    let instance = Instance::new(&mut *store, module, &[imported.into()])?;
+    if initialized {
+        instance
+            .get_typed_func::<(), ()>(&mut *store, "init")?
+            .call(&mut *store, ())?;
+    }
+    let memory = instance.get_memory(&mut *store, "memory").unwrap();
+    Ok((instance, memory))
+}
+
+fn assert_initialized(store: &mut Store<()>, instance: &Instance, memory: &Memory) -> Result<()> {
+    assert_eq!(memory.size(&*store), 3);
+    assert_eq!(&memory.data(&*store)[..5], b"\0ello");
+    assert_eq!(
+        &memory.data(&*store)[131072..131076],
+        &0xdeadbeef_u32.to_le_bytes()
+    );
+    let counter = instance.get_typed_func::<(), i32>(&mut *store, "counter")?;
+    assert_eq!(counter.call(&mut *store, ())?, 7);
+    let wide = instance.get_typed_func::<(), i64>(&mut *store, "wide")?;
+    assert_eq!(wide.call(&mut *store, ())?, -1);
+    let constant = instance.get_global(&mut *store, "constant").unwrap();
+    assert_eq!(constant.get(&mut *store).unwrap_i32(), 42);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn snapshot_and_restore() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, WAT)?;
+
+    let mut store = Store::new(&engine, ());
+    let (instance, _) = instantiate(&mut store, &module, true)?;
+    let snapshot = instance.snapshot(&mut store)?;
+
+    // Restore into a fresh store, going through the serialized form.
+    let snapshot = InstanceSnapshot::deserialize(&snapshot.serialize()?)?;
+    let mut store = Store::new(&engine, ());
+    let (instance, memory) = instantiate(&mut store, &module, false)?;
+    snapshot.restore(&mut store, &instance)?;
+    assert_initialized(&mut store, &instance, &memory)?;
+
+    // An instance which has grown beyond the snapshot can't be restored.
+    memory.grow(&mut store, 1)?;
+    assert!(snapshot.restore(&mut store, &instance).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn preinitialize() -> Result<()> {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT)?;
+    let module = Module::new(&engine, &wasm)?;
+
+    let mut store = Store::new(&engine, ());
+    let (instance, _) = instantiate(&mut store, &module, true)?;
+    let wasm = instance
+        .snapshot(&mut store)?
+        .preinitialize(&wasm, Some("init"))?;
+
+    let module = Module::new(&engine, &wasm)?;
+    assert!(module.get_export("init").is_none());
+
+    let mut store = Store::new(&engine, ());
+    let (instance, memory) = instantiate(&mut store, &module, false)?;
+    assert_initialized(&mut store, &instance, &memory)?;
+
+    // Passive data segments are kept.
+    instance
+        .get_typed_func::<i32, ()>(&mut store, "init-passive")?
+        .call(&mut store, 100)?;
+    let load = instance.get_typed_func::<i32, i32>(&mut store, "load")?;
+    assert_eq!(load.call(&mut store, 100)?, i32::from(b'p'));
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn snapshot_rejects_mutable_reference_globals() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"(module (global (mut funcref) (ref.null func)))"#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    assert!(instance.snapshot(&mut store).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn snapshot_rejects_modified_tables() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (table $t (export "table") 2 funcref)
+                (elem (table $t) (i32.const 0) func $f)
+                (func $f)
+                (func (export "set") (table.set $t (i32.const 1) (ref.func $f)))
+                (func (export "grow") (drop (table.grow $t (ref.null func) (i32.const 1)))))
+        "#,
+    )?;
+
+    // Tables which still have their initial contents are fine, even once
+    // their lazily-initialized elements have been read.
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let table = instance.get_table(&mut store, "table").unwrap();
+    assert!(table.get(&mut store, 0).is_some());
+    instance.snapshot(&mut store)?;
+
+    for func in ["set", "grow"] {
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        instance
+            .get_typed_func::<(), ()>(&mut store, func)?
+            .call(&mut store, ())?;
+        assert!(instance.snapshot(&mut store).is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn preinitialize_rejects_mismatched_memories() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, r#"(module (memory 1))"#)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let snapshot = instance.snapshot(&mut store)?;
+
+    for wat in [r#"(module (memory 1) (memory 1))"#, r#"(module)"#] {
+        let wasm = wat::parse_str(wat)?;
+        assert!(snapshot.preinitialize(&wasm, None).is_err());
+    }
+
+    Ok(())
+}
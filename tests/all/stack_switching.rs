@@ -0,0 +1,306 @@
+use wasmtime::*;
+
+fn config() -> Config {
+    let mut config = Config::new();
+    config.wasm_function_references(true);
+    config.wasm_exceptions(true);
+    config.wasm_stack_switching(true);
+    config
+}
+
+/// A module whose `start` export creates a continuation that suspends with
+/// `$yield` once before returning, and returns it suspended.
+const SUSPEND_ONCE: &str = r#"
+    (module
+        (type $ft (func))
+        (type $ct (cont $ft))
+        (tag $yield)
+        (func $body (suspend $yield))
+        (elem declare func $body)
+        (func (export "start") (result (ref null $ct))
+            (block $h (result (ref $ct))
+                (resume $ct (on $yield $h) (cont.new $ct (ref.func $body)))
+                (return (ref.null $ct))))
+        (func (export "finish") (param (ref null $ct))
+            (resume $ct (local.get 0)))
+    )
+"#;
+
+#[test]
+fn config_validation() -> Result<()> {
+    let mut config = config();
+    config.wasm_exceptions(false);
+    let err = Engine::new(&config).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("requires exceptions"),
+        "bad error: {err:?}"
+    );
+
+    let mut config = self::config();
+    config.async_support(true);
+    let err = Engine::new(&config).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("not compatible with async support"),
+        "bad error: {err:?}"
+    );
+
+    let mut config = self::config();
+    config.max_wasm_stack(1 << 20).async_stack_size(1 << 19);
+    assert!(Engine::new(&config).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gc_refs_survive_suspension() -> Result<()> {
+    let engine = Engine::new(&config())?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $ft (func (param externref) (result externref)))
+                (type $ct (cont $ft))
+                (type $ft0 (func (result externref)))
+                (type $ct0 (cont $ft0))
+                (tag $yield (param externref) (result externref))
+                (import "" "gc" (func $gc))
+
+                ;; Yields its argument and returns what it is resumed with.
+                (func $body (param externref) (result externref)
+                    (suspend $yield (local.get 0)))
+                (elem declare func $body)
+
+                (func (export "run") (param externref externref) (result externref)
+                    (local $k (ref null $ct))
+                    (block $h (result externref (ref $ct))
+                        (resume $ct0 (on $yield $h)
+                            (cont.bind $ct $ct0
+                                (local.get 0)
+                                (cont.new $ct (ref.func $body))))
+                        (unreachable))
+                    (local.set $k)
+                    ;; The continuation's stack holds a reference while it is
+                    ;; suspended.
+                    (call $gc)
+                    (drop)
+                    (resume $ct (local.get 1) (local.get $k)))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let gc = Func::wrap(&mut store, |mut caller: Caller<'_, ()>| caller.gc());
+    let instance = Instance::new(&mut store, &module, &[gc.into()])?;
+    let run = instance.get_typed_func::<
+        (Option<Rooted<ExternRef>>, Option<Rooted<ExternRef>>),
+        Option<Rooted<ExternRef>>,
+    >(&mut store, "run")?;
+
+    for i in 0..10 {
+        let a = ExternRef::new(&mut store, i)?;
+        let b = ExternRef::new(&mut store, i + 100)?;
+        let result = run.call(&mut store, (Some(a), Some(b)))?.unwrap();
+        assert_eq!(
+            *result.data(&store)?.unwrap().downcast_ref::<i32>().unwrap(),
+            i + 100
+        );
+        store.gc();
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn abandoned_continuations_are_cancelled() -> Result<()> {
+    const STACK_SIZE: usize = 1 << 20;
+    let mut config = config();
+    config.async_stack_size(STACK_SIZE);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, SUSPEND_ONCE)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let start = instance.get_func(&mut store, "start").unwrap();
+
+    for _ in 0..100 {
+        let mut scope = RootScope::new(&mut store);
+        let mut results = [Val::null_extern_ref()];
+        start.call(&mut scope, &[], &mut results)?;
+        drop(scope);
+        store.gc();
+    }
+    // Continuations whose references were collected are cancelled as new
+    // ones are created, so their stacks don't pile up.
+    assert!(store.resource_usage().fiber_stack_bytes <= 2 * STACK_SIZE);
+
+    // Dropping the store cancels the remaining suspended continuations.
+    let mut results = [Val::null_extern_ref()];
+    start.call(&mut store, &[], &mut results)?;
+    drop(store);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn host_can_resume_continuations() -> Result<()> {
+    let engine = Engine::new(&config())?;
+    let module = Module::new(&engine, SUSPEND_ONCE)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let start = instance.get_func(&mut store, "start").unwrap();
+    let finish = instance.get_func(&mut store, "finish").unwrap();
+
+    let mut results = [Val::null_extern_ref()];
+    start.call(&mut store, &[], &mut results)?;
+    let k = results[0].ref_().unwrap();
+    assert!(k.ty(&store)?.matches(&RefType::CONTREF));
+
+    finish.call(&mut store, &[k.clone().into()], &mut [])?;
+    let err = finish.call(&mut store, &[k.into()], &mut []).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Trap>(),
+        Some(&Trap::ContinuationAlreadyConsumed)
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stack_allocation_can_be_limited() -> Result<()> {
+    struct Limiter;
+
+    impl ResourceLimiter for Limiter {
+        fn memory_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+            Ok(true)
+        }
+        fn table_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+            Ok(true)
+        }
+        fn fiber_stack_allocating(&mut self, _: usize, _: usize) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    let engine = Engine::new(&config())?;
+    let module = Module::new(&engine, SUSPEND_ONCE)?;
+    let mut store = Store::new(&engine, Limiter);
+    store.limiter(|l| l);
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let start = instance.get_func(&mut store, "start").unwrap();
+    let err = start
+        .call(&mut store, &[], &mut [Val::null_extern_ref()])
+        .unwrap_err();
+    assert!(
+        format!("{err:?}").contains("continuation stack"),
+        "bad error: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn suspend_through_host_is_unhandled() -> Result<()> {
+    let engine = Engine::new(&config())?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $ft (func))
+                (type $ct (cont $ft))
+                (tag $yield (export "yield"))
+                (import "" "host" (func $host))
+                (func (export "suspend") (suspend $yield))
+                (func $body (call $host))
+                (elem declare func $body)
+                (func (export "run")
+                    (block $h (result (ref $ct))
+                        (resume $ct (on $yield $h) (cont.new $ct (ref.func $body)))
+                        (return))
+                    (unreachable))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap("", "host", |mut caller: Caller<'_, ()>| -> Result<()> {
+        let suspend = caller
+            .get_export("suspend")
+            .unwrap()
+            .into_func()
+            .unwrap()
+            .typed::<(), ()>(&caller)?;
+        suspend.call(&mut caller, ())
+    })?;
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    let err = run.call(&mut store, ()).unwrap_err();
+    assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::UnhandledTag));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn backtrace_spans_continuations() -> Result<()> {
+    let engine = Engine::new(&config())?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $ft (func))
+                (type $ct (cont $ft))
+                (func $inner (unreachable))
+                (func $body (call $inner))
+                (elem declare func $body)
+                (func $run (export "run")
+                    (resume $ct (cont.new $ct (ref.func $body))))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    let err = run.call(&mut store, ()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Trap>(),
+        Some(&Trap::UnreachableCodeReached)
+    );
+    let names = err
+        .downcast_ref::<WasmBacktrace>()
+        .unwrap()
+        .frames()
+        .iter()
+        .map(|f| f.func_name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [Some("inner"), Some("body"), Some("run")]);
+
+    // The store can still be used afterwards.
+    assert!(run.call(&mut store, ()).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn fuel_is_shared_with_continuations() -> Result<()> {
+    let mut config = config();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $ft (func))
+                (type $ct (cont $ft))
+                (func $body (loop $l (br $l)))
+                (elem declare func $body)
+                (func (export "run")
+                    (resume $ct (cont.new $ct (ref.func $body))))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    store.set_fuel(10_000)?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    let err = run.call(&mut store, ()).unwrap_err();
+    assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::OutOfFuel));
+    assert_eq!(store.get_fuel()?, 0);
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
-use wasmtime::{Engine, Store};
+use wasmtime::*;
 
 #[test]
 fn into_inner() {
@@ -20,3 +20,90 @@ fn into_inner() {
     Store::new(&engine, A).into_data();
     assert_eq!(HITS.load(SeqCst), 2);
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resource_usage() -> Result<()> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, [0u64; 4]);
+
+    let usage = store.resource_usage();
+    assert_eq!(usage.instances, 0);
+    assert_eq!(usage.memories, 0);
+    assert_eq!(usage.linear_memory_bytes, 0);
+    assert_eq!(usage.host_data_bytes, 32);
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory (export "memory") 2)
+                (table 10 funcref)
+                (func (export "grow") (result i32)
+                    (memory.grow (i32.const 1)))
+            )
+        "#,
+    )?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+
+    let usage = store.resource_usage();
+    assert_eq!(usage.instances, 1);
+    assert_eq!(usage.memories, 1);
+    assert_eq!(usage.tables, 1);
+    assert_eq!(usage.linear_memory_bytes, 2 << 16);
+    assert!(usage.table_bytes >= 10 * std::mem::size_of::<u32>());
+
+    let grow = instance.get_typed_func::<(), i32>(&mut store, "grow")?;
+    assert_eq!(grow.call(&mut store, ())?, 2);
+    assert_eq!(store.resource_usage().linear_memory_bytes, 3 << 16);
+
+    // Host-created memories are accounted for in byte sizes but are not
+    // counted against the limit on memories.
+    Memory::new(&mut store, MemoryType::new(1, None))?;
+    let usage = store.resource_usage();
+    assert_eq!(usage.memories, 1);
+    assert_eq!(usage.linear_memory_bytes, 4 << 16);
+    assert_eq!(
+        usage.total_bytes(),
+        usage.linear_memory_bytes
+            + usage.table_bytes
+            + usage.gc_heap_bytes
+            + usage.fiber_stack_bytes
+            + usage.host_data_bytes
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn resource_usage_fiber_stacks() -> Result<()> {
+    let mut config = Config::new();
+    config.async_support(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, 0);
+    assert_eq!(store.resource_usage().fiber_stack_bytes, 0);
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "f" (func $f))
+                (func (export "run") call $f)
+            )
+        "#,
+    )?;
+    let f = Func::wrap(&mut store, |mut caller: Caller<'_, usize>| {
+        let usage = caller.as_context().resource_usage();
+        *caller.data_mut() = usage.fiber_stack_bytes;
+    });
+    let instance = Instance::new_async(&mut store, &module, &[f.into()]).await?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    run.call_async(&mut store, ()).await?;
+
+    // The stack used while executing is cached by the store afterwards.
+    assert!(*store.data() > 0);
+    assert_eq!(store.resource_usage().fiber_stack_bytes, *store.data());
+
+    Ok(())
+}
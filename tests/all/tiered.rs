@@ -0,0 +1,211 @@
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use wasmtime::component::{Component, Linker};
+use wasmtime::*;
+
+const WAT: &str = r#"
+    (module
+        (import "env" "callback" (func $callback (param i32) (result i32 i64)))
+        (func (export "multi") (param i32) (result i32 i64)
+            (i32.add (local.get 0) (i32.const 1))
+            (i64.extend_i32_u (local.get 0)))
+        (func (export "run") (param i32) (result i64)
+            (local $b i64)
+            (if (i32.lt_s (local.get 0) (i32.const 0)) (then unreachable))
+            (call $callback (local.get 0))
+            local.set $b
+            i64.extend_i32_u
+            (i64.mul (local.get $b) (i64.const 1000))
+            i64.add)
+    )
+"#;
+
+fn engine() -> Result<Engine> {
+    let mut config = Config::new();
+    config
+        .strategy(Strategy::Tiered)
+        .epoch_interruption(true)
+        .tier_up_samples(2);
+    Engine::new(&config)
+}
+
+fn store(engine: &Engine) -> Store<()> {
+    let mut store = Store::new(engine, ());
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_callback(|_| Ok(UpdateDeadline::Continue(1)));
+    store
+}
+
+fn instantiate(store: &mut Store<()>, module: &Module, callback: Func) -> Result<Instance> {
+    Instance::new(&mut *store, module, &[callback.into()])
+}
+
+/// Calls `run`, after incrementing the epoch so the call reaches its epoch
+/// deadline and samples `run`.
+fn run(store: &mut Store<()>, instance: &Instance, x: i32) -> Result<i64> {
+    store.engine().increment_epoch();
+    let run = instance.get_typed_func::<i32, i64>(&mut *store, "run")?;
+    run.call(&mut *store, x)
+}
+
+/// Makes `run` trap and returns the image of the code it was running.
+fn run_image(store: &mut Store<()>, instance: &Instance) -> Range<*const u8> {
+    let err = run(store, instance, -1).unwrap_err();
+    let trace = err.downcast_ref::<WasmBacktrace>().unwrap();
+    trace.frames()[0].module().image_range()
+}
+
+fn wait_for_tier_up(module: &Module) {
+    let start = Instant::now();
+    while !module.is_tiered_up() {
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "module was never recompiled"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn hot_functions_are_recompiled() -> Result<()> {
+    if !cfg!(target_arch = "x86_64") {
+        return Ok(());
+    }
+
+    let engine = engine()?;
+    let module = Module::new(&engine, WAT)?;
+    let mut store = store(&engine);
+    let host = Func::wrap(&mut store, |x: i32| (x * 2, i64::from(x)));
+
+    // The first samples of `run` run Winch-compiled code, and the second one
+    // starts recompiling it in the background.
+    let baseline = instantiate(&mut store, &module, host)?;
+    assert_eq!(run_image(&mut store, &baseline), module.image_range());
+    assert!(!module.is_tiered_up());
+    assert_eq!(run(&mut store, &baseline, 3)?, 3006);
+    wait_for_tier_up(&module);
+
+    // The existing instance switches over to the recompiled code when it next
+    // reaches its epoch deadline, which is during this call.
+    assert_eq!(run_image(&mut store, &baseline), module.image_range());
+    assert_ne!(run_image(&mut store, &baseline), module.image_range());
+    assert_eq!(run(&mut store, &baseline, 4)?, 4008);
+
+    // New instances use the recompiled code right away, but still report the
+    // module they were created from.
+    let optimized = instantiate(&mut store, &module, host)?;
+    assert_ne!(run_image(&mut store, &optimized), module.image_range());
+    assert_eq!(run(&mut store, &optimized, 5)?, 5010);
+    let export = module.get_export_index("run").unwrap();
+    for instance in [baseline, optimized] {
+        assert_eq!(instance.module(&store).image_range(), module.image_range());
+        assert!(instance.get_module_export(&mut store, &export).is_some());
+    }
+
+    // Code from both compilers can call each other: `run` is compiled with
+    // Cranelift and `multi` with Winch.
+    let multi = baseline.get_func(&mut store, "multi").unwrap();
+    let instance = instantiate(&mut store, &module, multi)?;
+    assert_eq!(run(&mut store, &instance, 6)?, 6007);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn deserialized_modules_are_tiered() -> Result<()> {
+    if !cfg!(target_arch = "x86_64") {
+        return Ok(());
+    }
+
+    let engine = engine()?;
+    let bytes = Module::new(&engine, WAT)?.serialize()?;
+    let module = unsafe { Module::deserialize(&engine, &bytes)? };
+    let mut store = store(&engine);
+    let host = Func::wrap(&mut store, |x: i32| (x * 2, i64::from(x)));
+    let instance = instantiate(&mut store, &module, host)?;
+    assert_eq!(run(&mut store, &instance, 1)?, 1002);
+    assert_eq!(run(&mut store, &instance, 2)?, 2004);
+    wait_for_tier_up(&module);
+    let instance = instantiate(&mut store, &module, host)?;
+    assert_ne!(run_image(&mut store, &instance), module.image_range());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn components_are_tiered() -> Result<()> {
+    if !cfg!(target_arch = "x86_64") {
+        return Ok(());
+    }
+
+    let engine = engine()?;
+    let component = Component::new(
+        &engine,
+        r#"
+            (component
+                (core module $m
+                    (func (export "run") (param i32) (result i32)
+                        (if (i32.lt_s (local.get 0) (i32.const 0)) (then unreachable))
+                        (i32.add (local.get 0) (i32.const 1))))
+                (core instance $i (instantiate $m))
+                (func (export "run") (param "x" s32) (result s32)
+                    (canon lift (core func $i "run"))))
+        "#,
+    )?;
+    let linker = Linker::new(&engine);
+    let mut store = store(&engine);
+
+    // Traps poison component instances, so use a fresh instance for each call.
+    let mut run = |x: i32| -> Result<i32> {
+        let instance = linker.instantiate(&mut store, &component)?;
+        let run = instance.get_typed_func::<(i32,), (i32,)>(&mut store, "run")?;
+        engine.increment_epoch();
+        Ok(run.call(&mut store, (x,))?.0)
+    };
+
+    let start = Instant::now();
+    loop {
+        assert_eq!(run(1)?, 2);
+        let err = run(-1).unwrap_err();
+        let trace = err.downcast_ref::<WasmBacktrace>().unwrap();
+        if trace.frames()[0].module().image_range() != component.image_range() {
+            break;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "component was never recompiled"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    Ok(())
+}
+
+#[test]
+fn tiered_requires_epoch_interruption() -> Result<()> {
+    let mut config = Config::new();
+    config.strategy(Strategy::Tiered);
+    let err = Engine::new(&config).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("tiered compilation requires epoch interruption"),
+        "{err}"
+    );
+    Ok(())
+}
+
+#[test]
+fn deterministic_tiered_rejected() -> Result<()> {
+    let mut config = Config::new();
+    config
+        .strategy(Strategy::Tiered)
+        .epoch_interruption(true)
+        .deterministic(true);
+    let err = Engine::new(&config).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("not supported by the Winch compiler"),
+        "{err}"
+    );
+    Ok(())
+}
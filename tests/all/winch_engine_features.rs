@@ -66,3 +66,26 @@ fn ensure_compatibility_between_winch_and_generate_native_debuginfo(
 
     Ok(())
 }
+
+#[wasmtime_test(strategies(only(Winch)))]
+#[cfg_attr(miri, ignore)]
+fn unimplemented_simd_operators_are_compile_errors(config: &mut Config) -> Result<()> {
+    if !cfg!(target_arch = "x86_64") {
+        return Ok(());
+    }
+    let engine = Engine::new(&config)?;
+    let err = Module::new(
+        &engine,
+        r#"
+            (module
+                (func (param v128) (result v128)
+                    (i8x16.shl (local.get 0) (i32.const 1))))
+        "#,
+    )
+    .unwrap_err();
+    assert!(
+        format!("{err:?}").contains("Unimplemented Wasm instruction"),
+        "{err:?}"
+    );
+    Ok(())
+}
@@ -18,12 +18,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32, v4: i64, v5: i32):
 ;; @0027                               trapz v2, user16
+;; @0027                               v9 = load.i64 notrap aligned v0+48
 ;; @0027                               v10 = uextend.i64 v2
 ;; @0027                               v11 = iconst.i64 16
 ;; @0027                               v12 = uadd_overflow_trap v10, v11, user1  ; v11 = 16
 ;; @0027                               v13 = iconst.i64 4
 ;; @0027                               v14 = uadd_overflow_trap v12, v13, user1  ; v13 = 4
-;; @0027                               v9 = load.i64 notrap aligned readonly v0+48
 ;; @0027                               v15 = icmp ule v14, v9
 ;; @0027                               trapz v15, user1
 ;; @0027                               v7 = load.i64 notrap aligned readonly v0+40
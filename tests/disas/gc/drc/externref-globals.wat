@@ -40,11 +40,11 @@
 ;; @0034                               brif v11, block3, block4
 ;;
 ;;                                 block4:
+;; @0034                               v15 = load.i64 notrap aligned v0+48
 ;; @0034                               v16 = uextend.i64 v5
 ;; @0034                               v17 = iconst.i64 8
 ;; @0034                               v18 = uadd_overflow_trap v16, v17, user1  ; v17 = 8
 ;; @0034                               v20 = uadd_overflow_trap v18, v17, user1  ; v17 = 8
-;; @0034                               v15 = load.i64 notrap aligned readonly v0+48
 ;; @0034                               v21 = icmp ule v20, v15
 ;; @0034                               trapz v21, user1
 ;; @0034                               v13 = load.i64 notrap aligned readonly v0+40
@@ -90,12 +90,12 @@
 ;; @003b                               brif v6, block3, block2
 ;;
 ;;                                 block2:
+;; @003b                               v10 = load.i64 notrap aligned v0+48
 ;; @003b                               v11 = uextend.i64 v2
 ;; @003b                               v37 = iconst.i64 8
 ;; @003b                               v13 = uadd_overflow_trap v11, v37, user1  ; v37 = 8
 ;; @003b                               v15 = uadd_overflow_trap v13, v37, user1  ; v37 = 8
-;; @003b                               v35 = load.i64 notrap aligned readonly v0+48
-;; @003b                               v16 = icmp ule v15, v35
+;; @003b                               v16 = icmp ule v15, v10
 ;; @003b                               trapz v16, user1
 ;; @003b                               v33 = load.i64 notrap aligned readonly v0+40
 ;; @003b                               v17 = iadd v33, v13
@@ -113,15 +113,15 @@
 ;; @003b                               brif v66, block7, block4
 ;;
 ;;                                 block4:
+;; @003b                               v35 = load.i64 notrap aligned v0+48
 ;; @003b                               v36 = uextend.i64 v5
 ;;                                     v67 = iconst.i64 8
 ;; @003b                               v38 = uadd_overflow_trap v36, v67, user1  ; v67 = 8
 ;; @003b                               v40 = uadd_overflow_trap v38, v67, user1  ; v67 = 8
-;;                                     v68 = load.i64 notrap aligned readonly v0+48
-;; @003b                               v41 = icmp ule v40, v68
+;; @003b                               v41 = icmp ule v40, v35
 ;; @003b                               trapz v41, user1
-;;                                     v69 = load.i64 notrap aligned readonly v0+40
-;; @003b                               v42 = iadd v69, v38
+;;                                     v68 = load.i64 notrap aligned readonly v0+40
+;; @003b                               v42 = iadd v68, v38
 ;; @003b                               v43 = load.i64 notrap aligned v42
 ;;                                     v62 = iconst.i64 -1
 ;; @003b                               v44 = iadd v43, v62  ; v62 = -1
@@ -134,8 +134,11 @@
 ;; @003b                               jump block7
 ;;
 ;;                                 block6:
-;;                                     v70 = iadd.i64 v43, v62  ; v62 = -1
-;; @003b                               store notrap aligned v70, v42
+;; @003b                               v50 = load.i64 notrap aligned v0+48
+;; @003b                               v56 = icmp.i64 ule v40, v50
+;; @003b                               trapz v56, user1
+;;                                     v69 = iadd.i64 v43, v62  ; v62 = -1
+;; @003b                               store notrap aligned v69, v42
 ;; @003b                               jump block7
 ;;
 ;;                                 block7:
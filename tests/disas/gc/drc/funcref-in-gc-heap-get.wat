@@ -15,17 +15,17 @@
 ;;     gv2 = load.i64 notrap aligned gv1+16
 ;;     gv3 = vmctx
 ;;     sig0 = (i64 vmctx, i32, i32) -> i64 tail
-;;     fn0 = colocated u1:29 sig0
+;;     fn0 = colocated u1:30 sig0
 ;;     stack_limit = gv2
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @0020                               trapz v2, user16
+;; @0020                               v8 = load.i64 notrap aligned v0+48
 ;; @0020                               v9 = uextend.i64 v2
 ;; @0020                               v10 = iconst.i64 16
 ;; @0020                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 16
 ;;                                     v20 = iconst.i64 24
 ;; @0020                               v13 = uadd_overflow_trap v9, v20, user1  ; v20 = 24
-;; @0020                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0020                               v14 = icmp ule v13, v8
 ;; @0020                               trapz v14, user1
 ;; @0020                               v6 = load.i64 notrap aligned readonly v0+40
@@ -14,7 +14,7 @@
 ;;     gv2 = load.i64 notrap aligned gv1+16
 ;;     gv3 = vmctx
 ;;     sig0 = (i64 vmctx, i32, i32) -> i32 tail
-;;     fn0 = colocated u1:35 sig0
+;;     fn0 = colocated u1:36 sig0
 ;;     stack_limit = gv2
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
@@ -33,12 +33,12 @@
 ;; @001d                               brif v48, block4(v50), block3
 ;;
 ;;                                 block3:
+;; @001d                               v20 = load.i64 notrap aligned v0+48
 ;; @001d                               v21 = uextend.i64 v2
 ;; @001d                               v22 = iconst.i64 4
 ;; @001d                               v23 = uadd_overflow_trap v21, v22, user1  ; v22 = 4
 ;; @001d                               v24 = iconst.i64 8
 ;; @001d                               v25 = uadd_overflow_trap v23, v24, user1  ; v24 = 8
-;; @001d                               v20 = load.i64 notrap aligned readonly v0+48
 ;; @001d                               v26 = icmp ule v25, v20
 ;; @001d                               trapz v26, user1
 ;; @001d                               v18 = load.i64 notrap aligned readonly v0+40
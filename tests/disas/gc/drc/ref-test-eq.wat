@@ -28,12 +28,12 @@
 ;; @001b                               brif v43, block4(v43), block3
 ;;
 ;;                                 block3:
+;; @001b                               v15 = load.i64 notrap aligned v0+48
 ;; @001b                               v16 = uextend.i64 v2
 ;; @001b                               v17 = iconst.i64 0
 ;; @001b                               v18 = uadd_overflow_trap v16, v17, user1  ; v17 = 0
 ;;                                     v41 = iconst.i64 8
 ;; @001b                               v20 = uadd_overflow_trap v16, v41, user1  ; v41 = 8
-;; @001b                               v15 = load.i64 notrap aligned readonly v0+48
 ;; @001b                               v21 = icmp ule v20, v15
 ;; @001b                               trapz v21, user1
 ;; @001b                               v13 = load.i64 notrap aligned readonly v0+40
@@ -32,12 +32,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @0033                               trapz v2, user16
+;; @0033                               v8 = load.i64 notrap aligned v0+48
 ;; @0033                               v9 = uextend.i64 v2
 ;; @0033                               v10 = iconst.i64 16
 ;; @0033                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 16
 ;;                                     v17 = iconst.i64 32
 ;; @0033                               v13 = uadd_overflow_trap v9, v17, user1  ; v17 = 32
-;; @0033                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0033                               v14 = icmp ule v13, v8
 ;; @0033                               trapz v14, user1
 ;; @0033                               v6 = load.i64 notrap aligned readonly v0+40
@@ -58,12 +58,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @003c                               trapz v2, user16
+;; @003c                               v8 = load.i64 notrap aligned v0+48
 ;; @003c                               v9 = uextend.i64 v2
 ;; @003c                               v10 = iconst.i64 20
 ;; @003c                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 20
 ;;                                     v18 = iconst.i64 32
 ;; @003c                               v13 = uadd_overflow_trap v9, v18, user1  ; v18 = 32
-;; @003c                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @003c                               v14 = icmp ule v13, v8
 ;; @003c                               trapz v14, user1
 ;; @003c                               v6 = load.i64 notrap aligned readonly v0+40
@@ -85,12 +85,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @0045                               trapz v2, user16
+;; @0045                               v8 = load.i64 notrap aligned v0+48
 ;; @0045                               v9 = uextend.i64 v2
 ;; @0045                               v10 = iconst.i64 20
 ;; @0045                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 20
 ;;                                     v18 = iconst.i64 32
 ;; @0045                               v13 = uadd_overflow_trap v9, v18, user1  ; v18 = 32
-;; @0045                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0045                               v14 = icmp ule v13, v8
 ;; @0045                               trapz v14, user1
 ;; @0045                               v6 = load.i64 notrap aligned readonly v0+40
@@ -115,12 +115,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @004e                               trapz v2, user16
+;; @004e                               v8 = load.i64 notrap aligned v0+48
 ;; @004e                               v9 = uextend.i64 v2
 ;; @004e                               v10 = iconst.i64 24
 ;; @004e                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 24
 ;;                                     v68 = iconst.i64 32
 ;; @004e                               v13 = uadd_overflow_trap v9, v68, user1  ; v68 = 32
-;; @004e                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @004e                               v14 = icmp ule v13, v8
 ;; @004e                               trapz v14, user1
 ;; @004e                               v6 = load.i64 notrap aligned readonly v0+40
@@ -142,11 +142,12 @@
 ;; @004e                               brif v24, block3, block4
 ;;
 ;;                                 block4:
+;; @004e                               v28 = load.i64 notrap aligned v0+48
 ;; @004e                               v29 = uextend.i64 v16
 ;; @004e                               v30 = iconst.i64 8
 ;; @004e                               v31 = uadd_overflow_trap v29, v30, user1  ; v30 = 8
 ;; @004e                               v33 = uadd_overflow_trap v31, v30, user1  ; v30 = 8
-;; @004e                               v34 = icmp ule v33, v8
+;; @004e                               v34 = icmp ule v33, v28
 ;; @004e                               trapz v34, user1
 ;; @004e                               v35 = iadd.i64 v6, v31
 ;; @004e                               v36 = load.i64 notrap aligned v35
@@ -41,11 +41,11 @@
 ;; @0021                               brif v58, block3, block2  ; v58 = 1
 ;;
 ;;                                 block2:
+;; @0021                               v26 = load.i64 notrap aligned v0+48
 ;;                                     v65 = iconst.i64 0
 ;; @0021                               v28 = iconst.i64 8
 ;; @0021                               v29 = uadd_overflow_trap v65, v28, user1  ; v65 = 0, v28 = 8
 ;; @0021                               v31 = uadd_overflow_trap v29, v28, user1  ; v28 = 8
-;; @0021                               v26 = load.i64 notrap aligned readonly v0+48
 ;; @0021                               v32 = icmp ule v31, v26
 ;; @0021                               trapz v32, user1
 ;; @0021                               v33 = iadd.i64 v14, v29
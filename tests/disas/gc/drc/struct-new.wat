@@ -46,11 +46,11 @@
 ;; @002a                               brif v22, block3, block2
 ;;
 ;;                                 block2:
+;; @002a                               v26 = load.i64 notrap aligned v0+48
 ;; @002a                               v27 = uextend.i64 v50
 ;; @002a                               v28 = iconst.i64 8
 ;; @002a                               v29 = uadd_overflow_trap v27, v28, user1  ; v28 = 8
 ;; @002a                               v31 = uadd_overflow_trap v29, v28, user1  ; v28 = 8
-;; @002a                               v26 = load.i64 notrap aligned readonly v0+48
 ;; @002a                               v32 = icmp ule v31, v26
 ;; @002a                               trapz v32, user1
 ;; @002a                               v33 = iadd.i64 v14, v29
@@ -28,12 +28,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: f32):
 ;; @0034                               trapz v2, user16
+;; @0034                               v8 = load.i64 notrap aligned v0+48
 ;; @0034                               v9 = uextend.i64 v2
 ;; @0034                               v10 = iconst.i64 16
 ;; @0034                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 16
 ;;                                     v16 = iconst.i64 32
 ;; @0034                               v13 = uadd_overflow_trap v9, v16, user1  ; v16 = 32
-;; @0034                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0034                               v14 = icmp ule v13, v8
 ;; @0034                               trapz v14, user1
 ;; @0034                               v6 = load.i64 notrap aligned readonly v0+40
@@ -54,12 +54,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32):
 ;; @003f                               trapz v2, user16
+;; @003f                               v8 = load.i64 notrap aligned v0+48
 ;; @003f                               v9 = uextend.i64 v2
 ;; @003f                               v10 = iconst.i64 20
 ;; @003f                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 20
 ;;                                     v16 = iconst.i64 32
 ;; @003f                               v13 = uadd_overflow_trap v9, v16, user1  ; v16 = 32
-;; @003f                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @003f                               v14 = icmp ule v13, v8
 ;; @003f                               trapz v14, user1
 ;; @003f                               v6 = load.i64 notrap aligned readonly v0+40
@@ -82,12 +82,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32):
 ;; @004a                               trapz v2, user16
+;; @004a                               v8 = load.i64 notrap aligned v0+48
 ;; @004a                               v9 = uextend.i64 v2
 ;; @004a                               v10 = iconst.i64 24
 ;; @004a                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 24
 ;;                                     v78 = iconst.i64 32
 ;; @004a                               v13 = uadd_overflow_trap v9, v78, user1  ; v78 = 32
-;; @004a                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @004a                               v14 = icmp ule v13, v8
 ;; @004a                               trapz v14, user1
 ;; @004a                               v6 = load.i64 notrap aligned readonly v0+40
@@ -100,11 +100,12 @@
 ;; @004a                               brif v19, block3, block2
 ;;
 ;;                                 block2:
+;; @004a                               v23 = load.i64 notrap aligned v0+48
 ;; @004a                               v24 = uextend.i64 v3
 ;; @004a                               v52 = iconst.i64 8
 ;; @004a                               v26 = uadd_overflow_trap v24, v52, user1  ; v52 = 8
 ;; @004a                               v28 = uadd_overflow_trap v26, v52, user1  ; v52 = 8
-;; @004a                               v29 = icmp ule v28, v8
+;; @004a                               v29 = icmp ule v28, v23
 ;; @004a                               trapz v29, user1
 ;; @004a                               v30 = iadd.i64 v6, v26
 ;; @004a                               v31 = load.i64 notrap aligned v30
@@ -122,11 +123,12 @@
 ;; @004a                               brif v82, block7, block4
 ;;
 ;;                                 block4:
+;; @004a                               v50 = load.i64 notrap aligned v0+48
 ;; @004a                               v51 = uextend.i64 v16
 ;;                                     v83 = iconst.i64 8
 ;; @004a                               v53 = uadd_overflow_trap v51, v83, user1  ; v83 = 8
 ;; @004a                               v55 = uadd_overflow_trap v53, v83, user1  ; v83 = 8
-;; @004a                               v56 = icmp ule v55, v8
+;; @004a                               v56 = icmp ule v55, v50
 ;; @004a                               trapz v56, user1
 ;; @004a                               v57 = iadd.i64 v6, v53
 ;; @004a                               v58 = load.i64 notrap aligned v57
@@ -141,6 +143,9 @@
 ;; @004a                               jump block7
 ;;
 ;;                                 block6:
+;; @004a                               v65 = load.i64 notrap aligned v0+48
+;; @004a                               v71 = icmp.i64 ule v55, v65
+;; @004a                               trapz v71, user1
 ;;                                     v84 = iadd.i64 v58, v76  ; v76 = -1
 ;; @004a                               store notrap aligned v84, v57
 ;; @004a                               jump block7
@@ -14,54 +14,64 @@
 ;;     gv1 = load.i64 notrap aligned readonly gv0+8
 ;;     gv2 = load.i64 notrap aligned gv1+16
 ;;     gv3 = vmctx
+;;     sig0 = (i64 vmctx, i64) -> i8 tail
+;;     fn0 = colocated u1:28 sig0
 ;;     stack_limit = gv2
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i64, v3: i64, v4: i64):
-;;                                     v58 = iconst.i64 0
-;; @0025                               trapnz v58, user18  ; v58 = 0
+;;                                     v61 = iconst.i64 0
+;; @0025                               trapnz v61, user18  ; v61 = 0
 ;; @0025                               v6 = iconst.i32 16
-;;                                     v59 = iconst.i32 24
-;; @0025                               v12 = uadd_overflow_trap v6, v59, user18  ; v6 = 16, v59 = 24
+;;                                     v62 = iconst.i32 24
+;; @0025                               v12 = uadd_overflow_trap v6, v62, user18  ; v6 = 16, v62 = 24
 ;; @0025                               v14 = iconst.i32 -134217728
 ;; @0025                               v15 = band v12, v14  ; v14 = -134217728
 ;; @0025                               trapnz v15, user18
 ;; @0025                               v17 = load.i64 notrap aligned readonly v0+56
 ;; @0025                               v18 = load.i32 notrap aligned v17
-;;                                     v60 = iconst.i32 7
-;; @0025                               v21 = uadd_overflow_trap v18, v60, user18  ; v60 = 7
-;;                                     v67 = iconst.i32 -8
-;; @0025                               v23 = band v21, v67  ; v67 = -8
+;;                                     v63 = iconst.i32 7
+;; @0025                               v21 = uadd_overflow_trap v18, v63, user18  ; v63 = 7
+;;                                     v70 = iconst.i32 -8
+;; @0025                               v23 = band v21, v70  ; v70 = -8
 ;; @0025                               v24 = uadd_overflow_trap v23, v12, user18
+;; @0025                               v29 = load.i64 notrap aligned v0+48
 ;; @0025                               v25 = uextend.i64 v24
-;; @0025                               v29 = load.i64 notrap aligned readonly v0+48
 ;; @0025                               v30 = icmp ule v25, v29
-;; @0025                               trapz v30, user18
-;; @0025                               v33 = iconst.i32 -1476395008
-;;                                     v68 = bor v12, v33  ; v33 = -1476395008
+;; @0025                               brif v30, block3, block2
+;;
+;;                                 block2 cold:
+;; @0025                               v31 = isub.i64 v25, v29
+;; @0025                               v33 = call fn0(v0, v31)
+;; @0025                               jump block3
+;;
+;;                                 block3:
+;; @0025                               v36 = iconst.i32 -1476395008
+;;                                     v71 = bor.i32 v12, v36  ; v36 = -1476395008
 ;; @0025                               v27 = load.i64 notrap aligned readonly v0+40
-;; @0025                               v31 = uextend.i64 v23
-;; @0025                               v32 = iadd v27, v31
-;; @0025                               store notrap aligned v68, v32
-;; @0025                               v36 = load.i64 notrap aligned readonly v0+64
-;; @0025                               v37 = load.i32 notrap aligned readonly v36
-;; @0025                               store notrap aligned v37, v32+4
-;; @0025                               store notrap aligned v24, v17
+;;                                     v103 = band.i32 v21, v70  ; v70 = -8
+;;                                     v104 = uextend.i64 v103
+;; @0025                               v35 = iadd v27, v104
+;; @0025                               store notrap aligned v71, v35
+;; @0025                               v39 = load.i64 notrap aligned readonly v0+64
+;; @0025                               v40 = load.i32 notrap aligned readonly v39
+;; @0025                               store notrap aligned v40, v35+4
+;; @0025                               store.i32 notrap aligned v24, v17
 ;; @0025                               v7 = iconst.i32 3
-;;                                     v46 = iconst.i64 8
-;; @0025                               v38 = iadd v32, v46  ; v46 = 8
-;; @0025                               store notrap aligned v7, v38  ; v7 = 3
-;;                                     v71 = iconst.i64 16
-;;                                     v77 = iadd v32, v71  ; v71 = 16
-;; @0025                               store notrap aligned little v2, v77
-;;                                     v50 = iconst.i64 24
-;;                                     v84 = iadd v32, v50  ; v50 = 24
-;; @0025                               store notrap aligned little v3, v84
-;;                                     v47 = iconst.i64 32
-;;                                     v91 = iadd v32, v47  ; v47 = 32
-;; @0025                               store notrap aligned little v4, v91
+;;                                     v49 = iconst.i64 8
+;; @0025                               v41 = iadd v35, v49  ; v49 = 8
+;; @0025                               store notrap aligned v7, v41  ; v7 = 3
+;;                                     v74 = iconst.i64 16
+;;                                     v80 = iadd v35, v74  ; v74 = 16
+;; @0025                               store.i64 notrap aligned little v2, v80
+;;                                     v53 = iconst.i64 24
+;;                                     v87 = iadd v35, v53  ; v53 = 24
+;; @0025                               store.i64 notrap aligned little v3, v87
+;;                                     v50 = iconst.i64 32
+;;                                     v94 = iadd v35, v50  ; v50 = 32
+;; @0025                               store.i64 notrap aligned little v4, v94
 ;; @0029                               jump block1
 ;;
 ;;                                 block1:
-;;                                     v100 = band.i32 v21, v67  ; v67 = -8
-;; @0029                               return v100
+;;                                     v105 = band.i32 v21, v70  ; v70 = -8
+;; @0029                               return v105
 ;; }
@@ -14,66 +14,76 @@
 ;;     gv1 = load.i64 notrap aligned readonly gv0+8
 ;;     gv2 = load.i64 notrap aligned gv1+16
 ;;     gv3 = vmctx
+;;     sig0 = (i64 vmctx, i64) -> i8 tail
+;;     fn0 = colocated u1:28 sig0
 ;;     stack_limit = gv2
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i64, v3: i32):
 ;; @0022                               v6 = uextend.i64 v3
-;;                                     v50 = iconst.i64 3
-;;                                     v51 = ishl v6, v50  ; v50 = 3
-;;                                     v48 = iconst.i64 32
-;; @0022                               v8 = ushr v51, v48  ; v48 = 32
+;;                                     v53 = iconst.i64 3
+;;                                     v54 = ishl v6, v53  ; v53 = 3
+;;                                     v51 = iconst.i64 32
+;; @0022                               v8 = ushr v54, v51  ; v51 = 32
 ;; @0022                               trapnz v8, user18
 ;; @0022                               v5 = iconst.i32 16
-;;                                     v57 = iconst.i32 3
-;;                                     v58 = ishl v3, v57  ; v57 = 3
-;; @0022                               v10 = uadd_overflow_trap v5, v58, user18  ; v5 = 16
+;;                                     v60 = iconst.i32 3
+;;                                     v61 = ishl v3, v60  ; v60 = 3
+;; @0022                               v10 = uadd_overflow_trap v5, v61, user18  ; v5 = 16
 ;; @0022                               v12 = iconst.i32 -134217728
 ;; @0022                               v13 = band v10, v12  ; v12 = -134217728
 ;; @0022                               trapnz v13, user18
 ;; @0022                               v15 = load.i64 notrap aligned readonly v0+56
 ;; @0022                               v16 = load.i32 notrap aligned v15
-;;                                     v61 = iconst.i32 7
-;; @0022                               v19 = uadd_overflow_trap v16, v61, user18  ; v61 = 7
-;;                                     v68 = iconst.i32 -8
-;; @0022                               v21 = band v19, v68  ; v68 = -8
+;;                                     v64 = iconst.i32 7
+;; @0022                               v19 = uadd_overflow_trap v16, v64, user18  ; v64 = 7
+;;                                     v71 = iconst.i32 -8
+;; @0022                               v21 = band v19, v71  ; v71 = -8
 ;; @0022                               v22 = uadd_overflow_trap v21, v10, user18
+;; @0022                               v27 = load.i64 notrap aligned v0+48
 ;; @0022                               v23 = uextend.i64 v22
-;; @0022                               v27 = load.i64 notrap aligned readonly v0+48
 ;; @0022                               v28 = icmp ule v23, v27
-;; @0022                               trapz v28, user18
-;; @0022                               v31 = iconst.i32 -1476395008
-;;                                     v69 = bor v10, v31  ; v31 = -1476395008
-;; @0022                               v25 = load.i64 notrap aligned readonly v0+40
-;; @0022                               v29 = uextend.i64 v21
-;; @0022                               v30 = iadd v25, v29
-;; @0022                               store notrap aligned v69, v30
-;; @0022                               v34 = load.i64 notrap aligned readonly v0+64
-;; @0022                               v35 = load.i32 notrap aligned readonly v34
-;; @0022                               store notrap aligned v35, v30+4
-;; @0022                               store notrap aligned v22, v15
-;;                                     v47 = iconst.i64 8
-;; @0022                               v36 = iadd v30, v47  ; v47 = 8
-;; @0022                               store notrap aligned v3, v36
-;;                                     v72 = iconst.i64 16
-;;                                     v78 = iadd v30, v72  ; v72 = 16
-;; @0022                               v42 = uextend.i64 v10
-;; @0022                               v43 = iadd v30, v42
-;; @0022                               jump block2(v78)
+;; @0022                               brif v28, block3, block2
 ;;
-;;                                 block2(v44: i64):
-;; @0022                               v45 = icmp eq v44, v43
-;; @0022                               brif v45, block4, block3
+;;                                 block2 cold:
+;; @0022                               v29 = isub.i64 v23, v27
+;; @0022                               v31 = call fn0(v0, v29)
+;; @0022                               jump block3
 ;;
 ;;                                 block3:
-;; @0022                               store.i64 notrap aligned little v2, v44
-;;                                     v90 = iconst.i64 8
-;;                                     v91 = iadd.i64 v44, v90  ; v90 = 8
-;; @0022                               jump block2(v91)
+;; @0022                               v34 = iconst.i32 -1476395008
+;;                                     v72 = bor.i32 v10, v34  ; v34 = -1476395008
+;; @0022                               v25 = load.i64 notrap aligned readonly v0+40
+;;                                     v93 = band.i32 v19, v71  ; v71 = -8
+;;                                     v94 = uextend.i64 v93
+;; @0022                               v33 = iadd v25, v94
+;; @0022                               store notrap aligned v72, v33
+;; @0022                               v37 = load.i64 notrap aligned readonly v0+64
+;; @0022                               v38 = load.i32 notrap aligned readonly v37
+;; @0022                               store notrap aligned v38, v33+4
+;; @0022                               store.i32 notrap aligned v22, v15
+;;                                     v50 = iconst.i64 8
+;; @0022                               v39 = iadd v33, v50  ; v50 = 8
+;; @0022                               store.i32 notrap aligned v3, v39
+;;                                     v75 = iconst.i64 16
+;;                                     v81 = iadd v33, v75  ; v75 = 16
+;; @0022                               v45 = uextend.i64 v10
+;; @0022                               v46 = iadd v33, v45
+;; @0022                               jump block4(v81)
+;;
+;;                                 block4(v47: i64):
+;; @0022                               v48 = icmp eq v47, v46
+;; @0022                               brif v48, block6, block5
+;;
+;;                                 block5:
+;; @0022                               store.i64 notrap aligned little v2, v47
+;;                                     v95 = iconst.i64 8
+;;                                     v96 = iadd.i64 v47, v95  ; v95 = 8
+;; @0022                               jump block4(v96)
 ;;
-;;                                 block4:
+;;                                 block6:
 ;; @0025                               jump block1
 ;;
 ;;                                 block1:
-;;                                     v92 = band.i32 v19, v68  ; v68 = -8
-;; @0025                               return v92
+;;                                     v97 = band.i32 v19, v71  ; v71 = -8
+;; @0025                               return v97
 ;; }
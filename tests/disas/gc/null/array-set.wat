@@ -18,12 +18,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32, v4: i64):
 ;; @0024                               trapz v2, user16
+;; @0024                               v8 = load.i64 notrap aligned v0+48
 ;; @0024                               v9 = uextend.i64 v2
 ;; @0024                               v10 = iconst.i64 8
 ;; @0024                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 8
 ;; @0024                               v12 = iconst.i64 4
 ;; @0024                               v13 = uadd_overflow_trap v11, v12, user1  ; v12 = 4
-;; @0024                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0024                               v14 = icmp ule v13, v8
 ;; @0024                               trapz v14, user1
 ;; @0024                               v6 = load.i64 notrap aligned readonly v0+40
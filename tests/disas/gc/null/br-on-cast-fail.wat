@@ -25,7 +25,7 @@
 ;;     sig0 = (i64 vmctx, i32, i32) -> i32 tail
 ;;     sig1 = (i64 vmctx, i64) tail
 ;;     sig2 = (i64 vmctx, i64) tail
-;;     fn0 = colocated u1:35 sig0
+;;     fn0 = colocated u1:36 sig0
 ;;     fn1 = u0:0 sig1
 ;;     fn2 = u0:1 sig2
 ;;     stack_limit = gv2
@@ -48,12 +48,12 @@
 ;; @002e                               brif v63, block5(v65), block4
 ;;
 ;;                                 block4:
+;; @002e                               v20 = load.i64 notrap aligned v0+48
 ;; @002e                               v21 = uextend.i64 v2
 ;; @002e                               v22 = iconst.i64 4
 ;; @002e                               v23 = uadd_overflow_trap v21, v22, user1  ; v22 = 4
 ;; @002e                               v24 = iconst.i64 8
 ;; @002e                               v25 = uadd_overflow_trap v23, v24, user1  ; v24 = 8
-;; @002e                               v20 = load.i64 notrap aligned readonly v0+48
 ;; @002e                               v26 = icmp ule v25, v20
 ;; @002e                               trapz v26, user1
 ;; @002e                               v18 = load.i64 notrap aligned readonly v0+40
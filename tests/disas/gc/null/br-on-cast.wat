@@ -25,7 +25,7 @@
 ;;     sig0 = (i64 vmctx, i32, i32) -> i32 tail
 ;;     sig1 = (i64 vmctx, i64) tail
 ;;     sig2 = (i64 vmctx, i64) tail
-;;     fn0 = colocated u1:35 sig0
+;;     fn0 = colocated u1:36 sig0
 ;;     fn1 = u0:0 sig1
 ;;     fn2 = u0:1 sig2
 ;;     stack_limit = gv2
@@ -48,12 +48,12 @@
 ;; @002f                               brif v63, block5(v65), block4
 ;;
 ;;                                 block4:
+;; @002f                               v20 = load.i64 notrap aligned v0+48
 ;; @002f                               v21 = uextend.i64 v2
 ;; @002f                               v22 = iconst.i64 4
 ;; @002f                               v23 = uadd_overflow_trap v21, v22, user1  ; v22 = 4
 ;; @002f                               v24 = iconst.i64 8
 ;; @002f                               v25 = uadd_overflow_trap v23, v24, user1  ; v24 = 8
-;; @002f                               v20 = load.i64 notrap aligned readonly v0+48
 ;; @002f                               v26 = icmp ule v25, v20
 ;; @002f                               trapz v26, user1
 ;; @002f                               v18 = load.i64 notrap aligned readonly v0+40
@@ -19,12 +19,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32, v4: i32):
 ;; @0024                               trapz v2, user16
+;; @0024                               v10 = load.i64 notrap aligned v0+48
 ;; @0024                               v11 = uextend.i64 v2
 ;; @0024                               v12 = iconst.i64 8
 ;; @0024                               v13 = uadd_overflow_trap v11, v12, user1  ; v12 = 8
 ;; @0024                               v14 = iconst.i64 4
 ;; @0024                               v15 = uadd_overflow_trap v13, v14, user1  ; v14 = 4
-;; @0024                               v10 = load.i64 notrap aligned readonly v0+48
 ;; @0024                               v16 = icmp ule v15, v10
 ;; @0024                               trapz v16, user1
 ;; @0024                               v8 = load.i64 notrap aligned readonly v0+40
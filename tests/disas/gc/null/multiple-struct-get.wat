@@ -20,12 +20,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @0023                               trapz v2, user16
+;; @0023                               v9 = load.i64 notrap aligned v0+48
 ;; @0023                               v10 = uextend.i64 v2
 ;; @0023                               v11 = iconst.i64 8
 ;; @0023                               v12 = uadd_overflow_trap v10, v11, user1  ; v11 = 8
 ;;                                     v32 = iconst.i64 16
 ;; @0023                               v14 = uadd_overflow_trap v10, v32, user1  ; v32 = 16
-;; @0023                               v9 = load.i64 notrap aligned readonly v0+48
 ;; @0023                               v15 = icmp ule v14, v9
 ;; @0023                               trapz v15, user1
 ;; @0023                               v7 = load.i64 notrap aligned readonly v0+40
@@ -15,7 +15,7 @@
 ;;     gv2 = load.i64 notrap aligned gv1+16
 ;;     gv3 = vmctx
 ;;     sig0 = (i64 vmctx, i32, i32) -> i32 tail
-;;     fn0 = colocated u1:35 sig0
+;;     fn0 = colocated u1:36 sig0
 ;;     stack_limit = gv2
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
@@ -36,12 +36,12 @@
 ;; @001e                               brif v57, block4(v59), block3
 ;;
 ;;                                 block3:
+;; @001e                               v20 = load.i64 notrap aligned v0+48
 ;; @001e                               v21 = uextend.i64 v2
 ;; @001e                               v22 = iconst.i64 4
 ;; @001e                               v23 = uadd_overflow_trap v21, v22, user1  ; v22 = 4
 ;; @001e                               v24 = iconst.i64 8
 ;; @001e                               v25 = uadd_overflow_trap v23, v24, user1  ; v24 = 8
-;; @001e                               v20 = load.i64 notrap aligned readonly v0+48
 ;; @001e                               v26 = icmp ule v25, v20
 ;; @001e                               trapz v26, user1
 ;; @001e                               v18 = load.i64 notrap aligned readonly v0+40
@@ -30,12 +30,12 @@
 ;; @001b                               brif v46, block4(v48), block3
 ;;
 ;;                                 block3:
+;; @001b                               v18 = load.i64 notrap aligned v0+48
 ;; @001b                               v19 = uextend.i64 v2
 ;; @001b                               v20 = iconst.i64 0
 ;; @001b                               v21 = uadd_overflow_trap v19, v20, user1  ; v20 = 0
 ;;                                     v44 = iconst.i64 8
 ;; @001b                               v23 = uadd_overflow_trap v19, v44, user1  ; v44 = 8
-;; @001b                               v18 = load.i64 notrap aligned readonly v0+48
 ;; @001b                               v24 = icmp ule v23, v18
 ;; @001b                               trapz v24, user1
 ;; @001b                               v16 = load.i64 notrap aligned readonly v0+40
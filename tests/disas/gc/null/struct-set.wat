@@ -28,12 +28,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: f32):
 ;; @0034                               trapz v2, user16
+;; @0034                               v8 = load.i64 notrap aligned v0+48
 ;; @0034                               v9 = uextend.i64 v2
 ;; @0034                               v10 = iconst.i64 8
 ;; @0034                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 8
 ;;                                     v16 = iconst.i64 24
 ;; @0034                               v13 = uadd_overflow_trap v9, v16, user1  ; v16 = 24
-;; @0034                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0034                               v14 = icmp ule v13, v8
 ;; @0034                               trapz v14, user1
 ;; @0034                               v6 = load.i64 notrap aligned readonly v0+40
@@ -54,12 +54,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32):
 ;; @003f                               trapz v2, user16
+;; @003f                               v8 = load.i64 notrap aligned v0+48
 ;; @003f                               v9 = uextend.i64 v2
 ;; @003f                               v10 = iconst.i64 12
 ;; @003f                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 12
 ;;                                     v16 = iconst.i64 24
 ;; @003f                               v13 = uadd_overflow_trap v9, v16, user1  ; v16 = 24
-;; @003f                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @003f                               v14 = icmp ule v13, v8
 ;; @003f                               trapz v14, user1
 ;; @003f                               v6 = load.i64 notrap aligned readonly v0+40
@@ -80,12 +80,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32):
 ;; @004a                               trapz v2, user16
+;; @004a                               v8 = load.i64 notrap aligned v0+48
 ;; @004a                               v9 = uextend.i64 v2
 ;; @004a                               v10 = iconst.i64 16
 ;; @004a                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 16
 ;;                                     v16 = iconst.i64 24
 ;; @004a                               v13 = uadd_overflow_trap v9, v16, user1  ; v16 = 24
-;; @004a                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @004a                               v14 = icmp ule v13, v8
 ;; @004a                               trapz v14, user1
 ;; @004a                               v6 = load.i64 notrap aligned readonly v0+40
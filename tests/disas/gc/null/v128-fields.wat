@@ -20,12 +20,12 @@
 ;;
 ;;                                 block0(v0: i64, v1: i64, v2: i32):
 ;; @0022                               trapz v2, user16
+;; @0022                               v8 = load.i64 notrap aligned v0+48
 ;; @0022                               v9 = uextend.i64 v2
 ;; @0022                               v10 = iconst.i64 16
 ;; @0022                               v11 = uadd_overflow_trap v9, v10, user1  ; v10 = 16
 ;;                                     v31 = iconst.i64 48
 ;; @0022                               v13 = uadd_overflow_trap v9, v31, user1  ; v31 = 48
-;; @0022                               v8 = load.i64 notrap aligned readonly v0+48
 ;; @0022                               v14 = icmp ule v13, v8
 ;; @0022                               trapz v14, user1
 ;; @0022                               v6 = load.i64 notrap aligned readonly v0+40
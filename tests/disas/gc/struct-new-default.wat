@@ -43,11 +43,11 @@
 ;; @0023                               brif v61, block3, block2  ; v61 = 1
 ;;
 ;;                                 block2:
+;; @0023                               v27 = load.i64 notrap aligned v0+48
 ;;                                     v68 = iconst.i64 0
 ;; @0023                               v29 = iconst.i64 8
 ;; @0023                               v30 = uadd_overflow_trap v68, v29, user1  ; v68 = 0, v29 = 8
 ;; @0023                               v32 = uadd_overflow_trap v30, v29, user1  ; v29 = 8
-;; @0023                               v27 = load.i64 notrap aligned readonly v0+48
 ;; @0023                               v33 = icmp ule v32, v27
 ;; @0023                               trapz v33, user1
 ;; @0023                               v34 = iadd.i64 v15, v30
@@ -0,0 +1,83 @@
+;;! test = "optimize"
+;;! target = "x86_64"
+;;! flags = ["-Wmemory64"]
+
+;; Accesses to a 64-bit memory whose index is zero-extended from an `i32` can
+;; elide their bounds checks like accesses to a 32-bit memory, while accesses
+;; with an arbitrary 64-bit index cannot.
+
+(module
+  (memory i64 1)
+  (func $zero_extended (param i32) (result i32)
+    (i32.load (i64.extend_i32_u (local.get 0))))
+  (func $sign_extended (param i32) (result i32)
+    (i32.load (i64.extend_i32_s (local.get 0))))
+  (func $arbitrary (param i64) (result i32)
+    (i32.load (local.get 0)))
+)
+;; function u0:0(i64 vmctx, i64, i32) -> i32 tail {
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly gv0+8
+;;     gv2 = load.i64 notrap aligned gv1+16
+;;     gv3 = vmctx
+;;     gv4 = load.i64 notrap aligned gv3+88
+;;     gv5 = load.i64 notrap aligned checked gv3+80
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0028                               v5 = load.i64 notrap aligned checked v0+80
+;;                                     v4 = uextend.i64 v2
+;; @0028                               v6 = iadd v5, v4
+;; @0028                               v7 = load.i32 little heap v6
+;; @002b                               jump block1
+;;
+;;                                 block1:
+;; @002b                               return v7
+;; }
+;;
+;; function u0:1(i64 vmctx, i64, i32) -> i32 tail {
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly gv0+8
+;;     gv2 = load.i64 notrap aligned gv1+16
+;;     gv3 = vmctx
+;;     gv4 = load.i64 notrap aligned gv3+88
+;;     gv5 = load.i64 notrap aligned checked gv3+80
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0031                               v5 = load.i64 notrap aligned v0+88
+;; @0031                               v7 = load.i64 notrap aligned checked v0+80
+;;                                     v4 = sextend.i64 v2
+;; @0031                               v6 = icmp ugt v4, v5
+;; @0031                               v9 = iconst.i64 0
+;; @0031                               v8 = iadd v7, v4
+;; @0031                               v10 = select_spectre_guard v6, v9, v8  ; v9 = 0
+;; @0031                               v11 = load.i32 little heap v10
+;; @0034                               jump block1
+;;
+;;                                 block1:
+;; @0034                               return v11
+;; }
+;;
+;; function u0:2(i64 vmctx, i64, i64) -> i32 tail {
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly gv0+8
+;;     gv2 = load.i64 notrap aligned gv1+16
+;;     gv3 = vmctx
+;;     gv4 = load.i64 notrap aligned gv3+88
+;;     gv5 = load.i64 notrap aligned checked gv3+80
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i64):
+;; @0039                               v4 = load.i64 notrap aligned v0+88
+;; @0039                               v6 = load.i64 notrap aligned checked v0+80
+;; @0039                               v5 = icmp ugt v2, v4
+;; @0039                               v8 = iconst.i64 0
+;; @0039                               v7 = iadd v6, v2
+;; @0039                               v9 = select_spectre_guard v5, v8, v7  ; v8 = 0
+;; @0039                               v10 = load.i32 little heap v9
+;; @003c                               jump block1
+;;
+;;                                 block1:
+;; @003c                               return v10
+;; }
@@ -45,7 +45,7 @@
 ;;
 ;;                                 block4:
 ;; @008f                               v16 = load.i64 notrap aligned readonly v0+40
-;; @008f                               v18 = load.i64 notrap aligned readonly v0+48
+;; @008f                               v18 = load.i64 notrap aligned v0+48
 ;;                                     v98 = stack_addr.i64 ss0
 ;;                                     v92 = load.i32 notrap v98
 ;; @008f                               v19 = uextend.i64 v92
@@ -60,7 +60,7 @@
 ;;                                     v99 = iconst.i64 1
 ;; @008f                               v27 = iadd v26, v99  ; v99 = 1
 ;; @008f                               v29 = load.i64 notrap aligned readonly v0+40
-;; @008f                               v31 = load.i64 notrap aligned readonly v0+48
+;; @008f                               v31 = load.i64 notrap aligned v0+48
 ;;                                     v100 = stack_addr.i64 ss0
 ;;                                     v91 = load.i32 notrap v100
 ;; @008f                               v32 = uextend.i64 v91
@@ -107,7 +107,7 @@
 ;;
 ;;                                 block8:
 ;; @0091                               v52 = load.i64 notrap aligned readonly v0+40
-;; @0091                               v54 = load.i64 notrap aligned readonly v0+48
+;; @0091                               v54 = load.i64 notrap aligned v0+48
 ;;                                     v108 = stack_addr.i64 ss1
 ;;                                     v87 = load.i32 notrap v108
 ;; @0091                               v55 = uextend.i64 v87
@@ -122,7 +122,7 @@
 ;;                                     v109 = iconst.i64 1
 ;; @0091                               v63 = iadd v62, v109  ; v109 = 1
 ;; @0091                               v65 = load.i64 notrap aligned readonly v0+40
-;; @0091                               v67 = load.i64 notrap aligned readonly v0+48
+;; @0091                               v67 = load.i64 notrap aligned v0+48
 ;;                                     v110 = stack_addr.i64 ss1
 ;;                                     v86 = load.i32 notrap v110
 ;; @0091                               v68 = uextend.i64 v86
@@ -55,7 +55,7 @@
 ;;
 ;;                                 block4:
 ;; @0054                               v20 = load.i64 notrap aligned readonly v0+40
-;; @0054                               v22 = load.i64 notrap aligned readonly v0+48
+;; @0054                               v22 = load.i64 notrap aligned v0+48
 ;;                                     v57 = stack_addr.i64 ss0
 ;;                                     v50 = load.i32 notrap v57
 ;; @0054                               v23 = uextend.i64 v50
@@ -70,7 +70,7 @@
 ;;                                     v58 = iconst.i64 1
 ;; @0054                               v31 = iadd v30, v58  ; v58 = 1
 ;; @0054                               v33 = load.i64 notrap aligned readonly v0+40
-;; @0054                               v35 = load.i64 notrap aligned readonly v0+48
+;; @0054                               v35 = load.i64 notrap aligned v0+48
 ;;                                     v59 = stack_addr.i64 ss0
 ;;                                     v49 = load.i32 notrap v59
 ;; @0054                               v36 = uextend.i64 v49
@@ -144,7 +144,7 @@
 ;;
 ;;                                 block4:
 ;; @005b                               v20 = load.i64 notrap aligned readonly v0+40
-;; @005b                               v22 = load.i64 notrap aligned readonly v0+48
+;; @005b                               v22 = load.i64 notrap aligned v0+48
 ;;                                     v57 = stack_addr.i64 ss0
 ;;                                     v50 = load.i32 notrap v57
 ;; @005b                               v23 = uextend.i64 v50
@@ -159,7 +159,7 @@
 ;;                                     v58 = iconst.i64 1
 ;; @005b                               v31 = iadd v30, v58  ; v58 = 1
 ;; @005b                               v33 = load.i64 notrap aligned readonly v0+40
-;; @005b                               v35 = load.i64 notrap aligned readonly v0+48
+;; @005b                               v35 = load.i64 notrap aligned v0+48
 ;;                                     v59 = stack_addr.i64 ss0
 ;;                                     v49 = load.i32 notrap v59
 ;; @005b                               v36 = uextend.i64 v49
@@ -56,7 +56,7 @@
 ;;
 ;;                                 block4:
 ;; @0053                               v21 = load.i64 notrap aligned readonly v0+40
-;; @0053                               v23 = load.i64 notrap aligned readonly v0+48
+;; @0053                               v23 = load.i64 notrap aligned v0+48
 ;;                                     v59 = stack_addr.i64 ss0
 ;;                                     v51 = load.i32 notrap v59
 ;; @0053                               v24 = uextend.i64 v51
@@ -71,7 +71,7 @@
 ;;                                     v60 = iconst.i64 1
 ;; @0053                               v32 = iadd v31, v60  ; v60 = 1
 ;; @0053                               v34 = load.i64 notrap aligned readonly v0+40
-;; @0053                               v36 = load.i64 notrap aligned readonly v0+48
+;; @0053                               v36 = load.i64 notrap aligned v0+48
 ;;                                     v61 = stack_addr.i64 ss0
 ;;                                     v50 = load.i32 notrap v61
 ;; @0053                               v37 = uextend.i64 v50
@@ -147,7 +147,7 @@
 ;;
 ;;                                 block4:
 ;; @005a                               v21 = load.i64 notrap aligned readonly v0+40
-;; @005a                               v23 = load.i64 notrap aligned readonly v0+48
+;; @005a                               v23 = load.i64 notrap aligned v0+48
 ;;                                     v59 = stack_addr.i64 ss0
 ;;                                     v51 = load.i32 notrap v59
 ;; @005a                               v24 = uextend.i64 v51
@@ -162,7 +162,7 @@
 ;;                                     v60 = iconst.i64 1
 ;; @005a                               v32 = iadd v31, v60  ; v60 = 1
 ;; @005a                               v34 = load.i64 notrap aligned readonly v0+40
-;; @005a                               v36 = load.i64 notrap aligned readonly v0+48
+;; @005a                               v36 = load.i64 notrap aligned v0+48
 ;;                                     v61 = stack_addr.i64 ss0
 ;;                                     v50 = load.i32 notrap v61
 ;; @005a                               v37 = uextend.i64 v50
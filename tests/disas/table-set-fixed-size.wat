@@ -44,7 +44,7 @@
 ;;
 ;;                                 block2:
 ;; @0056                               v15 = load.i64 notrap aligned readonly v0+40
-;; @0056                               v17 = load.i64 notrap aligned readonly v0+48
+;; @0056                               v17 = load.i64 notrap aligned v0+48
 ;; @0056                               v18 = uextend.i64 v2
 ;; @0056                               v19 = iconst.i64 8
 ;; @0056                               v20 = uadd_overflow_trap v18, v19, user1  ; v19 = 8
@@ -57,7 +57,7 @@
 ;;                                     v68 = iconst.i64 1
 ;; @0056                               v26 = iadd v25, v68  ; v68 = 1
 ;; @0056                               v28 = load.i64 notrap aligned readonly v0+40
-;; @0056                               v30 = load.i64 notrap aligned readonly v0+48
+;; @0056                               v30 = load.i64 notrap aligned v0+48
 ;; @0056                               v31 = uextend.i64 v2
 ;; @0056                               v32 = iconst.i64 8
 ;; @0056                               v33 = uadd_overflow_trap v31, v32, user1  ; v32 = 8
@@ -77,7 +77,7 @@
 ;;
 ;;                                 block4:
 ;; @0056                               v40 = load.i64 notrap aligned readonly v0+40
-;; @0056                               v42 = load.i64 notrap aligned readonly v0+48
+;; @0056                               v42 = load.i64 notrap aligned v0+48
 ;; @0056                               v43 = uextend.i64 v12
 ;; @0056                               v44 = iconst.i64 8
 ;; @0056                               v45 = uadd_overflow_trap v43, v44, user1  ; v44 = 8
@@ -99,7 +99,7 @@
 ;;
 ;;                                 block6:
 ;; @0056                               v55 = load.i64 notrap aligned readonly v0+40
-;; @0056                               v57 = load.i64 notrap aligned readonly v0+48
+;; @0056                               v57 = load.i64 notrap aligned v0+48
 ;; @0056                               v58 = uextend.i64 v12
 ;; @0056                               v59 = iconst.i64 8
 ;; @0056                               v60 = uadd_overflow_trap v58, v59, user1  ; v59 = 8
@@ -145,7 +145,7 @@
 ;;
 ;;                                 block2:
 ;; @005f                               v15 = load.i64 notrap aligned readonly v0+40
-;; @005f                               v17 = load.i64 notrap aligned readonly v0+48
+;; @005f                               v17 = load.i64 notrap aligned v0+48
 ;; @005f                               v18 = uextend.i64 v3
 ;; @005f                               v19 = iconst.i64 8
 ;; @005f                               v20 = uadd_overflow_trap v18, v19, user1  ; v19 = 8
@@ -158,7 +158,7 @@
 ;;                                     v68 = iconst.i64 1
 ;; @005f                               v26 = iadd v25, v68  ; v68 = 1
 ;; @005f                               v28 = load.i64 notrap aligned readonly v0+40
-;; @005f                               v30 = load.i64 notrap aligned readonly v0+48
+;; @005f                               v30 = load.i64 notrap aligned v0+48
 ;; @005f                               v31 = uextend.i64 v3
 ;; @005f                               v32 = iconst.i64 8
 ;; @005f                               v33 = uadd_overflow_trap v31, v32, user1  ; v32 = 8
@@ -178,7 +178,7 @@
 ;;
 ;;                                 block4:
 ;; @005f                               v40 = load.i64 notrap aligned readonly v0+40
-;; @005f                               v42 = load.i64 notrap aligned readonly v0+48
+;; @005f                               v42 = load.i64 notrap aligned v0+48
 ;; @005f                               v43 = uextend.i64 v12
 ;; @005f                               v44 = iconst.i64 8
 ;; @005f                               v45 = uadd_overflow_trap v43, v44, user1  ; v44 = 8
@@ -200,7 +200,7 @@
 ;;
 ;;                                 block6:
 ;; @005f                               v55 = load.i64 notrap aligned readonly v0+40
-;; @005f                               v57 = load.i64 notrap aligned readonly v0+48
+;; @005f                               v57 = load.i64 notrap aligned v0+48
 ;; @005f                               v58 = uextend.i64 v12
 ;; @005f                               v59 = iconst.i64 8
 ;; @005f                               v60 = uadd_overflow_trap v58, v59, user1  ; v59 = 8
@@ -0,0 +1,33 @@
+;;! target = "riscv64"
+;;! test = "winch"
+(module
+  (func (export "as-br-if-cond")
+    (block (br_if 0 (br_if 0 (i32.const 1) (i32.const 1))))
+  )
+)
+;; wasm[0]::function[0]:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x10
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x10
+;;       sd      a0, 8(sp)
+;;       sd      a1, 0(sp)
+;;       addi    t2, zero, 1
+;;       sext.w  t3, t2
+;;       bnez    t3, 0xc
+;;       addi    t2, zero, 1
+;;       sext.w  t3, t2
+;;       addi    sp, sp, 0x10
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
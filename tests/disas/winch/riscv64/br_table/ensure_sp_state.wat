@@ -0,0 +1,50 @@
+;;! target = "riscv64"
+;;! test = "winch"
+
+(module
+  (func (export "") (result i32)
+    block (result i32)
+       i32.const 0
+    end
+    i32.const 0
+    i32.const 0
+    br_table 0
+  )
+)
+;; wasm[0]::function[0]:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x14
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x10
+;;       sd      a0, 8(sp)
+;;       sd      a1, 0(sp)
+;;       mv      a0, zero
+;;       addi    sp, sp, -4
+;;       sw      a0, 0(sp)
+;;       mv      t2, zero
+;;       mv      a0, zero
+;;       slli    t6, t2, 0x20
+;;       srli    t6, t6, 0x20
+;;       mv      a1, zero
+;;       bltu    t6, a1, 0xc
+;;       auipc   a1, 0
+;;       jalr    zero, a1, 0x18
+;;       auipc   t0, 0
+;;       slli    a1, t6, 3
+;;       add     t0, t0, a1
+;;       jalr    zero, t0, 0x10
+;;       addi    sp, sp, 4
+;;       addi    sp, sp, 0x10
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
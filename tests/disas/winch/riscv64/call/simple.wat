@@ -0,0 +1,81 @@
+;;! target = "riscv64"
+;;! test = "winch"
+
+(module
+  (func $main (result i32)
+    (local $var i32)
+    (call $product (i32.const 20) (i32.const 80))
+    (local.set $var (i32.const 2))
+    (local.get $var)
+    (i32.div_u))
+
+  (func $product (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.mul))
+)
+;; wasm[0]::function[0]::main:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x20
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x18
+;;       sd      a0, 0x10(sp)
+;;       sd      a1, 8(sp)
+;;       mv      t1, zero
+;;       sd      t1, 0(sp)
+;;       addi    sp, sp, -8
+;;       mv      a0, s1
+;;       mv      a1, s1
+;;       addi    a2, zero, 0x14
+;;       addi    a3, zero, 0x50
+;;       auipc   ra, 0
+;;       jalr    ra, ra, 0x3c
+;;       addi    sp, sp, 8
+;;       ld      s1, 0x10(sp)
+;;       addi    t2, zero, 2
+;;       sw      t2, 4(sp)
+;;       lw      t2, 4(sp)
+;;       bnez    t2, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       divuw   a0, a0, t2
+;;       addi    sp, sp, 0x18
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
+;;
+;; wasm[0]::function[1]::product:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x18
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x18
+;;       sd      a0, 0x10(sp)
+;;       sd      a1, 8(sp)
+;;       sw      a2, 4(sp)
+;;       sw      a3, 0(sp)
+;;       lw      t2, 0(sp)
+;;       lw      a0, 4(sp)
+;;       mulw    a0, a0, t2
+;;       addi    sp, sp, 0x18
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
@@ -0,0 +1,43 @@
+;;! target = "riscv64"
+;;! test = "winch"
+
+(module
+    (func (param f32) (param f32) (result f32)
+        (local.get 0)
+        (local.get 1)
+        (f32.min)
+    )
+)
+;; wasm[0]::function[0]:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x18
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x18
+;;       sd      a0, 0x10(sp)
+;;       sd      a1, 8(sp)
+;;       fsw     fa0, 4(sp)
+;;       fsw     fa1, 0(sp)
+;;       flw     ft0, 0(sp)
+;;       flw     ft1, 4(sp)
+;;       feq.s   t0, ft1, ft1
+;;       feq.s   t1, ft0, ft0
+;;       and     t0, t0, t1
+;;       beqz    t0, 0xc
+;;       fmin.s  ft1, ft1, ft0
+;;       j       8
+;;       fadd.s  ft1, ft1, ft0, rne
+;;       fmv.s   fa0, ft1
+;;       addi    sp, sp, 0x18
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
@@ -0,0 +1,38 @@
+;;! target = "riscv64"
+;;! test = "winch"
+
+(module
+    (func (param i32) (param i32) (result i32)
+        (local.get 0)
+        (local.get 1)
+        (i32.lt_u)
+    )
+)
+;; wasm[0]::function[0]:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x18
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x18
+;;       sd      a0, 0x10(sp)
+;;       sd      a1, 8(sp)
+;;       sw      a2, 4(sp)
+;;       sw      a3, 0(sp)
+;;       lw      t2, 0(sp)
+;;       lw      a0, 4(sp)
+;;       sext.w  t3, a0
+;;       sext.w  t4, t2
+;;       sltu    a0, t3, t4
+;;       addi    sp, sp, 0x18
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
@@ -0,0 +1,51 @@
+;;! target = "riscv64"
+;;! test = "winch"
+
+(module
+    (func (param f32) (result i32)
+        (local.get 0)
+        (i32.trunc_f32_s)
+    )
+)
+;; wasm[0]::function[0]:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x18
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x18
+;;       sd      a0, 0x10(sp)
+;;       sd      a1, 8(sp)
+;;       fsw     fa0, 4(sp)
+;;       flw     ft0, 4(sp)
+;;       feq.s   t0, ft0, ft0
+;;       bnez    t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       auipc   t0, 0
+;;       ld      t0, 0xc(t0)
+;;       j       0xc
+;;       .byte   0x01, 0x00, 0x00, 0xcf
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       fmv.w.x ft11, t0
+;;       fle.s   t0, ft0, ft11
+;;       beqz    t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       lui     t0, 0x4f000
+;;       fmv.w.x ft11, t0
+;;       fle.s   t0, ft11, ft0
+;;       beqz    t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       fcvt.w.s t2, ft0, rtz
+;;       mv      a0, t2
+;;       addi    sp, sp, 0x18
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
@@ -0,0 +1,45 @@
+;;! target = "riscv64"
+;;! test = "winch"
+
+(module
+    (func (param i64) (result i64)
+        (local.get 0)
+        (i64.popcnt)
+    )
+)
+;; wasm[0]::function[0]:
+;;       addi    sp, sp, -0x10
+;;       sd      ra, 8(sp)
+;;       sd      s0, 0(sp)
+;;       mv      s0, sp
+;;       ld      t0, 8(a0)
+;;       ld      t0, 0x10(t0)
+;;       lui     t1, 0
+;;       addiw   t1, t1, 0x18
+;;       add     t0, t0, t1
+;;       bgeu    sp, t0, 8
+;;       .byte   0x00, 0x00, 0x00, 0x00
+;;       mv      s1, a0
+;;       addi    sp, sp, -0x18
+;;       sd      a0, 0x10(sp)
+;;       sd      a1, 8(sp)
+;;       sd      a2, 0(sp)
+;;       ld      t2, 0(sp)
+;;       mv      t0, zero
+;;       addi    t1, zero, 0x40
+;;       addi    t3, zero, 1
+;;       slli    t3, t3, 0x3f
+;;       blez    t1, 0x1c
+;;       and     t5, t3, t2
+;;       beq     zero, t5, 8
+;;       addi    t0, t0, 1
+;;       addi    t1, t1, -1
+;;       srli    t3, t3, 1
+;;       j       -0x18
+;;       mv      t2, t0
+;;       mv      a0, t2
+;;       addi    sp, sp, 0x18
+;;       ld      ra, 8(sp)
+;;       ld      s0, 0(sp)
+;;       addi    sp, sp, 0x10
+;;       ret
@@ -0,0 +1,35 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = [ "-Ccranelift-has-avx" ]
+
+(module
+    (func (result v128)
+        (f32x4.gt (v128.const f32x4 1 2 3 4) (v128.const f32x4 4 3 2 1))
+    )
+)
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x10(%r11), %r11
+;;       addq    $0x10, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x4b
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x10, %rsp
+;;       movq    %rdi, 8(%rsp)
+;;       movq    %rsi, (%rsp)
+;;       movdqu  0x1c(%rip), %xmm0
+;;       movdqu  0x24(%rip), %xmm1
+;;       vcmpltps %xmm1, %xmm0, %xmm1
+;;       movdqa  %xmm1, %xmm0
+;;       addq    $0x10, %rsp
+;;       popq    %rbp
+;;       retq
+;;   4b: ud2
+;;   4d: addb    %al, (%rax)
+;;   4f: addb    %al, (%rax)
+;;   51: addb    %al, 0x40000040(%rax)
+;;   57: addb    %al, (%rax)
+;;   5a: addb    %al, (%rax)
+;;   5d: addb    %al, -0x7fffffc1(%rax)
@@ -0,0 +1,43 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = [ "-Ccranelift-has-avx" ]
+
+(module
+    (func (result v128)
+        (f32x4.neg (v128.const f32x4 1 -2 3 -4))
+    )
+)
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x10(%r11), %r11
+;;       addq    $0x10, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x42
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x10, %rsp
+;;       movq    %rdi, 8(%rsp)
+;;       movq    %rsi, (%rsp)
+;;       movdqu  0x1c(%rip), %xmm0
+;;       vxorps  0x24(%rip), %xmm0, %xmm0
+;;       addq    $0x10, %rsp
+;;       popq    %rbp
+;;       retq
+;;   42: ud2
+;;   44: addb    %al, (%rax)
+;;   46: addb    %al, (%rax)
+;;   48: addb    %al, (%rax)
+;;   4a: addb    %al, (%rax)
+;;   4c: addb    %al, (%rax)
+;;   4e: addb    %al, (%rax)
+;;   50: addb    %al, (%rax)
+;;   52: cmpb    $0, (%rdi)
+;;   55: addb    %al, (%rax)
+;;   57: rolb    $0, (%rax)
+;;   5a: addb    %al, (%rax)
+;;   5e: addb    $0, %al
+;;   61: addb    %al, (%rax)
+;;   63: addb    $0, (%rax)
+;;   66: addb    %al, -0x80000000(%rax)
+;;   6c: addb    %al, (%rax)
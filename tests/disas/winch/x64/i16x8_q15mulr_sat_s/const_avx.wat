@@ -0,0 +1,50 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = [ "-Ccranelift-has-avx" ]
+
+(module
+    (func (result v128)
+        (i16x8.q15mulr_sat_s (v128.const i16x8 1 2 3 4 5 6 7 8) (v128.const i16x8 8 7 6 5 4 3 2 1))
+    )
+)
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x10(%r11), %r11
+;;       addq    $0x10, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x57
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x10, %rsp
+;;       movq    %rdi, 8(%rsp)
+;;       movq    %rsi, (%rsp)
+;;       movdqu  0x2c(%rip), %xmm0
+;;       movdqu  0x34(%rip), %xmm1
+;;       vpmulhrsw %xmm0, %xmm1, %xmm1
+;;       vpcmpeqw 0x37(%rip), %xmm1, %xmm15
+;;       vpxor   %xmm1, %xmm15, %xmm1
+;;       movdqa  %xmm1, %xmm0
+;;       addq    $0x10, %rsp
+;;       popq    %rbp
+;;       retq
+;;   57: ud2
+;;   59: addb    %al, (%rax)
+;;   5b: addb    %al, (%rax)
+;;   5d: addb    %al, (%rax)
+;;   5f: addb    %cl, (%rax)
+;;   61: addb    %al, (%rdi)
+;;   63: addb    %al, (%rsi)
+;;   65: addb    %al, 0x3000400(%rip)
+;;   6b: addb    %al, (%rdx)
+;;   6d: addb    %al, (%rcx)
+;;   6f: addb    %al, (%rcx)
+;;   71: addb    %al, (%rdx)
+;;   73: addb    %al, (%rbx)
+;;   75: addb    %al, (%rax, %rax)
+;;   78: addl    $0x7000600, %eax
+;;   7d: addb    %cl, (%rax)
+;;   7f: addb    %al, (%rax)
+;;   81: addb    $0x80, (%rax)
+;;   84: addb    %al, -0x7fff8000(%rax)
+;;   8a: addb    %al, -0x7fff8000(%rax)
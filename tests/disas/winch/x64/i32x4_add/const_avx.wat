@@ -0,0 +1,45 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = [ "-Ccranelift-has-avx" ]
+
+(module
+    (func (result v128)
+        (i32x4.add (v128.const i32x4 1 2 3 4) (v128.const i32x4 5 6 7 8))
+    )
+)
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x10(%r11), %r11
+;;       addq    $0x10, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x4a
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x10, %rsp
+;;       movq    %rdi, 8(%rsp)
+;;       movq    %rsi, (%rsp)
+;;       movdqu  0x1c(%rip), %xmm0
+;;       movdqu  0x24(%rip), %xmm1
+;;       vpaddd  %xmm0, %xmm1, %xmm1
+;;       movdqa  %xmm1, %xmm0
+;;       addq    $0x10, %rsp
+;;       popq    %rbp
+;;       retq
+;;   4a: ud2
+;;   4c: addb    %al, (%rax)
+;;   4e: addb    %al, (%rax)
+;;   50: addl    $0x6000000, %eax
+;;   55: addb    %al, (%rax)
+;;   57: addb    %al, (%rdi)
+;;   59: addb    %al, (%rax)
+;;   5b: addb    %cl, (%rax)
+;;   5d: addb    %al, (%rax)
+;;   5f: addb    %al, (%rcx)
+;;   61: addb    %al, (%rax)
+;;   63: addb    %al, (%rdx)
+;;   65: addb    %al, (%rax)
+;;   67: addb    %al, (%rbx)
+;;   69: addb    %al, (%rax)
+;;   6b: addb    %al, (%rax, %rax)
+;;   6e: addb    %al, (%rax)
@@ -0,0 +1,34 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = [ "-Ccranelift-has-avx" ]
+
+(module
+    (func (result v128)
+        (v128.bitselect (v128.const i64x2 1 2) (v128.const i64x2 2 1) (v128.const i64x2 -1 0))
+    )
+)
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x10(%r11), %r11
+;;       addq    $0x10, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x5a
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x10, %rsp
+;;       movq    %rdi, 8(%rsp)
+;;       movq    %rsi, (%rsp)
+;;       movdqu  0x2c(%rip), %xmm0
+;;       movdqu  0x34(%rip), %xmm1
+;;       movdqu  0x3c(%rip), %xmm2
+;;       vpand   %xmm0, %xmm2, %xmm15
+;;       vpandn  %xmm1, %xmm0, %xmm2
+;;       vpor    %xmm2, %xmm15, %xmm2
+;;       movdqa  %xmm2, %xmm0
+;;       addq    $0x10, %rsp
+;;       popq    %rbp
+;;       retq
+;;   5a: ud2
+;;   5c: addb    %al, (%rax)
+;;   5e: addb    %al, (%rax)
@@ -0,0 +1,109 @@
+;;! exceptions = true
+
+(module
+  (tag $e0)
+  (tag $e1 (param i32))
+  (tag $e2 (param i64 f32))
+  (tag $e3 (param externref))
+
+  (func $throw-if (param i32)
+    (if (local.get 0) (then (throw $e0))))
+
+  (func (export "catch-all") (param i32) (result i32)
+    (block $h
+      (try_table (catch_all $h)
+        (call $throw-if (local.get 0)))
+      (return (i32.const 0)))
+    (i32.const 1))
+
+  (func (export "nested") (param i32) (result i32)
+    (block $outer (result i32)
+      (try_table (catch $e1 $outer)
+        (block $inner
+          (try_table (catch $e0 $inner)
+            (if (i32.eqz (local.get 0))
+              (then (throw $e0))
+              (else (throw $e1 (local.get 0))))))
+        (return (i32.const -1)))
+      (i32.const 0)))
+
+  (func (export "multi-payload") (result i64 f32)
+    (block $h (result i64 f32)
+      (try_table (catch $e2 $h)
+        (throw $e2 (i64.const 7) (f32.const 1.5)))
+      (unreachable)))
+
+  (func (export "in-loop") (param i32) (result i32)
+    (local $n i32)
+    (loop $l
+      (block $h
+        (try_table (catch_all $h)
+          (throw $e0)))
+      (local.set $n (i32.add (local.get $n) (i32.const 1)))
+      (br_if $l (i32.lt_u (local.get $n) (local.get 0))))
+    (local.get $n))
+
+  (func (export "externref") (param externref) (result externref)
+    (block $h (result externref)
+      (try_table (catch $e3 $h)
+        (throw $e3 (local.get 0)))
+      (unreachable)))
+
+  (func (export "rethrow") (param i32)
+    (block $h (result exnref)
+      (try_table (catch_all_ref $h)
+        (throw $e1 (local.get 0)))
+      (unreachable))
+    (throw_ref))
+
+  (func (export "uncaught")
+    (throw $e0))
+
+  (func (export "null")
+    (throw_ref (ref.null exn)))
+)
+
+(assert_return (invoke "catch-all" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "catch-all" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "nested" (i32.const 0)) (i32.const -1))
+(assert_return (invoke "nested" (i32.const 5)) (i32.const 5))
+(assert_return (invoke "multi-payload") (i64.const 7) (f32.const 1.5))
+(assert_return (invoke "in-loop" (i32.const 10)) (i32.const 10))
+(assert_return (invoke "externref" (ref.extern 1)) (ref.extern 1))
+(assert_return (invoke "externref" (ref.null extern)) (ref.null extern))
+(assert_exception (invoke "rethrow" (i32.const 1)))
+(assert_exception (invoke "uncaught"))
+(assert_trap (invoke "null") "null reference")
+
+;; Exceptions propagate across instances and tags are shared through imports.
+(module
+  (tag (export "e") (param i32))
+  (func (export "throw") (param i32)
+    (throw 0 (local.get 0)))
+)
+(register "m1")
+
+(module
+  (import "m1" "e" (tag $e (param i32)))
+  (import "m1" "throw" (func $throw (param i32)))
+  (tag $local (param i32))
+
+  (func (export "catch-imported") (result i32)
+    (block $h (result i32)
+      (try_table (catch $local $h) (catch $e $h)
+        (call $throw (i32.const 3)))
+      (i32.const 0)))
+
+  (func (export "miss") (result i32)
+    (block $h (result i32)
+      (try_table (catch $local $h)
+        (call $throw (i32.const 3)))
+      (i32.const 0)))
+)
+
+(assert_return (invoke "catch-imported") (i32.const 3))
+(assert_exception (invoke "miss"))
+
+(assert_unlinkable
+  (module (import "m1" "e" (tag (param i64))))
+  "incompatible")
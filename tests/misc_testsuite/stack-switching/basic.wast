@@ -0,0 +1,38 @@
+;;! stack_switching = true
+
+;; A generator that yields the numbers from its argument down to one.
+(module
+  (type $ft (func (param i32)))
+  (type $ct (cont $ft))
+  (type $ft0 (func))
+  (type $ct0 (cont $ft0))
+  (tag $yield (param i32))
+
+  (func $gen (param $n i32)
+    (loop $l
+      (if (local.get $n)
+        (then
+          (suspend $yield (local.get $n))
+          (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+          (br $l)))))
+  (elem declare func $gen)
+
+  (func (export "sum") (param $n i32) (result i32)
+    (local $k (ref null $ct0))
+    (local $sum i32)
+    (local.set $k (cont.bind $ct $ct0 (local.get $n) (cont.new $ct (ref.func $gen))))
+    (block $done
+      (loop $l
+        (block $on_yield (result i32 (ref $ct0))
+          (resume $ct0 (on $yield $on_yield) (local.get $k))
+          (br $done))
+        (local.set $k)
+        (local.set $sum (i32.add (local.get $sum)))
+        (br $l)))
+    (local.get $sum))
+)
+
+(assert_return (invoke "sum" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "sum" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "sum" (i32.const 10)) (i32.const 55))
+(assert_return (invoke "sum" (i32.const 1000)) (i32.const 500500))
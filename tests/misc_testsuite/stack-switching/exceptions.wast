@@ -0,0 +1,45 @@
+;;! stack_switching = true
+
+(module
+  (type $ft (func (result i32)))
+  (type $ct (cont $ft))
+  (tag $yield)
+  (tag $e (param i32))
+
+  ;; Catches the exception thrown into it with `resume_throw`.
+  (func $catcher (result i32)
+    (block $catch (result i32)
+      (try_table (catch $e $catch)
+        (suspend $yield))
+      (unreachable))
+    (i32.add (i32.const 1)))
+  (func $thrower (result i32)
+    (throw $e (i32.const 42)))
+  (elem declare func $catcher $thrower)
+
+  (func (export "resume-throw") (result i32)
+    (local $k (ref null $ct))
+    (block $h (result (ref $ct))
+      (resume $ct (on $yield $h) (cont.new $ct (ref.func $catcher)))
+      (unreachable))
+    (local.set $k)
+    (resume_throw $ct $e (i32.const 10) (local.get $k)))
+
+  (func (export "escapes") (result i32)
+    (block $catch (result i32)
+      (try_table (result i32) (catch $e $catch)
+        (resume $ct (cont.new $ct (ref.func $thrower))))
+      (return))
+    (i32.add (i32.const 1000)))
+
+  (func (export "resume-throw-fresh") (result i32)
+    (block $catch (result i32)
+      (try_table (result i32) (catch $e $catch)
+        (resume_throw $ct $e (i32.const 7) (cont.new $ct (ref.func $catcher))))
+      (return))
+    (i32.add (i32.const 2000)))
+)
+
+(assert_return (invoke "resume-throw") (i32.const 11))
+(assert_return (invoke "escapes") (i32.const 1042))
+(assert_return (invoke "resume-throw-fresh") (i32.const 2007))
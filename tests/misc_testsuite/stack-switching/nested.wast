@@ -0,0 +1,57 @@
+;;! stack_switching = true
+
+;; A continuation that resumes another continuation, whose suspensions to an
+;; outer handler suspend both of them and are resumed together.
+(module
+  (type $ft (func))
+  (type $ct (cont $ft))
+  (type $ft_i32 (func (param i32)))
+  (type $ct_i32 (cont $ft_i32))
+  (tag $outer (param i32) (result i32))
+  (tag $inner (param i32))
+
+  (global $log (mut i32) (i32.const 0))
+  (func $log (param i32)
+    (global.set $log
+      (i32.add (i32.mul (global.get $log) (i32.const 10)) (local.get 0))))
+
+  ;; Suspends to the outer handler, which doubles the value it is given, and
+  ;; then to the inner handler.
+  (func $leaf
+    (call $log (suspend $outer (i32.const 1)))
+    (suspend $inner (i32.const 3))
+    (call $log (suspend $outer (i32.const 2))))
+
+  (func $middle
+    (local $k (ref null $ct))
+    (local.set $k (cont.new $ct (ref.func $leaf)))
+    (loop $l
+      (block $h (result i32 (ref $ct))
+        (resume $ct (on $inner $h) (local.get $k))
+        (return))
+      (local.set $k)
+      (call $log)
+      (br $l)))
+  (elem declare func $leaf $middle)
+
+  (func (export "run") (result i32)
+    (local $k (ref null $ct_i32))
+    (local $v i32)
+    (block $h (result i32 (ref $ct_i32))
+      (resume $ct (on $outer $h) (cont.new $ct (ref.func $middle)))
+      (return (global.get $log)))
+    (local.set $k)
+    (local.set $v)
+    (loop $l
+      (block $h (result i32 (ref $ct_i32))
+        (resume $ct_i32 (on $outer $h)
+          (i32.mul (local.get $v) (i32.const 2))
+          (local.get $k))
+        (return (global.get $log)))
+      (local.set $k)
+      (local.set $v)
+      (br $l))
+    (unreachable))
+)
+
+(assert_return (invoke "run") (i32.const 234))
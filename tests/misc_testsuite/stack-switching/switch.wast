@@ -0,0 +1,33 @@
+;;! stack_switching = true
+;;! gc = true
+
+;; Two continuations that take turns incrementing a counter by switching
+;; directly to each other.
+(module
+  (rec
+    (type $ft (func (param i32 (ref null $ct)) (result i32)))
+    (type $ct (cont $ft)))
+  (type $ft0 (func (result i32)))
+  (type $ct0 (cont $ft0))
+  (tag $swap (result i32))
+
+  (func $ping (type $ft) (param $n i32) (param $k (ref null $ct)) (result i32)
+    (loop $l
+      (if (i32.lt_u (local.get $n) (i32.const 100))
+        (then
+          (switch $ct $swap (i32.add (local.get $n) (i32.const 1)) (local.get $k))
+          (local.set $k)
+          (local.set $n)
+          (br $l))))
+    (local.get $n))
+  (elem declare func $ping)
+
+  (func (export "run") (result i32)
+    (resume $ct0 (on $swap switch)
+      (cont.bind $ct $ct0
+        (i32.const 0)
+        (cont.new $ct (ref.func $ping))
+        (cont.new $ct (ref.func $ping)))))
+)
+
+(assert_return (invoke "run") (i32.const 100))
@@ -0,0 +1,42 @@
+;;! stack_switching = true
+
+(module
+  (type $ft (func))
+  (type $ct (cont $ft))
+  (tag $t)
+  (tag $unhandled)
+
+  (func $suspend_t (suspend $t))
+  (func $suspend_unhandled (suspend $unhandled))
+  (func $trap (unreachable))
+  (elem declare func $suspend_t $suspend_unhandled $trap)
+
+  (func (export "unhandled")
+    (block $h (result (ref $ct))
+      (resume $ct (on $t $h) (cont.new $ct (ref.func $suspend_unhandled)))
+      (return))
+    (drop))
+  (func (export "unhandled-outside-continuation")
+    (suspend $t))
+  (func (export "trap-in-continuation")
+    (resume $ct (cont.new $ct (ref.func $trap))))
+  (func (export "resume-twice")
+    (local $k (ref null $ct))
+    (local.set $k (cont.new $ct (ref.func $suspend_t)))
+    (block $h (result (ref $ct))
+      (resume $ct (on $t $h) (local.get $k))
+      (unreachable))
+    (drop)
+    (resume $ct (local.get $k)))
+  (func (export "resume-null")
+    (resume $ct (ref.null $ct)))
+  (func (export "cont-new-null")
+    (drop (cont.new $ct (ref.null $ft))))
+)
+
+(assert_trap (invoke "unhandled") "unhandled tag")
+(assert_trap (invoke "unhandled-outside-continuation") "unhandled tag")
+(assert_trap (invoke "trap-in-continuation") "unreachable")
+(assert_trap (invoke "resume-twice") "continuation already consumed")
+(assert_trap (invoke "resume-null") "null reference")
+(assert_trap (invoke "cont-new-null") "null reference")
@@ -0,0 +1,176 @@
+;;! simd = true
+
+;; Tests for the lane-wise arithmetic and bitwise instructions
+
+(module
+  (func (export "v128.and") (param v128 v128) (result v128) (v128.and (local.get 0) (local.get 1)))
+  (func (export "v128.andnot") (param v128 v128) (result v128) (v128.andnot (local.get 0) (local.get 1)))
+  (func (export "v128.or") (param v128 v128) (result v128) (v128.or (local.get 0) (local.get 1)))
+  (func (export "v128.xor") (param v128 v128) (result v128) (v128.xor (local.get 0) (local.get 1)))
+  (func (export "v128.not") (param v128) (result v128) (v128.not (local.get 0)))
+  (func (export "v128.bitselect") (param v128 v128 v128) (result v128)
+    (v128.bitselect (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "i8x16.add") (param v128 v128) (result v128) (i8x16.add (local.get 0) (local.get 1)))
+  (func (export "i8x16.add_sat_s") (param v128 v128) (result v128) (i8x16.add_sat_s (local.get 0) (local.get 1)))
+  (func (export "i8x16.add_sat_u") (param v128 v128) (result v128) (i8x16.add_sat_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.sub") (param v128 v128) (result v128) (i8x16.sub (local.get 0) (local.get 1)))
+  (func (export "i8x16.sub_sat_s") (param v128 v128) (result v128) (i8x16.sub_sat_s (local.get 0) (local.get 1)))
+  (func (export "i8x16.sub_sat_u") (param v128 v128) (result v128) (i8x16.sub_sat_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.neg") (param v128) (result v128) (i8x16.neg (local.get 0)))
+  (func (export "i8x16.min_s") (param v128 v128) (result v128) (i8x16.min_s (local.get 0) (local.get 1)))
+  (func (export "i8x16.max_u") (param v128 v128) (result v128) (i8x16.max_u (local.get 0) (local.get 1)))
+  (func (export "i8x16.avgr_u") (param v128 v128) (result v128) (i8x16.avgr_u (local.get 0) (local.get 1)))
+
+  (func (export "i16x8.add") (param v128 v128) (result v128) (i16x8.add (local.get 0) (local.get 1)))
+  (func (export "i16x8.sub_sat_s") (param v128 v128) (result v128) (i16x8.sub_sat_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.mul") (param v128 v128) (result v128) (i16x8.mul (local.get 0) (local.get 1)))
+  (func (export "i16x8.neg") (param v128) (result v128) (i16x8.neg (local.get 0)))
+  (func (export "i16x8.min_u") (param v128 v128) (result v128) (i16x8.min_u (local.get 0) (local.get 1)))
+  (func (export "i16x8.max_s") (param v128 v128) (result v128) (i16x8.max_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.avgr_u") (param v128 v128) (result v128) (i16x8.avgr_u (local.get 0) (local.get 1)))
+  (func (export "i16x8.q15mulr_sat_s") (param v128 v128) (result v128) (i16x8.q15mulr_sat_s (local.get 0) (local.get 1)))
+
+  (func (export "i32x4.add") (param v128 v128) (result v128) (i32x4.add (local.get 0) (local.get 1)))
+  (func (export "i32x4.sub") (param v128 v128) (result v128) (i32x4.sub (local.get 0) (local.get 1)))
+  (func (export "i32x4.mul") (param v128 v128) (result v128) (i32x4.mul (local.get 0) (local.get 1)))
+  (func (export "i32x4.neg") (param v128) (result v128) (i32x4.neg (local.get 0)))
+  (func (export "i32x4.min_s") (param v128 v128) (result v128) (i32x4.min_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.max_u") (param v128 v128) (result v128) (i32x4.max_u (local.get 0) (local.get 1)))
+
+  (func (export "i64x2.add") (param v128 v128) (result v128) (i64x2.add (local.get 0) (local.get 1)))
+  (func (export "i64x2.sub") (param v128 v128) (result v128) (i64x2.sub (local.get 0) (local.get 1)))
+  (func (export "i64x2.neg") (param v128) (result v128) (i64x2.neg (local.get 0)))
+
+  (func (export "f32x4.add") (param v128 v128) (result v128) (f32x4.add (local.get 0) (local.get 1)))
+  (func (export "f32x4.sub") (param v128 v128) (result v128) (f32x4.sub (local.get 0) (local.get 1)))
+  (func (export "f32x4.mul") (param v128 v128) (result v128) (f32x4.mul (local.get 0) (local.get 1)))
+  (func (export "f32x4.div") (param v128 v128) (result v128) (f32x4.div (local.get 0) (local.get 1)))
+  (func (export "f32x4.neg") (param v128) (result v128) (f32x4.neg (local.get 0)))
+  (func (export "f32x4.pmin") (param v128 v128) (result v128) (f32x4.pmin (local.get 0) (local.get 1)))
+  (func (export "f32x4.pmax") (param v128 v128) (result v128) (f32x4.pmax (local.get 0) (local.get 1)))
+
+  (func (export "f64x2.add") (param v128 v128) (result v128) (f64x2.add (local.get 0) (local.get 1)))
+  (func (export "f64x2.sub") (param v128 v128) (result v128) (f64x2.sub (local.get 0) (local.get 1)))
+  (func (export "f64x2.mul") (param v128 v128) (result v128) (f64x2.mul (local.get 0) (local.get 1)))
+  (func (export "f64x2.div") (param v128 v128) (result v128) (f64x2.div (local.get 0) (local.get 1)))
+  (func (export "f64x2.neg") (param v128) (result v128) (f64x2.neg (local.get 0)))
+  (func (export "f64x2.pmin") (param v128 v128) (result v128) (f64x2.pmin (local.get 0) (local.get 1)))
+  (func (export "f64x2.pmax") (param v128 v128) (result v128) (f64x2.pmax (local.get 0) (local.get 1)))
+
+  ;; Operands which are constants or locals are moved into registers before
+  ;; being operated on, make sure those aren't clobbered.
+  (func (export "sub-const") (param v128) (result v128)
+    (i32x4.sub (v128.const i32x4 10 20 30 40) (local.get 0)))
+  (func (export "reuse-local") (param v128) (result v128)
+    (i32x4.add (i32x4.neg (local.get 0)) (local.get 0)))
+)
+
+(assert_return (invoke "v128.and" (v128.const i32x4 0xff00ff00 0 -1 0x0f0f0f0f) (v128.const i32x4 0x0ff00ff0 -1 0 -1))
+               (v128.const i32x4 0x0f000f00 0 0 0x0f0f0f0f))
+(assert_return (invoke "v128.andnot" (v128.const i32x4 0xff00ff00 0 -1 0x0f0f0f0f) (v128.const i32x4 0x0ff00ff0 -1 0 -1))
+               (v128.const i32x4 0xf000f000 0 -1 0))
+(assert_return (invoke "v128.or" (v128.const i32x4 0xff00ff00 0 -1 0x0f0f0f0f) (v128.const i32x4 0x0ff00ff0 -1 0 0xf0f0f0f0))
+               (v128.const i32x4 0xfff0fff0 -1 -1 -1))
+(assert_return (invoke "v128.xor" (v128.const i32x4 0xff00ff00 0 -1 0x0f0f0f0f) (v128.const i32x4 0x0ff00ff0 -1 -1 -1))
+               (v128.const i32x4 0xf0f0f0f0 -1 0 0xf0f0f0f0))
+(assert_return (invoke "v128.not" (v128.const i32x4 0xff00ff00 0 -1 0x0f0f0f0f))
+               (v128.const i32x4 0x00ff00ff -1 0 0xf0f0f0f0))
+(assert_return (invoke "v128.bitselect"
+                 (v128.const i32x4 0xaaaaaaaa 0xaaaaaaaa 0xaaaaaaaa 0xaaaaaaaa)
+                 (v128.const i32x4 0x55555555 0x55555555 0x55555555 0x55555555)
+                 (v128.const i32x4 0xffff0000 0 -1 0x0f0f0f0f))
+               (v128.const i32x4 0xaaaa5555 0x55555555 0xaaaaaaaa 0x5a5a5a5a))
+
+(assert_return (invoke "i8x16.add" (v128.const i8x16 0 1 127 -128 -1 100 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 0 1 1 -1 1 100 0 0 0 0 0 0 0 0 0 1))
+               (v128.const i8x16 0 2 -128 127 0 -56 0 0 0 0 0 0 0 0 0 1))
+(assert_return (invoke "i8x16.add_sat_s" (v128.const i8x16 0 1 127 -128 -1 100 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 0 1 1 -1 1 100 0 0 0 0 0 0 0 0 0 1))
+               (v128.const i8x16 0 2 127 -128 0 127 0 0 0 0 0 0 0 0 0 1))
+(assert_return (invoke "i8x16.add_sat_u" (v128.const i8x16 0 1 127 -128 -1 100 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 0 1 1 -1 1 100 0 0 0 0 0 0 0 0 0 1))
+               (v128.const i8x16 0 2 -128 -1 -1 -56 0 0 0 0 0 0 0 0 0 1))
+(assert_return (invoke "i8x16.sub" (v128.const i8x16 0 1 -128 127 0 0 0 0 0 0 0 0 0 0 0 5) (v128.const i8x16 0 2 1 -1 1 0 0 0 0 0 0 0 0 0 0 3))
+               (v128.const i8x16 0 -1 127 -128 -1 0 0 0 0 0 0 0 0 0 0 2))
+(assert_return (invoke "i8x16.sub_sat_s" (v128.const i8x16 0 1 -128 127 0 0 0 0 0 0 0 0 0 0 0 5) (v128.const i8x16 0 2 1 -1 1 0 0 0 0 0 0 0 0 0 0 3))
+               (v128.const i8x16 0 -1 -128 127 -1 0 0 0 0 0 0 0 0 0 0 2))
+(assert_return (invoke "i8x16.sub_sat_u" (v128.const i8x16 0 1 -128 127 0 0 0 0 0 0 0 0 0 0 0 5) (v128.const i8x16 0 2 1 -1 1 0 0 0 0 0 0 0 0 0 0 3))
+               (v128.const i8x16 0 0 127 0 0 0 0 0 0 0 0 0 0 0 0 2))
+(assert_return (invoke "i8x16.neg" (v128.const i8x16 0 1 -1 127 -128 5 0 0 0 0 0 0 0 0 0 -5))
+               (v128.const i8x16 0 -1 1 -127 -128 -5 0 0 0 0 0 0 0 0 0 5))
+(assert_return (invoke "i8x16.min_s" (v128.const i8x16 0 1 -1 127 -128 5 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 0 -1 1 -128 127 5 0 0 0 0 0 0 0 0 0 1))
+               (v128.const i8x16 0 -1 -1 -128 -128 5 0 0 0 0 0 0 0 0 0 0))
+(assert_return (invoke "i8x16.max_u" (v128.const i8x16 0 1 -1 127 -128 5 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 0 -1 1 -128 127 5 0 0 0 0 0 0 0 0 0 1))
+               (v128.const i8x16 0 -1 -1 -128 -128 5 0 0 0 0 0 0 0 0 0 1))
+(assert_return (invoke "i8x16.avgr_u" (v128.const i8x16 0 1 -1 2 0 0 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 0 2 -1 4 0 0 0 0 0 0 0 0 0 0 0 1))
+               (v128.const i8x16 0 2 -1 3 0 0 0 0 0 0 0 0 0 0 0 1))
+
+(assert_return (invoke "i16x8.add" (v128.const i16x8 0 1 32767 -32768 -1 1000 0 0) (v128.const i16x8 0 1 1 -1 1 1000 0 1))
+               (v128.const i16x8 0 2 -32768 32767 0 2000 0 1))
+(assert_return (invoke "i16x8.sub_sat_s" (v128.const i16x8 0 1 -32768 32767 0 0 0 5) (v128.const i16x8 0 2 1 -1 1 0 0 3))
+               (v128.const i16x8 0 -1 -32768 32767 -1 0 0 2))
+(assert_return (invoke "i16x8.mul" (v128.const i16x8 0 2 -3 256 32767 0 0 7) (v128.const i16x8 5 3 4 256 2 0 0 -7))
+               (v128.const i16x8 0 6 -12 0 -2 0 0 -49))
+(assert_return (invoke "i16x8.neg" (v128.const i16x8 0 1 -1 32767 -32768 5 0 -5))
+               (v128.const i16x8 0 -1 1 -32767 -32768 -5 0 5))
+(assert_return (invoke "i16x8.min_u" (v128.const i16x8 0 1 -1 32767 -32768 5 0 0) (v128.const i16x8 0 -1 1 -32768 32767 5 0 1))
+               (v128.const i16x8 0 1 1 32767 32767 5 0 0))
+(assert_return (invoke "i16x8.max_s" (v128.const i16x8 0 1 -1 32767 -32768 5 0 0) (v128.const i16x8 0 -1 1 -32768 32767 5 0 1))
+               (v128.const i16x8 0 1 1 32767 32767 5 0 1))
+(assert_return (invoke "i16x8.avgr_u" (v128.const i16x8 0 1 -1 2 0 0 0 0) (v128.const i16x8 0 2 -1 4 0 0 0 1))
+               (v128.const i16x8 0 2 -1 3 0 0 0 1))
+(assert_return (invoke "i16x8.q15mulr_sat_s" (v128.const i16x8 -32768 16384 -32768 32767 0 1 0 0) (v128.const i16x8 -32768 16384 32767 32767 0 1 0 0))
+               (v128.const i16x8 32767 8192 -32767 32766 0 0 0 0))
+
+(assert_return (invoke "i32x4.add" (v128.const i32x4 1 0x7fffffff -1 100) (v128.const i32x4 2 1 1 -200))
+               (v128.const i32x4 3 0x80000000 0 -100))
+(assert_return (invoke "i32x4.sub" (v128.const i32x4 1 0x80000000 0 100) (v128.const i32x4 2 1 1 -200))
+               (v128.const i32x4 -1 0x7fffffff -1 300))
+(assert_return (invoke "i32x4.mul" (v128.const i32x4 3 0x10000 -7 0x7fffffff) (v128.const i32x4 4 0x10000 7 2))
+               (v128.const i32x4 12 0 -49 -2))
+(assert_return (invoke "i32x4.neg" (v128.const i32x4 0 1 -1 0x80000000))
+               (v128.const i32x4 0 -1 1 0x80000000))
+(assert_return (invoke "i32x4.min_s" (v128.const i32x4 0 1 -1 0x80000000) (v128.const i32x4 1 -1 0 0x7fffffff))
+               (v128.const i32x4 0 -1 -1 0x80000000))
+(assert_return (invoke "i32x4.max_u" (v128.const i32x4 0 1 -1 0x80000000) (v128.const i32x4 1 -1 0 0x7fffffff))
+               (v128.const i32x4 1 -1 -1 0x80000000))
+
+(assert_return (invoke "i64x2.add" (v128.const i64x2 0x7fffffffffffffff 0xffffffff) (v128.const i64x2 1 1))
+               (v128.const i64x2 0x8000000000000000 0x100000000))
+(assert_return (invoke "i64x2.sub" (v128.const i64x2 0 0x100000000) (v128.const i64x2 1 1))
+               (v128.const i64x2 -1 0xffffffff))
+(assert_return (invoke "i64x2.neg" (v128.const i64x2 1 0x8000000000000000))
+               (v128.const i64x2 -1 0x8000000000000000))
+
+(assert_return (invoke "f32x4.add" (v128.const f32x4 1.5 -2 inf 0) (v128.const f32x4 1.5 0.5 -inf -0))
+               (v128.const f32x4 3 -1.5 nan:canonical 0))
+(assert_return (invoke "f32x4.sub" (v128.const f32x4 1.5 -2 inf 0) (v128.const f32x4 1.5 0.5 inf 0))
+               (v128.const f32x4 0 -2.5 nan:canonical 0))
+(assert_return (invoke "f32x4.mul" (v128.const f32x4 1.5 -2 inf 0) (v128.const f32x4 2 0.5 -1 -1))
+               (v128.const f32x4 3 -1 -inf -0))
+(assert_return (invoke "f32x4.div" (v128.const f32x4 3 -2 1 0) (v128.const f32x4 2 0.5 0 0))
+               (v128.const f32x4 1.5 -4 inf nan:canonical))
+(assert_return (invoke "f32x4.neg" (v128.const f32x4 1.5 -2 0 -inf))
+               (v128.const f32x4 -1.5 2 -0 inf))
+(assert_return (invoke "f32x4.pmin" (v128.const f32x4 1 0 nan 2) (v128.const f32x4 2 -0 1 nan))
+               (v128.const f32x4 1 0 nan:canonical 2))
+(assert_return (invoke "f32x4.pmax" (v128.const f32x4 1 -0 nan 2) (v128.const f32x4 2 0 1 nan))
+               (v128.const f32x4 2 -0 nan:canonical 2))
+
+(assert_return (invoke "f64x2.add" (v128.const f64x2 1.5 -2) (v128.const f64x2 1.5 0.5))
+               (v128.const f64x2 3 -1.5))
+(assert_return (invoke "f64x2.sub" (v128.const f64x2 1.5 inf) (v128.const f64x2 1.5 inf))
+               (v128.const f64x2 0 nan:canonical))
+(assert_return (invoke "f64x2.mul" (v128.const f64x2 1.5 -2) (v128.const f64x2 2 0.5))
+               (v128.const f64x2 3 -1))
+(assert_return (invoke "f64x2.div" (v128.const f64x2 3 -1) (v128.const f64x2 2 0))
+               (v128.const f64x2 1.5 -inf))
+(assert_return (invoke "f64x2.neg" (v128.const f64x2 0 -inf))
+               (v128.const f64x2 -0 inf))
+(assert_return (invoke "f64x2.pmin" (v128.const f64x2 1 0) (v128.const f64x2 2 -0))
+               (v128.const f64x2 1 0))
+(assert_return (invoke "f64x2.pmax" (v128.const f64x2 1 -0) (v128.const f64x2 2 0))
+               (v128.const f64x2 2 -0))
+
+(assert_return (invoke "sub-const" (v128.const i32x4 1 2 3 4))
+               (v128.const i32x4 9 18 27 36))
+(assert_return (invoke "reuse-local" (v128.const i32x4 1 -2 3 0x80000000))
+               (v128.const i32x4 0 0 0 0))
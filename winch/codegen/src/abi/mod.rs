@@ -72,11 +72,11 @@ macro_rules! vmctx {
     };
 }
 
-/// Macro to get the designated general purpose scratch register or the
-/// designated scratch register for the given type.
+/// Macro to get the designated general purpose scratch register or, as a
+/// `Result`, the designated scratch register for the given type.
 macro_rules! scratch {
     ($m:ident) => {
-        <$m::ABI as $crate::abi::ABI>::scratch_for(&wasmtime_environ::WasmValType::I64)
+        <$m::ABI as $crate::abi::ABI>::scratch_reg()
     };
     ($m:ident, $wasm_type:expr) => {
         <$m::ABI as $crate::abi::ABI>::scratch_for($wasm_type)
@@ -139,8 +139,13 @@ pub(crate) trait ABI {
         Self::word_bits() / 8
     }
 
+    /// Returns the designated general purpose scratch register.
+    fn scratch_reg() -> Reg;
+
     /// Returns the designated scratch register for the given [WasmType].
-    fn scratch_for(ty: &WasmValType) -> Reg;
+    ///
+    /// Returns an error for types which aren't supported.
+    fn scratch_for(ty: &WasmValType) -> Result<Reg>;
 
     /// Returns the pinned register used to hold
     /// the `VMContext`.
@@ -150,7 +155,9 @@ pub(crate) trait ABI {
     fn stack_slot_size() -> u8;
 
     /// Returns the size in bytes of the given [`WasmType`].
-    fn sizeof(ty: &WasmValType) -> u8;
+    ///
+    /// Returns an error for types which aren't supported.
+    fn sizeof(ty: &WasmValType) -> Result<u8>;
 
     /// The target pointer size represented as [WasmValType].
     fn ptr_type() -> WasmValType {
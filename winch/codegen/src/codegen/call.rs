@@ -316,7 +316,7 @@ impl FnCall {
                 &ABIOperand::Stack { ty, offset, .. } => {
                     let addr = masm.address_at_sp(SPOffset::from_u32(offset))?;
                     let size: OperandSize = ty.try_into()?;
-                    let scratch = scratch!(M, &ty);
+                    let scratch = scratch!(M, &ty)?;
                     context.move_val_to_reg(val, scratch, masm)?;
                     masm.store(scratch.into(), addr, size)?;
                 }
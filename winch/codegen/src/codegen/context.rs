@@ -294,7 +294,7 @@ impl<'a> CodeGenContext<'a, Emission> {
                 masm.store(scratch.into(), addr, size)?;
             }
             Val::Memory(_) => {
-                let scratch = scratch!(M, &ty);
+                let scratch = scratch!(M, &ty)?;
                 masm.pop(writable!(scratch), size)?;
                 masm.store(scratch.into(), addr, size)?;
             }
@@ -807,7 +807,7 @@ impl<'a> CodeGenContext<'a, Emission> {
                 Val::Local(local) => {
                     let slot = frame.get_wasm_local(local.index);
                     let addr = masm.local_address(&slot)?;
-                    let scratch = scratch!(M, &slot.ty);
+                    let scratch = scratch!(M, &slot.ty)?;
                     masm.load(addr, writable!(scratch), slot.ty.try_into()?)?;
                     let stack_slot = masm.push(scratch, slot.ty.try_into()?)?;
                     *v = Val::mem(slot.ty, stack_slot);
@@ -1120,9 +1120,7 @@ impl Assembler {
 
                     (false, _) => -1.,
 
-                    (_, S128) => {
-                        unimplemented!("floating point conversion to 128bit are not supported")
-                    }
+                    (_, S128) => unreachable!("floating point conversion to 128bit"),
                 };
 
                 self.load_const_fp(min.to_bits() as u64, rd, in_size);
@@ -1136,9 +1134,7 @@ impl Assembler {
 
                     (false, _) => -1.,
 
-                    (_, S128) => {
-                        unimplemented!("floating point conversion to 128bit are not supported")
-                    }
+                    (_, S128) => unreachable!("floating point conversion to 128bit"),
                 };
 
                 self.load_const_fp(min.to_bits(), rd, in_size);
@@ -1171,9 +1167,7 @@ impl Assembler {
                     (false, S32) => u32::MAX as f32 + 1.,
                     (false, S64) => (u64::MAX as u128 + 1) as f32,
 
-                    (_, S128) => {
-                        unimplemented!("floating point conversion to 128bit are not supported")
-                    }
+                    (_, S128) => unreachable!("floating point conversion to 128bit"),
                 };
 
                 self.load_const_fp(max.to_bits() as u64, rd, in_size);
@@ -1190,9 +1184,7 @@ impl Assembler {
                     (false, S32) => u32::MAX as f64 + 1.,
                     (false, S64) => (u64::MAX as u128 + 1) as f64,
 
-                    (_, S128) => {
-                        unimplemented!("floating point conversion to 128bit are not supported")
-                    }
+                    (_, S128) => unreachable!("floating point conversion to 128bit"),
                 };
 
                 self.load_const_fp(max.to_bits(), rd, in_size);
@@ -1229,6 +1221,9 @@ impl Assembler {
 
     /// Convert the floating point of size `src_size` stored in `src`, into a integer of size
     /// `dst_size`, storing the result in `dst`.
+    ///
+    /// Wasm has no conversions to 128-bit integers, so `dst_size` must be 32
+    /// or 64 bits.
     pub fn fpu_to_int(
         &mut self,
         dst: Writable<Reg>,
@@ -1275,7 +1270,7 @@ impl Assembler {
             (OperandSize::S64, OperandSize::S32, true) => FpuToIntOp::F64ToI32,
             (OperandSize::S64, OperandSize::S64, false) => FpuToIntOp::F64ToU64,
             (OperandSize::S64, OperandSize::S64, true) => FpuToIntOp::F64ToI64,
-            (fsize, int_size, signed) => unimplemented!(
+            (fsize, int_size, signed) => unreachable!(
                 "unsupported conversion: f{} to {}{}",
                 fsize.num_bits(),
                 if signed { "i" } else { "u" },
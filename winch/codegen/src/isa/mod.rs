@@ -20,6 +20,9 @@ pub(crate) mod x64;
 #[cfg(feature = "arm64")]
 pub(crate) mod aarch64;
 
+#[cfg(feature = "riscv64")]
+pub(crate) mod riscv64;
+
 pub(crate) mod reg;
 
 macro_rules! isa_builder {
@@ -46,6 +49,9 @@ pub fn lookup(triple: Triple) -> Result<Builder> {
         Architecture::Aarch64 { .. } => {
             isa_builder!(aarch64, (feature = "arm64"), triple)
         }
+        Architecture::Riscv64 { .. } => {
+            isa_builder!(riscv64, (feature = "riscv64"), triple)
+        }
 
         _ => Err(anyhow!(LookupError::Unsupported)),
     }
@@ -0,0 +1,37 @@
+//! Riscv64 addressing mode.
+
+use cranelift_codegen::isa::riscv64::AMode;
+
+use crate::reg::Reg;
+
+/// Memory address representation.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Address {
+    /// Base register with an arbitrary offset.  Potentially gets
+    /// lowered into multiple instructions during code emission
+    /// depending on the offset.
+    Offset {
+        /// Base register.
+        base: Reg,
+        /// Offset.
+        offset: i64,
+    },
+}
+
+impl Address {
+    /// Create register and arbitrary offset addressing mode.
+    pub fn offset(base: Reg, offset: i64) -> Self {
+        Self::Offset { base, offset }
+    }
+}
+
+// Conversions between `winch-codegen`'s addressing mode representation
+// and `cranelift-codegen`s addressing mode representation for riscv64.
+
+impl From<Address> for AMode {
+    fn from(addr: Address) -> Self {
+        match addr {
+            Address::Offset { base, offset } => AMode::RegOffset(base.into(), offset),
+        }
+    }
+}
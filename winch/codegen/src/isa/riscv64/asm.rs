@@ -0,0 +1,599 @@
+//! Assembler library implementation for Riscv64.
+use super::{address::Address, regs};
+use crate::masm::{FloatCmpKind, IntCmpKind, OperandSize, RoundingMode};
+use crate::CallingConvention;
+use crate::{
+    masm::TRUSTED_FLAGS,
+    reg::{writable, Reg, WritableReg},
+};
+
+use cranelift_codegen::{
+    ir::{
+        condcodes::IntCC, types, ExternalName, LibCall, MemFlags, SourceLoc, TrapCode, Type,
+        UserExternalNameRef,
+    },
+    isa::{
+        riscv64::{
+            settings as riscv_settings, AluOPRRI, AluOPRRR, CondBrTarget, EmitInfo, EmitState,
+            FpuOPRR, FpuOPRRR, FpuOPWidth, Imm12, Inst, IntegerCompare, LoadOP, StoreOP, FRM,
+        },
+        unwind::UnwindInst,
+    },
+    settings, Final, MachBuffer, MachBufferFinalized, MachInst, MachInstEmit, MachInstEmitState,
+    MachLabel, PatchRegion, Writable,
+};
+
+impl From<IntCmpKind> for IntCC {
+    fn from(value: IntCmpKind) -> Self {
+        match value {
+            IntCmpKind::Eq => IntCC::Equal,
+            IntCmpKind::Ne => IntCC::NotEqual,
+            IntCmpKind::LtS => IntCC::SignedLessThan,
+            IntCmpKind::LtU => IntCC::UnsignedLessThan,
+            IntCmpKind::GtS => IntCC::SignedGreaterThan,
+            IntCmpKind::GtU => IntCC::UnsignedGreaterThan,
+            IntCmpKind::LeS => IntCC::SignedLessThanOrEqual,
+            IntCmpKind::LeU => IntCC::UnsignedLessThanOrEqual,
+            IntCmpKind::GeS => IntCC::SignedGreaterThanOrEqual,
+            IntCmpKind::GeU => IntCC::UnsignedGreaterThanOrEqual,
+        }
+    }
+}
+
+impl From<OperandSize> for FpuOPWidth {
+    fn from(size: OperandSize) -> Self {
+        match size {
+            OperandSize::S32 => FpuOPWidth::S,
+            OperandSize::S64 => FpuOPWidth::D,
+            s => panic!("Invalid floating point operand size {s:?}"),
+        }
+    }
+}
+
+impl From<RoundingMode> for FRM {
+    fn from(mode: RoundingMode) -> Self {
+        match mode {
+            RoundingMode::Nearest => FRM::RNE,
+            RoundingMode::Up => FRM::RUP,
+            RoundingMode::Down => FRM::RDN,
+            RoundingMode::Zero => FRM::RTZ,
+        }
+    }
+}
+
+/// Returns the integer type matching the given operand size.
+fn int_ty(size: OperandSize) -> Type {
+    match size {
+        OperandSize::S8 => types::I8,
+        OperandSize::S16 => types::I16,
+        OperandSize::S32 => types::I32,
+        OperandSize::S64 => types::I64,
+        OperandSize::S128 => types::I128,
+    }
+}
+
+/// Low level assembler implementation for Riscv64.
+pub(crate) struct Assembler {
+    /// The machine instruction buffer.
+    buffer: MachBuffer<Inst>,
+    /// Constant emission information.
+    emit_info: EmitInfo,
+    /// Emission state.
+    emit_state: EmitState,
+}
+
+impl Assembler {
+    /// Create a new Riscv64 assembler.
+    pub fn new(shared_flags: settings::Flags, isa_flags: riscv_settings::Flags) -> Self {
+        Self {
+            buffer: MachBuffer::<Inst>::new(),
+            emit_state: Default::default(),
+            emit_info: EmitInfo::new(shared_flags, isa_flags),
+        }
+    }
+}
+
+impl Assembler {
+    /// Return the emitted code.
+    pub fn finalize(mut self, loc: Option<SourceLoc>) -> MachBufferFinalized<Final> {
+        let constants = Default::default();
+        let stencil = self
+            .buffer
+            .finish(&constants, self.emit_state.ctrl_plane_mut());
+        stencil.apply_base_srcloc(loc.unwrap_or_default())
+    }
+
+    fn emit(&mut self, inst: Inst) {
+        self.emit_with_island(inst, Inst::worst_case_size());
+    }
+
+    fn emit_with_island(&mut self, inst: Inst, needed_space: u32) {
+        if self.buffer.island_needed(needed_space) {
+            let label = self.buffer.get_label();
+            let jmp = Inst::Jal { label };
+            jmp.emit(&mut self.buffer, &self.emit_info, &mut self.emit_state);
+            self.buffer
+                .emit_island(needed_space, self.emit_state.ctrl_plane_mut());
+            self.buffer
+                .bind_label(label, self.emit_state.ctrl_plane_mut());
+        }
+        inst.emit(&mut self.buffer, &self.emit_info, &mut self.emit_state);
+    }
+
+    /// Load a constant into a register.
+    pub fn load_constant(&mut self, imm: u64, rd: WritableReg) {
+        Inst::load_constant_u64(rd.map(Into::into), imm)
+            .into_iter()
+            .for_each(|i| self.emit(i));
+    }
+
+    /// Register to register move.
+    pub fn mov_rr(&mut self, rm: Reg, rd: WritableReg) {
+        self.emit(Inst::Mov {
+            rd: rd.map(Into::into),
+            rm: rm.into(),
+            ty: types::I64,
+        });
+    }
+
+    /// Floating point register to floating point register move.
+    pub fn fmov_rr(&mut self, rm: Reg, rd: WritableReg, size: OperandSize) {
+        let ty = match size {
+            OperandSize::S32 => types::F32,
+            OperandSize::S64 => types::F64,
+            s => panic!("Invalid floating point operand size {s:?}"),
+        };
+        self.emit(Inst::Mov {
+            rd: rd.map(Into::into),
+            rm: rm.into(),
+            ty,
+        });
+    }
+
+    /// Move the bits of an integer register to a floating point register.
+    pub fn mov_to_fpu(&mut self, rs: Reg, rd: WritableReg, size: OperandSize) {
+        self.fpu_rr(FpuOPRR::FmvFmtX, size, FRM::RNE, rs, rd);
+    }
+
+    /// Move the bits of a floating point register to an integer register.
+    pub fn mov_from_fpu(&mut self, rs: Reg, rd: WritableReg, size: OperandSize) {
+        self.fpu_rr(FpuOPRR::FmvXFmt, size, FRM::RNE, rs, rd);
+    }
+
+    /// Load an integer of the given size, zero extending it to 64 bits.
+    pub fn uload(&mut self, src: Address, rd: WritableReg, size: OperandSize, flags: MemFlags) {
+        let op = match size {
+            OperandSize::S8 => LoadOP::Lbu,
+            OperandSize::S16 => LoadOP::Lhu,
+            OperandSize::S32 => LoadOP::Lwu,
+            OperandSize::S64 => LoadOP::Ld,
+            s => panic!("Invalid load size {s:?}"),
+        };
+        self.load(src, rd, op, flags);
+    }
+
+    /// Load an integer of the given size, sign extending it to 64 bits.
+    pub fn sload(&mut self, src: Address, rd: WritableReg, size: OperandSize, flags: MemFlags) {
+        let op = match size {
+            OperandSize::S8 => LoadOP::Lb,
+            OperandSize::S16 => LoadOP::Lh,
+            OperandSize::S32 => LoadOP::Lw,
+            OperandSize::S64 => LoadOP::Ld,
+            s => panic!("Invalid load size {s:?}"),
+        };
+        self.load(src, rd, op, flags);
+    }
+
+    /// Load a floating point value of the given size.
+    pub fn fload(&mut self, src: Address, rd: WritableReg, size: OperandSize, flags: MemFlags) {
+        let op = match size {
+            OperandSize::S32 => LoadOP::Flw,
+            OperandSize::S64 => LoadOP::Fld,
+            s => panic!("Invalid load size {s:?}"),
+        };
+        self.load(src, rd, op, flags);
+    }
+
+    fn load(&mut self, src: Address, rd: WritableReg, op: LoadOP, flags: MemFlags) {
+        self.emit(Inst::Load {
+            rd: rd.map(Into::into),
+            op,
+            flags,
+            from: src.into(),
+        });
+    }
+
+    /// Compute the effective address of `src` into `rd`.
+    pub fn load_addr(&mut self, src: Address, rd: WritableReg) {
+        self.emit(Inst::LoadAddr {
+            rd: rd.map(Into::into),
+            mem: src.into(),
+        });
+    }
+
+    /// Store a register of the given size.
+    pub fn store(&mut self, src: Reg, dst: Address, size: OperandSize, flags: MemFlags) {
+        let op = match (src.is_int(), size) {
+            (true, OperandSize::S8) => StoreOP::Sb,
+            (true, OperandSize::S16) => StoreOP::Sh,
+            (true, OperandSize::S32) => StoreOP::Sw,
+            (true, OperandSize::S64) => StoreOP::Sd,
+            (false, OperandSize::S32) => StoreOP::Fsw,
+            (false, OperandSize::S64) => StoreOP::Fsd,
+            (_, s) => panic!("Invalid store size {s:?}"),
+        };
+        self.emit(Inst::Store {
+            to: dst.into(),
+            op,
+            flags,
+            src: src.into(),
+        });
+    }
+
+    /// Store a pair of registers at the given offsets from the stack pointer.
+    pub fn store_pair_to_sp(&mut self, first: (Reg, i64), second: (Reg, i64)) {
+        let sp = regs::sp();
+        for (reg, offset) in [first, second] {
+            self.store(
+                reg,
+                Address::offset(sp, offset),
+                OperandSize::S64,
+                TRUSTED_FLAGS,
+            );
+        }
+    }
+
+    /// Emit an unwind instruction.
+    pub fn unwind_inst(&mut self, inst: UnwindInst) {
+        self.emit(Inst::Unwind { inst })
+    }
+
+    /// Load a pair of registers from the given offsets from the stack pointer.
+    pub fn load_pair_from_sp(&mut self, first: (Reg, i64), second: (Reg, i64)) {
+        let sp = regs::sp();
+        for (reg, offset) in [first, second] {
+            self.uload(
+                Address::offset(sp, offset),
+                writable!(reg),
+                OperandSize::S64,
+                TRUSTED_FLAGS,
+            );
+        }
+    }
+
+    /// Emit an ALU instruction with two register operands.
+    pub fn alu_rrr(&mut self, op: AluOPRRR, rs1: Reg, rs2: Reg, rd: WritableReg) {
+        self.emit(Inst::AluRRR {
+            alu_op: op,
+            rd: rd.map(Into::into),
+            rs1: rs1.into(),
+            rs2: rs2.into(),
+        });
+    }
+
+    /// Emit an ALU instruction with a register and an immediate operand.
+    pub fn alu_rri(&mut self, op: AluOPRRI, rs: Reg, imm12: Imm12, rd: WritableReg) {
+        self.emit(Inst::AluRRImm12 {
+            alu_op: op,
+            rd: rd.map(Into::into),
+            rs: rs.into(),
+            imm12,
+        });
+    }
+
+    /// Emit an ALU instruction with a single register operand, e.g. `clz`,
+    /// for which the immediate field is part of the encoding.
+    pub fn alu_rr(&mut self, op: AluOPRRI, rs: Reg, rd: WritableReg) {
+        self.alu_rri(op, rs, Imm12::from_i16(0), rd);
+    }
+
+    /// Sign extend the lower 32 bits of `rs` into `rd`.
+    pub fn sext_w(&mut self, rs: Reg, rd: WritableReg) {
+        self.alu_rri(AluOPRRI::Addiw, rs, Imm12::from_i16(0), rd);
+    }
+
+    /// Sign or zero extend the lower `from_bits` of `rn` to 64 bits.
+    pub fn extend(&mut self, rn: Reg, rd: WritableReg, signed: bool, from_bits: u8) {
+        self.emit(Inst::Extend {
+            rd: rd.map(Into::into),
+            rn: rn.into(),
+            signed,
+            from_bits,
+            to_bits: 64,
+        });
+    }
+
+    /// Emit a floating point instruction with a single register operand.
+    pub fn fpu_rr(&mut self, op: FpuOPRR, size: OperandSize, frm: FRM, rs: Reg, rd: WritableReg) {
+        self.emit(Inst::FpuRR {
+            alu_op: op,
+            width: size.into(),
+            frm,
+            rd: rd.map(Into::into),
+            rs: rs.into(),
+        });
+    }
+
+    /// Emit a floating point instruction with two register operands.
+    pub fn fpu_rrr(
+        &mut self,
+        op: FpuOPRRR,
+        size: OperandSize,
+        rs1: Reg,
+        rs2: Reg,
+        rd: WritableReg,
+    ) {
+        // The rounding mode field doubles as the `funct3` field for
+        // instructions that don't round, which selects the operation.
+        let frm = match op {
+            FpuOPRRR::Fsgnjn | FpuOPRRR::Flt | FpuOPRRR::Fmax => FRM::RTZ,
+            FpuOPRRR::Fsgnjx | FpuOPRRR::Feq | FpuOPRRR::Fminm => FRM::RDN,
+            FpuOPRRR::Fmaxm => FRM::RUP,
+            _ => FRM::RNE,
+        };
+        self.emit(Inst::FpuRRR {
+            alu_op: op,
+            width: size.into(),
+            frm,
+            rd: rd.map(Into::into),
+            rs1: rs1.into(),
+            rs2: rs2.into(),
+        });
+    }
+
+    /// Set `rd` to 1 if the float comparison between `rs1` and `rs2` holds,
+    /// and to 0 otherwise.
+    pub fn fcmp(
+        &mut self,
+        kind: FloatCmpKind,
+        rs1: Reg,
+        rs2: Reg,
+        rd: WritableReg,
+        size: OperandSize,
+    ) {
+        let (op, rs1, rs2) = match kind {
+            FloatCmpKind::Eq | FloatCmpKind::Ne => (FpuOPRRR::Feq, rs1, rs2),
+            FloatCmpKind::Lt => (FpuOPRRR::Flt, rs1, rs2),
+            FloatCmpKind::Le => (FpuOPRRR::Fle, rs1, rs2),
+            FloatCmpKind::Gt => (FpuOPRRR::Flt, rs2, rs1),
+            FloatCmpKind::Ge => (FpuOPRRR::Fle, rs2, rs1),
+        };
+        self.fpu_rrr(op, size, rs1, rs2, rd);
+        if matches!(kind, FloatCmpKind::Ne) {
+            self.alu_rri(AluOPRRI::Xori, rd.to_reg(), Imm12::from_i16(1), rd);
+        }
+    }
+
+    /// Emit a floating point round instruction, available with the `Zfa`
+    /// extension.
+    pub fn fround(&mut self, rs: Reg, rd: WritableReg, mode: RoundingMode, size: OperandSize) {
+        self.fpu_rr(FpuOPRR::Fround, size, mode.into(), rs, rd);
+    }
+
+    /// Count the leading or trailing zeros of `rs`, for targets without the
+    /// `Zbb` extension.
+    pub fn cltz(
+        &mut self,
+        leading: bool,
+        rs: Reg,
+        sum: WritableReg,
+        step: WritableReg,
+        tmp: WritableReg,
+        size: OperandSize,
+    ) {
+        self.emit(Inst::Cltz {
+            leading,
+            sum: sum.map(Into::into),
+            step: step.map(Into::into),
+            tmp: tmp.map(Into::into),
+            rs: rs.into(),
+            ty: int_ty(size),
+        });
+    }
+
+    /// Count the number of set bits in `rs`, for targets without the `Zbb`
+    /// extension.
+    pub fn popcnt(
+        &mut self,
+        rs: Reg,
+        sum: WritableReg,
+        step: WritableReg,
+        tmp: WritableReg,
+        size: OperandSize,
+    ) {
+        self.emit(Inst::Popcnt {
+            sum: sum.map(Into::into),
+            step: step.map(Into::into),
+            tmp: tmp.map(Into::into),
+            rs: rs.into(),
+            ty: int_ty(size),
+        });
+    }
+
+    /// Return instruction.
+    pub fn ret(&mut self) {
+        self.emit(Inst::Ret);
+    }
+
+    /// An unconditional branch.
+    pub fn jmp(&mut self, target: MachLabel) {
+        self.emit(Inst::Jal { label: target });
+    }
+
+    /// A conditional branch, taken if the comparison between `rs1` and `rs2`
+    /// holds.
+    pub fn jmp_if(&mut self, kind: IntCC, rs1: Reg, rs2: Reg, taken: MachLabel) {
+        self.emit(Inst::CondBr {
+            taken: CondBrTarget::Label(taken),
+            not_taken: CondBrTarget::Fallthrough,
+            kind: IntegerCompare {
+                kind,
+                rs1: rs1.into(),
+                rs2: rs2.into(),
+            },
+        });
+    }
+
+    /// Emits a jump table sequence. The default target is expected to be the
+    /// first element of `targets`.
+    pub fn jmp_table(&mut self, targets: &[MachLabel], index: Reg, tmp1: Reg, tmp2: Reg) {
+        self.emit_with_island(
+            Inst::BrTable {
+                index: index.into(),
+                tmp1: Writable::from_reg(tmp1.into()),
+                tmp2: Writable::from_reg(tmp2.into()),
+                targets: targets.to_vec(),
+            },
+            // number of bytes needed for the jumptable sequence:
+            // 4 bytes per instruction, with 12 instructions base + two
+            // instructions for each entry in the jumptable.
+            (4 * (12 + 2 * targets.len())).try_into().unwrap(),
+        );
+    }
+
+    /// Permanently Undefined.
+    pub fn udf(&mut self, code: TrapCode) {
+        self.emit(Inst::Udf { trap_code: code });
+    }
+
+    /// Trap if the comparison between `rs1` and `rs2` holds.
+    pub fn trapif(&mut self, cc: IntCC, rs1: Reg, rs2: Reg, code: TrapCode) {
+        self.emit(Inst::TrapIf {
+            rs1: rs1.into(),
+            rs2: rs2.into(),
+            cc,
+            trap_code: code,
+        });
+    }
+
+    /// Trap if `rs` is zero.
+    pub fn trapz(&mut self, rs: Reg, code: TrapCode) {
+        self.trapif(IntCC::Equal, rs, regs::zero(), code);
+    }
+
+    /// Get a label from the underlying machine code buffer.
+    pub fn get_label(&mut self) -> MachLabel {
+        self.buffer.get_label()
+    }
+
+    /// Bind the label at the current offset.
+    pub fn bind(&mut self, label: MachLabel) {
+        self.buffer
+            .bind_label(label, self.emit_state.ctrl_plane_mut());
+    }
+
+    /// Get a mutable reference to underlying
+    /// machine buffer.
+    pub fn buffer_mut(&mut self) -> &mut MachBuffer<Inst> {
+        &mut self.buffer
+    }
+
+    /// Get a reference to the underlying machine buffer.
+    pub fn buffer(&self) -> &MachBuffer<Inst> {
+        &self.buffer
+    }
+
+    /// Emit a direct call to a function defined locally and
+    /// referenced to by `name`.
+    pub fn call_with_name(&mut self, name: UserExternalNameRef, call_conv: CallingConvention) {
+        self.emit(Inst::Call {
+            info: Box::new(cranelift_codegen::CallInfo::empty(
+                ExternalName::user(name),
+                call_conv.into(),
+            )),
+        })
+    }
+
+    /// Emit an indirect call to a function whose address is
+    /// stored the `callee` register.
+    pub fn call_with_reg(&mut self, callee: Reg, call_conv: CallingConvention) {
+        self.emit(Inst::CallInd {
+            info: Box::new(cranelift_codegen::CallInfo::empty(
+                callee.into(),
+                call_conv.into(),
+            )),
+        })
+    }
+
+    /// Emit a call to a well-known libcall.
+    /// `dst` is used as a scratch register to hold the address of the libcall function.
+    pub fn call_with_lib(&mut self, lib: LibCall, dst: Reg, call_conv: CallingConvention) {
+        let name = ExternalName::LibCall(lib);
+        self.emit(Inst::LoadExtName {
+            rd: writable!(dst.into()),
+            name: name.into(),
+            offset: 0,
+        });
+        self.call_with_reg(dst, call_conv)
+    }
+}
+
+/// Captures the region in a MachBuffer where a constant is loaded into a
+/// register and added to a destination register, so that the constant can be
+/// patched in once it's known. Used to add the maximum stack size of a function
+/// to the stack limit when checking for stack overflow in the prologue.
+///
+/// The patchable region contains a `lui` and an `addiw` loading the constant
+/// into `tmp`, which allows constants in the range of a signed 32-bit integer.
+/// The addition into the destination register follows the patchable region.
+pub(crate) struct PatchableAddToReg {
+    /// The region to be patched in the [`MachBuffer`].
+    region: PatchRegion,
+    /// The register holding the patched constant.
+    tmp: Reg,
+}
+
+impl PatchableAddToReg {
+    /// Create a new [`PatchableAddToReg`] adding a constant, initially `0`,
+    /// to `reg` by loading it into `tmp`.
+    pub(crate) fn new(reg: Reg, tmp: Reg, asm: &mut Assembler) -> Self {
+        // Make sure that no island gets emitted in the middle of the sequence.
+        if asm.buffer.island_needed(Inst::worst_case_size()) {
+            let label = asm.get_label();
+            asm.jmp(label);
+            asm.buffer
+                .emit_island(Inst::worst_case_size(), asm.emit_state.ctrl_plane_mut());
+            asm.bind(label);
+        }
+
+        let open = asm.buffer.start_patchable();
+        Self::put_load_bytes(tmp, 0, &mut asm.buffer);
+        let region = asm.buffer.end_patchable(open);
+
+        asm.alu_rrr(AluOPRRR::Add, reg, tmp, writable!(reg));
+
+        Self { region, tmp }
+    }
+
+    /// Emit the `lui` and `addiw` instructions loading `val` into `tmp`.
+    fn put_load_bytes(tmp: Reg, val: i32, buf: &mut MachBuffer<Inst>) {
+        for word in Self::load_words(tmp, val) {
+            buf.put4(word);
+        }
+    }
+
+    /// Encode the `lui` and `addiw` instructions loading `val` into `tmp`.
+    fn load_words(tmp: Reg, val: i32) -> [u32; 2] {
+        let rd = tmp.hw_enc() as u32;
+        // The low 12 bits are sign extended by `addiw`, so the upper 20 bits
+        // are rounded up when bit 11 is set.
+        let lo = ((val << 20) >> 20) as u32 & 0xfff;
+        let hi = (val.wrapping_sub((lo as i32) << 20 >> 20) as u32) >> 12;
+        let lui = 0b0110111 | (rd << 7) | (hi << 12);
+        let addiw = 0b0011011 | (rd << 7) | (rd << 15) | (lo << 20);
+        [lui, addiw]
+    }
+
+    /// Patch the [`MachBuffer`] with the known constant to be added to the
+    /// register.
+    pub(crate) fn finalize(self, val: i32, buffer: &mut MachBuffer<Inst>) {
+        let slice = self.region.patch(buffer);
+        debug_assert_eq!(slice.len(), 8);
+        for (chunk, word) in slice
+            .chunks_exact_mut(4)
+            .zip(Self::load_words(self.tmp, val))
+        {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+}
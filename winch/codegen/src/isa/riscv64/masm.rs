@@ -0,0 +1,1573 @@
+use super::{
+    abi::Riscv64ABI,
+    address::Address,
+    asm::{Assembler, PatchableAddToReg},
+    regs::{self, scratch},
+};
+use crate::{
+    abi::{self, align_to, calculate_frame_adjustment, local::LocalSlot, vmctx},
+    codegen::{ptr_type_from_ptr_size, CodeGenContext, CodeGenError, Emission, FuncEnv},
+    isa::{
+        reg::{writable, Reg, WritableReg},
+        CallingConvention,
+    },
+    masm::{
+        CalleeKind, DivKind, Extend, ExtendKind, ExtractLaneKind, FloatCmpKind, Imm as I,
+        IntCmpKind, LoadKind, MacroAssembler as Masm, MemOpKind, MulWideKind, OperandSize, RegImm,
+        RemKind, ReplaceLaneKind, RmwOp, RoundingMode, SPOffset, ShiftKind, SplatKind, StackSlot,
+        TrapCode, TruncKind, V128AddKind, V128MinMaxKind, V128MulKind, V128NegKind, V128SubKind,
+        Zero, TRUSTED_FLAGS, UNTRUSTED_FLAGS,
+    },
+    stack::TypedReg,
+};
+use anyhow::{anyhow, bail, Result};
+use cranelift_codegen::{
+    binemit::CodeOffset,
+    ir::{
+        condcodes::{CondCode, IntCC},
+        MemFlags, RelSourceLoc, SourceLoc,
+    },
+    isa::{
+        riscv64::{
+            f32_cvt_to_int_bounds, f64_cvt_to_int_bounds, settings as riscv_settings, AluOPRRI,
+            AluOPRRR, FpuOPRR, FpuOPRRR, Imm12, FRM,
+        },
+        unwind::UnwindInst,
+    },
+    settings, Final, MachBufferFinalized, MachLabel,
+};
+use regalloc2::RegClass;
+use wasmtime_environ::{PtrSize, WasmValType};
+
+/// Riscv64 MacroAssembler.
+///
+/// RISC-V doesn't have condition flags, so comparisons emitted through
+/// [`Masm::cmp`] copy their operands to [`regs::cmp_lhs`] and
+/// [`regs::cmp_rhs`], and the instructions consuming the comparison (e.g.
+/// [`Masm::trapif`]) compare those registers directly.
+///
+/// 32-bit integers are kept sign extended to 64 bits in registers, as
+/// required by the RISC-V psABI, which allows using 64-bit instructions on them
+/// when the upper bits don't affect the result.
+pub(crate) struct MacroAssembler {
+    /// Low level assembler.
+    asm: Assembler,
+    /// Stack pointer offset.
+    sp_offset: u32,
+    /// This value represents the maximum stack size seen while compiling the
+    /// function. While the function is still being compiled its value will
+    /// not be valid (the stack will grow and shrink as space is reserved and
+    /// freed during compilation), but once all instructions have been seen
+    /// this value will be the maximum stack usage seen.
+    sp_max: u32,
+    /// Add instructions that are used to add the constant stack max to a
+    /// register.
+    stack_max_use_add: Option<PatchableAddToReg>,
+    /// The target pointer size.
+    ptr_size: OperandSize,
+    /// Shared flags.
+    shared_flags: settings::Flags,
+    /// ISA specific flags.
+    isa_flags: riscv_settings::Flags,
+}
+
+impl MacroAssembler {
+    /// Create a Riscv64 MacroAssembler.
+    pub fn new(
+        ptr_size: impl PtrSize,
+        shared_flags: settings::Flags,
+        isa_flags: riscv_settings::Flags,
+    ) -> Result<Self> {
+        Ok(Self {
+            asm: Assembler::new(shared_flags.clone(), isa_flags.clone()),
+            sp_offset: 0u32,
+            sp_max: 0u32,
+            stack_max_use_add: None,
+            ptr_size: ptr_type_from_ptr_size(ptr_size.size()).try_into()?,
+            shared_flags,
+            isa_flags,
+        })
+    }
+}
+
+impl Masm for MacroAssembler {
+    type Address = Address;
+    type Ptr = u8;
+    type ABI = Riscv64ABI;
+
+    fn frame_setup(&mut self) -> Result<()> {
+        let ra = regs::ra();
+        let fp = regs::fp();
+        let sp = regs::sp();
+
+        self.adjust_sp(-16);
+        self.asm.store_pair_to_sp((ra, 8), (fp, 0));
+
+        if self.shared_flags.unwind_info() {
+            self.asm.unwind_inst(UnwindInst::PushFrameRegs {
+                offset_upward_to_caller_sp: <Self::ABI as abi::ABI>::arg_base_offset().into(),
+            })
+        }
+
+        self.asm.mov_rr(sp, writable!(fp));
+        Ok(())
+    }
+
+    fn check_stack(&mut self, vmctx: Reg) -> Result<()> {
+        let ptr_size: u8 = self.ptr_size.bytes().try_into().unwrap();
+        let scratch = regs::scratch();
+
+        self.load_ptr(
+            self.address_at_reg(vmctx, ptr_size.vmcontext_runtime_limits().into())?,
+            writable!(scratch),
+        )?;
+
+        self.load_ptr(
+            Address::offset(scratch, ptr_size.vmruntime_limits_stack_limit().into()),
+            writable!(scratch),
+        )?;
+
+        let patch = PatchableAddToReg::new(scratch, regs::scratch2(), &mut self.asm);
+        self.stack_max_use_add.replace(patch);
+
+        self.asm.trapif(
+            IntCC::UnsignedGreaterThan,
+            scratch,
+            regs::sp(),
+            TrapCode::STACK_OVERFLOW,
+        );
+
+        // Emit unwind info.
+        if self.shared_flags.unwind_info() {
+            self.asm.unwind_inst(UnwindInst::DefineNewFrame {
+                offset_upward_to_caller_sp: <Self::ABI as abi::ABI>::arg_base_offset().into(),
+
+                // The Winch calling convention has no callee-save registers, so nothing will be
+                // clobbered.
+                offset_downward_to_clobbers: 0,
+            })
+        }
+        Ok(())
+    }
+
+    fn frame_restore(&mut self) -> Result<()> {
+        debug_assert_eq!(self.sp_offset, 0);
+
+        let ra = regs::ra();
+        let fp = regs::fp();
+
+        self.asm.load_pair_from_sp((ra, 8), (fp, 0));
+        self.adjust_sp(16);
+        self.asm.ret();
+        Ok(())
+    }
+
+    fn reserve_stack(&mut self, bytes: u32) -> Result<()> {
+        if bytes == 0 {
+            return Ok(());
+        }
+
+        self.adjust_sp(-i64::from(bytes));
+        self.increment_sp(bytes);
+        Ok(())
+    }
+
+    fn free_stack(&mut self, bytes: u32) -> Result<()> {
+        if bytes == 0 {
+            return Ok(());
+        }
+
+        self.adjust_sp(i64::from(bytes));
+        self.decrement_sp(bytes);
+        Ok(())
+    }
+
+    fn reset_stack_pointer(&mut self, offset: SPOffset) -> Result<()> {
+        self.sp_offset = offset.as_u32();
+        Ok(())
+    }
+
+    fn local_address(&mut self, local: &LocalSlot) -> Result<Address> {
+        let (reg, offset) = local
+            .addressed_from_sp()
+            .then(|| {
+                let offset = self.sp_offset.checked_sub(local.offset).expect(&format!(
+                    "Invalid local offset = {}; sp offset = {}",
+                    local.offset, self.sp_offset
+                ));
+                (regs::sp(), offset)
+            })
+            .unwrap_or((regs::fp(), local.offset));
+
+        Ok(Address::offset(reg, offset as i64))
+    }
+
+    fn address_from_sp(&self, offset: SPOffset) -> Result<Self::Address> {
+        Ok(Address::offset(
+            regs::sp(),
+            (self.sp_offset - offset.as_u32()) as i64,
+        ))
+    }
+
+    fn address_at_sp(&self, offset: SPOffset) -> Result<Self::Address> {
+        Ok(Address::offset(regs::sp(), offset.as_u32() as i64))
+    }
+
+    fn address_at_vmctx(&self, offset: u32) -> Result<Self::Address> {
+        Ok(Address::offset(vmctx!(Self), offset as i64))
+    }
+
+    fn store_ptr(&mut self, src: Reg, dst: Self::Address) -> Result<()> {
+        self.store(src.into(), dst, self.ptr_size)
+    }
+
+    fn store(&mut self, src: RegImm, dst: Address, size: OperandSize) -> Result<()> {
+        let src = match src {
+            RegImm::Imm(v) => {
+                let scratch = regs::scratch2();
+                self.asm
+                    .load_constant(Self::imm_bits(&v)?, writable!(scratch));
+                if v.is_float() {
+                    let float_scratch = regs::float_scratch();
+                    self.asm
+                        .mov_to_fpu(scratch, writable!(float_scratch), v.size());
+                    float_scratch
+                } else {
+                    scratch
+                }
+            }
+            RegImm::Reg(reg) => reg,
+        };
+
+        self.asm.store(src, dst, size, TRUSTED_FLAGS);
+        Ok(())
+    }
+
+    fn wasm_store(
+        &mut self,
+        src: Reg,
+        dst: Self::Address,
+        size: OperandSize,
+        op_kind: MemOpKind,
+    ) -> Result<()> {
+        match op_kind {
+            MemOpKind::Atomic => Err(anyhow!(CodeGenError::unimplemented_masm_instruction())),
+            MemOpKind::Normal => {
+                self.asm.store(src, dst, size, UNTRUSTED_FLAGS);
+                Ok(())
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        stack_args_size: u32,
+        mut load_callee: impl FnMut(&mut Self) -> Result<(CalleeKind, CallingConvention)>,
+    ) -> Result<u32> {
+        let alignment: u32 = <Self::ABI as abi::ABI>::call_stack_align().into();
+        let addend: u32 = <Self::ABI as abi::ABI>::arg_base_offset().into();
+        let delta = calculate_frame_adjustment(self.sp_offset()?.as_u32(), addend, alignment);
+        let aligned_args_size = align_to(stack_args_size, alignment);
+        let total_stack = delta + aligned_args_size;
+        self.reserve_stack(total_stack)?;
+        let (callee, call_conv) = load_callee(self)?;
+        match callee {
+            CalleeKind::Indirect(reg) => self.asm.call_with_reg(reg, call_conv),
+            CalleeKind::Direct(idx) => self.asm.call_with_name(idx, call_conv),
+            CalleeKind::LibCall(lib) => self.asm.call_with_lib(lib, scratch(), call_conv),
+        }
+
+        Ok(total_stack)
+    }
+
+    fn load(&mut self, src: Address, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.load_impl(src, dst, size, TRUSTED_FLAGS);
+        Ok(())
+    }
+
+    fn load_ptr(&mut self, src: Self::Address, dst: WritableReg) -> Result<()> {
+        self.load(src, dst, self.ptr_size)
+    }
+
+    fn wasm_load(
+        &mut self,
+        src: Self::Address,
+        dst: WritableReg,
+        kind: LoadKind,
+        op_kind: MemOpKind,
+    ) -> Result<()> {
+        let size = kind.derive_operand_size();
+        match op_kind {
+            MemOpKind::Normal => match kind {
+                LoadKind::Operand(_) => self.load_impl(src, dst, size, UNTRUSTED_FLAGS),
+                LoadKind::Splat(_) => bail!(CodeGenError::UnimplementedWasmLoadKind),
+                LoadKind::ScalarExtend(extend_kind) => {
+                    if extend_kind.signed() {
+                        self.asm.sload(src, dst, size, UNTRUSTED_FLAGS)
+                    } else {
+                        self.asm.uload(src, dst, size, UNTRUSTED_FLAGS)
+                    }
+                }
+                LoadKind::VectorExtend(_vector_extend_kind) => {
+                    bail!(CodeGenError::UnimplementedWasmLoadKind)
+                }
+            },
+            MemOpKind::Atomic => bail!(CodeGenError::unimplemented_masm_instruction()),
+        }
+        Ok(())
+    }
+
+    fn load_addr(
+        &mut self,
+        src: Self::Address,
+        dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        self.asm.load_addr(src, dst);
+        Ok(())
+    }
+
+    fn pop(&mut self, dst: WritableReg, size: OperandSize) -> Result<()> {
+        let addr = self.address_from_sp(SPOffset::from_u32(self.sp_offset))?;
+        self.load_impl(addr, dst, size, TRUSTED_FLAGS);
+        self.free_stack(size.bytes())
+    }
+
+    fn sp_offset(&self) -> Result<SPOffset> {
+        Ok(SPOffset::from_u32(self.sp_offset))
+    }
+
+    fn finalize(mut self, base: Option<SourceLoc>) -> Result<MachBufferFinalized<Final>> {
+        if let Some(patch) = self.stack_max_use_add {
+            patch.finalize(i32::try_from(self.sp_max).unwrap(), self.asm.buffer_mut());
+        }
+
+        Ok(self.asm.finalize(base))
+    }
+
+    fn mov(&mut self, dst: WritableReg, src: RegImm, size: OperandSize) -> Result<()> {
+        match (src, dst) {
+            (RegImm::Imm(v), rd) => {
+                let imm = Self::imm_bits(&v)?;
+                match rd.to_reg().class() {
+                    RegClass::Int => Ok(self.asm.load_constant(imm, rd)),
+                    RegClass::Float => {
+                        let scratch = regs::scratch2();
+                        self.asm.load_constant(imm, writable!(scratch));
+                        Ok(self.asm.mov_to_fpu(scratch, rd, size))
+                    }
+                    _ => bail!(CodeGenError::invalid_operand_combination()),
+                }
+            }
+            (RegImm::Reg(rs), rd) => match (rs.class(), rd.to_reg().class()) {
+                (RegClass::Int, RegClass::Int) => Ok(self.asm.mov_rr(rs, rd)),
+                (RegClass::Float, RegClass::Float) => Ok(self.asm.fmov_rr(rs, rd, size)),
+                (RegClass::Int, RegClass::Float) => Ok(self.asm.mov_to_fpu(rs, rd, size)),
+                _ => bail!(CodeGenError::invalid_operand_combination()),
+            },
+        }
+    }
+
+    fn cmov(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        cc: IntCmpKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        let skip = self.asm.get_label();
+        self.asm.jmp_if(
+            IntCC::from(cc).complement(),
+            regs::cmp_lhs(),
+            regs::cmp_rhs(),
+            skip,
+        );
+        self.mov(dst, src.into(), size)?;
+        self.asm.bind(skip);
+        Ok(())
+    }
+
+    fn add(&mut self, dst: WritableReg, lhs: Reg, rhs: RegImm, size: OperandSize) -> Result<()> {
+        let (rrr, rri) = match size {
+            OperandSize::S32 => (AluOPRRR::Addw, AluOPRRI::Addiw),
+            OperandSize::S64 => (AluOPRRR::Add, AluOPRRI::Addi),
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.alu_op(dst, lhs, rhs, size, rrr, Some(rri))
+    }
+
+    fn checked_uadd(
+        &mut self,
+        dst: WritableReg,
+        lhs: Reg,
+        rhs: RegImm,
+        size: OperandSize,
+        trap: TrapCode,
+    ) -> Result<()> {
+        let sum = regs::cmp_lhs();
+        let tmp = regs::cmp_rhs();
+        let rhs = self.reg_or_load_imm(rhs, size)?;
+        match size {
+            OperandSize::S32 => {
+                // Perform the addition on the zero extended operands and
+                // check the upper 32 bits of the result for a carry.
+                self.asm.extend(lhs, writable!(sum), false, 32);
+                self.asm.extend(rhs, writable!(tmp), false, 32);
+                self.asm.alu_rrr(AluOPRRR::Add, sum, tmp, writable!(sum));
+                self.asm
+                    .alu_rri(AluOPRRI::Srli, sum, Imm12::from_i16(32), writable!(tmp));
+                self.asm.trapif(IntCC::NotEqual, tmp, regs::zero(), trap);
+                self.asm.sext_w(sum, dst);
+            }
+            OperandSize::S64 => {
+                // The addition overflowed if the result is smaller than
+                // one of the operands.
+                self.asm.alu_rrr(AluOPRRR::Add, lhs, rhs, writable!(sum));
+                self.asm.trapif(IntCC::UnsignedLessThan, sum, lhs, trap);
+                self.asm.mov_rr(sum, dst);
+            }
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        }
+        Ok(())
+    }
+
+    fn sub(&mut self, dst: WritableReg, lhs: Reg, rhs: RegImm, size: OperandSize) -> Result<()> {
+        let (rrr, rri) = match size {
+            OperandSize::S32 => (AluOPRRR::Subw, AluOPRRI::Addiw),
+            OperandSize::S64 => (AluOPRRR::Sub, AluOPRRI::Addi),
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        match rhs {
+            // There's no subtract immediate instruction, add the negated
+            // immediate instead, if it fits.
+            RegImm::Imm(v) => match Self::imm_to_i64(&v, size)?
+                .checked_neg()
+                .and_then(Imm12::maybe_from_i64)
+            {
+                Some(imm) => {
+                    self.asm.alu_rri(rri, lhs, imm, dst);
+                    Ok(())
+                }
+                None => self.alu_op(dst, lhs, rhs, size, rrr, None),
+            },
+            RegImm::Reg(_) => self.alu_op(dst, lhs, rhs, size, rrr, None),
+        }
+    }
+
+    fn mul(&mut self, dst: WritableReg, lhs: Reg, rhs: RegImm, size: OperandSize) -> Result<()> {
+        let rrr = match size {
+            OperandSize::S32 => AluOPRRR::Mulw,
+            OperandSize::S64 => AluOPRRR::Mul,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.alu_op(dst, lhs, rhs, size, rrr, None)
+    }
+
+    fn float_add(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg, size: OperandSize) -> Result<()> {
+        self.asm.fpu_rrr(FpuOPRRR::Fadd, size, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn float_sub(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg, size: OperandSize) -> Result<()> {
+        self.asm.fpu_rrr(FpuOPRRR::Fsub, size, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn float_mul(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg, size: OperandSize) -> Result<()> {
+        self.asm.fpu_rrr(FpuOPRRR::Fmul, size, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn float_div(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg, size: OperandSize) -> Result<()> {
+        self.asm.fpu_rrr(FpuOPRRR::Fdiv, size, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn float_min(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg, size: OperandSize) -> Result<()> {
+        if self.isa_flags.has_zfa() {
+            self.asm.fpu_rrr(FpuOPRRR::Fminm, size, lhs, rhs, dst);
+        } else {
+            self.float_min_max(FpuOPRRR::Fmin, dst, lhs, rhs, size);
+        }
+        Ok(())
+    }
+
+    fn float_max(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg, size: OperandSize) -> Result<()> {
+        if self.isa_flags.has_zfa() {
+            self.asm.fpu_rrr(FpuOPRRR::Fmaxm, size, lhs, rhs, dst);
+        } else {
+            self.float_min_max(FpuOPRRR::Fmax, dst, lhs, rhs, size);
+        }
+        Ok(())
+    }
+
+    fn float_copysign(
+        &mut self,
+        dst: WritableReg,
+        lhs: Reg,
+        rhs: Reg,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.asm.fpu_rrr(FpuOPRRR::Fsgnj, size, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn float_neg(&mut self, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.asm
+            .fpu_rrr(FpuOPRRR::Fsgnjn, size, dst.to_reg(), dst.to_reg(), dst);
+        Ok(())
+    }
+
+    fn float_abs(&mut self, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.asm
+            .fpu_rrr(FpuOPRRR::Fsgnjx, size, dst.to_reg(), dst.to_reg(), dst);
+        Ok(())
+    }
+
+    fn float_round<
+        F: FnMut(&mut FuncEnv<Self::Ptr>, &mut CodeGenContext<Emission>, &mut Self) -> Result<()>,
+    >(
+        &mut self,
+        mode: RoundingMode,
+        env: &mut FuncEnv<Self::Ptr>,
+        context: &mut CodeGenContext<Emission>,
+        size: OperandSize,
+        mut fallback: F,
+    ) -> Result<()> {
+        if self.isa_flags.has_zfa() {
+            let src = context.pop_to_reg(self, None)?;
+            self.asm
+                .fround(src.into(), writable!(src.into()), mode, size);
+            context.stack.push(src.into());
+            Ok(())
+        } else {
+            fallback(env, context, self)
+        }
+    }
+
+    fn float_sqrt(&mut self, dst: WritableReg, src: Reg, size: OperandSize) -> Result<()> {
+        self.asm.fpu_rr(FpuOPRR::Fsqrt, size, FRM::RNE, src, dst);
+        Ok(())
+    }
+
+    fn and(&mut self, dst: WritableReg, lhs: Reg, rhs: RegImm, size: OperandSize) -> Result<()> {
+        self.alu_op(dst, lhs, rhs, size, AluOPRRR::And, Some(AluOPRRI::Andi))
+    }
+
+    fn or(&mut self, dst: WritableReg, lhs: Reg, rhs: RegImm, size: OperandSize) -> Result<()> {
+        self.alu_op(dst, lhs, rhs, size, AluOPRRR::Or, Some(AluOPRRI::Ori))
+    }
+
+    fn xor(&mut self, dst: WritableReg, lhs: Reg, rhs: RegImm, size: OperandSize) -> Result<()> {
+        self.alu_op(dst, lhs, rhs, size, AluOPRRR::Xor, Some(AluOPRRI::Xori))
+    }
+
+    fn shift_ir(
+        &mut self,
+        dst: WritableReg,
+        imm: u64,
+        lhs: Reg,
+        kind: ShiftKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        let bits = u64::from(size.num_bits());
+        let amount = imm & (bits - 1);
+        let is_32 = match size {
+            OperandSize::S32 => true,
+            OperandSize::S64 => false,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        let shift_op = |kind| match (kind, is_32) {
+            (ShiftKind::Shl, true) => AluOPRRI::Slliw,
+            (ShiftKind::Shl, false) => AluOPRRI::Slli,
+            (ShiftKind::ShrS, true) => AluOPRRI::Sraiw,
+            (ShiftKind::ShrS, false) => AluOPRRI::Srai,
+            (ShiftKind::ShrU, true) => AluOPRRI::SrliW,
+            (ShiftKind::ShrU, false) => AluOPRRI::Srli,
+            (ShiftKind::Rotr, true) => AluOPRRI::Roriw,
+            (ShiftKind::Rotr, false) => AluOPRRI::Rori,
+            (ShiftKind::Rotl, _) => unreachable!(),
+        };
+        let imm = |amount: u64| Imm12::from_i16(amount as i16);
+
+        match kind {
+            ShiftKind::Shl | ShiftKind::ShrS | ShiftKind::ShrU => {
+                self.asm.alu_rri(shift_op(kind), lhs, imm(amount), dst);
+            }
+            ShiftKind::Rotl | ShiftKind::Rotr => {
+                // Left rotations are expressed as right rotations by the
+                // complementary amount.
+                let right_amount = if kind == ShiftKind::Rotl {
+                    (bits - amount) & (bits - 1)
+                } else {
+                    amount
+                };
+                if self.isa_flags.has_zbb() {
+                    self.asm
+                        .alu_rri(shift_op(ShiftKind::Rotr), lhs, imm(right_amount), dst);
+                } else {
+                    let left_amount = (bits - right_amount) & (bits - 1);
+                    let (lo, hi) = (regs::scratch(), regs::scratch2());
+                    self.asm.alu_rri(
+                        shift_op(ShiftKind::ShrU),
+                        lhs,
+                        imm(right_amount),
+                        writable!(lo),
+                    );
+                    self.asm.alu_rri(
+                        shift_op(ShiftKind::Shl),
+                        lhs,
+                        imm(left_amount),
+                        writable!(hi),
+                    );
+                    self.asm.alu_rrr(AluOPRRR::Or, lo, hi, dst);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn shift(
+        &mut self,
+        context: &mut CodeGenContext<Emission>,
+        kind: ShiftKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        let src = context.pop_to_reg(self, None)?;
+        let dst = context.pop_to_reg(self, None)?;
+
+        let is_32 = match size {
+            OperandSize::S32 => true,
+            OperandSize::S64 => false,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        let shift_op = |kind| match (kind, is_32) {
+            (ShiftKind::Shl, true) => AluOPRRR::Sllw,
+            (ShiftKind::Shl, false) => AluOPRRR::Sll,
+            (ShiftKind::ShrS, true) => AluOPRRR::Sraw,
+            (ShiftKind::ShrS, false) => AluOPRRR::Sra,
+            (ShiftKind::ShrU, true) => AluOPRRR::Srlw,
+            (ShiftKind::ShrU, false) => AluOPRRR::Srl,
+            (ShiftKind::Rotl, true) => AluOPRRR::Rolw,
+            (ShiftKind::Rotl, false) => AluOPRRR::Rol,
+            (ShiftKind::Rotr, true) => AluOPRRR::Rorw,
+            (ShiftKind::Rotr, false) => AluOPRRR::Ror,
+        };
+
+        let (amount, value) = (src.into(), dst.into());
+        match kind {
+            ShiftKind::Rotl | ShiftKind::Rotr if !self.isa_flags.has_zbb() => {
+                // Shift in both directions, using the negated amount for the
+                // complementary shift, given that only the low bits of the
+                // amount are used by the shift instructions.
+                let (first, second) = if kind == ShiftKind::Rotl {
+                    (ShiftKind::Shl, ShiftKind::ShrU)
+                } else {
+                    (ShiftKind::ShrU, ShiftKind::Shl)
+                };
+                let (lo, hi) = (regs::scratch(), regs::scratch2());
+                self.asm
+                    .alu_rrr(shift_op(first), value, amount, writable!(lo));
+                self.asm
+                    .alu_rrr(AluOPRRR::Sub, regs::zero(), amount, writable!(hi));
+                self.asm.alu_rrr(shift_op(second), value, hi, writable!(hi));
+                self.asm.alu_rrr(AluOPRRR::Or, lo, hi, writable!(value));
+            }
+            _ => self
+                .asm
+                .alu_rrr(shift_op(kind), value, amount, writable!(value)),
+        }
+
+        context.free_reg(src);
+        context.stack.push(dst.into());
+
+        Ok(())
+    }
+
+    fn div(
+        &mut self,
+        context: &mut CodeGenContext<Emission>,
+        kind: DivKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        let signed = kind == DivKind::Signed;
+        context.binop(self, size, |this, dividend, divisor, size| {
+            this.asm.trapz(divisor, TrapCode::INTEGER_DIVISION_BY_ZERO);
+            let op = match (signed, size) {
+                (true, OperandSize::S32) => AluOPRRR::Divw,
+                (true, OperandSize::S64) => AluOPRRR::Div,
+                (false, OperandSize::S32) => AluOPRRR::Divuw,
+                (false, OperandSize::S64) => AluOPRRR::DivU,
+                _ => bail!(CodeGenError::unexpected_operand_size()),
+            };
+            if signed {
+                this.trap_on_signed_div_overflow(dividend, divisor, size);
+            }
+            this.asm.alu_rrr(op, dividend, divisor, writable!(dividend));
+            match size {
+                OperandSize::S32 => Ok(TypedReg::new(WasmValType::I32, dividend)),
+                OperandSize::S64 => Ok(TypedReg::new(WasmValType::I64, dividend)),
+                _ => Err(anyhow!(CodeGenError::unexpected_operand_size())),
+            }
+        })
+    }
+
+    fn rem(
+        &mut self,
+        context: &mut CodeGenContext<Emission>,
+        kind: RemKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        context.binop(self, size, |this, dividend, divisor, size| {
+            this.asm.trapz(divisor, TrapCode::INTEGER_DIVISION_BY_ZERO);
+            // The remainder of the signed division overflow case is defined
+            // to be zero, which matches WebAssembly's semantics.
+            let op = match (kind, size) {
+                (RemKind::Signed, OperandSize::S32) => AluOPRRR::Remw,
+                (RemKind::Signed, OperandSize::S64) => AluOPRRR::Rem,
+                (RemKind::Unsigned, OperandSize::S32) => AluOPRRR::Remuw,
+                (RemKind::Unsigned, OperandSize::S64) => AluOPRRR::RemU,
+                _ => bail!(CodeGenError::unexpected_operand_size()),
+            };
+            this.asm.alu_rrr(op, dividend, divisor, writable!(dividend));
+            match size {
+                OperandSize::S32 => Ok(TypedReg::new(WasmValType::I32, dividend)),
+                OperandSize::S64 => Ok(TypedReg::new(WasmValType::I64, dividend)),
+                _ => Err(anyhow!(CodeGenError::unexpected_operand_size())),
+            }
+        })
+    }
+
+    fn zero(&mut self, reg: WritableReg) -> Result<()> {
+        self.asm.mov_rr(regs::zero(), reg);
+        Ok(())
+    }
+
+    fn popcnt(&mut self, context: &mut CodeGenContext<Emission>, size: OperandSize) -> Result<()> {
+        let src = context.pop_to_reg(self, None)?;
+        if self.isa_flags.has_zbb() {
+            let op = match size {
+                OperandSize::S32 => AluOPRRI::Cpopw,
+                OperandSize::S64 => AluOPRRI::Cpop,
+                _ => bail!(CodeGenError::unexpected_operand_size()),
+            };
+            self.asm.alu_rr(op, src.into(), writable!(src.into()));
+        } else {
+            let sum = regs::scratch();
+            self.asm.popcnt(
+                src.into(),
+                writable!(sum),
+                writable!(regs::scratch2()),
+                writable!(regs::cmp_lhs()),
+                size,
+            );
+            self.asm.mov_rr(sum, writable!(src.into()));
+        }
+        context.stack.push(src.into());
+        Ok(())
+    }
+
+    fn signed_truncate(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        src_size: OperandSize,
+        dst_size: OperandSize,
+        kind: TruncKind,
+    ) -> Result<()> {
+        self.float_to_int(dst, src, src_size, dst_size, kind, true)
+    }
+
+    fn unsigned_truncate(
+        &mut self,
+        ctx: &mut CodeGenContext<Emission>,
+        src_size: OperandSize,
+        dst_size: OperandSize,
+        kind: TruncKind,
+    ) -> Result<()> {
+        let dst_ty = match dst_size {
+            OperandSize::S32 => WasmValType::I32,
+            OperandSize::S64 => WasmValType::I64,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+
+        ctx.convert_op(self, dst_ty, |masm, dst, src, dst_size| {
+            masm.float_to_int(writable!(dst), src, src_size, dst_size, kind, false)
+        })
+    }
+
+    fn signed_convert(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        src_size: OperandSize,
+        dst_size: OperandSize,
+    ) -> Result<()> {
+        let op = match src_size {
+            OperandSize::S32 => FpuOPRR::FcvtFmtW,
+            OperandSize::S64 => FpuOPRR::FcvtFmtL,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.asm.fpu_rr(op, dst_size, FRM::RNE, src, dst);
+        Ok(())
+    }
+
+    fn unsigned_convert(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        _tmp_gpr: Reg,
+        src_size: OperandSize,
+        dst_size: OperandSize,
+    ) -> Result<()> {
+        let op = match src_size {
+            OperandSize::S32 => FpuOPRR::FcvtFmtWu,
+            OperandSize::S64 => FpuOPRR::FcvtFmtLu,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.asm.fpu_rr(op, dst_size, FRM::RNE, src, dst);
+        Ok(())
+    }
+
+    fn reinterpret_float_as_int(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.asm.mov_from_fpu(src, dst, size);
+        Ok(())
+    }
+
+    fn reinterpret_int_as_float(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.asm.mov_to_fpu(src, dst, size);
+        Ok(())
+    }
+
+    fn demote(&mut self, dst: WritableReg, src: Reg) -> Result<()> {
+        self.asm
+            .fpu_rr(FpuOPRR::FcvtSD, OperandSize::S32, FRM::RNE, src, dst);
+        Ok(())
+    }
+
+    fn promote(&mut self, dst: WritableReg, src: Reg) -> Result<()> {
+        self.asm
+            .fpu_rr(FpuOPRR::FcvtDS, OperandSize::S64, FRM::RNE, src, dst);
+        Ok(())
+    }
+
+    fn push(&mut self, reg: Reg, size: OperandSize) -> Result<StackSlot> {
+        self.reserve_stack(size.bytes())?;
+        let address = self.address_from_sp(SPOffset::from_u32(self.sp_offset))?;
+        self.asm.store(reg, address, size, TRUSTED_FLAGS);
+
+        Ok(StackSlot {
+            offset: SPOffset::from_u32(self.sp_offset),
+            size: size.bytes(),
+        })
+    }
+
+    fn address_at_reg(&self, reg: Reg, offset: u32) -> Result<Self::Address> {
+        Ok(Address::offset(reg, offset as i64))
+    }
+
+    fn cmp_with_set(
+        &mut self,
+        dst: WritableReg,
+        src: RegImm,
+        kind: IntCmpKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.cmp(dst.to_reg(), src, size)?;
+
+        let (lhs, rhs) = (regs::cmp_lhs(), regs::cmp_rhs());
+        // Each condition is computed from `slt[u]` or from the
+        // difference of the operands, negating the result if needed.
+        let (op, rs1, rs2, negate) = match kind {
+            IntCmpKind::Eq => (AluOPRRR::Xor, lhs, rhs, false),
+            IntCmpKind::Ne => (AluOPRRR::Xor, lhs, rhs, false),
+            IntCmpKind::LtS => (AluOPRRR::Slt, lhs, rhs, false),
+            IntCmpKind::LtU => (AluOPRRR::SltU, lhs, rhs, false),
+            IntCmpKind::GtS => (AluOPRRR::Slt, rhs, lhs, false),
+            IntCmpKind::GtU => (AluOPRRR::SltU, rhs, lhs, false),
+            IntCmpKind::LeS => (AluOPRRR::Slt, rhs, lhs, true),
+            IntCmpKind::LeU => (AluOPRRR::SltU, rhs, lhs, true),
+            IntCmpKind::GeS => (AluOPRRR::Slt, lhs, rhs, true),
+            IntCmpKind::GeU => (AluOPRRR::SltU, lhs, rhs, true),
+        };
+        self.asm.alu_rrr(op, rs1, rs2, dst);
+        match kind {
+            // seqz
+            IntCmpKind::Eq => {
+                self.asm
+                    .alu_rri(AluOPRRI::SltiU, dst.to_reg(), Imm12::from_i16(1), dst)
+            }
+            // snez
+            IntCmpKind::Ne => self
+                .asm
+                .alu_rrr(AluOPRRR::SltU, regs::zero(), dst.to_reg(), dst),
+            _ if negate => self
+                .asm
+                .alu_rri(AluOPRRI::Xori, dst.to_reg(), Imm12::from_i16(1), dst),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn cmp(&mut self, src1: Reg, src2: RegImm, size: OperandSize) -> Result<()> {
+        let (lhs, rhs) = (regs::cmp_lhs(), regs::cmp_rhs());
+        match size {
+            OperandSize::S32 => self.asm.sext_w(src1, writable!(lhs)),
+            OperandSize::S64 => self.asm.mov_rr(src1, writable!(lhs)),
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        }
+        match src2 {
+            RegImm::Reg(src2) => match size {
+                OperandSize::S32 => self.asm.sext_w(src2, writable!(rhs)),
+                _ => self.asm.mov_rr(src2, writable!(rhs)),
+            },
+            RegImm::Imm(v) => {
+                let imm = Self::imm_to_i64(&v, size)?;
+                self.asm.load_constant(imm as u64, writable!(rhs));
+            }
+        }
+        Ok(())
+    }
+
+    fn float_cmp_with_set(
+        &mut self,
+        dst: WritableReg,
+        src1: Reg,
+        src2: Reg,
+        kind: FloatCmpKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.asm.fcmp(kind, src1, src2, dst, size);
+        Ok(())
+    }
+
+    fn clz(&mut self, dst: WritableReg, src: Reg, size: OperandSize) -> Result<()> {
+        self.count_zeros(true, dst, src, size)
+    }
+
+    fn ctz(&mut self, dst: WritableReg, src: Reg, size: OperandSize) -> Result<()> {
+        self.count_zeros(false, dst, src, size)
+    }
+
+    fn wrap(&mut self, dst: WritableReg, src: Reg) -> Result<()> {
+        self.asm.sext_w(src, dst);
+        Ok(())
+    }
+
+    fn extend(&mut self, dst: WritableReg, src: Reg, kind: ExtendKind) -> Result<()> {
+        self.asm.extend(src, dst, kind.signed(), kind.from_bits());
+        Ok(())
+    }
+
+    fn get_label(&mut self) -> Result<MachLabel> {
+        Ok(self.asm.get_label())
+    }
+
+    fn bind(&mut self, label: MachLabel) -> Result<()> {
+        self.asm.bind(label);
+        Ok(())
+    }
+
+    fn branch(
+        &mut self,
+        kind: IntCmpKind,
+        lhs: Reg,
+        rhs: RegImm,
+        taken: MachLabel,
+        size: OperandSize,
+    ) -> Result<()> {
+        use IntCmpKind::*;
+
+        match &(lhs, rhs) {
+            // If the comparison kind is zero or not zero and both operands
+            // are the same register, compare the register against zero.
+            (rlhs, RegImm::Reg(rrhs)) if (kind == Eq || kind == Ne) && (rlhs == rrhs) => {
+                let lhs = regs::cmp_lhs();
+                match size {
+                    OperandSize::S32 => self.asm.sext_w(*rlhs, writable!(lhs)),
+                    _ => self.asm.mov_rr(*rlhs, writable!(lhs)),
+                }
+                self.asm.jmp_if(kind.into(), lhs, regs::zero(), taken);
+            }
+            _ => {
+                self.cmp(lhs, rhs, size)?;
+                self.asm
+                    .jmp_if(kind.into(), regs::cmp_lhs(), regs::cmp_rhs(), taken);
+            }
+        }
+        Ok(())
+    }
+
+    fn jmp(&mut self, target: MachLabel) -> Result<()> {
+        self.asm.jmp(target);
+        Ok(())
+    }
+
+    fn unreachable(&mut self) -> Result<()> {
+        self.asm.udf(wasmtime_cranelift::TRAP_UNREACHABLE);
+        Ok(())
+    }
+
+    fn jmp_table(&mut self, targets: &[MachLabel], index: Reg, tmp: Reg) -> Result<()> {
+        // At least one default target.
+        debug_assert!(targets.len() >= 1);
+        // The jump table sequence performs its own bounds check, and expects
+        // the default target as the first element.
+        let default_index = targets.len() - 1;
+        let targets: Vec<_> = std::iter::once(targets[default_index])
+            .chain(targets[..default_index].iter().copied())
+            .collect();
+        self.asm.jmp_table(&targets, index, scratch(), tmp);
+        Ok(())
+    }
+
+    fn trap(&mut self, code: TrapCode) -> Result<()> {
+        self.asm.udf(code);
+        Ok(())
+    }
+
+    fn trapz(&mut self, src: Reg, code: TrapCode) -> Result<()> {
+        self.asm.trapz(src, code);
+        Ok(())
+    }
+
+    fn trapif(&mut self, cc: IntCmpKind, code: TrapCode) -> Result<()> {
+        self.asm
+            .trapif(cc.into(), regs::cmp_lhs(), regs::cmp_rhs(), code);
+        Ok(())
+    }
+
+    fn start_source_loc(&mut self, loc: RelSourceLoc) -> Result<(CodeOffset, RelSourceLoc)> {
+        Ok(self.asm.buffer_mut().start_srcloc(loc))
+    }
+
+    fn end_source_loc(&mut self) -> Result<()> {
+        self.asm.buffer_mut().end_srcloc();
+        Ok(())
+    }
+
+    fn current_code_offset(&self) -> Result<CodeOffset> {
+        Ok(self.asm.buffer().cur_offset())
+    }
+
+    fn add128(
+        &mut self,
+        dst_lo: WritableReg,
+        dst_hi: WritableReg,
+        lhs_lo: Reg,
+        lhs_hi: Reg,
+        rhs_lo: Reg,
+        rhs_hi: Reg,
+    ) -> Result<()> {
+        let _ = (dst_lo, dst_hi, lhs_lo, lhs_hi, rhs_lo, rhs_hi);
+        Err(anyhow!(CodeGenError::unimplemented_masm_instruction()))
+    }
+
+    fn sub128(
+        &mut self,
+        dst_lo: WritableReg,
+        dst_hi: WritableReg,
+        lhs_lo: Reg,
+        lhs_hi: Reg,
+        rhs_lo: Reg,
+        rhs_hi: Reg,
+    ) -> Result<()> {
+        let _ = (dst_lo, dst_hi, lhs_lo, lhs_hi, rhs_lo, rhs_hi);
+        Err(anyhow!(CodeGenError::unimplemented_masm_instruction()))
+    }
+
+    fn mul_wide(
+        &mut self,
+        context: &mut CodeGenContext<Emission>,
+        kind: MulWideKind,
+    ) -> Result<()> {
+        let _ = (context, kind);
+        Err(anyhow!(CodeGenError::unimplemented_masm_instruction()))
+    }
+
+    fn splat(&mut self, _context: &mut CodeGenContext<Emission>, _size: SplatKind) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn shuffle(&mut self, _dst: WritableReg, _lhs: Reg, _rhs: Reg, _lanes: [u8; 16]) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn swizzle(&mut self, _dst: WritableReg, _lhs: Reg, _rhs: Reg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_add(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: V128AddKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_sub(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: V128SubKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_mul(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: V128MulKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_div(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_neg(&mut self, _dst: WritableReg, _kind: V128NegKind) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_min(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: V128MinMaxKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_max(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: V128MinMaxKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_pmin(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_pmax(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_avgr(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_q15mulr_sat_s(&mut self, _lhs: Reg, _rhs: Reg, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_and(&mut self, _src1: Reg, _src2: Reg, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_and_not(&mut self, _src1: Reg, _src2: Reg, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_or(&mut self, _src1: Reg, _src2: Reg, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_xor(&mut self, _src1: Reg, _src2: Reg, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_not(&mut self, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_bitselect(
+        &mut self,
+        _src1: Reg,
+        _src2: Reg,
+        _mask: Reg,
+        _dst: WritableReg,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_int_cmp(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: IntCmpKind,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_float_cmp(
+        &mut self,
+        _lhs: Reg,
+        _rhs: Reg,
+        _dst: WritableReg,
+        _kind: FloatCmpKind,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn atomic_rmw(
+        &mut self,
+        _context: &mut CodeGenContext<Emission>,
+        _addr: Self::Address,
+        _size: OperandSize,
+        _op: RmwOp,
+        _flags: MemFlags,
+        _extend: Option<Extend<Zero>>,
+    ) -> Result<()> {
+        Err(anyhow!(CodeGenError::unimplemented_masm_instruction()))
+    }
+
+    fn extract_lane(
+        &mut self,
+        _src: Reg,
+        _dst: WritableReg,
+        _lane: u8,
+        _kind: ExtractLaneKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn replace_lane(
+        &mut self,
+        _src: RegImm,
+        _dst: WritableReg,
+        _lane: u8,
+        _kind: ReplaceLaneKind,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn atomic_cas(
+        &mut self,
+        _context: &mut CodeGenContext<Emission>,
+        _addr: Self::Address,
+        _size: OperandSize,
+        _flags: MemFlags,
+        _extend: Option<Extend<Zero>>,
+    ) -> Result<()> {
+        Err(anyhow!(CodeGenError::unimplemented_masm_instruction()))
+    }
+}
+
+impl MacroAssembler {
+    fn increment_sp(&mut self, bytes: u32) {
+        self.sp_offset += bytes;
+
+        // NOTE: we use `max` here to track the largest stack allocation in `sp_max`. Once we have
+        // seen the entire function, this value will represent the maximum size for the stack
+        // frame.
+        self.sp_max = self.sp_max.max(self.sp_offset);
+    }
+
+    fn decrement_sp(&mut self, bytes: u32) {
+        self.sp_offset -= bytes;
+    }
+
+    /// Add `delta` to the stack pointer.
+    fn adjust_sp(&mut self, delta: i64) {
+        let sp = regs::sp();
+        match Imm12::maybe_from_i64(delta) {
+            Some(imm) => self.asm.alu_rri(AluOPRRI::Addi, sp, imm, writable!(sp)),
+            None => {
+                let scratch = regs::scratch2();
+                self.asm.load_constant(delta as u64, writable!(scratch));
+                self.asm.alu_rrr(AluOPRRR::Add, sp, scratch, writable!(sp));
+            }
+        }
+    }
+
+    /// Load a value of the given size into `dst`.
+    ///
+    /// 32-bit integers are sign extended, to preserve the invariant that
+    /// 32-bit values are kept sign extended in registers. Other integer sizes
+    /// are zero extended.
+    fn load_impl(&mut self, src: Address, dst: WritableReg, size: OperandSize, flags: MemFlags) {
+        if dst.to_reg().is_float() {
+            self.asm.fload(src, dst, size, flags);
+        } else if size == OperandSize::S32 {
+            self.asm.sload(src, dst, size, flags);
+        } else {
+            self.asm.uload(src, dst, size, flags);
+        }
+    }
+
+    /// Returns the bits of the given immediate. 32-bit integers are sign
+    /// extended to 64 bits.
+    fn imm_bits(imm: &I) -> Result<u64> {
+        Ok(match *imm {
+            I::I32(v) => v as i32 as i64 as u64,
+            I::I64(v) | I::F64(v) => v,
+            I::F32(v) => v.into(),
+            I::V128(_) => bail!(CodeGenError::unsupported_imm()),
+        })
+    }
+
+    /// Returns the value of an integer immediate, sign extended to 64 bits
+    /// if it's used in a 32-bit operation.
+    fn imm_to_i64(imm: &I, size: OperandSize) -> Result<i64> {
+        let v = match *imm {
+            I::I32(v) => v as i32 as i64,
+            I::I64(v) => v as i64,
+            _ => bail!(CodeGenError::unsupported_imm()),
+        };
+        Ok(match size {
+            OperandSize::S32 => v as i32 as i64,
+            _ => v,
+        })
+    }
+
+    /// Returns the register holding the given operand, loading immediates
+    /// into the secondary scratch register.
+    fn reg_or_load_imm(&mut self, operand: RegImm, size: OperandSize) -> Result<Reg> {
+        Ok(match operand {
+            RegImm::Reg(reg) => reg,
+            RegImm::Imm(v) => {
+                let scratch = regs::scratch2();
+                let imm = Self::imm_to_i64(&v, size)?;
+                self.asm.load_constant(imm as u64, writable!(scratch));
+                scratch
+            }
+        })
+    }
+
+    /// Emit a binary ALU operation, using the immediate form of the
+    /// instruction if available and if the immediate fits in 12 bits.
+    fn alu_op(
+        &mut self,
+        dst: WritableReg,
+        lhs: Reg,
+        rhs: RegImm,
+        size: OperandSize,
+        rrr: AluOPRRR,
+        rri: Option<AluOPRRI>,
+    ) -> Result<()> {
+        if let (RegImm::Imm(v), Some(rri)) = (rhs, rri) {
+            if let Some(imm) = Imm12::maybe_from_i64(Self::imm_to_i64(&v, size)?) {
+                self.asm.alu_rri(rri, lhs, imm, dst);
+                return Ok(());
+            }
+        }
+
+        let rhs = self.reg_or_load_imm(rhs, size)?;
+        self.asm.alu_rrr(rrr, lhs, rhs, dst);
+        Ok(())
+    }
+
+    /// Emit `fmin` or `fmax` with WebAssembly semantics, for targets without
+    /// the `Zfa` extension: RISC-V's `fmin` and `fmax` return the non-NaN
+    /// operand if only one operand is NaN, while WebAssembly requires a NaN
+    /// result.
+    fn float_min_max(
+        &mut self,
+        op: FpuOPRRR,
+        dst: WritableReg,
+        lhs: Reg,
+        rhs: Reg,
+        size: OperandSize,
+    ) {
+        let (lhs_ordered, rhs_ordered) = (regs::scratch(), regs::scratch2());
+        let nan = self.asm.get_label();
+        let done = self.asm.get_label();
+
+        self.asm
+            .fpu_rrr(FpuOPRRR::Feq, size, lhs, lhs, writable!(lhs_ordered));
+        self.asm
+            .fpu_rrr(FpuOPRRR::Feq, size, rhs, rhs, writable!(rhs_ordered));
+        self.asm.alu_rrr(
+            AluOPRRR::And,
+            lhs_ordered,
+            rhs_ordered,
+            writable!(lhs_ordered),
+        );
+        self.asm
+            .jmp_if(IntCC::Equal, lhs_ordered, regs::zero(), nan);
+        self.asm.fpu_rrr(op, size, lhs, rhs, dst);
+        self.asm.jmp(done);
+        // Adding the operands propagates the NaN.
+        self.asm.bind(nan);
+        self.asm.fpu_rrr(FpuOPRRR::Fadd, size, lhs, rhs, dst);
+        self.asm.bind(done);
+    }
+
+    /// Trap if the signed division of `dividend` by `divisor` overflows, that
+    /// is, if the dividend is the minimum integer and the divisor is -1.
+    fn trap_on_signed_div_overflow(&mut self, dividend: Reg, divisor: Reg, size: OperandSize) {
+        let scratch = regs::scratch2();
+        let done = self.asm.get_label();
+        let min = match size {
+            OperandSize::S32 => i64::from(i32::MIN),
+            _ => i64::MIN,
+        };
+
+        self.asm.load_constant(-1i64 as u64, writable!(scratch));
+        self.asm.jmp_if(IntCC::NotEqual, divisor, scratch, done);
+        self.asm.load_constant(min as u64, writable!(scratch));
+        self.asm
+            .trapif(IntCC::Equal, dividend, scratch, TrapCode::INTEGER_OVERFLOW);
+        self.asm.bind(done);
+    }
+
+    /// Count the leading or trailing zeros of `src`.
+    fn count_zeros(
+        &mut self,
+        leading: bool,
+        dst: WritableReg,
+        src: Reg,
+        size: OperandSize,
+    ) -> Result<()> {
+        if self.isa_flags.has_zbb() {
+            let op = match (leading, size) {
+                (true, OperandSize::S32) => AluOPRRI::Clzw,
+                (true, OperandSize::S64) => AluOPRRI::Clz,
+                (false, OperandSize::S32) => AluOPRRI::Ctzw,
+                (false, OperandSize::S64) => AluOPRRI::Ctz,
+                _ => bail!(CodeGenError::unexpected_operand_size()),
+            };
+            self.asm.alu_rr(op, src, dst);
+        } else {
+            let sum = regs::scratch();
+            self.asm.cltz(
+                leading,
+                src,
+                writable!(sum),
+                writable!(regs::scratch2()),
+                writable!(regs::cmp_lhs()),
+                size,
+            );
+            self.asm.mov_rr(sum, dst);
+        }
+        Ok(())
+    }
+
+    /// Convert the float in `src` to an integer.
+    ///
+    /// RISC-V's conversion instructions saturate out of bounds values and
+    /// convert NaN to the maximum integer. Checked conversions rely on the
+    /// saturation, only mapping NaN to zero, while unchecked conversions trap
+    /// on NaN and out of bounds values.
+    fn float_to_int(
+        &mut self,
+        dst: WritableReg,
+        src: Reg,
+        src_size: OperandSize,
+        dst_size: OperandSize,
+        kind: TruncKind,
+        signed: bool,
+    ) -> Result<()> {
+        let scratch = regs::scratch();
+        let op = match (signed, dst_size) {
+            (true, OperandSize::S32) => FpuOPRR::FcvtWFmt,
+            (true, OperandSize::S64) => FpuOPRR::FcvtLFmt,
+            (false, OperandSize::S32) => FpuOPRR::FcvtWuFmt,
+            (false, OperandSize::S64) => FpuOPRR::FcvtLuFmt,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+
+        if kind.is_unchecked() {
+            self.asm
+                .fpu_rrr(FpuOPRRR::Feq, src_size, src, src, writable!(scratch));
+            self.asm.trapz(scratch, TrapCode::BAD_CONVERSION_TO_INTEGER);
+
+            let out_bits = u32::from(dst_size.num_bits());
+            let (min, max) = match src_size {
+                OperandSize::S32 => {
+                    let (min, max) = f32_cvt_to_int_bounds(signed, out_bits);
+                    (u64::from(min.to_bits()), u64::from(max.to_bits()))
+                }
+                OperandSize::S64 => {
+                    let (min, max) = f64_cvt_to_int_bounds(signed, out_bits);
+                    (min.to_bits(), max.to_bits())
+                }
+                _ => bail!(CodeGenError::unexpected_operand_size()),
+            };
+
+            let bound = regs::float_scratch();
+            // Trap if `src <= min`.
+            self.asm.load_constant(min, writable!(scratch));
+            self.asm.mov_to_fpu(scratch, writable!(bound), src_size);
+            self.asm
+                .fpu_rrr(FpuOPRRR::Fle, src_size, src, bound, writable!(scratch));
+            self.asm.trapif(
+                IntCC::NotEqual,
+                scratch,
+                regs::zero(),
+                TrapCode::INTEGER_OVERFLOW,
+            );
+            // Trap if `src >= max`.
+            self.asm.load_constant(max, writable!(scratch));
+            self.asm.mov_to_fpu(scratch, writable!(bound), src_size);
+            self.asm
+                .fpu_rrr(FpuOPRRR::Fle, src_size, bound, src, writable!(scratch));
+            self.asm.trapif(
+                IntCC::NotEqual,
+                scratch,
+                regs::zero(),
+                TrapCode::INTEGER_OVERFLOW,
+            );
+        }
+
+        self.asm.fpu_rr(op, src_size, FRM::RTZ, src, dst);
+
+        if kind.is_checked() {
+            // Mask the result to zero if the source is NaN.
+            self.asm
+                .fpu_rrr(FpuOPRRR::Feq, src_size, src, src, writable!(scratch));
+            self.asm
+                .alu_rrr(AluOPRRR::Sub, regs::zero(), scratch, writable!(scratch));
+            self.asm.alu_rrr(AluOPRRR::And, dst.to_reg(), scratch, dst);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Riscv64 register definition.
+
+use crate::isa::reg::Reg;
+use regalloc2::{PReg, RegClass};
+
+/// FPR index bound.
+pub(crate) const MAX_FPR: u32 = 32;
+/// GPR index bound.
+pub(crate) const MAX_GPR: u32 = 32;
+
+/// Construct a X-register from an index.
+pub(crate) const fn xreg(num: u8) -> Reg {
+    assert!((num as u32) < MAX_GPR);
+    Reg::new(PReg::new(num as usize, RegClass::Int))
+}
+
+/// Construct a F-register from an index.
+pub(crate) const fn freg(num: u8) -> Reg {
+    assert!((num as u32) < MAX_FPR);
+    Reg::new(PReg::new(num as usize, RegClass::Float))
+}
+
+/// Zero register.
+pub(crate) const fn zero() -> Reg {
+    xreg(0)
+}
+
+/// Return address register.
+pub(crate) const fn ra() -> Reg {
+    xreg(1)
+}
+
+/// Stack pointer register.
+pub(crate) const fn sp() -> Reg {
+    xreg(2)
+}
+
+/// Global pointer register.
+const fn gp() -> Reg {
+    xreg(3)
+}
+
+/// Thread pointer register.
+const fn tp() -> Reg {
+    xreg(4)
+}
+
+/// Scratch register.
+pub(crate) const fn scratch() -> Reg {
+    xreg(5)
+}
+
+/// Secondary scratch register, used by the MacroAssembler for sequences
+/// that need more than one temporary register.
+pub(crate) const fn scratch2() -> Reg {
+    xreg(6)
+}
+
+/// Frame pointer register.
+pub(crate) const fn fp() -> Reg {
+    xreg(8)
+}
+
+/// The VM context register.
+pub(crate) const fn vmctx() -> Reg {
+    xreg(9)
+}
+
+/// Registers holding the operands of the last integer comparison.
+///
+/// Unlike x64 and aarch64, RISC-V has no condition flags: comparisons are
+/// always performed as part of the instruction consuming their result (e.g.
+/// a conditional branch). To support Winch's flag-based `cmp` followed by
+/// `trapif` or `cmov`, the operands of `cmp` are copied into these registers
+/// so that the comparison can be materialized by the consuming instruction.
+///
+/// 32-bit operands are sign-extended when copied, which preserves both their
+/// signed and unsigned ordering.
+pub(crate) const fn cmp_lhs() -> Reg {
+    xreg(28)
+}
+
+/// See [cmp_lhs].
+pub(crate) const fn cmp_rhs() -> Reg {
+    xreg(29)
+}
+
+/// Temporary register used by Cranelift's instruction emission, for example
+/// to materialize large offsets.
+const fn spilltmp2() -> Reg {
+    xreg(30)
+}
+
+/// Temporary register used by Cranelift's instruction emission, for example
+/// to materialize large offsets.
+const fn spilltmp() -> Reg {
+    xreg(31)
+}
+
+/// Float scratch register.
+pub(crate) const fn float_scratch() -> Reg {
+    freg(31)
+}
+
+/// Bitmask for non-allocatable GPR.
+pub(crate) const NON_ALLOCATABLE_GPR: u32 = (1 << zero().hw_enc())
+    | (1 << ra().hw_enc())
+    | (1 << sp().hw_enc())
+    | (1 << gp().hw_enc())
+    | (1 << tp().hw_enc())
+    | (1 << scratch().hw_enc())
+    | (1 << scratch2().hw_enc())
+    | (1 << fp().hw_enc())
+    | (1 << vmctx().hw_enc())
+    | (1 << cmp_lhs().hw_enc())
+    | (1 << cmp_rhs().hw_enc())
+    | (1 << spilltmp2().hw_enc())
+    | (1 << spilltmp().hw_enc());
+
+/// Bitmask to represent the available general purpose registers.
+pub(crate) const ALL_GPR: u32 = u32::MAX & !NON_ALLOCATABLE_GPR;
+
+/// Bitmask for non-allocatable FPR.
+/// All FPRs are allocatable, f10..=f17 are generally used for params and
+/// results.
+pub(crate) const NON_ALLOCATABLE_FPR: u32 = 1 << float_scratch().hw_enc();
+
+/// Bitmask to represent the available floating point registers.
+pub(crate) const ALL_FPR: u32 = u32::MAX & !NON_ALLOCATABLE_FPR;
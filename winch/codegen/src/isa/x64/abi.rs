@@ -6,7 +6,7 @@ use crate::{
     RegIndexEnv,
 };
 use anyhow::{bail, Result};
-use wasmtime_environ::{WasmHeapType, WasmRefType, WasmValType};
+use wasmtime_environ::{WasmHeapType, WasmValType};
 
 #[derive(Default)]
 pub(crate) struct X64ABI;
@@ -92,16 +92,15 @@ impl ABI for X64ABI {
         })
     }
 
-    fn scratch_for(ty: &WasmValType) -> Reg {
+    fn scratch_reg() -> Reg {
+        regs::scratch()
+    }
+
+    fn scratch_for(ty: &WasmValType) -> Result<Reg> {
         match ty {
-            WasmValType::I32
-            | WasmValType::I64
-            | WasmValType::Ref(WasmRefType {
-                heap_type: WasmHeapType::Func,
-                ..
-            }) => regs::scratch(),
-            WasmValType::F32 | WasmValType::F64 | WasmValType::V128 => regs::scratch_xmm(),
-            _ => unimplemented!(),
+            // References are pointers, regardless of their heap type.
+            WasmValType::I32 | WasmValType::I64 | WasmValType::Ref(_) => Ok(regs::scratch()),
+            WasmValType::F32 | WasmValType::F64 | WasmValType::V128 => Ok(regs::scratch_xmm()),
         }
     }
 
@@ -119,15 +118,15 @@ impl ABI for X64ABI {
         Self::word_bytes()
     }
 
-    fn sizeof(ty: &WasmValType) -> u8 {
+    fn sizeof(ty: &WasmValType) -> Result<u8> {
         match ty {
             WasmValType::Ref(rt) => match rt.heap_type {
-                WasmHeapType::Func | WasmHeapType::Extern => Self::word_bytes(),
-                ht => unimplemented!("Support for WasmHeapType: {ht}"),
+                WasmHeapType::Func | WasmHeapType::Extern => Ok(Self::word_bytes()),
+                _ => bail!(CodeGenError::unsupported_wasm_type()),
             },
-            WasmValType::F64 | WasmValType::I64 => Self::word_bytes(),
-            WasmValType::F32 | WasmValType::I32 => Self::word_bytes() / 2,
-            WasmValType::V128 => Self::word_bytes() * 2,
+            WasmValType::F64 | WasmValType::I64 => Ok(Self::word_bytes()),
+            WasmValType::F32 | WasmValType::I32 => Ok(Self::word_bytes() / 2),
+            WasmValType::V128 => Ok(Self::word_bytes() * 2),
         }
     }
 }
@@ -161,7 +160,7 @@ impl X64ABI {
             ),
         };
 
-        let ty_size = <Self as ABI>::sizeof(wasm_arg);
+        let ty_size = <Self as ABI>::sizeof(wasm_arg)?;
         let default = || {
             let arg = ABIOperand::stack_offset(stack_offset, *ty, ty_size as u32);
             let slot_size = Self::stack_slot_size();
@@ -18,7 +18,7 @@ use cranelift_codegen::{
         unwind::UnwindInst,
         x64::{
             args::{
-                self, AluRmiROpcode, Amode, AvxOpcode, CmpOpcode, DivSignedness, ExtMode,
+                self, AluRmiROpcode, Amode, AvxOpcode, CmpOpcode, DivSignedness, ExtMode, FcmpImm,
                 FenceKind, FromWritableReg, Gpr, GprMem, GprMemImm, Imm8Gpr, Imm8Reg, RegMem,
                 RegMemImm, ShiftKind as CraneliftShiftKind, SseOpcode, SyntheticAmode, WritableGpr,
                 WritableXmm, Xmm, XmmMem, XmmMemAligned, XmmMemImm, CC,
@@ -1674,6 +1674,36 @@ impl Assembler {
         })
     }
 
+    /// Performs the AVX operation `op` on `src1` and `src2`, putting the
+    /// result in `dst`.
+    pub fn xmm_vex_rr(&mut self, op: AvxOpcode, src1: Reg, src2: Reg, dst: WritableReg) {
+        self.emit(Inst::XmmRmiRVex {
+            op,
+            src1: src1.into(),
+            src2: XmmMemImm::unwrap_new(src2.into()),
+            dst: dst.to_reg().into(),
+        })
+    }
+
+    /// Performs the AVX operation `op` on `src1` and the value at `src2`,
+    /// putting the result in `dst`.
+    pub fn xmm_vex_rrm(&mut self, op: AvxOpcode, src1: Reg, src2: &Address, dst: WritableReg) {
+        let src2 = Self::to_synthetic_amode(
+            src2,
+            &mut self.pool,
+            &mut self.constants,
+            &mut self.buffer,
+            MemFlags::trusted(),
+        );
+
+        self.emit(Inst::XmmRmiRVex {
+            op,
+            src1: src1.into(),
+            src2: XmmMemImm::unwrap_new(RegMemImm::mem(src2)),
+            dst: dst.to_reg().into(),
+        })
+    }
+
     /// Add unsigned integers with unsigned saturation.
     ///
     /// Adds the src operands but when an individual byte result is larger than
@@ -1699,6 +1729,31 @@ impl Assembler {
         self.emit(Inst::Fence { kind });
     }
 
+    /// Compares each float lane of `src1` and `src2` with `pred`, setting the
+    /// lane in `dst` to all ones if it holds and to zero otherwise.
+    pub fn xmm_vcmpp_rrr(
+        &mut self,
+        dst: WritableReg,
+        src1: Reg,
+        src2: Reg,
+        size: OperandSize,
+        pred: FcmpImm,
+    ) {
+        let op = match size {
+            OperandSize::S32 => AvxOpcode::Vcmpps,
+            OperandSize::S64 => AvxOpcode::Vcmppd,
+            _ => unimplemented!(),
+        };
+
+        self.emit(Inst::XmmRmRImmVex {
+            op,
+            src1: src1.into(),
+            src2: XmmMem::unwrap_new(RegMem::reg(src2.into())),
+            dst: dst.to_reg().into(),
+            imm: pred as u8,
+        });
+    }
+
     /// Extract a value from `src` into `dst` (zero extended) determined by `lane`.
     pub fn xmm_vpextr_rr(&mut self, dst: WritableReg, src: Reg, lane: u8, size: OperandSize) {
         let op = match size {
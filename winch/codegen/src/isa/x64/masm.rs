@@ -9,8 +9,8 @@ use anyhow::{anyhow, bail, Result};
 use crate::masm::{
     DivKind, Extend, ExtendKind, ExtractLaneKind, FloatCmpKind, Imm as I, IntCmpKind, LoadKind,
     MacroAssembler as Masm, MemOpKind, MulWideKind, OperandSize, RegImm, RemKind, ReplaceLaneKind,
-    RmwOp, RoundingMode, ShiftKind, SplatKind, TrapCode, TruncKind, Zero, TRUSTED_FLAGS,
-    UNTRUSTED_FLAGS,
+    RmwOp, RoundingMode, ShiftKind, SplatKind, TrapCode, TruncKind, V128AddKind, V128MinMaxKind,
+    V128MulKind, V128NegKind, V128SubKind, Zero, TRUSTED_FLAGS, UNTRUSTED_FLAGS,
 };
 use crate::{
     abi::{self, align_to, calculate_frame_adjustment, LocalSlot},
@@ -34,7 +34,7 @@ use cranelift_codegen::{
     isa::{
         unwind::UnwindInst,
         x64::{
-            args::{FenceKind, CC},
+            args::{AvxOpcode, FcmpImm, FenceKind, CC},
             settings as x64_settings, AtomicRmwSeqOp,
         },
     },
@@ -1427,6 +1427,325 @@ impl Masm for MacroAssembler {
         Ok(())
     }
 
+    fn v128_add(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, kind: V128AddKind) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match kind {
+            V128AddKind::I8x16 => AvxOpcode::Vpaddb,
+            V128AddKind::I8x16SatS => AvxOpcode::Vpaddsb,
+            V128AddKind::I8x16SatU => AvxOpcode::Vpaddusb,
+            V128AddKind::I16x8 => AvxOpcode::Vpaddw,
+            V128AddKind::I16x8SatS => AvxOpcode::Vpaddsw,
+            V128AddKind::I16x8SatU => AvxOpcode::Vpaddusw,
+            V128AddKind::I32x4 => AvxOpcode::Vpaddd,
+            V128AddKind::I64x2 => AvxOpcode::Vpaddq,
+            V128AddKind::F32x4 => AvxOpcode::Vaddps,
+            V128AddKind::F64x2 => AvxOpcode::Vaddpd,
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_sub(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, kind: V128SubKind) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match kind {
+            V128SubKind::I8x16 => AvxOpcode::Vpsubb,
+            V128SubKind::I8x16SatS => AvxOpcode::Vpsubsb,
+            V128SubKind::I8x16SatU => AvxOpcode::Vpsubusb,
+            V128SubKind::I16x8 => AvxOpcode::Vpsubw,
+            V128SubKind::I16x8SatS => AvxOpcode::Vpsubsw,
+            V128SubKind::I16x8SatU => AvxOpcode::Vpsubusw,
+            V128SubKind::I32x4 => AvxOpcode::Vpsubd,
+            V128SubKind::I64x2 => AvxOpcode::Vpsubq,
+            V128SubKind::F32x4 => AvxOpcode::Vsubps,
+            V128SubKind::F64x2 => AvxOpcode::Vsubpd,
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_mul(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, kind: V128MulKind) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match kind {
+            V128MulKind::I16x8 => AvxOpcode::Vpmullw,
+            V128MulKind::I32x4 => AvxOpcode::Vpmulld,
+            V128MulKind::F32x4 => AvxOpcode::Vmulps,
+            V128MulKind::F64x2 => AvxOpcode::Vmulpd,
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_div(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match size {
+            OperandSize::S32 => AvxOpcode::Vdivps,
+            OperandSize::S64 => AvxOpcode::Vdivpd,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_neg(&mut self, dst: WritableReg, kind: V128NegKind) -> Result<()> {
+        self.ensure_has_avx()?;
+        // Integers are negated by subtracting them from zero and floats by
+        // flipping their sign bits.
+        let sub = match kind {
+            V128NegKind::I8x16 => AvxOpcode::Vpsubb,
+            V128NegKind::I16x8 => AvxOpcode::Vpsubw,
+            V128NegKind::I32x4 => AvxOpcode::Vpsubd,
+            V128NegKind::I64x2 => AvxOpcode::Vpsubq,
+            V128NegKind::F32x4 | V128NegKind::F64x2 => {
+                let (op, sign_mask) = if kind == V128NegKind::F32x4 {
+                    (AvxOpcode::Vxorps, 0x80000000_80000000_80000000_80000000u128)
+                } else {
+                    (AvxOpcode::Vxorpd, 0x80000000_00000000_80000000_00000000u128)
+                };
+                let mask = self.asm.add_constant(&sign_mask.to_le_bytes());
+                self.asm.xmm_vex_rrm(op, dst.to_reg(), &mask, dst);
+                return Ok(());
+            }
+        };
+        let scratch = writable!(regs::scratch_xmm());
+        self.asm.xmm_vex_rr(
+            AvxOpcode::Vpxor,
+            scratch.to_reg(),
+            scratch.to_reg(),
+            scratch,
+        );
+        self.asm
+            .xmm_vex_rr(sub, scratch.to_reg(), dst.to_reg(), dst);
+        Ok(())
+    }
+
+    fn v128_min(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: V128MinMaxKind,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match kind {
+            V128MinMaxKind::I8x16S => AvxOpcode::Vpminsb,
+            V128MinMaxKind::I8x16U => AvxOpcode::Vpminub,
+            V128MinMaxKind::I16x8S => AvxOpcode::Vpminsw,
+            V128MinMaxKind::I16x8U => AvxOpcode::Vpminuw,
+            V128MinMaxKind::I32x4S => AvxOpcode::Vpminsd,
+            V128MinMaxKind::I32x4U => AvxOpcode::Vpminud,
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_max(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: V128MinMaxKind,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match kind {
+            V128MinMaxKind::I8x16S => AvxOpcode::Vpmaxsb,
+            V128MinMaxKind::I8x16U => AvxOpcode::Vpmaxub,
+            V128MinMaxKind::I16x8S => AvxOpcode::Vpmaxsw,
+            V128MinMaxKind::I16x8U => AvxOpcode::Vpmaxuw,
+            V128MinMaxKind::I32x4S => AvxOpcode::Vpmaxsd,
+            V128MinMaxKind::I32x4U => AvxOpcode::Vpmaxud,
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_pmin(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match size {
+            OperandSize::S32 => AvxOpcode::Vminps,
+            OperandSize::S64 => AvxOpcode::Vminpd,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        // `vminps` returns its second operand if either is NaN or both are
+        // zero, which matches the semantics of `pmin` when the operands are
+        // reversed.
+        self.asm.xmm_vex_rr(op, rhs, lhs, dst);
+        Ok(())
+    }
+
+    fn v128_pmax(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match size {
+            OperandSize::S32 => AvxOpcode::Vmaxps,
+            OperandSize::S64 => AvxOpcode::Vmaxpd,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        // See `v128_pmin`.
+        self.asm.xmm_vex_rr(op, rhs, lhs, dst);
+        Ok(())
+    }
+
+    fn v128_avgr(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()> {
+        self.ensure_has_avx()?;
+        let op = match size {
+            OperandSize::S8 => AvxOpcode::Vpavgb,
+            OperandSize::S16 => AvxOpcode::Vpavgw,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.asm.xmm_vex_rr(op, lhs, rhs, dst);
+        Ok(())
+    }
+
+    fn v128_q15mulr_sat_s(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        // `vpmulhrsw` produces 0x8000 instead of saturating to 0x7FFF when
+        // both operands are 0x8000, so flip all bits of those lanes.
+        let overflow = self.asm.add_constant(&[0x00, 0x80].repeat(8));
+        let scratch = writable!(regs::scratch_xmm());
+        self.asm.xmm_vex_rr(AvxOpcode::Vpmulhrsw, lhs, rhs, dst);
+        self.asm
+            .xmm_vex_rrm(AvxOpcode::Vpcmpeqw, dst.to_reg(), &overflow, scratch);
+        self.asm
+            .xmm_vex_rr(AvxOpcode::Vpxor, dst.to_reg(), scratch.to_reg(), dst);
+        Ok(())
+    }
+
+    fn v128_and(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        self.asm.xmm_vex_rr(AvxOpcode::Vpand, src1, src2, dst);
+        Ok(())
+    }
+
+    fn v128_and_not(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        // `vpandn` complements its first operand.
+        self.asm.xmm_vex_rr(AvxOpcode::Vpandn, src2, src1, dst);
+        Ok(())
+    }
+
+    fn v128_or(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        self.asm.xmm_vex_rr(AvxOpcode::Vpor, src1, src2, dst);
+        Ok(())
+    }
+
+    fn v128_xor(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        self.asm.xmm_vex_rr(AvxOpcode::Vpxor, src1, src2, dst);
+        Ok(())
+    }
+
+    fn v128_not(&mut self, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        let ones = self.asm.add_constant(&[0xff; 16]);
+        self.asm
+            .xmm_vex_rrm(AvxOpcode::Vpxor, dst.to_reg(), &ones, dst);
+        Ok(())
+    }
+
+    fn v128_bitselect(&mut self, src1: Reg, src2: Reg, mask: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        // dst = (src1 & mask) | (src2 & !mask)
+        let scratch = writable!(regs::scratch_xmm());
+        self.asm.xmm_vex_rr(AvxOpcode::Vpand, src1, mask, scratch);
+        self.asm.xmm_vex_rr(AvxOpcode::Vpandn, mask, src2, dst);
+        self.asm
+            .xmm_vex_rr(AvxOpcode::Vpor, dst.to_reg(), scratch.to_reg(), dst);
+        Ok(())
+    }
+
+    fn v128_int_cmp(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: IntCmpKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+        let (eq, gt, min_u, max_u) = match size {
+            OperandSize::S8 => (
+                AvxOpcode::Vpcmpeqb,
+                AvxOpcode::Vpcmpgtb,
+                Some(AvxOpcode::Vpminub),
+                Some(AvxOpcode::Vpmaxub),
+            ),
+            OperandSize::S16 => (
+                AvxOpcode::Vpcmpeqw,
+                AvxOpcode::Vpcmpgtw,
+                Some(AvxOpcode::Vpminuw),
+                Some(AvxOpcode::Vpmaxuw),
+            ),
+            OperandSize::S32 => (
+                AvxOpcode::Vpcmpeqd,
+                AvxOpcode::Vpcmpgtd,
+                Some(AvxOpcode::Vpminud),
+                Some(AvxOpcode::Vpmaxud),
+            ),
+            // There are no unsigned comparisons of 64-bit lanes in
+            // WebAssembly.
+            OperandSize::S64 => (AvxOpcode::Vpcmpeqq, AvxOpcode::Vpcmpgtq, None, None),
+            OperandSize::S128 => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        let complement = match kind {
+            IntCmpKind::Eq | IntCmpKind::Ne => {
+                self.asm.xmm_vex_rr(eq, lhs, rhs, dst);
+                kind == IntCmpKind::Ne
+            }
+            IntCmpKind::GtS | IntCmpKind::LeS => {
+                self.asm.xmm_vex_rr(gt, lhs, rhs, dst);
+                kind == IntCmpKind::LeS
+            }
+            IntCmpKind::LtS | IntCmpKind::GeS => {
+                self.asm.xmm_vex_rr(gt, rhs, lhs, dst);
+                kind == IntCmpKind::GeS
+            }
+            // There's no unsigned `vpcmpgt`, so these are lowered as
+            // `min(lhs, rhs) == lhs` and `max(lhs, rhs) == lhs`.
+            IntCmpKind::LeU | IntCmpKind::GtU | IntCmpKind::GeU | IntCmpKind::LtU => {
+                let op = match kind {
+                    IntCmpKind::LeU | IntCmpKind::GtU => min_u,
+                    _ => max_u,
+                };
+                let op = op.ok_or_else(|| anyhow!(CodeGenError::unexpected_operand_size()))?;
+                let scratch = writable!(regs::scratch_xmm());
+                self.asm.xmm_vex_rr(op, lhs, rhs, scratch);
+                self.asm.xmm_vex_rr(eq, lhs, scratch.to_reg(), dst);
+                matches!(kind, IntCmpKind::GtU | IntCmpKind::LtU)
+            }
+        };
+        if complement {
+            self.v128_not(dst)?;
+        }
+        Ok(())
+    }
+
+    fn v128_float_cmp(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: FloatCmpKind,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+        if !matches!(size, OperandSize::S32 | OperandSize::S64) {
+            bail!(CodeGenError::unexpected_operand_size());
+        }
+        // Greater than comparisons are less than comparisons with the
+        // operands reversed. `NotEqual` is unordered, so it holds for NaNs as
+        // WebAssembly requires, while the other predicates are ordered.
+        let (pred, src1, src2) = match kind {
+            FloatCmpKind::Eq => (FcmpImm::Equal, lhs, rhs),
+            FloatCmpKind::Ne => (FcmpImm::NotEqual, lhs, rhs),
+            FloatCmpKind::Lt => (FcmpImm::LessThan, lhs, rhs),
+            FloatCmpKind::Le => (FcmpImm::LessThanOrEqual, lhs, rhs),
+            FloatCmpKind::Gt => (FcmpImm::LessThan, rhs, lhs),
+            FloatCmpKind::Ge => (FcmpImm::LessThanOrEqual, rhs, lhs),
+        };
+        self.asm.xmm_vcmpp_rrr(dst, src1, src2, size, pred);
+        Ok(())
+    }
+
     fn atomic_rmw(
         &mut self,
         context: &mut CodeGenContext<Emission>,
@@ -1783,6 +2102,15 @@ impl MacroAssembler {
         Ok(())
     }
 
+    /// Returns an error if AVX, which SIMD instructions are lowered with, is
+    /// not available.
+    fn ensure_has_avx(&self) -> Result<()> {
+        if !self.flags.has_avx() {
+            bail!(CodeGenError::UnimplementedForNoAvx)
+        }
+        Ok(())
+    }
+
     fn ensure_two_argument_form(dst: &Reg, lhs: &Reg) -> Result<()> {
         if dst != lhs {
             Err(anyhow!(CodeGenError::invalid_two_arg_form()))
@@ -427,6 +427,103 @@ impl ReplaceLaneKind {
     }
 }
 
+/// Kinds of vector addition supported by WebAssembly.
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub(crate) enum V128AddKind {
+    /// 16 lanes of 8 bit integers, wrapping on overflow.
+    I8x16,
+    /// 16 lanes of 8 bit integers, with signed saturation.
+    I8x16SatS,
+    /// 16 lanes of 8 bit integers, with unsigned saturation.
+    I8x16SatU,
+    /// 8 lanes of 16 bit integers, wrapping on overflow.
+    I16x8,
+    /// 8 lanes of 16 bit integers, with signed saturation.
+    I16x8SatS,
+    /// 8 lanes of 16 bit integers, with unsigned saturation.
+    I16x8SatU,
+    /// 4 lanes of 32 bit integers, wrapping on overflow.
+    I32x4,
+    /// 2 lanes of 64 bit integers, wrapping on overflow.
+    I64x2,
+    /// 4 lanes of 32 bit floats.
+    F32x4,
+    /// 2 lanes of 64 bit floats.
+    F64x2,
+}
+
+/// Kinds of vector subtraction supported by WebAssembly.
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub(crate) enum V128SubKind {
+    /// 16 lanes of 8 bit integers, wrapping on overflow.
+    I8x16,
+    /// 16 lanes of 8 bit integers, with signed saturation.
+    I8x16SatS,
+    /// 16 lanes of 8 bit integers, with unsigned saturation.
+    I8x16SatU,
+    /// 8 lanes of 16 bit integers, wrapping on overflow.
+    I16x8,
+    /// 8 lanes of 16 bit integers, with signed saturation.
+    I16x8SatS,
+    /// 8 lanes of 16 bit integers, with unsigned saturation.
+    I16x8SatU,
+    /// 4 lanes of 32 bit integers, wrapping on overflow.
+    I32x4,
+    /// 2 lanes of 64 bit integers, wrapping on overflow.
+    I64x2,
+    /// 4 lanes of 32 bit floats.
+    F32x4,
+    /// 2 lanes of 64 bit floats.
+    F64x2,
+}
+
+/// Kinds of vector multiplication supported by WebAssembly.
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub(crate) enum V128MulKind {
+    /// 8 lanes of 16 bit integers.
+    I16x8,
+    /// 4 lanes of 32 bit integers.
+    I32x4,
+    /// 4 lanes of 32 bit floats.
+    F32x4,
+    /// 2 lanes of 64 bit floats.
+    F64x2,
+}
+
+/// Kinds of vector negation supported by WebAssembly.
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub(crate) enum V128NegKind {
+    /// 16 lanes of 8 bit integers.
+    I8x16,
+    /// 8 lanes of 16 bit integers.
+    I16x8,
+    /// 4 lanes of 32 bit integers.
+    I32x4,
+    /// 2 lanes of 64 bit integers.
+    I64x2,
+    /// 4 lanes of 32 bit floats.
+    F32x4,
+    /// 2 lanes of 64 bit floats.
+    F64x2,
+}
+
+/// Kinds of integer vector minimum and maximum supported by WebAssembly.
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub(crate) enum V128MinMaxKind {
+    /// 16 lanes of signed 8 bit integers.
+    I8x16S,
+    /// 16 lanes of unsigned 8 bit integers.
+    I8x16U,
+    /// 8 lanes of signed 16 bit integers.
+    I16x8S,
+    /// 8 lanes of unsigned 16 bit integers.
+    I16x8U,
+    /// 4 lanes of signed 32 bit integers.
+    I32x4S,
+    /// 4 lanes of unsigned 32 bit integers.
+    I32x4U,
+}
+
 /// Kinds of behavior supported by Wasm loads.
 pub(crate) enum LoadKind {
     /// Load the entire bytes of the operand size without any modifications.
@@ -1436,6 +1533,100 @@ pub(crate) trait MacroAssembler {
     /// Performs a swizzle between two 128-bit vectors into a 128-bit result.
     fn swizzle(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg) -> Result<()>;
 
+    /// Performs a lane-wise addition of `lhs` and `rhs` into `dst`.
+    fn v128_add(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, kind: V128AddKind) -> Result<()>;
+
+    /// Performs a lane-wise subtraction of `rhs` from `lhs` into `dst`.
+    fn v128_sub(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, kind: V128SubKind) -> Result<()>;
+
+    /// Performs a lane-wise multiplication of `lhs` and `rhs` into `dst`.
+    fn v128_mul(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, kind: V128MulKind) -> Result<()>;
+
+    /// Performs a lane-wise division of the floats in `lhs` by those in
+    /// `rhs` into `dst`, with lanes of the given `size`.
+    fn v128_div(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()>;
+
+    /// Negates each lane of `dst`.
+    fn v128_neg(&mut self, dst: WritableReg, kind: V128NegKind) -> Result<()>;
+
+    /// Performs a lane-wise integer minimum of `lhs` and `rhs` into `dst`.
+    fn v128_min(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: V128MinMaxKind,
+    ) -> Result<()>;
+
+    /// Performs a lane-wise integer maximum of `lhs` and `rhs` into `dst`.
+    fn v128_max(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: V128MinMaxKind,
+    ) -> Result<()>;
+
+    /// Performs a lane-wise float pseudo-minimum of `lhs` and `rhs` into
+    /// `dst`, i.e. `rhs < lhs ? rhs : lhs`, with lanes of the given `size`.
+    fn v128_pmin(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()>;
+
+    /// Performs a lane-wise float pseudo-maximum of `lhs` and `rhs` into
+    /// `dst`, i.e. `lhs < rhs ? rhs : lhs`, with lanes of the given `size`.
+    fn v128_pmax(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()>;
+
+    /// Performs a lane-wise unsigned rounding average of `lhs` and `rhs`
+    /// into `dst`, with lanes of the given `size`.
+    fn v128_avgr(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()>;
+
+    /// Performs a lane-wise saturating, rounding Q15 multiplication of the
+    /// 16 bit integers in `lhs` and `rhs` into `dst`.
+    fn v128_q15mulr_sat_s(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Performs a bitwise `and` of `src1` and `src2` into `dst`.
+    fn v128_and(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Performs a bitwise `and` of `src1` and the complement of `src2` into
+    /// `dst`.
+    fn v128_and_not(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Performs a bitwise `or` of `src1` and `src2` into `dst`.
+    fn v128_or(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Performs a bitwise `xor` of `src1` and `src2` into `dst`.
+    fn v128_xor(&mut self, src1: Reg, src2: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Performs a bitwise complement of `dst`.
+    fn v128_not(&mut self, dst: WritableReg) -> Result<()>;
+
+    /// Selects bits from `src1` where the corresponding bit in `mask` is set
+    /// and from `src2` where it's not, into `dst`.
+    fn v128_bitselect(&mut self, src1: Reg, src2: Reg, mask: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Compares each integer lane of the given `size` in `lhs` and `rhs`
+    /// with `kind`, setting the lane in `dst` to all ones if the comparison
+    /// holds and to zero otherwise.
+    fn v128_int_cmp(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: IntCmpKind,
+        size: OperandSize,
+    ) -> Result<()>;
+
+    /// Compares each float lane of the given `size` in `lhs` and `rhs` with
+    /// `kind`, setting the lane in `dst` to all ones if the comparison holds
+    /// and to zero otherwise.
+    fn v128_float_cmp(
+        &mut self,
+        lhs: Reg,
+        rhs: Reg,
+        dst: WritableReg,
+        kind: FloatCmpKind,
+        size: OperandSize,
+    ) -> Result<()>;
+
     /// Performs the RMW `op` operation on the passed `addr`.
     ///
     /// The value *before* the operation was performed is written back to the `operand` register.
@@ -11,8 +11,8 @@ use crate::codegen::{
 use crate::masm::{
     DivKind, Extend, ExtractLaneKind, FloatCmpKind, IntCmpKind, LoadKind, MacroAssembler,
     MemMoveDirection, MemOpKind, MulWideKind, OperandSize, RegImm, RemKind, ReplaceLaneKind, RmwOp,
-    RoundingMode, SPOffset, ShiftKind, Signed, SplatKind, SplatLoadKind, TruncKind,
-    VectorExtendKind, Zero,
+    RoundingMode, SPOffset, ShiftKind, Signed, SplatKind, SplatLoadKind, TruncKind, V128AddKind,
+    V128MinMaxKind, V128MulKind, V128NegKind, V128SubKind, VectorExtendKind, Zero,
 };
 
 use crate::reg::{writable, Reg};
@@ -343,6 +343,111 @@ macro_rules! def_unsupported {
     (emit I64x2ReplaceLane $($rest:tt)*) => {};
     (emit F32x4ReplaceLane $($rest:tt)*) => {};
     (emit F64x2ReplaceLane $($rest:tt)*) => {};
+    (emit V128And $($rest:tt)*) => {};
+    (emit V128AndNot $($rest:tt)*) => {};
+    (emit V128Or $($rest:tt)*) => {};
+    (emit V128Xor $($rest:tt)*) => {};
+    (emit V128Not $($rest:tt)*) => {};
+    (emit V128Bitselect $($rest:tt)*) => {};
+    (emit I8x16Add $($rest:tt)*) => {};
+    (emit I8x16Sub $($rest:tt)*) => {};
+    (emit I8x16AddSatS $($rest:tt)*) => {};
+    (emit I8x16AddSatU $($rest:tt)*) => {};
+    (emit I8x16SubSatS $($rest:tt)*) => {};
+    (emit I8x16SubSatU $($rest:tt)*) => {};
+    (emit I8x16Neg $($rest:tt)*) => {};
+    (emit I8x16MinS $($rest:tt)*) => {};
+    (emit I8x16MinU $($rest:tt)*) => {};
+    (emit I8x16MaxS $($rest:tt)*) => {};
+    (emit I8x16MaxU $($rest:tt)*) => {};
+    (emit I8x16AvgrU $($rest:tt)*) => {};
+    (emit I16x8Add $($rest:tt)*) => {};
+    (emit I16x8Sub $($rest:tt)*) => {};
+    (emit I16x8AddSatS $($rest:tt)*) => {};
+    (emit I16x8AddSatU $($rest:tt)*) => {};
+    (emit I16x8SubSatS $($rest:tt)*) => {};
+    (emit I16x8SubSatU $($rest:tt)*) => {};
+    (emit I16x8Mul $($rest:tt)*) => {};
+    (emit I16x8Neg $($rest:tt)*) => {};
+    (emit I16x8MinS $($rest:tt)*) => {};
+    (emit I16x8MinU $($rest:tt)*) => {};
+    (emit I16x8MaxS $($rest:tt)*) => {};
+    (emit I16x8MaxU $($rest:tt)*) => {};
+    (emit I16x8AvgrU $($rest:tt)*) => {};
+    (emit I16x8Q15MulrSatS $($rest:tt)*) => {};
+    (emit I32x4Add $($rest:tt)*) => {};
+    (emit I32x4Sub $($rest:tt)*) => {};
+    (emit I32x4Mul $($rest:tt)*) => {};
+    (emit I32x4Neg $($rest:tt)*) => {};
+    (emit I32x4MinS $($rest:tt)*) => {};
+    (emit I32x4MinU $($rest:tt)*) => {};
+    (emit I32x4MaxS $($rest:tt)*) => {};
+    (emit I32x4MaxU $($rest:tt)*) => {};
+    (emit I64x2Add $($rest:tt)*) => {};
+    (emit I64x2Sub $($rest:tt)*) => {};
+    (emit I64x2Neg $($rest:tt)*) => {};
+    (emit F32x4Add $($rest:tt)*) => {};
+    (emit F32x4Sub $($rest:tt)*) => {};
+    (emit F32x4Mul $($rest:tt)*) => {};
+    (emit F32x4Neg $($rest:tt)*) => {};
+    (emit F32x4Div $($rest:tt)*) => {};
+    (emit F32x4PMin $($rest:tt)*) => {};
+    (emit F32x4PMax $($rest:tt)*) => {};
+    (emit F64x2Add $($rest:tt)*) => {};
+    (emit F64x2Sub $($rest:tt)*) => {};
+    (emit F64x2Mul $($rest:tt)*) => {};
+    (emit F64x2Neg $($rest:tt)*) => {};
+    (emit F64x2Div $($rest:tt)*) => {};
+    (emit F64x2PMin $($rest:tt)*) => {};
+    (emit F64x2PMax $($rest:tt)*) => {};
+    (emit I8x16Eq $($rest:tt)*) => {};
+    (emit I8x16Ne $($rest:tt)*) => {};
+    (emit I8x16LtS $($rest:tt)*) => {};
+    (emit I8x16LtU $($rest:tt)*) => {};
+    (emit I8x16GtS $($rest:tt)*) => {};
+    (emit I8x16GtU $($rest:tt)*) => {};
+    (emit I8x16LeS $($rest:tt)*) => {};
+    (emit I8x16LeU $($rest:tt)*) => {};
+    (emit I8x16GeS $($rest:tt)*) => {};
+    (emit I8x16GeU $($rest:tt)*) => {};
+    (emit I16x8Eq $($rest:tt)*) => {};
+    (emit I16x8Ne $($rest:tt)*) => {};
+    (emit I16x8LtS $($rest:tt)*) => {};
+    (emit I16x8LtU $($rest:tt)*) => {};
+    (emit I16x8GtS $($rest:tt)*) => {};
+    (emit I16x8GtU $($rest:tt)*) => {};
+    (emit I16x8LeS $($rest:tt)*) => {};
+    (emit I16x8LeU $($rest:tt)*) => {};
+    (emit I16x8GeS $($rest:tt)*) => {};
+    (emit I16x8GeU $($rest:tt)*) => {};
+    (emit I32x4Eq $($rest:tt)*) => {};
+    (emit I32x4Ne $($rest:tt)*) => {};
+    (emit I32x4LtS $($rest:tt)*) => {};
+    (emit I32x4LtU $($rest:tt)*) => {};
+    (emit I32x4GtS $($rest:tt)*) => {};
+    (emit I32x4GtU $($rest:tt)*) => {};
+    (emit I32x4LeS $($rest:tt)*) => {};
+    (emit I32x4LeU $($rest:tt)*) => {};
+    (emit I32x4GeS $($rest:tt)*) => {};
+    (emit I32x4GeU $($rest:tt)*) => {};
+    (emit I64x2Eq $($rest:tt)*) => {};
+    (emit I64x2Ne $($rest:tt)*) => {};
+    (emit I64x2LtS $($rest:tt)*) => {};
+    (emit I64x2GtS $($rest:tt)*) => {};
+    (emit I64x2LeS $($rest:tt)*) => {};
+    (emit I64x2GeS $($rest:tt)*) => {};
+    (emit F32x4Eq $($rest:tt)*) => {};
+    (emit F32x4Ne $($rest:tt)*) => {};
+    (emit F32x4Lt $($rest:tt)*) => {};
+    (emit F32x4Gt $($rest:tt)*) => {};
+    (emit F32x4Le $($rest:tt)*) => {};
+    (emit F32x4Ge $($rest:tt)*) => {};
+    (emit F64x2Eq $($rest:tt)*) => {};
+    (emit F64x2Ne $($rest:tt)*) => {};
+    (emit F64x2Lt $($rest:tt)*) => {};
+    (emit F64x2Gt $($rest:tt)*) => {};
+    (emit F64x2Le $($rest:tt)*) => {};
+    (emit F64x2Ge $($rest:tt)*) => {};
     (emit I32AtomicRmw8CmpxchgU $($rest:tt)*) => {};
     (emit I32AtomicRmw16CmpxchgU $($rest:tt)*) => {};
     (emit I32AtomicRmwCmpxchg $($rest:tt)*) => {};
@@ -2960,6 +3065,655 @@ where
             })
     }
 
+    fn visit_v128_and(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_and(dst, src, writable!(dst))?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_v128_andnot(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_and_not(dst, src, writable!(dst))?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_v128_or(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_or(dst, src, writable!(dst))?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_v128_xor(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_xor(dst, src, writable!(dst))?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_v128_not(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_not(writable!(reg))?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_v128_bitselect(&mut self) -> Self::Output {
+        let mask = self.context.pop_to_reg(self.masm, None)?;
+        let src2 = self.context.pop_to_reg(self.masm, None)?;
+        let src1 = self.context.pop_to_reg(self.masm, None)?;
+        self.masm.v128_bitselect(
+            src1.into(),
+            src2.into(),
+            mask.into(),
+            writable!(src1.into()),
+        )?;
+        self.context.stack.push(src1.into());
+        self.context.free_reg(src2);
+        self.context.free_reg(mask);
+        Ok(())
+    }
+
+    fn visit_i8x16_add(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I8x16)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_sub(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I8x16)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_add_sat_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I8x16SatS)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_add_sat_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I8x16SatU)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_sub_sat_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I8x16SatS)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_sub_sat_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I8x16SatU)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_neg(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_neg(writable!(reg), V128NegKind::I8x16)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_i8x16_min_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_min(dst, src, writable!(dst), V128MinMaxKind::I8x16S)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_min_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_min(dst, src, writable!(dst), V128MinMaxKind::I8x16U)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_max_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_max(dst, src, writable!(dst), V128MinMaxKind::I8x16S)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_max_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_max(dst, src, writable!(dst), V128MinMaxKind::I8x16U)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_avgr_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_avgr(dst, src, writable!(dst), OperandSize::S8)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_add(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I16x8)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_sub(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I16x8)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_add_sat_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I16x8SatS)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_add_sat_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I16x8SatU)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_sub_sat_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I16x8SatS)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_sub_sat_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I16x8SatU)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_mul(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_mul(dst, src, writable!(dst), V128MulKind::I16x8)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_neg(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_neg(writable!(reg), V128NegKind::I16x8)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_i16x8_min_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_min(dst, src, writable!(dst), V128MinMaxKind::I16x8S)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_min_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_min(dst, src, writable!(dst), V128MinMaxKind::I16x8U)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_max_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_max(dst, src, writable!(dst), V128MinMaxKind::I16x8S)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_max_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_max(dst, src, writable!(dst), V128MinMaxKind::I16x8U)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_avgr_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_avgr(dst, src, writable!(dst), OperandSize::S16)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_q15mulr_sat_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_q15mulr_sat_s(dst, src, writable!(dst))?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_add(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I32x4)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_sub(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I32x4)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_mul(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_mul(dst, src, writable!(dst), V128MulKind::I32x4)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_neg(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_neg(writable!(reg), V128NegKind::I32x4)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_i32x4_min_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_min(dst, src, writable!(dst), V128MinMaxKind::I32x4S)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_min_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_min(dst, src, writable!(dst), V128MinMaxKind::I32x4U)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_max_s(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_max(dst, src, writable!(dst), V128MinMaxKind::I32x4S)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_max_u(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_max(dst, src, writable!(dst), V128MinMaxKind::I32x4U)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i64x2_add(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::I64x2)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i64x2_sub(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::I64x2)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i64x2_neg(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_neg(writable!(reg), V128NegKind::I64x2)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_f32x4_add(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::F32x4)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f32x4_sub(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::F32x4)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f32x4_mul(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_mul(dst, src, writable!(dst), V128MulKind::F32x4)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f32x4_neg(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_neg(writable!(reg), V128NegKind::F32x4)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_f32x4_div(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_div(dst, src, writable!(dst), OperandSize::S32)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f32x4_pmin(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_pmin(dst, src, writable!(dst), OperandSize::S32)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f32x4_pmax(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_pmax(dst, src, writable!(dst), OperandSize::S32)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f64x2_add(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_add(dst, src, writable!(dst), V128AddKind::F64x2)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f64x2_sub(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_sub(dst, src, writable!(dst), V128SubKind::F64x2)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f64x2_mul(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_mul(dst, src, writable!(dst), V128MulKind::F64x2)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f64x2_neg(&mut self) -> Self::Output {
+        self.context.unop(self.masm, &mut |masm, reg| {
+            masm.v128_neg(writable!(reg), V128NegKind::F64x2)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_f64x2_div(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_div(dst, src, writable!(dst), OperandSize::S64)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f64x2_pmin(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_pmin(dst, src, writable!(dst), OperandSize::S64)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_f64x2_pmax(&mut self) -> Self::Output {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_pmax(dst, src, writable!(dst), OperandSize::S64)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i8x16_eq(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Eq, OperandSize::S8)
+    }
+
+    fn visit_i8x16_ne(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Ne, OperandSize::S8)
+    }
+
+    fn visit_i8x16_lt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtS, OperandSize::S8)
+    }
+
+    fn visit_i8x16_lt_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtU, OperandSize::S8)
+    }
+
+    fn visit_i8x16_gt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtS, OperandSize::S8)
+    }
+
+    fn visit_i8x16_gt_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtU, OperandSize::S8)
+    }
+
+    fn visit_i8x16_le_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeS, OperandSize::S8)
+    }
+
+    fn visit_i8x16_le_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeU, OperandSize::S8)
+    }
+
+    fn visit_i8x16_ge_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeS, OperandSize::S8)
+    }
+
+    fn visit_i8x16_ge_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeU, OperandSize::S8)
+    }
+
+    fn visit_i16x8_eq(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Eq, OperandSize::S16)
+    }
+
+    fn visit_i16x8_ne(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Ne, OperandSize::S16)
+    }
+
+    fn visit_i16x8_lt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtS, OperandSize::S16)
+    }
+
+    fn visit_i16x8_lt_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtU, OperandSize::S16)
+    }
+
+    fn visit_i16x8_gt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtS, OperandSize::S16)
+    }
+
+    fn visit_i16x8_gt_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtU, OperandSize::S16)
+    }
+
+    fn visit_i16x8_le_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeS, OperandSize::S16)
+    }
+
+    fn visit_i16x8_le_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeU, OperandSize::S16)
+    }
+
+    fn visit_i16x8_ge_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeS, OperandSize::S16)
+    }
+
+    fn visit_i16x8_ge_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeU, OperandSize::S16)
+    }
+
+    fn visit_i32x4_eq(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Eq, OperandSize::S32)
+    }
+
+    fn visit_i32x4_ne(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Ne, OperandSize::S32)
+    }
+
+    fn visit_i32x4_lt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtS, OperandSize::S32)
+    }
+
+    fn visit_i32x4_lt_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtU, OperandSize::S32)
+    }
+
+    fn visit_i32x4_gt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtS, OperandSize::S32)
+    }
+
+    fn visit_i32x4_gt_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtU, OperandSize::S32)
+    }
+
+    fn visit_i32x4_le_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeS, OperandSize::S32)
+    }
+
+    fn visit_i32x4_le_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeU, OperandSize::S32)
+    }
+
+    fn visit_i32x4_ge_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeS, OperandSize::S32)
+    }
+
+    fn visit_i32x4_ge_u(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeU, OperandSize::S32)
+    }
+
+    fn visit_i64x2_eq(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Eq, OperandSize::S64)
+    }
+
+    fn visit_i64x2_ne(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::Ne, OperandSize::S64)
+    }
+
+    fn visit_i64x2_lt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LtS, OperandSize::S64)
+    }
+
+    fn visit_i64x2_gt_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GtS, OperandSize::S64)
+    }
+
+    fn visit_i64x2_le_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::LeS, OperandSize::S64)
+    }
+
+    fn visit_i64x2_ge_s(&mut self) -> Self::Output {
+        self.cmp_v128_ints(IntCmpKind::GeS, OperandSize::S64)
+    }
+
+    fn visit_f32x4_eq(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Eq, OperandSize::S32)
+    }
+
+    fn visit_f32x4_ne(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Ne, OperandSize::S32)
+    }
+
+    fn visit_f32x4_lt(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Lt, OperandSize::S32)
+    }
+
+    fn visit_f32x4_gt(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Gt, OperandSize::S32)
+    }
+
+    fn visit_f32x4_le(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Le, OperandSize::S32)
+    }
+
+    fn visit_f32x4_ge(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Ge, OperandSize::S32)
+    }
+
+    fn visit_f64x2_eq(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Eq, OperandSize::S64)
+    }
+
+    fn visit_f64x2_ne(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Ne, OperandSize::S64)
+    }
+
+    fn visit_f64x2_lt(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Lt, OperandSize::S64)
+    }
+
+    fn visit_f64x2_gt(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Gt, OperandSize::S64)
+    }
+
+    fn visit_f64x2_le(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Le, OperandSize::S64)
+    }
+
+    fn visit_f64x2_ge(&mut self) -> Self::Output {
+        self.cmp_v128_floats(FloatCmpKind::Ge, OperandSize::S64)
+    }
+
     wasmparser::for_each_visit_simd_operator!(def_unsupported);
 }
 
@@ -2981,6 +3735,22 @@ where
                 Ok(TypedReg::i32(dst)) // Return value for comparisons is an `i32`.
             })
     }
+
+    fn cmp_v128_ints(&mut self, kind: IntCmpKind, size: OperandSize) -> Result<()> {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_int_cmp(dst, src, writable!(dst), kind, size)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn cmp_v128_floats(&mut self, kind: FloatCmpKind, size: OperandSize) -> Result<()> {
+        self.context
+            .binop(self.masm, OperandSize::S128, |masm, dst, src, _size| {
+                masm.v128_float_cmp(dst, src, writable!(dst), kind, size)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
 }
 
 impl TryFrom<WasmValType> for OperandSize {